@@ -575,6 +575,11 @@ mod test {
     }
 
     fn advance_tip(chainstate: &mut StacksChainState, parent_header_info: &StacksHeaderInfo, block_reward: &mut MinerPaymentSchedule, user_burns: &mut Vec<StagingUserBurnSupport>) -> StacksHeaderInfo {
+        let burn_header_hash = BurnchainHeaderHash(Sha512Trunc256Sum::from_data(&parent_header_info.burn_header_hash.0).0);
+        advance_tip_in_burn_block(chainstate, parent_header_info, &burn_header_hash, block_reward, user_burns)
+    }
+
+    fn advance_tip_in_burn_block(chainstate: &mut StacksChainState, parent_header_info: &StacksHeaderInfo, burn_header_hash: &BurnchainHeaderHash, block_reward: &mut MinerPaymentSchedule, user_burns: &mut Vec<StagingUserBurnSupport>) -> StacksHeaderInfo {
         let mut new_tip = parent_header_info.clone();
 
         new_tip.anchored_header.parent_block = parent_header_info.anchored_header.block_hash();
@@ -582,7 +587,7 @@ mod test {
         new_tip.anchored_header.total_work.work = parent_header_info.anchored_header.total_work.work + 1;
         new_tip.microblock_tail = None;
         new_tip.block_height = parent_header_info.block_height + 1;
-        new_tip.burn_header_hash = BurnchainHeaderHash(Sha512Trunc256Sum::from_data(&parent_header_info.burn_header_hash.0).0);
+        new_tip.burn_header_hash = burn_header_hash.clone();
 
         block_reward.parent_burn_header_hash = parent_header_info.burn_header_hash.clone();
         block_reward.parent_block_hash = parent_header_info.anchored_header.block_hash().clone();
@@ -658,6 +663,37 @@ mod test {
         }
     }
 
+    #[test]
+    fn get_header_tips() {
+        let mut chainstate = instantiate_chainstate(false, 0x80000000, "get_header_tips_test");
+        let mut reward = make_dummy_miner_payment_schedule(&StacksAddress { version: 0, bytes: Hash160([0u8; 20]) }, 0, 0, 0, 0, 0);
+
+        // only the boot block so far
+        let tips = StacksChainState::get_header_tips(&chainstate.headers_db, 10).unwrap();
+        assert_eq!(tips.len(), 1);
+        assert_eq!(tips[0].block_height, 0);
+
+        let genesis = StacksHeaderInfo::genesis_block_header_info(TrieHash([0u8; 32]));
+        let tip_1 = advance_tip(&mut chainstate, &genesis, &mut reward, &mut vec![]);
+        let tip_2 = advance_tip(&mut chainstate, &tip_1, &mut reward, &mut vec![]);
+
+        let tips = StacksChainState::get_header_tips(&chainstate.headers_db, 10).unwrap();
+        assert_eq!(tips, vec![tip_2.clone()]);
+
+        // fork off of block 1 in a different burnchain block.  The fork's block 2 has the same
+        // Stacks block hash as the original block 2, so only its burnchain block tells them apart.
+        let fork_tip_2 = advance_tip_in_burn_block(&mut chainstate, &tip_1, &BurnchainHeaderHash([0x22; 32]), &mut reward, &mut vec![]);
+        assert_eq!(fork_tip_2.anchored_header.block_hash(), tip_2.anchored_header.block_hash());
+        let fork_tip_3 = advance_tip(&mut chainstate, &fork_tip_2, &mut reward, &mut vec![]);
+
+        let tips = StacksChainState::get_header_tips(&chainstate.headers_db, 10).unwrap();
+        assert_eq!(tips, vec![fork_tip_3.clone(), tip_2.clone()]);
+
+        // highest first
+        let tips = StacksChainState::get_header_tips(&chainstate.headers_db, 1).unwrap();
+        assert_eq!(tips, vec![fork_tip_3.clone()]);
+    }
+
     #[test]
     fn load_store_miner_payment_schedule() {
         let mut chainstate = instantiate_chainstate(false, 0x80000000, "load_store_miner_payment_schedule");
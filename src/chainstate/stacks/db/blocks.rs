@@ -97,6 +97,11 @@ use vm::clarity::{
 pub use vm::analysis::errors::{CheckErrors, CheckError};
 
 use vm::database::ClarityDatabase;
+use vm::clarity::Error as clarity_error;
+use vm::errors::Error as InterpreterError;
+use vm::errors::InterpreterError as InterpreterFailure;
+
+use chainstate::stacks::index::Error as marf_error;
 
 use vm::contracts::Contract;
 
@@ -167,6 +172,8 @@ pub enum MemPoolRejection {
     NoSuchChainTip(BurnchainHeaderHash,BlockHeaderHash),
     ConflictingNonceInMempool,
     TooMuchChaining,
+    TooManyPendingTransactions(u64),
+    MemPoolFull(u64, u64),
     DBError(db_error),
     Other(String),
 }
@@ -181,6 +188,12 @@ impl MemPoolRejection {
                                           Some(json!({"message": e.to_string()}))),
             TooMuchChaining => ("TooMuchChaining",
                                 Some(json!({"message": "Nonce would exceed chaining limit in mempool"}))),
+            TooManyPendingTransactions(limit) => ("TooManyPendingTransactions",
+                                                  Some(json!({"limit": limit}))),
+            MemPoolFull(actual, expected) => ("MemPoolFull",
+                                              Some(json!({
+                                                  "expected": expected,
+                                                  "actual": actual}))),
             FailedToValidate(e) => ("SignatureValidation",
                                     Some(json!({"message": e.to_string()}))),
             FeeTooLow(actual, expected) => ("FeeTooLow", 
@@ -2750,6 +2763,22 @@ impl StacksChainState {
         Ok(())
     }
 
+    /// Did a block fail to process because it needs Clarity state that was pruned from this node
+    /// (see MARF::prune_blocks())?  Such a block isn't invalid, since an archive node can process it,
+    /// but this node can't process it or any of its descendants until it's resynced.
+    pub fn is_pruned_state_error(e: &Error) -> bool {
+        match e {
+            Error::MARFError(marf_error::PrunedError) => true,
+            Error::ClarityError(clarity_error::Interpreter(InterpreterError::Interpreter(InterpreterFailure::MarfFailure(ref e)))) => {
+                match e.err {
+                    marf_error::PrunedError => true,
+                    _ => false
+                }
+            },
+            _ => false
+        }
+    }
+
     /// Process the next pre-processed staging block.
     /// We've already processed parent_chain_tip.  chain_tip refers to a block we have _not_
     /// processed yet.
@@ -2811,10 +2840,20 @@ impl StacksChainState {
                        last_microblock_hash, last_microblock_seq, block.block_hash(), block.header.parent_microblock, block.header.parent_microblock_sequence);
             }
             
+            // a pruned node can't build on a pruned parent's state
+            let parent_index_block = StacksChainState::get_parent_index_block(&parent_burn_header_hash, &parent_block_hash);
+            if clarity_instance.with_marf(|marf| marf.borrow_storage_backend().is_block_pruned(&parent_index_block)).map_err(Error::MARFError)? {
+                return Err(Error::MARFError(marf_error::PrunedError));
+            }
+
             let mut clarity_tx = StacksChainState::chainstate_block_begin(chainstate_tx, clarity_instance, &parent_burn_header_hash, &parent_block_hash, &MINER_BLOCK_BURN_HEADER_HASH, &MINER_BLOCK_HEADER_HASH);
 
             // process microblock stream
             let (microblock_fees, microblock_burns, mut microblock_txs_receipts) = match StacksChainState::process_microblocks_transactions(&mut clarity_tx, &microblocks) {
+                Err((e, _)) if StacksChainState::is_pruned_state_error(&e) => {
+                    clarity_tx.rollback_block();
+                    return Err(e);
+                },
                 Err((e, offending_mblock_header_hash)) => {
                     let msg = format!("Invalid Stacks microblocks {},{} (offender {}): {:?}", block.header.parent_microblock, block.header.parent_microblock_sequence, offending_mblock_header_hash, &e);
                     warn!("{}", &msg);
@@ -2836,6 +2875,10 @@ impl StacksChainState {
 
             // process anchored block
             let (block_fees, block_burns, mut txs_receipts) = match StacksChainState::process_block_transactions(&mut clarity_tx, &block) {
+                Err(e) if StacksChainState::is_pruned_state_error(&e) => {
+                    clarity_tx.rollback_block();
+                    return Err(e);
+                },
                 Err(e) => {
                     let msg = format!("Invalid Stacks block {}: {:?}", block.block_hash(), &e);
                     warn!("{}", &msg);
@@ -2905,6 +2948,9 @@ impl StacksChainState {
                                                     user_burns)
             .expect("FATAL: failed to advance chain tip");
 
+        StacksChainState::insert_transaction_receipts(&mut chainstate_tx.headers_tx, &new_tip.index_block_hash(), block, microblocks, &txs_receipts)
+            .expect("FATAL: failed to store transaction receipts");
+
         let epoch_receipt = StacksEpochReceipt {
             header: new_tip, 
             tx_receipts: txs_receipts,
@@ -3090,6 +3136,12 @@ impl StacksChainState {
                                                  next_staging_block.sortition_burn,
                                                  &user_supports) {
             Ok(next_chain_tip_info) => next_chain_tip_info,
+            Err(e) if StacksChainState::is_pruned_state_error(&e) => {
+                // this node can't evaluate this block, but an archive node can -- so leave it
+                // unprocessed instead of deciding it's invalid.
+                error!("Cannot process {}/{}: it needs Clarity state that was pruned from this node", &next_staging_block.burn_header_hash, &block.block_hash());
+                return Err(e);
+            },
             Err(e) => {
                 // something's wrong with this epoch -- either a microblock was invalid, or the
                 // anchored block was invalid.  Either way, the anchored block will _never be_
@@ -5164,6 +5216,23 @@ pub mod test {
         }
     }
 
+    #[test]
+    fn test_is_pruned_state_error() {
+        use vm::errors::IncomparableError;
+
+        let marf_failure = |err| chainstate_error::ClarityError(clarity_error::Interpreter(
+            InterpreterError::Interpreter(InterpreterFailure::MarfFailure(IncomparableError { err }))));
+
+        // blocks that fail this way are left unprocessed...
+        assert!(StacksChainState::is_pruned_state_error(&chainstate_error::MARFError(marf_error::PrunedError)));
+        assert!(StacksChainState::is_pruned_state_error(&marf_failure(marf_error::PrunedError)));
+
+        // ...but not blocks that fail in any other way
+        assert!(!StacksChainState::is_pruned_state_error(&chainstate_error::MARFError(marf_error::NotFoundError)));
+        assert!(!StacksChainState::is_pruned_state_error(&marf_failure(marf_error::NotFoundError)));
+        assert!(!StacksChainState::is_pruned_state_error(&chainstate_error::InvalidStacksBlock("invalid".to_string())));
+    }
+
     // TODO: test multiple anchored blocks confirming the same microblock stream (in the same
     // place, and different places, with/without orphans)
     // TODO: process_next_staging_block
@@ -23,6 +23,7 @@ use rusqlite::types::ToSql;
 use std::io;
 use std::io::prelude::*;
 use std::fmt;
+use std::cmp;
 use std::fs;
 use std::collections::HashMap;
 
@@ -42,7 +43,9 @@ use util::db::{
     query_row,
     query_rows,
     query_row_columns,
-    query_count
+    query_count,
+    get_ancestor_block_hash,
+    u64_to_sql
 };
 
 use core::FIRST_STACKS_BLOCK_HASH;
@@ -211,6 +214,69 @@ impl StacksChainState {
         }
     }
 
+    /// Get the header info of the ancestor of the given chain tip at the given height, without
+    /// opening a transaction.  Returns None if the tip is unknown or if the height is beyond it.
+    pub fn get_ancestor_header_info(&self, tip_index_hash: &StacksBlockId, height: u64) -> Result<Option<StacksHeaderInfo>, Error> {
+        let tip_info = match StacksChainState::get_stacks_block_header_info_by_index_block_hash(&self.headers_db, tip_index_hash)? {
+            Some(tip_info) => tip_info,
+            None => {
+                return Ok(None);
+            }
+        };
+        if height > tip_info.block_height {
+            return Ok(None);
+        }
+        if height == tip_info.block_height {
+            return Ok(Some(tip_info));
+        }
+        match get_ancestor_block_hash(&self.headers_state_index, height, tip_index_hash).map_err(Error::DBError)? {
+            Some(bhh) => {
+                StacksChainState::get_stacks_block_header_info_by_index_block_hash(&self.headers_db, &bhh)
+            },
+            None => {
+                Ok(None)
+            }
+        }
+    }
+
+    /// Get up to `count` consecutive header infos from the fork that ends at the given chain tip,
+    /// starting at `start_height`.  The list stops early at the chain tip.
+    pub fn get_ancestor_header_infos(&self, tip_index_hash: &StacksBlockId, start_height: u64, count: u64) -> Result<Vec<StacksHeaderInfo>, Error> {
+        let tip_height = match StacksChainState::get_stacks_block_header_info_by_index_block_hash(&self.headers_db, tip_index_hash)? {
+            Some(tip_info) => tip_info.block_height,
+            None => {
+                return Ok(vec![]);
+            }
+        };
+
+        let end_height = cmp::min(start_height.saturating_add(count), tip_height.saturating_add(1));
+        let mut ret = vec![];
+        for height in start_height..end_height {
+            match self.get_ancestor_header_info(tip_index_hash, height)? {
+                Some(header_info) => ret.push(header_info),
+                None => break
+            }
+        }
+        Ok(ret)
+    }
+
+    /// Get the header infos of the heads of every known fork -- the processed blocks that no other
+    /// processed block builds on -- highest first.  At most `limit` are returned.  If no blocks
+    /// have been processed yet, this is just the boot block's header.
+    pub fn get_header_tips(conn: &Connection, limit: u64) -> Result<Vec<StacksHeaderInfo>, Error> {
+        // every processed block has a payments row that names its parent.  The boot block's
+        // header is synthetic and isn't named by its children, so it's handled separately.
+        let sql = "SELECT * FROM block_headers WHERE block_height > 0 AND NOT EXISTS \
+                   (SELECT 1 FROM payments WHERE payments.parent_block_hash = block_headers.block_hash AND payments.parent_burn_header_hash = block_headers.burn_header_hash) \
+                   ORDER BY block_height DESC, index_block_hash ASC LIMIT ?1";
+        let args: &[&dyn ToSql] = &[&u64_to_sql(limit)?];
+        let tips = query_rows::<StacksHeaderInfo, _>(conn, sql, args).map_err(Error::DBError)?;
+        if tips.len() > 0 || limit == 0 {
+            return Ok(tips);
+        }
+        Ok(vec![StacksChainState::get_genesis_header_info(conn)?])
+    }
+
     /// Get the genesis (boot code) block header
     pub fn get_genesis_header_info(conn: &Connection) -> Result<StacksHeaderInfo, Error> {
         // by construction, only one block can have height 0 in this DB
@@ -0,0 +1,309 @@
+/*
+ copyright: (c) 2013-2020 by Blockstack PBC, a public benefit corporation.
+
+ This file is part of Blockstack.
+
+ Blockstack is free software. You may redistribute or modify
+ it under the terms of the GNU General Public License as published by
+ the Free Software Foundation, either version 3 of the License or
+ (at your option) any later version.
+
+ Blockstack is distributed in the hope that it will be useful,
+ but WITHOUT ANY WARRANTY, including without the implied warranty of
+ MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ GNU General Public License for more details.
+
+ You should have received a copy of the GNU General Public License
+ along with Blockstack. If not, see <http://www.gnu.org/licenses/>.
+*/
+
+//! Integrity checks for the chainstate's MARFs, e.g. after an unclean shutdown.
+//!
+//! The chainstate has two MARFs: the headers index, whose root hashes the block headers record
+//! as index_root, and the Clarity state, whose root hashes they record as state_index_root.
+//! Checking a MARF walks its confirmed tries in the order they were stored (so each trie comes
+//! after its ancestors' tries), and for each one:
+//! * recomputes the hash of every node, and makes sure that every back-pointer resolves to a node
+//! in an ancestor's trie (see Trie::check_trie()).  Only what's left of a pruned trie is checked.
+//! * compares its root hash to the one in the block's header, if the block has a header.
+//! The walk stops at the first trie that fails.  Checking repairs nothing.  A MARF can be
+//! truncated at the first corrupt block, which deletes that block's trie and the tries of every
+//! block built on it, but only the MARF is rolled back: the block headers, the staging blocks and
+//! the sortition DB still record the deleted blocks as processed, so those blocks have to be
+//! processed again before the chainstate is usable.  Otherwise, a corrupt chainstate has to be
+//! rebuilt (e.g. from a snapshot, or by processing all the blocks again).
+
+use std::fs;
+
+use rusqlite::Connection;
+use rusqlite::OpenFlags;
+
+use chainstate::stacks::Error;
+use chainstate::stacks::*;
+use chainstate::stacks::db::*;
+
+use chainstate::stacks::index::TrieHash;
+use chainstate::stacks::index::marf::MARF;
+use chainstate::stacks::index::Error as marf_error;
+
+use util::db::Error as db_error;
+use util::db::query_row;
+
+/// One of the chainstate's MARFs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChainstateIndex {
+    /// the headers index, whose root hashes are the headers' index_root
+    Headers,
+    /// the Clarity state, whose root hashes are the headers' state_index_root
+    ClarityState
+}
+
+impl ChainstateIndex {
+    fn marf_path(&self, chainstate_dir: &str) -> String {
+        match *self {
+            ChainstateIndex::Headers => format!("{}/vm/index", chainstate_dir),
+            ChainstateIndex::ClarityState => format!("{}/vm/clarity/marf", chainstate_dir)
+        }
+    }
+
+    fn root_column(&self) -> &'static str {
+        match *self {
+            ChainstateIndex::Headers => "index_root",
+            ChainstateIndex::ClarityState => "state_index_root"
+        }
+    }
+
+    /// The root hash the header records for this MARF, if any.  The boot block's header has no
+    /// Clarity state root.
+    fn header_root<'a>(&self, header: &'a StacksHeaderInfo) -> Option<&'a TrieHash> {
+        match *self {
+            ChainstateIndex::Headers => Some(&header.index_root),
+            ChainstateIndex::ClarityState => {
+                if header.block_height == 0 {
+                    None
+                }
+                else {
+                    Some(&header.anchored_header.state_index_root)
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorruptBlock {
+    pub block: StacksBlockId,
+    pub reason: String
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarfCheckReport {
+    /// tries whose nodes were checked
+    pub num_checked: u64,
+    /// nodes whose hashes were recomputed
+    pub num_nodes: u64,
+    /// pruned tries, of which only the remaining nodes were checked
+    pub num_pruned: u64,
+    /// tries whose root hashes matched their headers'
+    pub num_matched: u64,
+    /// the first trie that failed a check
+    pub first_corrupt: Option<CorruptBlock>
+}
+
+fn open_marf(chainstate_dir: &str, index: ChainstateIndex) -> Result<MARF<StacksBlockId>, Error> {
+    let marf_path = index.marf_path(chainstate_dir);
+    if fs::metadata(&marf_path).is_err() {
+        error!("No such file or directory: {}", &marf_path);
+        return Err(Error::DBError(db_error::NoDBError));
+    }
+    MARF::from_path(&marf_path).map_err(Error::MARFError)
+}
+
+/// Check one of a chainstate's MARFs against the chainstate's block headers, and report the first
+/// corrupt trie.  The node must not be running: the MARF is opened for writing (but not written
+/// to), since recomputing node hashes reads through the MARF's write path.
+pub fn check_marf(chainstate_dir: &str, index: ChainstateIndex) -> Result<MarfCheckReport, Error> {
+    let marf_path = index.marf_path(chainstate_dir);
+    let headers_db_path = format!("{}/vm/headers.db", chainstate_dir);
+    if fs::metadata(&headers_db_path).is_err() {
+        error!("No such file or directory: {}", &headers_db_path);
+        return Err(Error::DBError(db_error::NoDBError));
+    }
+
+    let headers_db = Connection::open_with_flags(&headers_db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| Error::DBError(db_error::SqliteError(e)))?;
+    let mut marf = open_marf(chainstate_dir, index)?;
+
+    let mut report = MarfCheckReport {
+        num_checked: 0,
+        num_nodes: 0,
+        num_pruned: 0,
+        num_matched: 0,
+        first_corrupt: None
+    };
+
+    let block_ids = marf.borrow_storage_backend().read_all_block_ids().map_err(Error::MARFError)?;
+    for (_, block_hash, unconfirmed) in block_ids.into_iter() {
+        if unconfirmed {
+            // unconfirmed state is rebuilt from the microblock stream as needed
+            continue;
+        }
+        if let Some(reason) = check_block(&mut marf, &headers_db, index, &block_hash, &mut report)? {
+            warn!("Corrupt trie for block {} in {}: {}", &block_hash, &marf_path, &reason);
+            report.first_corrupt = Some(CorruptBlock {
+                block: block_hash,
+                reason
+            });
+            break;
+        }
+    }
+
+    Ok(report)
+}
+
+/// The blocks truncate_marf() would delete from one of a chainstate's MARFs: the given block and
+/// every block built on it, in the order their tries were stored.
+pub fn get_truncated_blocks(chainstate_dir: &str, index: ChainstateIndex, block: &StacksBlockId) -> Result<Vec<StacksBlockId>, Error> {
+    let mut marf = open_marf(chainstate_dir, index)?;
+    marf.borrow_storage_backend().get_truncated_blocks(block).map_err(Error::MARFError)
+}
+
+/// Roll one of a chainstate's MARFs back to before the given block (e.g. the first corrupt block
+/// check_marf() found), by deleting its trie and the tries of every block built on it.  Only the
+/// MARF is rolled back, so the deleted blocks have to be processed again, in the order they're
+/// returned in.  The node must not be running.
+pub fn truncate_marf(chainstate_dir: &str, index: ChainstateIndex, block: &StacksBlockId) -> Result<Vec<StacksBlockId>, Error> {
+    let mut marf = open_marf(chainstate_dir, index)?;
+    let deleted = marf.truncate_at(block).map_err(Error::MARFError)?;
+    info!("Truncated {} tries from {} at {}", deleted.len(), &index.marf_path(chainstate_dir), block);
+    Ok(deleted)
+}
+
+/// Check a block's trie, and return why it's corrupt (if it is)
+fn check_block(marf: &mut MARF<StacksBlockId>, headers_db: &Connection, index: ChainstateIndex, block_hash: &StacksBlockId,
+               report: &mut MarfCheckReport) -> Result<Option<String>, Error> {
+    if marf.borrow_storage_backend().is_block_pruned(block_hash).map_err(Error::MARFError)? {
+        report.num_pruned += 1;
+    }
+    match marf.check_trie_at(block_hash) {
+        Ok(num_nodes) => {
+            report.num_checked += 1;
+            report.num_nodes += num_nodes;
+        },
+        Err(marf_error::CorruptionError(reason)) => {
+            return Ok(Some(reason));
+        },
+        Err(e) => {
+            return Ok(Some(format!("Failed to check trie: {:?}", &e)));
+        }
+    }
+
+    let header_opt : Option<StacksHeaderInfo> = query_row(headers_db, "SELECT * FROM block_headers WHERE index_block_hash = ?1", &[block_hash])
+        .map_err(Error::DBError)?;
+
+    let header_root = match header_opt.as_ref().and_then(|header| index.header_root(header)) {
+        Some(header_root) => header_root,
+        None => {
+            return Ok(None);
+        }
+    };
+
+    let root_hash = match marf.get_root_hash_at(block_hash) {
+        Ok(root_hash) => root_hash,
+        Err(e) => {
+            return Ok(Some(format!("Failed to read root hash: {:?}", &e)));
+        }
+    };
+
+    if root_hash != *header_root {
+        return Ok(Some(format!("Root hash is {}, but the block's header has {} {}", &root_hash, index.root_column(), header_root)));
+    }
+
+    report.num_matched += 1;
+    Ok(None)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::path::PathBuf;
+
+    use rusqlite::types::ToSql;
+
+    use chainstate::stacks::db::test::*;
+    use chainstate::stacks::index::storage::TrieFileStorage;
+    use core::FIRST_STACKS_BLOCK_HASH;
+    use core::FIRST_BURNCHAIN_BLOCK_HASH;
+
+    #[test]
+    fn test_check_marf() {
+        let chainstate = instantiate_chainstate(false, 0x80000000, "check-marf");
+        let boot_block = StacksBlockHeader::make_index_block_hash(&FIRST_BURNCHAIN_BLOCK_HASH, &FIRST_STACKS_BLOCK_HASH);
+
+        // vm/clarity is in the chainstate directory
+        let mut chainstate_dir = PathBuf::from(&chainstate.clarity_state_index_root);
+        chainstate_dir.pop();
+        chainstate_dir.pop();
+        let chainstate_dir = chainstate_dir.to_str().unwrap().to_string();
+
+        // a fresh chainstate is sane.  Only the headers index can be cross-checked against the
+        // boot block's header.
+        let report = check_marf(&chainstate_dir, ChainstateIndex::Headers).unwrap();
+        assert!(report.num_checked > 0);
+        assert!(report.num_nodes > 0);
+        assert_eq!(report.num_matched, 1);
+        assert_eq!(report.first_corrupt, None);
+
+        let report = check_marf(&chainstate_dir, ChainstateIndex::ClarityState).unwrap();
+        assert!(report.num_checked > 0);
+        assert_eq!(report.num_matched, 0);
+        assert_eq!(report.first_corrupt, None);
+
+        // a header that disagrees with the MARF
+        let headers_db = Connection::open(&format!("{}/vm/headers.db", &chainstate_dir)).unwrap();
+        let index_root : String = headers_db.query_row("SELECT index_root FROM block_headers WHERE index_block_hash = ?1", &[&boot_block], |row| row.get(0)).unwrap();
+        let args : &[&dyn ToSql] = &[&TrieHash([0x01; 32]), &boot_block];
+        headers_db.execute("UPDATE block_headers SET index_root = ?1 WHERE index_block_hash = ?2", args).unwrap();
+
+        let report = check_marf(&chainstate_dir, ChainstateIndex::Headers).unwrap();
+        assert_eq!(report.first_corrupt.unwrap().block, boot_block);
+
+        let args : &[&dyn ToSql] = &[&index_root, &boot_block];
+        headers_db.execute("UPDATE block_headers SET index_root = ?1 WHERE index_block_hash = ?2", args).unwrap();
+
+        // a trie whose root node's hash is wrong
+        let marf_db = Connection::open(&format!("{}/vm/clarity/marf", &chainstate_dir)).unwrap();
+        let mut data : Vec<u8> = marf_db.query_row("SELECT data FROM marf_data WHERE block_hash = ?1", &[&boot_block], |row| row.get(0)).unwrap();
+        let root_hash_offset = TrieFileStorage::<StacksBlockId>::root_ptr_disk() as usize;
+        data[root_hash_offset] ^= 0x01;
+        let args : &[&dyn ToSql] = &[&data, &boot_block];
+        marf_db.execute("UPDATE marf_data SET data = ?1 WHERE block_hash = ?2", args).unwrap();
+
+        let report = check_marf(&chainstate_dir, ChainstateIndex::ClarityState).unwrap();
+        assert_eq!(report.first_corrupt.as_ref().unwrap().block, boot_block);
+
+        // checking doesn't change anything
+        let num_tries : i64 = marf_db.query_row("SELECT COUNT(*) FROM marf_data WHERE block_hash = ?1", &[&boot_block], |row| row.get(0)).unwrap();
+        assert_eq!(num_tries, 1);
+        let report = check_marf(&chainstate_dir, ChainstateIndex::ClarityState).unwrap();
+        assert_eq!(report.first_corrupt.as_ref().unwrap().block, boot_block);
+
+        // the headers index is still sane
+        let report = check_marf(&chainstate_dir, ChainstateIndex::Headers).unwrap();
+        assert_eq!(report.num_matched, 1);
+        assert_eq!(report.first_corrupt, None);
+
+        // truncating at the corrupt block deletes it and nothing else
+        assert_eq!(get_truncated_blocks(&chainstate_dir, ChainstateIndex::ClarityState, &boot_block).unwrap(), vec![boot_block.clone()]);
+        assert_eq!(truncate_marf(&chainstate_dir, ChainstateIndex::ClarityState, &boot_block).unwrap(), vec![boot_block.clone()]);
+
+        let report = check_marf(&chainstate_dir, ChainstateIndex::ClarityState).unwrap();
+        assert_eq!(report.num_checked, 0);
+        assert_eq!(report.first_corrupt, None);
+
+        let report = check_marf(&chainstate_dir, ChainstateIndex::Headers).unwrap();
+        assert_eq!(report.num_matched, 1);
+        assert_eq!(report.first_corrupt, None);
+    }
+}
@@ -23,6 +23,8 @@ pub mod contracts;
 pub mod headers;
 pub mod transactions;
 pub mod unconfirmed;
+pub mod snapshot;
+pub mod integrity;
 
 use rusqlite::Transaction;
 use rusqlite::Connection;
@@ -71,6 +73,7 @@ use util::db::{
     IndexDBTx,
     query_rows,
     query_count,
+    query_int,
     FromRow,
     FromColumn,
     db_mkdirs,
@@ -470,6 +473,67 @@ const STACKS_CHAIN_STATE_SQL : &'static [&'static str]= &[
     )"#
 ];
 
+/// The (opt-in) transaction index.  It's only created once enabled, and only covers the blocks
+/// processed since then.
+const STACKS_CHAIN_STATE_TRANSACTION_INDEX_SQL : &'static [&'static str] = &[
+    r#"
+    -- receipts of transactions that were confirmed by an anchored block (either in the block itself,
+    -- or in the microblock stream it confirms)
+    CREATE TABLE IF NOT EXISTS transactions(
+        txid TEXT NOT NULL,
+        index_block_hash TEXT NOT NULL,         -- anchored block that confirmed this transaction
+        microblock_hash TEXT,                   -- set only if the transaction was mined in a microblock
+        tx_index INTEGER NOT NULL,              -- position of the transaction within its (micro)block
+        tx BLOB NOT NULL,
+        result TEXT NOT NULL,                   -- hex-encoded Clarity value
+        post_condition_aborted INTEGER NOT NULL,
+        events TEXT NOT NULL,                   -- JSON-encoded list of events
+        execution_cost TEXT NOT NULL,           -- JSON-encoded ExecutionCost
+
+        PRIMARY KEY(txid,index_block_hash)
+    );
+    "#,
+    r#"
+    CREATE INDEX IF NOT EXISTS txs_by_index_block_hash ON transactions(index_block_hash);
+    "#,
+];
+
+/// Tables and indexes added to the chain state database after its schema was first released.  They are
+/// created whenever the database is opened and they're missing, so an existing chain state picks
+/// them up without having to be rebuilt.  New tables only cover the blocks processed since then.
+const STACKS_CHAIN_STATE_ADDED_SQL : &'static [&'static str] = &[
+    r#"
+    -- fee rates (per byte) paid by the transactions an anchored block confirmed (either in the block
+    -- itself, or in the microblock stream it confirms), sampled for fee estimation.  Coinbases pay
+    -- no fee, so they aren't sampled.
+    CREATE TABLE IF NOT EXISTS fee_rate_samples(
+        index_block_hash TEXT NOT NULL,         -- anchored block that confirmed the transaction
+        position INTEGER NOT NULL,              -- position of the transaction among those the block confirmed
+        fee_rate INTEGER NOT NULL,
+
+        PRIMARY KEY(index_block_hash,position)
+    );
+    "#,
+    r#"
+    -- principals that received a fungible or non-fungible token in a confirmed transaction.
+    -- These are only candidate holdings -- the asset may have since moved on, so they must be
+    -- checked against the Clarity state of the chain tip being queried.
+    CREATE TABLE IF NOT EXISTS asset_holdings(
+        principal TEXT NOT NULL,
+        contract_identifier TEXT NOT NULL,
+        asset_name TEXT NOT NULL,
+        value TEXT NOT NULL,                    -- hex-encoded NFT identifier, or '' for fungible tokens
+        index_block_hash TEXT NOT NULL,         -- anchored block that confirmed the transfer or mint
+
+        PRIMARY KEY(principal,contract_identifier,asset_name,value,index_block_hash)
+    );
+    "#,
+    r#"
+    -- lets a block's children be found from its payments rows, to tell which blocks are fork heads.
+    CREATE INDEX IF NOT EXISTS payments_parent_index ON payments(parent_block_hash,parent_burn_header_hash);
+    "#,
+];
+
 /// Built-in "system-level" smart contracts that are there from the beginning.
 /// Includes BNS and the miner trust fund.
 #[cfg(test)]
@@ -532,7 +596,7 @@ impl StacksChainState {
     fn instantiate_headers_db(conn: &mut DBConn, mainnet: bool, chain_id: u32, marf_path: &str) -> Result<(), Error> {
         let tx = tx_begin_immediate(conn)?;
         
-        for cmd in STACKS_CHAIN_STATE_SQL {
+        for cmd in STACKS_CHAIN_STATE_SQL.iter().chain(STACKS_CHAIN_STATE_ADDED_SQL.iter()) {
             tx.execute(cmd, NO_PARAMS).map_err(|e| Error::DBError(db_error::SqliteError(e)))?;
         }
 
@@ -588,10 +652,28 @@ impl StacksChainState {
                 error!("Invalid chain ID: expected {}, got {}", chain_id, db_config.chain_id);
                 return Err(Error::InvalidChainstateDB);
             }
+
+            StacksChainState::add_missing_tables(&mut conn)?;
         }
 
         Ok(conn)
     }
+
+    /// Create the tables that were added to the chain state database since it was created
+    fn add_missing_tables(conn: &mut DBConn) -> Result<(), Error> {
+        let tx = tx_begin_immediate(conn)?;
+        for cmd in STACKS_CHAIN_STATE_ADDED_SQL.iter() {
+            tx.execute(cmd, NO_PARAMS).map_err(|e| Error::DBError(db_error::SqliteError(e)))?;
+        }
+        tx.commit().map_err(|e| Error::DBError(db_error::SqliteError(e)))?;
+        Ok(())
+    }
+
+    fn has_table(conn: &DBConn, table: &str) -> Result<bool, Error> {
+        let num_tables = query_count(conn, &"SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1".to_string(), &[&table])
+            .map_err(Error::DBError)?;
+        Ok(num_tables > 0)
+    }
     
     pub fn open_index(marf_path: &str) -> Result<MARF<StacksBlockId>, Error> {
         test_debug!("Open MARF index at {}", marf_path);
@@ -957,6 +1039,47 @@ impl StacksChainState {
         res
     }
 
+    /// Start maintaining the (opt-in) index of Clarity data map keys, so that the live keys of a
+    /// contract's data maps can be paged through at a given chain tip.  Only keys written by blocks
+    /// processed from now on are indexed, and keys already written can't be recovered -- so the
+    /// index can only be started on a chainstate which holds nothing past the boot block.
+    pub fn enable_map_key_index(&mut self) -> Result<(), Error> {
+        if !self.clarity_state.has_map_key_index() {
+            let max_height = query_int(&self.headers_db, &"SELECT IFNULL(MAX(block_height), 0) FROM block_headers".to_string(), NO_PARAMS)
+                .map_err(Error::DBError)?;
+            if max_height > 0 {
+                error!("Refusing to index data map keys: this chainstate has already processed blocks up to height {}, whose keys would be missing from the index", max_height);
+                return Err(Error::InvalidChainstateDB);
+            }
+        }
+        self.clarity_state.enable_map_key_index()
+            .map_err(|e| Error::ClarityError(e.into()))
+    }
+
+    /// Attribute the execution cost of the contract calls and contract initializations in the
+    /// blocks processed from now on to what incurred it, and record that in their receipts.
+    pub fn set_cost_profiling(&mut self, enabled: bool) {
+        self.clarity_state.set_cost_profiling(enabled);
+    }
+
+    /// Is the (opt-in) index of confirmed transactions and their receipts maintained in this
+    /// chainstate?
+    pub fn has_transaction_index(&self) -> Result<bool, Error> {
+        StacksChainState::has_table(&self.headers_db, "transactions")
+    }
+
+    /// Start maintaining the (opt-in) index of confirmed transactions and their receipts, so that
+    /// they can be looked up by txid.  Once enabled, the index persists.  Only transactions
+    /// confirmed by blocks processed from now on are indexed.
+    pub fn enable_transaction_index(&mut self) -> Result<(), Error> {
+        let tx = tx_begin_immediate(&mut self.headers_db)?;
+        for cmd in STACKS_CHAIN_STATE_TRANSACTION_INDEX_SQL.iter() {
+            tx.execute(cmd, NO_PARAMS).map_err(|e| Error::DBError(db_error::SqliteError(e)))?;
+        }
+        tx.commit().map_err(|e| Error::DBError(db_error::SqliteError(e)))?;
+        Ok(())
+    }
+
     /// Run to_do on the unconfirmed Clarity VM state if the tip refers to the unconfirmed state;
     /// otherwise run to_do on the confirmed state of the Clarity VM.  If the tip doesn't exist,
     /// then return None.
@@ -1059,6 +1182,19 @@ impl StacksChainState {
         }
     }
    
+    /// The keys and values that a (non-boot) block adds to the headers index
+    fn headers_index_entries(new_tip: &StacksBlockHeader) -> (Vec<String>, Vec<String>) {
+        let indexed_keys = vec![
+            format!("chainstate::pubkey_hash::{}", new_tip.microblock_pubkey_hash)
+        ];
+
+        let indexed_values = vec![
+            "1".to_string()
+        ];
+
+        (indexed_keys, indexed_values)
+    }
+   
     /// Append a Stacks block to an existing Stacks block, and grant the miner the block reward.
     /// Return the new Stacks header info.
     pub fn advance_tip<'a>(headers_tx: &mut StacksDBTx<'a>, 
@@ -1080,13 +1216,7 @@ impl StacksChainState {
                    new_tip.total_work.work);
         
         let parent_hash = StacksChainState::get_index_hash(parent_burn_block, parent_tip); 
-        let indexed_keys = vec![
-            format!("chainstate::pubkey_hash::{}", new_tip.microblock_pubkey_hash)
-        ];
-
-        let indexed_values = vec![
-            "1".to_string()
-        ];
+        let (indexed_keys, indexed_values) = StacksChainState::headers_index_entries(new_tip);
 
         // store each indexed field
         headers_tx.put_indexed_begin(&parent_hash, &new_tip.index_block_hash(new_burn_block))
@@ -1167,4 +1297,54 @@ pub mod test {
             assert!(contract_res.is_some());
         }
     }
+
+    #[test]
+    fn test_open_chainstate_adds_missing_tables() {
+        let chainstate = instantiate_chainstate(false, 0x80000000, "open-chainstate-adds-missing-tables");
+        let mut headers_db_path = PathBuf::from(&chainstate.clarity_state_index_root);
+        headers_db_path.pop();
+        headers_db_path.push("headers.db");
+        drop(chainstate);
+
+        // a chain state from before the tables were added
+        let conn = Connection::open(&headers_db_path).unwrap();
+        conn.execute("DROP TABLE fee_rate_samples", NO_PARAMS).unwrap();
+        conn.execute("DROP TABLE asset_holdings", NO_PARAMS).unwrap();
+        drop(conn);
+
+        let chainstate = open_chainstate(false, 0x80000000, "open-chainstate-adds-missing-tables");
+        let num_samples : i64 = chainstate.headers_db.query_row("SELECT COUNT(*) FROM fee_rate_samples", NO_PARAMS, |row| row.get(0)).unwrap();
+        assert_eq!(num_samples, 0);
+        let num_holdings : i64 = chainstate.headers_db.query_row("SELECT COUNT(*) FROM asset_holdings", NO_PARAMS, |row| row.get(0)).unwrap();
+        assert_eq!(num_holdings, 0);
+    }
+
+    #[test]
+    fn test_enable_transaction_index() {
+        let mut chainstate = instantiate_chainstate(false, 0x80000000, "enable-transaction-index");
+        assert!(!chainstate.has_transaction_index().unwrap());
+        chainstate.enable_transaction_index().unwrap();
+        assert!(chainstate.has_transaction_index().unwrap());
+
+        // enabling it again is a no-op, and it stays enabled
+        chainstate.enable_transaction_index().unwrap();
+        drop(chainstate);
+        let chainstate = open_chainstate(false, 0x80000000, "enable-transaction-index");
+        assert!(chainstate.has_transaction_index().unwrap());
+    }
+
+    #[test]
+    fn test_enable_map_key_index() {
+        let mut chainstate = instantiate_chainstate(false, 0x80000000, "enable-map-key-index");
+        chainstate.enable_map_key_index().unwrap();
+        chainstate.headers_db.execute("UPDATE block_headers SET block_height = 1", NO_PARAMS).unwrap();
+
+        // already maintained, so nothing is missing
+        chainstate.enable_map_key_index().unwrap();
+
+        // keys written before the index is started would never be indexed
+        let mut chainstate = instantiate_chainstate(false, 0x80000000, "enable-map-key-index-late");
+        chainstate.headers_db.execute("UPDATE block_headers SET block_height = 1", NO_PARAMS).unwrap();
+        assert!(chainstate.enable_map_key_index().is_err());
+    }
 }
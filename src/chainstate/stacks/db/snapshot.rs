@@ -0,0 +1,1107 @@
+/*
+ copyright: (c) 2013-2020 by Blockstack PBC, a public benefit corporation.
+
+ This file is part of Blockstack.
+
+ Blockstack is free software. You may redistribute or modify
+ it under the terms of the GNU General Public License as published by
+ the Free Software Foundation, either version 3 of the License or
+ (at your option) any later version.
+
+ Blockstack is distributed in the hope that it will be useful,
+ but WITHOUT ANY WARRANTY, including without the implied warranty of
+ MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ GNU General Public License for more details.
+
+ You should have received a copy of the GNU General Public License
+ along with Blockstack. If not, see <http://www.gnu.org/licenses/>.
+*/
+
+//! Snapshots of the chainstate as of a chain tip, so a new node can start from a snapshot instead
+//! of replaying every block.
+//!
+//! A snapshot archive is a single sqlite file.  It holds:
+//! * the chain tip's Clarity trie, and its ancestors' tries pruned down to what the tip's trie
+//! still refers to through back-pointers (see MARF::prune_blocks()).  That is all it takes to
+//! read and prove the tip's state, and to recompute its state root.  Tries of other forks are
+//! left out.
+//! * the Clarity side-store's data_table, and the metadata_table rows of those blocks.
+//! * the block headers and miner payments of the chain tip and its ancestors.
+//!
+//! Importing a snapshot builds a new chainstate directory: the Clarity MARF and side-store, the
+//! headers DB, and the headers index, which is rebuilt from the block headers.  The archive is
+//! only trusted as far as the chain tip and state root that the caller got from elsewhere: every
+//! trie node must hash up to that state root, every block header must hash to the block ID that
+//! the tip's trie has at its height, and every data_table value must hash to its key.
+//!
+//! The tip's state root doesn't cover the metadata_table (contract sources, analyses and ASTs),
+//! so each contract's metadata is checked against the source that the tip's state commits to:
+//! the analysis and the contract's definitions are rebuilt from the source, and must match.  The
+//! values of its constants and its data size can't be rebuilt without replaying the transaction
+//! that published it, and neither can the miner payments that later blocks' rewards are computed
+//! from, so these are only as good as the node the archive came from.
+//!
+//! A snapshot has no block data, microblock streams, transaction receipts or asset holdings from
+//! before its chain tip.  Blocks that need the pruned state below it can't be processed (see
+//! StacksChainState::is_pruned_state_error()).
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+use serde::de::DeserializeOwned;
+
+use rusqlite::Connection;
+use rusqlite::OpenFlags;
+use rusqlite::Row;
+use rusqlite::NO_PARAMS;
+use rusqlite::types::ToSql;
+
+use chainstate::stacks::Error;
+use chainstate::stacks::*;
+use chainstate::stacks::db::*;
+
+use chainstate::stacks::index::MARFValue;
+use chainstate::stacks::index::TrieHash;
+use chainstate::stacks::index::marf::MARF;
+use chainstate::stacks::index::storage::TrieFileStorage;
+use chainstate::stacks::index::Error as marf_error;
+
+use util::db::Error as db_error;
+use util::db::{
+    FromRow,
+    FromColumn,
+    query_row,
+    tx_begin_immediate,
+};
+
+use util::hash::Sha512Trunc256Sum;
+
+use vm::ast;
+use vm::analysis::{type_check, ContractAnalysis};
+use vm::contracts::Contract;
+use vm::database::{
+    ClarityBackingStore,
+    ClarityDatabase,
+    ClarityDeserializable,
+    ClaritySerializable,
+    DataMapMetadata,
+    DataVariableMetadata,
+    FungibleTokenMetadata,
+    MarfedKV,
+    NonFungibleTokenMetadata,
+    SqliteConnection,
+    StoreType,
+};
+use vm::types::QualifiedContractIdentifier;
+
+pub const SNAPSHOT_VERSION : &'static str = "2";
+
+const SNAPSHOT_SQL : &'static [&'static str] = &[
+    r#"
+    CREATE TABLE snapshot_info(
+        version TEXT NOT NULL,
+        mainnet INTEGER NOT NULL,
+        chain_id INTEGER NOT NULL,
+        tip TEXT NOT NULL,
+        tip_height INTEGER NOT NULL,
+        state_index_root TEXT NOT NULL
+    );
+    "#,
+    r#"
+    -- tries, with the local block IDs that their descendants' back-pointers refer to them by
+    CREATE TABLE marf_data(
+        block_id INTEGER PRIMARY KEY,
+        block_hash TEXT UNIQUE NOT NULL,
+        block_height INTEGER UNIQUE NOT NULL,
+        pruned INTEGER NOT NULL,
+        data BLOB NOT NULL
+    );
+    "#,
+    r#"
+    CREATE TABLE data_table(key TEXT PRIMARY KEY, value TEXT);
+    "#,
+    r#"
+    CREATE TABLE metadata_table(key TEXT NOT NULL, blockhash TEXT, value TEXT, UNIQUE (key, blockhash));
+    "#,
+    r#"
+    CREATE TABLE block_headers(
+        version INTEGER NOT NULL,
+        total_burn TEXT NOT NULL,
+        total_work TEXT NOT NULL,
+        proof TEXT NOT NULL,
+        parent_block TEXT NOT NULL,
+        parent_microblock TEXT NOT NULL,
+        parent_microblock_sequence INTEGER NOT NULL,
+        tx_merkle_root TEXT NOT NULL,
+        state_index_root TEXT NOT NULL,
+        microblock_pubkey_hash TEXT NOT NULL,
+        block_hash TEXT NOT NULL,
+        index_block_hash TEXT UNIQUE NOT NULL,
+        block_height INTEGER NOT NULL,
+        index_root TEXT NOT NULL,
+        burn_header_hash TEXT UNIQUE NOT NULL,
+        burn_header_timestamp INT NOT NULL
+    );
+    "#,
+    r#"
+    CREATE TABLE payments(
+        address TEXT NOT NULL,
+        block_hash TEXT NOT NULL,
+        burn_header_hash TEXT NOT NULL,
+        parent_block_hash TEXT NOT NULL,
+        parent_burn_header_hash TEXT NOT NULL,
+        coinbase TEXT NOT NULL,
+        tx_fees_anchored TEXT NOT NULL,
+        tx_fees_streamed TEXT NOT NULL,
+        stx_burns TEXT NOT NULL,
+        burnchain_commit_burn INT NOT NULL,
+        burnchain_sortition_burn INT NOT NULL,
+        fill TEXT NOT NULL,
+        miner INT NOT NULL,
+        stacks_block_height INTEGER NOT NULL,
+        index_block_hash TEXT NOT NULL,
+        vtxindex INT NOT NULL
+    );
+    "#,
+];
+
+const BLOCK_HEADER_COLUMNS : &'static str = "version, total_burn, total_work, proof, parent_block, parent_microblock, parent_microblock_sequence, \
+                                              tx_merkle_root, state_index_root, microblock_pubkey_hash, block_hash, index_block_hash, \
+                                              block_height, index_root, burn_header_hash, burn_header_timestamp";
+
+const PAYMENT_COLUMNS : &'static str = "address, block_hash, burn_header_hash, parent_block_hash, parent_burn_header_hash, coinbase, \
+                                        tx_fees_anchored, tx_fees_streamed, stx_burns, burnchain_commit_burn, burnchain_sortition_burn, \
+                                        fill, miner, stacks_block_height, index_block_hash, vtxindex";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotInfo {
+    pub version: String,
+    pub mainnet: bool,
+    pub chain_id: u32,
+    pub tip: StacksBlockId,
+    pub tip_height: u64,
+    /// root hash of the chain tip's Clarity trie
+    pub state_index_root: TrieHash
+}
+
+impl FromRow<SnapshotInfo> for SnapshotInfo {
+    fn from_row<'a>(row: &'a Row) -> Result<SnapshotInfo, db_error> {
+        let version : String = row.get("version");
+        let mainnet_i64 : i64 = row.get("mainnet");
+        let chain_id_i64 : i64 = row.get("chain_id");
+        let tip = StacksBlockId::from_column(row, "tip")?;
+        let tip_height = u64::from_column(row, "tip_height")?;
+        let state_index_root = TrieHash::from_column(row, "state_index_root")?;
+
+        Ok(SnapshotInfo {
+            version,
+            mainnet: mainnet_i64 != 0,
+            chain_id: chain_id_i64 as u32,
+            tip,
+            tip_height,
+            state_index_root
+        })
+    }
+}
+
+/// The databases in a chainstate directory that a snapshot covers
+struct ChainstatePaths {
+    clarity_state: String,
+    clarity_marf: String,
+    clarity_data: String,
+    headers_db: String,
+    headers_index: String
+}
+
+impl ChainstatePaths {
+    fn new(chainstate_path: &str) -> ChainstatePaths {
+        let mut path = PathBuf::from(chainstate_path);
+        path.push("vm");
+        let path_str = |path: &PathBuf| path.to_string_lossy().to_string();
+
+        path.push("headers.db");
+        let headers_db = path_str(&path);
+
+        path.pop();
+        path.push("index");
+        let headers_index = path_str(&path);
+
+        path.pop();
+        path.push("clarity");
+        let clarity_state = path_str(&path);
+
+        path.push("marf");
+        let clarity_marf = path_str(&path);
+
+        path.pop();
+        path.push("data.sqlite");
+        let clarity_data = path_str(&path);
+
+        ChainstatePaths {
+            clarity_state,
+            clarity_marf,
+            clarity_data,
+            headers_db,
+            headers_index
+        }
+    }
+}
+
+fn sql_error(e: rusqlite::Error) -> Error {
+    Error::DBError(db_error::SqliteError(e))
+}
+
+fn io_error(e: std::io::Error) -> Error {
+    Error::DBError(db_error::IOError(e))
+}
+
+fn has_table(conn: &Connection, schema: &str, table: &str) -> Result<bool, Error> {
+    let sql = format!("SELECT 1 FROM {}.sqlite_master WHERE type = 'table' AND name = ?1", schema);
+    let row : Option<i64> = query_row(conn, &sql, &[&table])?;
+    Ok(row.is_some())
+}
+
+/// Export the chainstate as of the given chain tip to a new snapshot archive.
+/// chainstate_path is the chainstate directory (i.e. the one with vm/ and blocks/ in it).
+pub fn export_snapshot(chainstate_path: &str, tip: &StacksBlockId, archive_path: &str) -> Result<SnapshotInfo, Error> {
+    let paths = ChainstatePaths::new(chainstate_path);
+    for path in [&paths.headers_db, &paths.clarity_marf, &paths.clarity_data].iter() {
+        if fs::metadata(path).is_err() {
+            error!("No such file or directory: {}", path);
+            return Err(Error::DBError(db_error::NoDBError));
+        }
+    }
+    if fs::metadata(archive_path).is_ok() {
+        error!("Snapshot archive {} already exists", archive_path);
+        return Err(Error::DBError(db_error::ExistsError));
+    }
+
+    let scratch_marf_path = format!("{}.marf", archive_path);
+    if fs::metadata(&scratch_marf_path).is_ok() {
+        // left over from an interrupted export
+        fs::remove_file(&scratch_marf_path).map_err(io_error)?;
+    }
+
+    let result = inner_export_snapshot(&paths, tip, archive_path, &scratch_marf_path);
+    let _ = fs::remove_file(&scratch_marf_path);
+
+    match result {
+        Ok(info) => {
+            debug!("Exported snapshot of {} (height {}, state root {}) to {}", &info.tip, info.tip_height, &info.state_index_root, archive_path);
+            Ok(info)
+        },
+        Err(e) => {
+            let _ = fs::remove_file(archive_path);
+            Err(e)
+        }
+    }
+}
+
+/// Copy the tries of the chain tip and its ancestors to a scratch MARF, and prune all of them but
+/// the tip's.  Returns the scratch MARF and the tries' block hashes in height order.
+fn materialize_tip_trie(clarity_marf_path: &str, tip: &StacksBlockId, scratch_marf_path: &str) -> Result<(MARF<StacksBlockId>, Vec<StacksBlockId>), Error> {
+    let mut storage = TrieFileStorage::<StacksBlockId>::open_readonly(clarity_marf_path).map_err(Error::MARFError)?;
+    let tip_height = MARF::get_block_height(&mut storage, tip, tip).map_err(Error::MARFError)?
+        .ok_or(Error::NoSuchBlockError)?;
+    if storage.is_block_pruned(tip).map_err(Error::MARFError)? {
+        error!("Cannot export the pruned trie of {}", tip);
+        return Err(Error::MARFError(marf_error::PrunedError));
+    }
+
+    let mut scratch = TrieFileStorage::<StacksBlockId>::open(scratch_marf_path).map_err(Error::MARFError)?;
+    let mut block_hashes = vec![];
+    for height in 0..(tip_height + 1) {
+        let block_hash = MARF::get_block_at_height(&mut storage, height, tip).map_err(Error::MARFError)?
+            .ok_or_else(|| Error::MARFError(marf_error::CorruptionError(format!("No block at height {} in the fork of {}", height, tip))))?;
+
+        // ancestors that are already pruned still have everything the tip refers to
+        let pruned = storage.is_block_pruned(&block_hash).map_err(Error::MARFError)?;
+        let (block_id, data) = storage.read_trie_blob(&block_hash).map_err(Error::MARFError)?;
+        scratch.write_trie_blob_with_id(block_id, &block_hash, &data, pruned).map_err(Error::MARFError)?;
+        block_hashes.push(block_hash);
+    }
+
+    let mut scratch = MARF::from_storage(scratch);
+    scratch.prune_blocks(&block_hashes[0..(tip_height as usize)]).map_err(Error::MARFError)?;
+    Ok((scratch, block_hashes))
+}
+
+fn inner_export_snapshot(paths: &ChainstatePaths, tip: &StacksBlockId, archive_path: &str, scratch_marf_path: &str) -> Result<SnapshotInfo, Error> {
+    let (mut marf, block_hashes) = materialize_tip_trie(&paths.clarity_marf, tip, scratch_marf_path)?;
+    let tip_height = block_hashes.len() - 1;
+
+    let mut archive = Connection::open_with_flags(archive_path, OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_CREATE)
+        .map_err(sql_error)?;
+
+    // can't attach inside a transaction
+    archive.execute("ATTACH DATABASE ?1 AS headers", &[&paths.headers_db]).map_err(sql_error)?;
+    archive.execute("ATTACH DATABASE ?1 AS side_store", &[&paths.clarity_data]).map_err(sql_error)?;
+
+    let info = {
+        let tx = tx_begin_immediate(&mut archive)?;
+        for cmd in SNAPSHOT_SQL {
+            tx.execute_batch(cmd).map_err(sql_error)?;
+        }
+
+        for (height, block_hash) in block_hashes.iter().enumerate() {
+            let storage = marf.borrow_storage_backend();
+            let pruned = storage.is_block_pruned(block_hash).map_err(Error::MARFError)?;
+            let (block_id, data) = storage.read_trie_blob(block_hash).map_err(Error::MARFError)?;
+
+            let args : &[&dyn ToSql] = &[&block_id, block_hash, &(height as i64), &pruned, &data];
+            tx.execute("INSERT INTO marf_data (block_id, block_hash, block_height, pruned, data) VALUES (?1, ?2, ?3, ?4, ?5)", args)
+                .map_err(sql_error)?;
+        }
+
+        tx.execute(&format!("INSERT INTO block_headers ({}) SELECT {} FROM headers.block_headers WHERE index_block_hash IN (SELECT block_hash FROM marf_data)",
+                            BLOCK_HEADER_COLUMNS, BLOCK_HEADER_COLUMNS), NO_PARAMS)
+            .map_err(sql_error)?;
+
+        let num_headers : i64 = tx.query_row("SELECT COUNT(*) FROM block_headers", NO_PARAMS, |row| row.get(0)).map_err(sql_error)?;
+        if num_headers != (tip_height as i64) + 1 {
+            error!("Missing block headers: found {} headers for the {} blocks up to {}", num_headers, tip_height + 1, tip);
+            return Err(Error::NoSuchBlockError);
+        }
+
+        tx.execute(&format!("INSERT INTO payments ({}) SELECT {} FROM headers.payments WHERE index_block_hash IN (SELECT block_hash FROM marf_data)",
+                            PAYMENT_COLUMNS, PAYMENT_COLUMNS), NO_PARAMS)
+            .map_err(sql_error)?;
+
+        tx.execute("INSERT INTO data_table (key, value) SELECT key, value FROM side_store.data_table", NO_PARAMS)
+            .map_err(sql_error)?;
+        tx.execute("INSERT INTO metadata_table (key, blockhash, value) SELECT key, blockhash, value FROM side_store.metadata_table \
+                    WHERE blockhash IN (SELECT block_hash FROM marf_data)", NO_PARAMS)
+            .map_err(sql_error)?;
+
+        // the (opt-in) data map key index records keys from every fork, so it's copied whole
+        if has_table(&tx, "side_store", "data_map_keys")? {
+            tx.execute_batch("CREATE TABLE data_map_keys AS SELECT contract, map, key FROM side_store.data_map_keys")
+                .map_err(sql_error)?;
+        }
+
+        let config : DBConfig = query_row(&tx, "SELECT * FROM headers.db_config LIMIT 1", NO_PARAMS)?
+            .ok_or(Error::InvalidChainstateDB)?;
+
+        let info = SnapshotInfo {
+            version: SNAPSHOT_VERSION.to_string(),
+            mainnet: config.mainnet,
+            chain_id: config.chain_id,
+            tip: tip.clone(),
+            tip_height: tip_height as u64,
+            state_index_root: marf.get_root_hash_at(tip).map_err(Error::MARFError)?
+        };
+
+        let args : &[&dyn ToSql] = &[&info.version, &info.mainnet, &info.chain_id, &info.tip, &(info.tip_height as i64), &info.state_index_root];
+        tx.execute("INSERT INTO snapshot_info (version, mainnet, chain_id, tip, tip_height, state_index_root) VALUES (?1, ?2, ?3, ?4, ?5, ?6)", args)
+            .map_err(sql_error)?;
+
+        tx.commit().map_err(sql_error)?;
+        info
+    };
+
+    archive.execute_batch("DETACH DATABASE headers; DETACH DATABASE side_store;").map_err(sql_error)?;
+    Ok(info)
+}
+
+/// Read a snapshot archive's description, refusing archives of another version.
+pub fn read_snapshot_info(archive: &Connection) -> Result<SnapshotInfo, Error> {
+    let info : SnapshotInfo = query_row(archive, "SELECT * FROM snapshot_info LIMIT 1", NO_PARAMS)?
+        .ok_or(Error::InvalidChainstateDB)?;
+
+    if info.version != SNAPSHOT_VERSION {
+        error!("Invalid snapshot archive: expected version = {}, got {}", SNAPSHOT_VERSION, info.version);
+        return Err(Error::InvalidChainstateDB);
+    }
+    Ok(info)
+}
+
+/// Import a snapshot archive into a new chainstate directory at chainstate_path.  tip and
+/// state_index_root are the chain tip and its Clarity state root, which the caller must get from
+/// a source it trusts (and not from the archive).  The chainstate is built in a scratch directory
+/// next to chainstate_path, and only moved into place once the archive checks out against them.
+pub fn import_snapshot(archive_path: &str, chainstate_path: &str, tip: &StacksBlockId, state_index_root: &TrieHash) -> Result<SnapshotInfo, Error> {
+    if fs::metadata(chainstate_path).is_ok() {
+        error!("Chainstate {} already exists", chainstate_path);
+        return Err(Error::DBError(db_error::ExistsError));
+    }
+
+    let archive = Connection::open_with_flags(archive_path, OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(sql_error)?;
+    let info = read_snapshot_info(&archive)?;
+    if info.tip != *tip || info.state_index_root != *state_index_root {
+        error!("Snapshot archive {} is of {} with state root {}, not {} with state root {}",
+               archive_path, &info.tip, &info.state_index_root, tip, state_index_root);
+        return Err(Error::InvalidChainstateDB);
+    }
+
+    let scratch_path = format!("{}.import", chainstate_path);
+    if fs::metadata(&scratch_path).is_ok() {
+        // left over from an interrupted import
+        fs::remove_dir_all(&scratch_path).map_err(io_error)?;
+    }
+
+    match inner_import_snapshot(&archive, archive_path, &info, &scratch_path) {
+        Ok(()) => {
+            fs::rename(&scratch_path, chainstate_path).map_err(io_error)?;
+            debug!("Imported snapshot of {} (height {}, state root {}) to {}", &info.tip, info.tip_height, &info.state_index_root, chainstate_path);
+            Ok(info)
+        },
+        Err(e) => {
+            warn!("Refusing snapshot archive {}: {:?}", archive_path, &e);
+            let _ = fs::remove_dir_all(&scratch_path);
+            Err(e)
+        }
+    }
+}
+
+fn inner_import_snapshot(archive: &Connection, archive_path: &str, info: &SnapshotInfo, chainstate_path: &str) -> Result<(), Error> {
+    let paths = ChainstatePaths::new(chainstate_path);
+    fs::create_dir_all(&paths.clarity_state).map_err(io_error)?;
+
+    let headers = import_clarity_state(archive, info, &paths.clarity_marf)?;
+    import_headers(archive_path, info, &paths, &headers)?;
+    import_side_store(archive, archive_path, &paths.clarity_data)?;
+    check_contract_metadata(&paths, &info.tip)
+}
+
+/// Rebuild the Clarity MARF, and check its tries against the trusted state root.  Then check the
+/// archive's block headers against the tries.  Returns the block headers in height order.
+fn import_clarity_state(archive: &Connection, info: &SnapshotInfo, clarity_marf_path: &str) -> Result<Vec<StacksHeaderInfo>, Error> {
+    let mut storage = TrieFileStorage::<StacksBlockId>::open(clarity_marf_path).map_err(Error::MARFError)?;
+    let mut block_hashes = vec![];
+    {
+        let mut stmt = archive.prepare("SELECT block_id, block_hash, block_height, pruned, data FROM marf_data ORDER BY block_height")
+            .map_err(sql_error)?;
+        let mut rows = stmt.query(NO_PARAMS).map_err(sql_error)?;
+        while let Some(row_res) = rows.next() {
+            let row = row_res.map_err(sql_error)?;
+            let block_id : u32 = row.get("block_id");
+            let block_hash = StacksBlockId::from_column(&row, "block_hash")?;
+            let block_height = u64::from_column(&row, "block_height")?;
+            let pruned : bool = row.get("pruned");
+            let data : Vec<u8> = row.get("data");
+
+            if block_height != block_hashes.len() as u64 {
+                error!("Invalid snapshot archive: expected a trie at height {}, got {}", block_hashes.len(), block_height);
+                return Err(Error::InvalidChainstateDB);
+            }
+
+            storage.write_trie_blob_with_id(block_id, &block_hash, &data, pruned).map_err(Error::MARFError)?;
+            block_hashes.push(block_hash);
+        }
+    }
+
+    if block_hashes.len() as u64 != info.tip_height + 1 || block_hashes.last() != Some(&info.tip) {
+        error!("Invalid snapshot archive: tries do not end at chain tip {} (height {})", &info.tip, info.tip_height);
+        return Err(Error::InvalidChainstateDB);
+    }
+    if storage.is_block_pruned(&info.tip).map_err(Error::MARFError)? {
+        error!("Invalid snapshot archive: the trie of chain tip {} is pruned", &info.tip);
+        return Err(Error::InvalidChainstateDB);
+    }
+
+    let mut marf = MARF::from_storage(storage);
+    for (height, block_hash) in block_hashes.iter().enumerate() {
+        // every node hash must check out (ancestors' root hashes are part of the tip's)...
+        marf.check_trie_at(block_hash).map_err(Error::MARFError)?;
+
+        // ...and the chain tip's trie must agree that these are its ancestors
+        let ancestor = MARF::get_block_at_height(marf.borrow_storage_backend(), height as u32, &info.tip).map_err(Error::MARFError)?;
+        if ancestor.as_ref() != Some(block_hash) {
+            error!("Invalid snapshot archive: chain tip {} has ancestor {:?} at height {}, not {}", &info.tip, &ancestor, height, block_hash);
+            return Err(Error::InvalidChainstateDB);
+        }
+    }
+
+    let root_hash = marf.get_root_hash_at(&info.tip).map_err(Error::MARFError)?;
+    if root_hash != info.state_index_root {
+        error!("Invalid snapshot archive: state root of chain tip {} is {}, not {}", &info.tip, &root_hash, &info.state_index_root);
+        return Err(Error::InvalidChainstateDB);
+    }
+
+    // The tries are now vouched for by the state root, and with them, the block IDs of the tip's
+    // ancestors.  Each header must hash to its block's ID, and so can't have been tampered with.
+    let mut headers = vec![];
+    for (height, block_hash) in block_hashes.iter().enumerate() {
+        let header : StacksHeaderInfo = query_row(archive, "SELECT * FROM block_headers WHERE index_block_hash = ?1", &[block_hash])?
+            .ok_or_else(|| {
+                error!("Invalid snapshot archive: no header for block {}", block_hash);
+                Error::InvalidChainstateDB
+            })?;
+
+        let index_block_hash = StacksBlockHeader::make_index_block_hash(&header.burn_header_hash, &header.anchored_header.block_hash());
+        if index_block_hash != *block_hash {
+            error!("Invalid snapshot archive: header for block {} hashes to {}", block_hash, &index_block_hash);
+            return Err(Error::InvalidChainstateDB);
+        }
+
+        if header.block_height != height as u64 || header.anchored_header.total_work.work != height as u64 {
+            error!("Invalid snapshot archive: header for block {} has height {} (work {}), not {}",
+                   block_hash, header.block_height, header.anchored_header.total_work.work, height);
+            return Err(Error::InvalidChainstateDB);
+        }
+
+        // The boot code's header doesn't commit to a state root, so its trie is only vouched for
+        // by its descendants.
+        if height > 0 {
+            let root_hash = marf.get_root_hash_at(block_hash).map_err(Error::MARFError)?;
+            if root_hash != header.anchored_header.state_index_root {
+                error!("Invalid snapshot archive: state root of block {} is {}, but its header has {}",
+                       block_hash, &root_hash, &header.anchored_header.state_index_root);
+                return Err(Error::InvalidChainstateDB);
+            }
+        }
+        headers.push(header);
+    }
+
+    Ok(headers)
+}
+
+/// Create the headers DB, and rebuild the headers index block by block from the (checked) block
+/// headers.  The miner payments are copied over as they are.
+fn import_headers(archive_path: &str, info: &SnapshotInfo, paths: &ChainstatePaths, headers: &Vec<StacksHeaderInfo>) -> Result<(), Error> {
+    let mut headers_db = StacksChainState::open_headers_db(info.mainnet, info.chain_id, &paths.headers_db, &paths.headers_index)?;
+    let mut headers_index = StacksChainState::open_index(&paths.headers_index)?;
+
+    let mut parent_block = TrieFileStorage::block_sentinel();
+    for header in headers.iter() {
+        let block_hash = StacksBlockHeader::make_index_block_hash(&header.burn_header_hash, &header.anchored_header.block_hash());
+        let (indexed_keys, indexed_values) =
+            if header.block_height == 0 {
+                (vec![], vec![])
+            }
+            else {
+                StacksChainState::headers_index_entries(&header.anchored_header)
+            };
+
+        let tx = tx_begin_immediate(&mut headers_db)?;
+        let mut headers_tx = StacksDBTx::new(tx, &mut headers_index, ());
+        headers_tx.put_indexed_begin(&parent_block, &block_hash).map_err(Error::DBError)?;
+        let index_root = headers_tx.put_indexed_all(&indexed_keys, &indexed_values).map_err(Error::DBError)?;
+        if index_root != header.index_root {
+            error!("Invalid snapshot archive: headers index root of block {} is {}, but its header has {}", &block_hash, &index_root, &header.index_root);
+            return Err(Error::InvalidChainstateDB);
+        }
+
+        StacksChainState::insert_stacks_block_header(&mut headers_tx, header)?;
+        headers_tx.commit().map_err(Error::DBError)?;
+        parent_block = block_hash;
+    }
+
+    headers_db.execute("ATTACH DATABASE ?1 AS archive", &[&archive_path]).map_err(sql_error)?;
+    {
+        let tx = tx_begin_immediate(&mut headers_db)?;
+        tx.execute(&format!("INSERT INTO payments ({}) SELECT {} FROM archive.payments WHERE index_block_hash IN (SELECT index_block_hash FROM block_headers)",
+                            PAYMENT_COLUMNS, PAYMENT_COLUMNS), NO_PARAMS)
+            .map_err(sql_error)?;
+        tx.commit().map_err(sql_error)?;
+    }
+    headers_db.execute_batch("DETACH DATABASE archive;").map_err(sql_error)?;
+    Ok(())
+}
+
+/// Rebuild the Clarity side-store.  The data_table is keyed by the hashes of its values, which the
+/// MARF's leaves refer to, so each value is checked against its key.
+fn import_side_store(archive: &Connection, archive_path: &str, clarity_data_path: &str) -> Result<(), Error> {
+    {
+        let mut stmt = archive.prepare("SELECT key, value FROM data_table").map_err(sql_error)?;
+        let mut rows = stmt.query(NO_PARAMS).map_err(sql_error)?;
+        while let Some(row_res) = rows.next() {
+            let row = row_res.map_err(sql_error)?;
+            let key : String = row.get("key");
+            let value : Option<String> = row.get("value");
+            if value.map(|value| MARFValue::from_value(&value).to_hex()) != Some(key.clone()) {
+                error!("Invalid snapshot archive: data_table value for {} does not match its hash", &key);
+                return Err(Error::InvalidChainstateDB);
+            }
+        }
+    }
+
+    let has_map_keys = has_table(archive, "main", "data_map_keys")?;
+    {
+        let mut side_store = SqliteConnection::initialize(clarity_data_path)?;
+        if has_map_keys {
+            side_store.enable_map_key_index()?;
+        }
+    }
+
+    let mut conn = Connection::open(clarity_data_path).map_err(sql_error)?;
+    conn.execute("ATTACH DATABASE ?1 AS archive", &[&archive_path]).map_err(sql_error)?;
+    {
+        let tx = tx_begin_immediate(&mut conn)?;
+        tx.execute("INSERT INTO data_table (key, value) SELECT key, value FROM archive.data_table", NO_PARAMS)
+            .map_err(sql_error)?;
+        tx.execute("INSERT INTO metadata_table (key, blockhash, value) SELECT key, blockhash, value FROM archive.metadata_table", NO_PARAMS)
+            .map_err(sql_error)?;
+        if has_map_keys {
+            tx.execute("INSERT OR IGNORE INTO data_map_keys (contract, map, key) SELECT contract, map, key FROM archive.data_map_keys", NO_PARAMS)
+                .map_err(sql_error)?;
+        }
+        tx.commit().map_err(sql_error)?;
+    }
+    conn.execute_batch("DETACH DATABASE archive;").map_err(sql_error)?;
+
+    Ok(())
+}
+
+/// A contract's metadata_table rows: its metadata keys, with the block each row is filed under
+/// and its value
+type ContractMetadataRows = HashMap<String, (StacksBlockId, String)>;
+
+/// Split a metadata_table key into the contract it belongs to, and the key within that contract
+fn split_metadata_key(key: &str) -> Option<(&str, &str)> {
+    let prefix = "clr-meta::";
+    if !key.starts_with(prefix) {
+        return None;
+    }
+    let rest = &key[prefix.len()..];
+    let split = rest.find("::")?;
+    Some((&rest[..split], &rest[(split + 2)..]))
+}
+
+/// Check the imported metadata_table against the contracts in the chain tip's state.  Every
+/// contract's source must hash to the contract-commitment in the tip's trie, and its analysis and
+/// the definitions in its stored contract context must match the ones rebuilt from that source.
+/// Only the values of its constants and its data size are taken on trust.
+fn check_contract_metadata(paths: &ChainstatePaths, tip: &StacksBlockId) -> Result<(), Error> {
+    let mut contracts : BTreeMap<String, ContractMetadataRows> = BTreeMap::new();
+    {
+        let conn = Connection::open_with_flags(&paths.clarity_data, OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(sql_error)?;
+        let mut stmt = conn.prepare("SELECT key, blockhash, value FROM metadata_table").map_err(sql_error)?;
+        let mut rows = stmt.query(NO_PARAMS).map_err(sql_error)?;
+        while let Some(row_res) = rows.next() {
+            let row = row_res.map_err(sql_error)?;
+            let key : String = row.get("key");
+            let block_hash = StacksBlockId::from_column(&row, "blockhash")?;
+            let value : Option<String> = row.get("value");
+
+            let (contract, metadata_key) = split_metadata_key(&key)
+                .ok_or_else(|| {
+                    error!("Invalid snapshot archive: metadata_table key {} is not a contract's", &key);
+                    Error::InvalidChainstateDB
+                })?;
+            let value = value
+                .ok_or_else(|| {
+                    error!("Invalid snapshot archive: metadata_table has no value for {}", &key);
+                    Error::InvalidChainstateDB
+                })?;
+
+            contracts.entry(contract.to_string()).or_insert_with(HashMap::new)
+                .insert(metadata_key.to_string(), (block_hash, value));
+        }
+    }
+
+    let mut clarity_state = MarfedKV::open(&paths.clarity_state, Some(tip))?;
+
+    // Decode everything before analyzing anything, since analyzing a contract loads the analyses
+    // of the contracts it refers to, and those must at least be well-formed.
+    let mut decoded = vec![];
+    for (contract, rows) in contracts.iter() {
+        let (contract_id, source, contract) = decode_contract_metadata(&mut clarity_state, contract, rows)?;
+        decoded.push((contract_id, source, contract, rows));
+    }
+
+    for (contract_id, source, contract, rows) in decoded.into_iter() {
+        check_contract_analysis(&mut clarity_state, &contract_id, &source, &contract, rows)?;
+    }
+    Ok(())
+}
+
+fn invalid_contract_metadata(contract: &str, problem: &str) -> Error {
+    error!("Invalid snapshot archive: metadata of contract {} {}", contract, problem);
+    Error::InvalidChainstateDB
+}
+
+fn decode_metadata<T: DeserializeOwned>(contract: &str, rows: &ContractMetadataRows, key: &str) -> Result<T, Error> {
+    let (_, value) = rows.get(key)
+        .ok_or_else(|| invalid_contract_metadata(contract, &format!("has no {}", key)))?;
+    serde_json::from_str(value)
+        .map_err(|_| invalid_contract_metadata(contract, &format!("has a malformed {}", key)))
+}
+
+/// Check that a contract's metadata is filed under the block it was published in, and that its
+/// source is the one the chain tip's state commits to.  Returns the contract's ID, source and
+/// (decoded) contract context.
+fn decode_contract_metadata(clarity_state: &mut MarfedKV, contract: &str, rows: &ContractMetadataRows)
+                            -> Result<(QualifiedContractIdentifier, String, Contract), Error> {
+    let contract_id = QualifiedContractIdentifier::parse(contract)
+        .map_err(|_| invalid_contract_metadata(contract, "is for a malformed contract identifier"))?;
+    let (publish_block, source_hash) = clarity_state.get_contract_hash(&contract_id)
+        .map_err(|_| invalid_contract_metadata(contract, "is for a contract that the chain tip doesn't have"))?;
+
+    for (key, (block_hash, _)) in rows.iter() {
+        if *block_hash != publish_block {
+            return Err(invalid_contract_metadata(contract, &format!("has {} in block {}, but the contract was published in {}",
+                                                                    key, block_hash, &publish_block)));
+        }
+    }
+
+    let source_key = ClarityDatabase::make_metadata_key(StoreType::Contract, "contract-src");
+    let source = rows.get(&source_key)
+        .map(|(_, value)| String::deserialize(value))
+        .ok_or_else(|| invalid_contract_metadata(contract, "has no source"))?;
+    if Sha512Trunc256Sum::from_data(source.as_bytes()) != source_hash {
+        return Err(invalid_contract_metadata(contract, "has a source that doesn't match the contract's commitment"));
+    }
+
+    let source_size : u64 = decode_metadata(contract, rows, &ClarityDatabase::make_metadata_key(StoreType::Contract, "contract-size"))?;
+    if source_size != source.len() as u64 {
+        return Err(invalid_contract_metadata(contract, &format!("has contract-size {}, but its source is {} bytes", source_size, source.len())));
+    }
+
+    let _ : ContractAnalysis = decode_metadata(contract, rows, "analysis")?;
+    let contract_context : Contract = decode_metadata(contract, rows, &ClarityDatabase::make_metadata_key(StoreType::Contract, "contract"))?;
+
+    Ok((contract_id, source, contract_context))
+}
+
+/// Rebuild a contract's AST and analysis from its source, and check its stored analysis, contract
+/// context and per-definition metadata against them.
+fn check_contract_analysis(clarity_state: &mut MarfedKV, contract_id: &QualifiedContractIdentifier, source: &str,
+                           contract: &Contract, rows: &ContractMetadataRows) -> Result<(), Error> {
+    let name = contract_id.to_string();
+    let invalid = |problem: &str| invalid_contract_metadata(&name, problem);
+
+    let mut contract_ast = ast::build_ast(contract_id, source, &mut ())
+        .map_err(|_| invalid("has a source that doesn't parse"))?;
+    let analysis = type_check(contract_id, &mut contract_ast.expressions, &mut clarity_state.as_analysis_db(), false)
+        .map_err(|_| invalid("has a source that doesn't type-check"))?;
+
+    let (_, stored_analysis) = &rows["analysis"];
+    if analysis.serialize() != *stored_analysis {
+        return Err(invalid("has an analysis that doesn't match its source"));
+    }
+
+    let context = &contract.contract_context;
+    let defined_traits : BTreeMap<_, _> = context.defined_traits.clone().into_iter().collect();
+    let implemented_traits : BTreeSet<_> = context.implemented_traits.iter().cloned().collect();
+
+    let persisted_names : BTreeSet<_> = context.persisted_names.iter().cloned().collect();
+    let mut expected_persisted_names = BTreeSet::new();
+    expected_persisted_names.extend(analysis.persisted_variable_types.keys().cloned());
+    expected_persisted_names.extend(analysis.map_types.keys().cloned());
+    expected_persisted_names.extend(analysis.fungible_tokens.iter().cloned());
+    expected_persisted_names.extend(analysis.non_fungible_tokens.keys().cloned());
+
+    let constants_typecheck = context.variables.len() == analysis.variable_types.len() &&
+        context.variables.iter().all(|(constant, value)| {
+            analysis.variable_types.get(constant).map(|constant_type| constant_type.admits(value)).unwrap_or(false)
+        });
+
+    if context.contract_identifier != *contract_id || !contract.defines_functions_of(&contract_ast) || defined_traits != analysis.defined_traits ||
+        implemented_traits != analysis.implemented_traits || persisted_names != expected_persisted_names || !constants_typecheck {
+        return Err(invalid("has a contract context that doesn't match its source"));
+    }
+
+    let data_size : u64 = decode_metadata(&name, rows, &ClarityDatabase::make_metadata_key(StoreType::Contract, "contract-data-size"))?;
+    if data_size != context.data_size {
+        return Err(invalid(&format!("has contract-data-size {}, but its contract context has {}", data_size, context.data_size)));
+    }
+
+    // the data vars, maps and tokens' metadata must have the analysis' types, and there can't be
+    // any other metadata
+    let mut expected_keys : HashSet<String> = ["contract-src", "contract-size", "contract-data-size", "contract"].iter()
+        .map(|key| ClarityDatabase::make_metadata_key(StoreType::Contract, key))
+        .collect();
+    expected_keys.insert("analysis".to_string());
+
+    for (var_name, value_type) in analysis.persisted_variable_types.iter() {
+        let key = ClarityDatabase::make_metadata_key(StoreType::VariableMeta, var_name);
+        let metadata : DataVariableMetadata = decode_metadata(&name, rows, &key)?;
+        if metadata.value_type != *value_type {
+            return Err(invalid(&format!("has the wrong type for data var {}", var_name.as_str())));
+        }
+        expected_keys.insert(key);
+    }
+    for (map_name, (key_type, value_type)) in analysis.map_types.iter() {
+        let key = ClarityDatabase::make_metadata_key(StoreType::DataMapMeta, map_name);
+        let metadata : DataMapMetadata = decode_metadata(&name, rows, &key)?;
+        if metadata.key_type != *key_type || metadata.value_type != *value_type {
+            return Err(invalid(&format!("has the wrong types for map {}", map_name.as_str())));
+        }
+        expected_keys.insert(key);
+    }
+    for token_name in analysis.fungible_tokens.iter() {
+        let key = ClarityDatabase::make_metadata_key(StoreType::FungibleTokenMeta, token_name);
+        let _ : FungibleTokenMetadata = decode_metadata(&name, rows, &key)?;
+        expected_keys.insert(key);
+    }
+    for (token_name, key_type) in analysis.non_fungible_tokens.iter() {
+        let key = ClarityDatabase::make_metadata_key(StoreType::NonFungibleTokenMeta, token_name);
+        let metadata : NonFungibleTokenMetadata = decode_metadata(&name, rows, &key)?;
+        if metadata.key_type != *key_type {
+            return Err(invalid(&format!("has the wrong type for non-fungible token {}", token_name.as_str())));
+        }
+        expected_keys.insert(key);
+    }
+
+    if let Some(key) = rows.keys().find(|key| !expected_keys.contains(*key)) {
+        return Err(invalid(&format!("has an unexpected entry {}", key)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::path::Path;
+
+    use chainstate::stacks::db::test::*;
+    use core::FIRST_STACKS_BLOCK_HASH;
+    use core::FIRST_BURNCHAIN_BLOCK_HASH;
+
+    use util::hash::{Hash160, Sha512Trunc256Sum, to_hex};
+
+    use vm::database::NULL_HEADER_DB;
+    use vm::types::PrincipalData;
+
+    /// Returns the archive path, and the root and chain directory of the chainstate to import to
+    fn snapshot_test_paths(test_name: &str) -> (String, String, String) {
+        let archive_path = format!("/tmp/blockstack-test-snapshot-{}.sqlite", test_name);
+        let import_root = format!("/tmp/blockstack-test-snapshot-{}", test_name);
+        for path in [&archive_path, &import_root].iter() {
+            if fs::metadata(path).is_ok() {
+                if Path::new(path).is_dir() {
+                    fs::remove_dir_all(path).unwrap();
+                }
+                else {
+                    fs::remove_file(path).unwrap();
+                }
+            }
+        }
+        let import_path = format!("{}/chain-{}-testnet", &import_root, &to_hex(&0x80000000u32.to_le_bytes()));
+        fs::create_dir_all(&import_root).unwrap();
+        (archive_path, import_root, import_path)
+    }
+
+    fn chainstate_dir(chainstate: &StacksChainState) -> String {
+        let mut path = PathBuf::from(&chainstate.clarity_state_index_root);
+        path.pop();
+        path.pop();
+        path.to_str().unwrap().to_string()
+    }
+
+    /// Append a block that credits the recipient, and return its header
+    fn make_block(chainstate: &mut StacksChainState, parent: &StacksHeaderInfo, recipient: &PrincipalData) -> StacksHeaderInfo {
+        let parent_block = StacksBlockHeader::make_index_block_hash(&parent.burn_header_hash, &parent.anchored_header.block_hash());
+        let miner_block = StacksBlockHeader::make_index_block_hash(&MINER_BLOCK_BURN_HEADER_HASH, &MINER_BLOCK_HEADER_HASH);
+
+        let mut new_tip = parent.clone();
+        new_tip.anchored_header.parent_block = parent.anchored_header.block_hash();
+        new_tip.anchored_header.microblock_pubkey_hash = Hash160::from_data(&parent.anchored_header.microblock_pubkey_hash.0);
+        new_tip.anchored_header.total_work.work += 1;
+        new_tip.burn_header_hash = BurnchainHeaderHash(Sha512Trunc256Sum::from_data(&parent.burn_header_hash.0).0);
+
+        let mut clarity_tx = chainstate.clarity_state.begin_block(&parent_block, &miner_block, &NULL_HEADER_DB);
+        clarity_tx.as_transaction(|clarity| StacksChainState::account_credit(clarity, recipient, 1000));
+        new_tip.anchored_header.state_index_root = clarity_tx.get_root_hash();
+        clarity_tx.commit_to_block(&StacksBlockHeader::make_index_block_hash(&new_tip.burn_header_hash, &new_tip.anchored_header.block_hash()));
+
+        let block_reward = MinerPaymentSchedule {
+            address: StacksAddress { version: 0, bytes: Hash160([0u8; 20]) },
+            block_hash: new_tip.anchored_header.block_hash(),
+            burn_header_hash: new_tip.burn_header_hash.clone(),
+            parent_block_hash: parent.anchored_header.block_hash(),
+            parent_burn_header_hash: parent.burn_header_hash.clone(),
+            coinbase: 500,
+            tx_fees_anchored: 0,
+            tx_fees_streamed: 0,
+            stx_burns: 0,
+            burnchain_commit_burn: 100,
+            burnchain_sortition_burn: 100,
+            fill: 0xffffffffffffffff,
+            miner: true,
+            stacks_block_height: new_tip.anchored_header.total_work.work,
+            vtxindex: 0
+        };
+
+        let mut headers_tx = chainstate.headers_tx_begin().unwrap();
+        let tip = StacksChainState::advance_tip(&mut headers_tx, &parent.anchored_header, &parent.burn_header_hash,
+                                                &new_tip.anchored_header, &new_tip.burn_header_hash, new_tip.burn_header_timestamp,
+                                                None, &block_reward, &vec![]).unwrap();
+        headers_tx.commit().unwrap();
+        tip
+    }
+
+    /// Make a chainstate with a few blocks on top of the boot block.  Returns the chain tip's
+    /// header and the principals credited along the way.
+    fn make_chain(test_name: &str, num_blocks: u64) -> (StacksChainState, StacksHeaderInfo, Vec<PrincipalData>) {
+        let mut chainstate = instantiate_chainstate(false, 0x80000000, test_name);
+        let boot_block = StacksBlockHeader::make_index_block_hash(&FIRST_BURNCHAIN_BLOCK_HASH, &FIRST_STACKS_BLOCK_HASH);
+        let mut tip = StacksChainState::get_stacks_block_header_info_by_index_block_hash(&chainstate.headers_db, &boot_block).unwrap().unwrap();
+
+        let mut recipients = vec![];
+        for i in 0..num_blocks {
+            let recipient = PrincipalData::from(StacksAddress { version: 26, bytes: Hash160([i as u8; 20]) });
+            tip = make_block(&mut chainstate, &tip, &recipient);
+            recipients.push(recipient);
+        }
+        (chainstate, tip, recipients)
+    }
+
+    fn tip_block(header: &StacksHeaderInfo) -> StacksBlockId {
+        StacksBlockHeader::make_index_block_hash(&header.burn_header_hash, &header.anchored_header.block_hash())
+    }
+
+    #[test]
+    fn test_export_import_snapshot() {
+        let (chainstate, tip_header, recipients) = make_chain("export-import-snapshot", 6);
+        let (archive_path, import_root, import_path) = snapshot_test_paths("export-import");
+        let chainstate_path = chainstate_dir(&chainstate);
+        let tip = tip_block(&tip_header);
+
+        // unknown tips can't be exported
+        match export_snapshot(&chainstate_path, &StacksBlockId([0x01; 32]), &archive_path) {
+            Err(Error::NoSuchBlockError) => {},
+            x => panic!("Expected NoSuchBlockError, got {:?}", x)
+        }
+        assert!(fs::metadata(&archive_path).is_err());
+
+        let info = export_snapshot(&chainstate_path, &tip, &archive_path).unwrap();
+        assert_eq!(info.tip, tip);
+        assert_eq!(info.tip_height, 6);
+        assert_eq!(info.state_index_root, tip_header.anchored_header.state_index_root);
+        assert!(fs::metadata(&format!("{}.marf", &archive_path)).is_err());
+
+        // only the chain tip's trie is whole
+        {
+            let archive = Connection::open(&archive_path).unwrap();
+            let pruned : Vec<bool> = {
+                let mut stmt = archive.prepare("SELECT pruned FROM marf_data ORDER BY block_height").unwrap();
+                let rows = stmt.query_map(NO_PARAMS, |row| row.get(0)).unwrap();
+                rows.map(|row| row.unwrap()).collect()
+            };
+            assert_eq!(pruned, vec![true, true, true, true, true, true, false]);
+        }
+
+        // can't overwrite an archive
+        assert!(export_snapshot(&chainstate_path, &tip, &archive_path).is_err());
+
+        let imported_info = import_snapshot(&archive_path, &import_path, &tip, &info.state_index_root).unwrap();
+        assert_eq!(imported_info, info);
+
+        // the imported chainstate has the tip's state, headers, and headers index
+        let mut imported = StacksChainState::open(false, 0x80000000, &import_root).unwrap();
+        for (i, recipient) in recipients.iter().enumerate() {
+            let account = imported.with_read_only_clarity_tx(&tip, |conn| StacksChainState::get_account(conn, recipient));
+            assert_eq!(account.stx_balance, 1000, "balance of recipient {}", i);
+        }
+
+        let imported_tip = StacksChainState::get_stacks_block_header_info_by_index_block_hash(&imported.headers_db, &tip).unwrap().unwrap();
+        assert_eq!(imported_tip, tip_header);
+        assert_eq!(imported.headers_state_index.get_root_hash_at(&tip).unwrap(), tip_header.index_root);
+
+        let num_payments : i64 = imported.headers_db.query_row("SELECT COUNT(*) FROM payments", NO_PARAMS, |row| row.get(0)).unwrap();
+        assert_eq!(num_payments, 6);
+
+        // can't import over an existing chainstate
+        assert!(import_snapshot(&archive_path, &import_path, &tip, &info.state_index_root).is_err());
+    }
+
+    #[test]
+    fn test_import_snapshot_refuses_bad_archives() {
+        let (chainstate, tip_header, _) = make_chain("import-snapshot-bad-archives", 3);
+        let (archive_path, _, import_path) = snapshot_test_paths("bad-archives");
+        let chainstate_path = chainstate_dir(&chainstate);
+        let tip = tip_block(&tip_header);
+        let state_root = tip_header.anchored_header.state_index_root.clone();
+
+        let export = || {
+            let _ = fs::remove_file(&archive_path);
+            export_snapshot(&chainstate_path, &tip, &archive_path).unwrap();
+            Connection::open(&archive_path).unwrap()
+        };
+        let import = || {
+            let res = import_snapshot(&archive_path, &import_path, &tip, &state_root);
+            // nothing is left behind
+            assert!(fs::metadata(&import_path).is_err());
+            assert!(fs::metadata(&format!("{}.import", &import_path)).is_err());
+            res
+        };
+
+        // not the chain tip or state root the caller trusts
+        export();
+        match import_snapshot(&archive_path, &import_path, &StacksBlockId([0x01; 32]), &state_root) {
+            Err(Error::InvalidChainstateDB) => {},
+            x => panic!("Expected InvalidChainstateDB, got {:?}", x)
+        }
+        match import_snapshot(&archive_path, &import_path, &tip, &TrieHash([0x01; 32])) {
+            Err(Error::InvalidChainstateDB) => {},
+            x => panic!("Expected InvalidChainstateDB, got {:?}", x)
+        }
+
+        // unknown version
+        export().execute("UPDATE snapshot_info SET version = '0'", NO_PARAMS).unwrap();
+        match import() {
+            Err(Error::InvalidChainstateDB) => {},
+            x => panic!("Expected InvalidChainstateDB, got {:?}", x)
+        }
+
+        // corrupt the chain tip's root node hash
+        {
+            let archive = export();
+            let mut data : Vec<u8> = archive.query_row("SELECT data FROM marf_data WHERE block_hash = ?1", &[&tip], |row| row.get(0)).unwrap();
+            let root_hash_offset = TrieFileStorage::<StacksBlockId>::root_ptr_disk() as usize;
+            data[root_hash_offset] ^= 0x01;
+            let args : &[&dyn ToSql] = &[&data, &tip];
+            archive.execute("UPDATE marf_data SET data = ?1 WHERE block_hash = ?2", args).unwrap();
+        }
+        match import() {
+            Err(Error::MARFError(marf_error::CorruptionError(_))) => {},
+            x => panic!("Expected a corruption error, got {:?}", x)
+        }
+
+        // an ancestor's header that's been moved to another burnchain block
+        let args : &[&dyn ToSql] = &[&BurnchainHeaderHash([0x02; 32])];
+        export().execute("UPDATE block_headers SET burn_header_hash = ?1 WHERE block_height = 1", args).unwrap();
+        match import() {
+            Err(Error::InvalidChainstateDB) => {},
+            x => panic!("Expected InvalidChainstateDB, got {:?}", x)
+        }
+
+        // a header with a headers index root that doesn't match the rebuilt index
+        let args : &[&dyn ToSql] = &[&TrieHash([0x03; 32])];
+        export().execute("UPDATE block_headers SET index_root = ?1 WHERE block_height = 2", args).unwrap();
+        match import() {
+            Err(Error::InvalidChainstateDB) => {},
+            x => panic!("Expected InvalidChainstateDB, got {:?}", x)
+        }
+
+        // a tampered Clarity value
+        export().execute("UPDATE data_table SET value = value || '00' WHERE rowid = (SELECT MIN(rowid) FROM data_table)", NO_PARAMS).unwrap();
+        match import() {
+            Err(Error::InvalidChainstateDB) => {},
+            x => panic!("Expected InvalidChainstateDB, got {:?}", x)
+        }
+
+        // tampered contract metadata: a changed source, analysis, contract context or map type,
+        // metadata for a contract the chain tip doesn't have, and metadata filed under a block
+        // the contract wasn't published in
+        let metadata_tampers = [
+            "UPDATE metadata_table SET value = value || ' ' WHERE key LIKE '%::contract-src'",
+            "UPDATE metadata_table SET value = replace(value, '[\"UIntType\",\"NoType\"]', '[\"IntType\",\"NoType\"]') WHERE key LIKE '%::analysis'",
+            "UPDATE metadata_table SET value = replace(value, '\"Atom\":\"ok\"', '\"Atom\":\"err\"') WHERE key LIKE '%::contract'",
+            "UPDATE metadata_table SET value = replace(value, '{\"Int\":1}', '{\"UInt\":1}') WHERE key LIKE '%::contract'",
+            "UPDATE metadata_table SET value = replace(value, 'UIntType', 'IntType') WHERE key LIKE '%::rewards'",
+            "INSERT INTO metadata_table (key, blockhash, value) SELECT replace(key, 'miner-rewards', 'miner-rewardz'), blockhash, value FROM metadata_table",
+        ];
+        for tamper in metadata_tampers.iter() {
+            let archive = export();
+            assert!(archive.execute(tamper, NO_PARAMS).unwrap() > 0, "{}", tamper);
+            match import() {
+                Err(Error::InvalidChainstateDB) => {},
+                x => panic!("Expected InvalidChainstateDB after {}, got {:?}", tamper, x)
+            }
+        }
+
+        export().execute("UPDATE metadata_table SET blockhash = ?1 WHERE key LIKE '%::analysis'", &[&tip]).unwrap();
+        match import() {
+            Err(Error::InvalidChainstateDB) => {},
+            x => panic!("Expected InvalidChainstateDB, got {:?}", x)
+        }
+
+        // the untampered archive is fine
+        export();
+        import_snapshot(&archive_path, &import_path, &tip, &state_root).unwrap();
+    }
+}
@@ -24,6 +24,7 @@ use std::io::{Read, Write};
 use std::fmt;
 use std::fs;
 use std::collections::{HashSet, HashMap};
+use std::convert::TryFrom;
 
 use chainstate::stacks::Error;
 use chainstate::stacks::*;
@@ -31,11 +32,19 @@ use chainstate::stacks::db::*;
 
 use std::path::{Path, PathBuf};
 
+use rusqlite::Row;
+use rusqlite::types::ToSql;
+
 use util::db::Error as db_error;
 use util::db::{
     DBConn,
+    FromRow,
+    FromColumn,
     query_rows,
-    query_count
+    query_row_columns,
+    query_count,
+    u64_to_sql,
+    get_ancestor_block_height
 };
 
 use util::strings::StacksString;
@@ -60,12 +69,15 @@ use vm::contexts::{
 use vm::ast::build_ast;
 use vm::analysis::run_analysis;
 use vm::costs::ExecutionCost;
+use vm::costs::LimitedCostTracker;
 
 use vm::types::{
     Value,
     AssetIdentifier
 };
 
+use vm::representations::ClarityName;
+
 use vm::clarity::{
     ClarityBlockConnection,
     ClarityTransactionConnection,
@@ -79,7 +91,10 @@ use vm::analysis::types::ContractAnalysis;
 pub use vm::analysis::errors::CheckErrors;
 use vm::clarity::Error as clarity_error;
 
-use vm::database::ClarityDatabase;
+use vm::database::{
+    ClarityDatabase,
+    ClaritySerializable
+};
 
 use vm::contracts::Contract;
 
@@ -92,6 +107,115 @@ impl std::hash::Hash for Value {
     }
 }
 
+/// A transaction that was confirmed by an anchored block, and the receipt it produced.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StacksTransactionInfo {
+    pub txid: Txid,
+    pub index_block_hash: StacksBlockId,            // anchored block that confirmed the transaction
+    pub microblock_hash: Option<BlockHeaderHash>,   // set if the transaction was mined in a microblock
+    pub tx_index: u32,                              // position within the (micro)block
+    pub tx: StacksTransaction,
+    pub result: Value,
+    pub post_condition_aborted: bool,
+    pub events: Vec<serde_json::Value>,
+    pub execution_cost: ExecutionCost
+}
+
+impl FromRow<StacksTransactionInfo> for StacksTransactionInfo {
+    fn from_row<'a>(row: &'a Row) -> Result<StacksTransactionInfo, db_error> {
+        let txid = Txid::from_column(row, "txid")?;
+        let index_block_hash = StacksBlockId::from_column(row, "index_block_hash")?;
+        let microblock_hash_str : Option<String> = row.get("microblock_hash");
+        let tx_index : u32 = row.get("tx_index");
+        let tx_bytes : Vec<u8> = row.get("tx");
+        let result_hex : String = row.get("result");
+        let post_condition_aborted : bool = row.get("post_condition_aborted");
+        let events_json : String = row.get("events");
+        let execution_cost_json : String = row.get("execution_cost");
+
+        let microblock_hash = match microblock_hash_str {
+            Some(hash_str) => Some(BlockHeaderHash::from_hex(&hash_str).map_err(|_e| db_error::ParseError)?),
+            None => None
+        };
+
+        let tx = StacksTransaction::consensus_deserialize(&mut &tx_bytes[..])
+            .map_err(|_e| db_error::ParseError)?;
+
+        if tx.txid() != txid {
+            return Err(db_error::ParseError);
+        }
+
+        let result = Value::try_deserialize_hex_untyped(&result_hex)
+            .map_err(|_e| db_error::ParseError)?;
+        let events = serde_json::from_str(&events_json)
+            .map_err(|_e| db_error::ParseError)?;
+        let execution_cost = serde_json::from_str(&execution_cost_json)
+            .map_err(|_e| db_error::ParseError)?;
+
+        Ok(StacksTransactionInfo {
+            txid,
+            index_block_hash,
+            microblock_hash,
+            tx_index,
+            tx,
+            result,
+            post_condition_aborted,
+            events,
+            execution_cost
+        })
+    }
+}
+
+/// A fungible token, or a non-fungible token and the identifier of one of its instances.
+pub type AssetHolding = (AssetIdentifier, Option<Value>);
+
+/// A principal's receipt of a fungible or non-fungible token, as stored in the asset_holdings table.
+struct AssetHoldingRow {
+    asset_identifier: AssetIdentifier,
+    value: Option<Value>,                           // set only for non-fungible tokens
+    index_block_hash: StacksBlockId
+}
+
+impl AssetHoldingRow {
+    fn holding(&self) -> AssetHolding {
+        (self.asset_identifier.clone(), self.value.clone())
+    }
+
+    /// The contract_identifier, asset_name, and value columns that identify a holding
+    fn key_of(holding: &AssetHolding) -> (String, String, String) {
+        let (ref asset_identifier, ref value_opt) = holding;
+        (asset_identifier.contract_identifier.to_string(),
+         asset_identifier.asset_name.to_string(),
+         value_opt.as_ref().map(|value| value.serialize()).unwrap_or("".to_string()))
+    }
+}
+
+impl FromRow<AssetHoldingRow> for AssetHoldingRow {
+    fn from_row<'a>(row: &'a Row) -> Result<AssetHoldingRow, db_error> {
+        let contract_identifier_str : String = row.get("contract_identifier");
+        let asset_name_str : String = row.get("asset_name");
+        let value_hex : String = row.get("value");
+        let index_block_hash = StacksBlockId::from_column(row, "index_block_hash")?;
+
+        let contract_identifier = QualifiedContractIdentifier::parse(&contract_identifier_str)
+            .map_err(|_e| db_error::ParseError)?;
+        let asset_name = ClarityName::try_from(asset_name_str)
+            .map_err(|_e| db_error::ParseError)?;
+
+        let value = if value_hex.len() > 0 {
+            Some(Value::try_deserialize_hex_untyped(&value_hex).map_err(|_e| db_error::ParseError)?)
+        } else {
+            None
+        };
+
+        Ok(AssetHoldingRow {
+            asset_identifier: AssetIdentifier { contract_identifier, asset_name },
+            value,
+            index_block_hash
+        })
+    }
+}
+
 impl StacksTransactionReceipt {
     pub fn from_stx_transfer(tx: StacksTransaction, origin_account: &StacksAccount, recipient: PrincipalData, amount: u128, cost: ExecutionCost) -> StacksTransactionReceipt {
         let sender = origin_account.principal.clone();
@@ -103,7 +227,8 @@ impl StacksTransactionReceipt {
             post_condition_aborted: false,
             contract_analysis: None,
             transaction: tx,
-            execution_cost: cost
+            execution_cost: cost,
+            cost_profile: None
         }
     }
 
@@ -115,7 +240,8 @@ impl StacksTransactionReceipt {
             result,
             stx_burned: burned,
             contract_analysis: None,
-            execution_cost: cost
+            execution_cost: cost,
+            cost_profile: None
         }
     }
 
@@ -127,7 +253,8 @@ impl StacksTransactionReceipt {
             result,
             stx_burned: burned,
             contract_analysis: None,
-            execution_cost: cost
+            execution_cost: cost,
+            cost_profile: None
         }
     }
 
@@ -139,7 +266,8 @@ impl StacksTransactionReceipt {
             result: Value::okay_true(),
             stx_burned: burned,
             contract_analysis: Some(analysis),
-            execution_cost: cost
+            execution_cost: cost,
+            cost_profile: None
         }
     }
 
@@ -151,7 +279,8 @@ impl StacksTransactionReceipt {
             result: Value::okay_true(),
             stx_burned: burned,
             contract_analysis: Some(analysis),
-            execution_cost: cost
+            execution_cost: cost,
+            cost_profile: None
         }
     }
 
@@ -163,7 +292,8 @@ impl StacksTransactionReceipt {
             result: Value::okay_true(),
             stx_burned: 0,
             contract_analysis: None,
-            execution_cost: ExecutionCost::zero()
+            execution_cost: ExecutionCost::zero(),
+            cost_profile: None
         }
     }
 
@@ -176,6 +306,7 @@ impl StacksTransactionReceipt {
             stx_burned: 0,
             contract_analysis: None,
             execution_cost: analysis_cost,
+            cost_profile: None,
         }
     }
 }
@@ -557,6 +688,7 @@ impl StacksChainState {
 
                 let mut total_cost = clarity_tx.cost_so_far();
                 total_cost.sub(&cost_before).expect("BUG: total block cost decreased");
+                let cost_profile = clarity_tx.take_cost_profile();
 
                 let (result, asset_map, events) = match contract_call_resp {
                     Ok((return_value, asset_map, events)) => {
@@ -572,17 +704,21 @@ impl StacksChainState {
                                 Ok((Value::err_none(), AssetMap::new(), vec![]))
                             },
                             clarity_error::AbortedByCallback(value, assets, events) => {
-                                let receipt = StacksTransactionReceipt::from_condition_aborted_contract_call(
+                                let mut receipt = StacksTransactionReceipt::from_condition_aborted_contract_call(
                                     tx.clone(),
                                     events,
                                     value.expect("BUG: Post condition contract call must provide would-have-been-returned value"),
                                     assets.get_stx_burned_total(),
                                     total_cost);
+                                receipt.cost_profile = cost_profile;
                                 return Ok(receipt);
                             },
                             // log this for now
                             clarity_error::CostError(ref cost, ref budget) => {
                                 warn!("Block compute budget exceeded on {}: cost={}, budget={}", tx.txid(), cost, budget);
+                                if let Some(ref profile) = cost_profile {
+                                    debug!("Cost profile of {}:\n{}", tx.txid(), profile.make_summary());
+                                }
                                 Err(e)
                             },
                             _ => Err(e)
@@ -596,7 +732,8 @@ impl StacksChainState {
                     }
                 })?;
 
-                let receipt = StacksTransactionReceipt::from_contract_call(tx.clone(), events, result, asset_map.get_stx_burned_total(), total_cost);
+                let mut receipt = StacksTransactionReceipt::from_contract_call(tx.clone(), events, result, asset_map.get_stx_burned_total(), total_cost);
+                receipt.cost_profile = cost_profile;
                 Ok(receipt)
             },
             TransactionPayload::SmartContract(ref smart_contract) => {
@@ -663,6 +800,7 @@ impl StacksChainState {
 
                 let mut total_cost = clarity_tx.cost_so_far();
                 total_cost.sub(&cost_before).expect("BUG: total block cost decreased");
+                let cost_profile = clarity_tx.take_cost_profile();
 
                 let (asset_map, events) = match initialize_resp {
                     Ok(x) => Ok(x),
@@ -671,11 +809,15 @@ impl StacksChainState {
                             // log cost overflow errors
                             clarity_error::CostError(ref cost, ref budget) => {
                                 warn!("Block compute budget exceeded on {}: cost={}, budget={}", tx.txid(), cost, budget);
+                                if let Some(ref profile) = cost_profile {
+                                    debug!("Cost profile of {}:\n{}", tx.txid(), profile.make_summary());
+                                }
                                 Err(e)
                             },
                             clarity_error::AbortedByCallback(_, assets, events) => {
-                                let receipt = StacksTransactionReceipt::from_condition_aborted_smart_contract(
+                                let mut receipt = StacksTransactionReceipt::from_condition_aborted_smart_contract(
                                     tx.clone(), events, assets.get_stx_burned_total(), contract_analysis, total_cost);
+                                receipt.cost_profile = cost_profile;
                                 return Ok(receipt);
                             },
                             // runtime errors are okay -- we just have an empty asset map
@@ -698,7 +840,8 @@ impl StacksChainState {
                 clarity_tx.save_analysis(&contract_id, &contract_analysis)
                     .expect("FATAL: failed to store contract analysis");
 
-                let receipt = StacksTransactionReceipt::from_smart_contract(tx.clone(), events, asset_map.get_stx_burned_total(), contract_analysis, total_cost);
+                let mut receipt = StacksTransactionReceipt::from_smart_contract(tx.clone(), events, asset_map.get_stx_burned_total(), contract_analysis, total_cost);
+                receipt.cost_profile = cost_profile;
                 Ok(receipt)
             },
             TransactionPayload::PoisonMicroblock(ref _mblock_header_1, ref _mblock_header_2) => {
@@ -730,9 +873,19 @@ impl StacksChainState {
         StacksChainState::process_transaction_precheck(&clarity_block.config, tx)?;
 
         let mut transaction = clarity_block.connection().start_transaction_processing();
-        let (origin_account, payer_account) = StacksChainState::check_transaction_nonces(&mut transaction, tx, quiet)?;
+        let (fee, tx_receipt) = StacksChainState::inner_process_transaction(&mut transaction, tx, quiet)?;
+
+        transaction.commit();
+
+        Ok((fee, tx_receipt))
+    }
 
-        let tx_receipt = StacksChainState::process_transaction_payload(&mut transaction, tx, &origin_account)?;
+    /// Run a transaction's payload, pay its fee, and update its accounts' nonces, all within the
+    /// given Clarity transaction.  The caller decides whether or not to commit it.
+    fn inner_process_transaction(transaction: &mut ClarityTransactionConnection, tx: &StacksTransaction, quiet: bool) -> Result<(u64, StacksTransactionReceipt), Error> {
+        let (origin_account, payer_account) = StacksChainState::check_transaction_nonces(transaction, tx, quiet)?;
+
+        let tx_receipt = StacksChainState::process_transaction_payload(transaction, tx, &origin_account)?;
 
         // pay fee borne by runtime costs.
         // NOTE: the fee must be paid _after_ we run the payload, because we will (eventually) be
@@ -741,20 +894,247 @@ impl StacksChainState {
         // transaction body runs.
         // TODO: this field is the fee *rate*, not the absolute fee.  This code is broken until we have
         // the true block reward system built.
-        let new_payer_account = StacksChainState::get_payer_account(&mut transaction, tx);
+        let new_payer_account = StacksChainState::get_payer_account(transaction, tx);
         let fee = tx.get_fee_rate();
-        StacksChainState::pay_transaction_fee(&mut transaction, fee, &new_payer_account)?;
+        StacksChainState::pay_transaction_fee(transaction, fee, &new_payer_account)?;
 
         // update the account nonces
-        StacksChainState::update_account_nonce(&mut transaction, &origin_account);
+        StacksChainState::update_account_nonce(transaction, &origin_account);
         if origin_account != payer_account {
-            StacksChainState::update_account_nonce(&mut transaction, &payer_account);
+            StacksChainState::update_account_nonce(transaction, &payer_account);
         }
 
-        transaction.commit();
-
         Ok((fee, tx_receipt))
     }
+
+    /// Store a single transaction receipt, confirmed by the given anchored block.
+    fn insert_transaction_receipt<'a>(tx: &mut StacksDBTx<'a>, index_block_hash: &StacksBlockId, microblock_hash_opt: Option<&BlockHeaderHash>,
+                                      tx_index: u32, receipt: &StacksTransactionReceipt) -> Result<(), Error> {
+        let txid = receipt.transaction.txid();
+
+        let mut tx_bytes = vec![];
+        receipt.transaction.consensus_serialize(&mut tx_bytes).map_err(Error::NetError)?;
+
+        let result_hex = receipt.result.serialize();
+
+        let committed = !receipt.post_condition_aborted;
+        let events : Vec<serde_json::Value> = receipt.events.iter().map(|event| event.json_serialize(&txid, committed)).collect();
+        let events_json = serde_json::to_string(&events)
+            .map_err(|e| Error::DBError(db_error::SerializationError(e)))?;
+        let execution_cost_json = serde_json::to_string(&receipt.execution_cost)
+            .map_err(|e| Error::DBError(db_error::SerializationError(e)))?;
+
+        let microblock_hash_str = microblock_hash_opt.map(|hash| hash.to_hex());
+
+        let args: &[&dyn ToSql] = &[&txid, index_block_hash, &microblock_hash_str, &tx_index, &tx_bytes, &result_hex,
+                                    &receipt.post_condition_aborted, &events_json, &execution_cost_json];
+
+        tx.execute("INSERT OR REPLACE INTO transactions \
+                    (txid, index_block_hash, microblock_hash, tx_index, tx, result, post_condition_aborted, events, execution_cost) \
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)", args)
+            .map_err(|e| Error::DBError(db_error::SqliteError(e)))?;
+
+        Ok(())
+    }
+
+    /// Store the receipts of all transactions confirmed by an anchored block, so they can be
+    /// looked up by txid later (if the transaction index is enabled), along with the assets they
+    /// moved.  The receipts must be in the order in which append_block() produces them: the
+    /// anchored block's transactions first, followed by the transactions of the parent microblock
+    /// stream the block confirms.
+    pub fn insert_transaction_receipts<'a>(tx: &mut StacksDBTx<'a>, index_block_hash: &StacksBlockId, block: &StacksBlock,
+                                           microblocks: &Vec<StacksMicroblock>, receipts: &Vec<StacksTransactionReceipt>) -> Result<(), Error> {
+        let mut locations = vec![];
+        for i in 0..block.txs.len() {
+            locations.push((None, i as u32));
+        }
+        for microblock in microblocks.iter() {
+            let microblock_hash = microblock.block_hash();
+            for i in 0..microblock.txs.len() {
+                locations.push((Some(microblock_hash.clone()), i as u32));
+            }
+        }
+
+        assert_eq!(locations.len(), receipts.len(), "BUG: number of receipts does not match number of confirmed transactions");
+
+        let index_transactions = StacksChainState::has_table(tx, "transactions")?;
+        for (position, ((microblock_hash_opt, tx_index), receipt)) in locations.iter().zip(receipts.iter()).enumerate() {
+            if index_transactions {
+                StacksChainState::insert_transaction_receipt(tx, index_block_hash, microblock_hash_opt.as_ref(), *tx_index, receipt)?;
+            }
+            StacksChainState::insert_asset_holdings(tx, index_block_hash, receipt)?;
+            StacksChainState::insert_fee_rate_sample(tx, index_block_hash, position as u32, receipt)?;
+        }
+        Ok(())
+    }
+
+    /// Record the fee rate paid by a confirmed transaction, for fee estimation.  Transactions that
+    /// pay no fee (i.e. coinbases) are skipped.
+    fn insert_fee_rate_sample<'a>(tx: &mut StacksDBTx<'a>, index_block_hash: &StacksBlockId, position: u32, receipt: &StacksTransactionReceipt) -> Result<(), Error> {
+        let fee_rate = receipt.transaction.get_fee_rate();
+        if fee_rate == 0 {
+            return Ok(());
+        }
+
+        let fee_rate = u64_to_sql(fee_rate).map_err(Error::DBError)?;
+        let args: &[&dyn ToSql] = &[index_block_hash, &position, &fee_rate];
+
+        tx.execute("INSERT OR REPLACE INTO fee_rate_samples (index_block_hash, position, fee_rate) VALUES (?1, ?2, ?3)", args)
+            .map_err(|e| Error::DBError(db_error::SqliteError(e)))?;
+
+        Ok(())
+    }
+
+    /// Record the recipients of the fungible and non-fungible tokens minted or transferred by a
+    /// confirmed transaction.  Transactions aborted by their post-conditions moved no assets, so
+    /// they are skipped.
+    fn insert_asset_holdings<'a>(tx: &mut StacksDBTx<'a>, index_block_hash: &StacksBlockId, receipt: &StacksTransactionReceipt) -> Result<(), Error> {
+        if receipt.post_condition_aborted {
+            return Ok(());
+        }
+
+        for event in receipt.events.iter() {
+            let (recipient, asset_identifier, value_hex) = match event {
+                StacksTransactionEvent::FTEvent(FTEventType::FTTransferEvent(ref data)) => (&data.recipient, &data.asset_identifier, "".to_string()),
+                StacksTransactionEvent::FTEvent(FTEventType::FTMintEvent(ref data)) => (&data.recipient, &data.asset_identifier, "".to_string()),
+                StacksTransactionEvent::NFTEvent(NFTEventType::NFTTransferEvent(ref data)) => (&data.recipient, &data.asset_identifier, data.value.serialize()),
+                StacksTransactionEvent::NFTEvent(NFTEventType::NFTMintEvent(ref data)) => (&data.recipient, &data.asset_identifier, data.value.serialize()),
+                _ => {
+                    continue;
+                }
+            };
+
+            let principal = recipient.to_string();
+            let contract_identifier = asset_identifier.contract_identifier.to_string();
+            let asset_name = asset_identifier.asset_name.to_string();
+            let args: &[&dyn ToSql] = &[&principal, &contract_identifier, &asset_name, &value_hex, index_block_hash];
+
+            tx.execute("INSERT OR IGNORE INTO asset_holdings (principal, contract_identifier, asset_name, value, index_block_hash) VALUES (?1, ?2, ?3, ?4, ?5)", args)
+                .map_err(|e| Error::DBError(db_error::SqliteError(e)))?;
+        }
+        Ok(())
+    }
+
+    /// Find the fungible and non-fungible tokens that the given principal has received in the
+    /// fork whose tip is the given index block hash.  Each entry is the asset and, for
+    /// non-fungible tokens, the identifier of the token received.  These are candidates only:
+    /// the principal may have since spent or transferred them, so callers must check each one
+    /// against the Clarity state at the tip.  Transactions in a not-yet-confirmed microblock
+    /// stream are not considered.
+    /// Candidates are found in order of asset and token identifier, starting after `after`.  About
+    /// `count` receipts are examined -- one per block the asset was received in, in any fork -- so
+    /// along with the candidates, this returns the last one examined to continue after, if there
+    /// may be more.
+    pub fn get_asset_holding_candidates(&self, tip: &StacksBlockId, principal: &PrincipalData, after: Option<&AssetHolding>, count: u32) -> Result<(Vec<AssetHolding>, Option<AssetHolding>), Error> {
+        let tip = match self.unconfirmed_state {
+            Some(ref unconfirmed_state) if *tip == unconfirmed_state.unconfirmed_chain_tip => unconfirmed_state.confirmed_chain_tip.clone(),
+            _ => tip.clone()
+        };
+
+        let principal = principal.to_string();
+        let (after_contract, after_asset, after_value) = match after {
+            Some(holding) => AssetHoldingRow::key_of(holding),
+            None => ("".to_string(), "".to_string(), "".to_string())
+        };
+
+        let sql = "SELECT * FROM asset_holdings WHERE principal = ?1 AND \
+                   (contract_identifier > ?2 OR (contract_identifier = ?2 AND (asset_name > ?3 OR (asset_name = ?3 AND value > ?4)))) \
+                   ORDER BY contract_identifier, asset_name, value LIMIT ?5";
+        let args: &[&dyn ToSql] = &[&principal, &after_contract, &after_asset, &after_value, &count];
+        let mut rows = query_rows::<AssetHoldingRow, _>(&self.headers_db, sql, args).map_err(Error::DBError)?;
+
+        let next = if rows.len() as u32 >= count && count > 0 {
+            // the last holding examined may have been received in more blocks than fit under the
+            // limit, so fetch all of them
+            let last = rows[rows.len() - 1].holding();
+            let (last_contract, last_asset, last_value) = AssetHoldingRow::key_of(&last);
+            let sql = "SELECT * FROM asset_holdings WHERE principal = ?1 AND \
+                       (contract_identifier > ?2 OR (contract_identifier = ?2 AND (asset_name > ?3 OR (asset_name = ?3 AND value > ?4)))) AND \
+                       (contract_identifier < ?5 OR (contract_identifier = ?5 AND (asset_name < ?6 OR (asset_name = ?6 AND value <= ?7)))) \
+                       ORDER BY contract_identifier, asset_name, value";
+            let args: &[&dyn ToSql] = &[&principal, &after_contract, &after_asset, &after_value, &last_contract, &last_asset, &last_value];
+            rows = query_rows::<AssetHoldingRow, _>(&self.headers_db, sql, args).map_err(Error::DBError)?;
+            Some(last)
+        } else {
+            None
+        };
+
+        let mut candidates = vec![];
+        let mut seen = HashSet::new();
+        for row in rows.into_iter() {
+            let holding = row.holding();
+            if seen.contains(&holding) {
+                continue;
+            }
+            if get_ancestor_block_height(&self.headers_state_index, &row.index_block_hash, &tip).map_err(Error::DBError)?.is_none() {
+                // received in a different fork
+                continue;
+            }
+            seen.insert(holding.clone());
+            candidates.push(holding);
+        }
+        Ok((candidates, next))
+    }
+
+    /// Find a confirmed transaction and its receipt in the fork whose tip is the given index block hash.
+    /// Returns None if the transaction was not confirmed in this fork, or if it's not indexed.
+    pub fn get_transaction_info(&self, tip: &StacksBlockId, txid: &Txid) -> Result<Option<StacksTransactionInfo>, Error> {
+        if !self.has_transaction_index()? {
+            return Ok(None);
+        }
+
+        let sql = "SELECT * FROM transactions WHERE txid = ?1";
+        let args: &[&dyn ToSql] = &[txid];
+        let candidates = query_rows::<StacksTransactionInfo, _>(&self.headers_db, sql, args).map_err(Error::DBError)?;
+
+        // the same transaction can be confirmed in more than one fork
+        for candidate in candidates.into_iter() {
+            if get_ancestor_block_height(&self.headers_state_index, &candidate.index_block_hash, tip).map_err(Error::DBError)?.is_some() {
+                return Ok(Some(candidate));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Get the fee rates paid by the transactions confirmed by the last `num_blocks` anchored
+    /// blocks in the fork whose tip is the given index block hash (including the transactions of
+    /// the microblock streams they confirmed).  Used for fee estimation.  Coinbases pay no fee, so
+    /// they are skipped.
+    pub fn get_recent_fee_rates(&self, tip: &StacksBlockId, num_blocks: u64) -> Result<Vec<u64>, Error> {
+        let tip_height = match StacksChainState::get_stacks_block_header_info_by_index_block_hash(&self.headers_db, tip)? {
+            Some(tip_info) => tip_info.block_height,
+            None => {
+                return Ok(vec![]);
+            }
+        };
+
+        let start_height = tip_height.saturating_sub(num_blocks.saturating_sub(1));
+        let mut fee_rates = vec![];
+        for header_info in self.get_ancestor_header_infos(tip, start_height, num_blocks)?.iter() {
+            let sql = "SELECT fee_rate FROM fee_rate_samples WHERE index_block_hash = ?1 ORDER BY position".to_string();
+            let args: &[&dyn ToSql] = &[&header_info.index_block_hash()];
+            let mut block_fee_rates = query_row_columns::<u64, _>(&self.headers_db, &sql, args, "fee_rate").map_err(Error::DBError)?;
+            fee_rates.append(&mut block_fee_rates);
+        }
+        Ok(fee_rates)
+    }
+
+    /// Run a transaction on top of the given chain tip, and throw away its effects.  Used to find
+    /// out what a transaction would do (and cost) if it were mined.  The transaction is run on a
+    /// read-only view of the tip, and its execution is capped at the given cost limit (not the
+    /// block limit), just like a read-only function call.
+    pub fn simulate_transaction(&mut self, tip: &StacksBlockId, tx: &StacksTransaction, cost_limit: &ExecutionCost) -> Result<StacksTransactionReceipt, Error> {
+        let config = self.config();
+        StacksChainState::process_transaction_precheck(&config, tx)?;
+
+        let cost_track = LimitedCostTracker::new(cost_limit.clone());
+        self.maybe_read_only_clarity_tx(tip, |clarity_tx| {
+            clarity_tx.with_simulated_transaction(cost_track, |transaction| {
+                StacksChainState::inner_process_transaction(transaction, tx, true)
+            })
+        })
+        .map(|(_fee, receipt)| receipt)
+    }
 }
 
 #[cfg(test)]
@@ -1091,6 +1471,48 @@ pub mod test {
         assert!(contract_res.is_ok());
     }
 
+    #[test]
+    fn simulate_smart_contract_transaction() {
+        let contract = "
+        (define-data-var bar int 0)
+        (define-public (get-bar) (ok (var-get bar)))";
+
+        let mut chainstate = instantiate_chainstate(false, 0x80000000, "simulate-smart-contract-transaction");
+
+        let privk = StacksPrivateKey::from_hex("6d430bb91222408e7706c9001cfaeb91b08c2be6d5ac95779ab52c6b431950e001").unwrap();
+        let auth = TransactionAuth::from_p2pkh(&privk).unwrap();
+        let addr = auth.origin().address_testnet();
+
+        let mut tx_contract = StacksTransaction::new(TransactionVersion::Testnet,
+                                                     auth.clone(),
+                                                     TransactionPayload::new_smart_contract(&"hello-world".to_string(), &contract.to_string()).unwrap());
+
+        tx_contract.chain_id = 0x80000000;
+        tx_contract.set_fee_rate(0);
+
+        let mut signer = StacksTransactionSigner::new(&tx_contract);
+        signer.sign_origin(&privk).unwrap();
+
+        let signed_tx = signer.get_tx().unwrap();
+        let tip = StacksBlockHeader::make_index_block_hash(&FIRST_BURNCHAIN_BLOCK_HASH, &FIRST_STACKS_BLOCK_HASH);
+        let contract_id = QualifiedContractIdentifier::new(StandardPrincipalData::from(addr.clone()), ContractName::from("hello-world"));
+
+        let receipt = chainstate.simulate_transaction(&tip, &signed_tx, &ExecutionCost::max_value()).unwrap();
+        assert!(receipt.execution_cost.runtime > 0);
+
+        // nothing was written
+        chainstate.with_read_only_clarity_tx(&tip, |conn| {
+            assert!(StacksChainState::get_contract(conn, &contract_id).unwrap().is_none());
+            assert_eq!(StacksChainState::get_account(conn, &addr.to_account_principal()).nonce, 0);
+        });
+
+        // the simulation is capped by the given cost limit, not the block limit
+        match chainstate.simulate_transaction(&tip, &signed_tx, &ExecutionCost::zero()) {
+            Err(Error::CostOverflowError(..)) | Err(Error::ClarityError(clarity_error::CostError(..))) => {},
+            res => panic!("Expected a cost error, got {:?}", &res)
+        }
+    }
+
     #[test]
     fn process_smart_contract_transaction_invalid() {
         let contract_correct = "
@@ -3295,5 +3717,55 @@ pub mod test {
         assert_eq!(fee, 0);
         if let Error::InvalidFee = err {} else { assert!(false) };
     }
+
+    #[test]
+    fn get_asset_holding_candidates_pages() {
+        let chainstate = instantiate_chainstate(false, 0x80000000, "get-asset-holding-candidates-pages");
+        let tip = StacksBlockHeader::make_index_block_hash(&FIRST_BURNCHAIN_BLOCK_HASH, &FIRST_STACKS_BLOCK_HASH);
+        let other_fork = StacksBlockId([0xf0; 32]);
+        let principal = PrincipalData::from(StacksAddress { version: 1, bytes: Hash160([0xff; 20]) });
+
+        let asset = |name: &str| AssetIdentifier {
+            contract_identifier: QualifiedContractIdentifier::local("tokens").unwrap(),
+            asset_name: ClarityName::try_from(name.to_string()).unwrap()
+        };
+        let holdings = vec![
+            ((asset("a-token"), None), vec![&tip]),
+            ((asset("b-names"), Some(Value::Int(1))), vec![&tip, &other_fork]),
+            ((asset("b-names"), Some(Value::Int(2))), vec![&other_fork]),
+            ((asset("c-token"), None), vec![&other_fork, &tip]),
+        ];
+        for ((asset_identifier, value_opt), blocks) in holdings.iter() {
+            for block in blocks.iter() {
+                let args: &[&dyn ToSql] = &[&principal.to_string(), &asset_identifier.contract_identifier.to_string(), &asset_identifier.asset_name.to_string(),
+                                            &value_opt.as_ref().map(|value| value.serialize()).unwrap_or("".to_string()), *block];
+                chainstate.headers_db.execute("INSERT INTO asset_holdings (principal, contract_identifier, asset_name, value, index_block_hash) VALUES (?1, ?2, ?3, ?4, ?5)", args).unwrap();
+            }
+        }
+
+        let (candidates, next) = chainstate.get_asset_holding_candidates(&tip, &principal, None, 10).unwrap();
+        assert_eq!(candidates, vec![holdings[0].0.clone(), holdings[1].0.clone(), holdings[3].0.clone()]);
+        assert_eq!(next, None);
+
+        // every block a holding was received in counts towards the page, and all of the last
+        // holding's are examined
+        let (candidates, next) = chainstate.get_asset_holding_candidates(&tip, &principal, None, 3).unwrap();
+        assert_eq!(candidates, vec![holdings[0].0.clone(), holdings[1].0.clone()]);
+        assert_eq!(next, Some(holdings[1].0.clone()));
+
+        // holdings received only in other forks are skipped, even if that leaves the page empty
+        let (candidates, next) = chainstate.get_asset_holding_candidates(&tip, &principal, next.as_ref(), 1).unwrap();
+        assert_eq!(candidates, vec![]);
+        assert_eq!(next, Some(holdings[2].0.clone()));
+
+        let (candidates, next) = chainstate.get_asset_holding_candidates(&tip, &principal, next.as_ref(), 1).unwrap();
+        assert_eq!(candidates, vec![holdings[3].0.clone()]);
+        assert_eq!(next, Some(holdings[3].0.clone()));
+
+        let (candidates, next) = chainstate.get_asset_holding_candidates(&tip, &principal, next.as_ref(), 1).unwrap();
+        assert_eq!(candidates, vec![]);
+        assert_eq!(next, None);
+    }
+
     // TODO: test poison microblock
 }
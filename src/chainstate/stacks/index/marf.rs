@@ -33,6 +33,7 @@ use std::path::{
 };
 
 use std::fs;
+use std::collections::HashSet;
 
 use sha2::Digest;
 
@@ -411,6 +412,43 @@ impl <T: MarfTrieId> MARF <T> {
         return Err(Error::CorruptionError("Trie has a cycle".to_string()));
     }
 
+    /// Walk down this MARF at the given block hash like MARF::walk(), and return the local block
+    /// ID and pointer of every node visited on the way to the leaf, starting with the root.
+    fn walk_path_nodes(storage: &mut TrieFileStorage<T>, block_hash: &T, path: &TriePath) -> Result<Vec<(u32, TriePtr)>, Error> {
+        storage.open_block(block_hash)?;
+
+        let mut cursor = TrieCursor::new(path, storage.root_trieptr());
+        let mut nodes = vec![(storage.get_cur_block_identifier()?, storage.root_trieptr())];
+        let (mut node, _) = Trie::read_root(storage)?;
+
+        for _ in 0..(cursor.path.len()+1) {
+            match Trie::walk_from(storage, &node, &mut cursor) {
+                Ok(Some((ptr, next_node, _))) => {
+                    nodes.push((storage.get_cur_block_identifier()?, ptr));
+                    node = next_node;
+                },
+                Ok(None) => {
+                    return Ok(nodes);
+                },
+                Err(Error::CursorError(CursorError::BackptrEncountered(ptr))) => {
+                    let (next_node, _, next_node_ptr, back_block_id) = MARF::walk_backptr(storage, &node, ptr.chr(), &mut cursor)?;
+                    cursor.repair_backptr_finish(&next_node_ptr, storage.get_cur_block());
+                    nodes.push((back_block_id, next_node_ptr));
+                    node = next_node;
+                },
+                Err(Error::CursorError(_)) => {
+                    return Err(Error::NotFoundError);
+                },
+                Err(e) => {
+                    return Err(e);
+                }
+            }
+        }
+
+        trace!("Trie has a cycle");
+        return Err(Error::CorruptionError("Trie has a cycle".to_string()));
+    }
+
     pub fn format(storage: &mut TrieFileStorage<T>, first_block_hash: &T) -> Result<(), Error> {
         if storage.readonly {
             return Err(Error::ReadOnlyError);
@@ -521,15 +559,26 @@ impl <T: MarfTrieId> MARF <T> {
 
     /// Resolve a key from the MARF to a MARFValue with respect to the given block height.
     pub fn get(&mut self, block_hash: &T, key: &str) -> Result<Option<MARFValue>, Error> {
+        MARF::check_not_pruned(&self.storage, block_hash)?;
         MARF::get_by_key(&mut self.storage, block_hash, key)
     }
 
+    /// Fail with PrunedError if the given block's trie has been pruned.
+    pub fn check_not_pruned(storage: &TrieFileStorage<T>, block_hash: &T) -> Result<(), Error> {
+        if storage.is_block_pruned(block_hash)? {
+            debug!("Trie for {} has been pruned", block_hash);
+            return Err(Error::PrunedError);
+        }
+        Ok(())
+    }
+
     /// Target the MARF's storage at a given block.
     pub fn open_block(&mut self, block_hash: &T) -> Result<(), Error> {
         self.storage.open_block(block_hash)
     }
 
     pub fn get_with_proof(&mut self, block_hash: &T, key: &str) -> Result<Option<(MARFValue, TrieMerkleProof<T>)>, Error> {
+        MARF::check_not_pruned(&self.storage, block_hash)?;
         let marf_value = match MARF::get_by_key(&mut self.storage, block_hash, key)? {
             None => return Ok(None),
             Some(x) => x
@@ -538,6 +587,16 @@ impl <T: MarfTrieId> MARF <T> {
         Ok(Some((marf_value, proof)))
     }
 
+    /// Prove that a key has no value with respect to the given block.  Returns None if it does.
+    pub fn get_exclusion_proof(&mut self, block_hash: &T, key: &str) -> Result<Option<TrieMerkleProof<T>>, Error> {
+        MARF::check_not_pruned(&self.storage, block_hash)?;
+        if MARF::get_by_key(&mut self.storage, block_hash, key)?.is_some() {
+            return Ok(None);
+        }
+        let proof = TrieMerkleProof::from_absent_entry(&mut self.storage, key, block_hash)?;
+        Ok(Some(proof))
+    }
+
     pub fn get_bhh_at_height(&mut self, block_hash: &T, height: u32) -> Result<Option<T>, Error> {
         MARF::get_block_at_height(&mut self.storage, height, block_hash)
     }
@@ -798,6 +857,7 @@ impl <T: MarfTrieId> MARF <T> {
             error!("Block data already exists: {}", next_chain_tip);
             return Err(Error::ExistsError);
         }
+        MARF::check_not_pruned(&self.storage, chain_tip)?;
         
         let block_height = self.inner_get_extension_height(chain_tip, next_chain_tip)?;
         MARF::extend_trie(&mut self.storage, next_chain_tip)?;
@@ -837,6 +897,7 @@ impl <T: MarfTrieId> MARF <T> {
             error!("No such confirmed block {}", chain_tip);
             return Err(Error::NotFoundError);
         }
+        MARF::check_not_pruned(&self.storage, chain_tip)?;
         
         let unconfirmed_tip = MARF::make_unconfirmed_chain_tip(chain_tip);
 
@@ -966,6 +1027,8 @@ impl <T: MarfTrieId> MARF <T> {
             return Err(Error::NonMatchingForks(bhh.clone().to_bytes(), cur_block_hash.to_bytes()))
         }
 
+        MARF::check_not_pruned(&self.storage, bhh)?;
+
         // test open
         let result = self.storage.open_block(bhh);
 
@@ -1016,6 +1079,137 @@ impl <T: MarfTrieId> MARF <T> {
         self.storage.open_block(&cur_block_hash)?;
         root_hash_res
     }
+
+    /// Check the integrity of the trie at a particular block.  See Trie::check_trie().
+    pub fn check_trie_at(&mut self, block_hash: &T) -> Result<u64, Error> {
+        Trie::check_trie(&mut self.storage, block_hash)
+    }
+
+    /// Prune the tries of the blocks more than retention_depth blocks below the highest block, in
+    /// every fork.  See MARF::prune_blocks().
+    /// Returns the number of tries pruned.
+    pub fn prune(&mut self, retention_depth: u32) -> Result<u64, Error> {
+        if self.storage.readonly {
+            return Err(Error::ReadOnlyError);
+        }
+        if self.open_chain_tip.is_some() {
+            return Err(Error::InProgressError);
+        }
+
+        let (cur_block_hash, cur_block_id) = self.storage.get_cur_block_and_id();
+        let result = MARF::inner_prune(&mut self.storage, retention_depth);
+
+        // restore
+        self.storage.open_block_maybe_id(&cur_block_hash, cur_block_id)?;
+        result
+    }
+
+    /// Prune the tries of the given confirmed blocks.  A pruned trie keeps only the nodes that the
+    /// remaining tries still refer to, plus what it takes to look up its own height and the
+    /// ancestors its root hash covers (see TrieFileStorage::prune_tries()).
+    /// Afterwards, reading a key or making a proof as of a pruned block fails with PrunedError.
+    /// Keys and proofs as of the remaining blocks are unaffected, even where the proofs pass
+    /// through pruned tries, and block heights and hashes can still be looked up from any block.
+    /// Returns the number of tries pruned.
+    pub fn prune_blocks(&mut self, blocks: &[T]) -> Result<u64, Error> {
+        if self.storage.readonly {
+            return Err(Error::ReadOnlyError);
+        }
+        if self.open_chain_tip.is_some() {
+            return Err(Error::InProgressError);
+        }
+
+        let (cur_block_hash, cur_block_id) = self.storage.get_cur_block_and_id();
+        let result = MARF::inner_prune_blocks(&mut self.storage, blocks);
+
+        // restore
+        self.storage.open_block_maybe_id(&cur_block_hash, cur_block_id)?;
+        result
+    }
+
+    fn inner_prune(storage: &mut TrieFileStorage<T>, retention_depth: u32) -> Result<u64, Error> {
+        let mut confirmed = vec![];
+        for (_, block_hash, unconfirmed) in storage.read_all_block_ids()?.into_iter() {
+            if unconfirmed || storage.is_block_pruned(&block_hash)? {
+                continue;
+            }
+            let height = MARF::get_block_height(storage, &block_hash, &block_hash)?
+                .ok_or_else(|| Error::CorruptionError(format!("Could not obtain block height for block {}", &block_hash)))?;
+            confirmed.push((block_hash, height));
+        }
+
+        let max_height = match confirmed.iter().map(|(_, height)| *height).max() {
+            Some(height) => height,
+            None => return Ok(0)
+        };
+        let min_retained_height = max_height.saturating_sub(retention_depth);
+
+        let blocks : Vec<T> = confirmed.into_iter()
+            .filter(|(_, height)| *height < min_retained_height)
+            .map(|(block_hash, _)| block_hash)
+            .collect();
+
+        debug!("Prune {} tries below height {} in {}", blocks.len(), min_retained_height, &storage.db_path);
+        MARF::inner_prune_blocks(storage, &blocks)
+    }
+
+    fn inner_prune_blocks(storage: &mut TrieFileStorage<T>, blocks: &[T]) -> Result<u64, Error> {
+        let blocks : HashSet<&[u8]> = blocks.iter().map(|block_hash| block_hash.as_bytes()).collect();
+        let mut retained = vec![];
+        let mut pruned = vec![];
+        let mut keep = vec![];
+        for (block_id, block_hash, unconfirmed) in storage.read_all_block_ids()?.into_iter() {
+            if unconfirmed {
+                retained.push(block_id);
+                continue;
+            }
+            if storage.is_block_pruned(&block_hash)? {
+                continue;
+            }
+            if !blocks.contains(block_hash.as_bytes()) {
+                retained.push(block_id);
+                continue;
+            }
+
+            // keep the paths to the block's own height and to the ancestors its root hash covers,
+            // so they can still be looked up from the block itself when checking its trie or
+            // proving the remaining tries' state through it.
+            let height = MARF::get_block_height(storage, &block_hash, &block_hash)?
+                .ok_or_else(|| Error::CorruptionError(format!("Could not obtain block height for block {}", &block_hash)))?;
+            let mut keys = vec![OWN_BLOCK_HEIGHT_KEY.to_string()];
+            let mut log_depth = 0;
+            while log_depth < 32 && (1u32 << log_depth) <= height {
+                keys.push(format!("{}::{}", BLOCK_HEIGHT_TO_HASH_MAPPING_KEY, height - (1u32 << log_depth)));
+                log_depth += 1;
+            }
+            for key in keys.iter() {
+                let mut nodes = MARF::walk_path_nodes(storage, &block_hash, &TriePath::from_key(key))?;
+                keep.append(&mut nodes);
+            }
+            pruned.push((block_id, block_hash));
+        }
+
+        if pruned.len() == 0 {
+            return Ok(0);
+        }
+
+        debug!("Prune {} tries in {}", pruned.len(), &storage.db_path);
+        storage.prune_tries(&retained, &pruned, &keep)?;
+        Ok(pruned.len() as u64)
+    }
+
+    /// Delete the trie of the given block and of every block built on it, e.g. to roll the MARF
+    /// back to before a corrupt trie.  The blocks can be appended again afterwards.  See
+    /// TrieFileStorage::truncate_at().
+    /// Returns the deleted blocks.
+    pub fn truncate_at(&mut self, block_hash: &T) -> Result<Vec<T>, Error> {
+        if self.open_chain_tip.is_some() {
+            return Err(Error::InProgressError);
+        }
+        let deleted = self.storage.truncate_at(block_hash)?;
+        debug!("Truncated {} tries at {} in {}", deleted.len(), block_hash, &self.storage.db_path);
+        Ok(deleted)
+    }
 }
 
 #[cfg(test)]
@@ -2414,5 +2608,140 @@ mod test {
         let e = MARF::get_path(marf.borrow_storage_backend(), &unconfirmed_tip, &triepath_2).unwrap_err();
         if let Error::NotFoundError = e { } else { assert!(false); }
     }
-}
 
+    #[test]
+    fn test_marf_prune() {
+        let path = "/tmp/rust_marf_prune";
+        if fs::metadata(path).is_ok() {
+            fs::remove_file(path).unwrap();
+        }
+        let mut marf : MARF<BlockHeaderHash> = MARF::from_path(path).unwrap();
+
+        // 10 blocks; "constant" is only written in the first one
+        let blocks : Vec<_> = (0..10).map(|i| BlockHeaderHash([i as u8 + 1; 32])).collect();
+        let mut parent = TrieFileStorage::block_sentinel();
+        for (i, block) in blocks.iter().enumerate() {
+            marf.begin(&parent, block).unwrap();
+            if i == 0 {
+                marf.insert("constant", MARFValue::from_value("constant")).unwrap();
+            }
+            marf.insert(&format!("key-{}", i), MARFValue::from_value(&format!("value-{}", i))).unwrap();
+            marf.insert("counter", MARFValue::from_value(&format!("{}", i))).unwrap();
+            marf.commit().unwrap();
+            parent = block.clone();
+        }
+        let tip = blocks[9].clone();
+        let root_hashes : Vec<_> = blocks.iter().map(|block| marf.get_root_hash_at(block).unwrap()).collect();
+
+        // blocks at heights 0-4 are pruned
+        assert_eq!(marf.prune(4).unwrap(), 5);
+        assert_eq!(marf.prune(4).unwrap(), 0);
+
+        // nothing changed as of the retained blocks...
+        for (block, root_hash) in blocks.iter().zip(root_hashes.iter()) {
+            assert_eq!(&marf.get_root_hash_at(block).unwrap(), root_hash);
+        }
+        for i in 0..10 {
+            // (what's left of the pruned tries still checks out)
+            marf.check_trie_at(&blocks[i]).unwrap();
+        }
+        assert_eq!(marf.get(&tip, "constant").unwrap(), Some(MARFValue::from_value("constant")));
+        assert_eq!(marf.get(&tip, "counter").unwrap(), Some(MARFValue::from_value("9")));
+        for i in 0..10 {
+            assert_eq!(marf.get(&tip, &format!("key-{}", i)).unwrap(), Some(MARFValue::from_value(&format!("value-{}", i))));
+        }
+        merkle_test_marf_key_value(marf.borrow_storage_backend(), &tip, &"key-7".to_string(), &"value-7".to_string(), None);
+
+        // ...but the pruned blocks can't be read or built on
+        match marf.get(&blocks[2], "key-0") {
+            Err(Error::PrunedError) => {},
+            x => panic!("Expected PrunedError, got {:?}", x)
+        }
+        match marf.get_with_proof(&blocks[2], "key-0") {
+            Err(Error::PrunedError) => {},
+            x => panic!("Expected PrunedError, got {:?}", x)
+        }
+        match marf.begin(&blocks[4], &BlockHeaderHash([0xf0; 32])) {
+            Err(Error::PrunedError) => {},
+            x => panic!("Expected PrunedError, got {:?}", x)
+        }
+
+        // proofs as of the retained blocks still pass through the pruned tries
+        merkle_test_marf_key_value(marf.borrow_storage_backend(), &tip, &"constant".to_string(), &"constant".to_string(), None);
+        merkle_test_marf_key_value(marf.borrow_storage_backend(), &tip, &"key-0".to_string(), &"value-0".to_string(), None);
+        merkle_test_marf_key_value(marf.borrow_storage_backend(), &blocks[5], &"key-2".to_string(), &"value-2".to_string(), None);
+        let exclusion_proof = marf.get_exclusion_proof(&tip, "missing").unwrap().unwrap();
+        let root_to_block = marf.borrow_storage_backend().read_root_to_block_table().unwrap();
+        assert!(exclusion_proof.verify_exclusion(&TriePath::from_key("missing"), &root_hashes[9], &root_to_block));
+
+        // block heights still resolve
+        for (i, block) in blocks.iter().enumerate() {
+            assert_eq!(MARF::get_block_height(marf.borrow_storage_backend(), block, &tip).unwrap(), Some(i as u32));
+            assert_eq!(MARF::get_block_height(marf.borrow_storage_backend(), block, block).unwrap(), Some(i as u32));
+            assert_eq!(MARF::get_block_at_height(marf.borrow_storage_backend(), i as u32, &tip).unwrap().as_ref(), Some(block));
+        }
+
+        // the chain can grow and be pruned again
+        let next_tip = BlockHeaderHash([0x0b; 32]);
+        marf.begin(&tip, &next_tip).unwrap();
+        marf.insert("counter", MARFValue::from_value("10")).unwrap();
+        marf.commit().unwrap();
+        assert_eq!(marf.prune(4).unwrap(), 1);
+
+        let mut marf : MARF<BlockHeaderHash> = MARF::from_path(path).unwrap();
+        assert_eq!(marf.get(&next_tip, "constant").unwrap(), Some(MARFValue::from_value("constant")));
+        assert_eq!(marf.get(&next_tip, "key-5").unwrap(), Some(MARFValue::from_value("value-5")));
+        marf.check_trie_at(&next_tip).unwrap();
+        marf.check_trie_at(&blocks[5]).unwrap();
+        merkle_test_marf_key_value(marf.borrow_storage_backend(), &next_tip, &"constant".to_string(), &"constant".to_string(), None);
+        match marf.get(&blocks[5], "key-5") {
+            Err(Error::PrunedError) => {},
+            x => panic!("Expected PrunedError, got {:?}", x)
+        }
+    }
+
+    #[test]
+    fn test_marf_truncate_at() {
+        let path = "/tmp/rust_marf_truncate_at";
+        if fs::metadata(path).is_ok() {
+            fs::remove_file(path).unwrap();
+        }
+        let mut marf : MARF<BlockHeaderHash> = MARF::from_path(path).unwrap();
+
+        // blocks 0-4 in one fork, and a second fork off of block 2
+        let blocks : Vec<_> = (0..5).map(|i| BlockHeaderHash([i as u8 + 1; 32])).collect();
+        let fork_block = BlockHeaderHash([0xf0; 32]);
+        let mut parent = TrieFileStorage::block_sentinel();
+        for (i, block) in blocks.iter().enumerate() {
+            marf.begin(&parent, block).unwrap();
+            marf.insert(&format!("key-{}", i), MARFValue::from_value(&format!("value-{}", i))).unwrap();
+            marf.commit().unwrap();
+            parent = block.clone();
+        }
+        marf.begin(&blocks[2], &fork_block).unwrap();
+        marf.insert("fork", MARFValue::from_value("fork")).unwrap();
+        marf.commit().unwrap();
+
+        // only block 3 and its descendants go
+        assert_eq!(marf.borrow_storage_backend().get_truncated_blocks(&blocks[3]).unwrap(), vec![blocks[3].clone(), blocks[4].clone()]);
+        assert_eq!(marf.truncate_at(&blocks[3]).unwrap(), vec![blocks[3].clone(), blocks[4].clone()]);
+        assert!(!marf.borrow_storage_backend().has_block(&blocks[3]).unwrap());
+        assert!(!marf.borrow_storage_backend().has_block(&blocks[4]).unwrap());
+        assert_eq!(marf.get(&fork_block, "key-2").unwrap(), Some(MARFValue::from_value("value-2")));
+        assert_eq!(marf.get(&fork_block, "fork").unwrap(), Some(MARFValue::from_value("fork")));
+        marf.check_trie_at(&fork_block).unwrap();
+
+        match marf.truncate_at(&blocks[4]) {
+            Err(Error::NotFoundError) => {},
+            x => panic!("Expected NotFoundError, got {:?}", x)
+        }
+
+        // the truncated blocks can be appended again
+        marf.begin(&blocks[2], &blocks[3]).unwrap();
+        marf.insert("key-3", MARFValue::from_value("value-3")).unwrap();
+        marf.commit().unwrap();
+        assert_eq!(marf.get(&blocks[3], "key-0").unwrap(), Some(MARFValue::from_value("value-0")));
+        assert_eq!(MARF::get_block_height(marf.borrow_storage_backend(), &blocks[3], &blocks[3]).unwrap(), Some(3));
+        marf.check_trie_at(&blocks[3]).unwrap();
+    }
+}
@@ -24,6 +24,7 @@ pub mod proofs;
 pub mod storage;
 pub mod trie;
 pub mod trie_sql;
+pub mod verifier;
 
 use std::fmt;
 use std::error;
@@ -275,7 +276,8 @@ pub enum Error {
     WriteNotBegunError,
     CursorError(node::CursorError),
     RestoreMarfBlockError(Box<Error>),
-    NonMatchingForks([u8; 32], [u8; 32])
+    NonMatchingForks([u8; 32], [u8; 32]),
+    PrunedError
 }
 
 impl From<io::Error> for Error {
@@ -331,6 +333,7 @@ impl fmt::Display for Error {
             Error::WriteNotBegunError => write!(f, "Write has not begun"),
             Error::RestoreMarfBlockError(_) => write!(f, "Failed to restore previous open block during block header check"),
             Error::NonMatchingForks(_, _) => write!(f, "The supplied blocks are not in the same fork"),
+            Error::PrunedError => write!(f, "The block's trie has been pruned"),
             Error::RequestedIdentifierForExtensionTrie => write!(f, "BUG: MARF requested the identifier for a RAM trie"),
         }
     }
@@ -161,12 +161,13 @@ pub enum TrieMerkleProofType <T: MarfTrieId> {
     Node48((u8, ProofTrieNode<T>, [TrieHash; 47])),
     Node256((u8, ProofTrieNode<T>, [TrieHash; 255])),
     Leaf((u8, TrieLeaf)),
-    Shunt((i64, Vec<TrieHash>))
+    Shunt((i64, Vec<TrieHash>)),
+    Terminal((ProofTrieNode<T>, Vec<TrieHash>))
 }
 
 
 define_u8_enum!( TrieMerkleProofTypeIndicator {
-    Node4 = 0, Node16 = 1, Node48 = 2, Node256 = 3, Leaf = 4, Shunt = 5
+    Node4 = 0, Node16 = 1, Node48 = 2, Node256 = 3, Leaf = 4, Shunt = 5, Terminal = 6
 });
 
 pub fn hashes_fmt(hashes: &[TrieHash]) -> String {
@@ -193,7 +194,8 @@ impl <T: MarfTrieId> fmt::Debug for TrieMerkleProofType<T> {
             TrieMerkleProofType::Node48((ref chr, ref node, ref hashes)) => write!(f, "TrieMerkleProofType::Node48(0x{:02x}, node={:?}, hashes={})", chr, node, hashes_fmt(hashes)),
             TrieMerkleProofType::Node256((ref chr, ref node, ref hashes)) => write!(f, "TrieMerkleProofType::Node256(0x{:02x}, node={:?}, hashes={})", chr, node, hashes_fmt(hashes)),
             TrieMerkleProofType::Leaf((ref chr, ref node)) => write!(f, "TrieMerkleProofType::Leaf(0x{:02x}, node={:?})", chr, node),
-            TrieMerkleProofType::Shunt((ref idx, ref hashes)) => write!(f, "TrieMerkleProofType::Shunt(idx={}, hashes={:?})", idx, hashes)
+            TrieMerkleProofType::Shunt((ref idx, ref hashes)) => write!(f, "TrieMerkleProofType::Shunt(idx={}, hashes={:?})", idx, hashes),
+            TrieMerkleProofType::Terminal((ref node, ref hashes)) => write!(f, "TrieMerkleProofType::Terminal(node={:?}, hashes={})", node, hashes_fmt(hashes))
         }
     }
 }
@@ -219,6 +221,9 @@ impl <T: MarfTrieId> PartialEq for TrieMerkleProofType<T> {
             (TrieMerkleProofType::Shunt((ref idx_1, ref hashes_1)), TrieMerkleProofType::Shunt((ref idx_2, ref hashes_2))) => {
                 idx_1 == idx_2 && hashes_1 == hashes_2
             },
+            (TrieMerkleProofType::Terminal((ref node, ref hashes)), TrieMerkleProofType::Terminal((ref other_node, ref other_hashes))) => {
+                node == other_node && hashes == other_hashes
+            },
             (_, _) => false
         }
     }
@@ -227,6 +232,44 @@ impl <T: MarfTrieId> PartialEq for TrieMerkleProofType<T> {
 #[derive(Debug)]
 pub struct TrieMerkleProof <T: MarfTrieId> (pub Vec<TrieMerkleProofType<T>>);
 
+/// Why a Merkle proof failed to verify
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProofRejection <T: MarfTrieId> {
+    /// The proof isn't an alternating sequence of segment and shunt proofs
+    Malformed(String),
+    /// The proof is for some other path
+    WrongPath,
+    /// The proof maps the path to some other value (given)
+    WrongValue(MARFValue),
+    /// The proof shows that the path is absent, rather than mapping it to a value
+    NotIncluded,
+    /// The proof doesn't show that the path is absent
+    NotExcluded,
+    /// A node's hash doesn't match the hashes of its children
+    BadHashes(String),
+    /// The proof passes through a trie whose block isn't known to the verifier.  If the proof
+    /// reaches it through a backptr, the backptr's block is given.
+    UntrustedTrie(TrieHash, Option<T>),
+    /// The proof leads to some other root hash (given)
+    WrongRoot(TrieHash)
+}
+
+impl <T: MarfTrieId> fmt::Display for ProofRejection <T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ProofRejection::Malformed(ref s) => write!(f, "malformed proof: {}", s),
+            ProofRejection::WrongPath => write!(f, "proof is for a different key"),
+            ProofRejection::WrongValue(ref value) => write!(f, "proof is for a different value (value hash {})", value.to_hex()),
+            ProofRejection::NotIncluded => write!(f, "proof shows the key is absent"),
+            ProofRejection::NotExcluded => write!(f, "proof does not show the key is absent"),
+            ProofRejection::BadHashes(ref s) => write!(f, "bad hashes: {}", s),
+            ProofRejection::UntrustedTrie(ref root_hash, Some(ref block)) => write!(f, "no trusted root hash for block {} (trie root {})", block, root_hash.to_hex()),
+            ProofRejection::UntrustedTrie(ref root_hash, None) => write!(f, "no trusted block for trie root {}", root_hash.to_hex()),
+            ProofRejection::WrongRoot(ref root_hash) => write!(f, "proof leads to root hash {}, not the trusted root hash", root_hash.to_hex())
+        }
+    }
+}
+
 impl <T: MarfTrieId> Deref for TrieMerkleProof <T> {
     type Target = Vec<TrieMerkleProofType<T>>;
     fn deref(&self) -> &Vec<TrieMerkleProofType<T>> {
@@ -297,7 +340,8 @@ impl <T: MarfTrieId> StacksMessageCodec for TrieMerkleProofType <T> {
             TrieMerkleProofType::Node48(_) => TrieMerkleProofTypeIndicator::Node48,
             TrieMerkleProofType::Node256(_) => TrieMerkleProofTypeIndicator::Node256,
             TrieMerkleProofType::Leaf(_) => TrieMerkleProofTypeIndicator::Leaf,
-            TrieMerkleProofType::Shunt(_) => TrieMerkleProofTypeIndicator::Shunt
+            TrieMerkleProofType::Shunt(_) => TrieMerkleProofTypeIndicator::Shunt,
+            TrieMerkleProofType::Terminal(_) => TrieMerkleProofTypeIndicator::Terminal
         } as u8;
 
         type_byte.consensus_serialize(fd)?;
@@ -315,6 +359,10 @@ impl <T: MarfTrieId> StacksMessageCodec for TrieMerkleProofType <T> {
                 id.consensus_serialize(fd)?;
                 hashes.consensus_serialize(fd)
             },
+            TrieMerkleProofType::Terminal((proof_node, hashes)) => {
+                proof_node.consensus_serialize(fd)?;
+                hashes.consensus_serialize(fd)
+            },
         }
     }
 
@@ -345,6 +393,11 @@ impl <T: MarfTrieId> StacksMessageCodec for TrieMerkleProofType <T> {
                 let hashes = read_next(fd)?;
                 TrieMerkleProofType::Shunt((id, hashes))
             },
+            TrieMerkleProofTypeIndicator::Terminal => {
+                let node = read_next(fd)?;
+                let hashes = read_next(fd)?;
+                TrieMerkleProofType::Terminal((node, hashes))
+            },
         };
 
         Ok(codec)
@@ -358,6 +411,16 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
         to_hex(&marf_proof)
     }
 
+    /// Decode a proof from its consensus serialization, which must be the whole of the bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<TrieMerkleProof<T>, ::net::Error> {
+        let mut cursor = bytes;
+        let proof : Vec<TrieMerkleProofType<T>> = read_next(&mut cursor)?;
+        if cursor.len() > 0 {
+            return Err(::net::Error::DeserializeError(format!("{} trailing bytes after proof", cursor.len())));
+        }
+        Ok(TrieMerkleProof(proof))
+    }
+
     fn make_proof_hashes(node: &TrieNodeType, all_hashes: &Vec<TrieHash>, chr: u8) -> Result<Vec<TrieHash>, Error> {
         let mut hashes = vec![];
         assert!(all_hashes.len() == node.ptrs().len());
@@ -459,7 +522,10 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
     ///
     /// All intermediate shunt proofs will contain all ancestor hashes for each node in-between the
     /// backptr and the non-backptr node.  The intermediate root hashes will be calculated by the verifier.
-    fn make_backptr_shunt_proof(storage: &mut TrieFileStorage<T>, backptr: &TriePtr) -> Result<Vec<TrieMerkleProofType<T>>, Error> {
+    ///
+    /// Block heights are looked up as of root_block_header, the block the whole proof is made
+    /// from, since the tries in-between may have been pruned.
+    fn make_backptr_shunt_proof(storage: &mut TrieFileStorage<T>, backptr: &TriePtr, root_block_header: &T) -> Result<Vec<TrieMerkleProofType<T>>, Error> {
         // the proof is built "backwards" -- starting from the current block all the way back to backptr.
         assert!(is_backptr(backptr.id()));
 
@@ -474,10 +540,10 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
 
         let mut found_backptr = false;
         
-        let ancestor_height = MARF::get_block_height_miner_tip(storage, &ancestor_block_hash, &block_header)?
-            .ok_or_else(|| Error::CorruptionError(format!("Could not find block height of ancestor block {} from {}", &ancestor_block_hash, &block_header)))?;
-        let mut current_height = MARF::get_block_height_miner_tip(storage, &block_header, &block_header)?
-            .ok_or_else(|| Error::CorruptionError(format!("Could not find block height of current block {} from {}", &block_header, &block_header)))?;
+        let ancestor_height = MARF::get_block_height_miner_tip(storage, &ancestor_block_hash, root_block_header)?
+            .ok_or_else(|| Error::CorruptionError(format!("Could not find block height of ancestor block {} from {}", &ancestor_block_hash, root_block_header)))?;
+        let mut current_height = MARF::get_block_height_miner_tip(storage, &block_header, root_block_header)?
+            .ok_or_else(|| Error::CorruptionError(format!("Could not find block height of current block {} from {}", &block_header, root_block_header)))?;
 
         if current_height == ancestor_height {
             debug!("Already at the ancestor: {} =? {}, heights: {} =? {}", &ancestor_block_hash, &block_header,
@@ -516,7 +582,7 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
 
             current_height -= 1u32 << idx;
 
-            block_header = MARF::get_block_at_height(storage, current_height, &block_header)?
+            block_header = MARF::get_block_at_height(storage, current_height, root_block_header)?
                 .ok_or_else(|| Error::CorruptionError(format!("Could not find block at height of {}", current_height)))?
                 .clone();
 
@@ -748,6 +814,80 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
         Ok(proof_segment)
     }
 
+    /// Given the list of non-backptr ptrs to the node at which a walk down a path stopped, calculate
+    /// a Merkle proof that the path goes no further.  The node itself is included with _all_ of its
+    /// children's hashes (or as a leaf, if the walk stopped at a leaf for some other path), and the
+    /// rest of the segment proof links it to the trie root.
+    fn make_terminal_segment_proof(storage: &mut TrieFileStorage<T>, ptrs: &Vec<TriePtr>) -> Result<Vec<TrieMerkleProofType<T>>, Error> {
+        trace!("make_terminal_segment_proof: ptrs = {:?}", &ptrs);
+
+        assert!(ptrs.len() > 0);
+        let terminal_ptr = ptrs[ptrs.len() - 1].clone();
+
+        let (node, _) = storage.read_nodetype(&terminal_ptr)?;
+        let terminal_node = match node {
+            TrieNodeType::Leaf(ref data) => {
+                TrieMerkleProofType::Leaf((terminal_ptr.chr(), data.clone()))
+            },
+            _ => {
+                let all_hashes = Trie::get_children_hashes(storage, &node)?;
+                assert!(all_hashes.len() == node.ptrs().len());
+
+                let mut hashes = Vec::with_capacity(all_hashes.len());
+                for i in 0..node.ptrs().len() {
+                    if node.ptrs()[i].id() == TrieNodeID::Empty as u8 {
+                        hashes.push(TrieHash::from_data(&[]));
+                    }
+                    else {
+                        hashes.push(all_hashes[i].clone());
+                    }
+                }
+
+                let proof_node = match node {
+                    TrieNodeType::Node4(ref data) => ProofTrieNode::try_from_trie_node(data, storage)?,
+                    TrieNodeType::Node16(ref data) => ProofTrieNode::try_from_trie_node(data, storage)?,
+                    TrieNodeType::Node48(ref data) => ProofTrieNode::try_from_trie_node(data, storage)?,
+                    TrieNodeType::Node256(ref data) => ProofTrieNode::try_from_trie_node(data, storage)?,
+                    TrieNodeType::Leaf(_) => unreachable!()
+                };
+                TrieMerkleProofType::Terminal((proof_node, hashes))
+            }
+        };
+
+        trace!("make_terminal_segment_proof: terminal proof node at {:?}: {:?}", &terminal_ptr, &terminal_node);
+
+        let mut proof_segment = vec![terminal_node];
+        if ptrs.len() > 1 {
+            let parent_ptrs = ptrs[0..ptrs.len() - 1].to_vec();
+            let mut parent_segment = TrieMerkleProof::make_segment_proof(storage, &parent_ptrs, terminal_ptr.chr())?;
+            proof_segment.append(&mut parent_segment);
+        }
+
+        Ok(proof_segment)
+    }
+
+    /// Given the node at which an exclusion proof terminates, and all of its children's hashes,
+    /// find its hash.
+    fn get_terminal_proof_hash(node: &ProofTrieNode<T>, hashes: &[TrieHash]) -> Option<TrieHash> {
+        let count = match TrieNodeID::from_u8(node.id) {
+            Some(TrieNodeID::Node4) => 4,
+            Some(TrieNodeID::Node16) => 16,
+            Some(TrieNodeID::Node48) => 48,
+            Some(TrieNodeID::Node256) => 256,
+            _ => {
+                trace!("Invalid terminal node ID 0x{:02x}", node.id);
+                return None;
+            }
+        };
+
+        if node.ptrs().len() != count || hashes.len() != count {
+            trace!("verify_get_terminal_hash: expected {} ptrs and hashes, got {} and {}", count, node.ptrs().len(), hashes.len());
+            return None;
+        }
+
+        Some(get_node_hash(node, &hashes.to_vec(), &mut ()))
+    }
+
     /// Given a node in a segment proof, find the hash
     fn get_segment_proof_hash(node: &ProofTrieNode<T>, hash: &TrieHash, chr: u8, hashes: &[TrieHash], count: usize) -> Option<TrieHash> {
         let mut all_hashes = vec![];
@@ -791,6 +931,10 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
                     //   have any child hashes to check.
                     Some(get_leaf_hash(node))
                 },
+                TrieMerkleProofType::Terminal((ref node, ref hashes)) => {
+                    // likewise, the node ending an exclusion proof carries all of its child hashes
+                    TrieMerkleProof::get_terminal_proof_hash(node, hashes)
+                },
                 TrieMerkleProofType::Node4((ref chr, ref node, ref hashes)) => {
                     TrieMerkleProof::get_segment_proof_hash(node, &hash, *chr, hashes, 4)
                 },
@@ -829,6 +973,9 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
                     // path_parts.push(vec![*chr]);
                     path_parts.push(node.path.clone());
                 },
+                TrieMerkleProofType::Terminal((ref node, _)) => {
+                    path_parts.push(node.path.clone());
+                },
                 TrieMerkleProofType::Node4((ref chr, ref node, _)) => {
                     path_parts.push(vec![*chr]);
                     path_parts.push(node.path.clone());
@@ -860,22 +1007,95 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
         Some(path)
     }
 
+    /// Given the first segment proof of an exclusion proof, determine whether or not it shows that
+    /// the walk down the expected path stops short of a leaf for that path.  This is the case if
+    /// the segment proof's path leads to the expected path up to its terminal node, and either:
+    /// * the terminal node is a leaf for some other path, or
+    /// * the terminal node's compressed path diverges from the expected path, or
+    /// * the terminal node has no child for the next byte of the expected path.
+    fn check_path_excluded(segment_proof: &[TrieMerkleProofType<T>], expected_path: &TriePath) -> Result<(), ProofRejection<T>> {
+        let path_bytes = match TrieMerkleProof::get_segment_proof_path_prefix(segment_proof) {
+            Some(bytes) => bytes,
+            None => {
+                return Err(ProofRejection::Malformed("failed to get the path from the proof".into()));
+            }
+        };
+        let expected_bytes = expected_path.as_bytes();
+
+        let terminal_path_len = match segment_proof[0] {
+            TrieMerkleProofType::Leaf((_, ref node)) => node.path.len(),
+            TrieMerkleProofType::Terminal((ref node, _)) => node.path.len(),
+            _ => {
+                return Err(ProofRejection::Malformed("first proof node is neither a leaf nor a terminal node".into()));
+            }
+        };
+
+        // the path to the terminal node must follow the expected path
+        let parent_len = path_bytes.len() - terminal_path_len;
+        if parent_len > expected_bytes.len() || path_bytes[0..parent_len] != expected_bytes[0..parent_len] {
+            trace!("Invalid proof -- path bytes {:?} do not lead along the expected path {:?}", &path_bytes, expected_path);
+            return Err(ProofRejection::WrongPath);
+        }
+
+        match segment_proof[0] {
+            TrieMerkleProofType::Leaf(_) => {
+                if path_bytes.len() != expected_bytes.len() || path_bytes[..] == expected_bytes[..] {
+                    trace!("Invalid proof -- leaf path {:?} does not exclude {:?}", &path_bytes, expected_path);
+                    return Err(ProofRejection::NotExcluded);
+                }
+                Ok(())
+            },
+            TrieMerkleProofType::Terminal((ref node, _)) => {
+                if !expected_bytes[parent_len..].starts_with(&node.path) {
+                    // path diverged
+                    return Ok(());
+                }
+
+                let chr_index = parent_len + node.path.len();
+                if chr_index >= expected_bytes.len() {
+                    return Err(ProofRejection::Malformed("expected path ends at a non-leaf node".into()));
+                }
+
+                let chr = expected_bytes[chr_index];
+                for child_ptr in node.ptrs() {
+                    if child_ptr.id != TrieNodeID::Empty as u8 && child_ptr.chr == chr {
+                        trace!("Invalid proof -- terminal node has a child for 0x{:02x}", chr);
+                        return Err(ProofRejection::NotExcluded);
+                    }
+                }
+                Ok(())
+            },
+            _ => unreachable!()
+        }
+    }
+
     /// Verify that a proof is well-formed:
     /// * it must have the same number of segment and shunt proofs
     /// * segment proof i+1 must be a prefix of segment proof i
-    /// * segment proof 0 must end in a leaf
+    /// * segment proof 0 must end in a leaf (or, for an exclusion proof, in a leaf or terminal
+    /// node that shows the expected path is absent)
     /// * all segment proofs must end in a Node256 (a root)
-    fn is_proof_well_formed(proof: &Vec<TrieMerkleProofType<T>>, expected_path: &TriePath) -> bool {
+    fn check_proof_well_formed(proof: &Vec<TrieMerkleProofType<T>>, expected_path: &TriePath, exclusion: bool) -> Result<(), ProofRejection<T>> {
         if proof.len() == 0 {
-            trace!("Proof is empty");
-            return false;
+            return Err(ProofRejection::Malformed("proof is empty".into()));
         }
 
         match proof[0] {
             TrieMerkleProofType::Leaf(_) => {},
+            TrieMerkleProofType::Terminal(_) if exclusion => {},
+            TrieMerkleProofType::Terminal(_) => {
+                // an exclusion proof was given where an inclusion proof was expected
+                return Err(ProofRejection::NotIncluded);
+            },
             _ => {
-                trace!("First proof node is not a leaf");
-                return false;
+                return Err(ProofRejection::Malformed("first proof node is not a leaf".into()));
+            }
+        }
+
+        // only the first proof node can be a terminal node
+        for proof_node in proof[1..].iter() {
+            if let TrieMerkleProofType::Terminal(_) = proof_node {
+                return Err(ProofRejection::Malformed("terminal proof node is not the first proof node".into()));
             }
         }
 
@@ -899,22 +1119,27 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
 
             let segment_proof = &proof[i..j];
             
-            if i == 0 {
+            if i == 0 && exclusion {
+                TrieMerkleProof::check_path_excluded(segment_proof, expected_path)?;
+
+                // later segment proofs must be prefixes of the expected path
+                path_bytes = expected_path.as_bytes().to_vec();
+            }
+            else if i == 0 {
                 // detect the path
                 path_bytes = match TrieMerkleProof::get_segment_proof_path_prefix(segment_proof) {
                     Some(bytes) => {
                         bytes
                     },
                     None => {
-                        trace!("Failed to get the path from the proof");
-                        return false;
+                        return Err(ProofRejection::Malformed("failed to get the path from the proof".into()));
                     }
                 };
                 
                 // first path bytes must be the expected TriePath
                 if expected_path.as_bytes().to_vec() != path_bytes {
                     trace!("Invalid proof -- path bytes {:?} differs from the expected path {:?}", &path_bytes, expected_path);
-                    return false;
+                    return Err(ProofRejection::WrongPath);
                 }
             }
             else {
@@ -924,22 +1149,20 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
                         bytes
                     },
                     None => {
-                        trace!("Failed to et the path prefix from the proof");
-                        return false;
+                        return Err(ProofRejection::Malformed("failed to get the path prefix from the proof".into()));
                     }
                 };
 
                 if path_bytes.len() < new_path_bytes.len() {
-                    trace!("Segment proof path is {}, which is longer than the previous segment proof length {}", path_bytes.len(), new_path_bytes.len());
                     trace!("path_bytes: {:?}", &path_bytes);
                     trace!("new path bytes: {:?}", &new_path_bytes);
-                    return false;
+                    return Err(ProofRejection::Malformed(format!("segment proof path is {}, which is longer than the previous segment proof length {}", new_path_bytes.len(), path_bytes.len())));
                 }
 
                 for i in 0..new_path_bytes.len() {
                     if path_bytes[i] != new_path_bytes[i] {
                         trace!("Segment path {:?} is not a prefix of previous segment path {:?}", &new_path_bytes, &path_bytes);
-                        return false;
+                        return Err(ProofRejection::Malformed("segment path is not a prefix of the previous segment path".into()));
                     }
                 }
             }
@@ -947,8 +1170,7 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
             // next shunt proof 
             i = j;
             if i >= proof.len() {
-                trace!("Proof is incomplete -- must end with a shunt proof");
-                return false;
+                return Err(ProofRejection::Malformed("proof is incomplete -- must end with a shunt proof".into()));
             }
 
             j = i + 1;
@@ -967,7 +1189,7 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
             i = j;
         }
 
-        true
+        Ok(())
     }
 
 
@@ -978,11 +1200,20 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
     /// headers.
     /// NOTE: Trie root hashes are globally unique by design, even if they represent the same contents, so the root_to_block map is bijective with high probability.
     pub fn verify_proof(proof: &Vec<TrieMerkleProofType<T>>, path: &TriePath, value: &MARFValue, root_hash: &TrieHash, root_to_block: &HashMap<TrieHash, T>) -> bool {
-        if !TrieMerkleProof::is_proof_well_formed(&proof, path) {
-            return false;
+        match TrieMerkleProof::check_proof(proof, path, value, root_hash, root_to_block) {
+            Ok(()) => true,
+            Err(rejection) => {
+                trace!("Invalid proof -- {}", &rejection);
+                false
+            }
         }
+    }
+
+    /// Like verify_proof(), but say why the proof is invalid if it is.
+    pub fn check_proof(proof: &Vec<TrieMerkleProofType<T>>, path: &TriePath, value: &MARFValue, root_hash: &TrieHash, root_to_block: &HashMap<TrieHash, T>) -> Result<(), ProofRejection<T>> {
+        TrieMerkleProof::check_proof_well_formed(&proof, path, false)?;
 
-        let (mut node_hash, node_data) = match proof[0] {
+        let (node_hash, node_data) = match proof[0] {
             TrieMerkleProofType::Leaf((_, ref node)) => {
                 (get_leaf_hash(node), node.data.clone())
             },
@@ -994,9 +1225,50 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
         // proof must be for this value
         if node_data != *value {
             trace!("Invalid proof -- not for value hash {:?}", value.to_value_hash());
-            return false;
+            return Err(ProofRejection::WrongValue(node_data));
         }
 
+        TrieMerkleProof::check_proof_hashes(proof, node_hash, root_hash, root_to_block)
+    }
+
+    /// Given a segment proof's first (deepest) node, find the block its backptr for the segment's
+    /// path points to, if it has one.
+    fn get_segment_proof_backptr_block(proof_node: &TrieMerkleProofType<T>) -> Option<T> {
+        let (chr, node) = match proof_node {
+            TrieMerkleProofType::Node4((ref chr, ref node, _)) => (chr, node),
+            TrieMerkleProofType::Node16((ref chr, ref node, _)) => (chr, node),
+            TrieMerkleProofType::Node48((ref chr, ref node, _)) => (chr, node),
+            TrieMerkleProofType::Node256((ref chr, ref node, _)) => (chr, node),
+            _ => {
+                return None;
+            }
+        };
+        node.ptrs().iter()
+            .find(|ptr| is_backptr(ptr.id) && ptr.chr == *chr)
+            .map(|ptr| ptr.back_block.clone())
+    }
+
+    /// Find the block whose trie has the given root hash, where proof_node is the first node of
+    /// the segment proof that follows a backptr out of that trie.  The trie must be in
+    /// root_to_block: the newer trie's backptr only commits to a block hash, and the shunt proof
+    /// only commits to the trie being one of the newer trie's ancestors, so nothing in the proof
+    /// itself ties the trie to the backptr's block.  The backptr's block is reported if the trie
+    /// isn't known, so the verifier can say which block's root hash it needs.
+    fn get_trie_block(trie_hash: &TrieHash, proof_node: &TrieMerkleProofType<T>, root_to_block: &HashMap<TrieHash, T>) -> Result<T, ProofRejection<T>> {
+        if let Some(bhh) = root_to_block.get(trie_hash) {
+            trace!("Block hash for {:?} is {:?}", trie_hash, bhh);
+            return Ok(bhh.clone());
+        }
+
+        let backptr_block = TrieMerkleProof::get_segment_proof_backptr_block(proof_node);
+        trace!("Trie hash not found in root-to-block map: {:?}", trie_hash);
+        trace!("root-to-block map: {:?}", root_to_block);
+        Err(ProofRejection::UntrustedTrie(trie_hash.clone(), backptr_block))
+    }
+
+    /// Given a well-formed proof and the hash of the node its first segment proof starts at,
+    /// verify that the proof's segment and shunt proofs link that node to the root hash.
+    fn check_proof_hashes(proof: &Vec<TrieMerkleProofType<T>>, mut node_hash: TrieHash, root_hash: &TrieHash, root_to_block: &HashMap<TrieHash, T>) -> Result<(), ProofRejection<T>> {
         let mut i = 0;
 
         // verify the very first segment proof 
@@ -1018,14 +1290,13 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
                 h
             },
             None => {
-                return false;
+                return Err(ProofRejection::BadHashes(format!("invalid segment proof in range {}..{}", i, j)));
             }
         };
         
         i = j;
         if i >= proof.len() {
-            trace!("Proof is too short -- needed at least one shunt proof for the first segment");
-            return false;
+            return Err(ProofRejection::Malformed("proof is too short -- needed at least one shunt proof for the first segment".into()));
         }
 
         // verify the very first shunt proof head.
@@ -1035,7 +1306,7 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
                 h
             },
             None => {
-                return false;
+                return Err(ProofRejection::BadHashes(format!("invalid shunt proof head at {}", i)));
             }
         };
         trace!("shunt proof head hash: {:?}", &trie_hash);
@@ -1043,33 +1314,22 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
         i += 1;
         if i >= proof.len() {
             // done -- no further shunts
-            return true;
+            trace!("Verify proof: {:?} =?= {:?}", root_hash, &trie_hash);
+            return TrieMerkleProof::check_root_hash(root_hash, &trie_hash);
         }
 
-        // next node hash is the hash of the block from which its root came
-        node_hash = match root_to_block.get(&trie_hash) {
-            Some(bhh) => {
-                trace!("Block hash for {:?} is {:?}", &trie_hash, bhh);
-
-                // safe because block header hashes are 32 bytes long
-                TrieHash(bhh.clone().to_bytes())
-            },
-            None => {
-                trace!("Trie hash not found in root-to-block map: {:?}", &trie_hash);
-                trace!("root-to-block map: {:?}", &root_to_block);
-                return false;
-            }
-        };
-
         // next proof item should be part of a segment proof
         match proof[i] {
             TrieMerkleProofType::Shunt(_) => {
-                trace!("Malformed proof -- exepcted segment proof following first shunt proof head at {}", i);
-                return false;
+                return Err(ProofRejection::Malformed(format!("expected segment proof following first shunt proof head at {}", i)));
             },
             _ => {}
         }
 
+        // next node hash is the hash of the block from which its root came
+        // (safe because block header hashes are 32 bytes long)
+        node_hash = TrieHash(TrieMerkleProof::get_trie_block(&trie_hash, &proof[i], root_to_block)?.to_bytes());
+
         while i < proof.len() {
             // find the next segment proof
             j = i + 1;
@@ -1090,14 +1350,13 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
                     h
                 },
                 None => {
-                    return false;
+                    return Err(ProofRejection::BadHashes(format!("invalid segment proof in range {}..{}", i, j)));
                 }
             };
 
             i = j;
             if i >= proof.len() {
-                trace!("Proof to short -- no shunt proof tail");
-                return false;
+                return Err(ProofRejection::Malformed("proof is too short -- no shunt proof tail".into()));
             }
 
             // find the tail end
@@ -1118,8 +1377,7 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
             j -= 1;
 
             if j < i {
-                trace!("Proof is malformed -- no tail or junction proof");
-                return false;
+                return Err(ProofRejection::Malformed("no shunt proof tail or junction".into()));
             }
 
             trace!("verify shunt proof tail in range {}..{} initial hash = {:?}: {:?}", i, j, &trie_hash, &proof[i..j]);
@@ -1128,15 +1386,14 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
                     h
                 },
                 None => {
-                    return false;
+                    return Err(ProofRejection::BadHashes(format!("invalid shunt proof tail in range {}..{}", i, j)));
                 }
             };
             trace!("verify shunt proof tail in range {}..{}: penultimate trie hash is {:?}", i, j, &penultimate_trie_hash);
 
             i = j;
             if i >= proof.len() {
-                trace!("Proof to short -- no junction proof");
-                return false;
+                return Err(ProofRejection::Malformed("proof is too short -- no shunt proof junction".into()));
             }
 
             trace!("verify shunt junction proof at {} next_node_root_hash = {:?} penultimate hash = {:?}: {:?}", i, &next_node_root_hash, &penultimate_trie_hash, &proof[i]);
@@ -1145,36 +1402,39 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
                     h
                 },
                 None => {
-                    return false;
+                    return Err(ProofRejection::BadHashes(format!("invalid shunt proof junction at {}", i)));
                 }
             };
             
-            // next node hash is the hash of the block from which its root came
             trie_hash = next_trie_hash;
-            node_hash = match root_to_block.get(&trie_hash) {
-                Some(bhh) => {
-                    trace!("Block hash for {:?} is {:?}", &trie_hash, bhh);
-
-                    // safe because block header hashes are 32 bytes long
-                    TrieHash(bhh.clone().to_bytes())
-                },
-                None => {
-                    trace!("Trie hash not found in root-to-block map: {:?}", &trie_hash);
-                    trace!("root-to-block map: {:?}", &root_to_block);
-                    return false;
-                }
-            };
-
             i += 1;
 
             if trie_hash == *root_hash {
                 trace!("Appeared to find the root hash early, with the remaining proof:\n{:?}", &proof[i..]);
                 break;
             }
+
+            if i >= proof.len() {
+                break;
+            }
+
+            // next node hash is the hash of the block from which its root came
+            // (safe because block header hashes are 32 bytes long)
+            node_hash = TrieHash(TrieMerkleProof::get_trie_block(&trie_hash, &proof[i], root_to_block)?.to_bytes());
         }
 
         trace!("Verify proof: {:?} =?= {:?}", root_hash, &trie_hash);
-        *root_hash == trie_hash
+        TrieMerkleProof::check_root_hash(root_hash, &trie_hash)
+    }
+
+    /// Check that the root hash a proof leads to is the expected one
+    fn check_root_hash(root_hash: &TrieHash, trie_hash: &TrieHash) -> Result<(), ProofRejection<T>> {
+        if *root_hash == *trie_hash {
+            Ok(())
+        }
+        else {
+            Err(ProofRejection::WrongRoot(trie_hash.clone()))
+        }
     }
 
     /// Verify this proof
@@ -1182,8 +1442,64 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
         TrieMerkleProof::<T>::verify_proof(&self.0, &path, &marf_value, root_hash, root_to_block)
     }
 
+    /// Given the root hash from which this exclusion proof was (supposedly) generated, verify
+    /// that it shows the path is absent from the trie with that root hash.  As with inclusion
+    /// proofs, the verifier needs to know which Trie roots correspond to which block headers.
+    pub fn verify_exclusion_proof(proof: &Vec<TrieMerkleProofType<T>>, path: &TriePath, root_hash: &TrieHash, root_to_block: &HashMap<TrieHash, T>) -> bool {
+        match TrieMerkleProof::check_exclusion_proof(proof, path, root_hash, root_to_block) {
+            Ok(()) => true,
+            Err(rejection) => {
+                trace!("Invalid exclusion proof -- {}", &rejection);
+                false
+            }
+        }
+    }
+
+    /// Like verify_exclusion_proof(), but say why the proof is invalid if it is.
+    pub fn check_exclusion_proof(proof: &Vec<TrieMerkleProofType<T>>, path: &TriePath, root_hash: &TrieHash, root_to_block: &HashMap<TrieHash, T>) -> Result<(), ProofRejection<T>> {
+        TrieMerkleProof::check_proof_well_formed(&proof, path, true)?;
+
+        let node_hash = match proof[0] {
+            TrieMerkleProofType::Leaf((_, ref node)) => {
+                get_leaf_hash(node)
+            },
+            TrieMerkleProofType::Terminal((ref node, ref hashes)) => {
+                match TrieMerkleProof::get_terminal_proof_hash(node, hashes) {
+                    Some(h) => h,
+                    None => {
+                        return Err(ProofRejection::BadHashes("invalid terminal node".into()));
+                    }
+                }
+            },
+            _ => {
+                unreachable!()
+            }
+        };
+
+        TrieMerkleProof::check_proof_hashes(proof, node_hash, root_hash, root_to_block)
+    }
+
+    /// Verify this proof as an exclusion proof
+    pub fn verify_exclusion(&self, path: &TriePath, root_hash: &TrieHash, root_to_block: &HashMap<TrieHash, T>) -> bool {
+        TrieMerkleProof::<T>::verify_exclusion_proof(&self.0, &path, root_hash, root_to_block)
+    }
+
     /// Walk down the trie pointed to by s until we reach a backptr or a leaf
     fn walk_to_leaf_or_backptr(storage: &mut TrieFileStorage<T>, path: &TriePath) -> Result<(TrieCursor<T>, TrieNodeType, TriePtr), Error> {
+        let (cursor, node, ptr) = TrieMerkleProof::walk_to_end_or_backptr(storage, path)?;
+        match cursor.last_error {
+            Some(CursorError::PathDiverged) | Some(CursorError::ChrNotFound) => {
+                // we're done -- the path isn't present.  No backptr-walking can help us.
+                Err(Error::NotFoundError)
+            },
+            _ => Ok((cursor, node, ptr))
+        }
+    }
+
+    /// Walk down the trie pointed to by s until we reach a backptr, a leaf, or the node at which
+    /// the path diverges or has no child.  In the last case, the cursor's last_error says why the
+    /// walk stopped, and the returned node and ptr are the node at which it stopped.
+    fn walk_to_end_or_backptr(storage: &mut TrieFileStorage<T>, path: &TriePath) -> Result<(TrieCursor<T>, TrieNodeType, TriePtr), Error> {
         trace!("Walk path {:?} from {:?} to the first backptr", path, &storage.get_cur_block());
         
         let mut node_ptr = storage.root_trieptr();
@@ -1213,14 +1529,14 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
                         Error::CursorError(cursor_error) => {
                             match cursor_error {
                                 CursorError::PathDiverged => {
-                                    // we're done -- path diverged.  No backptr-walking can help us.
+                                    // we're done -- path diverged.
                                     trace!("Path diverged -- we're done.");
-                                    return Err(Error::NotFoundError);
+                                    return Ok((cursor, node, node_ptr));
                                 },
                                 CursorError::ChrNotFound => {
                                     // node isn't present
                                     trace!("Failed to walk from {:?}", &node);
-                                    return Err(Error::NotFoundError);
+                                    return Ok((cursor, node, node_ptr));
                                 },
                                 CursorError::BackptrEncountered(ptr) => {
                                     // expect backptr
@@ -1272,7 +1588,7 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
 
             if is_backptr(backptr.id()) {
                 // make the shunt proof connecting this block to the next block we'll visit.
-                let shunt_proof = TrieMerkleProof::make_backptr_shunt_proof(storage, &backptr)?;
+                let shunt_proof = TrieMerkleProof::make_backptr_shunt_proof(storage, &backptr, root_block_header)?;
                 shunt_proofs.push(shunt_proof);
             }
             else {
@@ -1342,6 +1658,78 @@ impl <T: MarfTrieId> TrieMerkleProof <T> {
         let path = TriePath::from_key(key);
         TrieMerkleProof::from_path(storage, &path, value, root_block_header)
     }
+
+    /// Make a merkle proof of exclusion from a path -- i.e. a proof that the walk down the path
+    /// as of the given block stops at a node whose child slot for the path is empty, at a node
+    /// whose compressed path diverges from it, or at a leaf for some other path.
+    /// If the path resolves, return an error (ExistsError)
+    pub fn from_absent_path(storage: &mut TrieFileStorage<T>, path: &TriePath, root_block_header: &T) -> Result<TrieMerkleProof<T>, Error> {
+        // as with inclusion proofs, accumulate proofs in reverse order
+        let mut segment_proofs = vec![];
+        let mut shunt_proofs = vec![];
+        let mut block_header = root_block_header.clone();
+
+        loop {
+            storage.open_block(&block_header)?;
+
+            trace!("Walk {:?} path {:?} to its end or a backptr", &storage.get_cur_block(), path);
+            let (cursor, _, backptr) = TrieMerkleProof::walk_to_end_or_backptr(storage, path)?;
+
+            match cursor.last_error {
+                Some(CursorError::BackptrEncountered(_)) => {
+                    // make a proof to the node with the backptr, and a shunt proof connecting
+                    // this block to the next block we'll visit.
+                    trace!("Make segment proof at {:?} from {:?}", &storage.get_cur_block(), &cursor.node_ptrs);
+                    let segment_proof = TrieMerkleProof::make_segment_proof(storage, &cursor.node_ptrs, cursor.chr().unwrap())?;
+                    segment_proofs.push(segment_proof);
+
+                    trace!("Make shunt proof {:?} back to the block containing {:?}", &storage.get_cur_block(), &backptr);
+                    let shunt_proof = TrieMerkleProof::make_backptr_shunt_proof(storage, &backptr, root_block_header)?;
+                    shunt_proofs.push(shunt_proof);
+                },
+                Some(CursorError::PathDiverged) | Some(CursorError::ChrNotFound) => {
+                    // make a proof to the node at which the path stops
+                    trace!("Make terminal segment proof at {:?} from {:?}", &storage.get_cur_block(), &cursor.node_ptrs);
+                    let segment_proof = TrieMerkleProof::make_terminal_segment_proof(storage, &cursor.node_ptrs)?;
+                    segment_proofs.push(segment_proof);
+
+                    let first_shunt_proof = TrieMerkleProof::make_initial_shunt_proof(storage)?;
+                    shunt_proofs.push(first_shunt_proof);
+                    break;
+                },
+                None => {
+                    trace!("Path {:?} is present in {:?}", path, &storage.get_cur_block());
+                    return Err(Error::ExistsError);
+                }
+            }
+
+            storage.open_block(&block_header)?;
+
+            trace!("Walk back for {:?} from {:?}", &backptr, &storage.get_cur_block());
+            block_header = storage.get_block_from_local_id(backptr.back_block())?.clone();
+        }
+
+        assert_eq!(shunt_proofs.len(), segment_proofs.len());
+
+        // terminal node proof needs to be first
+        segment_proofs.reverse();
+        shunt_proofs.reverse();
+
+        let mut proof = Vec::with_capacity(segment_proofs.len() + shunt_proofs.len());
+        for i in 0..shunt_proofs.len() {
+            proof.append(&mut segment_proofs[i]);
+            proof.append(&mut shunt_proofs[i]);
+        }
+
+        Ok(TrieMerkleProof(proof))
+    }
+
+    /// Make a merkle proof of exclusion from a key.
+    /// If the key is present, return an error (ExistsError)
+    pub fn from_absent_entry(storage: &mut TrieFileStorage<T>, key: &str, root_block_header: &T) -> Result<TrieMerkleProof<T>, Error> {
+        let path = TriePath::from_key(key);
+        TrieMerkleProof::from_absent_path(storage, &path, root_block_header)
+    }
 }
 
 #[cfg(test)]
@@ -1350,6 +1738,7 @@ mod test {
     use chainstate::stacks::index::test::*;
     use chainstate::stacks::index::*;
     use chainstate::stacks::index::marf::*;
+    use util::hash::hex_bytes;
 
     #[test]
     fn verifier_catches_stale_proof() {
@@ -1412,4 +1801,59 @@ mod test {
         let marf_value_1 = MARFValue::from_value(&old_v);
         assert!(proof_1.verify(&triepath_1, &marf_value_1, &root_hash_1, &root_to_block));
     }
+
+    #[test]
+    fn verify_exclusion_proofs() {
+        let mut m = MARF::from_path(":memory:").unwrap();
+
+        let sentinel_block = TrieFileStorage::block_sentinel();
+        let blocks : Vec<_> = (0..4).map(|i| BlockHeaderHash([i as u8; 32])).collect();
+
+        // each block adds a few keys, so the later tries reach most paths through backptrs
+        let mut parent = sentinel_block;
+        for (i, block) in blocks.iter().enumerate() {
+            m.begin(&parent, block).unwrap();
+            for j in 0..8 {
+                m.insert(&format!("K{}-{}", i, j), MARFValue::from_value(&format!("V{}-{}", i, j))).unwrap();
+            }
+            m.commit().unwrap();
+            parent = block.clone();
+        }
+
+        let root_to_block = m.borrow_storage_backend().read_root_to_block_table().unwrap();
+        let root_hashes : Vec<_> = blocks.iter().map(|block| m.get_root_hash_at(block).unwrap()).collect();
+
+        let missing_path = TriePath::from_key("missing");
+        let present_path = TriePath::from_key("K0-0");
+        for i in 0..blocks.len() {
+            let proof = m.get_exclusion_proof(&blocks[i], "missing").unwrap().unwrap();
+            assert!(proof.verify_exclusion(&missing_path, &root_hashes[i], &root_to_block));
+
+            // the proof survives a round trip through its encoding
+            let bytes = hex_bytes(&proof.to_hex()).unwrap();
+            let proof_nodes : Vec<TrieMerkleProofType<BlockHeaderHash>> = read_next(&mut &bytes[..]).unwrap();
+            assert!(TrieMerkleProof::verify_exclusion_proof(&proof_nodes, &missing_path, &root_hashes[i], &root_to_block));
+
+            // it only proves that this path is absent, and only as of this block
+            assert!(!proof.verify_exclusion(&present_path, &root_hashes[i], &root_to_block));
+            assert!(!proof.verify_exclusion(&missing_path, &root_hashes[(i + 1) % blocks.len()], &root_to_block));
+        }
+
+        // keys that are present have no exclusion proof
+        assert!(m.get_exclusion_proof(&blocks[3], "K0-0").unwrap().is_none());
+        match TrieMerkleProof::from_absent_entry(m.borrow_storage_backend(), "K0-0", &blocks[3]) {
+            Err(Error::ExistsError) => {},
+            x => panic!("Expected ExistsError, got {:?}", x)
+        }
+
+        // ...but are absent before they're inserted
+        let later_path = TriePath::from_key("K3-0");
+        let proof = m.get_exclusion_proof(&blocks[1], "K3-0").unwrap().unwrap();
+        assert!(proof.verify_exclusion(&later_path, &root_hashes[1], &root_to_block));
+        assert!(!proof.verify_exclusion(&later_path, &root_hashes[3], &root_to_block));
+
+        // an inclusion proof isn't an exclusion proof
+        let proof = m.get_with_proof(&blocks[3], "K0-0").unwrap().unwrap().1;
+        assert!(!proof.verify_exclusion(&present_path, &root_hashes[3], &root_to_block));
+    }
 }
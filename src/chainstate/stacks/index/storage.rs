@@ -32,7 +32,7 @@ use std::io::{
 use std::char::from_digit;
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
-use std::collections::{VecDeque, HashMap, HashSet};
+use std::collections::{VecDeque, HashMap, HashSet, BTreeMap};
 
 use std::fs;
 use std::path::{
@@ -601,6 +601,9 @@ pub struct TrieFileStorage <T: MarfTrieId> {
     pub readonly: bool,
     pub unconfirmed: bool,
 
+    /// whether or not some of this MARF's tries have been pruned (see prune_tries())
+    pruned: bool,
+
     // used in testing in order to short-circuit block-height lookups
     //   when the trie struct is tested outside of marf.rs usage
     #[cfg(test)]
@@ -658,6 +661,8 @@ impl <T: MarfTrieId> TrieFileStorage <T> {
             trie_sql::create_tables_if_needed(&mut db)?;
         }
 
+        let pruned = trie_sql::has_pruned_tries(&db)?;
+
         debug!("Opened TrieFileStorage {};", db_path);
 
         let ret = TrieFileStorage {
@@ -682,6 +687,7 @@ impl <T: MarfTrieId> TrieFileStorage <T> {
   
             readonly: readonly,
             unconfirmed: unconfirmed,
+            pruned: pruned,
 
             // used in testing in order to short-circuit block-height lookups
             //   when the trie struct is tested outside of marf.rs usage
@@ -733,6 +739,7 @@ impl <T: MarfTrieId> TrieFileStorage <T> {
   
             readonly: true,
             unconfirmed: true,
+            pruned: self.pruned,
             
             // used in testing in order to short-circuit block-height lookups
             //   when the trie struct is tested outside of marf.rs usage
@@ -939,6 +946,343 @@ impl <T: MarfTrieId> TrieFileStorage <T> {
         Ok(created)
     }
 
+    /// Read a confirmed block's trie as it is stored, along with its local block identifier.
+    pub fn read_trie_blob(&self, bhh: &T) -> Result<(u32, Vec<u8>), Error> {
+        trie_sql::read_confirmed_trie_blob(&self.db, bhh)?
+            .ok_or(Error::NotFoundError)
+    }
+
+    /// Store a confirmed block's trie, as read by read_trie_blob() from another MARF, under its
+    /// original local block identifier (so the back-pointers of this and later tries still
+    /// resolve).  The trie's ancestors must be stored the same way.  If the trie was pruned in the
+    /// other MARF, it's recorded as pruned here too.
+    pub fn write_trie_blob_with_id(&mut self, block_id: u32, bhh: &T, data: &[u8], pruned: bool) -> Result<(), Error> {
+        if self.readonly {
+            return Err(Error::ReadOnlyError);
+        }
+        if self.unconfirmed {
+            return Err(Error::UnconfirmedError);
+        }
+        if trie_sql::get_block_identifier(&self.db, bhh).is_ok() {
+            warn!("Block already exists: {}", &bhh);
+            return Err(Error::ExistsError);
+        }
+
+        trie_sql::write_trie_blob_with_id(&self.db, block_id, bhh, data)?;
+        if pruned {
+            trie_sql::set_block_pruned(&self.db, block_id, bhh)?;
+            self.pruned = true;
+        }
+        Ok(())
+    }
+
+    pub fn read_all_block_ids(&self) -> Result<Vec<(u32, T, bool)>, Error> {
+        trie_sql::read_all_block_ids(&self.db)
+    }
+
+    /// Has this block's trie been pruned?  Always false if this MARF was never pruned.
+    pub fn is_block_pruned(&self, bhh: &T) -> Result<bool, Error> {
+        if !self.pruned {
+            return Ok(false);
+        }
+        trie_sql::is_block_pruned(&self.db, bhh)
+    }
+
+    /// Prune the given blocks' tries.  A pruned trie keeps only:
+    /// * the nodes that the retained tries can still reach through back-pointers (and their
+    /// subtrees), since the retained tries' state still includes them;
+    /// * the given nodes (without their subtrees), e.g. the path to a key that must stay
+    /// readable;
+    /// * its root node, and the path from it to each of the above, since its root hash is part
+    /// of its descendants' root hashes, and proofs of the retained state pass through it;
+    /// * the children of the nodes on those paths, but only for their hashes.
+    /// So the trie's remaining nodes can still be hash-checked from its root.
+    /// The remaining nodes are compacted, and every pointer to them is updated to match.  Pointers
+    /// to nodes that are gone are set to 0, i.e. the trie's header, where they won't decode as a
+    /// node.  Node hashes do not cover node offsets, so no hash changes.  Pruned tries are
+    /// recorded as such, so the MARF can refuse to read them.
+    /// retained has the local block IDs of every trie that isn't pruned, pruned has the local
+    /// block IDs and hashes of the tries to prune, and keep has the local block IDs and pointers
+    /// of the extra nodes to keep.  Tries that were pruned before are in neither, and are left as
+    /// they are.
+    pub fn prune_tries(&mut self, retained: &[u32], pruned: &[(u32, T)], keep: &[(u32, TriePtr)]) -> Result<(), Error> {
+        if self.readonly {
+            return Err(Error::ReadOnlyError);
+        }
+        if self.unconfirmed {
+            return Err(Error::UnconfirmedError);
+        }
+        if self.last_extended.is_some() {
+            return Err(Error::InProgressError);
+        }
+
+        let root_ptr = TrieFileStorage::<T>::root_ptr_disk();
+        let pruned_ids : HashSet<u32> = pruned.iter().map(|(block_id, _)| *block_id).collect();
+
+        // offsets and node IDs of the nodes to keep in each pruned trie
+        let mut kept : HashMap<u32, BTreeMap<u32, u8>> = HashMap::new();
+        for (block_id, _) in pruned.iter() {
+            kept.entry(*block_id).or_insert_with(BTreeMap::new)
+                .insert(root_ptr, TrieNodeID::Node256 as u8);
+        }
+        for (block_id, ptr) in keep.iter() {
+            if let Some(block_kept) = kept.get_mut(block_id) {
+                block_kept.insert(ptr.ptr(), clear_backptr(ptr.id()));
+            }
+        }
+
+        // walk each retained trie, and find the nodes that point into pruned tries
+        let mut frontier : Vec<(u32, TriePtr)> = vec![];
+        let mut retained_updates : Vec<(u32, Vec<(u32, TrieNodeType, TrieHash)>)> = vec![];
+        for block_id in retained.iter() {
+            let blob = trie_sql::read_trie_blob_by_id(&self.db, *block_id)?;
+            let mut updates = vec![];
+            let mut ptrs = vec![TriePtr::new(TrieNodeID::Node256 as u8, 0, root_ptr)];
+
+            while let Some(ptr) = ptrs.pop() {
+                let (node, hash) = read_nodetype(&mut Cursor::new(&blob), &ptr)?;
+                let mut points_to_pruned = false;
+                for child_ptr in node.ptrs().iter() {
+                    if child_ptr.id() == TrieNodeID::Empty as u8 {
+                        continue;
+                    }
+                    if !is_backptr(child_ptr.id()) {
+                        ptrs.push(child_ptr.clone());
+                    }
+                    else if pruned_ids.contains(&child_ptr.back_block()) {
+                        frontier.push((child_ptr.back_block(), child_ptr.from_backptr()));
+                        points_to_pruned = true;
+                    }
+                }
+                if points_to_pruned {
+                    updates.push((ptr.ptr(), node, hash));
+                }
+            }
+            if updates.len() > 0 {
+                retained_updates.push((*block_id, updates));
+            }
+        }
+
+        // everything reachable from those nodes stays
+        let mut expanded = HashSet::new();
+        while let Some((block_id, ptr)) = frontier.pop() {
+            if !expanded.insert((block_id, ptr.ptr())) {
+                continue;
+            }
+            let (node, _) = trie_sql::read_node_type(&self.db, block_id, &ptr)?;
+            kept.get_mut(&block_id)
+                .expect("BUG: frontier node is not in a pruned trie")
+                .insert(ptr.ptr(), ptr.id());
+
+            for child_ptr in node.ptrs().iter() {
+                if child_ptr.id() == TrieNodeID::Empty as u8 {
+                    continue;
+                }
+                if !is_backptr(child_ptr.id()) {
+                    frontier.push((block_id, child_ptr.clone()));
+                }
+                else if pruned_ids.contains(&child_ptr.back_block()) {
+                    frontier.push((child_ptr.back_block(), child_ptr.from_backptr()));
+                }
+                // (otherwise, it points into a trie that was pruned before, which stays as-is)
+            }
+        }
+
+        // keep the path from each pruned trie's root to each of its kept nodes, and the children
+        // of the nodes on those paths (for their hashes only)
+        for (block_id, _) in pruned.iter() {
+            let blob = trie_sql::read_trie_blob_by_id(&self.db, *block_id)?;
+
+            // find each node's parent, and each node's children in this trie
+            let mut parents : HashMap<u32, (u32, u8)> = HashMap::new();
+            let mut children : HashMap<u32, Vec<TriePtr>> = HashMap::new();
+            let mut ptrs = vec![TriePtr::new(TrieNodeID::Node256 as u8, 0, root_ptr)];
+            while let Some(ptr) = ptrs.pop() {
+                let (node, _) = read_nodetype(&mut Cursor::new(&blob), &ptr)?;
+                if node.is_leaf() {
+                    continue;
+                }
+                let mut node_children = vec![];
+                for child_ptr in node.ptrs().iter() {
+                    if child_ptr.id() == TrieNodeID::Empty as u8 || is_backptr(child_ptr.id()) {
+                        continue;
+                    }
+                    parents.insert(child_ptr.ptr(), (ptr.ptr(), ptr.id()));
+                    node_children.push(child_ptr.clone());
+                    ptrs.push(child_ptr.clone());
+                }
+                children.insert(ptr.ptr(), node_children);
+            }
+
+            let block_kept = kept.get_mut(block_id).expect("BUG: pruned trie has no kept nodes");
+            let kept_ptrs : Vec<u32> = block_kept.keys().cloned().collect();
+            for ptr in kept_ptrs.into_iter() {
+                let mut cur_ptr = ptr;
+                while let Some((parent_ptr, parent_id)) = parents.get(&cur_ptr) {
+                    if block_kept.insert(*parent_ptr, *parent_id).is_some() {
+                        break;
+                    }
+                    cur_ptr = *parent_ptr;
+                }
+            }
+
+            let mut hashed = vec![];
+            for ptr in block_kept.keys() {
+                for child_ptr in children.get(ptr).unwrap_or(&vec![]).iter() {
+                    if !block_kept.contains_key(&child_ptr.ptr()) {
+                        hashed.push(child_ptr.clone());
+                    }
+                }
+            }
+            for child_ptr in hashed.into_iter() {
+                block_kept.insert(child_ptr.ptr(), child_ptr.id());
+            }
+        }
+
+        // lay out the kept nodes of each pruned trie after its header, in their original order
+        let mut remaps : HashMap<u32, HashMap<u32, u32>> = HashMap::new();
+        let mut layouts = vec![];
+        for (block_id, _) in pruned.iter() {
+            let blob = trie_sql::read_trie_blob_by_id(&self.db, *block_id)?;
+            let mut remap = HashMap::new();
+            let mut nodes = vec![];
+            let mut next_ptr = root_ptr;
+            for (ptr, id) in kept[block_id].iter() {
+                let (node, hash) = read_nodetype(&mut Cursor::new(&blob), &TriePtr::new(*id, 0, *ptr))?;
+                remap.insert(*ptr, next_ptr);
+                next_ptr += get_node_byte_len(&node) as u32;
+                nodes.push((node, hash));
+            }
+            remaps.insert(*block_id, remap);
+            layouts.push((*block_id, blob[0..(root_ptr as usize)].to_vec(), nodes));
+        }
+
+        let remap_ptrs = |block_id: u32, node: &mut TrieNodeType| -> Result<(), Error> {
+            if node.is_leaf() {
+                return Ok(());
+            }
+            for ptr in node.ptrs_mut().iter_mut() {
+                if ptr.id() == TrieNodeID::Empty as u8 {
+                    continue;
+                }
+                if is_backptr(ptr.id()) {
+                    if let Some(remap) = remaps.get(&ptr.back_block()) {
+                        ptr.ptr = match remap.get(&ptr.ptr()) {
+                            Some(new_ptr) => *new_ptr,
+                            // nodes in pruned tries may point to nodes that are gone
+                            None if remaps.contains_key(&block_id) => 0,
+                            None => {
+                                return Err(Error::CorruptionError(format!("Back-pointer {:?} in trie {} points to a pruned node", ptr, block_id)));
+                            }
+                        };
+                    }
+                }
+                else if let Some(remap) = remaps.get(&block_id) {
+                    ptr.ptr = *remap.get(&ptr.ptr()).unwrap_or(&0);
+                }
+            }
+            Ok(())
+        };
+
+        let tx = tx_begin_immediate(&mut self.db)?;
+        for (block_id, header, nodes) in layouts.into_iter() {
+            let header_len = header.len() as u64;
+            let mut buffer = Cursor::new(header);
+            buffer.set_position(header_len);
+            for (mut node, hash) in nodes.into_iter() {
+                remap_ptrs(block_id, &mut node)?;
+                write_nodetype_bytes(&mut buffer, &node, hash)?;
+            }
+            trie_sql::update_trie_blob(&tx, block_id, &buffer.into_inner())?;
+        }
+
+        for (block_id, updates) in retained_updates.into_iter() {
+            let mut buffer = Cursor::new(trie_sql::read_trie_blob_by_id(&tx, block_id)?);
+            for (ptr, mut node, hash) in updates.into_iter() {
+                remap_ptrs(block_id, &mut node)?;
+                buffer.set_position(ptr as u64);
+                write_nodetype_bytes(&mut buffer, &node, hash)?;
+            }
+            trie_sql::update_trie_blob(&tx, block_id, &buffer.into_inner())?;
+        }
+
+        for (block_id, block_hash) in pruned.iter() {
+            trie_sql::set_block_pruned(&tx, *block_id, block_hash)?;
+        }
+        tx.commit()?;
+        self.pruned = true;
+
+        // give the space back
+        self.db.execute_batch("VACUUM")?;
+        Ok(())
+    }
+
+    /// Find a confirmed block's trie, along with the tries of every block built on it (in any
+    /// fork, confirmed or not).  Tries are always stored after their parents' tries, so the
+    /// descendants are found in one pass over the later tries, by the parent block hash each one
+    /// starts with.  Returns their block IDs and hashes, in the order they were stored.
+    fn find_descendant_tries(&self, bhh: &T) -> Result<Vec<(u32, T)>, Error> {
+        let block_id = trie_sql::get_confirmed_block_identifier(&self.db, bhh)?
+            .ok_or(Error::NotFoundError)?;
+
+        let mut found_hashes = HashSet::new();
+        found_hashes.insert(bhh.clone().to_bytes());
+        let mut found = vec![(block_id, bhh.clone())];
+
+        for (later_id, later_hash, _) in trie_sql::read_all_block_ids::<T>(&self.db)?.into_iter() {
+            if later_id <= block_id {
+                continue;
+            }
+            let parent_hash : T = trie_sql::read_parent_block_hash(&self.db, later_id)?;
+            if found_hashes.contains(&parent_hash.to_bytes()) {
+                found_hashes.insert(later_hash.clone().to_bytes());
+                found.push((later_id, later_hash));
+            }
+        }
+
+        Ok(found)
+    }
+
+    /// The blocks truncate_at() would delete, in the order their tries were stored.
+    pub fn get_truncated_blocks(&self, bhh: &T) -> Result<Vec<T>, Error> {
+        let found = self.find_descendant_tries(bhh)?;
+        Ok(found.into_iter().map(|(_, block_hash)| block_hash).collect())
+    }
+
+    /// Delete a confirmed block's trie, along with the tries of every block built on it (in any
+    /// fork, confirmed or not).  No other trie refers to them, so the remaining tries stay
+    /// consistent.
+    /// Returns the deleted blocks, in the order their tries were stored.
+    pub fn truncate_at(&mut self, bhh: &T) -> Result<Vec<T>, Error> {
+        if self.readonly {
+            return Err(Error::ReadOnlyError);
+        }
+        if self.unconfirmed {
+            return Err(Error::UnconfirmedError);
+        }
+        if self.last_extended.is_some() {
+            return Err(Error::InProgressError);
+        }
+
+        let deleted = self.find_descendant_tries(bhh)?;
+
+        let tx = tx_begin_immediate(&mut self.db)?;
+        for (block_id, block_hash) in deleted.iter() {
+            trie_sql::delete_trie(&tx, *block_id)?;
+            trie_sql::drop_lock(&tx, block_hash)?;
+        }
+        tx.commit()?;
+
+        // forget what we knew about the deleted tries
+        self.block_hash_cache.clear();
+        self.clear_cached_ancestor_hashes_bytes();
+        self.cur_block = TrieFileStorage::block_sentinel();
+        self.cur_block_id = None;
+        self.pruned = trie_sql::has_pruned_tries(&self.db)?;
+
+        Ok(deleted.into_iter().map(|(_, block_hash)| block_hash).collect())
+    }
+
     pub fn has_confirmed_block(&self, bhh: &T) -> Result<bool, Error> {
         match trie_sql::get_confirmed_block_identifier(&self.db, bhh) {
             Ok(Some(_)) => Ok(true),
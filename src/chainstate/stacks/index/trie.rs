@@ -31,6 +31,7 @@ use std::io::{
 };
 
 use std::marker::PhantomData;
+use std::collections::HashMap;
 
 use chainstate::burn::BlockHeaderHash;
 use chainstate::burn::BLOCK_HEADER_HASH_ENCODED_SIZE;
@@ -639,6 +640,123 @@ impl Trie {
         }
     }
 
+    /// Check a trie's integrity: recompute the hash of every node in the trie (including the
+    /// root's ancestor hashes) and compare it to the stored hash, and make sure that every
+    /// back-pointer resolves to a node in an ancestor trie.  Returns the number of nodes checked.
+    /// In a pruned trie, only the remaining nodes are checked: nodes whose children are gone are
+    /// covered by their parents' hashes, and back-pointers to nodes that are gone are skipped, as
+    /// are the ancestor checks (the trie can no longer look up its ancestors' heights).
+    /// Fails with a CorruptionError describing the first inconsistency found.
+    /// The storage must be writable, since node hashing reads through the write path.
+    pub fn check_trie<T: MarfTrieId>(storage: &mut TrieFileStorage<T>, block_hash: &T) -> Result<u64, Error> {
+        let (cur_block_hash, cur_block_id) = storage.get_cur_block_and_id();
+
+        storage.open_block(block_hash)?;
+        let result = Trie::inner_check_trie(storage, block_hash);
+
+        // restore
+        storage.open_block_maybe_id(&cur_block_hash, cur_block_id)?;
+        result
+    }
+
+    fn inner_check_trie<T: MarfTrieId>(storage: &mut TrieFileStorage<T>, block_hash: &T) -> Result<u64, Error> {
+        let block_id = storage.get_cur_block_identifier()?;
+        let block_height = MARF::get_block_height(storage, block_hash, block_hash)?
+            .ok_or_else(|| Error::CorruptionError(format!("Could not obtain block height for block {}", block_hash)))?;
+        let pruned = storage.is_block_pruned(block_hash)?;
+        storage.open_block_known_id(block_hash, block_id)?;
+
+        // heights of the blocks that back-pointers point to, by local block ID
+        let mut back_block_heights = HashMap::new();
+        let mut num_nodes = 0;
+
+        let (root, root_hash) = Trie::read_root(storage)?;
+        let mut frontier = vec![(root, root_hash, true)];
+
+        while let Some((node, node_hash, is_root)) = frontier.pop() {
+            num_nodes += 1;
+
+            if pruned && node.ptrs().iter().any(|ptr| ptr.id() != TrieNodeID::Empty as u8 && !is_backptr(ptr.id()) && ptr.ptr() == 0) {
+                // only kept for its hash
+                continue;
+            }
+
+            let computed_hash = match node {
+                TrieNodeType::Leaf(ref leaf) => get_leaf_hash(leaf),
+                _ => {
+                    let content_hash = get_nodetype_hash(storage, &node)?;
+                    if is_root {
+                        Trie::get_trie_root_hash(storage, &content_hash)?
+                    }
+                    else {
+                        content_hash
+                    }
+                }
+            };
+
+            if computed_hash != node_hash {
+                return Err(Error::CorruptionError(format!("Node {:?} in block {} has hash {}, but its contents hash to {}",
+                                                          &node, block_hash, &node_hash, &computed_hash)));
+            }
+
+            for ptr in node.ptrs().iter() {
+                if ptr.id() == TrieNodeID::Empty as u8 {
+                    continue;
+                }
+
+                if !is_backptr(ptr.id()) {
+                    let (child, child_hash) = storage.read_nodetype(ptr)?;
+                    frontier.push((child, child_hash, false));
+                    continue;
+                }
+
+                if pruned && ptr.ptr() == 0 {
+                    // points to a node that was pruned
+                    continue;
+                }
+
+                // back-pointers must point to a node in an ancestor's trie
+                let back_block = storage.get_block_from_local_id(ptr.back_block())
+                    .map_err(|_| Error::CorruptionError(format!("Back-pointer {:?} in block {} points to an unknown block", ptr, block_hash)))?
+                    .clone();
+
+                if !pruned && !back_block_heights.contains_key(&ptr.back_block()) {
+                    let back_block_height = MARF::get_block_height(storage, &back_block, block_hash)?;
+                    storage.open_block_known_id(block_hash, block_id)?;
+
+                    match back_block_height {
+                        Some(height) if height < block_height => {
+                            back_block_heights.insert(ptr.back_block(), height);
+                        },
+                        _ => {
+                            return Err(Error::CorruptionError(format!("Back-pointer {:?} in block {} points to block {}, which is not an ancestor",
+                                                                      ptr, block_hash, &back_block)));
+                        }
+                    }
+                }
+
+                storage.open_block_known_id(&back_block, ptr.back_block())?;
+                let back_node_res = storage.read_nodetype(ptr);
+                storage.open_block_known_id(block_hash, block_id)?;
+
+                match back_node_res {
+                    Ok((back_node, _)) => {
+                        if back_node.id() != clear_backptr(ptr.id()) {
+                            return Err(Error::CorruptionError(format!("Back-pointer {:?} in block {} points to a {:?} in block {}",
+                                                                      ptr, block_hash, &back_node, &back_block)));
+                        }
+                    },
+                    Err(e) => {
+                        return Err(Error::CorruptionError(format!("Back-pointer {:?} in block {} does not resolve to a node in block {}: {:?}",
+                                                                  ptr, block_hash, &back_block, &e)));
+                    }
+                }
+            }
+        }
+
+        Ok(num_nodes)
+    }
+
     /// Unwind a TrieCursor to update the Merkle root of the trie.
     /// The root hashes of each trie form a Merkle skip-list -- the hash of Trie i is calculated
     /// from the hash of its children, plus the hash Tries i-1, i-2, i-4, i-8, ..., i-2**j, ...
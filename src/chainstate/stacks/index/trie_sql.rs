@@ -130,6 +130,14 @@ static SQL_EXTENSION_LOCKS_TABLE: &str = "
 CREATE TABLE IF NOT EXISTS block_extension_locks (block_hash TEXT PRIMARY KEY);
 ";
 
+// only created once a MARF is pruned
+static SQL_MARF_PRUNED_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS marf_pruned (
+   block_id INTEGER PRIMARY KEY,
+   block_hash TEXT UNIQUE NOT NULL
+);
+";
+
 pub fn create_tables_if_needed(conn: &mut Connection) -> Result<(), Error> {
     let tx = tx_begin_immediate(conn)?;
 
@@ -201,6 +209,92 @@ pub fn write_trie_blob_to_unconfirmed<T: MarfTrieId>(conn: &Connection, block_ha
     Ok(block_id)
 }
 
+/// Write a confirmed trie blob under a given local block identifier, instead of the next free one.
+/// Back-pointers in tries refer to blocks by their local identifiers, so a trie copied from
+/// another MARF must keep the identifiers it had there.
+pub fn write_trie_blob_with_id<T: MarfTrieId>(conn: &Connection, block_id: u32, block_hash: &T, data: &[u8]) -> Result<(), Error> {
+    let args: &[&dyn ToSql] = &[&block_id, block_hash, &data, &0];
+    conn.execute("INSERT INTO marf_data (block_id, block_hash, data, unconfirmed) VALUES (?, ?, ?, ?)", args)?;
+    Ok(())
+}
+
+/// Read a confirmed trie blob, and its local block identifier
+pub fn read_confirmed_trie_blob<T: MarfTrieId>(conn: &Connection, block_hash: &T) -> Result<Option<(u32, Vec<u8>)>, Error> {
+    conn.query_row("SELECT block_id, data FROM marf_data WHERE block_hash = ? AND unconfirmed = 0", &[block_hash],
+                   |row| (row.get("block_id"), row.get("data")))
+        .optional()
+        .map_err(|e| e.into())
+}
+
+/// Read every trie's local block identifier, block hash, and whether or not it's unconfirmed
+pub fn read_all_block_ids<T: MarfTrieId>(conn: &Connection) -> Result<Vec<(u32, T, bool)>, Error> {
+    let mut s = conn.prepare("SELECT block_id, block_hash, unconfirmed FROM marf_data ORDER BY block_id")?;
+    let rows = s.query_and_then(NO_PARAMS, |row| {
+        let block_id: u32 = row.get("block_id");
+        let block_hash: T = row.get("block_hash");
+        let unconfirmed: i64 = row.get("unconfirmed");
+        Ok((block_id, block_hash, unconfirmed != 0))
+    })?;
+    rows.collect()
+}
+
+pub fn read_trie_blob_by_id(conn: &Connection, block_id: u32) -> Result<Vec<u8>, Error> {
+    conn.query_row("SELECT data FROM marf_data WHERE block_id = ?", &[block_id],
+                   |row| row.get("data"))
+        .map_err(|e| e.into())
+}
+
+pub fn update_trie_blob(conn: &Connection, block_id: u32, data: &[u8]) -> Result<(), Error> {
+    let args: &[&dyn ToSql] = &[&data, &block_id];
+    conn.execute("UPDATE marf_data SET data = ? WHERE block_id = ?", args)?;
+    Ok(())
+}
+
+pub fn has_pruned_tries(conn: &Connection) -> Result<bool, Error> {
+    let has_table = conn.query_row("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'marf_pruned'", NO_PARAMS,
+                                   |row| row.get::<_, i64>(0))
+        .optional()?
+        .is_some();
+    if !has_table {
+        return Ok(false);
+    }
+    let has_rows = conn.query_row("SELECT 1 FROM marf_pruned LIMIT 1", NO_PARAMS,
+                                  |row| row.get::<_, i64>(0))
+        .optional()?
+        .is_some();
+    Ok(has_rows)
+}
+
+pub fn is_block_pruned<T: MarfTrieId>(conn: &Connection, bhh: &T) -> Result<bool, Error> {
+    let pruned = conn.query_row("SELECT 1 FROM marf_pruned WHERE block_hash = ?", &[bhh],
+                                |row| row.get::<_, i64>(0))
+        .optional()?
+        .is_some();
+    Ok(pruned)
+}
+
+pub fn set_block_pruned<T: MarfTrieId>(conn: &Connection, block_id: u32, bhh: &T) -> Result<(), Error> {
+    conn.execute_batch(SQL_MARF_PRUNED_TABLE)?;
+    let args: &[&dyn ToSql] = &[&block_id, bhh];
+    conn.execute("INSERT OR REPLACE INTO marf_pruned (block_id, block_hash) VALUES (?, ?)", args)?;
+    Ok(())
+}
+
+/// Read the hash of a trie's parent block, which is stored at the start of its blob
+pub fn read_parent_block_hash<T: MarfTrieId>(conn: &Connection, block_id: u32) -> Result<T, Error> {
+    let mut blob = open_trie_blob(conn, block_id)?;
+    let parent_hash_bytes = read_hash_bytes(&mut blob)?;
+    Ok(T::from_bytes(parent_hash_bytes))
+}
+
+pub fn delete_trie(conn: &Connection, block_id: u32) -> Result<(), Error> {
+    conn.execute("DELETE FROM marf_data WHERE block_id = ?", &[block_id])?;
+    if has_pruned_tries(conn)? {
+        conn.execute("DELETE FROM marf_pruned WHERE block_id = ?", &[block_id])?;
+    }
+    Ok(())
+}
+
 pub fn open_trie_blob<'a>(conn: &'a Connection, block_id: u32) -> Result<Blob<'a>, Error> {
     let blob = conn.blob_open(rusqlite::DatabaseName::Main, "marf_data", "data", block_id.into(), true)?;
     Ok(blob)
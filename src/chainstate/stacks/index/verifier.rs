@@ -0,0 +1,299 @@
+/*
+ copyright: (c) 2013-2020 by Blockstack PBC, a public benefit corporation.
+
+ This file is part of Blockstack.
+
+ Blockstack is free software. You may redistribute or modify
+ it under the terms of the GNU General Public License as published by
+ the Free Software Foundation, either version 3 of the License or
+ (at your option) any later version.
+
+ Blockstack is distributed in the hope that it will be useful,
+ but WITHOUT ANY WARRANTY, including without the implied warranty of
+ MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ GNU General Public License for more details.
+
+ You should have received a copy of the GNU General Public License
+ along with Blockstack. If not, see <http://www.gnu.org/licenses/>.
+*/
+
+//! Verification of MARF proofs from the outside: a client that has a proof from the RPC
+//! interface, the Clarity key it was asked about, and the headers it trusts can check the proof
+//! here without a MARF, a chainstate, or a Clarity VM of its own.  This module only depends on
+//! the MARF's proof and hash types, so it can be split out into a client library.
+
+use std::collections::HashMap;
+
+use chainstate::stacks::index::{
+    TrieHash,
+    MARFValue,
+    MarfTrieId
+};
+
+use chainstate::stacks::index::node::TriePath;
+
+use chainstate::stacks::index::proofs::{
+    TrieMerkleProof,
+    ProofRejection
+};
+
+use util::hash::hex_bytes;
+
+/// The state roots a verifier trusts.  A proof is made from some chain tip, and checked against
+/// that tip's state root.  If the key was last written before the tip, the proof follows the
+/// MARF's backptrs to the tries of the older blocks that wrote it, and the verifier must also
+/// trust those blocks' state roots (i.e. the state_index_root of their verified headers).  The
+/// proof alone can't say which block an older trie belongs to, so a proof that passes through a
+/// trie the verifier doesn't trust is rejected with ProofRejection::UntrustedTrie, which names
+/// the block whose state root is needed.
+pub struct TrustedRoots <T: MarfTrieId> {
+    root_hash: TrieHash,
+    root_to_block: HashMap<TrieHash, T>
+}
+
+impl <T: MarfTrieId> TrustedRoots <T> {
+    /// Trust a chain tip, given its state root
+    pub fn new(tip: T, root_hash: TrieHash) -> TrustedRoots<T> {
+        let mut root_to_block = HashMap::new();
+        root_to_block.insert(root_hash.clone(), tip);
+        TrustedRoots { root_hash, root_to_block }
+    }
+
+    /// Also trust an ancestor of the chain tip, given its state root
+    pub fn add_ancestor(&mut self, block: T, root_hash: TrieHash) {
+        self.root_to_block.insert(root_hash, block);
+    }
+
+    pub fn root_hash(&self) -> &TrieHash {
+        &self.root_hash
+    }
+}
+
+fn strip_hex_prefix(s: &str) -> &str {
+    if s.starts_with("0x") {
+        &s[2..]
+    }
+    else {
+        s
+    }
+}
+
+/// Decode a hex-encoded MARF proof, with or without a leading 0x
+pub fn decode_proof<T: MarfTrieId>(proof_hex: &str) -> Result<TrieMerkleProof<T>, ProofRejection<T>> {
+    let bytes = hex_bytes(strip_hex_prefix(proof_hex))
+        .map_err(|_| ProofRejection::Malformed("proof is not a hex string".into()))?;
+
+    TrieMerkleProof::from_bytes(&bytes)
+        .map_err(|e| ProofRejection::Malformed(format!("failed to decode proof: {:?}", &e)))
+}
+
+/// Verify a hex-encoded MARF proof of a Clarity key's value, as of the trusted chain tip.
+/// The key is the one the Clarity database stores the value under (i.e. from one of the
+/// ClarityDatabase::make_key_for_*() functions), and the expected value is the value as the
+/// Clarity database stores it -- for a Clarity value, its hex serialization (as given in the RPC
+/// interface's responses), with or without a leading 0x.  If no value is expected, the proof must
+/// show that the key has no value.
+pub fn verify_marf_proof<T: MarfTrieId>(proof_hex: &str, key: &str, expected_value: Option<&str>, trusted: &TrustedRoots<T>) -> Result<(), ProofRejection<T>> {
+    let proof = decode_proof(proof_hex)?;
+    let path = TriePath::from_key(key);
+
+    match expected_value {
+        Some(value) => {
+            let marf_value = MARFValue::from_value(strip_hex_prefix(value));
+            TrieMerkleProof::check_proof(&proof.0, &path, &marf_value, &trusted.root_hash, &trusted.root_to_block)
+        },
+        None => {
+            TrieMerkleProof::check_exclusion_proof(&proof.0, &path, &trusted.root_hash, &trusted.root_to_block)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chainstate::burn::BlockHeaderHash;
+    use chainstate::stacks::index::proofs::TrieMerkleProofType;
+    use chainstate::stacks::index::marf::MARF;
+    use chainstate::stacks::index::storage::TrieFileStorage;
+
+    #[test]
+    fn verify_proofs_with_trusted_roots() {
+        let mut m = MARF::from_path(":memory:").unwrap();
+
+        let sentinel_block = TrieFileStorage::block_sentinel();
+        let blocks : Vec<_> = (0..3).map(|i| BlockHeaderHash([i as u8; 32])).collect();
+
+        // the keys are written in the first block, and untouched after
+        let keys : Vec<_> = (0..16).map(|i| format!("key-{}", i)).collect();
+        let mut parent = sentinel_block;
+        for (i, block) in blocks.iter().enumerate() {
+            m.begin(&parent, block).unwrap();
+            if i == 0 {
+                for key in keys.iter() {
+                    m.insert(key, MARFValue::from_value("0a0b")).unwrap();
+                }
+            }
+            m.insert(&format!("other-{}", i), MARFValue::from_value("00")).unwrap();
+            m.commit().unwrap();
+            parent = block.clone();
+        }
+
+        let root_hashes : Vec<_> = blocks.iter().map(|block| m.get_root_hash_at(block).unwrap()).collect();
+
+        // find a key whose proof follows a backptr out of the tip's trie, i.e. has more than
+        // one shunt proof
+        let (key, proof) = keys.iter()
+            .map(|key| (key.as_str(), format!("0x{}", m.get_with_proof(&blocks[2], key).unwrap().unwrap().1.to_hex())))
+            .find(|(_, proof)| {
+                decode_proof::<BlockHeaderHash>(proof).unwrap().iter()
+                    .filter(|node| match node { TrieMerkleProofType::Shunt(_) => true, _ => false })
+                    .count() > 1
+            })
+            .expect("no key's proof follows a backptr");
+        let absent_proof = m.get_exclusion_proof(&blocks[2], "absent").unwrap().unwrap().to_hex();
+
+        // trusting only the tip isn't enough, since the key's leaf is in the first block's trie
+        let mut trusted = TrustedRoots::new(blocks[2].clone(), root_hashes[2].clone());
+        assert_eq!(verify_marf_proof(&proof, key, Some("0x0a0b"), &trusted),
+                   Err(ProofRejection::UntrustedTrie(root_hashes[0].clone(), Some(blocks[0].clone()))));
+
+        // trusted ancestors must agree with the proof
+        let mut misplaced = TrustedRoots::new(blocks[2].clone(), root_hashes[2].clone());
+        misplaced.add_ancestor(blocks[1].clone(), root_hashes[0].clone());
+        match verify_marf_proof(&proof, key, Some("0x0a0b"), &misplaced) {
+            Err(ProofRejection::BadHashes(_)) | Err(ProofRejection::WrongRoot(_)) => {},
+            x => panic!("Expected the proof to be rejected, got {:?}", x)
+        }
+
+        trusted.add_ancestor(blocks[0].clone(), root_hashes[0].clone());
+        trusted.add_ancestor(blocks[1].clone(), root_hashes[1].clone());
+        assert_eq!(verify_marf_proof(&proof, key, Some("0x0a0b"), &trusted), Ok(()));
+        assert_eq!(verify_marf_proof(&absent_proof, "absent", None, &trusted), Ok(()));
+
+        // wrong expectations are rejected with a reason
+        assert_eq!(verify_marf_proof(&proof, key, Some("0a0c"), &trusted), Err(ProofRejection::WrongValue(MARFValue::from_value("0a0b"))));
+        assert_eq!(verify_marf_proof(&proof, "other-0", Some("0a0b"), &trusted), Err(ProofRejection::WrongPath));
+        match verify_marf_proof(&absent_proof, "absent", Some("0a0b"), &trusted) {
+            // depends on whether the absent path ends at a leaf or at an interior node
+            Err(ProofRejection::NotIncluded) | Err(ProofRejection::WrongPath) => {},
+            x => panic!("Expected the exclusion proof to be rejected, got {:?}", x)
+        }
+        assert_eq!(verify_marf_proof(&proof, key, None, &trusted), Err(ProofRejection::NotExcluded));
+
+        let mut older = TrustedRoots::new(blocks[1].clone(), root_hashes[1].clone());
+        older.add_ancestor(blocks[0].clone(), root_hashes[0].clone());
+        match verify_marf_proof(&proof, key, Some("0a0b"), &older) {
+            Err(ProofRejection::WrongRoot(_)) => {},
+            x => panic!("Expected the proof to be rejected, got {:?}", x)
+        }
+
+        match verify_marf_proof::<BlockHeaderHash>("0xzz", "key", None, &trusted) {
+            Err(ProofRejection::Malformed(_)) => {},
+            x => panic!("Expected a malformed proof, got {:?}", x)
+        }
+        match verify_marf_proof::<BlockHeaderHash>(&format!("{}00", &absent_proof), "absent", None, &trusted) {
+            Err(ProofRejection::Malformed(_)) => {},
+            x => panic!("Expected a malformed proof, got {:?}", x)
+        }
+    }
+
+    /// Split a proof into its segment proofs, each with the shunt proofs that follow it
+    fn split_proof(proof: &[TrieMerkleProofType<BlockHeaderHash>]) -> Vec<(Vec<TrieMerkleProofType<BlockHeaderHash>>, Vec<TrieMerkleProofType<BlockHeaderHash>>)> {
+        let is_shunt = |node: &TrieMerkleProofType<BlockHeaderHash>| match node { TrieMerkleProofType::Shunt(_) => true, _ => false };
+        let mut parts = vec![];
+        let mut i = 0;
+        while i < proof.len() {
+            let mut j = i;
+            while j < proof.len() && !is_shunt(&proof[j]) {
+                j += 1;
+            }
+            let mut k = j;
+            while k < proof.len() && is_shunt(&proof[k]) {
+                k += 1;
+            }
+            parts.push((proof[i..j].to_vec(), proof[j..k].to_vec()));
+            i = k;
+        }
+        parts
+    }
+
+    #[test]
+    fn reject_spliced_older_trie() {
+        // a node could splice an older trie into a proof made at the tip, to prove a stale value
+        // or the absence of a key that was written later, and the spliced proof still links up
+        // to the tip's state root
+        let mut m = MARF::from_path(":memory:").unwrap();
+
+        let sentinel_block = TrieFileStorage::block_sentinel();
+        let blocks : Vec<_> = (0..3).map(|i| BlockHeaderHash([i as u8; 32])).collect();
+
+        // the keys are written in the first block, then one of them is overwritten and a new one
+        // is written in the second block
+        let keys : Vec<_> = (0..16).map(|i| format!("key-{}", i)).collect();
+        let mut parent = sentinel_block;
+        for (i, block) in blocks.iter().enumerate() {
+            m.begin(&parent, block).unwrap();
+            if i == 0 {
+                for key in keys.iter() {
+                    m.insert(key, MARFValue::from_value("0a0b")).unwrap();
+                }
+            }
+            else if i == 1 {
+                m.insert(&keys[0], MARFValue::from_value("0c0d")).unwrap();
+                m.insert("late", MARFValue::from_value("0e0f")).unwrap();
+            }
+            m.insert(&format!("other-{}", i), MARFValue::from_value("00")).unwrap();
+            m.commit().unwrap();
+            parent = block.clone();
+        }
+
+        let root_hashes : Vec<_> = blocks.iter().map(|block| m.get_root_hash_at(block).unwrap()).collect();
+
+        // the shunt proofs that link the first block's trie to the tip's trie, taken from a key
+        // that is still as the first block wrote it
+        let first_block_shunts = keys[1..].iter()
+            .map(|key| split_proof(&m.get_with_proof(&blocks[2], key).unwrap().unwrap().1))
+            .find(|parts| parts.len() == 2)
+            .expect("no key's proof goes from the tip's trie straight to the first block's trie")
+            [1].1.clone();
+
+        // splice the first block's trie into proofs made at the tip: the overwritten value, and
+        // the absence of the key that was only written later
+        let splice = |old_proof: TrieMerkleProof<BlockHeaderHash>, new_proof: TrieMerkleProof<BlockHeaderHash>| -> String {
+            let new_parts = split_proof(&new_proof);
+            assert_eq!(new_parts.len(), 2);
+            let mut spliced = old_proof.0.clone();
+            spliced.extend_from_slice(&new_parts[1].0);
+            spliced.extend_from_slice(&first_block_shunts);
+            format!("0x{}", TrieMerkleProof(spliced).to_hex())
+        };
+        let stale_proof = splice(m.get_with_proof(&blocks[0], &keys[0]).unwrap().unwrap().1,
+                                 m.get_with_proof(&blocks[2], &keys[0]).unwrap().unwrap().1);
+        let fake_absent_proof = splice(m.get_exclusion_proof(&blocks[0], "late").unwrap().unwrap(),
+                                       m.get_with_proof(&blocks[2], "late").unwrap().unwrap().1);
+
+        // the spliced tries link up to the tip's state root, but not to their blocks
+        let mut trusted = TrustedRoots::new(blocks[2].clone(), root_hashes[2].clone());
+        assert_eq!(verify_marf_proof(&stale_proof, &keys[0], Some("0a0b"), &trusted),
+                   Err(ProofRejection::UntrustedTrie(root_hashes[0].clone(), Some(blocks[1].clone()))));
+        assert_eq!(verify_marf_proof(&fake_absent_proof, "late", None, &trusted),
+                   Err(ProofRejection::UntrustedTrie(root_hashes[0].clone(), Some(blocks[1].clone()))));
+
+        trusted.add_ancestor(blocks[0].clone(), root_hashes[0].clone());
+        trusted.add_ancestor(blocks[1].clone(), root_hashes[1].clone());
+        match verify_marf_proof(&stale_proof, &keys[0], Some("0a0b"), &trusted) {
+            Err(ProofRejection::BadHashes(_)) | Err(ProofRejection::WrongRoot(_)) => {},
+            x => panic!("Expected the spliced proof to be rejected, got {:?}", x)
+        }
+        match verify_marf_proof(&fake_absent_proof, "late", None, &trusted) {
+            Err(ProofRejection::BadHashes(_)) | Err(ProofRejection::WrongRoot(_)) => {},
+            x => panic!("Expected the spliced proof to be rejected, got {:?}", x)
+        }
+
+        // the real proofs verify
+        let proof = format!("0x{}", m.get_with_proof(&blocks[2], &keys[0]).unwrap().unwrap().1.to_hex());
+        assert_eq!(verify_marf_proof(&proof, &keys[0], Some("0c0d"), &trusted), Ok(()));
+        let proof = format!("0x{}", m.get_with_proof(&blocks[2], "late").unwrap().unwrap().1.to_hex());
+        assert_eq!(verify_marf_proof(&proof, "late", Some("0e0f"), &trusted), Ok(()));
+    }
+}
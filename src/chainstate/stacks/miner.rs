@@ -3587,6 +3587,74 @@ pub mod test {
         }
     }
 
+    #[test]
+    fn test_build_anchored_blocks_fee_rate_samples() {
+        let privk = StacksPrivateKey::from_hex("42faca653724860da7a41bfcef7e6ba78db55146f6900de8cb2a9f760ffac70c01").unwrap();
+        let addr = StacksAddress::from_public_keys(C32_ADDRESS_VERSION_TESTNET_SINGLESIG, &AddressHashMode::SerializeP2PKH, 1, &vec![StacksPublicKey::from_private(&privk)]).unwrap();
+
+        let mut peer_config = TestPeerConfig::new("test_build_anchored_blocks_fee_rate_samples", 2016, 2017);
+        peer_config.initial_balances = vec![
+            (addr.to_account_principal(), 1000000000)
+        ];
+
+        let mut peer = TestPeer::new(peer_config);
+
+        let chainstate_path = peer.chainstate_path.clone();
+
+        let num_blocks = 5;
+        let recipient = StacksAddress::from_string("ST1RFD5Q2QPK3E0F08HG9XDX7SSC7CNRS0QR0SGEV").unwrap();
+        let mut sender_nonce = 0;
+
+        let mut last_block = None;
+        for tenure_id in 0..num_blocks {
+            let tip = SortitionDB::get_canonical_burn_chain_tip_stubbed(&peer.sortdb.as_ref().unwrap().conn()).unwrap();
+
+            let (burn_ops, stacks_block, microblocks) = peer.make_tenure(|ref mut miner, ref mut sortdb, ref mut chainstate, vrf_proof, ref parent_opt, ref parent_microblock_header_opt| {
+                let parent_tip = match parent_opt {
+                    None => {
+                        StacksChainState::get_genesis_header_info(&chainstate.headers_db).unwrap()
+                    }
+                    Some(block) => {
+                        let ic = sortdb.index_conn();
+                        let snapshot = SortitionDB::get_block_snapshot_for_winning_stacks_block(
+                            &ic, &tip.sortition_id, &block.block_hash()).unwrap().unwrap();      // succeeds because we don't fork
+                        StacksChainState::get_anchored_block_header_info(&chainstate.headers_db, &snapshot.burn_header_hash, &snapshot.winning_stacks_block_hash).unwrap().unwrap()
+                    }
+                };
+
+                let parent_header_hash = parent_tip.anchored_header.block_hash();
+                let parent_tip_bhh = parent_tip.burn_header_hash.clone();
+
+                let mut mempool = MemPoolDB::open(false, 0x80000000, &chainstate_path).unwrap();
+
+                let coinbase_tx = make_coinbase(miner, tenure_id);
+
+                // initial balances aren't materialized if the tip is the genesis header
+                if tenure_id > 0 {
+                    let stx_transfer = make_user_stacks_transfer(&privk, sender_nonce, 100 * (tenure_id as u64), &recipient.to_account_principal(), 1);
+                    sender_nonce += 1;
+
+                    mempool.submit(&parent_tip_bhh, &parent_header_hash, stx_transfer).unwrap();
+                }
+                let anchored_block = StacksBlockBuilder::build_anchored_block(chainstate, &mempool, &parent_tip, tip.total_burn, vrf_proof, Hash160([tenure_id as u8; 20]), &coinbase_tx, ExecutionCost::max_value()).unwrap();
+                (anchored_block.0, vec![])
+            });
+
+            last_block = Some(stacks_block.clone());
+
+            peer.next_burnchain_block(burn_ops.clone());
+            peer.process_stacks_epoch_at_tip(&stacks_block, &microblocks);
+        }
+
+        let tip = SortitionDB::get_canonical_burn_chain_tip_stubbed(&peer.sortdb.as_ref().unwrap().conn()).unwrap();
+        let tip_index_hash = StacksBlockHeader::make_index_block_hash(&tip.burn_header_hash, &tip.winning_stacks_block_hash);
+
+        // coinbases pay no fee, so only the transfers are sampled
+        assert_eq!(peer.chainstate().get_recent_fee_rates(&tip_index_hash, 3).unwrap(), vec![200, 300, 400]);
+        assert_eq!(peer.chainstate().get_recent_fee_rates(&tip_index_hash, 100).unwrap(), vec![100, 200, 300, 400]);
+        assert_eq!(peer.chainstate().get_recent_fee_rates(&tip_index_hash, 0).unwrap(), Vec::<u64>::new());
+    }
+
     #[test]
     fn test_build_anchored_blocks_stx_transfers_multi() {
         let mut privks = vec![];
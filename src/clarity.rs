@@ -6,12 +6,14 @@ use std::env;
 use std::process;
 use std::convert::TryInto;
 use std::path::PathBuf;
+use std::collections::HashMap;
 
 use util::log;
 
 use chainstate::burn::BlockHeaderHash;
 use chainstate::stacks::index::{MarfTrieId, storage::{TrieFileStorage}};
 use chainstate::stacks::StacksBlockId;
+use chainstate::stacks::events::StacksTransactionEvent;
 
 use rusqlite::{Connection, OpenFlags, NO_PARAMS};
 use rusqlite::types::ToSql;
@@ -21,7 +23,14 @@ use rusqlite::Transaction;
 use util::db::FromColumn;
 
 use vm::ast::{build_ast};
+use vm::ast::formatter::format_source;
 use vm::contexts::OwnedEnvironment;
+use vm::hooks::EvalHook;
+use vm::profiler::{CostProfiler, CostMetric};
+#[cfg(feature = "developer-mode")]
+use vm::tracing::ExecutionTracer;
+#[cfg(feature = "developer-mode")]
+use vm::debugger::Debugger;
 use vm::database::{ClarityDatabase, SqliteConnection,
                    MarfedKV, MemoryBackingStore, NULL_HEADER_DB};
 use vm::errors::{InterpreterResult, RuntimeErrorType, Error};
@@ -29,8 +38,11 @@ use vm::{SymbolicExpression, SymbolicExpressionType, Value, execute as vm_execut
 use vm::analysis;
 use vm::analysis::{errors::CheckResult, AnalysisDatabase, ContractAnalysis};
 use vm::analysis::contract_interface_builder::build_contract_interface;
-use vm::types::{QualifiedContractIdentifier, PrincipalData};
-use vm::costs::LimitedCostTracker;
+use vm::bindings::generate_bindings;
+use vm::analysis::lint_checker::LintChecker;
+use vm::analysis::AnalysisPass;
+use vm::types::{QualifiedContractIdentifier, PrincipalData, FunctionType, FixedFunction};
+use vm::costs::{LimitedCostTracker, ExecutionCost};
 
 use address::c32::c32_address;
 
@@ -51,7 +63,9 @@ fn print_usage(invoked_by: &str) {
 where command is one of:
 
   initialize         to initialize a local VM state database.
-  check              to typecheck a potential contract definition.
+  check              to typecheck a potential contract definition (and, with --lint, warn about likely mistakes).
+  fmt                to reformat a contract's source, or check that it is formatted.
+  generate_bindings  to generate Rust bindings for calling a contract, from its source or interface.
   launch             to launch a initialize a new contract in the local state database.
   eval               to evaluate (in read-only mode) a program in a given contract context.
   eval_at_chaintip   like `eval`, but does not advance to a new block.
@@ -60,6 +74,11 @@ where command is one of:
   eval_raw           to typecheck and evaluate an expression without a contract or database context.
   repl               to typecheck and evaluate expressions in a stdin/stdout loop.
   execute            to execute a public function of a defined contract.
+  test               to run the `test-*` functions of a test contract against a contract.
+  trace              like `execute`, but prints each evaluated expression and does not commit.
+  coverage           like `test`, but writes an lcov coverage report of the contract.
+  debug              like `trace`, but steps through the call interactively.
+  profile            like `trace`, but reports where the cost of the call went, as folded stacks.
   generate_address   to generate a random Stacks public address for testing purposes.
 ", invoked_by);
     panic_test!()
@@ -101,6 +120,14 @@ fn run_analysis(contract_identifier: &QualifiedContractIdentifier,
         .map_err(|(e, _)| e)
 }
 
+/// Run the lint pass over an analyzed contract, if requested.
+fn run_lint(mut contract_analysis: ContractAnalysis, analysis_db: &mut AnalysisDatabase,
+            lint: bool) -> CheckResult<ContractAnalysis> {
+    if lint {
+        LintChecker::run_pass(&mut contract_analysis, analysis_db)?;
+    }
+    Ok(contract_analysis)
+}
 
 fn create_or_open_db(path: &String) -> Connection {
     let open_flags = match fs::metadata(path) {
@@ -263,6 +290,346 @@ fn get_eval_input(invoked_by: &str, args: &[String]) -> EvalInput {
     }
 }
 
+// Tests in a test contract are its zero-argument public functions named `test-*`.  Each one
+//   may be preceded by `;; @directive` comments which set up the VM state it runs against.
+//   Directives are read line by line from the source text, not from the parsed contract, so
+//   they only apply to a test whose `(define-public (test-...` starts its own line.  Directives
+//   that don't apply to any test are reported rather than ignored.
+const TEST_FUNCTION_PREFIX: &str = "test-";
+const DEFAULT_TEST_SENDER: &str = "S1G2081040G2081040G2081040G208105NK8PE5";
+
+#[derive(Debug, Clone, PartialEq, Default)]
+struct TestAnnotations {
+    sender: Option<PrincipalData>,
+    advance_blocks: u32,
+    stx_mints: Vec<(PrincipalData, u128)>,
+    expected: Option<Value>,
+}
+
+struct TestOutcome {
+    name: String,
+    passed: bool,
+    result: Result<Value, Error>,
+    cost: ExecutionCost,
+    events: Vec<StacksTransactionEvent>,
+}
+
+fn parse_test_value(input: &str) -> Result<Value, String> {
+    match vm_execute(input) {
+        Ok(Some(value)) => Ok(value),
+        Ok(None) => Err(format!("'{}' does not evaluate to a value", input)),
+        Err(e) => Err(format!("failed to evaluate '{}': {}", input, e))
+    }
+}
+
+fn parse_test_principal(input: &str) -> Result<PrincipalData, String> {
+    match parse_test_value(input)? {
+        Value::Principal(principal) => Ok(principal),
+        x => Err(format!("expected a principal, found {}", x))
+    }
+}
+
+/// Read the `;; @directive` comments directly above each `(define-public (test-...` line.
+///  The format is line-based: each directive is a whole `;;` comment line of its own, and
+///  the directives collected so far attach to the next line that starts with
+///  `(define-public (test-`.  Other comment lines and blank lines may sit in between.  It's
+///  an error for directives to be followed by any other line of code (or by nothing), or to
+///  trail code on the same line, since they wouldn't attach to any test.
+///  Supported directives:
+///    @sender <principal>           -- the tx-sender of the test (default: S1G2081040G2081040G2081040G208105NK8PE5)
+///    @mint-stx <principal> <uint>  -- credit STX to a principal before the test runs (repeatable)
+///    @advance-blocks <n>           -- mine n empty blocks before the test runs
+///    @expect <value>               -- the value the test must return (default: any `(ok ...)`)
+fn parse_test_annotations(test_content: &str) -> Result<HashMap<String, TestAnnotations>, String> {
+    let mut annotations = HashMap::new();
+    let mut pending = TestAnnotations::default();
+    // the line of the first directive not yet attached to a test
+    let mut pending_line = None;
+
+    let unattached = |directive_line: usize| {
+        format!("line {}: test directive does not apply to any test -- directives must be directly above a line starting with `(define-public ({}`",
+                directive_line, TEST_FUNCTION_PREFIX)
+    };
+
+    for (line_num, line) in test_content.lines().enumerate() {
+        let line = line.trim();
+        let at_line = |e: String| format!("line {}: {}", line_num + 1, e);
+
+        if line.starts_with(";;") {
+            let comment = line.trim_start_matches(';').trim();
+            if !comment.starts_with('@') {
+                continue;
+            }
+            pending_line = pending_line.or(Some(line_num + 1));
+            let mut parts = comment[1..].splitn(2, char::is_whitespace);
+            let directive = parts.next().unwrap_or("");
+            let argument = parts.next().unwrap_or("").trim();
+            match directive {
+                "sender" => {
+                    pending.sender = Some(parse_test_principal(argument).map_err(at_line)?);
+                },
+                "mint-stx" => {
+                    let mut mint_args = argument.split_whitespace();
+                    let (principal, amount) = match (mint_args.next(), mint_args.next(), mint_args.next()) {
+                        (Some(principal), Some(amount), None) => (principal, amount),
+                        _ => return Err(at_line("@mint-stx expects a principal and a uint amount".into()))
+                    };
+                    let principal = parse_test_principal(principal).map_err(at_line)?;
+                    let amount = match parse_test_value(amount).map_err(at_line)? {
+                        Value::UInt(amount) => amount,
+                        x => return Err(at_line(format!("expected a uint amount, found {}", x)))
+                    };
+                    pending.stx_mints.push((principal, amount));
+                },
+                "advance-blocks" => {
+                    pending.advance_blocks = argument.parse()
+                        .map_err(|_| at_line(format!("@advance-blocks expects a block count, found '{}'", argument)))?;
+                },
+                "expect" => {
+                    pending.expected = Some(parse_test_value(argument).map_err(at_line)?);
+                },
+                _ => {
+                    return Err(at_line(format!("unknown test directive '@{}'", directive)));
+                }
+            }
+        } else if line.len() > 0 {
+            if has_trailing_directive(line) {
+                return Err(unattached(line_num + 1));
+            }
+
+            let name: Option<String> = if line.starts_with("(define-public (") {
+                Some(line["(define-public (".len()..].chars()
+                     .take_while(|c| !c.is_whitespace() && *c != ')')
+                     .collect())
+            } else {
+                None
+            };
+
+            match name {
+                Some(name) if name.starts_with(TEST_FUNCTION_PREFIX) => {
+                    let directives = std::mem::replace(&mut pending, TestAnnotations::default());
+                    annotations.insert(name, directives);
+                    pending_line = None;
+                },
+                _ => {
+                    if let Some(directive_line) = pending_line {
+                        return Err(unattached(directive_line));
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(directive_line) = pending_line {
+        return Err(unattached(directive_line));
+    }
+
+    Ok(annotations)
+}
+
+/// Does a line of code end in a `;; @directive` comment?  Semicolons in string literals don't
+///  start a comment.
+fn has_trailing_directive(line: &str) -> bool {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ';' {
+            return line[i..].trim_start_matches(';').trim().starts_with('@');
+        }
+    }
+    false
+}
+
+fn test_block_id(height: u32) -> StacksBlockId {
+    let mut bytes = [0u8; 32];
+    bytes[28..32].copy_from_slice(&height.to_be_bytes());
+    StacksBlockId(bytes)
+}
+
+/// Parse `[contract-identifier] [public-function-name] [sender-address] [args...]` command
+///  arguments into a contract call.
+fn get_execute_input(args: &[String]) -> (QualifiedContractIdentifier, String, PrincipalData, Vec<SymbolicExpression>) {
+    let contract_identifier = friendly_expect(QualifiedContractIdentifier::parse(&args[0]), "Failed to parse contract identifier.");
+
+    let tx_name = args[1].clone();
+    let sender_in = &args[2];
+
+    let sender = {
+        if let Ok(sender) = PrincipalData::parse_standard_principal(sender_in) {
+            PrincipalData::Standard(sender.clone())
+        } else {
+            eprintln!("Unexpected result parsing sender: {}", sender_in);
+            panic_test!();
+        }
+    };
+
+    let arguments: Vec<_> = args[3..]
+        .iter()
+        .map(|argument| {
+            let argument_parsed = friendly_expect(
+                vm_execute(argument),
+                &format!("Error parsing argument \"{}\"", argument));
+            let argument_value = friendly_expect_opt(
+                argument_parsed,
+                &format!("Failed to parse a value from the argument: {}", argument));
+            SymbolicExpression::atom_value(argument_value.clone())
+        })
+        .collect();
+
+    (contract_identifier, tx_name, sender, arguments)
+}
+
+/// A contract under test, and the test contract exercising it.
+struct TestSuite {
+    contract_identifier: QualifiedContractIdentifier,
+    contract_content: String,
+    test_identifier: QualifiedContractIdentifier,
+    test_content: String,
+    annotations: HashMap<String, TestAnnotations>,
+    test_names: Vec<String>,
+}
+
+/// Load a test suite from `[contract-identifier] [contract-definition.clar] [test-contract.clar]`
+///  command arguments, and find its test functions by type-checking it.
+fn load_test_suite(args: &[String]) -> TestSuite {
+    let contract_identifier = friendly_expect(QualifiedContractIdentifier::parse(&args[1]), "Failed to parse contract identifier.");
+    let test_identifier = friendly_expect(QualifiedContractIdentifier::parse(&format!("{}-test", &args[1])),
+                                          "Failed to construct test contract identifier.");
+
+    let contract_content: String = friendly_expect(fs::read_to_string(&args[2]),
+                                                   &format!("Error reading file: {}", args[2]));
+    let test_content: String = friendly_expect(fs::read_to_string(&args[3]),
+                                               &format!("Error reading file: {}", args[3]));
+
+    let annotations = friendly_expect(parse_test_annotations(&test_content),
+                                      &format!("Failed to read test directives in {}", args[3]));
+
+    let mut analysis_marf = MemoryBackingStore::new();
+    {
+        // the test contract calls into the contract under test, which it can only resolve once
+        //  the contract has been committed to, as it would be by deploying it
+        let mut db = analysis_marf.as_clarity_db();
+        db.begin();
+        friendly_expect(db.insert_contract_hash(&contract_identifier, &contract_content), "Failed to store contract hash.");
+        db.commit();
+    }
+    let mut db = analysis_marf.as_analysis_db();
+    let mut contract_ast = friendly_expect(parse(&contract_identifier, &contract_content), "Failed to parse program.");
+    friendly_expect(run_analysis(&contract_identifier, &mut contract_ast, &mut db, true)
+                    .map_err(|e| e.diagnostic), "Contract type-check error.");
+    let mut test_ast = friendly_expect(parse(&test_identifier, &test_content), "Failed to parse test program.");
+    let test_analysis = friendly_expect(run_analysis(&test_identifier, &mut test_ast, &mut db, true)
+                                        .map_err(|e| e.diagnostic), "Test contract type-check error.");
+
+    let test_names = test_analysis.public_function_types.iter()
+        .filter(|(name, _)| name.starts_with(TEST_FUNCTION_PREFIX))
+        .filter_map(|(name, function_type)| match function_type {
+            FunctionType::Fixed(FixedFunction { args: function_args, .. }) if function_args.len() == 0 => Some(name.to_string()),
+            _ => {
+                eprintln!("Skipping {}: test functions must take no arguments", name.as_str());
+                None
+            }
+        })
+        .collect();
+
+    TestSuite { contract_identifier, contract_content, test_identifier, test_content, annotations, test_names }
+}
+
+/// Run a single test function against a fresh VM state, into which the contract under test and
+///  the test contract have been deployed.  If a hook is given, it observes the deployments as
+///  well as the test itself.  Returns an error if the state could not be set up.
+fn run_clarity_test(suite: &TestSuite, test_name: &str, mut hook: Option<&mut dyn EvalHook>) -> Result<TestOutcome, String> {
+    let annotations = suite.annotations.get(test_name).cloned().unwrap_or_default();
+    let mut marf = MarfedKV::temporary();
+    let mut height = 1;
+
+    marf.begin(&StacksBlockId::sentinel(), &test_block_id(height));
+    {
+        let mut db = marf.as_clarity_db(&NULL_HEADER_DB);
+        db.initialize();
+    }
+
+    for (identifier, content) in [(&suite.contract_identifier, &suite.contract_content),
+                                  (&suite.test_identifier, &suite.test_content)].iter() {
+        let mut ast = parse(identifier, content)
+            .map_err(|e| format!("Failed to parse {}: {}", identifier, e))?;
+        {
+            let mut db = AnalysisDatabase::new(&mut marf);
+            run_analysis(identifier, &mut ast, &mut db, true)
+                .map_err(|e| format!("Failed to type-check {}:\n{}", identifier, e.diagnostic))?;
+        }
+        let db = marf.as_clarity_db(&NULL_HEADER_DB);
+        let mut vm_env = OwnedEnvironment::new_cost_limited(db, LimitedCostTracker::new_max_limit());
+        if let Some(ref mut hook) = hook {
+            vm_env.set_eval_hook(&mut **hook);
+        }
+        vm_env.initialize_contract((*identifier).clone(), content)
+            .map_err(|e| format!("Failed to initialize {}: {}", identifier, e))?;
+    }
+
+    {
+        let mut db = marf.as_clarity_db(&NULL_HEADER_DB);
+        db.begin();
+        for (principal, amount) in annotations.stx_mints.iter() {
+            let balance = db.get_account_stx_balance(principal).checked_add(*amount)
+                .ok_or_else(|| format!("STX balance overflow minting to {}", principal))?;
+            db.set_account_stx_balance(principal, balance);
+        }
+        db.commit();
+    }
+    marf.commit_to(&test_block_id(height));
+
+    for _ in 0..annotations.advance_blocks {
+        marf.begin(&test_block_id(height), &test_block_id(height + 1));
+        height += 1;
+        marf.commit_to(&test_block_id(height));
+    }
+
+    let sender = match annotations.sender {
+        Some(ref sender) => sender.clone(),
+        None => PrincipalData::parse(DEFAULT_TEST_SENDER)
+            .expect("FATAL: failed to parse default test sender")
+    };
+
+    marf.begin(&test_block_id(height), &test_block_id(height + 1));
+    let (result, cost) = {
+        let db = marf.as_clarity_db(&NULL_HEADER_DB);
+        let mut vm_env = OwnedEnvironment::new_cost_limited(db, LimitedCostTracker::new_max_limit());
+        if let Some(ref mut hook) = hook {
+            vm_env.set_eval_hook(&mut **hook);
+        }
+        let result = vm_env.execute_transaction(Value::Principal(sender), suite.test_identifier.clone(), test_name, &[]);
+        let cost = vm_env.destruct()
+            .map(|(_, cost_tracker)| cost_tracker.get_total())
+            .unwrap_or_else(ExecutionCost::zero);
+        (result, cost)
+    };
+    marf.rollback();
+
+    let (result, events) = match result {
+        Ok((value, _, events)) => (Ok(value), events),
+        Err(e) => (Err(e), vec![])
+    };
+
+    let passed = match (&result, &annotations.expected) {
+        (Ok(value), Some(expected)) => value == expected,
+        (Ok(Value::Response(data)), None) => data.committed,
+        _ => false
+    };
+
+    Ok(TestOutcome { name: test_name.to_string(), passed, result, cost, events })
+}
+
 #[derive(Serialize, Deserialize)]
 struct InitialAllocation {
     principal: String,
@@ -330,8 +697,10 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) {
             println!("{}", addr);
         },
         "check" => {
+            let lint = args.iter().any(|arg| arg == "--lint");
+            let args: Vec<String> = args.iter().filter(|arg| *arg != "--lint").cloned().collect();
             if args.len() < 2 {
-                eprintln!("Usage: {} {} [program-file.clar] (vm-state.db)", invoked_by, args[0]);
+                eprintln!("Usage: {} {} [program-file.clar] (vm-state.db) (--lint)", invoked_by, args[0]);
                 panic_test!();
             }
 
@@ -351,7 +720,8 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) {
                         marf_kv,
                         |mut marf| {
                             let result = { let mut db = AnalysisDatabase::new(&mut marf);
-                                           run_analysis(&contract_id, &mut ast, &mut db, false) };
+                                           run_analysis(&contract_id, &mut ast, &mut db, false)
+                                               .and_then(|analysis| run_lint(analysis, &mut db, lint)) };
                             (marf, result)
                         });
                     result
@@ -359,12 +729,17 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) {
                     let mut analysis_marf = MemoryBackingStore::new();
                     let mut db = analysis_marf.as_analysis_db();
                     run_analysis(&contract_id, &mut ast, &mut db, false)
+                        .and_then(|analysis| run_lint(analysis, &mut db, lint))
                 }
             }.unwrap_or_else(|e| {
                 println!("{}", &e.diagnostic);
                 panic_test!();
             });
 
+            for warning in contract_analysis.warnings.iter() {
+                println!("{}", warning);
+            }
+
             match args.last() {
                 Some(s) if s == "--output_analysis" => {
                     println!("{}", build_contract_interface(&contract_analysis).serialize());
@@ -374,6 +749,61 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) {
                 }
             }
         },
+        "fmt" => {
+            let check = args.iter().any(|arg| arg == "--check");
+            let args: Vec<String> = args.iter().filter(|arg| *arg != "--check").cloned().collect();
+            if args.len() < 2 {
+                eprintln!("Usage: {} {} [program-file.clar] (--check)", invoked_by, args[0]);
+                panic_test!();
+            }
+
+            let content: String = friendly_expect(fs::read_to_string(&args[1]),
+                                                  &format!("Error reading file: {}", args[1]));
+            let formatted = friendly_expect(format_source(&content), "Failed to parse program");
+
+            if check {
+                if formatted != content {
+                    eprintln!("{} is not formatted.", args[1]);
+                    panic_test!();
+                }
+                println!("{} is formatted.", args[1]);
+            } else {
+                print!("{}", formatted);
+            }
+        },
+        "generate_bindings" => {
+            if args.len() < 2 {
+                eprintln!("Usage: {} {} [program-file.clar | contract-interface.json] (contract-name)", invoked_by, args[0]);
+                panic_test!();
+            }
+
+            let content: String = friendly_expect(fs::read_to_string(&args[1]),
+                                                  &format!("Error reading file: {}", args[1]));
+
+            let interface = if args[1].ends_with(".clar") {
+                let contract_id = QualifiedContractIdentifier::transient();
+                let mut ast = friendly_expect(parse(&contract_id, &content), "Failed to parse program");
+                let mut analysis_marf = MemoryBackingStore::new();
+                let mut db = analysis_marf.as_analysis_db();
+                let contract_analysis = run_analysis(&contract_id, &mut ast, &mut db, false)
+                    .unwrap_or_else(|e| {
+                        println!("{}", &e.diagnostic);
+                        panic_test!();
+                    });
+                build_contract_interface(&contract_analysis)
+            } else {
+                friendly_expect(serde_json::from_str(&content), "Failed to parse contract interface")
+            };
+
+            // by default, the contract is named after its file
+            let contract_name = match args.get(2) {
+                Some(name) => name.clone(),
+                None => friendly_expect_opt(PathBuf::from(&args[1]).file_stem().and_then(|stem| stem.to_str()).map(|stem| stem.to_string()),
+                                            "Failed to name the contract; pass its name")
+            };
+
+            print!("{}", generate_bindings(&contract_name, &interface));
+        },
         "repl" => {
             let mut marf = MemoryBackingStore::new();
             let mut vm_env = OwnedEnvironment::new_cost_limited(marf.as_clarity_db(), LimitedCostTracker::new_max_limit());
@@ -629,32 +1059,7 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) {
             let vm_filename = &args[1];
             let marf_kv = friendly_expect(MarfedKV::open(vm_filename, None), "Failed to open VM database.");
 
-            let contract_identifier = friendly_expect(QualifiedContractIdentifier::parse(&args[2]), "Failed to parse contract identifier.");
-
-            let tx_name = &args[3];            
-            let sender_in = &args[4];
-
-            let sender = {
-                if let Ok(sender) = PrincipalData::parse_standard_principal(sender_in) {
-                    PrincipalData::Standard(sender.clone())
-                } else {
-                    eprintln!("Unexpected result parsing sender: {}", sender_in);
-                    panic_test!();
-                }
-            };
-
-            let arguments: Vec<_> = args[5..]
-                .iter()
-                .map(|argument| {
-                    let argument_parsed = friendly_expect(
-                        vm_execute(argument),
-                        &format!("Error parsing argument \"{}\"", argument));
-                    let argument_value = friendly_expect_opt(
-                        argument_parsed,
-                        &format!("Failed to parse a value from the argument: {}", argument));
-                    SymbolicExpression::atom_value(argument_value.clone())
-                })
-                .collect();
+            let (contract_identifier, tx_name, sender, arguments) = get_execute_input(&args[2..]);
 
             let result = in_block(vm_filename, marf_kv, |mut marf| {
                 let result = {
@@ -682,6 +1087,192 @@ pub fn invoke_command(invoked_by: &str, args: &[String]) {
                 }
             }
         },
+        #[cfg(feature = "developer-mode")]
+        "trace" => {
+            if args.len() < 5 {
+                eprintln!("Usage: {} {} [vm-state.db] [contract-identifier] [public-function-name] [sender-address] [args...]", invoked_by, args[0]);
+                eprintln!("   like `execute`, but prints every evaluated expression and does not commit the transaction.");
+                panic_test!();
+            }
+            let vm_filename = &args[1];
+            let marf_kv = friendly_expect(MarfedKV::open(vm_filename, None), "Failed to open VM database.");
+
+            let (contract_identifier, tx_name, sender, arguments) = get_execute_input(&args[2..]);
+
+            let mut tracer = ExecutionTracer::new();
+            let result = at_chaintip(vm_filename, marf_kv, |mut marf| {
+                let result = {
+                    let db = marf.as_clarity_db(&NULL_HEADER_DB);
+                    let mut vm_env = OwnedEnvironment::new_cost_limited(db, LimitedCostTracker::new_max_limit());
+                    vm_env.set_eval_hook(&mut tracer);
+                    vm_env.execute_transaction(Value::Principal(sender), contract_identifier, &tx_name, &arguments) };
+                (marf, result)
+            });
+
+            print!("{}", tracer.format_trace());
+            if let Some((contract_identifier, span)) = tracer.get_failure_location() {
+                println!("Failed at {} {}:{}", contract_identifier, span.start_line, span.start_column);
+            }
+
+            match result {
+                Ok((x, _, _)) => {
+                    println!("Returned: {}", x);
+                },
+                Err(error) => {
+                    eprintln!("Transaction execution error: \n{}", error);
+                    panic_test!();
+                }
+            }
+        },
+        #[cfg(feature = "developer-mode")]
+        "debug" => {
+            if args.len() < 5 {
+                eprintln!("Usage: {} {} [vm-state.db] [contract-identifier] [public-function-name] [sender-address] [args...]", invoked_by, args[0]);
+                eprintln!("   like `execute`, but stops at the first expression and reads debugger commands from stdin.");
+                eprintln!("   type `help` at the `(debug)` prompt for a list of commands. The transaction is not committed.");
+                panic_test!();
+            }
+            let vm_filename = &args[1];
+            let marf_kv = friendly_expect(MarfedKV::open(vm_filename, None), "Failed to open VM database.");
+
+            let (contract_identifier, tx_name, sender, arguments) = get_execute_input(&args[2..]);
+
+            let stdin = io::stdin();
+            let mut debugger = Debugger::new(stdin.lock(), io::stdout());
+            let result = at_chaintip(vm_filename, marf_kv, |mut marf| {
+                let result = {
+                    let db = marf.as_clarity_db(&NULL_HEADER_DB);
+                    let mut vm_env = OwnedEnvironment::new_cost_limited(db, LimitedCostTracker::new_max_limit());
+                    vm_env.set_eval_hook(&mut debugger);
+                    vm_env.execute_transaction(Value::Principal(sender), contract_identifier, &tx_name, &arguments) };
+                (marf, result)
+            });
+
+            match result {
+                Ok((x, _, events)) => {
+                    println!("Returned: {}\n{:?}", x, events);
+                },
+                Err(error) => {
+                    eprintln!("Transaction execution error: \n{}", error);
+                    panic_test!();
+                }
+            }
+        },
+        "profile" => {
+            let metric_flag = args.get(1).and_then(|flag| {
+                if flag.starts_with("--metric=") { Some(flag["--metric=".len()..].to_string()) } else { None }
+            });
+            let (metric, args) = match metric_flag {
+                Some(metric) => (friendly_expect(metric.parse::<CostMetric>(), "Failed to parse cost metric."), &args[1..]),
+                None => (CostMetric::Runtime, args)
+            };
+            if args.len() < 5 {
+                eprintln!("Usage: {} profile [--metric=runtime] [vm-state.db] [contract-identifier] [public-function-name] [sender-address] [args...]", invoked_by);
+                eprintln!("   like `execute`, but prints a summary of the cost of each function, native and data access to stderr,");
+                eprintln!("   and folded stacks for flamegraph.pl to stdout. The transaction is not committed.");
+                eprintln!("   --metric is one of runtime, read_count, read_length, write_count or write_length.");
+                panic_test!();
+            }
+            let vm_filename = &args[1];
+            let marf_kv = friendly_expect(MarfedKV::open(vm_filename, None), "Failed to open VM database.");
+
+            let (contract_identifier, tx_name, sender, arguments) = get_execute_input(&args[2..]);
+
+            let mut profiler = CostProfiler::new();
+            let (result, cost) = at_chaintip(vm_filename, marf_kv, |mut marf| {
+                let result = {
+                    let db = marf.as_clarity_db(&NULL_HEADER_DB);
+                    let mut vm_env = OwnedEnvironment::new_cost_limited(db, LimitedCostTracker::new_max_limit());
+                    vm_env.set_eval_hook(&mut profiler);
+                    let result = vm_env.execute_transaction(Value::Principal(sender), contract_identifier, &tx_name, &arguments);
+                    (result, vm_env.get_cost_total()) };
+                (marf, result)
+            });
+
+            let profile = profiler.into_profile(cost);
+            eprint!("{}", profile.make_summary());
+            print!("{}", profile.make_folded_report(metric));
+
+            if let Err(error) = result {
+                eprintln!("Transaction execution error: \n{}", error);
+                panic_test!();
+            }
+        },
+        "test" => {
+            if args.len() < 4 {
+                eprintln!("Usage: {} {} [contract-identifier] [contract-definition.clar] [test-contract.clar]", invoked_by, args[0]);
+                eprintln!("   each zero-argument public function named `test-*` in the test contract is run against");
+                eprintln!("   a fresh VM state, deployed as [contract-identifier]-test, and passes if it returns (ok ...).");
+                eprintln!("   `;; @sender`, `;; @mint-stx`, `;; @advance-blocks` and `;; @expect` comment lines directly above");
+                eprintln!("   a line starting with `(define-public (test-...` set its tx-sender, credit STX, mine empty blocks");
+                eprintln!("   and set the expected result. Each directive must be on its own line, and apply to a test.");
+                panic_test!();
+            }
+
+            let suite = load_test_suite(args);
+
+            println!("running {} tests", suite.test_names.len());
+
+            let mut failed = 0;
+            for test_name in suite.test_names.iter() {
+                let outcome = friendly_expect(run_clarity_test(&suite, test_name, None),
+                                              &format!("Failed to set up {}", test_name));
+
+                println!("{} ... {}", outcome.name, if outcome.passed { "ok" } else { "FAILED" });
+                match outcome.result {
+                    Ok(ref value) => println!("  returned: {}", value),
+                    Err(ref error) => println!("  error: {}", error)
+                }
+                if !outcome.passed {
+                    if let Some(expected) = suite.annotations.get(test_name).and_then(|a| a.expected.as_ref()) {
+                        println!("  expected: {}", expected);
+                    }
+                    failed += 1;
+                }
+                println!("  cost: {}", outcome.cost);
+                println!("  events: {}", outcome.events.len());
+                for event in outcome.events.iter() {
+                    println!("    {:?}", event);
+                }
+            }
+
+            println!("\ntest result: {}. {} passed; {} failed",
+                     if failed == 0 { "ok" } else { "FAILED" }, suite.test_names.len() - failed, failed);
+            if failed > 0 {
+                panic_test!();
+            }
+        },
+        #[cfg(feature = "developer-mode")]
+        "coverage" => {
+            if args.len() < 5 {
+                eprintln!("Usage: {} {} [contract-identifier] [contract-definition.clar] [test-contract.clar] [output.lcov]", invoked_by, args[0]);
+                eprintln!("   runs the tests of the test contract as `test` does, and writes an lcov report of the");
+                eprintln!("   lines and functions of the contract they reached.");
+                panic_test!();
+            }
+
+            let suite = load_test_suite(args);
+            let contract_ast = friendly_expect(parse(&suite.contract_identifier, &suite.contract_content), "Failed to parse program.");
+
+            let mut tracer = ExecutionTracer::coverage_only();
+            let mut failed = 0;
+            for test_name in suite.test_names.iter() {
+                let outcome = friendly_expect(run_clarity_test(&suite, test_name, Some(&mut tracer)),
+                                              &format!("Failed to set up {}", test_name));
+                if !outcome.passed {
+                    println!("{} ... FAILED", outcome.name);
+                    failed += 1;
+                }
+            }
+
+            let report = tracer.make_lcov_report(&suite.contract_identifier, &contract_ast, &args[2]);
+            friendly_expect(fs::write(&args[4], report), &format!("Failed to write coverage report to {}", args[4]));
+
+            println!("{} passed; {} failed. Coverage written to {}", suite.test_names.len() - failed, failed, args[4]);
+            if failed > 0 {
+                panic_test!();
+            }
+        },
         _ => {
             print_usage(invoked_by)
         }
@@ -715,6 +1306,52 @@ mod test {
                                  clar_name, db_name]);
     }
 
+    #[test]
+    fn test_test_annotations() {
+        let annotations = parse_test_annotations(r#"
+;; @sender 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR
+;; a plain comment does not interrupt the directives
+;; @mint-stx 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR u10
+;; @advance-blocks 3
+;; @expect (err u1)
+(define-public (test-annotated)
+  (err u1))
+;; a plain comment
+(define-constant x 1)
+(define-public (test-plain)
+  (ok true))
+"#).unwrap();
+
+        let sender = PrincipalData::parse("SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR").unwrap();
+        assert_eq!(annotations["test-annotated"],
+                   TestAnnotations { sender: Some(sender.clone()),
+                                     advance_blocks: 3,
+                                     stx_mints: vec![(sender, 10)],
+                                     expected: Some(Value::error(Value::UInt(1)).unwrap()) });
+        assert_eq!(annotations["test-plain"], TestAnnotations::default());
+
+        // directives that don't apply to a test are reported
+        let unattached = |content: &str| parse_test_annotations(content).unwrap_err();
+        assert!(unattached("(define-constant y \"a\") ;; @advance-blocks 5\n(define-public (test-trailing)\n  (ok true))").starts_with("line 1:"));
+        assert!(unattached(";; @advance-blocks 2\n(define-constant y 2)\n(define-public (test-after-code)\n  (ok true))").starts_with("line 1:"));
+        assert!(unattached(";; @advance-blocks 2\n(define-public (not-a-test)\n  (ok true))").starts_with("line 1:"));
+        assert!(unattached("(define-public (test-ok)\n  (ok true))\n;; @expect (ok true)\n").starts_with("line 3:"));
+        assert!(unattached("(define-public\n  ;; @sender 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR\n  (test-split-line)\n  (ok true))").starts_with("line 2:"));
+
+        // a directive-like string isn't a directive
+        let annotations = parse_test_annotations(r#"
+(define-constant y ";; @advance-blocks 5")
+;; @advance-blocks 2
+(define-public (test-string)
+  (ok true))
+"#).unwrap();
+        assert_eq!(annotations["test-string"].advance_blocks, 2);
+
+        assert!(parse_test_annotations(";; @advance-blocks many").is_err());
+        assert!(parse_test_annotations(";; @mint-stx 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR 10").is_err());
+        assert!(parse_test_annotations(";; @unknown").is_err());
+    }
+
     #[test]
     fn test_samples() {
         let db_name = format!("/tmp/db_{}", rand::thread_rng().gen::<i32>());
@@ -724,6 +1361,15 @@ mod test {
 
         eprintln!("check tokens");
         invoke_command("test", &["check".to_string(), "sample-contracts/tokens.clar".to_string()]);
+
+        eprintln!("fmt tokens");
+        invoke_command("test", &["fmt".to_string(), "sample-contracts/tokens.clar".to_string()]);
+
+        eprintln!("generate_bindings tokens");
+        invoke_command("test", &["generate_bindings".to_string(), "sample-contracts/tokens.clar".to_string()]);
+
+        eprintln!("check tokens (lint)");
+        invoke_command("test", &["check".to_string(), "sample-contracts/tokens.clar".to_string(), "--lint".to_string()]);
         
         eprintln!("check tokens");
         invoke_command("test", &["check".to_string(), "sample-contracts/tokens.clar".to_string(), db_name.clone()]);
@@ -750,6 +1396,34 @@ mod test {
                                  db_name.clone()
                                  ]);
 
+        eprintln!("test tokens");
+        invoke_command("test", &["test".to_string(), "S1G2081040G2081040G2081040G208105NK8PE5.tokens".to_string(),
+                                 "sample-contracts/tokens.clar".to_string(),
+                                 "sample-contracts/tokens-test.clar".to_string()]);
+
+        #[cfg(feature = "developer-mode")]
+        {
+            eprintln!("trace tokens");
+            invoke_command("test", &["trace".to_string(), db_name.clone(), "S1G2081040G2081040G2081040G208105NK8PE5.tokens".to_string(),
+                                     "mint!".to_string(), "SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR".to_string(),
+                                     "u10".to_string()]);
+        }
+
+        eprintln!("profile tokens");
+        invoke_command("test", &["profile".to_string(), "--metric=write_count".to_string(), db_name.clone(),
+                                 "S1G2081040G2081040G2081040G208105NK8PE5.tokens".to_string(),
+                                 "mint!".to_string(), "SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR".to_string(),
+                                 "u10".to_string()]);
+
+        #[cfg(feature = "developer-mode")]
+        {
+            eprintln!("coverage tokens");
+            invoke_command("test", &["coverage".to_string(), "S1G2081040G2081040G2081040G208105NK8PE5.tokens".to_string(),
+                                     "sample-contracts/tokens.clar".to_string(),
+                                     "sample-contracts/tokens-test.clar".to_string(),
+                                     format!("{}.lcov", db_name)]);
+        }
+
         eprintln!("eval_at_chaintip tokens");
         invoke_command("test", &["eval_at_chaintip".to_string(), "S1G2081040G2081040G2081040G208105NK8PE5.tokens".to_string(), 
                                  "sample-contracts/tokens-mint.clar".to_string(),
@@ -0,0 +1,160 @@
+/*
+ copyright: (c) 2013-2019 by Blockstack PBC, a public benefit corporation.
+
+ This file is part of Blockstack.
+
+ Blockstack is free software. You may redistribute or modify
+ it under the terms of the GNU General Public License as published by
+ the Free Software Foundation, either version 3 of the License or
+ (at your option) any later version.
+
+ Blockstack is distributed in the hope that it will be useful,
+ but WITHOUT ANY WARRANTY, including without the implied warranty of
+ MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ GNU General Public License for more details.
+
+ You should have received a copy of the GNU General Public License
+ along with Blockstack. If not, see <http://www.gnu.org/licenses/>.
+*/
+
+
+// Fee rate estimation.  Estimates are derived from the fee rates paid by the transactions that
+// were recently mined (in anchored blocks and in the microblock streams they confirmed), as well
+// as from the fee rates of the transactions still waiting in the mempool.
+
+use std::cmp;
+
+use chainstate::stacks::StacksBlockId;
+use chainstate::stacks::Error as chain_error;
+use chainstate::stacks::db::StacksChainState;
+use chainstate::stacks::db::blocks::MINIMUM_TX_FEE_RATE_PER_BYTE;
+
+use core::mempool::MemPoolDB;
+
+// how many recent anchored blocks to sample confirmed fee rates from
+pub const FEE_ESTIMATE_BLOCK_WINDOW : u64 = 10;
+
+// how many of the highest-paying pending transactions to sample fee rates from.  Only these
+// compete for the next few blocks, so a long tail of cheap transactions doesn't drag the
+// estimates down (and doesn't have to be read).
+pub const FEE_ESTIMATE_MEMPOOL_SAMPLE : u64 = 1000;
+
+// percentiles of the sampled fee rates to use for the low, medium, and high estimates
+pub const FEE_ESTIMATE_LOW_PERCENTILE : u64 = 25;
+pub const FEE_ESTIMATE_MEDIUM_PERCENTILE : u64 = 50;
+pub const FEE_ESTIMATE_HIGH_PERCENTILE : u64 = 75;
+
+/// Low, medium, and high estimates, either of fee rates (microSTX per byte) or of total fees
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeRateEstimate {
+    pub low: u64,
+    pub medium: u64,
+    pub high: u64
+}
+
+impl FeeRateEstimate {
+    /// What we estimate when we have nothing to go on
+    pub fn minimum() -> FeeRateEstimate {
+        FeeRateEstimate {
+            low: MINIMUM_TX_FEE_RATE_PER_BYTE,
+            medium: MINIMUM_TX_FEE_RATE_PER_BYTE,
+            high: MINIMUM_TX_FEE_RATE_PER_BYTE
+        }
+    }
+
+    /// Turn these fee rates into total fees for a transaction of the given length
+    pub fn fees_for_len(&self, len: u64) -> FeeRateEstimate {
+        FeeRateEstimate {
+            low: self.low.saturating_mul(len),
+            medium: self.medium.saturating_mul(len),
+            high: self.high.saturating_mul(len)
+        }
+    }
+}
+
+/// Get the value at the given percentile of a sorted list.  The list must be non-empty.
+fn percentile(sorted: &Vec<u64>, pct: u64) -> u64 {
+    assert!(sorted.len() > 0);
+    let idx = ((sorted.len() - 1) as u64) * pct / 100;
+    sorted[idx as usize]
+}
+
+/// Estimate fee rates from the fee rates of recently-confirmed transactions and of pending
+/// transactions.  The confirmed fee rates set the baseline.  The medium and high estimates are
+/// then raised so they outbid most of the mempool backlog -- a transaction that wants to get
+/// mined soon has to compete with everything else waiting to be mined.
+pub fn estimate_fee_rates_from_samples(mut confirmed: Vec<u64>, mut pending: Vec<u64>) -> FeeRateEstimate {
+    confirmed.sort();
+    pending.sort();
+
+    let mut estimate = 
+        if confirmed.len() > 0 {
+            FeeRateEstimate {
+                low: percentile(&confirmed, FEE_ESTIMATE_LOW_PERCENTILE),
+                medium: percentile(&confirmed, FEE_ESTIMATE_MEDIUM_PERCENTILE),
+                high: percentile(&confirmed, FEE_ESTIMATE_HIGH_PERCENTILE)
+            }
+        }
+        else {
+            FeeRateEstimate::minimum()
+        };
+
+    if pending.len() > 0 {
+        estimate.medium = cmp::max(estimate.medium, percentile(&pending, FEE_ESTIMATE_MEDIUM_PERCENTILE));
+        estimate.high = cmp::max(estimate.high, percentile(&pending, FEE_ESTIMATE_HIGH_PERCENTILE));
+    }
+
+    estimate.low = cmp::max(estimate.low, MINIMUM_TX_FEE_RATE_PER_BYTE);
+    estimate.medium = cmp::max(estimate.medium, estimate.low);
+    estimate.high = cmp::max(estimate.high, estimate.medium);
+    estimate
+}
+
+/// Estimate fee rates for a transaction to be mined on top of the given chain tip.
+pub fn estimate_fee_rates(chainstate: &StacksChainState, mempool: &MemPoolDB, tip: &StacksBlockId) -> Result<FeeRateEstimate, chain_error> {
+    let confirmed = chainstate.get_recent_fee_rates(tip, FEE_ESTIMATE_BLOCK_WINDOW)?;
+    let pending = MemPoolDB::get_highest_fee_rates(mempool.conn(), FEE_ESTIMATE_MEMPOOL_SAMPLE).map_err(chain_error::DBError)?;
+    Ok(estimate_fee_rates_from_samples(confirmed, pending))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_estimate_fee_rates_empty() {
+        assert_eq!(estimate_fee_rates_from_samples(vec![], vec![]), FeeRateEstimate::minimum());
+    }
+
+    #[test]
+    fn test_estimate_fee_rates_confirmed_only() {
+        let confirmed = vec![9, 1, 8, 2, 7, 3, 6, 4, 5];
+        let estimate = estimate_fee_rates_from_samples(confirmed, vec![]);
+        assert_eq!(estimate, FeeRateEstimate { low: 3, medium: 5, high: 7 });
+    }
+
+    #[test]
+    fn test_estimate_fee_rates_mempool_backlog() {
+        let confirmed = vec![1, 2, 3, 4, 5];
+
+        // a cheap backlog doesn't change anything
+        let estimate = estimate_fee_rates_from_samples(confirmed.clone(), vec![1, 1, 1]);
+        assert_eq!(estimate, FeeRateEstimate { low: 2, medium: 3, high: 4 });
+
+        // an expensive backlog raises the medium and high estimates, but not the low one
+        let estimate = estimate_fee_rates_from_samples(confirmed.clone(), vec![10, 20, 30, 40, 50]);
+        assert_eq!(estimate, FeeRateEstimate { low: 2, medium: 30, high: 40 });
+    }
+
+    #[test]
+    fn test_estimate_fee_rates_floor() {
+        let estimate = estimate_fee_rates_from_samples(vec![0, 0, 0], vec![]);
+        assert_eq!(estimate, FeeRateEstimate::minimum());
+    }
+
+    #[test]
+    fn test_fees_for_len() {
+        let estimate = FeeRateEstimate { low: 1, medium: 2, high: u64::max_value() };
+        assert_eq!(estimate.fees_for_len(100), FeeRateEstimate { low: 100, medium: 200, high: u64::max_value() });
+    }
+}
@@ -28,6 +28,7 @@ use rusqlite::Row;
 use std::ops::Deref;
 use std::ops::DerefMut;
 use std::cmp;
+use std::collections::HashSet;
 
 use burnchains::BurnchainHeaderHash;
 use burnchains::Txid;
@@ -52,6 +53,9 @@ use util::db::u64_to_sql;
 use util::db::{DBConn, DBTx, FromRow};
 use util::db::FromColumn;
 use util::db::query_rows;
+use util::db::query_row_columns;
+use util::db::query_count;
+use util::db::query_int;
 use util::db::query_row;
 use util::db::Error as db_error;
 use util::get_epoch_time_secs;
@@ -67,6 +71,36 @@ use rusqlite::Error as SqliteError;
 pub const MEMPOOL_MAX_TRANSACTION_AGE: u64 = 256;
 pub const MAXIMUM_MEMPOOL_TX_CHAINING: u64 = 5;
 
+// default maximum total size, in bytes, of all transactions in the mempool
+pub const MEMPOOL_DEFAULT_MAX_SIZE: u64 = 128 * 1024 * 1024;
+// default maximum number of pending transactions a single origin address may have in the mempool
+pub const MEMPOOL_DEFAULT_MAX_TXS_PER_ORIGIN: u64 = 100;
+// default minimum percentage by which a replace-by-fee transaction must increase the fee
+pub const MEMPOOL_DEFAULT_REPLACE_BY_FEE_MIN_BUMP: u64 = 0;
+
+/// Resource limits on the mempool.  These are node-local policy, not consensus rules.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemPoolLimits {
+    /// maximum total size, in bytes, of all transactions in the mempool.  Once this is reached,
+    /// the transactions with the lowest fee rates are evicted to make room for new ones.
+    pub max_size: u64,
+    /// maximum number of pending transactions a single origin address may have
+    pub max_txs_per_origin: u64,
+    /// a transaction that replaces another with the same origin or sponsor nonce must pay a fee
+    /// that is at least this many percent higher than the one it replaces.
+    pub replace_by_fee_min_bump: u64,
+}
+
+impl Default for MemPoolLimits {
+    fn default() -> MemPoolLimits {
+        MemPoolLimits {
+            max_size: MEMPOOL_DEFAULT_MAX_SIZE,
+            max_txs_per_origin: MEMPOOL_DEFAULT_MAX_TXS_PER_ORIGIN,
+            replace_by_fee_min_bump: MEMPOOL_DEFAULT_REPLACE_BY_FEE_MIN_BUMP,
+        }
+    }
+}
+
 pub struct MemPoolAdmitter {
     // mempool admission should have its own chain state view.
     //   the mempool admitter interacts with the chain state
@@ -166,6 +200,15 @@ impl FromRow<MemPoolTxInfo> for MemPoolTxInfo {
 }
 
 
+/// An account's pending transactions in the mempool, relative to its confirmed nonce.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MemPoolNonceInfo {
+    /// pending transactions that use one of the account's unconfirmed nonces, ordered by nonce
+    pub pending_txids: Vec<Txid>,
+    /// nonces between the confirmed nonce and the highest pending nonce that no pending transaction uses
+    pub missing_nonces: Vec<u64>,
+}
+
 const MEMPOOL_SQL : &'static [&'static str] = &[
     r#"
     CREATE TABLE mempool(
@@ -197,15 +240,49 @@ const MEMPOOL_SQL : &'static [&'static str] = &[
     "#
 ];
 
+// indexes added after the mempool schema was first deployed, so they're (re)created whenever
+//   the mempool is opened.  One statement per entry.
+const MEMPOOL_INDEXES : &'static [&'static str] = &[
+    "CREATE INDEX IF NOT EXISTS by_fee_rate ON mempool(fee_rate);",
+];
+
+// running total of the size of the mempool's transactions, so admitting a transaction doesn't
+//   have to scan the whole mempool.  Also (re)created whenever the mempool is opened, and seeded
+//   from the mempool's contents if it's new.  The triggers rely on recursive_triggers being set,
+//   so that the rows an INSERT OR REPLACE removes are subtracted too.
+const MEMPOOL_SIZE_SQL : &'static [&'static str] = &[
+    r#"
+    CREATE TABLE IF NOT EXISTS mempool_size(
+        id INTEGER PRIMARY KEY CHECK (id = 0),
+        total_size INTEGER NOT NULL
+    );
+    "#,
+    "INSERT OR IGNORE INTO mempool_size (id, total_size) SELECT 0, ifnull(SUM(length), 0) FROM mempool;",
+    r#"
+    CREATE TRIGGER IF NOT EXISTS mempool_size_insert AFTER INSERT ON mempool
+    BEGIN
+        UPDATE mempool_size SET total_size = total_size + NEW.length WHERE id = 0;
+    END;
+    "#,
+    r#"
+    CREATE TRIGGER IF NOT EXISTS mempool_size_delete AFTER DELETE ON mempool
+    BEGIN
+        UPDATE mempool_size SET total_size = total_size - OLD.length WHERE id = 0;
+    END;
+    "#,
+];
+
 pub struct MemPoolDB {
     db: DBConn,
     path: String,
     admitter: MemPoolAdmitter,
+    limits: MemPoolLimits,
 }
 
 pub struct MemPoolTx<'a> {
     tx: DBTx<'a>,
-    admitter: &'a mut MemPoolAdmitter
+    admitter: &'a mut MemPoolAdmitter,
+    limits: &'a MemPoolLimits,
 }
 
 impl<'a> Deref for MemPoolTx<'a> {
@@ -222,10 +299,11 @@ impl<'a> DerefMut for MemPoolTx<'a> {
 }
 
 impl<'a> MemPoolTx<'a> {
-    pub fn new(tx: DBTx<'a>, admitter: &'a mut MemPoolAdmitter) -> MemPoolTx<'a> {
+    pub fn new(tx: DBTx<'a>, admitter: &'a mut MemPoolAdmitter, limits: &'a MemPoolLimits) -> MemPoolTx<'a> {
         MemPoolTx {
             tx,
-            admitter
+            admitter,
+            limits
         }
     }
     
@@ -306,6 +384,28 @@ impl MemPoolDB {
         Ok(())
     }
 
+    fn add_indexes(conn: &mut DBConn) -> Result<(), db_error> {
+        let tx = tx_begin_immediate(conn)?;
+
+        for cmd in MEMPOOL_INDEXES {
+            tx.execute(cmd, NO_PARAMS).map_err(db_error::SqliteError)?;
+        }
+
+        tx.commit().map_err(db_error::SqliteError)?;
+        Ok(())
+    }
+
+    fn add_size_counter(conn: &mut DBConn) -> Result<(), db_error> {
+        let tx = tx_begin_immediate(conn)?;
+
+        for cmd in MEMPOOL_SIZE_SQL {
+            tx.execute(cmd, NO_PARAMS).map_err(db_error::SqliteError)?;
+        }
+
+        tx.commit().map_err(db_error::SqliteError)?;
+        Ok(())
+    }
+
     /// Open the mempool db within the chainstate directory.
     /// The chainstate must be instantiated already.
     pub fn open(mainnet: bool, chain_id: u32, chainstate_path: &str) -> Result<MemPoolDB, db_error> {
@@ -344,19 +444,33 @@ impl MemPoolDB {
 
         let mut conn = DBConn::open_with_flags(&db_path, open_flags).map_err(db_error::SqliteError)?;
         conn.busy_handler(Some(tx_busy_handler)).map_err(db_error::SqliteError)?;
+        conn.execute_batch("PRAGMA recursive_triggers = ON;").map_err(db_error::SqliteError)?;
 
         if create_flag {
             // instantiate!
             MemPoolDB::instantiate_mempool_db(&mut conn)?;
         }
+
+        MemPoolDB::add_indexes(&mut conn)?;
+        MemPoolDB::add_size_counter(&mut conn)?;
         
         Ok(MemPoolDB {
             db: conn,
             path: db_path.to_string(),
             admitter: admitter,
+            limits: MemPoolLimits::default(),
         })
     }
 
+    /// Set the resource limits this mempool enforces on newly-submitted transactions.
+    pub fn set_limits(&mut self, limits: MemPoolLimits) {
+        self.limits = limits;
+    }
+
+    pub fn get_limits(&self) -> &MemPoolLimits {
+        &self.limits
+    }
+
     fn walk(&self, chainstate: &mut StacksChainState, tip_burn_header_hash: &BurnchainHeaderHash, tip_block_hash: &BlockHeaderHash, tip_height: u64) -> Result<Option<(BurnchainHeaderHash, BlockHeaderHash, u64, u64)>, ChainstateError> {
         // Walk back to the next-highest
         // ancestor of this tip, and see if we can include anything from there.
@@ -488,7 +602,7 @@ impl MemPoolDB {
 
     pub fn tx_begin<'a>(&'a mut self) -> Result<MemPoolTx<'a>, db_error> {
         let tx = tx_begin_immediate(&mut self.db)?;
-        Ok(MemPoolTx::new(tx, &mut self.admitter))
+        Ok(MemPoolTx::new(tx, &mut self.admitter, &self.limits))
     }
 
     fn db_has_tx(conn: &DBConn, txid: &Txid) -> Result<bool, db_error> {
@@ -512,6 +626,90 @@ impl MemPoolDB {
         Ok(rows)
     }
 
+    /// Get the highest fee rates of the pending transactions, across all tips, up to the given
+    /// number of them.  Uses the fee rate index, so this doesn't scan the whole mempool.
+    pub fn get_highest_fee_rates(conn: &DBConn, count: u64) -> Result<Vec<u64>, db_error> {
+        let sql = "SELECT fee_rate FROM mempool ORDER BY fee_rate DESC LIMIT ?1";
+        let args : &[&dyn ToSql] = &[&u64_to_sql(count)?];
+        query_rows::<u64, _>(conn, &sql, args)
+    }
+
+    /// Get the number of transactions in the mempool, across all tips.
+    pub fn get_num_txs(conn: &DBConn) -> Result<u64, db_error> {
+        let sql = "SELECT COUNT(*) FROM mempool".to_string();
+        query_count(conn, &sql, NO_PARAMS).map(|count| count as u64)
+    }
+
+    /// Get the total size, in bytes, of all transactions in the mempool, across all tips.
+    pub fn get_total_size(conn: &DBConn) -> Result<u64, db_error> {
+        let sql = "SELECT total_size FROM mempool_size WHERE id = 0".to_string();
+        query_int(conn, &sql, NO_PARAMS).map(|size| size as u64)
+    }
+
+    /// Get a page of the txids of all pending transactions, across all tips.
+    /// Transactions are ordered by the time they were accepted, so pages are stable as new
+    /// transactions arrive.
+    pub fn get_txids(conn: &DBConn, offset: u64, count: u64) -> Result<Vec<Txid>, db_error> {
+        let sql = "SELECT txid FROM mempool ORDER BY accept_time ASC, txid ASC LIMIT ?1 OFFSET ?2".to_string();
+        let args : &[&dyn ToSql] = &[&u64_to_sql(count)?, &u64_to_sql(offset)?];
+        query_row_columns::<Txid, _>(conn, &sql, args, "txid")
+    }
+
+    /// Get all pending transactions in which the given address is either the origin or the
+    /// sponsor, across all tips.  Order them by origin nonce.
+    pub fn get_txs_for_address(conn: &DBConn, addr: &StacksAddress) -> Result<Vec<MemPoolTxInfo>, db_error> {
+        let sql = "SELECT * FROM mempool WHERE origin_address = ?1 OR sponsor_address = ?1 ORDER BY origin_nonce ASC, accept_time ASC";
+        let args : &[&dyn ToSql] = &[&addr.to_string()];
+        query_rows::<MemPoolTxInfo, _>(conn, sql, args)
+    }
+
+    /// Summarize the pending transactions that use the given address's nonces (either as an
+    /// origin or as a sponsor), given the nonce the address has already confirmed.  Pending
+    /// transactions with nonces below the confirmed nonce are ignored.  The next nonce the
+    /// address can use comes from get_next_nonce_for_address().
+    pub fn get_nonce_info_for_address(conn: &DBConn, addr: &StacksAddress, confirmed_nonce: u64) -> Result<MemPoolNonceInfo, db_error> {
+        let sql = "SELECT * FROM mempool WHERE (origin_address = ?1 AND origin_nonce >= ?2) OR (sponsor_address = ?1 AND sponsor_nonce >= ?2)";
+        let args : &[&dyn ToSql] = &[&addr.to_string(), &u64_to_sql(confirmed_nonce)?];
+        let rows = query_rows::<MemPoolTxMetadata, _>(conn, sql, args)?;
+
+        let mut pending = vec![];
+        let mut pending_nonces = HashSet::new();
+        for md in rows.into_iter() {
+            // a tx that isn't sponsored has the origin as its sponsor, so prefer the origin nonce
+            let nonce = if md.origin_address == *addr && md.origin_nonce >= confirmed_nonce {
+                md.origin_nonce
+            } else {
+                md.sponsor_nonce
+            };
+            pending_nonces.insert(nonce);
+            pending.push((nonce, md.txid));
+        }
+        pending.sort();
+
+        let end_nonce = match pending.last() {
+            Some((max_nonce, _)) => max_nonce + 1,
+            None => confirmed_nonce
+        };
+
+        let missing_nonces = (confirmed_nonce..end_nonce)
+            .filter(|nonce| !pending_nonces.contains(nonce))
+            .collect();
+
+        Ok(MemPoolNonceInfo {
+            pending_txids: pending.into_iter().map(|(_, txid)| txid).collect(),
+            missing_nonces
+        })
+    }
+
+    /// Count the pending transactions sent by the given origin address, not counting the given
+    /// transaction (i.e. the one it would replace).
+    fn get_num_txs_for_origin(conn: &DBConn, addr: &StacksAddress, exclude_txid: Option<&Txid>) -> Result<u64, db_error> {
+        let sql = "SELECT COUNT(*) FROM mempool WHERE origin_address = ?1 AND txid != ?2".to_string();
+        let exclude_txid = exclude_txid.cloned().unwrap_or(Txid([0u8; 32]));
+        let args : &[&dyn ToSql] = &[&addr.to_string(), &exclude_txid];
+        query_count(conn, &sql, args).map(|count| count as u64)
+    }
+
     /// Get the next timestamp after this one that occurs in this chain tip.
     pub fn get_next_timestamp(conn: &DBConn, burnchain_header_hash: &BurnchainHeaderHash, block_header_hash: &BlockHeaderHash, timestamp: u64) -> Result<Option<u64>, db_error> {
         let sql = "SELECT accept_time FROM mempool WHERE accept_time > ?1 AND burn_header_hash = ?2 AND block_header_hash = ?3 ORDER BY accept_time ASC LIMIT 1";
@@ -613,8 +811,85 @@ impl MemPoolDB {
         Ok(cmp::max(as_origin, as_sponsor))
     }
 
-    /// Add a transaction to the mempool.  If it already exists, then replace it if the given fee
-    /// is higher than the one that's already there.
+    /// Is a replacement transaction's fee high enough to replace a transaction with the given fee?
+    /// It must be strictly higher, and at least `replace_by_fee_min_bump` percent higher.
+    fn is_fee_bump(limits: &MemPoolLimits, prior_fee: u64, new_fee: u64) -> bool {
+        let min_fee = (prior_fee as u128) * (100 + (limits.replace_by_fee_min_bump as u128)) / 100;
+        new_fee > prior_fee && (new_fee as u128) >= min_fee
+    }
+
+    /// Make room for a new transaction of the given length and fee rate, if adding it would
+    /// exceed the mempool's maximum size.  Transactions with the lowest fee rates are evicted
+    /// first (and among those, the most recently-accepted ones).  The transaction being replaced
+    /// by the new one, if any, is not counted.  Fails without evicting anything if the new
+    /// transaction would itself be the one evicted.
+    fn make_room_for_tx<'a>(tx: &mut MemPoolTx<'a>, replaced_tx: Option<&MemPoolTxMetadata>, length: u64, fee_rate: u64) -> Result<(), MemPoolRejection> {
+        let max_size = tx.limits.max_size;
+        let cur_size = MemPoolDB::get_total_size(tx)?
+            .saturating_sub(replaced_tx.map(|md| md.len).unwrap_or(0));
+
+        if cur_size.saturating_add(length) <= max_size {
+            return Ok(());
+        }
+        if length > max_size {
+            return Err(MemPoolRejection::MemPoolFull(fee_rate, u64::max_value()));
+        }
+
+        let mut size_to_free = cur_size + length - max_size;
+        let mut evicted = vec![];
+        {
+            let sql = "SELECT txid, length, fee_rate FROM mempool ORDER BY fee_rate ASC, accept_time DESC";
+            let mut stmt = tx.prepare(sql)
+                .map_err(|e| MemPoolRejection::DBError(db_error::SqliteError(e)))?;
+
+            let mut rows = stmt.query(NO_PARAMS)
+                .map_err(|e| MemPoolRejection::DBError(db_error::SqliteError(e)))?;
+
+            while let Some(row_res) = rows.next() {
+                let row = row_res.map_err(|e| MemPoolRejection::DBError(db_error::SqliteError(e)))?;
+                let evict_txid = Txid::from_column(&row, "txid")?;
+                let evict_len = u64::from_column(&row, "length")?;
+                let evict_fee_rate = u64::from_column(&row, "fee_rate")?;
+
+                if let Some(replaced_md) = replaced_tx {
+                    if replaced_md.txid == evict_txid {
+                        continue;
+                    }
+                }
+
+                if evict_fee_rate >= fee_rate {
+                    // the new transaction would be evicted before this one
+                    return Err(MemPoolRejection::MemPoolFull(fee_rate, evict_fee_rate + 1));
+                }
+
+                evicted.push(evict_txid);
+                if evict_len >= size_to_free {
+                    size_to_free = 0;
+                    break;
+                }
+                size_to_free -= evict_len;
+            }
+        }
+
+        if size_to_free > 0 {
+            // can't happen unless the replaced tx alone exceeds the limit
+            return Err(MemPoolRejection::MemPoolFull(fee_rate, u64::max_value()));
+        }
+
+        for evict_txid in evicted.iter() {
+            info!("Evict tx {} from full mempool to make room for tx with fee rate {}", evict_txid, fee_rate);
+            tx.execute("DELETE FROM mempool WHERE txid = ?1", &[evict_txid as &dyn ToSql])
+                .map_err(|e| MemPoolRejection::DBError(db_error::SqliteError(e)))?;
+        }
+        Ok(())
+    }
+
+    /// Add a transaction to the mempool.  If there is already a transaction with the same origin
+    /// nonce or sponsor nonce, then replace it if the given fee is sufficiently higher than the one
+    /// that's already there (i.e. replace-by-fee), or if the existing transaction was submitted
+    /// on a different fork.
+    /// Enforces the per-origin pending transaction limit, and evicts the lowest-fee-rate
+    /// transactions if the mempool is full.
     /// Carry out the mempool admission test before adding.
     /// Don't call directly; use submit()
     fn try_add_tx<'a>(tx: &mut MemPoolTx<'a>, 
@@ -641,8 +916,8 @@ impl MemPoolDB {
         };
 
         // if so, is this a replace-by-fee? or a replace-in-chain-tip?
-        let add_tx = if let Some(prior_tx) = prior_tx {
-            if estimated_fee > prior_tx.estimated_fee {
+        let add_tx = if let Some(ref prior_tx) = prior_tx {
+            if MemPoolDB::is_fee_bump(tx.limits, prior_tx.estimated_fee, estimated_fee) {
                 // is this a replace-by-fee ?
                 true
             } else if !tx.is_block_in_fork(&prior_tx.burn_header_hash, &prior_tx.block_header_hash,
@@ -664,6 +939,16 @@ impl MemPoolDB {
             return Err(MemPoolRejection::ConflictingNonceInMempool);
         }
 
+        // does this origin already have too many pending transactions?
+        let num_origin_txs = MemPoolDB::get_num_txs_for_origin(tx, origin_address, prior_tx.as_ref().map(|md| &md.txid))?;
+        if num_origin_txs >= tx.limits.max_txs_per_origin {
+            info!("TX origin has too many pending transactions: txid={}, origin_addr={}, num_txs={}, limit={}",
+                  txid, origin_address, num_origin_txs, tx.limits.max_txs_per_origin);
+            return Err(MemPoolRejection::TooManyPendingTransactions(tx.limits.max_txs_per_origin));
+        }
+
+        MemPoolDB::make_room_for_tx(tx, prior_tx.as_ref(), length, fee_rate)?;
+
         let sql = "INSERT OR REPLACE INTO mempool (
             txid,
             origin_address,
@@ -830,9 +1115,11 @@ mod tests {
         StacksTransaction, TransactionSmartContract, TransactionContractCall, StacksAddress };
 
     use util::db::{DBConn, FromRow};
-    use super::MemPoolDB;
+    use super::{MemPoolDB, MemPoolTx, MemPoolLimits};
+
+    use rusqlite::NO_PARAMS;
 
-    use burnchains::BurnchainHeaderHash;
+    use burnchains::{BurnchainHeaderHash, Txid};
     use chainstate::stacks::test::codec_all_transactions;
     use chainstate::stacks::db::test::chainstate_path;
     use chainstate::stacks::db::test::instantiate_chainstate;
@@ -847,7 +1134,31 @@ mod tests {
     fn mempool_db_init() {
         let _chainstate = instantiate_chainstate(false, 0x80000000, "mempool_db_init");
         let chainstate_path = chainstate_path("mempool_db_init");
-        let _mempool = MemPoolDB::open(false, 0x80000000, &chainstate_path).unwrap();
+        let mempool = MemPoolDB::open(false, 0x80000000, &chainstate_path).unwrap();
+
+        let has_fee_rate_index = |mempool: &MemPoolDB| -> bool {
+            let count : i64 = mempool.db.query_row("SELECT COUNT(*) FROM sqlite_master WHERE type = 'index' AND name = 'by_fee_rate'",
+                                                   NO_PARAMS, |row| row.get(0)).unwrap();
+            count == 1
+        };
+        assert!(has_fee_rate_index(&mempool));
+
+        // a mempool created before the index existed gets it when it's next opened
+        mempool.db.execute("DROP INDEX by_fee_rate", NO_PARAMS).unwrap();
+        assert!(!has_fee_rate_index(&mempool));
+        drop(mempool);
+
+        let mempool = MemPoolDB::open(false, 0x80000000, &chainstate_path).unwrap();
+        assert!(has_fee_rate_index(&mempool));
+
+        // likewise for the size counter, which starts out with the size of what's already there
+        mempool.db.execute_batch("DROP TRIGGER mempool_size_insert; DROP TRIGGER mempool_size_delete; DROP TABLE mempool_size;").unwrap();
+        mempool.db.execute("INSERT INTO mempool (txid, origin_address, origin_nonce, sponsor_address, sponsor_nonce, estimated_fee, fee_rate, length, burn_header_hash, block_header_hash, height, accept_time, tx) \
+                            VALUES ('00', 'a', 0, 'a', 0, 10, 1, 10, '01', '02', 1, 0, x'00')", NO_PARAMS).unwrap();
+        drop(mempool);
+
+        let mempool = MemPoolDB::open(false, 0x80000000, &chainstate_path).unwrap();
+        assert_eq!(MemPoolDB::get_total_size(&mempool.db).unwrap(), 10);
     }
 
     #[test]
@@ -1099,4 +1410,178 @@ mod tests {
         let txs = MemPoolDB::get_txs_after(&mempool.db, &BurnchainHeaderHash([0x1; 32]), &BlockHeaderHash([0x2; 32]), 0, num_txs).unwrap();
         assert_eq!(txs.len(), 0);
     }
+
+    /// Add a copy of `tx` with the given origin nonce and fee rate, on behalf of the given origin address.
+    fn add_test_tx(mempool_tx: &mut MemPoolTx, tx: &StacksTransaction, origin_address: &StacksAddress, origin_nonce: u64, fee_rate: u64) -> Result<Txid, MemPoolRejection> {
+        let mut tx = tx.clone();
+        tx.set_origin_nonce(origin_nonce);
+        tx.set_fee_rate(fee_rate);
+
+        let txid = tx.txid();
+        let tx_bytes = tx.serialize_to_vec();
+        let estimated_fee = fee_rate * (tx_bytes.len() as u64);
+
+        MemPoolDB::try_add_tx(mempool_tx, &BurnchainHeaderHash([0x1; 32]), &BlockHeaderHash([0x2; 32]),
+                              txid.clone(), tx_bytes, estimated_fee, fee_rate, 100, origin_address, origin_nonce, origin_address, origin_nonce)?;
+        Ok(txid)
+    }
+
+    fn test_tx() -> StacksTransaction {
+        let mut txs = codec_all_transactions(&TransactionVersion::Testnet, 0x80000000, &TransactionAnchorMode::Any, &TransactionPostConditionMode::Allow);
+        txs.pop().unwrap()
+    }
+
+    #[test]
+    fn mempool_replace_by_fee_min_bump() {
+        let _chainstate = instantiate_chainstate(false, 0x80000000, "mempool_replace_by_fee_min_bump");
+        let chainstate_path = chainstate_path("mempool_replace_by_fee_min_bump");
+        let mut mempool = MemPoolDB::open(false, 0x80000000, &chainstate_path).unwrap();
+        mempool.set_limits(MemPoolLimits {
+            replace_by_fee_min_bump: 10,
+            ..MemPoolLimits::default()
+        });
+
+        let tx = test_tx();
+        let origin_address = StacksAddress { version: 22, bytes: Hash160::from_data(&[0; 32]) };
+        let mut mempool_tx = mempool.tx_begin().unwrap();
+
+        let first_txid = add_test_tx(&mut mempool_tx, &tx, &origin_address, 0, 100).unwrap();
+
+        // not enough of a bump
+        match add_test_tx(&mut mempool_tx, &tx, &origin_address, 0, 109).unwrap_err() {
+            MemPoolRejection::ConflictingNonceInMempool => {},
+            e => panic!("Unexpected rejection: {:?}", &e)
+        };
+        assert!(MemPoolDB::db_has_tx(&mempool_tx, &first_txid).unwrap());
+
+        // enough of a bump
+        let second_txid = add_test_tx(&mut mempool_tx, &tx, &origin_address, 0, 110).unwrap();
+        assert!(!MemPoolDB::db_has_tx(&mempool_tx, &first_txid).unwrap());
+        assert!(MemPoolDB::db_has_tx(&mempool_tx, &second_txid).unwrap());
+        assert_eq!(MemPoolDB::get_num_txs(&mempool_tx).unwrap(), 1);
+    }
+
+    #[test]
+    fn mempool_max_txs_per_origin() {
+        let _chainstate = instantiate_chainstate(false, 0x80000000, "mempool_max_txs_per_origin");
+        let chainstate_path = chainstate_path("mempool_max_txs_per_origin");
+        let mut mempool = MemPoolDB::open(false, 0x80000000, &chainstate_path).unwrap();
+        mempool.set_limits(MemPoolLimits {
+            max_txs_per_origin: 2,
+            ..MemPoolLimits::default()
+        });
+
+        let tx = test_tx();
+        let origin_address = StacksAddress { version: 22, bytes: Hash160::from_data(&[0; 32]) };
+        let other_address = StacksAddress { version: 22, bytes: Hash160::from_data(&[1; 32]) };
+        let mut mempool_tx = mempool.tx_begin().unwrap();
+
+        add_test_tx(&mut mempool_tx, &tx, &origin_address, 0, 100).unwrap();
+        add_test_tx(&mut mempool_tx, &tx, &origin_address, 1, 100).unwrap();
+
+        match add_test_tx(&mut mempool_tx, &tx, &origin_address, 2, 100).unwrap_err() {
+            MemPoolRejection::TooManyPendingTransactions(2) => {},
+            e => panic!("Unexpected rejection: {:?}", &e)
+        };
+
+        // replacing a pending transaction is still allowed
+        add_test_tx(&mut mempool_tx, &tx, &origin_address, 1, 200).unwrap();
+
+        // other origins are unaffected
+        add_test_tx(&mut mempool_tx, &tx, &other_address, 2, 100).unwrap();
+
+        let origin_txs = MemPoolDB::get_txs_for_address(&mempool_tx, &origin_address).unwrap();
+        assert_eq!(origin_txs.len(), 2);
+        assert_eq!(origin_txs[0].metadata.origin_nonce, 0);
+        assert_eq!(origin_txs[1].metadata.origin_nonce, 1);
+        assert_eq!(origin_txs[1].metadata.fee_rate, 200);
+    }
+
+    #[test]
+    fn mempool_evict_lowest_fee_rate() {
+        let _chainstate = instantiate_chainstate(false, 0x80000000, "mempool_evict_lowest_fee_rate");
+        let chainstate_path = chainstate_path("mempool_evict_lowest_fee_rate");
+        let mut mempool = MemPoolDB::open(false, 0x80000000, &chainstate_path).unwrap();
+
+        let tx = test_tx();
+        let tx_len = tx.serialize_to_vec().len() as u64;
+        mempool.set_limits(MemPoolLimits {
+            max_size: 3 * tx_len,
+            ..MemPoolLimits::default()
+        });
+
+        let addrs : Vec<_> = (0..5u8).map(|i| StacksAddress { version: 22, bytes: Hash160::from_data(&[i; 32]) }).collect();
+        let mut mempool_tx = mempool.tx_begin().unwrap();
+
+        let txid_10 = add_test_tx(&mut mempool_tx, &tx, &addrs[0], 0, 10).unwrap();
+        let txid_20 = add_test_tx(&mut mempool_tx, &tx, &addrs[1], 0, 20).unwrap();
+        let txid_30 = add_test_tx(&mut mempool_tx, &tx, &addrs[2], 0, 30).unwrap();
+        assert_eq!(MemPoolDB::get_total_size(&mempool_tx).unwrap(), 3 * tx_len);
+
+        // too low a fee rate to get in
+        match add_test_tx(&mut mempool_tx, &tx, &addrs[3], 0, 9).unwrap_err() {
+            MemPoolRejection::MemPoolFull(9, 11) => {},
+            e => panic!("Unexpected rejection: {:?}", &e)
+        };
+        assert_eq!(MemPoolDB::get_num_txs(&mempool_tx).unwrap(), 3);
+
+        // evicts the lowest-fee-rate tx
+        let txid_25 = add_test_tx(&mut mempool_tx, &tx, &addrs[4], 0, 25).unwrap();
+        assert!(!MemPoolDB::db_has_tx(&mempool_tx, &txid_10).unwrap());
+        assert!(MemPoolDB::db_has_tx(&mempool_tx, &txid_20).unwrap());
+        assert!(MemPoolDB::db_has_tx(&mempool_tx, &txid_30).unwrap());
+        assert!(MemPoolDB::db_has_tx(&mempool_tx, &txid_25).unwrap());
+        assert_eq!(MemPoolDB::get_total_size(&mempool_tx).unwrap(), 3 * tx_len);
+
+        // a replace-by-fee doesn't evict anything, since it frees up the space of the tx it replaces
+        let txid_21 = add_test_tx(&mut mempool_tx, &tx, &addrs[1], 0, 21).unwrap();
+        assert!(!MemPoolDB::db_has_tx(&mempool_tx, &txid_20).unwrap());
+        assert!(MemPoolDB::db_has_tx(&mempool_tx, &txid_21).unwrap());
+        assert_eq!(MemPoolDB::get_num_txs(&mempool_tx).unwrap(), 3);
+        assert_eq!(MemPoolDB::get_total_size(&mempool_tx).unwrap(), 3 * tx_len);
+
+        let txids = MemPoolDB::get_txids(&mempool_tx, 0, 10).unwrap();
+        assert_eq!(txids.len(), 3);
+        let txids = MemPoolDB::get_txids(&mempool_tx, 2, 10).unwrap();
+        assert_eq!(txids.len(), 1);
+
+        MemPoolDB::garbage_collect(&mut mempool_tx, 101).unwrap();
+        assert_eq!(MemPoolDB::get_num_txs(&mempool_tx).unwrap(), 0);
+        assert_eq!(MemPoolDB::get_total_size(&mempool_tx).unwrap(), 0);
+    }
+
+    #[test]
+    fn mempool_nonce_info_for_address() {
+        let _chainstate = instantiate_chainstate(false, 0x80000000, "mempool_nonce_info_for_address");
+        let chainstate_path = chainstate_path("mempool_nonce_info_for_address");
+        let mut mempool = MemPoolDB::open(false, 0x80000000, &chainstate_path).unwrap();
+
+        let tx = test_tx();
+        let addr = StacksAddress { version: 22, bytes: Hash160::from_data(&[0; 32]) };
+        let other_addr = StacksAddress { version: 22, bytes: Hash160::from_data(&[1; 32]) };
+        let mut mempool_tx = mempool.tx_begin().unwrap();
+
+        // nothing pending
+        let nonce_info = MemPoolDB::get_nonce_info_for_address(&mempool_tx, &addr, 3).unwrap();
+        assert_eq!(nonce_info.pending_txids.len(), 0);
+        assert_eq!(nonce_info.missing_nonces.len(), 0);
+
+        // stale
+        add_test_tx(&mut mempool_tx, &tx, &addr, 1, 100).unwrap();
+
+        let txid_6 = add_test_tx(&mut mempool_tx, &tx, &addr, 6, 100).unwrap();
+        let txid_3 = add_test_tx(&mut mempool_tx, &tx, &addr, 3, 100).unwrap();
+        let txid_4 = add_test_tx(&mut mempool_tx, &tx, &addr, 4, 100).unwrap();
+        add_test_tx(&mut mempool_tx, &tx, &other_addr, 5, 100).unwrap();
+
+        let nonce_info = MemPoolDB::get_nonce_info_for_address(&mempool_tx, &addr, 3).unwrap();
+        assert_eq!(MemPoolDB::get_next_nonce_for_address(&mempool_tx, &addr).unwrap(), 7);
+        assert_eq!(nonce_info.pending_txids, vec![txid_3, txid_4, txid_6]);
+        assert_eq!(nonce_info.missing_nonces, vec![5]);
+
+        let nonce_info = MemPoolDB::get_nonce_info_for_address(&mempool_tx, &other_addr, 2).unwrap();
+        assert_eq!(MemPoolDB::get_next_nonce_for_address(&mempool_tx, &other_addr).unwrap(), 6);
+        assert_eq!(nonce_info.pending_txids.len(), 1);
+        assert_eq!(nonce_info.missing_nonces, vec![2, 3, 4]);
+    }
 }
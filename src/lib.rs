@@ -33,6 +33,8 @@ extern crate curve25519_dalek;
 extern crate ed25519_dalek;
 #[macro_use] extern crate lazy_static;
 extern crate sha2;
+extern crate sha1;
+extern crate base64;
 extern crate sha3;
 extern crate ripemd160;
 extern crate regex;
@@ -67,6 +69,7 @@ pub mod deps;
 pub mod vm;
 
 pub mod clarity;
+pub mod lsp;
 
 pub mod monitoring;
 
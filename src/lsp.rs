@@ -0,0 +1,786 @@
+/*
+ copyright: (c) 2013-2020 by Blockstack PBC, a public benefit corporation.
+
+ This file is part of Blockstack.
+
+ Blockstack is free software. You may redistribute or modify
+ it under the terms of the GNU General Public License as published by
+ the Free Software Foundation, either version 3 of the License or
+ (at your option) any later version.
+
+ Blockstack is distributed in the hope that it will be useful,
+ but WITHOUT ANY WARRANTY, including without the implied warranty of
+ MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ GNU General Public License for more details.
+
+ You should have received a copy of the GNU General Public License
+ along with Blockstack. If not, see <http://www.gnu.org/licenses/>.
+*/
+
+// A language server for Clarity, speaking the Language Server Protocol over stdio.
+//
+// Every open document is parsed and analyzed on each change.  Contracts are named after their
+//  files, so `.counter` refers to `counter.clar`: other open documents and `.clar` files beside
+//  the document are analyzed first, so that `contract-call?`s to them can be checked.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::io::{BufRead, Write};
+
+use regex::Regex;
+use serde_json::Value as JsonValue;
+use url::Url;
+
+use vm::ast::build_ast;
+use vm::analysis;
+use vm::analysis::{AnalysisDatabase, AnalysisPass, ContractAnalysis};
+use vm::analysis::lint_checker::LintChecker;
+use vm::costs::LimitedCostTracker;
+use vm::database::MemoryBackingStore;
+use vm::diagnostic::{Diagnostic, Level};
+use vm::docs::{lookup_function_reference, lookup_define_reference, lookup_keyword_reference, native_names};
+use vm::representations::{Span, SymbolicExpression, SymbolicExpressionType};
+use vm::types::{FunctionType, PrincipalData, QualifiedContractIdentifier, Value};
+
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+// LSP enumerations
+const SEVERITY_ERROR: u64 = 1;
+const SEVERITY_WARNING: u64 = 2;
+const TEXT_DOCUMENT_SYNC_FULL: u64 = 1;
+const COMPLETION_FUNCTION: u64 = 3;
+const COMPLETION_VARIABLE: u64 = 6;
+const COMPLETION_KEYWORD: u64 = 14;
+const COMPLETION_CONSTANT: u64 = 21;
+
+lazy_static! {
+    // finds definitions even in documents which don't parse yet, for completion
+    static ref DEFINITION_REGEX: Regex = Regex::new(r"\((define-[a-z-]+)\s+\(?\s*([^\s()]+)").unwrap();
+}
+
+/// Read a message framed by a `Content-Length` header, or None at the end of the input.
+pub fn read_message<R: BufRead>(input: &mut R) -> io::Result<Option<JsonValue>> {
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let mut header = line.splitn(2, ':');
+        if let (Some(name), Some(value)) = (header.next(), header.next()) {
+            if name.trim().eq_ignore_ascii_case("Content-Length") {
+                content_length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let content_length = content_length
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Missing Content-Length header"))?;
+    let mut content = vec![0; content_length];
+    input.read_exact(&mut content)?;
+    serde_json::from_slice(&content)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+pub fn write_message<W: Write>(output: &mut W, message: &JsonValue) -> io::Result<()> {
+    let content = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", content.len(), content)?;
+    output.flush()
+}
+
+/// Serve requests until the client says to exit.  Returns whether the client shut the server
+///  down first, as the protocol expects.
+pub fn run<R: BufRead, W: Write>(mut input: R, mut output: W) -> io::Result<bool> {
+    let mut server = LanguageServer::new();
+    while let Some(message) = read_message(&mut input)? {
+        for reply in server.handle_message(&message).iter() {
+            write_message(&mut output, reply)?;
+        }
+        if server.exited {
+            break;
+        }
+    }
+    Ok(server.shut_down)
+}
+
+fn response(id: JsonValue, result: JsonValue) -> JsonValue {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn error_response(id: JsonValue, code: i64, message: String) -> JsonValue {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn notification(method: &str, params: JsonValue) -> JsonValue {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+// Contracts are named after their files, when that makes a legal contract name.
+fn contract_identifier_for(uri: &Url) -> QualifiedContractIdentifier {
+    uri.to_file_path().ok()
+        .and_then(|path| path.file_stem().and_then(|stem| stem.to_str()).map(|stem| stem.to_string()))
+        .and_then(|stem| QualifiedContractIdentifier::local(&stem).ok())
+        .unwrap_or_else(QualifiedContractIdentifier::transient)
+}
+
+// Positions in the protocol are zero-based and count UTF-16 code units, while spans are
+//  one-based and count bytes.
+fn to_column(line: &str, character: u64) -> u32 {
+    let mut units = 0;
+    for (index, c) in line.char_indices() {
+        if units >= character {
+            return index as u32 + 1;
+        }
+        units += c.len_utf16() as u64;
+    }
+    line.len() as u32 + 1
+}
+
+fn to_character(line: &str, column: u32) -> u64 {
+    let bytes = (column as usize).saturating_sub(1).min(line.len());
+    match line.get(..bytes) {
+        Some(prefix) => prefix.encode_utf16().count() as u64,
+        None => bytes as u64
+    }
+}
+
+fn range_of(source: &str, span: &Span) -> JsonValue {
+    if span.start_line == 0 {
+        return json!({ "start": { "line": 0, "character": 0 }, "end": { "line": 0, "character": 0 } });
+    }
+    let lines: Vec<_> = source.split('\n').collect();
+    let line = |number: u32| lines.get(number as usize - 1).cloned().unwrap_or("");
+    json!({
+        "start": { "line": span.start_line - 1,
+                   "character": to_character(line(span.start_line), span.start_column) },
+        "end": { "line": span.end_line - 1,
+                 "character": to_character(line(span.end_line), span.end_column + 1) }
+    })
+}
+
+// The source text of a span, which ends on (and includes) its end column.
+fn span_text<'a>(source: &'a str, span: &Span) -> Option<&'a str> {
+    let mut offset = 0;
+    let mut start = None;
+    let mut end = None;
+    for (index, line) in source.split('\n').enumerate() {
+        let number = index as u32 + 1;
+        if number == span.start_line {
+            start = Some(offset + span.start_column as usize - 1);
+        }
+        if number == span.end_line {
+            end = Some(offset + span.end_column as usize);
+        }
+        offset += line.len() + 1;
+    }
+    source.get(start?..end?)
+}
+
+fn span_contains(span: &Span, line: u32, column: u32) -> bool {
+    (span.start_line, span.start_column) <= (line, column) &&
+        (line, column) <= (span.end_line, span.end_column)
+}
+
+// The expressions enclosing a position, outermost first.  Lists added by desugaring (such as
+//  the pairs of a tuple literal) have no span, so they're searched but not included.
+fn find_path<'a>(expressions: &'a [SymbolicExpression], line: u32, column: u32, path: &mut Vec<&'a SymbolicExpression>) -> bool {
+    for expr in expressions.iter() {
+        let synthesized = expr.span.start_line == 0;
+        if !synthesized && !span_contains(&expr.span, line, column) {
+            continue;
+        }
+        match expr.expr {
+            SymbolicExpressionType::List(ref list) => {
+                if !synthesized {
+                    path.push(expr);
+                    find_path(list, line, column, path);
+                    return true;
+                }
+                if find_path(list, line, column, path) {
+                    return true;
+                }
+            },
+            _ => {
+                if !synthesized {
+                    path.push(expr);
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
+// The define function a top-level expression calls, and its arguments, if it's a definition.
+fn parse_define(expr: &SymbolicExpression) -> Option<(&str, &[SymbolicExpression])> {
+    let (define_name, args) = expr.match_list()?.split_first()?;
+    let define_name = define_name.match_atom()?.as_str();
+    lookup_define_reference(define_name)?;
+    Some((define_name, args))
+}
+
+// The expression naming the definition made by a top-level expression, if it makes one.
+fn definition_name(expr: &SymbolicExpression) -> Option<&SymbolicExpression> {
+    let (define_name, args) = parse_define(expr)?;
+    match define_name {
+        "define-private" | "define-public" | "define-read-only" =>
+            args.get(0)?.match_list()?.get(0),
+        "impl-trait" => None,
+        _ => args.get(0)
+    }
+}
+
+fn find_definition<'a>(expressions: &'a [SymbolicExpression], name: &str) -> Option<(&'a SymbolicExpression, &'a SymbolicExpression)> {
+    expressions.iter()
+        .filter_map(|expr| definition_name(expr).map(|name_expr| (expr, name_expr)))
+        .find(|(_, name_expr)| name_expr.match_atom().map(|atom| atom.as_str() == name).unwrap_or(false))
+}
+
+// The binding of `name` by an enclosing function signature, `let` or `match`, innermost first.
+fn find_local_binding<'a>(path: &[&'a SymbolicExpression], name: &str) -> Option<&'a SymbolicExpression> {
+    let names = |expr: &'a SymbolicExpression| expr.match_atom().map(|atom| atom.as_str() == name).unwrap_or(false);
+    for expr in path.iter().rev() {
+        let list = match expr.match_list() {
+            Some(list) => list,
+            None => continue
+        };
+        let binding = match list.get(0).and_then(|head| head.match_atom()).map(|head| head.as_str()) {
+            Some("let") => list.get(1).and_then(|bindings| bindings.match_list())
+                .and_then(|bindings| bindings.iter()
+                          .filter_map(|binding| binding.match_list().and_then(|pair| pair.get(0)))
+                          .find(|binding| names(*binding))),
+            Some("define-private") | Some("define-public") | Some("define-read-only") =>
+                list.get(1).and_then(|signature| signature.match_list())
+                .and_then(|signature| signature.iter().skip(1)
+                          .filter_map(|arg| arg.match_list().and_then(|pair| pair.get(0)))
+                          .find(|arg| names(*arg))),
+            // `(match opt name some-branch none-branch)` or `(match resp ok-name ok-branch err-name err-branch)`
+            Some("match") => [2, 4].iter()
+                .filter(|index| **index == 2 || list.len() == 6)
+                .filter_map(|index| list.get(*index))
+                .find(|binding| names(*binding)),
+            _ => None
+        };
+        if binding.is_some() {
+            return binding;
+        }
+    }
+    None
+}
+
+// If `path` ends at the function name of a `contract-call?` to a known contract, the contract.
+fn contract_call_target(path: &[&SymbolicExpression]) -> Option<QualifiedContractIdentifier> {
+    if path.len() < 2 {
+        return None;
+    }
+    let list = path[path.len() - 2].match_list()?;
+    if list.get(0)?.match_atom()?.as_str() != "contract-call?" || list.get(2)?.id != path[path.len() - 1].id {
+        return None;
+    }
+    match list.get(1)?.match_literal_value()? {
+        Value::Principal(PrincipalData::Contract(contract_identifier)) => Some(contract_identifier.clone()),
+        _ => None
+    }
+}
+
+
+fn describe_native(name: &str) -> Option<String> {
+    let (signature, input_type, output_type, description, example) =
+        if let Some(api) = lookup_function_reference(name).or_else(|| lookup_define_reference(name)) {
+            (api.signature, api.input_type, api.output_type, api.description, api.example)
+        } else {
+            let api = lookup_keyword_reference(name)?;
+            (api.name.to_string(), "".to_string(), api.output_type.to_string(),
+             api.description.to_string(), api.example.to_string())
+        };
+
+    let mut hover = format!("```clarity\n{}\n```\n", signature);
+    if !input_type.is_empty() {
+        hover.push_str(&format!("input: `{}`  \n", input_type));
+    }
+    hover.push_str(&format!("output: `{}`\n\n{}\n\n```clarity\n{}\n```", output_type, description, example.trim_end()));
+    Some(hover)
+}
+
+// How a definition reads in the source: a function's signature, or the whole of any other definition.
+fn definition_header(source: &str, define: &SymbolicExpression) -> Option<String> {
+    let (define_name, args) = parse_define(define)?;
+    match define_name {
+        "define-private" | "define-public" | "define-read-only" =>
+            Some(format!("({} {})", define_name, span_text(source, &args.get(0)?.span)?)),
+        _ => span_text(source, &define.span).map(|text| text.to_string())
+    }
+}
+
+fn describe_type(contract_analysis: &ContractAnalysis, name: &str) -> Option<String> {
+    let function_type = contract_analysis.get_public_function_type(name)
+        .or_else(|| contract_analysis.get_read_only_function_type(name))
+        .or_else(|| contract_analysis.get_private_function(name));
+    if let Some(FunctionType::Fixed(function)) = function_type {
+        return Some(format!("returns: `{}`", function.returns));
+    }
+    if let Some(variable_type) = contract_analysis.get_variable_type(name)
+        .or_else(|| contract_analysis.get_persisted_variable_type(name)) {
+        return Some(format!("type: `{}`", variable_type));
+    }
+    if let Some((key_type, value_type)) = contract_analysis.get_map_type(name) {
+        return Some(format!("key: `{}`  \nvalue: `{}`", key_type, value_type));
+    }
+    contract_analysis.non_fungible_tokens.get(name)
+        .map(|asset_type| format!("asset: `{}`", asset_type))
+}
+
+fn location(uri: &Url, source: &str, span: &Span) -> JsonValue {
+    json!({ "uri": uri.as_str(), "range": range_of(source, span) })
+}
+
+struct DocumentAnalysis {
+    // empty when the document doesn't parse
+    expressions: Vec<SymbolicExpression>,
+    contract_analysis: Option<ContractAnalysis>,
+    diagnostics: Vec<Diagnostic>
+}
+
+pub struct LanguageServer {
+    documents: BTreeMap<String, String>,
+    shut_down: bool,
+    exited: bool
+}
+
+impl LanguageServer {
+    pub fn new() -> LanguageServer {
+        LanguageServer { documents: BTreeMap::new(), shut_down: false, exited: false }
+    }
+
+    /// Handle a request or notification from the client, returning the messages to send back.
+    pub fn handle_message(&mut self, message: &JsonValue) -> Vec<JsonValue> {
+        let method = message["method"].as_str().unwrap_or("");
+        let params = &message["params"];
+        let id = message.get("id").cloned();
+
+        match (method, id) {
+            ("initialize", Some(id)) => vec![response(id, json!({
+                "capabilities": {
+                    "textDocumentSync": TEXT_DOCUMENT_SYNC_FULL,
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                    "completionProvider": { "triggerCharacters": ["("] }
+                },
+                "serverInfo": { "name": "clarity-lsp" }
+            }))],
+            ("shutdown", Some(id)) => {
+                self.shut_down = true;
+                vec![response(id, JsonValue::Null)]
+            },
+            ("exit", _) => {
+                self.exited = true;
+                vec![]
+            },
+            ("textDocument/didOpen", None) => {
+                if let (Some(uri), Some(text)) = (document_uri(params), params["textDocument"]["text"].as_str()) {
+                    self.documents.insert(uri.to_string(), text.to_string());
+                }
+                self.publish_diagnostics()
+            },
+            ("textDocument/didChange", None) => {
+                // the server asks for full syncing, so the last change holds the whole document
+                let text = params["contentChanges"].as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+                if let (Some(uri), Some(text)) = (document_uri(params), text) {
+                    self.documents.insert(uri.to_string(), text.to_string());
+                }
+                self.publish_diagnostics()
+            },
+            ("textDocument/didSave", None) => self.publish_diagnostics(),
+            ("textDocument/didClose", None) => {
+                let mut messages = vec![];
+                if let Some(uri) = document_uri(params) {
+                    self.documents.remove(uri.as_str());
+                    messages.push(notification("textDocument/publishDiagnostics",
+                                               json!({ "uri": uri.as_str(), "diagnostics": [] })));
+                }
+                messages.extend(self.publish_diagnostics());
+                messages
+            },
+            ("textDocument/hover", Some(id)) => match self.position(params) {
+                Some((uri, line, column)) => {
+                    let hover = self.hover(&uri, line, column)
+                        .map(|value| json!({ "contents": { "kind": "markdown", "value": value } }))
+                        .unwrap_or(JsonValue::Null);
+                    vec![response(id, hover)]
+                },
+                None => vec![error_response(id, INVALID_PARAMS, "Unknown document or position".into())]
+            },
+            ("textDocument/definition", Some(id)) => match self.position(params) {
+                Some((uri, line, column)) => vec![response(id, self.definition(&uri, line, column).unwrap_or(JsonValue::Null))],
+                None => vec![error_response(id, INVALID_PARAMS, "Unknown document or position".into())]
+            },
+            ("textDocument/completion", Some(id)) => match document_uri(params) {
+                Some(uri) => vec![response(id, JsonValue::Array(self.completion(&uri)))],
+                None => vec![error_response(id, INVALID_PARAMS, "Unknown document".into())]
+            },
+            (_, Some(id)) => vec![error_response(id, METHOD_NOT_FOUND, format!("Unsupported method: {}", method))],
+            // other notifications can be ignored
+            (_, None) => vec![]
+        }
+    }
+
+    // A change to one document can break another which calls it, so every document is rechecked.
+    fn publish_diagnostics(&self) -> Vec<JsonValue> {
+        self.documents.iter()
+            .filter_map(|(uri, source)| {
+                let uri = Url::parse(uri).ok()?;
+                let diagnostics: Vec<_> = self.analyze(&uri)?.diagnostics.iter()
+                    .map(|diagnostic| diagnostic_json(source, diagnostic))
+                    .collect();
+                Some(notification("textDocument/publishDiagnostics",
+                                  json!({ "uri": uri.as_str(), "diagnostics": diagnostics })))
+            })
+            .collect()
+    }
+
+    // The document and one-based line and column of a request's position.
+    fn position(&self, params: &JsonValue) -> Option<(Url, u32, u32)> {
+        let uri = document_uri(params)?;
+        let line = params["position"]["line"].as_u64()?;
+        let character = params["position"]["character"].as_u64()?;
+        let line_text = self.documents.get(uri.as_str())?.split('\n').nth(line as usize)?;
+        let column = to_column(line_text, character);
+        Some((uri, line as u32 + 1, column))
+    }
+
+    fn analyze(&self, uri: &Url) -> Option<DocumentAnalysis> {
+        let source = self.documents.get(uri.as_str())?;
+        let mut marf = MemoryBackingStore::new();
+        let mut db = marf.as_analysis_db();
+        self.deploy_dependencies(uri, &mut db);
+
+        let contract_identifier = contract_identifier_for(uri);
+        let mut contract_ast = match build_ast(&contract_identifier, source, &mut ()) {
+            Ok(contract_ast) => contract_ast,
+            Err(e) => return Some(DocumentAnalysis {
+                expressions: vec![], contract_analysis: None, diagnostics: vec![e.diagnostic] })
+        };
+
+        let analysis = analysis::run_analysis(&contract_identifier, &mut contract_ast.expressions, &mut db,
+                                              false, LimitedCostTracker::new_max_limit());
+        let (contract_analysis, diagnostics) = match analysis {
+            Ok(mut contract_analysis) => {
+                let mut diagnostics = vec![];
+                if let Err(e) = db.execute(|db| LintChecker::run_pass(&mut contract_analysis, db)) {
+                    diagnostics.push(e.diagnostic);
+                }
+                diagnostics.extend(contract_analysis.warnings.drain(..));
+                (Some(contract_analysis), diagnostics)
+            },
+            Err((e, _)) => (None, vec![e.diagnostic])
+        };
+
+        Some(DocumentAnalysis { expressions: contract_ast.expressions, contract_analysis, diagnostics })
+    }
+
+    // Analyze the other open documents, and the contracts beside this one, so that it can call
+    //  them.  Contracts which call each other have to be deployed in order, so this repeats until
+    //  no more can be.
+    fn deploy_dependencies(&self, uri: &Url, db: &mut AnalysisDatabase) {
+        let mut pending: BTreeMap<String, String> = BTreeMap::new();
+        if let Some(directory) = uri.to_file_path().ok().and_then(|path| path.parent().map(|parent| parent.to_path_buf())) {
+            if let Ok(entries) = fs::read_dir(directory) {
+                for path in entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+                    if path.extension().map(|extension| extension == "clar").unwrap_or(false) {
+                        if let (Ok(file_uri), Ok(source)) = (Url::from_file_path(&path), fs::read_to_string(&path)) {
+                            pending.insert(file_uri.to_string(), source);
+                        }
+                    }
+                }
+            }
+        }
+        for (open_uri, source) in self.documents.iter() {
+            pending.insert(open_uri.clone(), source.clone());
+        }
+        pending.remove(uri.as_str());
+
+        let own_identifier = contract_identifier_for(uri);
+        let mut deployed_any = true;
+        while deployed_any {
+            deployed_any = false;
+            let deployable: Vec<_> = pending.iter()
+                .filter(|(dependency_uri, source)| {
+                    let contract_identifier = match Url::parse(dependency_uri) {
+                        Ok(dependency_uri) => contract_identifier_for(&dependency_uri),
+                        Err(_) => return false
+                    };
+                    if contract_identifier == own_identifier || contract_identifier == QualifiedContractIdentifier::transient() {
+                        return false;
+                    }
+                    match build_ast(&contract_identifier, source, &mut ()) {
+                        Ok(mut contract_ast) => db.execute(|db| {
+                            db.insert_contract_hash(&contract_identifier, source);
+                            analysis::type_check(&contract_identifier, &mut contract_ast.expressions, db, true)
+                        }).is_ok(),
+                        Err(_) => false
+                    }
+                })
+                .map(|(dependency_uri, _)| dependency_uri.clone())
+                .collect();
+            for dependency_uri in deployable.iter() {
+                pending.remove(dependency_uri);
+                deployed_any = true;
+            }
+        }
+    }
+
+    // The document defining a contract: `.name` is looked for in `name.clar` beside `uri`, then
+    //  among the open documents.
+    fn find_contract(&self, uri: &Url, contract_identifier: &QualifiedContractIdentifier) -> Option<(Url, String)> {
+        if let Ok(candidate) = uri.join(&format!("{}.clar", contract_identifier.name.as_str())) {
+            if let Some(source) = self.documents.get(candidate.as_str()) {
+                return Some((candidate, source.clone()));
+            }
+            if let Some(source) = candidate.to_file_path().ok().and_then(|path| fs::read_to_string(path).ok()) {
+                return Some((candidate, source));
+            }
+        }
+        self.documents.iter()
+            .filter_map(|(open_uri, source)| Url::parse(open_uri).ok().map(|open_uri| (open_uri, source)))
+            .find(|(open_uri, _)| contract_identifier_for(open_uri).name == contract_identifier.name)
+            .map(|(open_uri, source)| (open_uri, source.clone()))
+    }
+
+    fn parse_contract(&self, uri: &Url, contract_identifier: &QualifiedContractIdentifier) -> Option<(Url, String, Vec<SymbolicExpression>)> {
+        let (contract_uri, source) = self.find_contract(uri, contract_identifier)?;
+        let expressions = build_ast(contract_identifier, &source, &mut ()).ok()?.expressions;
+        Some((contract_uri, source, expressions))
+    }
+
+    fn hover(&self, uri: &Url, line: u32, column: u32) -> Option<String> {
+        let source = self.documents.get(uri.as_str())?;
+        let document = self.analyze(uri)?;
+        let mut path = vec![];
+        find_path(&document.expressions, line, column, &mut path);
+        let name = path.last()?.match_atom()?;
+
+        if let Some(contract_identifier) = contract_call_target(&path) {
+            let (_, callee_source, callee_expressions) = self.parse_contract(uri, &contract_identifier)?;
+            let (define, _) = find_definition(&callee_expressions, name)?;
+            return Some(format!("```clarity\n{}\n```", definition_header(&callee_source, define)?));
+        }
+
+        if find_local_binding(&path, name).is_none() {
+            if let Some((define, _)) = find_definition(&document.expressions, name) {
+                let mut hover = format!("```clarity\n{}\n```", definition_header(source, define)?);
+                if let Some(type_description) = document.contract_analysis.as_ref().and_then(|analysis| describe_type(analysis, name)) {
+                    hover.push_str(&format!("\n\n{}", type_description));
+                }
+                return Some(hover);
+            }
+        }
+
+        describe_native(name)
+    }
+
+    fn definition(&self, uri: &Url, line: u32, column: u32) -> Option<JsonValue> {
+        let source = self.documents.get(uri.as_str())?;
+        let document = self.analyze(uri)?;
+        let mut path = vec![];
+        find_path(&document.expressions, line, column, &mut path);
+        let target = *path.last()?;
+
+        match target.expr {
+            SymbolicExpressionType::LiteralValue(Value::Principal(PrincipalData::Contract(ref contract_identifier))) => {
+                let (contract_uri, contract_source) = self.find_contract(uri, contract_identifier)?;
+                Some(location(&contract_uri, &contract_source, &Span::zero()))
+            },
+            SymbolicExpressionType::Field(ref trait_identifier) => {
+                let (contract_uri, contract_source, expressions) = self.parse_contract(uri, &trait_identifier.contract_identifier)?;
+                let (_, name_expr) = find_definition(&expressions, trait_identifier.name.as_str())?;
+                Some(location(&contract_uri, &contract_source, &name_expr.span))
+            },
+            SymbolicExpressionType::TraitReference(ref name, _) => {
+                let (_, name_expr) = find_definition(&document.expressions, name.as_str())?;
+                Some(location(uri, source, &name_expr.span))
+            },
+            SymbolicExpressionType::Atom(ref name) => {
+                if let Some(contract_identifier) = contract_call_target(&path) {
+                    let (contract_uri, contract_source, expressions) = self.parse_contract(uri, &contract_identifier)?;
+                    let (_, name_expr) = find_definition(&expressions, name.as_str())?;
+                    return Some(location(&contract_uri, &contract_source, &name_expr.span));
+                }
+                let name_expr = match find_local_binding(&path, name.as_str()) {
+                    Some(binding) => binding,
+                    None => find_definition(&document.expressions, name.as_str())?.1
+                };
+                Some(location(uri, source, &name_expr.span))
+            },
+            _ => None
+        }
+    }
+
+    fn completion(&self, uri: &Url) -> Vec<JsonValue> {
+        let mut items = vec![];
+        let (function_names, define_names, keyword_names) = native_names();
+        for name in function_names.iter() {
+            let detail = lookup_function_reference(name)
+                .map(|api| api.signature)
+                .unwrap_or_default();
+            items.push(json!({ "label": name, "kind": COMPLETION_FUNCTION, "detail": detail }));
+        }
+        for name in define_names.iter().chain(keyword_names.iter()) {
+            items.push(json!({ "label": name, "kind": COMPLETION_KEYWORD }));
+        }
+
+        if let Some(source) = self.documents.get(uri.as_str()) {
+            let mut seen = BTreeMap::new();
+            for captures in DEFINITION_REGEX.captures_iter(source) {
+                let kind = match &captures[1] {
+                    "define-public" | "define-private" | "define-read-only" => COMPLETION_FUNCTION,
+                    "define-constant" => COMPLETION_CONSTANT,
+                    "impl-trait" => continue,
+                    _ => COMPLETION_VARIABLE
+                };
+                seen.entry(captures[2].to_string()).or_insert((kind, captures[1].to_string()));
+            }
+            for (name, (kind, define_type)) in seen.into_iter() {
+                items.push(json!({ "label": name, "kind": kind, "detail": define_type }));
+            }
+        }
+        items
+    }
+}
+
+fn document_uri(params: &JsonValue) -> Option<Url> {
+    params["textDocument"]["uri"].as_str().and_then(|uri| Url::parse(uri).ok())
+}
+
+fn diagnostic_json(source: &str, diagnostic: &Diagnostic) -> JsonValue {
+    let span = diagnostic.spans.get(0).cloned().unwrap_or_else(Span::zero);
+    let severity = match diagnostic.level {
+        Level::Warning => SEVERITY_WARNING,
+        _ => SEVERITY_ERROR
+    };
+    let message = match diagnostic.suggestion {
+        Some(ref suggestion) => format!("{}\n{}", diagnostic.message, suggestion),
+        None => diagnostic.message.clone()
+    };
+    json!({ "range": range_of(source, &span), "severity": severity, "source": "clarity", "message": message })
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+    use serde_json::Value as JsonValue;
+    use super::*;
+
+    fn open(server: &mut LanguageServer, uri: &str, text: &str) -> Vec<JsonValue> {
+        server.handle_message(&json!({
+            "jsonrpc": "2.0", "method": "textDocument/didOpen",
+            "params": { "textDocument": { "uri": uri, "languageId": "clarity", "version": 1, "text": text } }
+        }))
+    }
+
+    fn request(server: &mut LanguageServer, method: &str, uri: &str, line: u64, character: u64) -> JsonValue {
+        let mut replies = server.handle_message(&json!({
+            "jsonrpc": "2.0", "id": 1, "method": method,
+            "params": { "textDocument": { "uri": uri }, "position": { "line": line, "character": character } }
+        }));
+        assert_eq!(replies.len(), 1);
+        replies.pop().unwrap()["result"].clone()
+    }
+
+    fn diagnostics_for<'a>(messages: &'a [JsonValue], uri: &str) -> &'a Vec<JsonValue> {
+        messages.iter()
+            .find(|message| message["params"]["uri"] == uri)
+            .and_then(|message| message["params"]["diagnostics"].as_array())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_framing() {
+        let message = json!({ "jsonrpc": "2.0", "id": 1, "method": "shutdown" });
+        let mut framed = vec![];
+        write_message(&mut framed, &message).unwrap();
+        write_message(&mut framed, &json!({ "jsonrpc": "2.0", "method": "exit" })).unwrap();
+
+        let mut input = Cursor::new(framed.clone());
+        assert_eq!(read_message(&mut input).unwrap(), Some(message));
+
+        let mut output = vec![];
+        assert!(run(Cursor::new(framed), &mut output).unwrap());
+        let reply = read_message(&mut Cursor::new(output)).unwrap().unwrap();
+        assert_eq!(reply["id"], 1);
+        assert_eq!(reply["result"], JsonValue::Null);
+    }
+
+    #[test]
+    fn test_language_features() {
+        let counter_uri = "file:///project/counter.clar";
+        let caller_uri = "file:///project/caller.clar";
+        let counter = "(define-data-var count int 0)
+(define-public (increment (by int))
+  (begin
+    (var-set count (+ (var-get count) by))
+    (ok (var-get count))))";
+        let caller = "(define-constant step 1)
+(define-public (bump)
+  (contract-call? .counter increment step))";
+
+        let mut server = LanguageServer::new();
+        let published = open(&mut server, counter_uri, counter);
+        let warnings = diagnostics_for(&published, counter_uri);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0]["severity"], SEVERITY_WARNING);
+        assert_eq!(warnings[0]["range"]["start"], json!({ "line": 1, "character": 0 }));
+
+        open(&mut server, caller_uri, caller);
+
+        let hover = request(&mut server, "textDocument/hover", caller_uri, 2, 29);
+        assert_eq!(hover["contents"]["value"], "```clarity\n(define-public (increment (by int)))\n```");
+
+        assert_eq!(request(&mut server, "textDocument/definition", caller_uri, 2, 29), json!({
+            "uri": counter_uri,
+            "range": { "start": { "line": 1, "character": 16 }, "end": { "line": 1, "character": 25 } }
+        }));
+        assert_eq!(request(&mut server, "textDocument/definition", caller_uri, 2, 20)["range"]["start"],
+                   json!({ "line": 0, "character": 0 }));
+        assert_eq!(request(&mut server, "textDocument/definition", caller_uri, 2, 38), json!({
+            "uri": caller_uri,
+            "range": { "start": { "line": 0, "character": 17 }, "end": { "line": 0, "character": 21 } }
+        }));
+
+        let hover = request(&mut server, "textDocument/hover", caller_uri, 2, 5);
+        let hover = hover["contents"]["value"].as_str().unwrap();
+        assert!(hover.starts_with("```clarity\n(contract-call? .contract-name function-name arg0 arg1 ...)\n```"));
+
+        let hover = request(&mut server, "textDocument/hover", caller_uri, 0, 18);
+        assert_eq!(hover["contents"]["value"], "```clarity\n(define-constant step 1)\n```\n\ntype: `int`");
+
+        let completion = request(&mut server, "textDocument/completion", caller_uri, 2, 3);
+        let labels: Vec<_> = completion.as_array().unwrap().iter()
+            .map(|item| item["label"].as_str().unwrap().to_string())
+            .collect();
+        for expected in ["contract-call?", "define-public", "tx-sender", "step", "bump"].iter() {
+            assert!(labels.contains(&expected.to_string()), "missing completion {}", expected);
+        }
+
+        // passing a uint to `increment` is a type error
+        let published = server.handle_message(&json!({
+            "jsonrpc": "2.0", "method": "textDocument/didChange",
+            "params": { "textDocument": { "uri": caller_uri, "version": 2 },
+                        "contentChanges": [ { "text": caller.replace("step 1", "step u1") } ] }
+        }));
+        let errors = diagnostics_for(&published, caller_uri);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0]["severity"], SEVERITY_ERROR);
+
+        let unknown = server.handle_message(&json!({ "jsonrpc": "2.0", "id": 2, "method": "workspace/symbol", "params": {} }));
+        assert_eq!(unknown[0]["error"]["code"], METHOD_NOT_FOUND);
+    }
+}
@@ -42,6 +42,10 @@ use blockstack_lib::chainstate::stacks::*;
 use blockstack_lib::util::hash::{hex_bytes, to_hex};
 use blockstack_lib::util::retry::LogReader;
 use blockstack_lib::chainstate::stacks::index::marf::MARF;
+use blockstack_lib::chainstate::stacks::index::TrieHash;
+use blockstack_lib::chainstate::stacks::index::verifier::{TrustedRoots, verify_marf_proof};
+use blockstack_lib::chainstate::stacks::db::snapshot::{export_snapshot, import_snapshot};
+use blockstack_lib::chainstate::stacks::db::integrity::{ChainstateIndex, check_marf, get_truncated_blocks, truncate_marf};
 use blockstack_lib::chainstate::stacks::StacksBlockHeader;
 use blockstack_lib::chainstate::burn::BlockHeaderHash;
 use blockstack_lib::burnchains::BurnchainHeaderHash;
@@ -228,6 +232,89 @@ fn main() {
         process::exit(0);
     }
 
+    if argv[1] == "verify-proof" {
+        if argv.len() < 6 {
+            eprintln!("Usage: {} verify-proof PROOF_HEX KEY VALUE_HEX|none TIP_BLOCK_ID:ROOT_HASH [ANCESTOR_BLOCK_ID:ROOT_HASH ...]", argv[0]);
+            process::exit(1);
+        }
+        let proof_hex = &argv[2];
+        let key = &argv[3];
+        let expected_value = if argv[4] == "none" { None } else { Some(argv[4].as_str()) };
+
+        let parse_trusted_root = |arg: &str| -> (StacksBlockId, TrieHash) {
+            let parts : Vec<&str> = arg.split(':').collect();
+            if parts.len() != 2 {
+                eprintln!("Expected BLOCK_ID:ROOT_HASH, got {}", arg);
+                process::exit(1);
+            }
+            let block_id = StacksBlockId::from_hex(parts[0]).expect("Bad block ID");
+            let root_hash = TrieHash::from_hex(parts[1]).expect("Bad root hash");
+            (block_id, root_hash)
+        };
+
+        let (tip, tip_root_hash) = parse_trusted_root(&argv[5]);
+        let mut trusted = TrustedRoots::new(tip, tip_root_hash);
+        for arg in argv[6..].iter() {
+            let (block_id, root_hash) = parse_trusted_root(arg);
+            trusted.add_ancestor(block_id, root_hash);
+        }
+
+        match verify_marf_proof(proof_hex, key, expected_value, &trusted) {
+            Ok(()) => {
+                println!("accept");
+                process::exit(0);
+            },
+            Err(rejection) => {
+                println!("reject: {}", rejection);
+                process::exit(1);
+            }
+        }
+    }
+
+    if argv[1] == "export-snapshot" {
+        if argv.len() < 5 {
+            eprintln!("Usage: {} export-snapshot CHAINSTATE_DIR TIP_BLOCK_ID ARCHIVE_PATH", argv[0]);
+            process::exit(1);
+        }
+        let chainstate_dir = &argv[2];
+        let tip = StacksBlockId::from_hex(&argv[3]).expect("Bad block ID");
+        let archive_path = &argv[4];
+
+        match export_snapshot(chainstate_dir, &tip, archive_path) {
+            Ok(info) => {
+                println!("Exported {} at height {} (state root {}) to {}", &info.tip, info.tip_height, &info.state_index_root, archive_path);
+                process::exit(0);
+            },
+            Err(e) => {
+                eprintln!("Failed to export snapshot: {:?}", &e);
+                process::exit(1);
+            }
+        }
+    }
+
+    if argv[1] == "import-snapshot" {
+        if argv.len() < 6 {
+            eprintln!("Usage: {} import-snapshot ARCHIVE_PATH CHAINSTATE_DIR TIP_BLOCK_ID STATE_ROOT", argv[0]);
+            eprintln!("TIP_BLOCK_ID and STATE_ROOT must come from a source you trust, not from the archive.");
+            process::exit(1);
+        }
+        let archive_path = &argv[2];
+        let chainstate_dir = &argv[3];
+        let tip = StacksBlockId::from_hex(&argv[4]).expect("Bad block ID");
+        let state_root = TrieHash::from_hex(&argv[5]).expect("Bad state root");
+
+        match import_snapshot(archive_path, chainstate_dir, &tip, &state_root) {
+            Ok(info) => {
+                println!("Imported {} at height {} (state root {}) to {}", &info.tip, info.tip_height, &info.state_index_root, chainstate_dir);
+                process::exit(0);
+            },
+            Err(e) => {
+                eprintln!("Failed to import snapshot: {:?}", &e);
+                process::exit(1);
+            }
+        }
+    }
+
     if argv[1] == "exec_program" {
         if argv.len() < 3 {
             eprintln!("Usage: {} exec_program [program-file.clar]", argv[0]);
@@ -260,6 +347,98 @@ fn main() {
         return
     }
 
+    if argv[1] == "marf-prune" {
+        if argv.len() < 4 {
+            eprintln!("Usage: {} marf-prune MARF_PATH RETENTION_DEPTH", argv[0]);
+            eprintln!("Prunes the tries of the blocks more than RETENTION_DEPTH blocks below the highest block.");
+            eprintln!("WARNING: a node can't process a block that reads pruned state (e.g. with `at-block`), or any block built on it.");
+            eprintln!("When that happens, stacks-node exits, and it must be resynced from an unpruned chainstate or an empty working_dir.");
+            process::exit(1);
+        }
+        let path = &argv[2];
+        let retention_depth : u32 = argv[3].parse().expect("Bad retention depth");
+
+        if fs::metadata(path).is_err() {
+            eprintln!("No such file or directory: {}", path);
+            process::exit(1);
+        }
+
+        // A node with a pruned Clarity MARF never decides a block's validity differently from an
+        // archive node: blocks that need pruned state (e.g. via at-block, or by building on a
+        // pruned block) are left unprocessed, and stacks-node exits so that it can be resynced.
+        let mut marf : MARF<StacksBlockId> = MARF::from_path(path).expect("Failed to open MARF");
+        let num_pruned = marf.prune(retention_depth).expect("Failed to prune MARF");
+        println!("Pruned {} tries", num_pruned);
+        process::exit(0);
+    }
+
+    if argv[1] == "marf-check" {
+        if argv.len() < 3 || (argv.len() > 3 && argv[3] != "--truncate") {
+            eprintln!("Usage: {} marf-check CHAINSTATE_DIR [--truncate]", argv[0]);
+            eprintln!("With --truncate, offer to delete the first corrupt block's trie, and the tries of every block built on it.");
+            process::exit(1);
+        }
+        let chainstate_dir = &argv[2];
+        let truncate = argv.len() > 3;
+
+        let mut sane = true;
+        for (name, index) in [("headers index", ChainstateIndex::Headers), ("Clarity state", ChainstateIndex::ClarityState)].iter() {
+            let report = match check_marf(chainstate_dir, *index) {
+                Ok(report) => report,
+                Err(e) => {
+                    eprintln!("Failed to check the {}: {:?}", name, &e);
+                    process::exit(1);
+                }
+            };
+
+            println!("{}: checked {} nodes in {} tries ({} of them pruned), {} root hashes match their headers",
+                     name, report.num_nodes, report.num_checked, report.num_pruned, report.num_matched);
+
+            if let Some(corrupt) = report.first_corrupt {
+                sane = false;
+                println!("{}: first corrupt block is {}: {}", name, &corrupt.block, &corrupt.reason);
+
+                if !truncate {
+                    continue;
+                }
+
+                let blocks = match get_truncated_blocks(chainstate_dir, *index, &corrupt.block) {
+                    Ok(blocks) => blocks,
+                    Err(e) => {
+                        eprintln!("Failed to find the blocks built on {}: {:?}", &corrupt.block, &e);
+                        process::exit(1);
+                    }
+                };
+
+                println!("{}: truncating at {} deletes the tries of these {} blocks, which will need to be processed again, in this order:", name, &corrupt.block, blocks.len());
+                for block in blocks.iter() {
+                    println!("  {}", block);
+                }
+                println!("The block headers, staging blocks and sortition DB are not rolled back, and still record these blocks as processed.");
+                print!("Truncate the {}? [y/N] ", name);
+                io::stdout().flush().expect("Failed to write to stdout");
+
+                let mut answer = String::new();
+                io::stdin().read_line(&mut answer).expect("Failed to read from stdin");
+                if answer.trim() != "y" && answer.trim() != "Y" {
+                    println!("{}: not truncated", name);
+                    continue;
+                }
+
+                match truncate_marf(chainstate_dir, *index, &corrupt.block) {
+                    Ok(deleted) => {
+                        println!("{}: deleted the tries of {} blocks", name, deleted.len());
+                    },
+                    Err(e) => {
+                        eprintln!("Failed to truncate the {}: {:?}", name, &e);
+                        process::exit(1);
+                    }
+                }
+            }
+        }
+        process::exit(if sane { 0 } else { 1 });
+    }
+
     if argv[1] == "get-ancestors" {
         let path = &argv[2];
         let tip = BlockHeaderHash::from_hex(&argv[3]).unwrap();
@@ -106,3 +106,9 @@ pub fn update_active_miners_count_gauge(value: i64) {
     #[cfg(feature = "monitoring_prom")]
     prometheus::ACTIVE_MINERS_COUNT_GAUGE.set(value);    
 }
+
+#[allow(unused_variables)]
+pub fn update_event_observer_backlog_gauge(observer: &str, value: i64) {
+    #[cfg(feature = "monitoring_prom")]
+    prometheus::EVENT_OBSERVER_BACKLOG_GAUGE.with_label_values(&[observer]).set(value);
+}
@@ -1,4 +1,4 @@
-use prometheus::{IntCounter, IntGauge};
+use prometheus::{IntCounter, IntGauge, IntGaugeVec};
 
 lazy_static! {
     pub static ref RPC_CALL_COUNTER: IntCounter = register_int_counter!(opts!(
@@ -126,4 +126,9 @@ lazy_static! {
         "Total number of active miners.",
         labels! {"handler" => "all",}
     )).unwrap();
+
+    pub static ref EVENT_OBSERVER_BACKLOG_GAUGE: IntGaugeVec = register_int_gauge_vec!(opts!(
+        "stacks_node_event_observer_backlog",
+        "Number of event payloads waiting to be delivered to an event observer."
+    ), &["observer"]).unwrap();
 }
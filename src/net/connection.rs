@@ -353,6 +353,8 @@ pub struct ConnectionOptions {
     pub public_ip_request_timeout: u64,
     pub public_ip_timeout: u64,
     pub public_ip_max_retries: u64,
+    pub websocket_ping_interval: u64,
+    pub max_websocket_buffer: usize,
     
     // fault injection
     pub disable_neighbor_walk: bool,
@@ -409,6 +411,8 @@ impl std::default::Default for ConnectionOptions {
             public_ip_request_timeout: 60,  // how often we can attempt to look up our public IP address
             public_ip_timeout: 3600,        // re-learn the public IP ever hour, if it's not given
             public_ip_max_retries: 3,       // maximum number of retries before self-throttling for $public_ip_timeout
+            websocket_ping_interval: 10,    // how often to ping event stream clients, so they don't look idle
+            max_websocket_buffer: 4 * 1024 * 1024,  // how many bytes of events a slow event stream client can fall behind by before it's dropped
 
             // no faults on by default
             disable_neighbor_walk: false,
@@ -49,12 +49,16 @@ use net::HttpRequestMetadata;
 use net::HttpResponseMetadata;
 use net::NeighborAddress;
 use net::CallReadOnlyRequestBody;
+use net::EvalReadOnlyRequestBody;
 use net::HTTP_PREAMBLE_MAX_ENCODED_SIZE;
 use net::HTTP_PREAMBLE_MAX_NUM_HEADERS;
 use net::MAX_MESSAGE_LEN;
 use net::MAX_MICROBLOCKS_UNCONFIRMED;
+use net::MAX_HEADERS_RANGE_LEN;
 use net::HTTP_REQUEST_ID_RESERVED;
 use net::ClientError;
+use net::websocket;
+use net::websocket::EventStreamTopic;
 
 use burnchains::{ Txid, Address };
 use chainstate::burn::BlockHeaderHash;
@@ -62,6 +66,7 @@ use chainstate::stacks::{
     StacksAddress, StacksTransaction, StacksBlock, StacksMicroblock, StacksPublicKey,
     StacksBlockId
 };
+use chainstate::stacks::db::transactions::AssetHolding;
 
 use util::log;
 use util::hash::hex_bytes;
@@ -73,7 +78,7 @@ use vm::{
     ast::parser::{
         STANDARD_PRINCIPAL_REGEX, PRINCIPAL_DATA_REGEX, CLARITY_NAME_REGEX, CONTRACT_NAME_REGEX
     },
-    types::{ PrincipalData, BOUND_VALUE_SERIALIZATION_HEX },
+    types::{ PrincipalData, QualifiedContractIdentifier, AssetIdentifier, BOUND_VALUE_SERIALIZATION_HEX },
     ClarityName, ContractName, Value
 };
 
@@ -99,15 +104,42 @@ lazy_static! {
     static ref PATH_GETMICROBLOCKS_CONFIRMED : Regex = Regex::new(r#"^/v2/microblocks/confirmed/([0-9a-f]{64})$"#).unwrap();
     static ref PATH_GETMICROBLOCKS_UNCONFIRMED : Regex = Regex::new(r#"^/v2/microblocks/unconfirmed/([0-9a-f]{64})/([0-9]{1,5})$"#).unwrap();
     static ref PATH_POSTTRANSACTION : Regex = Regex::new(r#"^/v2/transactions$"#).unwrap();
+    static ref PATH_GETTRANSACTION : Regex = Regex::new(r#"^/v2/transactions/([0-9a-f]{64})$"#).unwrap();
+    static ref PATH_GETHEADER : Regex = Regex::new(r#"^/v2/headers/([0-9a-f]{64})$"#).unwrap();
+    static ref PATH_GETHEADER_BY_HEIGHT : Regex = Regex::new(r#"^/v2/headers/height/([0-9]{1,20})$"#).unwrap();
+    static ref PATH_GETHEADERS : Regex = Regex::new(r#"^/v2/headers/range/([0-9]{1,20})/([0-9]{1,20})$"#).unwrap();
+    static ref PATH_GETHEADER_TIPS : Regex = Regex::new(r#"^/v2/headers/tips$"#).unwrap();
     static ref PATH_POSTMICROBLOCK : Regex = Regex::new(r#"^/v2/microblocks$"#).unwrap();
     static ref PATH_GET_ACCOUNT: Regex = Regex::new(&format!(
         "^/v2/accounts/(?P<principal>{})$", *PRINCIPAL_DATA_REGEX)).unwrap();
+    static ref PATH_GET_ACCOUNT_NONCES: Regex = Regex::new(&format!(
+        "^/v2/accounts/(?P<principal>{})/nonces$", *PRINCIPAL_DATA_REGEX)).unwrap();
     static ref PATH_GET_MAP_ENTRY: Regex = Regex::new(&format!(
         "^/v2/map_entry/(?P<address>{})/(?P<contract>{})/(?P<map>{})$",
         *STANDARD_PRINCIPAL_REGEX, *CONTRACT_NAME_REGEX, *CLARITY_NAME_REGEX)).unwrap();
+    static ref PATH_GET_MAP_KEYS: Regex = Regex::new(&format!(
+        "^/v2/map_keys/(?P<address>{})/(?P<contract>{})/(?P<map>{})$",
+        *STANDARD_PRINCIPAL_REGEX, *CONTRACT_NAME_REGEX, *CLARITY_NAME_REGEX)).unwrap();
+    static ref PATH_GET_DATA_VAR: Regex = Regex::new(&format!(
+        "^/v2/data_var/(?P<address>{})/(?P<contract>{})/(?P<var>{})$",
+        *STANDARD_PRINCIPAL_REGEX, *CONTRACT_NAME_REGEX, *CLARITY_NAME_REGEX)).unwrap();
+    static ref PATH_GET_FT_BALANCE: Regex = Regex::new(&format!(
+        "^/v2/ft/(?P<address>{})/(?P<contract>{})/(?P<token>{})/balance/(?P<principal>{})$",
+        *STANDARD_PRINCIPAL_REGEX, *CONTRACT_NAME_REGEX, *CLARITY_NAME_REGEX, *PRINCIPAL_DATA_REGEX)).unwrap();
+    static ref PATH_GET_FT_SUPPLY: Regex = Regex::new(&format!(
+        "^/v2/ft/(?P<address>{})/(?P<contract>{})/(?P<token>{})/supply$",
+        *STANDARD_PRINCIPAL_REGEX, *CONTRACT_NAME_REGEX, *CLARITY_NAME_REGEX)).unwrap();
+    static ref PATH_GET_NFT_OWNER: Regex = Regex::new(&format!(
+        "^/v2/nft/(?P<address>{})/(?P<contract>{})/(?P<asset>{})/owner/(?P<value>(0x)?[0-9a-fA-F]+)$",
+        *STANDARD_PRINCIPAL_REGEX, *CONTRACT_NAME_REGEX, *CLARITY_NAME_REGEX)).unwrap();
+    static ref PATH_GET_ASSET_HOLDINGS: Regex = Regex::new(&format!(
+        "^/v2/assets/(?P<principal>{})$", *PRINCIPAL_DATA_REGEX)).unwrap();
     static ref PATH_POST_CALL_READ_ONLY: Regex = Regex::new(&format!(
         "^/v2/contracts/call-read/(?P<address>{})/(?P<contract>{})/(?P<function>{})$",
         *STANDARD_PRINCIPAL_REGEX, *CONTRACT_NAME_REGEX, *CLARITY_NAME_REGEX)).unwrap();
+    static ref PATH_POST_EVAL_READ_ONLY: Regex = Regex::new(&format!(
+        "^/v2/contracts/eval/(?P<address>{})/(?P<contract>{})$",
+        *STANDARD_PRINCIPAL_REGEX, *CONTRACT_NAME_REGEX)).unwrap();
     static ref PATH_GET_CONTRACT_SRC: Regex = Regex::new(&format!(
         "^/v2/contracts/source/(?P<address>{})/(?P<contract>{})$",
         *STANDARD_PRINCIPAL_REGEX, *CONTRACT_NAME_REGEX)).unwrap();
@@ -115,6 +147,13 @@ lazy_static! {
         "^/v2/contracts/interface/(?P<address>{})/(?P<contract>{})$",
         *STANDARD_PRINCIPAL_REGEX, *CONTRACT_NAME_REGEX)).unwrap();
     static ref PATH_GET_TRANSFER_COST: Regex = Regex::new("^/v2/fees/transfer$").unwrap();
+    static ref PATH_GET_FEE_RATES: Regex = Regex::new("^/v2/fees/rates$").unwrap();
+    static ref PATH_POST_FEE_ESTIMATE: Regex = Regex::new("^/v2/fees/transaction$").unwrap();
+    static ref PATH_GET_MEMPOOL_TXIDS: Regex = Regex::new("^/v2/mempool/txids$").unwrap();
+    static ref PATH_GET_MEMPOOL_TRANSACTION: Regex = Regex::new(r#"^/v2/mempool/transactions/([0-9a-f]{64})$"#).unwrap();
+    static ref PATH_GET_MEMPOOL_ADDRESS: Regex = Regex::new(&format!(
+        "^/v2/mempool/address/(?P<address>{})$", *STANDARD_PRINCIPAL_REGEX)).unwrap();
+    static ref PATH_GET_EVENT_STREAM: Regex = Regex::new("^/v2/events$").unwrap();
     static ref PATH_OPTIONS_WILDCARD: Regex = Regex::new("^/v2/.{0,4096}$").unwrap();
 }
 
@@ -881,6 +920,12 @@ impl StacksMessageCodec for HttpRequestPreamble {
                         else if value.to_lowercase() == "keep-alive" {
                             keep_alive = true;
                         }
+                        else if value.to_lowercase().split(",").any(|token| token.trim() == "upgrade") {
+                            // protocol upgrade (i.e. to a WebSocket).  Keep the header around so
+                            // the request parser can check it.
+                            keep_alive = true;
+                            headers.insert(key, value);
+                        }
                         else {
                             return Err(net_error::DeserializeError("Inavlid HTTP request: invalid Connection: header".to_string()));
                         }
@@ -894,6 +939,11 @@ impl StacksMessageCodec for HttpRequestPreamble {
                     return Err(net_error::DeserializeError("Missing Host header".to_string()));
                 };
 
+                if headers.contains_key("connection") && !headers.contains_key("upgrade") {
+                    // asked to upgrade, but not to what
+                    return Err(net_error::DeserializeError("Invalid HTTP request: Connection: upgrade without an Upgrade: header".to_string()));
+                }
+
                 Ok(HttpRequestPreamble {
                     version: version,
                     verb: verb,
@@ -927,6 +977,19 @@ impl HttpResponsePreamble {
                                              md.request_id, |ref mut fd| keep_alive_headers(fd, md))
     }
 
+    /// Reply to a WebSocket upgrade request.  There is no body, so there is no Content-Type or
+    /// Content-Length.
+    pub fn websocket_upgrade<W: Write>(fd: &mut W, accept_key: &str, request_id: u32) -> Result<(), net_error> {
+        fd.write_all("HTTP/1.1 101 Switching Protocols\r\n".as_bytes()).map_err(net_error::WriteError)?;
+        fd.write_all("Server: stacks/2.0\r\nDate: ".as_bytes()).map_err(net_error::WriteError)?;
+        fd.write_all(rfc7231_now().as_bytes()).map_err(net_error::WriteError)?;
+        fd.write_all("\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: ".as_bytes()).map_err(net_error::WriteError)?;
+        fd.write_all(accept_key.as_bytes()).map_err(net_error::WriteError)?;
+        fd.write_all("\r\nX-Request-Id: ".as_bytes()).map_err(net_error::WriteError)?;
+        fd.write_all(format!("{}\r\n\r\n", request_id).as_bytes()).map_err(net_error::WriteError)?;
+        Ok(())
+    }
+
     pub fn new_serialized<W: Write, F>(fd: &mut W, status_code: u16, reason: &str, content_length: Option<u32>, content_type: &HttpContentType, request_id: u32, mut write_headers: F) -> Result<(), net_error>
     where 
         F: FnMut(&mut W) -> Result<(), net_error>
@@ -1170,13 +1233,32 @@ impl HttpRequestType {
             ("GET", &PATH_GETMICROBLOCKS_CONFIRMED, &HttpRequestType::parse_getmicroblocks_confirmed),
             ("GET", &PATH_GETMICROBLOCKS_UNCONFIRMED, &HttpRequestType::parse_getmicroblocks_unconfirmed),
             ("POST", &PATH_POSTTRANSACTION, &HttpRequestType::parse_posttransaction),
+            ("GET", &PATH_GETTRANSACTION, &HttpRequestType::parse_gettransaction),
+            ("GET", &PATH_GETHEADER, &HttpRequestType::parse_getheader),
+            ("GET", &PATH_GETHEADER_BY_HEIGHT, &HttpRequestType::parse_getheader_by_height),
+            ("GET", &PATH_GETHEADERS, &HttpRequestType::parse_getheaders),
+            ("GET", &PATH_GETHEADER_TIPS, &HttpRequestType::parse_getheader_tips),
             ("POST", &PATH_POSTMICROBLOCK, &HttpRequestType::parse_postmicroblock),
             ("GET", &PATH_GET_ACCOUNT, &HttpRequestType::parse_get_account),
+            ("GET", &PATH_GET_ACCOUNT_NONCES, &HttpRequestType::parse_get_account_nonces),
             ("POST", &PATH_GET_MAP_ENTRY, &HttpRequestType::parse_get_map_entry),
+            ("GET", &PATH_GET_MAP_KEYS, &HttpRequestType::parse_get_map_keys),
+            ("GET", &PATH_GET_DATA_VAR, &HttpRequestType::parse_get_data_var),
+            ("GET", &PATH_GET_FT_BALANCE, &HttpRequestType::parse_get_ft_balance),
+            ("GET", &PATH_GET_FT_SUPPLY, &HttpRequestType::parse_get_ft_supply),
+            ("GET", &PATH_GET_NFT_OWNER, &HttpRequestType::parse_get_nft_owner),
+            ("GET", &PATH_GET_ASSET_HOLDINGS, &HttpRequestType::parse_get_asset_holdings),
             ("GET", &PATH_GET_TRANSFER_COST, &HttpRequestType::parse_get_transfer_cost),
+            ("GET", &PATH_GET_FEE_RATES, &HttpRequestType::parse_get_fee_rates),
+            ("POST", &PATH_POST_FEE_ESTIMATE, &HttpRequestType::parse_post_fee_estimate),
+            ("GET", &PATH_GET_MEMPOOL_TXIDS, &HttpRequestType::parse_get_mempool_txids),
+            ("GET", &PATH_GET_MEMPOOL_TRANSACTION, &HttpRequestType::parse_get_mempool_transaction),
+            ("GET", &PATH_GET_MEMPOOL_ADDRESS, &HttpRequestType::parse_get_mempool_address),
+            ("GET", &PATH_GET_EVENT_STREAM, &HttpRequestType::parse_get_event_stream),
             ("GET", &PATH_GET_CONTRACT_SRC, &HttpRequestType::parse_get_contract_source),
             ("GET", &PATH_GET_CONTRACT_ABI, &HttpRequestType::parse_get_contract_abi),
             ("POST", &PATH_POST_CALL_READ_ONLY, &HttpRequestType::parse_call_read_only),
+            ("POST", &PATH_POST_EVAL_READ_ONLY, &HttpRequestType::parse_eval_read_only),
             ("OPTIONS", &PATH_OPTIONS_WILDCARD, &HttpRequestType::parse_options_preflight),
         ];
 
@@ -1230,6 +1312,135 @@ impl HttpRequestType {
         Ok(HttpRequestType::GetTransferCost(HttpRequestMetadata::from_preamble(preamble)))
     }
 
+    fn parse_get_fee_rates<R: Read>(_protocol: &mut StacksHttp, preamble: &HttpRequestPreamble, _regex: &Captures, query: Option<&str>, _fd: &mut R) -> Result<HttpRequestType, net_error> {
+        if preamble.get_content_length() != 0 {
+            return Err(net_error::DeserializeError("Invalid Http request: expected 0-length body for GetFeeRates".to_string()));
+        }
+
+        let tip = HttpRequestType::get_chain_tip_query(query);
+        Ok(HttpRequestType::GetFeeRates(HttpRequestMetadata::from_preamble(preamble), tip))
+    }
+
+    fn parse_get_mempool_txids<R: Read>(_protocol: &mut StacksHttp, preamble: &HttpRequestPreamble, _regex: &Captures, query: Option<&str>, _fd: &mut R) -> Result<HttpRequestType, net_error> {
+        if preamble.get_content_length() != 0 {
+            return Err(net_error::DeserializeError("Invalid Http request: expected 0-length body for GetMemPoolTxids".to_string()));
+        }
+
+        let page = HttpRequestType::get_page_query(query);
+        Ok(HttpRequestType::GetMemPoolTxids(HttpRequestMetadata::from_preamble(preamble), page))
+    }
+
+    fn parse_get_mempool_transaction<R: Read>(_protocol: &mut StacksHttp, preamble: &HttpRequestPreamble, captures: &Captures, _query: Option<&str>, _fd: &mut R) -> Result<HttpRequestType, net_error> {
+        if preamble.get_content_length() != 0 {
+            return Err(net_error::DeserializeError("Invalid Http request: expected 0-length body for GetMemPoolTransaction".to_string()));
+        }
+
+        let txid_str = captures
+            .get(1)
+            .ok_or(net_error::DeserializeError("Failed to match path to txid group".to_string()))?
+            .as_str();
+
+        let txid = Txid::from_hex(txid_str)
+            .map_err(|_e| net_error::DeserializeError("Failed to parse txid".to_string()))?;
+
+        Ok(HttpRequestType::GetMemPoolTransaction(HttpRequestMetadata::from_preamble(preamble), txid))
+    }
+
+    fn parse_get_mempool_address<R: Read>(_protocol: &mut StacksHttp, preamble: &HttpRequestPreamble, captures: &Captures, _query: Option<&str>, _fd: &mut R) -> Result<HttpRequestType, net_error> {
+        if preamble.get_content_length() != 0 {
+            return Err(net_error::DeserializeError("Invalid Http request: expected 0-length body for GetMemPoolTransactionsForAddress".to_string()));
+        }
+
+        let addr = StacksAddress::from_string(&captures["address"])
+            .ok_or_else(|| net_error::DeserializeError("Failed to parse address".into()))?;
+
+        Ok(HttpRequestType::GetMemPoolTransactionsForAddress(HttpRequestMetadata::from_preamble(preamble), addr))
+    }
+
+    /// get the event stream topics optional query argument (`topics`), a comma-separated list.
+    fn get_topics_query(query: Option<&str>) -> Result<Vec<EventStreamTopic>, net_error> {
+        if let Some(query_string) = query {
+            if let Some((_k, value)) = form_urlencoded::parse(query_string.as_bytes()).find(|(key, _v)| key == "topics") {
+                let topic_strs : Vec<String> = value.split(",")
+                    .filter(|topic| topic.len() > 0)
+                    .map(|topic| topic.to_string())
+                    .collect();
+
+                if topic_strs.len() > websocket::MAX_WEBSOCKET_TOPICS {
+                    return Err(net_error::ClientError(ClientError::Message(format!("Too many topics (at most {} are allowed)", websocket::MAX_WEBSOCKET_TOPICS))));
+                }
+                return websocket::parse_topics(&topic_strs)
+                    .map_err(|msg| net_error::ClientError(ClientError::Message(msg)));
+            }
+        }
+        Ok(vec![])
+    }
+
+    fn parse_get_event_stream<R: Read>(_protocol: &mut StacksHttp, preamble: &HttpRequestPreamble, _captures: &Captures, query: Option<&str>, _fd: &mut R) -> Result<HttpRequestType, net_error> {
+        if preamble.get_content_length() != 0 {
+            return Err(net_error::DeserializeError("Invalid Http request: expected 0-length body for GetEventStream".to_string()));
+        }
+
+        let is_upgrade = preamble.headers.get("upgrade")
+            .map(|value| value.to_lowercase() == "websocket")
+            .unwrap_or(false);
+        let is_connection_upgrade = preamble.headers.get("connection")
+            .map(|value| value.to_lowercase().split(",").any(|token| token.trim() == "upgrade"))
+            .unwrap_or(false);
+
+        if !is_upgrade || !is_connection_upgrade {
+            return Err(net_error::ClientError(ClientError::Message("Expected a WebSocket upgrade request".to_string())));
+        }
+
+        match preamble.headers.get("sec-websocket-version") {
+            Some(version) if version.trim() == websocket::WEBSOCKET_VERSION => {},
+            _ => {
+                return Err(net_error::ClientError(ClientError::Message(
+                    format!("Unsupported WebSocket version; expected {}", websocket::WEBSOCKET_VERSION))));
+            }
+        }
+
+        let key = preamble.headers.get("sec-websocket-key")
+            .map(|key| key.trim().to_string())
+            .filter(|key| key.len() > 0)
+            .ok_or(net_error::ClientError(ClientError::Message("Missing Sec-WebSocket-Key header".to_string())))?;
+
+        let topics = HttpRequestType::get_topics_query(query)?;
+
+        Ok(HttpRequestType::GetEventStream(HttpRequestMetadata::from_preamble(preamble), key, topics))
+    }
+
+    fn parse_post_fee_estimate<R: Read>(_protocol: &mut StacksHttp, preamble: &HttpRequestPreamble, _regex: &Captures, query: Option<&str>, fd: &mut R) -> Result<HttpRequestType, net_error> {
+        if preamble.get_content_length() == 0 {
+            return Err(net_error::DeserializeError("Invalid Http request: expected non-zero-length body for EstimateTransactionFee".to_string()));
+        }
+
+        // content-type must be given, and must be application/octet-stream
+        match preamble.content_type {
+            None => {
+                return Err(net_error::DeserializeError("Missing Content-Type for transaction".to_string()));
+            },
+            Some(ref c) => {
+                if *c != HttpContentType::Bytes {
+                    return Err(net_error::DeserializeError("Wrong Content-Type for transaction; expected application/octet-stream".to_string()));
+                }
+            }
+        };
+
+        let tx = StacksTransaction::consensus_deserialize(fd)
+            .map_err(|e| {
+                if let net_error::DeserializeError(msg) = e {
+                    net_error::ClientError(ClientError::Message(
+                        format!("Failed to deserialize transaction to estimate: {}", msg)))
+                } else {
+                    e
+                }
+            })?;
+
+        let tip = HttpRequestType::get_chain_tip_query(query);
+        Ok(HttpRequestType::EstimateTransactionFee(HttpRequestMetadata::from_preamble(preamble), tx, tip))
+    }
+
     /// check whether the given option query string
     ///   sets proof=0 (setting proof to false).
     /// Defaults to _true_
@@ -1246,6 +1457,19 @@ impl HttpRequestType {
         !no_proof
     }
 
+    /// get the page number optional query argument (`page`).
+    /// Defaults to the first page (0) if it is missing or can't be parsed.
+    fn get_page_query(query: Option<&str>) -> u64 {
+        if let Some(query_string) = query {
+            form_urlencoded::parse(query_string.as_bytes())
+                .find(|(key, _v)| key == "page")
+                .and_then(|(_k, value)| value.parse::<u64>().ok())
+                .unwrap_or(0)
+        } else {
+            0
+        }
+    }
+
     /// get the chain tip optional query argument (`tip`)
     /// Take the first value we can parse.
     fn get_chain_tip_query(query: Option<&str>) -> Option<StacksBlockId> {
@@ -1282,6 +1506,19 @@ impl HttpRequestType {
         Ok(HttpRequestType::GetAccount(HttpRequestMetadata::from_preamble(preamble), principal, tip, with_proof))
     }
 
+    fn parse_get_account_nonces<R: Read>(_protocol: &mut StacksHttp, preamble: &HttpRequestPreamble, captures: &Captures, query: Option<&str>, _fd: &mut R) -> Result<HttpRequestType, net_error> {
+        if preamble.get_content_length() != 0 {
+            return Err(net_error::DeserializeError("Invalid Http request: expected 0-length body for GetAccountNonces".to_string()));
+        }
+
+        let principal = PrincipalData::parse(&captures["principal"])
+            .map_err(|_e| net_error::DeserializeError("Failed to parse account principal".into()))?;
+
+        let tip = HttpRequestType::get_chain_tip_query(query);
+
+        Ok(HttpRequestType::GetAccountNonces(HttpRequestMetadata::from_preamble(preamble), principal, tip))
+    }
+
     fn parse_get_map_entry<R: Read>(_protocol: &mut StacksHttp, preamble: &HttpRequestPreamble, captures: &Captures, query: Option<&str>, fd: &mut R) -> Result<HttpRequestType, net_error> {
         let content_len = preamble.get_content_length();
         if !(content_len > 0 && content_len < (BOUND_VALUE_SERIALIZATION_HEX)) {
@@ -1311,6 +1548,186 @@ impl HttpRequestType {
         Ok(HttpRequestType::GetMapEntry(HttpRequestMetadata::from_preamble(preamble), contract_addr, contract_name, map_name, value, tip, with_proof))
     }
 
+    /// get the map key to page after, from the optional query argument (`after`).
+    /// This is a hex-encoded serialized Clarity value, with or without a leading `0x`.
+    fn get_after_query(query: Option<&str>) -> Result<Option<String>, net_error> {
+        let after_opt = query.and_then(|query_string| {
+            form_urlencoded::parse(query_string.as_bytes())
+                .find(|(key, _v)| key == "after")
+                .map(|(_k, value)| value.to_string())
+        });
+
+        match after_opt {
+            Some(after) => {
+                let after = after.trim_start_matches("0x").to_lowercase();
+                hex_bytes(&after)
+                    .map_err(|_e| net_error::DeserializeError("Failed to parse map key to page after".into()))?;
+                Ok(Some(after))
+            },
+            None => Ok(None)
+        }
+    }
+
+    fn parse_get_map_keys<R: Read>(_protocol: &mut StacksHttp, preamble: &HttpRequestPreamble, captures: &Captures, query: Option<&str>, _fd: &mut R) -> Result<HttpRequestType, net_error> {
+        if preamble.get_content_length() != 0 {
+            return Err(net_error::DeserializeError("Invalid Http request: expected 0-length body for GetMapKeys".to_string()));
+        }
+
+        let contract_addr =  StacksAddress::from_string(&captures["address"])
+            .ok_or_else(|| net_error::DeserializeError("Failed to parse contract address".into()))?;
+        let contract_name = ContractName::try_from(captures["contract"].to_string())
+            .map_err(|_e| net_error::DeserializeError("Failed to parse contract name".into()))?;
+        let map_name = ClarityName::try_from(captures["map"].to_string())
+            .map_err(|_e| net_error::DeserializeError("Failed to parse map name".into()))?;
+
+        let after = HttpRequestType::get_after_query(query)?;
+        let tip = HttpRequestType::get_chain_tip_query(query);
+
+        Ok(HttpRequestType::GetMapKeys(HttpRequestMetadata::from_preamble(preamble), contract_addr, contract_name, map_name, after, tip))
+    }
+
+    fn parse_get_data_var<R: Read>(_protocol: &mut StacksHttp, preamble: &HttpRequestPreamble, captures: &Captures, query: Option<&str>, _fd: &mut R) -> Result<HttpRequestType, net_error> {
+        if preamble.get_content_length() != 0 {
+            return Err(net_error::DeserializeError("Invalid Http request: expected 0-length body for GetDataVar".to_string()));
+        }
+
+        let contract_addr =  StacksAddress::from_string(&captures["address"])
+            .ok_or_else(|| net_error::DeserializeError("Failed to parse contract address".into()))?;
+        let contract_name = ContractName::try_from(captures["contract"].to_string())
+            .map_err(|_e| net_error::DeserializeError("Failed to parse contract name".into()))?;
+        let var_name = ClarityName::try_from(captures["var"].to_string())
+            .map_err(|_e| net_error::DeserializeError("Failed to parse variable name".into()))?;
+
+        let with_proof = HttpRequestType::get_proof_query(query);
+        let tip = HttpRequestType::get_chain_tip_query(query);
+
+        Ok(HttpRequestType::GetDataVar(HttpRequestMetadata::from_preamble(preamble), contract_addr, contract_name, var_name, tip, with_proof))
+    }
+
+    fn parse_get_ft_balance<R: Read>(_protocol: &mut StacksHttp, preamble: &HttpRequestPreamble, captures: &Captures, query: Option<&str>, _fd: &mut R) -> Result<HttpRequestType, net_error> {
+        if preamble.get_content_length() != 0 {
+            return Err(net_error::DeserializeError("Invalid Http request: expected 0-length body for GetFungibleTokenBalance".to_string()));
+        }
+
+        let contract_addr =  StacksAddress::from_string(&captures["address"])
+            .ok_or_else(|| net_error::DeserializeError("Failed to parse contract address".into()))?;
+        let contract_name = ContractName::try_from(captures["contract"].to_string())
+            .map_err(|_e| net_error::DeserializeError("Failed to parse contract name".into()))?;
+        let token_name = ClarityName::try_from(captures["token"].to_string())
+            .map_err(|_e| net_error::DeserializeError("Failed to parse token name".into()))?;
+        let principal = PrincipalData::parse(&captures["principal"])
+            .map_err(|_e| net_error::DeserializeError("Failed to parse account principal".into()))?;
+
+        let with_proof = HttpRequestType::get_proof_query(query);
+        let tip = HttpRequestType::get_chain_tip_query(query);
+
+        Ok(HttpRequestType::GetFungibleTokenBalance(HttpRequestMetadata::from_preamble(preamble), contract_addr, contract_name, token_name, principal, tip, with_proof))
+    }
+
+    fn parse_get_ft_supply<R: Read>(_protocol: &mut StacksHttp, preamble: &HttpRequestPreamble, captures: &Captures, query: Option<&str>, _fd: &mut R) -> Result<HttpRequestType, net_error> {
+        if preamble.get_content_length() != 0 {
+            return Err(net_error::DeserializeError("Invalid Http request: expected 0-length body for GetFungibleTokenSupply".to_string()));
+        }
+
+        let contract_addr =  StacksAddress::from_string(&captures["address"])
+            .ok_or_else(|| net_error::DeserializeError("Failed to parse contract address".into()))?;
+        let contract_name = ContractName::try_from(captures["contract"].to_string())
+            .map_err(|_e| net_error::DeserializeError("Failed to parse contract name".into()))?;
+        let token_name = ClarityName::try_from(captures["token"].to_string())
+            .map_err(|_e| net_error::DeserializeError("Failed to parse token name".into()))?;
+
+        let with_proof = HttpRequestType::get_proof_query(query);
+        let tip = HttpRequestType::get_chain_tip_query(query);
+
+        Ok(HttpRequestType::GetFungibleTokenSupply(HttpRequestMetadata::from_preamble(preamble), contract_addr, contract_name, token_name, tip, with_proof))
+    }
+
+    fn parse_get_nft_owner<R: Read>(_protocol: &mut StacksHttp, preamble: &HttpRequestPreamble, captures: &Captures, query: Option<&str>, _fd: &mut R) -> Result<HttpRequestType, net_error> {
+        if preamble.get_content_length() != 0 {
+            return Err(net_error::DeserializeError("Invalid Http request: expected 0-length body for GetNonFungibleTokenOwner".to_string()));
+        }
+
+        let contract_addr =  StacksAddress::from_string(&captures["address"])
+            .ok_or_else(|| net_error::DeserializeError("Failed to parse contract address".into()))?;
+        let contract_name = ContractName::try_from(captures["contract"].to_string())
+            .map_err(|_e| net_error::DeserializeError("Failed to parse contract name".into()))?;
+        let asset_name = ClarityName::try_from(captures["asset"].to_string())
+            .map_err(|_e| net_error::DeserializeError("Failed to parse asset name".into()))?;
+
+        let value_hex = captures["value"].to_lowercase();
+        if value_hex.len() > BOUND_VALUE_SERIALIZATION_HEX as usize {
+            return Err(net_error::DeserializeError("Invalid Http request: asset identifier is too long".into()));
+        }
+        let value = Value::try_deserialize_hex_untyped(&value_hex)
+            .map_err(|_e| net_error::DeserializeError("Failed to deserialize asset identifier".into()))?;
+
+        let with_proof = HttpRequestType::get_proof_query(query);
+        let tip = HttpRequestType::get_chain_tip_query(query);
+
+        Ok(HttpRequestType::GetNonFungibleTokenOwner(HttpRequestMetadata::from_preamble(preamble), contract_addr, contract_name, asset_name, value, tip, with_proof))
+    }
+
+    fn parse_get_asset_holdings<R: Read>(_protocol: &mut StacksHttp, preamble: &HttpRequestPreamble, captures: &Captures, query: Option<&str>, _fd: &mut R) -> Result<HttpRequestType, net_error> {
+        if preamble.get_content_length() != 0 {
+            return Err(net_error::DeserializeError("Invalid Http request: expected 0-length body for GetAssetHoldings".to_string()));
+        }
+
+        let principal = PrincipalData::parse(&captures["principal"])
+            .map_err(|_e| net_error::DeserializeError("Failed to parse account principal".into()))?;
+
+        let after = HttpRequestType::get_asset_holdings_after_query(query)?;
+        let with_proof = HttpRequestType::get_proof_query(query);
+        let tip = HttpRequestType::get_chain_tip_query(query);
+
+        Ok(HttpRequestType::GetAssetHoldings(HttpRequestMetadata::from_preamble(preamble), principal, after, tip, with_proof))
+    }
+
+    /// The asset holding to page after, given as `contract::asset` for a fungible token, or
+    /// `contract::asset::0x...` for an instance of a non-fungible token
+    fn get_asset_holdings_after_query(query: Option<&str>) -> Result<Option<AssetHolding>, net_error> {
+        let after_opt = query.and_then(|query_string| {
+            form_urlencoded::parse(query_string.as_bytes())
+                .find(|(key, _v)| key == "after")
+                .map(|(_k, value)| value.to_string())
+        });
+
+        let after = match after_opt {
+            Some(after) => after,
+            None => {
+                return Ok(None);
+            }
+        };
+
+        let parts: Vec<&str> = after.split("::").collect();
+        if parts.len() != 2 && parts.len() != 3 {
+            return Err(net_error::DeserializeError("Failed to parse asset holding to page after".into()));
+        }
+
+        let contract_identifier = QualifiedContractIdentifier::parse(parts[0])
+            .map_err(|_e| net_error::DeserializeError("Failed to parse contract identifier of asset holding to page after".into()))?;
+        let asset_name = ClarityName::try_from(parts[1].to_string())
+            .map_err(|_e| net_error::DeserializeError("Failed to parse asset name of asset holding to page after".into()))?;
+        let value = match parts.get(2) {
+            Some(value_hex) => Some(Value::try_deserialize_hex_untyped(value_hex.trim_start_matches("0x"))
+                                    .map_err(|_e| net_error::DeserializeError("Failed to deserialize token of asset holding to page after".into()))?),
+            None => None
+        };
+
+        Ok(Some((AssetIdentifier { contract_identifier, asset_name }, value)))
+    }
+
+    /// Encode an asset holding to page after, as `get_asset_holdings_after_query` expects it
+    pub fn make_asset_holdings_cursor(holding: &AssetHolding) -> String {
+        match holding {
+            (asset_identifier, Some(value)) => {
+                let mut value_bytes = vec![];
+                value.serialize_write(&mut value_bytes).expect("FATAL: failed to serialize to vec");
+                format!("{}::0x{}", asset_identifier, to_hex(&value_bytes))
+            },
+            (asset_identifier, None) => format!("{}", asset_identifier)
+        }
+    }
+
     fn parse_call_read_only<R: Read>(protocol: &mut StacksHttp, preamble: &HttpRequestPreamble, captures: &Captures, query: Option<&str>, fd: &mut R) -> Result<HttpRequestType, net_error> {
         let content_len = preamble.get_content_length();
         if !(content_len > 0 && content_len < protocol.maximum_call_argument_size) {
@@ -1346,6 +1763,34 @@ impl HttpRequestType {
             contract_addr, contract_name, sender, func_name, arguments, tip))
     }
 
+    fn parse_eval_read_only<R: Read>(protocol: &mut StacksHttp, preamble: &HttpRequestPreamble, captures: &Captures, query: Option<&str>, fd: &mut R) -> Result<HttpRequestType, net_error> {
+        let content_len = preamble.get_content_length();
+        if !(content_len > 0 && content_len < protocol.maximum_call_argument_size) {
+            return Err(net_error::DeserializeError(format!("Invalid Http request: invalid body length for EvalReadOnly ({})", content_len)));
+        }
+
+        if preamble.content_type != Some(HttpContentType::JSON) {
+            return Err(net_error::DeserializeError("Invalid content-type: expected application/json".to_string()));
+        }
+
+        let contract_addr =  StacksAddress::from_string(&captures["address"])
+            .ok_or_else(|| net_error::DeserializeError("Failed to parse contract address".into()))?;
+        let contract_name = ContractName::try_from(captures["contract"].to_string())
+            .map_err(|_e| net_error::DeserializeError("Failed to parse contract name".into()))?;
+
+        let body: EvalReadOnlyRequestBody = serde_json::from_reader(fd)
+            .map_err(|_e| net_error::DeserializeError("Failed to parse JSON body".into()))?;
+
+        let sender = PrincipalData::parse(&body.sender)
+            .map_err(|_e| net_error::DeserializeError("Failed to parse sender principal".into()))?;
+
+        let tip = HttpRequestType::get_chain_tip_query(query);
+
+        Ok(HttpRequestType::EvalReadOnly(
+            HttpRequestMetadata::from_preamble(preamble),
+            contract_addr, contract_name, sender, body.expression, tip))
+    }
+
     fn parse_get_contract_arguments(preamble: &HttpRequestPreamble, captures: &Captures) -> Result<(HttpRequestMetadata, StacksAddress, ContractName), net_error> {
         if preamble.get_content_length() != 0 {
             return Err(net_error::DeserializeError("Invalid Http request: expected 0-length body".to_string()));
@@ -1420,6 +1865,96 @@ impl HttpRequestType {
         Ok(HttpRequestType::GetMicroblocksConfirmed(HttpRequestMetadata::from_preamble(preamble), block_hash))
     }
     
+    fn parse_gettransaction<R: Read>(_protocol: &mut StacksHttp, preamble: &HttpRequestPreamble, captures: &Captures, query: Option<&str>, _fd: &mut R) -> Result<HttpRequestType, net_error> {
+        if preamble.get_content_length() != 0 {
+            return Err(net_error::DeserializeError("Invalid Http request: expected 0-length body for GetTransaction".to_string()));
+        }
+
+        let txid_str = captures
+            .get(1)
+            .ok_or(net_error::DeserializeError("Failed to match path to txid group".to_string()))?
+            .as_str();
+
+        let txid = Txid::from_hex(txid_str)
+            .map_err(|_e| net_error::DeserializeError("Failed to parse txid".to_string()))?;
+
+        let tip = HttpRequestType::get_chain_tip_query(query);
+
+        Ok(HttpRequestType::GetTransaction(HttpRequestMetadata::from_preamble(preamble), txid, tip))
+    }
+
+    fn parse_getheader<R: Read>(_protocol: &mut StacksHttp, preamble: &HttpRequestPreamble, captures: &Captures, _query: Option<&str>, _fd: &mut R) -> Result<HttpRequestType, net_error> {
+        if preamble.get_content_length() != 0 {
+            return Err(net_error::DeserializeError("Invalid Http request: expected 0-length body for GetHeader".to_string()));
+        }
+
+        let block_hash_str = captures
+            .get(1)
+            .ok_or(net_error::DeserializeError("Failed to match path to index block hash group".to_string()))?
+            .as_str();
+
+        let index_block_hash = StacksBlockId::from_hex(block_hash_str)
+            .map_err(|_e| net_error::DeserializeError("Failed to parse index block hash".to_string()))?;
+
+        Ok(HttpRequestType::GetHeader(HttpRequestMetadata::from_preamble(preamble), index_block_hash))
+    }
+
+    fn parse_getheader_by_height<R: Read>(_protocol: &mut StacksHttp, preamble: &HttpRequestPreamble, captures: &Captures, query: Option<&str>, _fd: &mut R) -> Result<HttpRequestType, net_error> {
+        if preamble.get_content_length() != 0 {
+            return Err(net_error::DeserializeError("Invalid Http request: expected 0-length body for GetHeaderByHeight".to_string()));
+        }
+
+        let height_str = captures
+            .get(1)
+            .ok_or(net_error::DeserializeError("Failed to match path to block height group".to_string()))?
+            .as_str();
+
+        let height = u64::from_str_radix(height_str, 10)
+            .map_err(|_e| net_error::DeserializeError("Failed to parse block height".to_string()))?;
+
+        let tip = HttpRequestType::get_chain_tip_query(query);
+
+        Ok(HttpRequestType::GetHeaderByHeight(HttpRequestMetadata::from_preamble(preamble), height, tip))
+    }
+
+    fn parse_getheaders<R: Read>(_protocol: &mut StacksHttp, preamble: &HttpRequestPreamble, captures: &Captures, query: Option<&str>, _fd: &mut R) -> Result<HttpRequestType, net_error> {
+        if preamble.get_content_length() != 0 {
+            return Err(net_error::DeserializeError("Invalid Http request: expected 0-length body for GetHeaders".to_string()));
+        }
+
+        let start_height_str = captures
+            .get(1)
+            .ok_or(net_error::DeserializeError("Failed to match path to start height group".to_string()))?
+            .as_str();
+
+        let count_str = captures
+            .get(2)
+            .ok_or(net_error::DeserializeError("Failed to match path to header count group".to_string()))?
+            .as_str();
+
+        let start_height = u64::from_str_radix(start_height_str, 10)
+            .map_err(|_e| net_error::DeserializeError("Failed to parse start height".to_string()))?;
+
+        let count = u64::from_str_radix(count_str, 10)
+            .map_err(|_e| net_error::DeserializeError("Failed to parse header count".to_string()))?;
+
+        if count == 0 || count > MAX_HEADERS_RANGE_LEN {
+            return Err(net_error::DeserializeError(format!("Invalid header count: must be between 1 and {}", MAX_HEADERS_RANGE_LEN)));
+        }
+
+        let tip = HttpRequestType::get_chain_tip_query(query);
+
+        Ok(HttpRequestType::GetHeaders(HttpRequestMetadata::from_preamble(preamble), start_height, count, tip))
+    }
+
+    fn parse_getheader_tips<R: Read>(_protocol: &mut StacksHttp, preamble: &HttpRequestPreamble, _captures: &Captures, _query: Option<&str>, _fd: &mut R) -> Result<HttpRequestType, net_error> {
+        if preamble.get_content_length() != 0 {
+            return Err(net_error::DeserializeError("Invalid Http request: expected 0-length body for GetHeaderTips".to_string()));
+        }
+
+        Ok(HttpRequestType::GetHeaderTips(HttpRequestMetadata::from_preamble(preamble)))
+    }
+
     fn parse_getmicroblocks_unconfirmed<R: Read>(_protocol: &mut StacksHttp, preamble: &HttpRequestPreamble, captures: &Captures, _query: Option<&str>, _fd: &mut R) -> Result<HttpRequestType, net_error> {
         if preamble.get_content_length() != 0 {
             return Err(net_error::DeserializeError("Invalid Http request: expected 0-length body for GetMicrolocksUnconfirmed".to_string()));
@@ -1510,11 +2045,30 @@ impl HttpRequestType {
             HttpRequestType::PostTransaction(ref md, _) => md,
             HttpRequestType::PostMicroblock(ref md, ..) => md,
             HttpRequestType::GetAccount(ref md, ..) => md,
+            HttpRequestType::GetAccountNonces(ref md, ..) => md,
             HttpRequestType::GetMapEntry(ref md, ..) => md,
+            HttpRequestType::GetMapKeys(ref md, ..) => md,
+            HttpRequestType::GetDataVar(ref md, ..) => md,
+            HttpRequestType::GetFungibleTokenBalance(ref md, ..) => md,
+            HttpRequestType::GetFungibleTokenSupply(ref md, ..) => md,
+            HttpRequestType::GetNonFungibleTokenOwner(ref md, ..) => md,
+            HttpRequestType::GetAssetHoldings(ref md, ..) => md,
             HttpRequestType::GetTransferCost(ref md) => md,
             HttpRequestType::GetContractABI(ref md, ..) => md,
             HttpRequestType::GetContractSrc(ref md, ..) => md,
+            HttpRequestType::GetTransaction(ref md, ..) => md,
+            HttpRequestType::GetHeader(ref md, _) => md,
+            HttpRequestType::GetHeaderByHeight(ref md, ..) => md,
+            HttpRequestType::GetHeaders(ref md, ..) => md,
+            HttpRequestType::GetHeaderTips(ref md) => md,
+            HttpRequestType::GetFeeRates(ref md, _) => md,
+            HttpRequestType::EstimateTransactionFee(ref md, ..) => md,
+            HttpRequestType::GetMemPoolTxids(ref md, _) => md,
+            HttpRequestType::GetMemPoolTransaction(ref md, _) => md,
+            HttpRequestType::GetMemPoolTransactionsForAddress(ref md, _) => md,
+            HttpRequestType::GetEventStream(ref md, ..) => md,
             HttpRequestType::CallReadOnlyFunction(ref md, ..) => md,
+            HttpRequestType::EvalReadOnly(ref md, ..) => md,
             HttpRequestType::OptionsPreflight(ref md, ..) => md,
             HttpRequestType::ClientError(ref md, ..) => md,
         }
@@ -1531,11 +2085,30 @@ impl HttpRequestType {
             HttpRequestType::PostTransaction(ref mut md, _) => md,
             HttpRequestType::PostMicroblock(ref mut md, ..) => md,
             HttpRequestType::GetAccount(ref mut md, ..) => md,
+            HttpRequestType::GetAccountNonces(ref mut md, ..) => md,
             HttpRequestType::GetMapEntry(ref mut md, ..) => md,
+            HttpRequestType::GetMapKeys(ref mut md, ..) => md,
+            HttpRequestType::GetDataVar(ref mut md, ..) => md,
+            HttpRequestType::GetFungibleTokenBalance(ref mut md, ..) => md,
+            HttpRequestType::GetFungibleTokenSupply(ref mut md, ..) => md,
+            HttpRequestType::GetNonFungibleTokenOwner(ref mut md, ..) => md,
+            HttpRequestType::GetAssetHoldings(ref mut md, ..) => md,
             HttpRequestType::GetTransferCost(ref mut md) => md,
             HttpRequestType::GetContractABI(ref mut md, ..) => md,
             HttpRequestType::GetContractSrc(ref mut md, ..) => md,
+            HttpRequestType::GetTransaction(ref mut md, ..) => md,
+            HttpRequestType::GetHeader(ref mut md, _) => md,
+            HttpRequestType::GetHeaderByHeight(ref mut md, ..) => md,
+            HttpRequestType::GetHeaders(ref mut md, ..) => md,
+            HttpRequestType::GetHeaderTips(ref mut md) => md,
+            HttpRequestType::GetFeeRates(ref mut md, _) => md,
+            HttpRequestType::EstimateTransactionFee(ref mut md, ..) => md,
+            HttpRequestType::GetMemPoolTxids(ref mut md, _) => md,
+            HttpRequestType::GetMemPoolTransaction(ref mut md, _) => md,
+            HttpRequestType::GetMemPoolTransactionsForAddress(ref mut md, _) => md,
+            HttpRequestType::GetEventStream(ref mut md, ..) => md,
             HttpRequestType::CallReadOnlyFunction(ref mut md, ..) => md,
+            HttpRequestType::EvalReadOnly(ref mut md, ..) => md,
             HttpRequestType::OptionsPreflight(ref mut md, ..) => md,
             HttpRequestType::ClientError(ref mut md, ..) => md,
         }
@@ -1562,14 +2135,72 @@ impl HttpRequestType {
             HttpRequestType::GetMicroblocksConfirmed(_md, block_hash) => format!("/v2/microblocks/confirmed/{}", block_hash.to_hex()),
             HttpRequestType::GetMicroblocksUnconfirmed(_md, block_hash, min_seq) => format!("/v2/microblocks/unconfirmed/{}/{}", block_hash.to_hex(), min_seq),
             HttpRequestType::PostTransaction(_md, ..) => "/v2/transactions".to_string(),
+            HttpRequestType::GetTransaction(_md, txid, tip_opt) =>
+                format!("/v2/transactions/{}{}", txid.to_hex(), HttpRequestType::make_query_string(tip_opt.as_ref(), true)),
+            HttpRequestType::GetHeader(_md, index_block_hash) => format!("/v2/headers/{}", index_block_hash.to_hex()),
+            HttpRequestType::GetHeaderByHeight(_md, height, tip_opt) =>
+                format!("/v2/headers/height/{}{}", height, HttpRequestType::make_query_string(tip_opt.as_ref(), true)),
+            HttpRequestType::GetHeaders(_md, start_height, count, tip_opt) =>
+                format!("/v2/headers/range/{}/{}{}", start_height, count, HttpRequestType::make_query_string(tip_opt.as_ref(), true)),
+            HttpRequestType::GetHeaderTips(_md) => "/v2/headers/tips".to_string(),
             HttpRequestType::PostMicroblock(_md, _, tip_opt) =>
                 format!("/v2/microblocks{}", HttpRequestType::make_query_string(tip_opt.as_ref(), true)),
             HttpRequestType::GetAccount(_md, principal, tip_opt, with_proof) => 
                 format!("/v2/accounts/{}{}", &principal.to_string(), HttpRequestType::make_query_string(tip_opt.as_ref(), *with_proof)),
+            HttpRequestType::GetAccountNonces(_md, principal, tip_opt) =>
+                format!("/v2/accounts/{}/nonces{}", &principal.to_string(), HttpRequestType::make_query_string(tip_opt.as_ref(), true)),
             HttpRequestType::GetMapEntry(_md, contract_addr, contract_name, map_name, _key, tip_opt, with_proof) =>
                 format!("/v2/map_entry/{}/{}/{}{}",
                         &contract_addr.to_string(), contract_name.as_str(), map_name.as_str(), HttpRequestType::make_query_string(tip_opt.as_ref(), *with_proof)),
+            HttpRequestType::GetMapKeys(_md, contract_addr, contract_name, map_name, after_opt, tip_opt) => {
+                let query = HttpRequestType::make_query_string(tip_opt.as_ref(), true);
+                let after = match after_opt {
+                    Some(after) => format!("{}after=0x{}", if query.len() > 0 { "&" } else { "?" }, after),
+                    None => "".to_string()
+                };
+                format!("/v2/map_keys/{}/{}/{}{}{}",
+                        &contract_addr.to_string(), contract_name.as_str(), map_name.as_str(), query, after)
+            },
+            HttpRequestType::GetDataVar(_md, contract_addr, contract_name, var_name, tip_opt, with_proof) =>
+                format!("/v2/data_var/{}/{}/{}{}",
+                        &contract_addr.to_string(), contract_name.as_str(), var_name.as_str(), HttpRequestType::make_query_string(tip_opt.as_ref(), *with_proof)),
+            HttpRequestType::GetFungibleTokenBalance(_md, contract_addr, contract_name, token_name, principal, tip_opt, with_proof) =>
+                format!("/v2/ft/{}/{}/{}/balance/{}{}",
+                        &contract_addr.to_string(), contract_name.as_str(), token_name.as_str(), &principal.to_string(), HttpRequestType::make_query_string(tip_opt.as_ref(), *with_proof)),
+            HttpRequestType::GetFungibleTokenSupply(_md, contract_addr, contract_name, token_name, tip_opt, with_proof) =>
+                format!("/v2/ft/{}/{}/{}/supply{}",
+                        &contract_addr.to_string(), contract_name.as_str(), token_name.as_str(), HttpRequestType::make_query_string(tip_opt.as_ref(), *with_proof)),
+            HttpRequestType::GetNonFungibleTokenOwner(_md, contract_addr, contract_name, asset_name, value, tip_opt, with_proof) => {
+                let mut value_bytes = vec![];
+                value.serialize_write(&mut value_bytes).expect("FATAL: failed to serialize to vec");
+                format!("/v2/nft/{}/{}/{}/owner/0x{}{}",
+                        &contract_addr.to_string(), contract_name.as_str(), asset_name.as_str(), to_hex(&value_bytes), HttpRequestType::make_query_string(tip_opt.as_ref(), *with_proof))
+            },
+            HttpRequestType::GetAssetHoldings(_md, principal, after_opt, tip_opt, with_proof) => {
+                let query = HttpRequestType::make_query_string(tip_opt.as_ref(), *with_proof);
+                let after = match after_opt {
+                    Some(after) => format!("{}after={}", if query.len() > 0 { "&" } else { "?" }, HttpRequestType::make_asset_holdings_cursor(after)),
+                    None => "".to_string()
+                };
+                format!("/v2/assets/{}{}{}", &principal.to_string(), query, after)
+            },
             HttpRequestType::GetTransferCost(_md) => "/v2/fees/transfer".into(),
+            HttpRequestType::GetFeeRates(_md, tip_opt) =>
+                format!("/v2/fees/rates{}", HttpRequestType::make_query_string(tip_opt.as_ref(), true)),
+            HttpRequestType::EstimateTransactionFee(_md, _, tip_opt) =>
+                format!("/v2/fees/transaction{}", HttpRequestType::make_query_string(tip_opt.as_ref(), true)),
+            HttpRequestType::GetMemPoolTxids(_md, page) => format!("/v2/mempool/txids?page={}", page),
+            HttpRequestType::GetMemPoolTransaction(_md, txid) => format!("/v2/mempool/transactions/{}", txid.to_hex()),
+            HttpRequestType::GetMemPoolTransactionsForAddress(_md, addr) => format!("/v2/mempool/address/{}", addr),
+            HttpRequestType::GetEventStream(_md, _key, topics) => {
+                if topics.len() > 0 {
+                    let topic_strs : Vec<String> = topics.iter().map(|topic| topic.to_string()).collect();
+                    format!("/v2/events?topics={}", topic_strs.join(","))
+                }
+                else {
+                    "/v2/events".to_string()
+                }
+            },
             HttpRequestType::GetContractABI(_, contract_addr, contract_name, tip_opt) =>
                 format!("/v2/contracts/interface/{}/{}{}", contract_addr, contract_name.as_str(), HttpRequestType::make_query_string(tip_opt.as_ref(), true)),
             HttpRequestType::GetContractSrc(_, contract_addr, contract_name, tip_opt, with_proof) => 
@@ -1577,6 +2208,9 @@ impl HttpRequestType {
             HttpRequestType::CallReadOnlyFunction(_, contract_addr, contract_name, _, func_name, _, tip_opt) => {
                 format!("/v2/contracts/call-read/{}/{}/{}{}", contract_addr, contract_name.as_str(), func_name.as_str(), HttpRequestType::make_query_string(tip_opt.as_ref(), true))
             },
+            HttpRequestType::EvalReadOnly(_, contract_addr, contract_name, _, _, tip_opt) => {
+                format!("/v2/contracts/eval/{}/{}{}", contract_addr, contract_name.as_str(), HttpRequestType::make_query_string(tip_opt.as_ref(), true))
+            },
             HttpRequestType::OptionsPreflight(_md, path) => path.to_string(),
             HttpRequestType::ClientError(_md, e) => {
                 match e {
@@ -1596,6 +2230,13 @@ impl HttpRequestType {
                 HttpRequestPreamble::new_serialized(fd, &md.version, "POST", &self.request_path(), &md.peer, md.keep_alive, Some(tx_bytes.len() as u32), Some(&HttpContentType::Bytes), empty_headers)?;
                 fd.write_all(&tx_bytes).map_err(net_error::WriteError)?;
             },
+            HttpRequestType::EstimateTransactionFee(md, tx, ..) => {
+                let mut tx_bytes = vec![];
+                write_next(&mut tx_bytes, tx)?;
+
+                HttpRequestPreamble::new_serialized(fd, &md.version, "POST", &self.request_path(), &md.peer, md.keep_alive, Some(tx_bytes.len() as u32), Some(&HttpContentType::Bytes), empty_headers)?;
+                fd.write_all(&tx_bytes).map_err(net_error::WriteError)?;
+            },
             HttpRequestType::PostMicroblock(md, mb, ..) => {
                 let mut mb_bytes = vec![];
                 write_next(&mut mb_bytes, mb)?;
@@ -1630,6 +2271,27 @@ impl HttpRequestType {
                 HttpRequestPreamble::new_serialized(fd, &md.version, "POST", &self.request_path(), &md.peer, md.keep_alive, Some(request_body_bytes.len() as u32), Some(&HttpContentType::JSON), empty_headers)?;
                 fd.write_all(&request_body_bytes).map_err(net_error::WriteError)?;
             }
+            HttpRequestType::EvalReadOnly(md, _contract_addr, _contract_name, sender, expression, ..) => {
+                let request_body = EvalReadOnlyRequestBody {
+                    sender: sender.to_string(),
+                    expression: expression.clone()
+                };
+
+                let mut request_body_bytes = vec![];
+                serde_json::to_writer(&mut request_body_bytes, &request_body).map_err(|e| net_error::SerializeError(format!("Failed to serialize read-only eval to JSON: {:?}", &e)))?;
+
+                HttpRequestPreamble::new_serialized(fd, &md.version, "POST", &self.request_path(), &md.peer, md.keep_alive, Some(request_body_bytes.len() as u32), Some(&HttpContentType::JSON), empty_headers)?;
+                fd.write_all(&request_body_bytes).map_err(net_error::WriteError)?;
+            }
+            HttpRequestType::GetEventStream(md, key, ..) => {
+                HttpRequestPreamble::new_serialized(fd, &md.version, "GET", &self.request_path(), &md.peer, md.keep_alive, None, None,
+                                                    |ref mut fd| {
+                                                        fd.write_all("Upgrade: websocket\r\nConnection: Upgrade\r\n".as_bytes()).map_err(net_error::WriteError)?;
+                                                        fd.write_all(format!("Sec-WebSocket-Key: {}\r\n", key).as_bytes()).map_err(net_error::WriteError)?;
+                                                        fd.write_all(format!("Sec-WebSocket-Version: {}\r\n", websocket::WEBSOCKET_VERSION).as_bytes()).map_err(net_error::WriteError)?;
+                                                        Ok(())
+                                                    })?;
+            },
             other_type => {
                 let md = other_type.metadata();
                 let request_path = other_type.request_path();
@@ -1804,12 +2466,30 @@ impl HttpResponseType {
             (&PATH_GETMICROBLOCKS_CONFIRMED, &HttpResponseType::parse_microblocks),
             (&PATH_GETMICROBLOCKS_UNCONFIRMED, &HttpResponseType::parse_microblocks_unconfirmed),
             (&PATH_POSTTRANSACTION, &HttpResponseType::parse_txid),
+            (&PATH_GETTRANSACTION, &HttpResponseType::parse_get_transaction),
+            (&PATH_GETHEADER, &HttpResponseType::parse_get_header),
+            (&PATH_GETHEADER_BY_HEIGHT, &HttpResponseType::parse_get_header),
+            (&PATH_GETHEADERS, &HttpResponseType::parse_get_headers),
+            (&PATH_GETHEADER_TIPS, &HttpResponseType::parse_get_headers),
+            (&PATH_GET_FEE_RATES, &HttpResponseType::parse_fee_rates),
+            (&PATH_POST_FEE_ESTIMATE, &HttpResponseType::parse_transaction_fee_estimate),
+            (&PATH_GET_MEMPOOL_TXIDS, &HttpResponseType::parse_mempool_txids),
+            (&PATH_GET_MEMPOOL_TRANSACTION, &HttpResponseType::parse_mempool_transaction),
+            (&PATH_GET_MEMPOOL_ADDRESS, &HttpResponseType::parse_mempool_transactions),
             (&PATH_POSTMICROBLOCK, &HttpResponseType::parse_microblock_hash),
             (&PATH_GET_ACCOUNT, &HttpResponseType::parse_get_account),
+            (&PATH_GET_ACCOUNT_NONCES, &HttpResponseType::parse_get_account_nonces),
             (&PATH_GET_CONTRACT_SRC, &HttpResponseType::parse_get_contract_src),
             (&PATH_GET_CONTRACT_ABI, &HttpResponseType::parse_get_contract_abi),
             (&PATH_POST_CALL_READ_ONLY, &HttpResponseType::parse_call_read_only),
+            (&PATH_POST_EVAL_READ_ONLY, &HttpResponseType::parse_eval_read_only),
             (&PATH_GET_MAP_ENTRY, &HttpResponseType::parse_get_map_entry),
+            (&PATH_GET_MAP_KEYS, &HttpResponseType::parse_get_map_keys),
+            (&PATH_GET_DATA_VAR, &HttpResponseType::parse_get_data_var),
+            (&PATH_GET_FT_BALANCE, &HttpResponseType::parse_get_ft_balance),
+            (&PATH_GET_FT_SUPPLY, &HttpResponseType::parse_get_ft_supply),
+            (&PATH_GET_NFT_OWNER, &HttpResponseType::parse_get_nft_owner),
+            (&PATH_GET_ASSET_HOLDINGS, &HttpResponseType::parse_get_asset_holdings),
         ];
         
         // use url::Url to parse path and query string
@@ -1866,11 +2546,86 @@ impl HttpResponseType {
         Ok(HttpResponseType::GetAccount(HttpResponseMetadata::from_preamble(request_version, preamble), account_entry))
     }
 
+    fn parse_get_account_nonces<R: Read>(_protocol: &mut StacksHttp, request_version: HttpVersion, preamble: &HttpResponsePreamble, fd: &mut R, len_hint: Option<usize>) -> Result<HttpResponseType, net_error> {
+        let account_nonces = HttpResponseType::parse_json(preamble, fd, len_hint, MAX_MESSAGE_LEN as u64)?;
+        Ok(HttpResponseType::GetAccountNonces(HttpResponseMetadata::from_preamble(request_version, preamble), account_nonces))
+    }
+
+    fn parse_get_transaction<R: Read>(_protocol: &mut StacksHttp, request_version: HttpVersion, preamble: &HttpResponsePreamble, fd: &mut R, len_hint: Option<usize>) -> Result<HttpResponseType, net_error> {
+        let tx_info = HttpResponseType::parse_json(preamble, fd, len_hint, MAX_MESSAGE_LEN as u64)?;
+        Ok(HttpResponseType::GetTransaction(HttpResponseMetadata::from_preamble(request_version, preamble), tx_info))
+    }
+
+    fn parse_get_header<R: Read>(_protocol: &mut StacksHttp, request_version: HttpVersion, preamble: &HttpResponsePreamble, fd: &mut R, len_hint: Option<usize>) -> Result<HttpResponseType, net_error> {
+        let header_info = HttpResponseType::parse_json(preamble, fd, len_hint, MAX_MESSAGE_LEN as u64)?;
+        Ok(HttpResponseType::GetHeader(HttpResponseMetadata::from_preamble(request_version, preamble), header_info))
+    }
+
+    fn parse_get_headers<R: Read>(_protocol: &mut StacksHttp, request_version: HttpVersion, preamble: &HttpResponsePreamble, fd: &mut R, len_hint: Option<usize>) -> Result<HttpResponseType, net_error> {
+        let header_infos = HttpResponseType::parse_json(preamble, fd, len_hint, MAX_MESSAGE_LEN as u64)?;
+        Ok(HttpResponseType::GetHeaders(HttpResponseMetadata::from_preamble(request_version, preamble), header_infos))
+    }
+
+    fn parse_fee_rates<R: Read>(_protocol: &mut StacksHttp, request_version: HttpVersion, preamble: &HttpResponsePreamble, fd: &mut R, len_hint: Option<usize>) -> Result<HttpResponseType, net_error> {
+        let fee_rates = HttpResponseType::parse_json(preamble, fd, len_hint, MAX_MESSAGE_LEN as u64)?;
+        Ok(HttpResponseType::FeeRates(HttpResponseMetadata::from_preamble(request_version, preamble), fee_rates))
+    }
+
+    fn parse_transaction_fee_estimate<R: Read>(_protocol: &mut StacksHttp, request_version: HttpVersion, preamble: &HttpResponsePreamble, fd: &mut R, len_hint: Option<usize>) -> Result<HttpResponseType, net_error> {
+        let fee_estimate = HttpResponseType::parse_json(preamble, fd, len_hint, MAX_MESSAGE_LEN as u64)?;
+        Ok(HttpResponseType::TransactionFeeEstimate(HttpResponseMetadata::from_preamble(request_version, preamble), fee_estimate))
+    }
+
+    fn parse_mempool_txids<R: Read>(_protocol: &mut StacksHttp, request_version: HttpVersion, preamble: &HttpResponsePreamble, fd: &mut R, len_hint: Option<usize>) -> Result<HttpResponseType, net_error> {
+        let txids = HttpResponseType::parse_json(preamble, fd, len_hint, MAX_MESSAGE_LEN as u64)?;
+        Ok(HttpResponseType::MemPoolTxids(HttpResponseMetadata::from_preamble(request_version, preamble), txids))
+    }
+
+    fn parse_mempool_transaction<R: Read>(_protocol: &mut StacksHttp, request_version: HttpVersion, preamble: &HttpResponsePreamble, fd: &mut R, len_hint: Option<usize>) -> Result<HttpResponseType, net_error> {
+        let tx_info = HttpResponseType::parse_json(preamble, fd, len_hint, MAX_MESSAGE_LEN as u64)?;
+        Ok(HttpResponseType::MemPoolTransaction(HttpResponseMetadata::from_preamble(request_version, preamble), tx_info))
+    }
+
+    fn parse_mempool_transactions<R: Read>(_protocol: &mut StacksHttp, request_version: HttpVersion, preamble: &HttpResponsePreamble, fd: &mut R, len_hint: Option<usize>) -> Result<HttpResponseType, net_error> {
+        let tx_infos = HttpResponseType::parse_json(preamble, fd, len_hint, MAX_MESSAGE_LEN as u64)?;
+        Ok(HttpResponseType::MemPoolTransactions(HttpResponseMetadata::from_preamble(request_version, preamble), tx_infos))
+    }
+
     fn parse_get_map_entry<R: Read>(_protocol: &mut StacksHttp, request_version: HttpVersion, preamble: &HttpResponsePreamble, fd: &mut R, len_hint: Option<usize>) -> Result<HttpResponseType, net_error> {
         let map_entry = HttpResponseType::parse_json(preamble, fd, len_hint, MAX_MESSAGE_LEN as u64)?;
         Ok(HttpResponseType::GetMapEntry(HttpResponseMetadata::from_preamble(request_version, preamble), map_entry))
     }
 
+    fn parse_get_map_keys<R: Read>(_protocol: &mut StacksHttp, request_version: HttpVersion, preamble: &HttpResponsePreamble, fd: &mut R, len_hint: Option<usize>) -> Result<HttpResponseType, net_error> {
+        let map_keys = HttpResponseType::parse_json(preamble, fd, len_hint, MAX_MESSAGE_LEN as u64)?;
+        Ok(HttpResponseType::GetMapKeys(HttpResponseMetadata::from_preamble(request_version, preamble), map_keys))
+    }
+
+    fn parse_get_data_var<R: Read>(_protocol: &mut StacksHttp, request_version: HttpVersion, preamble: &HttpResponsePreamble, fd: &mut R, len_hint: Option<usize>) -> Result<HttpResponseType, net_error> {
+        let data_var = HttpResponseType::parse_json(preamble, fd, len_hint, MAX_MESSAGE_LEN as u64)?;
+        Ok(HttpResponseType::GetDataVar(HttpResponseMetadata::from_preamble(request_version, preamble), data_var))
+    }
+
+    fn parse_get_ft_balance<R: Read>(_protocol: &mut StacksHttp, request_version: HttpVersion, preamble: &HttpResponsePreamble, fd: &mut R, len_hint: Option<usize>) -> Result<HttpResponseType, net_error> {
+        let ft_balance = HttpResponseType::parse_json(preamble, fd, len_hint, MAX_MESSAGE_LEN as u64)?;
+        Ok(HttpResponseType::GetFungibleTokenBalance(HttpResponseMetadata::from_preamble(request_version, preamble), ft_balance))
+    }
+
+    fn parse_get_ft_supply<R: Read>(_protocol: &mut StacksHttp, request_version: HttpVersion, preamble: &HttpResponsePreamble, fd: &mut R, len_hint: Option<usize>) -> Result<HttpResponseType, net_error> {
+        let ft_supply = HttpResponseType::parse_json(preamble, fd, len_hint, MAX_MESSAGE_LEN as u64)?;
+        Ok(HttpResponseType::GetFungibleTokenSupply(HttpResponseMetadata::from_preamble(request_version, preamble), ft_supply))
+    }
+
+    fn parse_get_nft_owner<R: Read>(_protocol: &mut StacksHttp, request_version: HttpVersion, preamble: &HttpResponsePreamble, fd: &mut R, len_hint: Option<usize>) -> Result<HttpResponseType, net_error> {
+        let nft_owner = HttpResponseType::parse_json(preamble, fd, len_hint, MAX_MESSAGE_LEN as u64)?;
+        Ok(HttpResponseType::GetNonFungibleTokenOwner(HttpResponseMetadata::from_preamble(request_version, preamble), nft_owner))
+    }
+
+    fn parse_get_asset_holdings<R: Read>(_protocol: &mut StacksHttp, request_version: HttpVersion, preamble: &HttpResponsePreamble, fd: &mut R, len_hint: Option<usize>) -> Result<HttpResponseType, net_error> {
+        let holdings = HttpResponseType::parse_json(preamble, fd, len_hint, MAX_MESSAGE_LEN as u64)?;
+        Ok(HttpResponseType::GetAssetHoldings(HttpResponseMetadata::from_preamble(request_version, preamble), holdings))
+    }
+
     fn parse_get_contract_src<R: Read>(_protocol: &mut StacksHttp, request_version: HttpVersion, preamble: &HttpResponsePreamble, fd: &mut R, len_hint: Option<usize>) -> Result<HttpResponseType, net_error> {
         let src_data = HttpResponseType::parse_json(preamble, fd, len_hint, MAX_MESSAGE_LEN as u64)?;
         Ok(HttpResponseType::GetContractSrc(HttpResponseMetadata::from_preamble(request_version, preamble), src_data))
@@ -1886,6 +2641,11 @@ impl HttpResponseType {
         Ok(HttpResponseType::CallReadOnlyFunction(HttpResponseMetadata::from_preamble(request_version, preamble), call_data))
     }
 
+    fn parse_eval_read_only<R: Read>(_protocol: &mut StacksHttp, request_version: HttpVersion, preamble: &HttpResponsePreamble, fd: &mut R, len_hint: Option<usize>) -> Result<HttpResponseType, net_error> {
+        let eval_data = HttpResponseType::parse_json(preamble, fd, len_hint, MAX_MESSAGE_LEN as u64)?;
+        Ok(HttpResponseType::EvalReadOnly(HttpResponseMetadata::from_preamble(request_version, preamble), eval_data))
+    }
+
     fn parse_microblocks_unconfirmed<R: Read>(_protocol: &mut StacksHttp, request_version: HttpVersion, preamble: &HttpResponsePreamble, fd: &mut R, len_hint: Option<usize>) -> Result<HttpResponseType, net_error> {
         // NOTE: there will be no length prefix on this, but we won't ever get more than
         // MAX_MICROBLOCKS_UNCONFIRMED microblocks
@@ -1969,10 +2729,27 @@ impl HttpResponseType {
             HttpResponseType::MicroblockHash(ref md, _) => md,
             HttpResponseType::TokenTransferCost(ref md, _) => md,
             HttpResponseType::GetMapEntry(ref md, _) => md,
+            HttpResponseType::GetMapKeys(ref md, _) => md,
+            HttpResponseType::GetDataVar(ref md, _) => md,
+            HttpResponseType::GetFungibleTokenBalance(ref md, _) => md,
+            HttpResponseType::GetFungibleTokenSupply(ref md, _) => md,
+            HttpResponseType::GetNonFungibleTokenOwner(ref md, _) => md,
+            HttpResponseType::GetAssetHoldings(ref md, _) => md,
             HttpResponseType::GetAccount(ref md, _) => md,
+            HttpResponseType::GetAccountNonces(ref md, _) => md,
             HttpResponseType::GetContractABI(ref md, _) => md,
             HttpResponseType::GetContractSrc(ref md, _) => md,
+            HttpResponseType::GetTransaction(ref md, _) => md,
+            HttpResponseType::GetHeader(ref md, _) => md,
+            HttpResponseType::GetHeaders(ref md, _) => md,
+            HttpResponseType::FeeRates(ref md, _) => md,
+            HttpResponseType::TransactionFeeEstimate(ref md, _) => md,
+            HttpResponseType::MemPoolTxids(ref md, _) => md,
+            HttpResponseType::MemPoolTransaction(ref md, _) => md,
+            HttpResponseType::MemPoolTransactions(ref md, _) => md,
+            HttpResponseType::EventStreamUpgrade(ref md, _) => md,
             HttpResponseType::CallReadOnlyFunction(ref md, _) => md,
+            HttpResponseType::EvalReadOnly(ref md, _) => md,
             HttpResponseType::OptionsPreflight(ref md) => md,
             // errors
             HttpResponseType::BadRequestJSON(ref md, _) => md,
@@ -2038,6 +2815,10 @@ impl HttpResponseType {
                 HttpResponsePreamble::ok_JSON_from_md(fd, md)?;
                 HttpResponseType::send_json(protocol, md, fd, account_data)?;
             },
+            HttpResponseType::GetAccountNonces(ref md, ref account_nonces) => {
+                HttpResponsePreamble::ok_JSON_from_md(fd, md)?;
+                HttpResponseType::send_json(protocol, md, fd, account_nonces)?;
+            },
             HttpResponseType::GetContractABI(ref md, ref data) => {
                 HttpResponsePreamble::ok_JSON_from_md(fd, md)?;
                 HttpResponseType::send_json(protocol, md, fd, data)?;
@@ -2054,10 +2835,73 @@ impl HttpResponseType {
                 HttpResponsePreamble::ok_JSON_from_md(fd, md)?;
                 HttpResponseType::send_json(protocol, md, fd, data)?;
             },
+            HttpResponseType::EvalReadOnly(ref md, ref data) => {
+                HttpResponsePreamble::ok_JSON_from_md(fd, md)?;
+                HttpResponseType::send_json(protocol, md, fd, data)?;
+            },
             HttpResponseType::GetMapEntry(ref md, ref map_data) => {
                 HttpResponsePreamble::ok_JSON_from_md(fd, md)?;
                 HttpResponseType::send_json(protocol, md, fd, map_data)?;
             },
+            HttpResponseType::GetMapKeys(ref md, ref map_keys) => {
+                HttpResponsePreamble::ok_JSON_from_md(fd, md)?;
+                HttpResponseType::send_json(protocol, md, fd, map_keys)?;
+            },
+            HttpResponseType::GetDataVar(ref md, ref data_var) => {
+                HttpResponsePreamble::ok_JSON_from_md(fd, md)?;
+                HttpResponseType::send_json(protocol, md, fd, data_var)?;
+            },
+            HttpResponseType::GetFungibleTokenBalance(ref md, ref ft_balance) => {
+                HttpResponsePreamble::ok_JSON_from_md(fd, md)?;
+                HttpResponseType::send_json(protocol, md, fd, ft_balance)?;
+            },
+            HttpResponseType::GetFungibleTokenSupply(ref md, ref ft_supply) => {
+                HttpResponsePreamble::ok_JSON_from_md(fd, md)?;
+                HttpResponseType::send_json(protocol, md, fd, ft_supply)?;
+            },
+            HttpResponseType::GetNonFungibleTokenOwner(ref md, ref nft_owner) => {
+                HttpResponsePreamble::ok_JSON_from_md(fd, md)?;
+                HttpResponseType::send_json(protocol, md, fd, nft_owner)?;
+            },
+            HttpResponseType::GetAssetHoldings(ref md, ref holdings) => {
+                HttpResponsePreamble::ok_JSON_from_md(fd, md)?;
+                HttpResponseType::send_json(protocol, md, fd, holdings)?;
+            },
+            HttpResponseType::GetTransaction(ref md, ref tx_info) => {
+                HttpResponsePreamble::ok_JSON_from_md(fd, md)?;
+                HttpResponseType::send_json(protocol, md, fd, tx_info)?;
+            },
+            HttpResponseType::GetHeader(ref md, ref header_info) => {
+                HttpResponsePreamble::ok_JSON_from_md(fd, md)?;
+                HttpResponseType::send_json(protocol, md, fd, header_info)?;
+            },
+            HttpResponseType::GetHeaders(ref md, ref header_infos) => {
+                HttpResponsePreamble::ok_JSON_from_md(fd, md)?;
+                HttpResponseType::send_json(protocol, md, fd, header_infos)?;
+            },
+            HttpResponseType::FeeRates(ref md, ref fee_rates) => {
+                HttpResponsePreamble::ok_JSON_from_md(fd, md)?;
+                HttpResponseType::send_json(protocol, md, fd, fee_rates)?;
+            },
+            HttpResponseType::TransactionFeeEstimate(ref md, ref fee_estimate) => {
+                HttpResponsePreamble::ok_JSON_from_md(fd, md)?;
+                HttpResponseType::send_json(protocol, md, fd, fee_estimate)?;
+            },
+            HttpResponseType::MemPoolTxids(ref md, ref txids) => {
+                HttpResponsePreamble::ok_JSON_from_md(fd, md)?;
+                HttpResponseType::send_json(protocol, md, fd, txids)?;
+            },
+            HttpResponseType::MemPoolTransaction(ref md, ref tx_info) => {
+                HttpResponsePreamble::ok_JSON_from_md(fd, md)?;
+                HttpResponseType::send_json(protocol, md, fd, tx_info)?;
+            },
+            HttpResponseType::MemPoolTransactions(ref md, ref tx_infos) => {
+                HttpResponsePreamble::ok_JSON_from_md(fd, md)?;
+                HttpResponseType::send_json(protocol, md, fd, tx_infos)?;
+            },
+            HttpResponseType::EventStreamUpgrade(ref md, ref accept_key) => {
+                HttpResponsePreamble::websocket_upgrade(fd, accept_key, md.request_id)?;
+            },
             HttpResponseType::PeerInfo(ref md, ref peer_info) => {
                 HttpResponsePreamble::ok_JSON_from_md(fd, md)?;
                 HttpResponseType::send_json(protocol, md, fd, peer_info)?;
@@ -2171,23 +3015,50 @@ impl MessageSequence for StacksHttpMessage {
                 HttpRequestType::GetMicroblocksConfirmed(_, _) => "HTTP(GetMicroblocksConfirmed)",
                 HttpRequestType::GetMicroblocksUnconfirmed(_, _, _) => "HTTP(GetMicroblocksUnconfirmed)",
                 HttpRequestType::PostTransaction(_, _) => "HTTP(PostTransaction)",
+                HttpRequestType::GetTransaction(..) => "HTTP(GetTransaction)",
+                HttpRequestType::GetHeader(..) => "HTTP(GetHeader)",
+                HttpRequestType::GetHeaderByHeight(..) => "HTTP(GetHeaderByHeight)",
+                HttpRequestType::GetHeaders(..) => "HTTP(GetHeaders)",
+                HttpRequestType::GetHeaderTips(_) => "HTTP(GetHeaderTips)",
+                HttpRequestType::GetFeeRates(..) => "HTTP(GetFeeRates)",
+                HttpRequestType::EstimateTransactionFee(..) => "HTTP(EstimateTransactionFee)",
+                HttpRequestType::GetMemPoolTxids(..) => "HTTP(GetMemPoolTxids)",
+                HttpRequestType::GetMemPoolTransaction(..) => "HTTP(GetMemPoolTransaction)",
+                HttpRequestType::GetMemPoolTransactionsForAddress(..) => "HTTP(GetMemPoolTransactionsForAddress)",
+                HttpRequestType::GetEventStream(..) => "HTTP(GetEventStream)",
                 HttpRequestType::PostMicroblock(..) => "HTTP(PostMicroblock)",
                 HttpRequestType::GetAccount(..) => "HTTP(GetAccount)",
+                HttpRequestType::GetAccountNonces(..) => "HTTP(GetAccountNonces)",
                 HttpRequestType::GetMapEntry(..) => "HTTP(GetMapEntry)",
+                HttpRequestType::GetMapKeys(..) => "HTTP(GetMapKeys)",
+                HttpRequestType::GetDataVar(..) => "HTTP(GetDataVar)",
+                HttpRequestType::GetFungibleTokenBalance(..) => "HTTP(GetFungibleTokenBalance)",
+                HttpRequestType::GetFungibleTokenSupply(..) => "HTTP(GetFungibleTokenSupply)",
+                HttpRequestType::GetNonFungibleTokenOwner(..) => "HTTP(GetNonFungibleTokenOwner)",
+                HttpRequestType::GetAssetHoldings(..) => "HTTP(GetAssetHoldings)",
                 HttpRequestType::GetTransferCost(_) => "HTTP(GetTransferCost)",
                 HttpRequestType::GetContractABI(..) => "HTTP(GetContractABI)",
                 HttpRequestType::GetContractSrc(..) => "HTTP(GetContractSrc)",
                 HttpRequestType::CallReadOnlyFunction(..) => "HTTP(CallReadOnlyFunction)",
+                HttpRequestType::EvalReadOnly(..) => "HTTP(EvalReadOnly)",
                 HttpRequestType::OptionsPreflight(..) => "HTTP(OptionsPreflight)",
                 HttpRequestType::ClientError(..) => "HTTP(ClientError)",
             },
             StacksHttpMessage::Response(ref res) => match res {
                 HttpResponseType::TokenTransferCost(_, _) => "HTTP(TokenTransferCost)",
                 HttpResponseType::GetMapEntry(_, _) => "HTTP(GetMapEntry)",
+                HttpResponseType::GetMapKeys(_, _) => "HTTP(GetMapKeys)",
+                HttpResponseType::GetDataVar(_, _) => "HTTP(GetDataVar)",
+                HttpResponseType::GetFungibleTokenBalance(_, _) => "HTTP(GetFungibleTokenBalance)",
+                HttpResponseType::GetFungibleTokenSupply(_, _) => "HTTP(GetFungibleTokenSupply)",
+                HttpResponseType::GetNonFungibleTokenOwner(_, _) => "HTTP(GetNonFungibleTokenOwner)",
+                HttpResponseType::GetAssetHoldings(_, _) => "HTTP(GetAssetHoldings)",
                 HttpResponseType::GetAccount(_, _) => "HTTP(GetAccount)",
+                HttpResponseType::GetAccountNonces(_, _) => "HTTP(GetAccountNonces)",
                 HttpResponseType::GetContractABI(..) => "HTTP(GetContractABI)",
                 HttpResponseType::GetContractSrc(..) => "HTTP(GetContractSrc)",
                 HttpResponseType::CallReadOnlyFunction(..) => "HTTP(CallReadOnlyFunction)",
+                HttpResponseType::EvalReadOnly(..) => "HTTP(EvalReadOnly)",
                 HttpResponseType::PeerInfo(_, _) => "HTTP(PeerInfo)",
                 HttpResponseType::Neighbors(_, _) => "HTTP(Neighbors)",
                 HttpResponseType::Block(_, _) => "HTTP(Block)",
@@ -2195,6 +3066,15 @@ impl MessageSequence for StacksHttpMessage {
                 HttpResponseType::Microblocks(_, _) => "HTTP(Microblocks)",
                 HttpResponseType::MicroblockStream(_) => "HTTP(MicroblockStream)",
                 HttpResponseType::TransactionID(_, _) => "HTTP(Transaction)",
+                HttpResponseType::GetTransaction(_, _) => "HTTP(GetTransaction)",
+                HttpResponseType::GetHeader(_, _) => "HTTP(GetHeader)",
+                HttpResponseType::GetHeaders(_, _) => "HTTP(GetHeaders)",
+                HttpResponseType::FeeRates(_, _) => "HTTP(FeeRates)",
+                HttpResponseType::TransactionFeeEstimate(_, _) => "HTTP(TransactionFeeEstimate)",
+                HttpResponseType::MemPoolTxids(_, _) => "HTTP(MemPoolTxids)",
+                HttpResponseType::MemPoolTransaction(_, _) => "HTTP(MemPoolTransaction)",
+                HttpResponseType::MemPoolTransactions(_, _) => "HTTP(MemPoolTransactions)",
+                HttpResponseType::EventStreamUpgrade(_, _) => "HTTP(EventStreamUpgrade)",
                 HttpResponseType::MicroblockHash(_, _) => "HTTP(Microblock)",
                 HttpResponseType::OptionsPreflight(_) => "HTTP(OptionsPreflight)",
                 HttpResponseType::BadRequestJSON(..) | HttpResponseType::BadRequest(..) => "HTTP(400)",
@@ -2645,6 +3525,7 @@ mod test {
 
     use util::hash::Hash160;
     use util::hash::Sha512Trunc256Sum;
+    use vm::types::QualifiedContractIdentifier;
     use util::hash::MerkleTree;
     use util::hash::to_hex;
 
@@ -3314,6 +4195,14 @@ mod test {
             HttpRequestType::GetNeighbors(http_request_metadata_ip.clone()),
             HttpRequestType::GetBlock(http_request_metadata_dns.clone(), StacksBlockId([2u8; 32])),
             HttpRequestType::GetMicroblocksIndexed(http_request_metadata_ip.clone(), StacksBlockId([3u8; 32])),
+            HttpRequestType::GetTransaction(http_request_metadata_dns.clone(), Txid([4u8; 32]), None),
+            HttpRequestType::GetHeader(http_request_metadata_ip.clone(), StacksBlockId([5u8; 32])),
+            HttpRequestType::GetHeaderByHeight(http_request_metadata_dns.clone(), 123, None),
+            HttpRequestType::GetHeaders(http_request_metadata_ip.clone(), 45, 67, None),
+            HttpRequestType::GetHeaderTips(http_request_metadata_dns.clone()),
+            HttpRequestType::GetFeeRates(http_request_metadata_dns.clone(), None),
+            HttpRequestType::GetMemPoolTxids(http_request_metadata_ip.clone(), 2),
+            HttpRequestType::GetMemPoolTransaction(http_request_metadata_dns.clone(), Txid([6u8; 32])),
             HttpRequestType::PostTransaction(http_request_metadata_dns.clone(), make_test_transaction()),
             HttpRequestType::OptionsPreflight(http_request_metadata_ip.clone(), "/".to_string()),
         ];
@@ -3330,11 +4219,27 @@ mod test {
             HttpRequestPreamble::new(HttpVersion::Http11, "GET".to_string(), "/v2/neighbors".to_string(), http_request_metadata_ip.peer.hostname(), http_request_metadata_ip.peer.port(), http_request_metadata_ip.keep_alive),
             HttpRequestPreamble::new(HttpVersion::Http11, "GET".to_string(), format!("/v2/blocks/{}", StacksBlockId([2u8; 32]).to_hex()), http_request_metadata_dns.peer.hostname(), http_request_metadata_dns.peer.port(), http_request_metadata_dns.keep_alive),
             HttpRequestPreamble::new(HttpVersion::Http11, "GET".to_string(), format!("/v2/microblocks/{}", StacksBlockId([3u8; 32]).to_hex()), http_request_metadata_ip.peer.hostname(), http_request_metadata_ip.peer.port(), http_request_metadata_ip.keep_alive),
+            HttpRequestPreamble::new(HttpVersion::Http11, "GET".to_string(), format!("/v2/transactions/{}", Txid([4u8; 32]).to_hex()), http_request_metadata_dns.peer.hostname(), http_request_metadata_dns.peer.port(), http_request_metadata_dns.keep_alive),
+            HttpRequestPreamble::new(HttpVersion::Http11, "GET".to_string(), format!("/v2/headers/{}", StacksBlockId([5u8; 32]).to_hex()), http_request_metadata_ip.peer.hostname(), http_request_metadata_ip.peer.port(), http_request_metadata_ip.keep_alive),
+            HttpRequestPreamble::new(HttpVersion::Http11, "GET".to_string(), "/v2/headers/height/123".to_string(), http_request_metadata_dns.peer.hostname(), http_request_metadata_dns.peer.port(), http_request_metadata_dns.keep_alive),
+            HttpRequestPreamble::new(HttpVersion::Http11, "GET".to_string(), "/v2/headers/range/45/67".to_string(), http_request_metadata_ip.peer.hostname(), http_request_metadata_ip.peer.port(), http_request_metadata_ip.keep_alive),
+            HttpRequestPreamble::new(HttpVersion::Http11, "GET".to_string(), "/v2/headers/tips".to_string(), http_request_metadata_dns.peer.hostname(), http_request_metadata_dns.peer.port(), http_request_metadata_dns.keep_alive),
+            HttpRequestPreamble::new(HttpVersion::Http11, "GET".to_string(), "/v2/fees/rates".to_string(), http_request_metadata_dns.peer.hostname(), http_request_metadata_dns.peer.port(), http_request_metadata_dns.keep_alive),
+            HttpRequestPreamble::new(HttpVersion::Http11, "GET".to_string(), "/v2/mempool/txids?page=2".to_string(), http_request_metadata_ip.peer.hostname(), http_request_metadata_ip.peer.port(), http_request_metadata_ip.keep_alive),
+            HttpRequestPreamble::new(HttpVersion::Http11, "GET".to_string(), format!("/v2/mempool/transactions/{}", Txid([6u8; 32]).to_hex()), http_request_metadata_dns.peer.hostname(), http_request_metadata_dns.peer.port(), http_request_metadata_dns.keep_alive),
             post_transaction_preamble,
             HttpRequestPreamble::new(HttpVersion::Http11, "OPTIONS".to_string(), format!("/"), http_request_metadata_ip.peer.hostname(), http_request_metadata_ip.peer.port(), http_request_metadata_ip.keep_alive),
         ];
 
         let expected_http_bodies = vec![
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+            vec![],
             vec![],
             vec![],
             vec![],
@@ -3395,6 +4300,55 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_http_parse_event_stream_request() {
+        let contract_id = QualifiedContractIdentifier::parse("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world").unwrap();
+
+        let request = "GET /v2/events?topics=blocks,contract:ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world HTTP/1.1\r\nHost: localhost:20443\r\nUpgrade: websocket\r\nConnection: keep-alive, Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n";
+        let mut http = StacksHttp::new();
+        let (preamble, offset) = http.read_preamble(request.as_bytes()).unwrap();
+        let (msg, _) = http.read_payload(&preamble, &request.as_bytes()[offset..]).unwrap();
+        match msg {
+            StacksHttpMessage::Request(HttpRequestType::GetEventStream(md, key, topics)) => {
+                assert!(md.keep_alive);
+                assert_eq!(key, "dGhlIHNhbXBsZSBub25jZQ==");
+                assert_eq!(topics, vec![EventStreamTopic::Blocks, EventStreamTopic::ContractEvents(contract_id.clone())]);
+            },
+            x => panic!("Did not get an event stream request: {:?}", &x)
+        }
+
+        // round-trip
+        let req = HttpRequestType::GetEventStream(HttpRequestMetadata::from_host(PeerHost::DNS("localhost".to_string(), 20443)),
+                                                  "dGhlIHNhbXBsZSBub25jZQ==".to_string(),
+                                                  vec![EventStreamTopic::MemPool, EventStreamTopic::ContractEvents(contract_id.clone())]);
+        let mut bytes = vec![];
+        http.write_message(&mut bytes, &StacksHttpMessage::Request(req.clone())).unwrap();
+        let (preamble, offset) = http.read_preamble(&bytes).unwrap();
+        let (msg, _) = http.read_payload(&preamble, &bytes[offset..]).unwrap();
+        assert_eq!(msg, StacksHttpMessage::Request(req));
+
+        let bad_requests = vec![
+            // not an upgrade
+            "GET /v2/events HTTP/1.1\r\nHost: localhost:20443\r\n\r\n",
+            // wrong version
+            "GET /v2/events HTTP/1.1\r\nHost: localhost:20443\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 8\r\n\r\n",
+            // no key
+            "GET /v2/events HTTP/1.1\r\nHost: localhost:20443\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Version: 13\r\n\r\n",
+            // bad topic
+            "GET /v2/events?topics=nope HTTP/1.1\r\nHost: localhost:20443\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n",
+        ];
+
+        for bad_request in bad_requests {
+            let mut http = StacksHttp::new();
+            let (preamble, offset) = http.read_preamble(bad_request.as_bytes()).unwrap();
+            let (msg, _) = http.read_payload(&preamble, &bad_request.as_bytes()[offset..]).unwrap();
+            match msg {
+                StacksHttpMessage::Request(HttpRequestType::ClientError(..)) => {},
+                x => panic!("Expected a client error: {:?}", &x)
+            }
+        }
+    }
+
     #[test]
     fn test_http_response_type_codec() {
         let test_neighbors_info = RPCNeighborsInfo {
@@ -3838,6 +4792,23 @@ mod test {
         assert_eq!(HttpRequestType::get_chain_tip_query(Some(query_txt_none)), None);
     }
 
+    #[test]
+    fn test_http_parse_asset_holdings_after_query() {
+        let asset_identifier = AssetIdentifier {
+            contract_identifier: QualifiedContractIdentifier::parse("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world").unwrap(),
+            asset_name: ClarityName::try_from("names".to_string()).unwrap()
+        };
+
+        for holding in [(asset_identifier.clone(), None), (asset_identifier.clone(), Some(Value::Int(1)))].iter() {
+            let query_txt = format!("tip=7070f213d719143d6045e08fd80f85014a161f8bbd3a42d1251576740826a392&after={}", HttpRequestType::make_asset_holdings_cursor(holding));
+            assert_eq!(HttpRequestType::get_asset_holdings_after_query(Some(&query_txt)).unwrap().as_ref(), Some(holding));
+        }
+
+        assert_eq!(HttpRequestType::get_asset_holdings_after_query(Some("proof=0")).unwrap(), None);
+        assert!(HttpRequestType::get_asset_holdings_after_query(Some("after=ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world")).is_err());
+        assert!(HttpRequestType::get_asset_holdings_after_query(Some("after=ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world::names::0xzz")).is_err());
+    }
+
     #[test]
     fn test_http_live_headers() {
         // headers pulled from prod
@@ -33,6 +33,7 @@ pub mod prune;
 pub mod rpc;
 pub mod relay;
 pub mod server;
+pub mod websocket;
 
 use std::fmt;
 use std::hash::Hash;
@@ -65,6 +66,7 @@ use serde::{Serialize, Deserialize};
 use regex::Regex;
 
 use core::mempool::*;
+use core::fee_estimator::FeeRateEstimate;
 
 use burnchains::BurnchainHeaderHash;
 use burnchains::Txid;
@@ -83,6 +85,7 @@ use chainstate::stacks::{
     Error as chain_error
 };
 use chainstate::stacks::db::blocks::MemPoolRejection;
+use chainstate::stacks::db::transactions::AssetHolding;
 
 use chainstate::stacks::Error as chainstate_error;
 
@@ -92,6 +95,7 @@ use vm::{
     Value,
     types::PrincipalData,
     analysis::contract_interface_builder::ContractInterface,
+    costs::ExecutionCost,
 };
 
 use util::hash::Hash160;
@@ -117,6 +121,7 @@ use vm::clarity::Error as clarity_error;
 use chainstate::stacks::index::Error as marf_error;
 
 use self::dns::*;
+use self::websocket::EventStreamTopic;
 
 #[derive(Debug)]
 pub enum Error {
@@ -936,6 +941,87 @@ pub struct MapEntryResponse {
     pub marf_proof: Option<String>
 }
 
+/// The data we return on GET /v2/data_var/...
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataVarResponse {
+    pub data: String,
+    #[serde(rename = "proof")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")] 
+    pub marf_proof: Option<String>
+}
+
+/// The data we return on GET /v2/map_keys/...
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MapKeysResponse {
+    pub keys: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")] 
+    pub next: Option<String>
+}
+
+/// The data we return on GET /v2/ft/.../balance/...
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FungibleTokenBalanceResponse {
+    pub balance: String,
+    #[serde(rename = "proof")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")] 
+    pub marf_proof: Option<String>
+}
+
+/// The data we return on GET /v2/ft/.../supply.  The total supply is only set for tokens
+/// defined with one; the circulating supply is tracked for every token.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FungibleTokenSupplyResponse {
+    pub total_supply: Option<String>,
+    pub circulating_supply: Option<String>,
+    #[serde(rename = "proof")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")] 
+    pub marf_proof: Option<String>
+}
+
+/// The data we return on GET /v2/nft/.../owner/...
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NonFungibleTokenOwnerResponse {
+    pub owner: String,
+    #[serde(rename = "proof")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")] 
+    pub marf_proof: Option<String>
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FungibleTokenHolding {
+    pub asset_identifier: String,
+    pub balance: String,
+    #[serde(rename = "proof")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")] 
+    pub marf_proof: Option<String>
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NonFungibleTokenHolding {
+    pub asset_identifier: String,
+    pub value: String,
+    #[serde(rename = "proof")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")] 
+    pub marf_proof: Option<String>
+}
+
+/// The data we return on GET /v2/assets/...
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetHoldingsResponse {
+    pub fungible_tokens: Vec<FungibleTokenHolding>,
+    pub non_fungible_tokens: Vec<NonFungibleTokenHolding>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")] 
+    pub next: Option<String>
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ContractSrcResponse {
     pub source: String,
@@ -969,6 +1055,106 @@ pub struct AccountEntryResponse {
     pub nonce_proof: Option<String>
 }
 
+/// The data we return on GET /v2/accounts/{principal}/nonces
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountNoncesResponse {
+    pub confirmed_nonce: u64,
+    pub possible_next_nonce: u64,
+    pub pending_txids: Vec<String>,
+    pub missing_nonces: Vec<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransactionInfoResponse {
+    pub txid: String,
+    pub status: String,     // one of "pending", "anchored" or "microblock"
+    pub tx: String,
+    #[serde(skip_serializing_if = "Option::is_none")] 
+    #[serde(default)]
+    pub index_block_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")] 
+    #[serde(default)]
+    pub microblock_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")] 
+    #[serde(default)]
+    pub tx_index: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")] 
+    #[serde(default)]
+    pub result: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")] 
+    #[serde(default)]
+    pub post_condition_aborted: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")] 
+    #[serde(default)]
+    pub events: Option<Vec<serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")] 
+    #[serde(default)]
+    pub execution_cost: Option<ExecutionCost>
+}
+
+/// The data we return on GET /v2/headers/...
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeaderInfoResponse {
+    pub index_block_hash: StacksBlockId,
+    pub block_hash: BlockHeaderHash,
+    pub block_height: u64,
+    pub burn_header_hash: String,
+    pub burn_header_timestamp: u64,
+    #[serde(skip_serializing_if = "Option::is_none")] 
+    #[serde(default)]
+    pub consensus_hash: Option<ConsensusHash>,
+    pub parent_block_hash: BlockHeaderHash,
+    pub parent_microblock_hash: BlockHeaderHash,     // tail of the confirmed microblock stream
+    pub parent_microblock_sequence: u16,
+    pub total_work: u64,
+    pub total_burn: u64,
+}
+
+/// The data we return on POST /v2/fees/transaction
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransactionFeeEstimateResponse {
+    pub txid: String,
+    pub estimated_len: u64,
+    pub fee_rates: FeeRateEstimate,
+    pub estimated_fees: FeeRateEstimate,
+    pub okay: bool,
+    #[serde(skip_serializing_if = "Option::is_none")] 
+    #[serde(default)]
+    pub result: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")] 
+    #[serde(default)]
+    pub execution_cost: Option<ExecutionCost>,
+    #[serde(skip_serializing_if = "Option::is_none")] 
+    #[serde(default)]
+    pub cause: Option<String>
+}
+
+/// The data we return on GET /v2/mempool/txids
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemPoolTxidsResponse {
+    pub txids: Vec<String>,
+    pub page: u64,
+    pub total: u64,
+}
+
+/// The data we return for each pending transaction on GET /v2/mempool/...
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemPoolTransactionResponse {
+    pub txid: String,
+    pub tx: String,
+    pub origin_address: String,
+    pub origin_nonce: u64,
+    pub sponsor_address: String,
+    pub sponsor_nonce: u64,
+    pub fee_rate: u64,
+    pub estimated_fee: u64,
+    pub len: u64,
+    pub accept_time: u64,
+    pub burn_header_hash: String,
+    pub block_hash: BlockHeaderHash,
+    pub block_height: u64,
+}
+
 /// Request ID to use or expect from non-Stacks HTTP clients.
 /// In particular, if a HTTP response does not contain the x-request-id header, then it's assumed
 /// to be this value.  This is needed to support fetching immutables like block and microblock data
@@ -1007,6 +1193,12 @@ pub struct CallReadOnlyRequestBody {
     pub arguments: Vec<String>,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct EvalReadOnlyRequestBody {
+    pub sender: String,
+    pub expression: String,
+}
+
 /// Items in the NeighborsInfo -- combines NeighborKey and NeighborAddress
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RPCNeighbor {
@@ -1052,12 +1244,32 @@ pub enum HttpRequestType {
     PostTransaction(HttpRequestMetadata, StacksTransaction),
     PostMicroblock(HttpRequestMetadata, StacksMicroblock, Option<StacksBlockId>),
     GetAccount(HttpRequestMetadata, PrincipalData, Option<StacksBlockId>, bool),
+    GetAccountNonces(HttpRequestMetadata, PrincipalData, Option<StacksBlockId>),
     GetMapEntry(HttpRequestMetadata, StacksAddress, ContractName, ClarityName, Value, Option<StacksBlockId>, bool),
+    GetMapKeys(HttpRequestMetadata, StacksAddress, ContractName, ClarityName, Option<String>, Option<StacksBlockId>),
+    GetDataVar(HttpRequestMetadata, StacksAddress, ContractName, ClarityName, Option<StacksBlockId>, bool),
+    GetFungibleTokenBalance(HttpRequestMetadata, StacksAddress, ContractName, ClarityName, PrincipalData, Option<StacksBlockId>, bool),
+    GetFungibleTokenSupply(HttpRequestMetadata, StacksAddress, ContractName, ClarityName, Option<StacksBlockId>, bool),
+    GetNonFungibleTokenOwner(HttpRequestMetadata, StacksAddress, ContractName, ClarityName, Value, Option<StacksBlockId>, bool),
+    GetAssetHoldings(HttpRequestMetadata, PrincipalData, Option<AssetHolding>, Option<StacksBlockId>, bool),
     CallReadOnlyFunction(HttpRequestMetadata, StacksAddress, ContractName,
                          PrincipalData, ClarityName, Vec<Value>, Option<StacksBlockId>),
+    EvalReadOnly(HttpRequestMetadata, StacksAddress, ContractName, PrincipalData, String, Option<StacksBlockId>),
     GetTransferCost(HttpRequestMetadata),
     GetContractSrc(HttpRequestMetadata, StacksAddress, ContractName, Option<StacksBlockId>, bool),
     GetContractABI(HttpRequestMetadata, StacksAddress, ContractName, Option<StacksBlockId>),
+    GetTransaction(HttpRequestMetadata, Txid, Option<StacksBlockId>),
+    GetHeader(HttpRequestMetadata, StacksBlockId),
+    GetHeaderByHeight(HttpRequestMetadata, u64, Option<StacksBlockId>),
+    GetHeaders(HttpRequestMetadata, u64, u64, Option<StacksBlockId>),
+    GetHeaderTips(HttpRequestMetadata),
+    GetFeeRates(HttpRequestMetadata, Option<StacksBlockId>),
+    EstimateTransactionFee(HttpRequestMetadata, StacksTransaction, Option<StacksBlockId>),
+    GetMemPoolTxids(HttpRequestMetadata, u64),
+    GetMemPoolTransaction(HttpRequestMetadata, Txid),
+    GetMemPoolTransactionsForAddress(HttpRequestMetadata, StacksAddress),
+    /// WebSocket upgrade for the event stream, with the client's Sec-WebSocket-Key and initial topics
+    GetEventStream(HttpRequestMetadata, String, Vec<EventStreamTopic>),
     OptionsPreflight(HttpRequestMetadata, String),
     /// catch-all for any errors we should surface from parsing
     ClientError(HttpRequestMetadata, ClientError),
@@ -1130,10 +1342,28 @@ pub enum HttpResponseType {
     MicroblockHash(HttpResponseMetadata, BlockHeaderHash),
     TokenTransferCost(HttpResponseMetadata, u64),
     GetMapEntry(HttpResponseMetadata, MapEntryResponse),
+    GetMapKeys(HttpResponseMetadata, MapKeysResponse),
+    GetDataVar(HttpResponseMetadata, DataVarResponse),
+    GetFungibleTokenBalance(HttpResponseMetadata, FungibleTokenBalanceResponse),
+    GetFungibleTokenSupply(HttpResponseMetadata, FungibleTokenSupplyResponse),
+    GetNonFungibleTokenOwner(HttpResponseMetadata, NonFungibleTokenOwnerResponse),
+    GetAssetHoldings(HttpResponseMetadata, AssetHoldingsResponse),
     CallReadOnlyFunction(HttpResponseMetadata, CallReadOnlyResponse),
+    EvalReadOnly(HttpResponseMetadata, CallReadOnlyResponse),
     GetAccount(HttpResponseMetadata, AccountEntryResponse),
+    GetAccountNonces(HttpResponseMetadata, AccountNoncesResponse),
     GetContractABI(HttpResponseMetadata, ContractInterface),
     GetContractSrc(HttpResponseMetadata, ContractSrcResponse),
+    GetTransaction(HttpResponseMetadata, TransactionInfoResponse),
+    GetHeader(HttpResponseMetadata, HeaderInfoResponse),
+    GetHeaders(HttpResponseMetadata, Vec<HeaderInfoResponse>),
+    FeeRates(HttpResponseMetadata, FeeRateEstimate),
+    TransactionFeeEstimate(HttpResponseMetadata, TransactionFeeEstimateResponse),
+    MemPoolTxids(HttpResponseMetadata, MemPoolTxidsResponse),
+    MemPoolTransaction(HttpResponseMetadata, MemPoolTransactionResponse),
+    MemPoolTransactions(HttpResponseMetadata, Vec<MemPoolTransactionResponse>),
+    /// 101 Switching Protocols, with the Sec-WebSocket-Accept value
+    EventStreamUpgrade(HttpResponseMetadata, String),
     OptionsPreflight(HttpResponseMetadata),
     // peer-given error responses
     BadRequest(HttpResponseMetadata, String),
@@ -1398,6 +1628,21 @@ pub const NUM_NEIGHBORS : usize = 32;
 // maximum number of unconfirmed microblocks can get streamed to us
 pub const MAX_MICROBLOCKS_UNCONFIRMED : usize = 1024;
 
+// maximum number of block headers that can be requested at once over HTTP
+pub const MAX_HEADERS_RANGE_LEN : u64 = 256;
+
+// maximum number of mempool txids returned in one page over HTTP
+pub const MAX_MEMPOOL_TXIDS_PAGE_LEN : u64 = 1024;
+
+// maximum number of data map keys returned in one page over HTTP
+pub const MAX_MAP_KEYS_PAGE_LEN : u32 = 256;
+
+// maximum number of indexed data map keys examined to serve one page over HTTP
+pub const MAX_MAP_KEYS_SCAN_LEN : u32 = 4096;
+
+// maximum number of asset holdings examined to serve one page over HTTP
+pub const MAX_ASSET_HOLDINGS_PAGE_LEN : u32 = 256;
+
 // how long a peer will be denied for if it misbehaves
 #[cfg(test)] pub const DENY_BAN_DURATION : u64 = 30;           // seconds
 #[cfg(not(test))] pub const DENY_BAN_DURATION : u64 = 86400;   // seconds (1 day)
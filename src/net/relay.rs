@@ -38,6 +38,7 @@ use chainstate::stacks::db::{StacksChainState, StacksHeaderInfo, StacksEpochRece
 use chainstate::stacks::StacksBlockHeader;
 use chainstate::stacks::StacksBlockId;
 use chainstate::stacks::events::StacksTransactionReceipt;
+use chainstate::stacks::index::Error as marf_error;
 
 use core::mempool::*;
 
@@ -87,6 +88,7 @@ pub struct RelayerStats {
 
 pub struct ProcessedNetReceipts {
     pub blocks_processed: Vec<StacksEpochReceipt>,
+    pub microblocks_processed: Vec<MicroblocksData>,
     pub mempool_txs_added: Vec<StacksTransaction>
 }
 
@@ -802,8 +804,15 @@ impl Relayer {
         
         // process as many epochs as we can.
         let max_epochs = if new_blocks.len() < 1024 { 1024 } else { new_blocks.len() };
-        let receipts: Vec<_> = chainstate.process_blocks(sortdb, max_epochs)?.into_iter()
-            .filter_map(|block_result| block_result.0).collect();
+        let receipts: Vec<_> = match chainstate.process_blocks(sortdb, max_epochs) {
+            Ok(block_results) => block_results.into_iter().filter_map(|block_result| block_result.0).collect(),
+            Err(e) if StacksChainState::is_pruned_state_error(&e) => {
+                return Err(net_error::MARFError(marf_error::PrunedError));
+            },
+            Err(e) => {
+                return Err(e.into());
+            }
+        };
 
         if receipts.len() > 0 || network_result.uploaded_microblocks.len() > 0 {
             Relayer::setup_unconfirmed_state(chainstate, sortdb, &receipts)?;
@@ -917,8 +926,11 @@ impl Relayer {
     /// * Forward transactions we didn't already have.
     /// Mask errors from invalid data -- all errors due to invalid blocks and invalid data should be captured, and
     /// turned into peer bans.
+    /// Fails with MARFError(PrunedError) if the next block needs Clarity state that was pruned from
+    /// this node, since no amount of retrying will process it.
     pub fn process_network_result(&mut self, _local_peer: &LocalPeer, network_result: &mut NetworkResult,
                                   sortdb: &mut SortitionDB, chainstate: &mut StacksChainState, mempool: &mut MemPoolDB) -> Result<ProcessedNetReceipts, net_error> {
+        let mut microblocks_processed = vec![];
         let blocks_processed = match Relayer::process_new_blocks(network_result, sortdb, chainstate) {
             Ok((new_blocks, new_confirmed_microblocks, new_microblocks, bad_block_neighbors, receipts)) => {
                 // attempt to relay messages (note that this is all best-effort).
@@ -953,6 +965,7 @@ impl Relayer {
                     debug!("{:?}: Unconfirmed microblocks: {}", &_local_peer, new_microblocks.len());
                     for (relayers, mblocks_msg) in new_microblocks.into_iter() {
                         debug!("{:?}: Send {} microblocks for {}", &_local_peer, mblocks_msg.microblocks.len(), &mblocks_msg.index_anchor_block);
+                        microblocks_processed.push(mblocks_msg.clone());
                         let msg = StacksMessageType::Microblocks(mblocks_msg);
                         if let Err(e) = self.p2p.broadcast_message(relayers, msg) {
                             warn!("Failed to broadcast microblock: {:?}", &e);
@@ -961,6 +974,11 @@ impl Relayer {
                 }
                 receipts
             },
+            Err(net_error::MARFError(marf_error::PrunedError)) => {
+                // the next block needs Clarity state that was pruned from this node, so this node
+                // can't make any more progress on its own.  Let the caller decide what to do.
+                return Err(net_error::MARFError(marf_error::PrunedError));
+            },
             Err(e) => {
                 warn!("Failed to process new blocks: {:?}", &e);
 
@@ -988,6 +1006,7 @@ impl Relayer {
 
         let receipts = ProcessedNetReceipts {
             blocks_processed,
+            microblocks_processed,
             mempool_txs_added
         };
 
@@ -21,6 +21,7 @@ use std::io::prelude::*;
 use std::io;
 use std::io::{Read, Write, Seek, SeekFrom};
 use std::fmt;
+use std::cmp;
 use std::net::SocketAddr;
 
 use std::collections::HashMap;
@@ -49,23 +50,35 @@ use net::StacksMessageType;
 use net::connection::ConnectionHttp;
 use net::connection::ReplyHandleHttp;
 use net::connection::ConnectionOptions;
+use net::websocket;
+use net::websocket::{ WebSocketState, EventStreamMessage };
 use net::db::PeerDB;
 use net::p2p::PeerNetwork;
 use net::{ RPCNeighbor, RPCNeighborsInfo };
-use net::{ MapEntryResponse, AccountEntryResponse, CallReadOnlyResponse, ContractSrcResponse };
+use net::{ MapEntryResponse, AccountEntryResponse, CallReadOnlyResponse, ContractSrcResponse, TransactionInfoResponse, HeaderInfoResponse, TransactionFeeEstimateResponse };
+use net::{ MemPoolTxidsResponse, MemPoolTransactionResponse, AccountNoncesResponse };
+use net::{ DataVarResponse, MapKeysResponse };
+use net::{ FungibleTokenBalanceResponse, FungibleTokenSupplyResponse, NonFungibleTokenOwnerResponse };
+use net::{ AssetHoldingsResponse, FungibleTokenHolding, NonFungibleTokenHolding };
+use net::MAX_MEMPOOL_TXIDS_PAGE_LEN;
+use net::MAX_HEADERS_RANGE_LEN;
+use net::{MAX_MAP_KEYS_PAGE_LEN, MAX_MAP_KEYS_SCAN_LEN, MAX_ASSET_HOLDINGS_PAGE_LEN};
 use net::p2p::PeerMap;
 use core::mempool::*;
+use core::fee_estimator;
 
 use burnchains::Burnchain;
 use burnchains::BurnchainView;
 use burnchains::BurnchainHeaderHash;
 
-use chainstate::burn::db::sortdb::SortitionDB;
+use chainstate::burn::db::sortdb::{SortitionDB, SortitionHandleConn};
 use chainstate::burn::BlockHeaderHash;
 use chainstate::stacks::db::{
     StacksChainState,
+    StacksHeaderInfo,
     BlockStreamData,
-    blocks::MINIMUM_TX_FEE_RATE_PER_BYTE};
+    blocks::MINIMUM_TX_FEE_RATE_PER_BYTE,
+    transactions::AssetHolding};
 use chainstate::stacks::Error as chain_error;
 use chainstate::stacks::*;
 use burnchains::*;
@@ -87,6 +100,11 @@ use vm::{
     ContractName,
     Value,
     SymbolicExpression,
+    ast,
+    analysis::read_only_checker::ReadOnlyChecker,
+    analysis::type_checker::TypeChecker,
+    errors::{ Error as InterpreterError,
+              RuntimeErrorType },
     costs::{ LimitedCostTracker,
              ExecutionCost },
     types::{ PrincipalData,
@@ -130,6 +148,10 @@ pub struct ConversationHttp {
     pending_request: Option<ReplyHandleHttp>,
     pending_response: Option<HttpResponseType>,
     pending_error_response: Option<HttpResponseType>,
+
+    // set once the client upgrades this conversation to an event stream WebSocket
+    websocket: Option<WebSocketState>,
+    max_websocket_buffer: usize,
 }
 
 impl fmt::Display for ConversationHttp {
@@ -190,6 +212,52 @@ impl RPCPeerInfoData {
     }
 }
 
+impl HeaderInfoResponse {
+    /// Convert a header info from the chainstate into its RPC representation.  The consensus hash
+    /// is only filled in if the header's burnchain block is on the given sortition handle's fork.
+    pub fn from_header_info(sort_handle: &SortitionHandleConn, header_info: &StacksHeaderInfo) -> Result<HeaderInfoResponse, net_error> {
+        let consensus_hash = sort_handle.get_block_snapshot(&header_info.burn_header_hash)?
+            .map(|sn| sn.consensus_hash);
+
+        let header = &header_info.anchored_header;
+        Ok(HeaderInfoResponse {
+            index_block_hash: header_info.index_block_hash(),
+            block_hash: header.block_hash(),
+            block_height: header_info.block_height,
+            burn_header_hash: header_info.burn_header_hash.to_hex(),
+            burn_header_timestamp: header_info.burn_header_timestamp,
+            consensus_hash,
+            parent_block_hash: header.parent_block.clone(),
+            parent_microblock_hash: header.parent_microblock.clone(),
+            parent_microblock_sequence: header.parent_microblock_sequence,
+            total_work: header.total_work.work,
+            total_burn: header.total_work.burn,
+        })
+    }
+}
+
+impl MemPoolTransactionResponse {
+    /// Convert a pending transaction into its RPC representation.
+    pub fn from_mempool_tx(tx_info: &MemPoolTxInfo) -> MemPoolTransactionResponse {
+        let md = &tx_info.metadata;
+        MemPoolTransactionResponse {
+            txid: md.txid.to_hex(),
+            tx: format!("0x{}", to_hex(&tx_info.tx.serialize_to_vec())),
+            origin_address: md.origin_address.to_string(),
+            origin_nonce: md.origin_nonce,
+            sponsor_address: md.sponsor_address.to_string(),
+            sponsor_nonce: md.sponsor_nonce,
+            fee_rate: md.fee_rate,
+            estimated_fee: md.estimated_fee,
+            len: md.len,
+            accept_time: md.accept_time,
+            burn_header_hash: md.burn_header_hash.to_hex(),
+            block_hash: md.block_header_hash.clone(),
+            block_height: md.block_height,
+        }
+    }
+}
+
 impl RPCNeighborsInfo {
     /// Load neighbor address information from the peer network
     pub fn from_p2p(network_id: u32, peers: &PeerMap, chain_view: &BurnchainView, peerdb: &PeerDB) -> Result<RPCNeighborsInfo, net_error> {
@@ -244,7 +312,9 @@ impl ConversationHttp {
             total_reply_count: 0,
             last_request_timestamp: 0,
             last_response_timestamp: 0,
-            connection_time: get_epoch_time_secs()
+            connection_time: get_epoch_time_secs(),
+            websocket: None,
+            max_websocket_buffer: conn_opts.max_websocket_buffer
         }
     }
 
@@ -267,6 +337,25 @@ impl ConversationHttp {
     pub fn is_request_inflight(&self) -> bool {
         self.pending_request.is_some()
     }
+
+    /// Has this conversation been upgraded to an event stream WebSocket?
+    pub fn is_websocket(&self) -> bool {
+        self.websocket.is_some()
+    }
+
+    /// Queue up any events this event stream client is subscribed to, and ping it if it's been
+    /// quiet for too long.  Fails if the client has fallen too far behind.
+    pub fn push_event_stream(&mut self, msgs: &[EventStreamMessage], ping_interval: u64) -> Result<(), net_error> {
+        match self.websocket {
+            Some(ref mut ws) => {
+                for msg in msgs.iter() {
+                    ws.push_message(msg)?;
+                }
+                ws.try_ping(get_epoch_time_secs(), ping_interval)
+            },
+            None => Ok(())
+        }
+    }
     
     /// Start a HTTP request from this peer, and expect a response.
     /// Returns the request handle; does not set the handle into this connection.
@@ -454,28 +543,105 @@ impl ConversationHttp {
         response.send(http, fd).map(|_| ())
     }
 
+    /// Handle a GET on the low/medium/high fee rate estimates, given the current chain tip.
+    fn handle_get_fee_rates<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType, chainstate: &StacksChainState,
+                                      mempool: &MemPoolDB, tip: &StacksBlockId) -> Result<(), net_error> {
+        let response_metadata = HttpResponseMetadata::from(req);
+        let response = match fee_estimator::estimate_fee_rates(chainstate, mempool, tip) {
+            Ok(estimate) => HttpResponseType::FeeRates(response_metadata, estimate),
+            Err(e) => {
+                warn!("Failed to estimate fee rates off of {}: {:?}", tip, &e);
+                HttpResponseType::ServerError(response_metadata, format!("Failed to estimate fee rates"))
+            }
+        };
+        response.send(http, fd).map(|_| ())
+    }
+
+    /// Handle a POST to estimate the fee for a transaction.  Besides the fee estimates, the reply
+    /// says whether or not the transaction would run successfully on top of the given chain tip,
+    /// and what it would cost to execute.  The transaction's effects are thrown away.
+    fn handle_estimate_transaction_fee<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType, chainstate: &mut StacksChainState,
+                                                 mempool: &MemPoolDB, burn_header_hash: &BurnchainHeaderHash, block_hash: &BlockHeaderHash,
+                                                 tx: &StacksTransaction, options: &ConnectionOptions) -> Result<(), net_error> {
+        let response_metadata = HttpResponseMetadata::from(req);
+        let tip = StacksBlockHeader::make_index_block_hash(burn_header_hash, block_hash);
+
+        let fee_rates = match fee_estimator::estimate_fee_rates(chainstate, mempool, &tip) {
+            Ok(estimate) => estimate,
+            Err(e) => {
+                warn!("Failed to estimate fee rates off of {}: {:?}", &tip, &e);
+                let response = HttpResponseType::ServerError(response_metadata, format!("Failed to estimate fee rates"));
+                return response.send(http, fd).map(|_| ());
+            }
+        };
+
+        let estimated_len = tx.serialize_to_vec().len() as u64;
+        let estimated_fees = fee_rates.fees_for_len(estimated_len);
+
+        let data = match chainstate.simulate_transaction(&tip, tx, &options.read_only_call_limit) {
+            Ok(receipt) => TransactionFeeEstimateResponse {
+                txid: tx.txid().to_hex(),
+                estimated_len,
+                fee_rates,
+                estimated_fees,
+                okay: true,
+                result: Some(format!("0x{}", receipt.result.serialize())),
+                execution_cost: Some(receipt.execution_cost),
+                cause: None
+            },
+            Err(e) => TransactionFeeEstimateResponse {
+                txid: tx.txid().to_hex(),
+                estimated_len,
+                fee_rates,
+                estimated_fees,
+                okay: false,
+                result: None,
+                execution_cost: None,
+                cause: Some(format!("{:?}", &e))
+            }
+        };
+
+        let response = HttpResponseType::TransactionFeeEstimate(response_metadata, data);
+        response.send(http, fd).map(|_| ())
+    }
+
+    /// Render a MARF proof that a key has no value at the current chain tip, if one was asked
+    /// for.  Used by the handlers whose queries have a `none` result.
+    fn exclusion_proof_hex(clarity_db: &mut ClarityDatabase, key: &str, with_proof: bool) -> Result<String, InterpreterError> {
+        if !with_proof {
+            return Ok("".into());
+        }
+        let proof_hex = clarity_db.get_exclusion_proof(key)?
+            .map(|proof| format!("0x{}", proof.to_hex()))
+            .unwrap_or_else(|| "".into());
+        Ok(proof_hex)
+    }
+
     /// Handle a GET on an existing account, given the current chain tip.  Optionally supplies a
-    /// MARF proof for each account detail loaded from the chain tip.
+    /// MARF proof for each account detail loaded from the chain tip, or a proof of its absence if
+    /// the account has never had a balance or nonce.
     fn handle_get_account_entry<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType,
                                           chainstate: &mut StacksChainState, tip: &StacksBlockId,
                                           account: &PrincipalData, with_proof: bool) -> Result<(), net_error> {
         let response_metadata = HttpResponseMetadata::from(req);
 
         let data = chainstate.maybe_read_only_clarity_tx(tip, |clarity_tx| {
-            clarity_tx.with_clarity_db_readonly(|clarity_db| {
+            clarity_tx.with_clarity_db_readonly(|clarity_db| -> Result<AccountEntryResponse, InterpreterError> {
                 let key = ClarityDatabase::make_key_for_account_balance(&account);
-                let (balance, balance_proof) = clarity_db.get_with_proof::<u128>(&key)
-                    .map(|(a, b)| (a, format!("0x{}", b.to_hex())))
-                    .unwrap_or_else(|| (0, "".into()));
+                let (balance, balance_proof) = match clarity_db.get_with_proof::<u128>(&key)? {
+                    Some((a, b)) => (a, format!("0x{}", b.to_hex())),
+                    None => (0, ConversationHttp::exclusion_proof_hex(clarity_db, &key, with_proof)?)
+                };
                 let balance_proof = if with_proof {
                     Some(balance_proof)
                 } else {
                     None
                 };
                 let key = ClarityDatabase::make_key_for_account_nonce(&account);
-                let (nonce, nonce_proof) = clarity_db.get_with_proof(&key)
-                    .map(|(a, b)| (a, format!("0x{}", b.to_hex())))
-                    .unwrap_or_else(|| (0, "".into()));
+                let (nonce, nonce_proof) = match clarity_db.get_with_proof(&key)? {
+                    Some((a, b)) => (a, format!("0x{}", b.to_hex())),
+                    None => (0, ConversationHttp::exclusion_proof_hex(clarity_db, &key, with_proof)?)
+                };
                 let nonce_proof = if with_proof {
                     Some(nonce_proof)
                 } else {
@@ -483,18 +649,75 @@ impl ConversationHttp {
                 };
 
                 let balance = format!("0x{}", to_hex(&balance.to_be_bytes()));
-                AccountEntryResponse { balance, nonce, balance_proof, nonce_proof }
+                Ok(AccountEntryResponse { balance, nonce, balance_proof, nonce_proof })
+            })
+        });
+
+        let response = match data {
+            Ok(data) => HttpResponseType::GetAccount(response_metadata, data),
+            Err(e) => {
+                warn!("Failed to load account {}: {:?}", account, &e);
+                HttpResponseType::ServerError(response_metadata, format!("Failed to load account {}", account))
+            }
+        };
+
+        response.send(http, fd).map(|_| ())
+    }
+
+    /// Handle a GET on an account's nonces, given the current chain tip.  Reports the nonce the
+    /// account has confirmed, the next nonce it can use once its pending mempool transactions are
+    /// accounted for, and which nonces (if any) are missing between the two.
+    fn handle_get_account_nonces<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType,
+                                           chainstate: &mut StacksChainState, mempool: &MemPoolDB, tip: &StacksBlockId,
+                                           account: &PrincipalData) -> Result<(), net_error> {
+        let response_metadata = HttpResponseMetadata::from(req);
+
+        let confirmed_nonce = chainstate.maybe_read_only_clarity_tx(tip, |clarity_tx| {
+            clarity_tx.with_clarity_db_readonly(|clarity_db| {
+                clarity_db.get_account_nonce(account)
             })
         });
 
-        let response = HttpResponseType::GetAccount(
-            response_metadata, data);
+        // only standard principals can originate or sponsor transactions.  Stale pending
+        // transactions can leave the mempool's next nonce below the confirmed one.
+        let nonce_info_res = match account {
+            PrincipalData::Standard(ref standard) => {
+                let addr = StacksAddress::from(standard.clone());
+                MemPoolDB::get_next_nonce_for_address(mempool.conn(), &addr)
+                    .and_then(|next_nonce| {
+                        let nonce_info = MemPoolDB::get_nonce_info_for_address(mempool.conn(), &addr, confirmed_nonce)?;
+                        Ok((cmp::max(next_nonce, confirmed_nonce), nonce_info))
+                    })
+            },
+            PrincipalData::Contract(_) => {
+                Ok((confirmed_nonce, MemPoolNonceInfo {
+                    pending_txids: vec![],
+                    missing_nonces: vec![]
+                }))
+            }
+        };
+
+        let response = match nonce_info_res {
+            Ok((possible_next_nonce, nonce_info)) => {
+                let data = AccountNoncesResponse {
+                    confirmed_nonce,
+                    possible_next_nonce,
+                    pending_txids: nonce_info.pending_txids.into_iter().map(|txid| txid.to_hex()).collect(),
+                    missing_nonces: nonce_info.missing_nonces
+                };
+                HttpResponseType::GetAccountNonces(response_metadata, data)
+            },
+            Err(e) => {
+                warn!("Failed to load pending transactions for {}: {:?}", account, &e);
+                HttpResponseType::ServerError(response_metadata, format!("Failed to query pending transactions for {}", account))
+            }
+        };
 
         response.send(http, fd).map(|_| ())
     }
 
     /// Handle a GET on a smart contract's data map, given the current chain tip.  Optionally
-    /// supplies a MARF proof for the value.
+    /// supplies a MARF proof for the value, or a proof of its absence if the result is `none`.
     fn handle_get_map_entry<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType,
                                       chainstate: &mut StacksChainState, tip: &StacksBlockId,
                                       contract_addr: &StacksAddress, contract_name: &ContractName,
@@ -503,14 +726,15 @@ impl ConversationHttp {
         let contract_identifier = QualifiedContractIdentifier::new(contract_addr.clone().into(), contract_name.clone());
 
         let data = chainstate.maybe_read_only_clarity_tx(tip, |clarity_tx| {
-            clarity_tx.with_clarity_db_readonly(|clarity_db| {
+            clarity_tx.with_clarity_db_readonly(|clarity_db| -> Result<MapEntryResponse, InterpreterError> {
                 let key = ClarityDatabase::make_key_for_data_map_entry(&contract_identifier, map_name, key);
-                let (value, marf_proof) = clarity_db.get_with_proof::<Value>(&key)
-                    .map(|(a, b)| (a, format!("0x{}", b.to_hex())))
-                    .unwrap_or_else(|| {
+                let (value, marf_proof) = match clarity_db.get_with_proof::<Value>(&key)? {
+                    Some((a, b)) => (a, format!("0x{}", b.to_hex())),
+                    None => {
                         test_debug!("No value for '{}' in {}", &key, tip);
-                        (Value::none(), "".into())
-                    });
+                        (Value::none(), ConversationHttp::exclusion_proof_hex(clarity_db, &key, with_proof)?)
+                    }
+                };
                 let marf_proof = if with_proof {
                     test_debug!("Return a MARF proof of '{}' of {} bytes", &key, marf_proof.as_bytes().len());
                     Some(marf_proof)
@@ -519,13 +743,301 @@ impl ConversationHttp {
                 };
 
                 let data = format!("0x{}", value.serialize());
-                MapEntryResponse { data, marf_proof }
+                Ok(MapEntryResponse { data, marf_proof })
+            })
+        });
+
+        let response = match data {
+            Ok(data) => HttpResponseType::GetMapEntry(response_metadata, data),
+            Err(e) => {
+                warn!("Failed to load entry of {}.{}: {:?}", &contract_identifier, map_name.as_str(), &e);
+                HttpResponseType::ServerError(response_metadata, format!("Failed to load entry of {}.{}", &contract_identifier, map_name.as_str()))
+            }
+        };
+
+        response.send(http, fd).map(|_| ())
+    }
+
+    /// Handle a GET to page through the keys of a smart contract's data map which have an entry at
+    /// the given chain tip.  Requires the (opt-in) data map key index; if it isn't enabled on this
+    /// node, a 404 is returned.
+    fn handle_get_map_keys<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType,
+                                     chainstate: &mut StacksChainState, tip: &StacksBlockId,
+                                     contract_addr: &StacksAddress, contract_name: &ContractName,
+                                     map_name: &ClarityName, after: Option<&str>) -> Result<(), net_error> {
+        let response_metadata = HttpResponseMetadata::from(req);
+        let contract_identifier = QualifiedContractIdentifier::new(contract_addr.clone().into(), contract_name.clone());
+
+        let data = chainstate.maybe_read_only_clarity_tx(tip, |clarity_tx| {
+            clarity_tx.with_clarity_db_readonly(|clarity_db| {
+                clarity_db.get_map_keys(&contract_identifier, map_name, after, MAX_MAP_KEYS_PAGE_LEN, MAX_MAP_KEYS_SCAN_LEN)
+            })
+        });
+
+        let response = match data {
+            Ok(Some((keys, next))) => {
+                let next = next.map(|key| format!("0x{}", key));
+                let keys = keys.into_iter().map(|key| format!("0x{}", key)).collect();
+                HttpResponseType::GetMapKeys(response_metadata, MapKeysResponse { keys, next })
+            },
+            Ok(None) => HttpResponseType::NotFound(response_metadata, "Data map key index is not enabled on this node".into()),
+            Err(e) => {
+                debug!("Failed to load keys of {}.{}: {:?}", &contract_identifier, map_name.as_str(), &e);
+                HttpResponseType::NotFound(response_metadata, "No such data map".into())
+            }
+        };
+
+        response.send(http, fd).map(|_| ())
+    }
+
+    /// Handle a GET on a smart contract's data var or constant, given the current chain tip.
+    /// Optionally returns a MARF proof of a data var's value (constants are part of the contract
+    /// itself, so they have no proof of their own).
+    fn handle_get_data_var<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType,
+                                     chainstate: &mut StacksChainState, tip: &StacksBlockId,
+                                     contract_addr: &StacksAddress, contract_name: &ContractName,
+                                     var_name: &ClarityName, with_proof: bool) -> Result<(), net_error> {
+        let response_metadata = HttpResponseMetadata::from(req);
+        let contract_identifier = QualifiedContractIdentifier::new(contract_addr.clone().into(), contract_name.clone());
+
+        let data = chainstate.maybe_read_only_clarity_tx(tip, |clarity_tx| {
+            clarity_tx.with_clarity_db_readonly(|clarity_db| -> Result<Option<DataVarResponse>, InterpreterError> {
+                match clarity_db.lookup_variable(&contract_identifier, var_name) {
+                    Ok(value) => {
+                        let marf_proof = if with_proof {
+                            let key = ClarityDatabase::make_key_for_data_var(&contract_identifier, var_name);
+                            clarity_db.get_with_proof::<Value>(&key)?
+                                .map(|(_, proof)| format!("0x{}", proof.to_hex()))
+                        } else {
+                            None
+                        };
+                        Ok(Some(DataVarResponse { data: format!("0x{}", value.serialize()), marf_proof }))
+                    },
+                    Err(_) => {
+                        // not a data var -- it may be a constant
+                        Ok(clarity_db.get_contract(&contract_identifier).ok()
+                            .and_then(|contract| contract.contract_context.variables.get(var_name).cloned())
+                            .map(|value| DataVarResponse { data: format!("0x{}", value.serialize()), marf_proof: None }))
+                    }
+                }
+            })
+        });
+
+        let response = match data {
+            Ok(Some(data)) => HttpResponseType::GetDataVar(response_metadata, data),
+            Ok(None) => HttpResponseType::NotFound(response_metadata, "No such data var or constant".into()),
+            Err(e) => {
+                warn!("Failed to load data var {}.{}: {:?}", &contract_identifier, var_name.as_str(), &e);
+                HttpResponseType::ServerError(response_metadata, format!("Failed to load data var {}.{}", &contract_identifier, var_name.as_str()))
+            }
+        };
+
+        response.send(http, fd).map(|_| ())
+    }
+
+    /// Handle a GET on a principal's balance of a fungible token, given the current chain tip.
+    /// Optionally returns a MARF proof of the balance (or of its absence, if the principal has
+    /// never held the token).
+    fn handle_get_ft_balance<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType,
+                                       chainstate: &mut StacksChainState, tip: &StacksBlockId,
+                                       contract_addr: &StacksAddress, contract_name: &ContractName,
+                                       token_name: &ClarityName, principal: &PrincipalData, with_proof: bool) -> Result<(), net_error> {
+        let response_metadata = HttpResponseMetadata::from(req);
+        let contract_identifier = QualifiedContractIdentifier::new(contract_addr.clone().into(), contract_name.clone());
+
+        let data = chainstate.maybe_read_only_clarity_tx(tip, |clarity_tx| {
+            clarity_tx.with_clarity_db_readonly(|clarity_db| {
+                let balance = clarity_db.get_ft_balance(&contract_identifier, token_name, principal)?;
+                let marf_proof = if with_proof {
+                    let key = ClarityDatabase::make_key_for_ft_balance(&contract_identifier, token_name, principal);
+                    match clarity_db.get_with_proof::<u128>(&key)? {
+                        Some((_, proof)) => Some(format!("0x{}", proof.to_hex())),
+                        None => Some(ConversationHttp::exclusion_proof_hex(clarity_db, &key, true)?)
+                    }
+                } else {
+                    None
+                };
+                let balance = format!("0x{}", to_hex(&balance.to_be_bytes()));
+                Ok(FungibleTokenBalanceResponse { balance, marf_proof })
+            })
+        });
+
+        let response = match data {
+            Ok(data) => HttpResponseType::GetFungibleTokenBalance(response_metadata, data),
+            Err(InterpreterError::Interpreter(e)) => {
+                warn!("Failed to load balance of {}::{} for {}: {:?}", &contract_identifier, token_name.as_str(), principal, &e);
+                HttpResponseType::ServerError(response_metadata, format!("Failed to load balance of {}::{} for {}", &contract_identifier, token_name.as_str(), principal))
+            },
+            Err(e) => {
+                debug!("Failed to load balance of {}::{} for {}: {:?}", &contract_identifier, token_name.as_str(), principal, &e);
+                HttpResponseType::NotFound(response_metadata, "No such fungible token".into())
+            }
+        };
+
+        response.send(http, fd).map(|_| ())
+    }
+
+    /// Handle a GET on a fungible token's total and circulating supply, given the current chain
+    /// tip.  Optionally returns a MARF proof of the circulating supply.
+    fn handle_get_ft_supply<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType,
+                                      chainstate: &mut StacksChainState, tip: &StacksBlockId,
+                                      contract_addr: &StacksAddress, contract_name: &ContractName,
+                                      token_name: &ClarityName, with_proof: bool) -> Result<(), net_error> {
+        let response_metadata = HttpResponseMetadata::from(req);
+        let contract_identifier = QualifiedContractIdentifier::new(contract_addr.clone().into(), contract_name.clone());
+
+        let data : Result<FungibleTokenSupplyResponse, InterpreterError> = chainstate.maybe_read_only_clarity_tx(tip, |clarity_tx| {
+            clarity_tx.with_clarity_db_readonly(|clarity_db| {
+                let total_supply = clarity_db.get_ft_total_supply(&contract_identifier, token_name)?;
+                let circulating_supply = clarity_db.get_ft_circulating_supply(&contract_identifier, token_name)?;
+                let marf_proof = if with_proof {
+                    let key = ClarityDatabase::make_key_for_ft_supply(&contract_identifier, token_name);
+                    clarity_db.get_with_proof::<u128>(&key)?
+                        .map(|(_, proof)| format!("0x{}", proof.to_hex()))
+                } else {
+                    None
+                };
+                Ok(FungibleTokenSupplyResponse {
+                    total_supply: total_supply.map(|supply| format!("0x{}", to_hex(&supply.to_be_bytes()))),
+                    circulating_supply: circulating_supply.map(|supply| format!("0x{}", to_hex(&supply.to_be_bytes()))),
+                    marf_proof
+                })
+            })
+        });
+
+        let response = match data {
+            Ok(data) => HttpResponseType::GetFungibleTokenSupply(response_metadata, data),
+            Err(InterpreterError::Interpreter(e)) => {
+                warn!("Failed to load supply of {}::{}: {:?}", &contract_identifier, token_name.as_str(), &e);
+                HttpResponseType::ServerError(response_metadata, format!("Failed to load supply of {}::{}", &contract_identifier, token_name.as_str()))
+            },
+            Err(e) => {
+                debug!("Failed to load supply of {}::{}: {:?}", &contract_identifier, token_name.as_str(), &e);
+                HttpResponseType::NotFound(response_metadata, "No such fungible token".into())
+            }
+        };
+
+        response.send(http, fd).map(|_| ())
+    }
+
+    /// Handle a GET on the owner of a non-fungible token, given the current chain tip.
+    /// Optionally returns a MARF proof of the owner.
+    fn handle_get_nft_owner<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType,
+                                      chainstate: &mut StacksChainState, tip: &StacksBlockId,
+                                      contract_addr: &StacksAddress, contract_name: &ContractName,
+                                      asset_name: &ClarityName, asset: &Value, with_proof: bool) -> Result<(), net_error> {
+        let response_metadata = HttpResponseMetadata::from(req);
+        let contract_identifier = QualifiedContractIdentifier::new(contract_addr.clone().into(), contract_name.clone());
+
+        let data = chainstate.maybe_read_only_clarity_tx(tip, |clarity_tx| {
+            clarity_tx.with_clarity_db_readonly(|clarity_db| {
+                let owner = clarity_db.get_nft_owner(&contract_identifier, asset_name, asset)?;
+                let marf_proof = if with_proof {
+                    let key = ClarityDatabase::make_key_for_nft_owner(&contract_identifier, asset_name, asset);
+                    clarity_db.get_with_proof::<Option<PrincipalData>>(&key)?
+                        .map(|(_, proof)| format!("0x{}", proof.to_hex()))
+                } else {
+                    None
+                };
+                Ok(NonFungibleTokenOwnerResponse { owner: owner.to_string(), marf_proof })
             })
         });
 
-        let response = HttpResponseType::GetMapEntry(
-            response_metadata, data);
+        let response = match data {
+            Ok(data) => HttpResponseType::GetNonFungibleTokenOwner(response_metadata, data),
+            Err(InterpreterError::Interpreter(e)) => {
+                warn!("Failed to load owner of {}::{} {}: {:?}", &contract_identifier, asset_name.as_str(), asset, &e);
+                HttpResponseType::ServerError(response_metadata, format!("Failed to load owner of {}::{} {}", &contract_identifier, asset_name.as_str(), asset))
+            },
+            Err(e) => {
+                debug!("Failed to load owner of {}::{} {}: {:?}", &contract_identifier, asset_name.as_str(), asset, &e);
+                HttpResponseType::NotFound(response_metadata, "No such non-fungible token".into())
+            }
+        };
+
+        response.send(http, fd).map(|_| ())
+    }
+
+    /// Handle a GET on all the fungible and non-fungible tokens a principal holds, given the
+    /// current chain tip.  The chainstate's asset holdings index only says which assets the
+    /// principal has received, so each one is checked against the Clarity state at the tip.
+    /// Optionally returns a MARF proof of each holding.
+    fn handle_get_asset_holdings<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType,
+                                           chainstate: &mut StacksChainState, tip: &StacksBlockId,
+                                           principal: &PrincipalData, after: Option<&AssetHolding>, with_proof: bool) -> Result<(), net_error> {
+        let response_metadata = HttpResponseMetadata::from(req);
+
+        let (candidates, next) = match chainstate.get_asset_holding_candidates(tip, principal, after, MAX_ASSET_HOLDINGS_PAGE_LEN) {
+            Ok(page) => page,
+            Err(e) => {
+                warn!("Failed to load asset holdings of {}: {:?}", principal, &e);
+                let response = HttpResponseType::ServerError(response_metadata, format!("Failed to load asset holdings of {}", principal));
+                return response.send(http, fd).map(|_| ());
+            }
+        };
+
+        let data = chainstate.maybe_read_only_clarity_tx(tip, |clarity_tx| {
+            clarity_tx.with_clarity_db_readonly(|clarity_db| -> Result<AssetHoldingsResponse, InterpreterError> {
+                let mut fungible_tokens = vec![];
+                let mut non_fungible_tokens = vec![];
+                for (asset_identifier, value_opt) in candidates.into_iter() {
+                    let contract_identifier = &asset_identifier.contract_identifier;
+                    let asset_name = asset_identifier.asset_name.as_str();
+                    match value_opt {
+                        None => {
+                            let balance = match clarity_db.get_ft_balance(contract_identifier, asset_name, principal) {
+                                Ok(balance) if balance > 0 => balance,
+                                _ => {
+                                    continue;
+                                }
+                            };
+                            let marf_proof = if with_proof {
+                                let key = ClarityDatabase::make_key_for_ft_balance(contract_identifier, asset_name, principal);
+                                clarity_db.get_with_proof::<u128>(&key)?
+                                    .map(|(_, proof)| format!("0x{}", proof.to_hex()))
+                            } else {
+                                None
+                            };
+                            fungible_tokens.push(FungibleTokenHolding {
+                                asset_identifier: format!("{}", &asset_identifier),
+                                balance: format!("0x{}", to_hex(&balance.to_be_bytes())),
+                                marf_proof
+                            });
+                        },
+                        Some(value) => {
+                            match clarity_db.get_nft_owner(contract_identifier, asset_name, &value) {
+                                Ok(ref owner) if owner == principal => {},
+                                _ => {
+                                    continue;
+                                }
+                            }
+                            let marf_proof = if with_proof {
+                                let key = ClarityDatabase::make_key_for_nft_owner(contract_identifier, asset_name, &value);
+                                clarity_db.get_with_proof::<Option<PrincipalData>>(&key)?
+                                    .map(|(_, proof)| format!("0x{}", proof.to_hex()))
+                            } else {
+                                None
+                            };
+                            non_fungible_tokens.push(NonFungibleTokenHolding {
+                                asset_identifier: format!("{}", &asset_identifier),
+                                value: format!("0x{}", value.serialize()),
+                                marf_proof
+                            });
+                        }
+                    }
+                }
+                let next = next.as_ref().map(|holding| HttpRequestType::make_asset_holdings_cursor(holding));
+                Ok(AssetHoldingsResponse { fungible_tokens, non_fungible_tokens, next })
+            })
+        });
 
+        let response = match data {
+            Ok(data) => HttpResponseType::GetAssetHoldings(response_metadata, data),
+            Err(e) => {
+                warn!("Failed to load asset holdings of {}: {:?}", principal, &e);
+                HttpResponseType::ServerError(response_metadata, format!("Failed to load asset holdings of {}", principal))
+            }
+        };
         response.send(http, fd).map(|_| ())
     }
 
@@ -560,6 +1072,51 @@ impl ConversationHttp {
         response.send(http, fd).map(|_| ())
     }
 
+    /// Handle a POST to evaluate an arbitrary read-only expression in the context of a smart
+    /// contract, given the chain tip.  The expression is rejected without being evaluated if the
+    /// read-only checker finds that it could write to the chain state, or if it doesn't type
+    /// check against the contract's analysis.  Returns a CallReadOnlyResponse.
+    fn handle_eval_read_only<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType,
+                                       chainstate: &mut StacksChainState, tip: &StacksBlockId,
+                                       contract_addr: &StacksAddress, contract_name: &ContractName,
+                                       sender: &PrincipalData, expression: &str, options: &ConnectionOptions) -> Result<(), net_error> {
+        let response_metadata = HttpResponseMetadata::from(req);
+        let contract_identifier = QualifiedContractIdentifier::new(contract_addr.clone().into(), contract_name.clone());
+
+        // parsing, analysis, and evaluation all draw from the same budget
+        let mut cost_track = LimitedCostTracker::new(options.read_only_call_limit.clone());
+
+        let data: Result<Value, InterpreterError> = chainstate.maybe_read_only_clarity_tx(tip, |clarity_tx| {
+            let parsed = ast::build_ast(&contract_identifier, expression, &mut cost_track)?
+                .expressions;
+            if parsed.len() != 1 {
+                return Err(RuntimeErrorType::ParseError("Expected a single expression".to_string()).into())
+            }
+
+            let (analysis, cost_track) = clarity_tx.with_analysis_db_readonly(|db| {
+                if let Err(e) = ReadOnlyChecker::check_read_only_expression(db, &contract_identifier, &parsed[0]) {
+                    return (Err(e), cost_track);
+                }
+                TypeChecker::type_check_expression(db, &contract_identifier, &parsed[0], cost_track)
+            });
+            analysis.map_err(|e| InterpreterError::from(e.err))?;
+
+            clarity_tx.with_readonly_clarity_env(sender.clone(), cost_track, |env| {
+                env.eval_read_only(&contract_identifier, expression)
+            })
+        });
+
+        let response = match data {
+            Ok(data) => 
+                CallReadOnlyResponse { okay: true, result: Some(format!("0x{}", data.serialize())), cause: None },
+            Err(e) =>
+                CallReadOnlyResponse { okay: false, result: None, cause: Some(e.to_string()) },
+        };
+
+        let response = HttpResponseType::EvalReadOnly(response_metadata, response);
+        response.send(http, fd).map(|_| ())
+    }
+
     /// Handle a GET to fetch a contract's source code, given the chain tip.  Optionally returns a
     /// MARF proof as well.
     fn handle_get_contract_src<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType,
@@ -569,10 +1126,13 @@ impl ConversationHttp {
         let contract_identifier = QualifiedContractIdentifier::new(contract_addr.clone().into(), contract_name.clone());
 
         let data = chainstate.maybe_read_only_clarity_tx(tip, |clarity_tx| {
-            clarity_tx.with_clarity_db_readonly(|db| {
-                let source = db.get_contract_src(&contract_identifier)?;
+            clarity_tx.with_clarity_db_readonly(|db| -> Result<Option<ContractSrcResponse>, InterpreterError> {
+                let source = match db.get_contract_src(&contract_identifier) {
+                    Some(source) => source,
+                    None => return Ok(None)
+                };
                 let contract_commit_key = MarfedKV::make_contract_hash_key(&contract_identifier);
-                let (contract_commit, proof) = db.get_with_proof::<ContractCommitment>(&contract_commit_key)
+                let (contract_commit, proof) = db.get_with_proof::<ContractCommitment>(&contract_commit_key)?
                     .expect("BUG: obtained source, but couldn't get MARF proof.");
                 let marf_proof = if with_proof {
                     Some(proof.to_hex())
@@ -580,13 +1140,17 @@ impl ConversationHttp {
                     None
                 };
                 let publish_height = contract_commit.block_height;
-                Some(ContractSrcResponse { source, publish_height, marf_proof })
+                Ok(Some(ContractSrcResponse { source, publish_height, marf_proof }))
             })
         });
 
         let response = match data {
-            Some(data) => HttpResponseType::GetContractSrc(response_metadata, data),
-            None => HttpResponseType::NotFound(response_metadata, "No contract source data found".into())
+            Ok(Some(data)) => HttpResponseType::GetContractSrc(response_metadata, data),
+            Ok(None) => HttpResponseType::NotFound(response_metadata, "No contract source data found".into()),
+            Err(e) => {
+                warn!("Failed to load contract source of {}: {:?}", &contract_identifier, &e);
+                HttpResponseType::ServerError(response_metadata, format!("Failed to load contract source of {}", &contract_identifier))
+            }
         };
         
         response.send(http, fd).map(|_| ())
@@ -657,9 +1221,19 @@ impl ConversationHttp {
     /// * tip_opt, if it's Some(..),
     /// * the unconfirmed canonical stacks chain tip, if initialized
     /// * the confirmed canonical stacks chain tip
-    fn handle_load_stacks_chain_tip<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType, tip_opt: Option<&StacksBlockId>, sortdb: &SortitionDB, chainstate: &StacksChainState) -> Result<Option<StacksBlockId>, net_error> {
+    fn handle_load_stacks_chain_tip<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType, tip_opt: Option<&StacksBlockId>, sortdb: &SortitionDB, chainstate: &mut StacksChainState) -> Result<Option<StacksBlockId>, net_error> {
         match tip_opt {
-            Some(tip) => Ok(Some(*tip).clone()),
+            Some(tip) => {
+                // a pruned node can't read the state as of a pruned block
+                let pruned = chainstate.with_clarity_marf(|marf| marf.borrow_storage_backend().is_block_pruned(tip))
+                    .map_err(|e| net_error::from(chain_error::MARFError(e)))?;
+                if pruned {
+                    let response_metadata = HttpResponseMetadata::from(req);
+                    let response = HttpResponseType::NotFound(response_metadata, format!("State as of {} has been pruned from this node", tip));
+                    return response.send(http, fd).and_then(|_| Ok(None));
+                }
+                Ok(Some(*tip).clone())
+            },
             None => {
                 match chainstate.get_stacks_chain_tip(sortdb)? {
                     Some(tip) => Ok(Some(StacksBlockHeader::make_index_block_hash(&tip.burn_header_hash, &tip.anchored_block_hash))),
@@ -699,33 +1273,257 @@ impl ConversationHttp {
         response.send(http, fd).and_then(|_| Ok(None))
     }
 
-    /// Handle a transaction.  Directly submit it to the mempool so the client can see any
-    /// rejection reasons up-front (different from how the peer network handles it).  Indicate
-    /// whether or not the transaction was accepted (and thus needs to be forwarded) in the return
-    /// value.
-    fn handle_post_transaction<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType, burn_header_hash: BurnchainHeaderHash, block_hash: BlockHeaderHash, mempool: &mut MemPoolDB, tx: StacksTransaction) -> Result<bool, net_error> {
-        let txid = tx.txid();
+    /// Reply with a single block header, or a 404 if it could not be found
+    fn reply_header_info<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType, sortdb: &SortitionDB,
+                                   header_info_res: Result<Option<StacksHeaderInfo>, chain_error>, not_found_msg: String) -> Result<(), net_error> {
         let response_metadata = HttpResponseMetadata::from(req);
-        let (response, accepted) = 
-            if mempool.has_tx(&txid) {
-                (HttpResponseType::TransactionID(response_metadata, txid), false)
-            }
-            else {
-                match mempool.submit(&burn_header_hash, &block_hash, tx) {
-                    Ok(_) => {
-                        (HttpResponseType::TransactionID(response_metadata, txid), true)
-                    }
+        let response = match header_info_res.map_err(net_error::from) {
+            Ok(Some(header_info)) => {
+                let data_res = SortitionDB::get_canonical_burn_chain_tip_stubbed(&sortdb.conn)
+                    .map_err(net_error::from)
+                    .and_then(|burn_tip| HeaderInfoResponse::from_header_info(&sortdb.index_handle(&burn_tip.sortition_id), &header_info));
+
+                match data_res {
+                    Ok(data) => HttpResponseType::GetHeader(response_metadata, data),
                     Err(e) => {
-                        (HttpResponseType::BadRequestJSON(response_metadata, e.into_json(&txid)), false)
+                        warn!("Failed to load consensus hash for {}: {:?}", &header_info.index_block_hash(), &e);
+                        HttpResponseType::ServerError(response_metadata, format!("Failed to query block header"))
                     }
                 }
-            };
+            },
+            Ok(None) => {
+                HttpResponseType::NotFound(response_metadata, not_found_msg)
+            },
+            Err(e) => {
+                warn!("Failed to load block header: {:?}", &e);
+                HttpResponseType::ServerError(response_metadata, format!("Failed to query block header"))
+            }
+        };
 
-        response.send(http, fd).and_then(|_| Ok(accepted))
+        response.send(http, fd).map(|_| ())
     }
 
-    /// Handle a microblock.  Directly submit it to the microblock store so the client can see any
-    /// rejection reasons up-front (different from how the peer network handles it).  Indicate
+    /// Handle a GET on a block header, given its index block hash.  The header need not be on the
+    /// canonical fork.
+    fn handle_get_header<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType, sortdb: &SortitionDB,
+                                   chainstate: &StacksChainState, index_block_hash: &StacksBlockId) -> Result<(), net_error> {
+        let header_info_res = StacksChainState::get_stacks_block_header_info_by_index_block_hash(&chainstate.headers_db, index_block_hash);
+        ConversationHttp::reply_header_info(http, fd, req, sortdb, header_info_res, format!("No such block header {}", index_block_hash))
+    }
+
+    /// Handle a GET on the block header at a given height in the fork identified by the given chain tip.
+    fn handle_get_header_by_height<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType, sortdb: &SortitionDB,
+                                             chainstate: &StacksChainState, tip: &StacksBlockId, height: u64) -> Result<(), net_error> {
+        let header_info_res = chainstate.get_ancestor_header_info(tip, height);
+        ConversationHttp::reply_header_info(http, fd, req, sortdb, header_info_res, format!("No block header at height {} off of {}", height, tip))
+    }
+
+    /// Handle a GET on a range of consecutive block headers in the fork identified by the given
+    /// chain tip.  The list will be shorter than requested (or empty) if it runs past the tip.
+    fn handle_get_headers<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType, sortdb: &SortitionDB,
+                                    chainstate: &StacksChainState, tip: &StacksBlockId, start_height: u64, count: u64) -> Result<(), net_error> {
+        let response_metadata = HttpResponseMetadata::from(req);
+        let data_res = chainstate.get_ancestor_header_infos(tip, start_height, count)
+            .map_err(net_error::from)
+            .and_then(|header_infos| {
+                let burn_tip = SortitionDB::get_canonical_burn_chain_tip_stubbed(&sortdb.conn)?;
+                let sort_handle = sortdb.index_handle(&burn_tip.sortition_id);
+                header_infos
+                    .iter()
+                    .map(|header_info| HeaderInfoResponse::from_header_info(&sort_handle, header_info))
+                    .collect::<Result<Vec<HeaderInfoResponse>, net_error>>()
+            });
+
+        let response = match data_res {
+            Ok(data) => HttpResponseType::GetHeaders(response_metadata, data),
+            Err(e) => {
+                warn!("Failed to load block headers {}-{} off of {}: {:?}", start_height, start_height.saturating_add(count), tip, &e);
+                HttpResponseType::ServerError(response_metadata, format!("Failed to query block headers"))
+            }
+        };
+
+        response.send(http, fd).map(|_| ())
+    }
+
+    /// Handle a GET on the heads of all known forks, highest first.
+    fn handle_get_header_tips<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType, sortdb: &SortitionDB,
+                                        chainstate: &StacksChainState) -> Result<(), net_error> {
+        let response_metadata = HttpResponseMetadata::from(req);
+        let data_res = StacksChainState::get_header_tips(&chainstate.headers_db, MAX_HEADERS_RANGE_LEN)
+            .map_err(net_error::from)
+            .and_then(|header_infos| {
+                let burn_tip = SortitionDB::get_canonical_burn_chain_tip_stubbed(&sortdb.conn)?;
+                let sort_handle = sortdb.index_handle(&burn_tip.sortition_id);
+                header_infos
+                    .iter()
+                    .map(|header_info| HeaderInfoResponse::from_header_info(&sort_handle, header_info))
+                    .collect::<Result<Vec<HeaderInfoResponse>, net_error>>()
+            });
+
+        let response = match data_res {
+            Ok(data) => HttpResponseType::GetHeaders(response_metadata, data),
+            Err(e) => {
+                warn!("Failed to load fork tip block headers: {:?}", &e);
+                HttpResponseType::ServerError(response_metadata, format!("Failed to query block headers"))
+            }
+        };
+
+        response.send(http, fd).map(|_| ())
+    }
+
+    /// Handle a GET on a transaction, given the current chain tip.  Reports whether or not the
+    /// transaction was confirmed in this fork (and if so, where, and what its receipt was), or
+    /// whether or not it is still pending in the mempool.
+    fn handle_get_transaction<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType, chainstate: &StacksChainState,
+                                        mempool: &MemPoolDB, tip: &StacksBlockId, txid: &Txid) -> Result<(), net_error> {
+        let response_metadata = HttpResponseMetadata::from(req);
+
+        let confirmed = match chainstate.get_transaction_info(tip, txid) {
+            Ok(tx_info_opt) => tx_info_opt,
+            Err(e) => {
+                warn!("Failed to load transaction {} off of {}: {:?}", txid, tip, &e);
+                let response = HttpResponseType::ServerError(response_metadata, format!("Failed to query transaction {}", txid));
+                return response.send(http, fd).map(|_| ());
+            }
+        };
+
+        let response = match confirmed {
+            Some(tx_info) => {
+                let status = if tx_info.microblock_hash.is_some() { "microblock" } else { "anchored" };
+                let data = TransactionInfoResponse {
+                    txid: tx_info.txid.to_hex(),
+                    status: status.to_string(),
+                    tx: format!("0x{}", to_hex(&tx_info.tx.serialize_to_vec())),
+                    index_block_hash: Some(tx_info.index_block_hash.to_hex()),
+                    microblock_hash: tx_info.microblock_hash.map(|hash| hash.to_hex()),
+                    tx_index: Some(tx_info.tx_index),
+                    result: Some(format!("0x{}", tx_info.result.serialize())),
+                    post_condition_aborted: Some(tx_info.post_condition_aborted),
+                    events: Some(tx_info.events),
+                    execution_cost: Some(tx_info.execution_cost)
+                };
+                HttpResponseType::GetTransaction(response_metadata, data)
+            },
+            None => {
+                match MemPoolDB::get_tx(mempool.conn(), txid) {
+                    Ok(Some(mempool_tx)) => {
+                        let data = TransactionInfoResponse {
+                            txid: txid.to_hex(),
+                            status: "pending".to_string(),
+                            tx: format!("0x{}", to_hex(&mempool_tx.tx.serialize_to_vec())),
+                            index_block_hash: None,
+                            microblock_hash: None,
+                            tx_index: None,
+                            result: None,
+                            post_condition_aborted: None,
+                            events: None,
+                            execution_cost: None
+                        };
+                        HttpResponseType::GetTransaction(response_metadata, data)
+                    },
+                    Ok(None) => {
+                        HttpResponseType::NotFound(response_metadata, format!("No such transaction {}", txid))
+                    },
+                    Err(e) => {
+                        warn!("Failed to load transaction {} from the mempool: {:?}", txid, &e);
+                        HttpResponseType::ServerError(response_metadata, format!("Failed to query transaction {}", txid))
+                    }
+                }
+            }
+        };
+
+        response.send(http, fd).map(|_| ())
+    }
+
+    /// Handle a GET on a page of the txids of all pending transactions in the mempool.
+    fn handle_get_mempool_txids<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType, mempool: &MemPoolDB, page: u64) -> Result<(), net_error> {
+        let response_metadata = HttpResponseMetadata::from(req);
+        let offset = page.saturating_mul(MAX_MEMPOOL_TXIDS_PAGE_LEN);
+        let txids_res = MemPoolDB::get_num_txs(mempool.conn())
+            .and_then(|total| {
+                MemPoolDB::get_txids(mempool.conn(), offset, MAX_MEMPOOL_TXIDS_PAGE_LEN)
+                    .map(|txids| (total, txids))
+            });
+
+        let response = match txids_res {
+            Ok((total, txids)) => {
+                let data = MemPoolTxidsResponse {
+                    txids: txids.into_iter().map(|txid| txid.to_hex()).collect(),
+                    page: page,
+                    total: total
+                };
+                HttpResponseType::MemPoolTxids(response_metadata, data)
+            },
+            Err(e) => {
+                warn!("Failed to load mempool txids: {:?}", &e);
+                HttpResponseType::ServerError(response_metadata, format!("Failed to query mempool txids"))
+            }
+        };
+        response.send(http, fd).map(|_| ())
+    }
+
+    /// Handle a GET on a pending transaction in the mempool.
+    fn handle_get_mempool_transaction<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType, mempool: &MemPoolDB, txid: &Txid) -> Result<(), net_error> {
+        let response_metadata = HttpResponseMetadata::from(req);
+        let response = match MemPoolDB::get_tx(mempool.conn(), txid) {
+            Ok(Some(tx_info)) => {
+                HttpResponseType::MemPoolTransaction(response_metadata, MemPoolTransactionResponse::from_mempool_tx(&tx_info))
+            },
+            Ok(None) => {
+                HttpResponseType::NotFound(response_metadata, format!("No such pending transaction {}", txid))
+            },
+            Err(e) => {
+                warn!("Failed to load transaction {} from the mempool: {:?}", txid, &e);
+                HttpResponseType::ServerError(response_metadata, format!("Failed to query transaction {}", txid))
+            }
+        };
+        response.send(http, fd).map(|_| ())
+    }
+
+    /// Handle a GET on all pending transactions in the mempool that the given address either
+    /// sent or sponsored.
+    fn handle_get_mempool_transactions_for_address<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType, mempool: &MemPoolDB, addr: &StacksAddress) -> Result<(), net_error> {
+        let response_metadata = HttpResponseMetadata::from(req);
+        let response = match MemPoolDB::get_txs_for_address(mempool.conn(), addr) {
+            Ok(tx_infos) => {
+                let data = tx_infos.iter().map(|tx_info| MemPoolTransactionResponse::from_mempool_tx(tx_info)).collect();
+                HttpResponseType::MemPoolTransactions(response_metadata, data)
+            },
+            Err(e) => {
+                warn!("Failed to load pending transactions for {}: {:?}", addr, &e);
+                HttpResponseType::ServerError(response_metadata, format!("Failed to query pending transactions for {}", addr))
+            }
+        };
+        response.send(http, fd).map(|_| ())
+    }
+
+    /// Handle a transaction.  Directly submit it to the mempool so the client can see any
+    /// rejection reasons up-front (different from how the peer network handles it).  Indicate
+    /// whether or not the transaction was accepted (and thus needs to be forwarded) in the return
+    /// value.
+    fn handle_post_transaction<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType, burn_header_hash: BurnchainHeaderHash, block_hash: BlockHeaderHash, mempool: &mut MemPoolDB, tx: StacksTransaction) -> Result<bool, net_error> {
+        let txid = tx.txid();
+        let response_metadata = HttpResponseMetadata::from(req);
+        let (response, accepted) = 
+            if mempool.has_tx(&txid) {
+                (HttpResponseType::TransactionID(response_metadata, txid), false)
+            }
+            else {
+                match mempool.submit(&burn_header_hash, &block_hash, tx) {
+                    Ok(_) => {
+                        (HttpResponseType::TransactionID(response_metadata, txid), true)
+                    }
+                    Err(e) => {
+                        (HttpResponseType::BadRequestJSON(response_metadata, e.into_json(&txid)), false)
+                    }
+                }
+            };
+
+        response.send(http, fd).and_then(|_| Ok(accepted))
+    }
+
+    /// Handle a microblock.  Directly submit it to the microblock store so the client can see any
+    /// rejection reasons up-front (different from how the peer network handles it).  Indicate
     /// whether or not the microblock was accepted (and thus needs to be forwarded) in the return
     /// value.
     fn handle_post_microblock<W: Write>(http: &mut StacksHttp, fd: &mut W, req: &HttpRequestType, burn_header_hash: BurnchainHeaderHash, block_hash: BlockHeaderHash, chainstate: &mut StacksChainState, microblock: StacksMicroblock) -> Result<bool, net_error> {
@@ -792,6 +1590,12 @@ impl ConversationHttp {
                 }
                 None
             },
+            HttpRequestType::GetAccountNonces(ref _md, ref principal, ref tip_opt) => {
+                if let Some(tip) = ConversationHttp::handle_load_stacks_chain_tip(&mut self.connection.protocol, &mut reply, &req, tip_opt.as_ref(), sortdb, chainstate)? {
+                    ConversationHttp::handle_get_account_nonces(&mut self.connection.protocol, &mut reply, &req, chainstate, mempool, &tip, principal)?;
+                }
+                None
+            },
             HttpRequestType::GetMapEntry(ref _md, ref contract_addr, ref contract_name, ref map_name, ref key, ref tip_opt, ref with_proof) => {
                 if let Some(tip) = ConversationHttp::handle_load_stacks_chain_tip(&mut self.connection.protocol, &mut reply, &req, tip_opt.as_ref(), sortdb, chainstate)? {
                     ConversationHttp::handle_get_map_entry(&mut self.connection.protocol, &mut reply, &req, chainstate, &tip,
@@ -803,6 +1607,19 @@ impl ConversationHttp {
                 ConversationHttp::handle_token_transfer_cost(&mut self.connection.protocol, &mut reply, &req)?;
                 None
             },
+            HttpRequestType::GetFeeRates(ref _md, ref tip_opt) => {
+                if let Some(tip) = ConversationHttp::handle_load_stacks_chain_tip(&mut self.connection.protocol, &mut reply, &req, tip_opt.as_ref(), sortdb, chainstate)? {
+                    ConversationHttp::handle_get_fee_rates(&mut self.connection.protocol, &mut reply, &req, chainstate, mempool, &tip)?;
+                }
+                None
+            },
+            HttpRequestType::EstimateTransactionFee(ref _md, ref tx, ref tip_opt) => {
+                if let Some((burn_header_hash, block_hash)) = ConversationHttp::handle_load_stacks_chain_tip_hashes(&mut self.connection.protocol, &mut reply, &req, tip_opt.as_ref(), sortdb, chainstate)? {
+                    ConversationHttp::handle_estimate_transaction_fee(&mut self.connection.protocol, &mut reply, &req, chainstate, mempool,
+                                                                      &burn_header_hash, &block_hash, tx, &self.connection.options)?;
+                }
+                None
+            },
             HttpRequestType::GetContractABI(ref _md, ref contract_addr, ref contract_name, ref tip_opt) => {
                 if let Some(tip) = ConversationHttp::handle_load_stacks_chain_tip(&mut self.connection.protocol, &mut reply, &req, tip_opt.as_ref(), sortdb, chainstate)? {
                     ConversationHttp::handle_get_contract_abi(&mut self.connection.protocol, &mut reply, &req, chainstate, &tip,
@@ -818,6 +1635,55 @@ impl ConversationHttp {
                 }
                 None
             },
+            HttpRequestType::GetMapKeys(ref _md, ref contract_addr, ref contract_name, ref map_name, ref after, ref tip_opt) => {
+                if let Some(tip) = ConversationHttp::handle_load_stacks_chain_tip(&mut self.connection.protocol, &mut reply, &req, tip_opt.as_ref(), sortdb, chainstate)? {
+                    ConversationHttp::handle_get_map_keys(&mut self.connection.protocol, &mut reply, &req, chainstate, &tip,
+                                                          contract_addr, contract_name, map_name, after.as_ref().map(|key| key.as_str()))?;
+                }
+                None
+            },
+            HttpRequestType::GetDataVar(ref _md, ref contract_addr, ref contract_name, ref var_name, ref tip_opt, ref with_proof) => {
+                if let Some(tip) = ConversationHttp::handle_load_stacks_chain_tip(&mut self.connection.protocol, &mut reply, &req, tip_opt.as_ref(), sortdb, chainstate)? {
+                    ConversationHttp::handle_get_data_var(&mut self.connection.protocol, &mut reply, &req, chainstate, &tip,
+                                                          contract_addr, contract_name, var_name, *with_proof)?;
+                }
+                None
+            },
+            HttpRequestType::GetFungibleTokenBalance(ref _md, ref contract_addr, ref contract_name, ref token_name, ref principal, ref tip_opt, ref with_proof) => {
+                if let Some(tip) = ConversationHttp::handle_load_stacks_chain_tip(&mut self.connection.protocol, &mut reply, &req, tip_opt.as_ref(), sortdb, chainstate)? {
+                    ConversationHttp::handle_get_ft_balance(&mut self.connection.protocol, &mut reply, &req, chainstate, &tip,
+                                                            contract_addr, contract_name, token_name, principal, *with_proof)?;
+                }
+                None
+            },
+            HttpRequestType::GetFungibleTokenSupply(ref _md, ref contract_addr, ref contract_name, ref token_name, ref tip_opt, ref with_proof) => {
+                if let Some(tip) = ConversationHttp::handle_load_stacks_chain_tip(&mut self.connection.protocol, &mut reply, &req, tip_opt.as_ref(), sortdb, chainstate)? {
+                    ConversationHttp::handle_get_ft_supply(&mut self.connection.protocol, &mut reply, &req, chainstate, &tip,
+                                                           contract_addr, contract_name, token_name, *with_proof)?;
+                }
+                None
+            },
+            HttpRequestType::GetNonFungibleTokenOwner(ref _md, ref contract_addr, ref contract_name, ref asset_name, ref asset, ref tip_opt, ref with_proof) => {
+                if let Some(tip) = ConversationHttp::handle_load_stacks_chain_tip(&mut self.connection.protocol, &mut reply, &req, tip_opt.as_ref(), sortdb, chainstate)? {
+                    ConversationHttp::handle_get_nft_owner(&mut self.connection.protocol, &mut reply, &req, chainstate, &tip,
+                                                           contract_addr, contract_name, asset_name, asset, *with_proof)?;
+                }
+                None
+            },
+            HttpRequestType::GetAssetHoldings(ref _md, ref principal, ref after, ref tip_opt, ref with_proof) => {
+                if let Some(tip) = ConversationHttp::handle_load_stacks_chain_tip(&mut self.connection.protocol, &mut reply, &req, tip_opt.as_ref(), sortdb, chainstate)? {
+                    ConversationHttp::handle_get_asset_holdings(&mut self.connection.protocol, &mut reply, &req, chainstate, &tip,
+                                                                principal, after.as_ref(), *with_proof)?;
+                }
+                None
+            },
+            HttpRequestType::EvalReadOnly(ref _md, ref ctrct_addr, ref ctrct_name, ref as_sender, ref expression, ref tip_opt) => {
+                if let Some(tip) = ConversationHttp::handle_load_stacks_chain_tip(&mut self.connection.protocol, &mut reply, &req, tip_opt.as_ref(), sortdb, chainstate)? {
+                    ConversationHttp::handle_eval_read_only(&mut self.connection.protocol, &mut reply, &req, chainstate, &tip,
+                                                            ctrct_addr, ctrct_name, as_sender, expression, &self.connection.options)?;
+                }
+                None
+            },
             HttpRequestType::GetContractSrc(ref _md, ref contract_addr, ref contract_name, ref tip_opt, ref with_proof) => {
                 if let Some(tip) = ConversationHttp::handle_load_stacks_chain_tip(&mut self.connection.protocol, &mut reply, &req, tip_opt.as_ref(), sortdb, chainstate)? {
                     ConversationHttp::handle_get_contract_src(&mut self.connection.protocol, &mut reply, &req, chainstate, &tip,
@@ -825,6 +1691,44 @@ impl ConversationHttp {
                 }
                 None
             },
+            HttpRequestType::GetTransaction(ref _md, ref txid, ref tip_opt) => {
+                if let Some(tip) = ConversationHttp::handle_load_stacks_chain_tip(&mut self.connection.protocol, &mut reply, &req, tip_opt.as_ref(), sortdb, chainstate)? {
+                    ConversationHttp::handle_get_transaction(&mut self.connection.protocol, &mut reply, &req, chainstate, mempool, &tip, txid)?;
+                }
+                None
+            },
+            HttpRequestType::GetHeader(ref _md, ref index_block_hash) => {
+                ConversationHttp::handle_get_header(&mut self.connection.protocol, &mut reply, &req, sortdb, chainstate, index_block_hash)?;
+                None
+            },
+            HttpRequestType::GetHeaderByHeight(ref _md, ref height, ref tip_opt) => {
+                if let Some(tip) = ConversationHttp::handle_load_stacks_chain_tip(&mut self.connection.protocol, &mut reply, &req, tip_opt.as_ref(), sortdb, chainstate)? {
+                    ConversationHttp::handle_get_header_by_height(&mut self.connection.protocol, &mut reply, &req, sortdb, chainstate, &tip, *height)?;
+                }
+                None
+            },
+            HttpRequestType::GetHeaders(ref _md, ref start_height, ref count, ref tip_opt) => {
+                if let Some(tip) = ConversationHttp::handle_load_stacks_chain_tip(&mut self.connection.protocol, &mut reply, &req, tip_opt.as_ref(), sortdb, chainstate)? {
+                    ConversationHttp::handle_get_headers(&mut self.connection.protocol, &mut reply, &req, sortdb, chainstate, &tip, *start_height, *count)?;
+                }
+                None
+            },
+            HttpRequestType::GetHeaderTips(ref _md) => {
+                ConversationHttp::handle_get_header_tips(&mut self.connection.protocol, &mut reply, &req, sortdb, chainstate)?;
+                None
+            },
+            HttpRequestType::GetMemPoolTxids(ref _md, ref page) => {
+                ConversationHttp::handle_get_mempool_txids(&mut self.connection.protocol, &mut reply, &req, mempool, *page)?;
+                None
+            },
+            HttpRequestType::GetMemPoolTransaction(ref _md, ref txid) => {
+                ConversationHttp::handle_get_mempool_transaction(&mut self.connection.protocol, &mut reply, &req, mempool, txid)?;
+                None
+            },
+            HttpRequestType::GetMemPoolTransactionsForAddress(ref _md, ref addr) => {
+                ConversationHttp::handle_get_mempool_transactions_for_address(&mut self.connection.protocol, &mut reply, &req, mempool, addr)?;
+                None
+            },
             HttpRequestType::PostTransaction(ref _md, ref tx) => {
                 match chainstate.get_stacks_chain_tip(sortdb)? {
                     Some(tip) => {
@@ -854,6 +1758,15 @@ impl ConversationHttp {
                 }
                 None
             },
+            HttpRequestType::GetEventStream(ref _md, ref key, ref topics) => {
+                let response_metadata = HttpResponseMetadata::from(&req);
+                let response = HttpResponseType::EventStreamUpgrade(response_metadata, websocket::make_accept_key(key));
+                response.send(&mut self.connection.protocol, &mut reply).map(|_| ())?;
+
+                // everything after the 101 reply is WebSocket traffic
+                self.websocket = Some(WebSocketState::new(topics.clone(), self.max_websocket_buffer, get_epoch_time_secs()));
+                None
+            },
             HttpRequestType::OptionsPreflight(ref _md, ref _path) => {
                 let response_metadata = HttpResponseMetadata::from(&req);
                 let response = HttpResponseType::OptionsPreflight(response_metadata);
@@ -1053,8 +1966,12 @@ impl ConversationHttp {
     }
 
     /// Is the conversation out of pending data?
-    /// Don't consider it drained if we haven't received anything yet
+    /// Don't consider it drained if we haven't received anything yet.
+    /// An event stream is only drained once it has been closed and the close has been sent.
     pub fn is_drained(&self) -> bool {
+        if let Some(ref ws) = self.websocket {
+            return ws.is_closed() && ws.is_flushed() && self.is_idle();
+        }
         ((self.total_request_count > 0 && self.total_reply_count > 0) || self.pending_error_response.is_some()) && self.is_idle()
     }
 
@@ -1138,6 +2055,18 @@ impl ConversationHttp {
 
     /// Load data into our HTTP connection
     pub fn recv<R: Read>(&mut self, r: &mut R) -> Result<usize, net_error> {
+        if let Some(ref mut ws) = self.websocket {
+            // no more HTTP on this conversation
+            let nrecv = ws.recv(r)?;
+            if nrecv > 0 {
+                self.last_request_timestamp = get_epoch_time_secs();
+            }
+            if ws.is_closed() {
+                self.keep_alive = false;
+            }
+            return Ok(nrecv);
+        }
+
         let mut total_recv = 0;
         loop {
             let nrecv = match self.connection.recv_data(r) {
@@ -1166,7 +2095,7 @@ impl ConversationHttp {
             // prime the Write
             self.try_flush(chainstate)?;
 
-            let sz = match self.connection.send_data(w) {
+            let mut sz = match self.connection.send_data(w) {
                 Ok(sz) => sz,
                 Err(e) => {
                     info!("{:?}: failed to send on HTTP conversation: {:?}", self, &e);
@@ -1174,6 +2103,13 @@ impl ConversationHttp {
                 }
             };
 
+            if sz == 0 && self.reply_streams.len() == 0 {
+                // HTTP replies (i.e. the 101 upgrade) are all sent, so event stream frames can go out
+                if let Some(ref mut ws) = self.websocket {
+                    sz = ws.send(w)?;
+                }
+            }
+
             total_sz += sz;
             if sz > 0 {
                 self.last_response_timestamp = get_epoch_time_secs();
@@ -1225,11 +2161,96 @@ impl ConversationHttp {
         HttpRequestType::PostMicroblock(HttpRequestMetadata::from_host(self.peer_host.clone()), mblock, tip_opt)
     }
 
+    /// Make a new request for a transaction
+    pub fn new_gettransaction(&self, txid: Txid, tip_opt: Option<StacksBlockId>) -> HttpRequestType {
+        HttpRequestType::GetTransaction(HttpRequestMetadata::from_host(self.peer_host.clone()), txid, tip_opt)
+    }
+
+    /// Make a new request for a block header by index block hash
+    pub fn new_getheader(&self, index_block_hash: StacksBlockId) -> HttpRequestType {
+        HttpRequestType::GetHeader(HttpRequestMetadata::from_host(self.peer_host.clone()), index_block_hash)
+    }
+
+    /// Make a new request for a block header by height
+    pub fn new_getheader_by_height(&self, height: u64, tip_opt: Option<StacksBlockId>) -> HttpRequestType {
+        HttpRequestType::GetHeaderByHeight(HttpRequestMetadata::from_host(self.peer_host.clone()), height, tip_opt)
+    }
+
+    /// Make a new request for a range of block headers
+    pub fn new_getheaders(&self, start_height: u64, count: u64, tip_opt: Option<StacksBlockId>) -> HttpRequestType {
+        HttpRequestType::GetHeaders(HttpRequestMetadata::from_host(self.peer_host.clone()), start_height, count, tip_opt)
+    }
+
+    /// Make a new request for the heads of all known forks
+    pub fn new_getheader_tips(&self) -> HttpRequestType {
+        HttpRequestType::GetHeaderTips(HttpRequestMetadata::from_host(self.peer_host.clone()))
+    }
+
+    /// Make a new request for fee rate estimates
+    pub fn new_getfeerates(&self, tip_opt: Option<StacksBlockId>) -> HttpRequestType {
+        HttpRequestType::GetFeeRates(HttpRequestMetadata::from_host(self.peer_host.clone()), tip_opt)
+    }
+
+    /// Make a new request to estimate a transaction's fee
+    pub fn new_estimate_transaction_fee(&self, tx: StacksTransaction, tip_opt: Option<StacksBlockId>) -> HttpRequestType {
+        HttpRequestType::EstimateTransactionFee(HttpRequestMetadata::from_host(self.peer_host.clone()), tx, tip_opt)
+    }
+
+    /// Make a new request for a page of pending txids
+    pub fn new_getmempooltxids(&self, page: u64) -> HttpRequestType {
+        HttpRequestType::GetMemPoolTxids(HttpRequestMetadata::from_host(self.peer_host.clone()), page)
+    }
+
+    /// Make a new request for a pending transaction
+    pub fn new_getmempooltransaction(&self, txid: Txid) -> HttpRequestType {
+        HttpRequestType::GetMemPoolTransaction(HttpRequestMetadata::from_host(self.peer_host.clone()), txid)
+    }
+
+    /// Make a new request for an address's pending transactions
+    pub fn new_getmempooltransactions_for_address(&self, addr: StacksAddress) -> HttpRequestType {
+        HttpRequestType::GetMemPoolTransactionsForAddress(HttpRequestMetadata::from_host(self.peer_host.clone()), addr)
+    }
+
     /// Make a new request for an account
     pub fn new_getaccount(&self, principal: PrincipalData, tip_opt: Option<StacksBlockId>, with_proof: bool) -> HttpRequestType {
         HttpRequestType::GetAccount(HttpRequestMetadata::from_host(self.peer_host.clone()), principal, tip_opt, with_proof)
     }
 
+    /// Make a new request for an account's confirmed and pending nonces
+    pub fn new_getaccountnonces(&self, principal: PrincipalData, tip_opt: Option<StacksBlockId>) -> HttpRequestType {
+        HttpRequestType::GetAccountNonces(HttpRequestMetadata::from_host(self.peer_host.clone()), principal, tip_opt)
+    }
+
+    /// Make a new request for a page of a data map's keys
+    pub fn new_getmapkeys(&self, contract_addr: StacksAddress, contract_name: ContractName, map_name: ClarityName, after: Option<String>, tip_opt: Option<StacksBlockId>) -> HttpRequestType {
+        HttpRequestType::GetMapKeys(HttpRequestMetadata::from_host(self.peer_host.clone()), contract_addr, contract_name, map_name, after, tip_opt)
+    }
+
+    /// Make a new request for a data var or constant
+    pub fn new_getdatavar(&self, contract_addr: StacksAddress, contract_name: ContractName, var_name: ClarityName, tip_opt: Option<StacksBlockId>, with_proof: bool) -> HttpRequestType {
+        HttpRequestType::GetDataVar(HttpRequestMetadata::from_host(self.peer_host.clone()), contract_addr, contract_name, var_name, tip_opt, with_proof)
+    }
+
+    /// Make a new request for a principal's balance of a fungible token
+    pub fn new_getftbalance(&self, contract_addr: StacksAddress, contract_name: ContractName, token_name: ClarityName, principal: PrincipalData, tip_opt: Option<StacksBlockId>, with_proof: bool) -> HttpRequestType {
+        HttpRequestType::GetFungibleTokenBalance(HttpRequestMetadata::from_host(self.peer_host.clone()), contract_addr, contract_name, token_name, principal, tip_opt, with_proof)
+    }
+
+    /// Make a new request for a fungible token's supply
+    pub fn new_getftsupply(&self, contract_addr: StacksAddress, contract_name: ContractName, token_name: ClarityName, tip_opt: Option<StacksBlockId>, with_proof: bool) -> HttpRequestType {
+        HttpRequestType::GetFungibleTokenSupply(HttpRequestMetadata::from_host(self.peer_host.clone()), contract_addr, contract_name, token_name, tip_opt, with_proof)
+    }
+
+    /// Make a new request for the owner of a non-fungible token
+    pub fn new_getnftowner(&self, contract_addr: StacksAddress, contract_name: ContractName, asset_name: ClarityName, asset: Value, tip_opt: Option<StacksBlockId>, with_proof: bool) -> HttpRequestType {
+        HttpRequestType::GetNonFungibleTokenOwner(HttpRequestMetadata::from_host(self.peer_host.clone()), contract_addr, contract_name, asset_name, asset, tip_opt, with_proof)
+    }
+
+    /// Make a new request for a page of the fungible and non-fungible tokens a principal holds
+    pub fn new_getassetholdings(&self, principal: PrincipalData, after: Option<AssetHolding>, tip_opt: Option<StacksBlockId>, with_proof: bool) -> HttpRequestType {
+        HttpRequestType::GetAssetHoldings(HttpRequestMetadata::from_host(self.peer_host.clone()), principal, after, tip_opt, with_proof)
+    }
+
     /// Make a new request for a data map
     pub fn new_getmapentry(&self, contract_addr: StacksAddress, contract_name: ContractName, map_name: ClarityName, key: Value, tip_opt: Option<StacksBlockId>, with_proof: bool) -> HttpRequestType {
         HttpRequestType::GetMapEntry(HttpRequestMetadata::from_host(self.peer_host.clone()), contract_addr, contract_name, map_name, key, tip_opt, with_proof)
@@ -1249,6 +2270,11 @@ impl ConversationHttp {
     pub fn new_callreadonlyfunction(&self, contract_addr: StacksAddress, contract_name: ContractName, sender: PrincipalData, function_name: ClarityName, function_args: Vec<Value>, tip_opt: Option<StacksBlockId>) -> HttpRequestType {
         HttpRequestType::CallReadOnlyFunction(HttpRequestMetadata::from_host(self.peer_host.clone()), contract_addr, contract_name, sender, function_name, function_args, tip_opt)
     }
+
+    /// Make a new request to evaluate a read-only expression in the context of a contract
+    pub fn new_evalreadonly(&self, contract_addr: StacksAddress, contract_name: ContractName, sender: PrincipalData, expression: String, tip_opt: Option<StacksBlockId>) -> HttpRequestType {
+        HttpRequestType::EvalReadOnly(HttpRequestMetadata::from_host(self.peer_host.clone()), contract_addr, contract_name, sender, expression, tip_opt)
+    }
 }
 
 #[cfg(test)]
@@ -1272,6 +2298,9 @@ mod test {
     use chainstate::stacks::Error as chain_error;
     use chainstate::stacks::*;
     use chainstate::stacks::miner::*;
+    use chainstate::stacks::index::marf::MARF;
+    use chainstate::stacks::index::node::TriePath;
+    use chainstate::stacks::index::verifier::decode_proof;
     use burnchains::*;
 
     use address::*;
@@ -1283,6 +2312,7 @@ mod test {
     use std::convert::TryInto;
 
     use vm::types::*;
+    use vm::database::ClaritySerializable;
 
     const TEST_CONTRACT : &'static str = "
         (define-data-var bar int 0)
@@ -1294,8 +2324,12 @@ mod test {
           (begin 
             (map-set unit-map ((account tx-sender)) ((units 1)) )
             (ok 1)))
+        (define-fungible-token stackaroos u1000000)
+        (define-non-fungible-token names int)
         (begin
-          (map-set unit-map ((account 'ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R)) ((units 123))))";
+          (map-set unit-map ((account 'ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R)) ((units 123))))
+        (ft-mint? stackaroos u100 'ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R)
+        (nft-mint? names 1 'ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R)";
 
     fn convo_send_recv(sender: &mut ConversationHttp, sender_chainstate: &mut StacksChainState, receiver: &mut ConversationHttp, receiver_chainstate: &mut StacksChainState) -> () {
         let (mut pipe_read, mut pipe_write) = Pipe::new();
@@ -1357,6 +2391,13 @@ mod test {
         let mut peer_1 = TestPeer::new(peer_1_config);
         let mut peer_2 = TestPeer::new(peer_2_config);
 
+        // index data map keys, so they can be paged through, and transactions, so they can be
+        // looked up
+        peer_1.chainstate().enable_map_key_index().unwrap();
+        peer_2.chainstate().enable_map_key_index().unwrap();
+        peer_1.chainstate().enable_transaction_index().unwrap();
+        peer_2.chainstate().enable_transaction_index().unwrap();
+
         // mine one block with a contract in it
         // first the coinbase
         // make a coinbase for this miner
@@ -2045,6 +3086,53 @@ mod test {
                  });
     }
     
+    #[test]
+    #[ignore]
+    fn test_rpc_get_map_entry_missing_with_proof() {
+        test_rpc("test_rpc_get_map_entry_missing_with_proof", 40420, 40421, 50420, 50421,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     // the contract itself never gets any units
+                     let principal = PrincipalData::Contract(QualifiedContractIdentifier::parse("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world").unwrap());
+                     convo_client.new_getmapentry(StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(),
+                                                  "hello-world".try_into().unwrap(), "unit-map".try_into().unwrap(),
+                                                  Value::Tuple(TupleData::from_data(vec![("account".into(), Value::Principal(principal))]).unwrap()), None, true)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     match http_response {
+                         HttpResponseType::GetMapEntry(response_md, data) => {
+                             assert_eq!(Value::try_deserialize_hex_untyped(&data.data).unwrap(), Value::none());
+                             let proof = decode_proof::<StacksBlockId>(data.marf_proof.as_ref().unwrap()).unwrap();
+
+                             // the proof shows the key is absent as of the tip's state root
+                             let peer_server_stacks_node = peer_server.stacks_node.as_mut().unwrap();
+                             let tip = peer_server_stacks_node.chainstate.get_stacks_chain_tip(peer_server.sortdb.as_ref().unwrap()).unwrap().unwrap();
+                             let tip = StacksBlockHeader::make_index_block_hash(&tip.burn_header_hash, &tip.anchored_block_hash);
+                             let (root_hash, root_to_block) = peer_server_stacks_node.chainstate.with_clarity_marf(|marf| {
+                                 let tip_height = MARF::get_block_height(marf.borrow_storage_backend(), &tip, &tip).unwrap().unwrap();
+                                 let mut root_to_block = HashMap::new();
+                                 for height in 0..(tip_height + 1) {
+                                     let block = marf.get_bhh_at_height(&tip, height).unwrap().unwrap();
+                                     root_to_block.insert(marf.get_root_hash_at(&block).unwrap(), block);
+                                 }
+                                 (marf.get_root_hash_at(&tip).unwrap(), root_to_block)
+                             });
+
+                             let contract_identifier = QualifiedContractIdentifier::parse("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world").unwrap();
+                             let principal = PrincipalData::Contract(contract_identifier.clone());
+                             let map_key = Value::Tuple(TupleData::from_data(vec![("account".into(), Value::Principal(principal))]).unwrap());
+                             let key = ClarityDatabase::make_key_for_data_map_entry(&contract_identifier, "unit-map", &map_key);
+                             assert!(proof.verify_exclusion(&TriePath::from_key(&key), &root_hash, &root_to_block));
+                             true
+                         },
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
     #[test]
     #[ignore]
     fn test_rpc_get_contract_abi() {
@@ -2140,5 +3228,738 @@ mod test {
                      }
                  });
     }
-}
+    
+    #[test]
+    #[ignore]
+    fn test_rpc_get_transaction() {
+        let confirmed_tx_cell = RefCell::new(None);
+
+        test_rpc("test_rpc_get_transaction", 40190, 40191, 50190, 50191,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     let sortdb = peer_server.sortdb.take().unwrap();
+                     let tip = peer_server.chainstate().get_stacks_chain_tip(&sortdb).unwrap().unwrap();
+                     peer_server.sortdb = Some(sortdb);
+
+                     // the smart contract transaction in the anchored block
+                     let block = StacksChainState::load_block(&peer_server.chainstate().blocks_path, &tip.burn_header_hash, &tip.anchored_block_hash).unwrap().unwrap();
+                     let txid = block.txs[1].txid();
+                     let index_block_hash = StacksBlockHeader::make_index_block_hash(&tip.burn_header_hash, &tip.anchored_block_hash);
+
+                     *confirmed_tx_cell.borrow_mut() = Some((txid.clone(), index_block_hash));
+                     convo_client.new_gettransaction(txid, None)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     let (txid, index_block_hash) = (*confirmed_tx_cell.borrow()).clone().unwrap();
+                     match http_response {
+                         HttpResponseType::GetTransaction(response_md, data) => {
+                             assert_eq!(data.txid, txid.to_hex());
+                             assert_eq!(data.status, "anchored");
+                             assert_eq!(data.index_block_hash, Some(index_block_hash.to_hex()));
+                             assert_eq!(data.microblock_hash, None);
+                             assert_eq!(data.tx_index, Some(1));
+                             assert_eq!(data.post_condition_aborted, Some(false));
+                             assert!(data.execution_cost.is_some());
+                             true
+                         },
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
 
+    #[test]
+    #[ignore]
+    fn test_rpc_get_transaction_missing() {
+        test_rpc("test_rpc_get_transaction_missing", 40200, 40201, 50200, 50201,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     convo_client.new_gettransaction(Txid([0x11; 32]), None)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     match http_response {
+                         HttpResponseType::NotFound(_, _) => true,
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rpc_get_header() {
+        let tip_header_cell = RefCell::new(None);
+
+        test_rpc("test_rpc_get_header", 40210, 40211, 50210, 50211,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     let sortdb = peer_server.sortdb.take().unwrap();
+                     let tip = peer_server.chainstate().get_stacks_chain_tip(&sortdb).unwrap().unwrap();
+                     peer_server.sortdb = Some(sortdb);
+
+                     let tip_header = StacksChainState::get_anchored_block_header_info(&peer_server.chainstate().headers_db, &tip.burn_header_hash, &tip.anchored_block_hash).unwrap().unwrap();
+                     let index_block_hash = tip_header.index_block_hash();
+
+                     *tip_header_cell.borrow_mut() = Some(tip_header);
+                     convo_client.new_getheader(index_block_hash)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     let tip_header = (*tip_header_cell.borrow()).clone().unwrap();
+                     match http_response {
+                         HttpResponseType::GetHeader(response_md, data) => {
+                             assert_eq!(data.index_block_hash, tip_header.index_block_hash());
+                             assert_eq!(data.block_hash, tip_header.anchored_header.block_hash());
+                             assert_eq!(data.block_height, tip_header.block_height);
+                             assert_eq!(data.burn_header_hash, tip_header.burn_header_hash.to_hex());
+                             assert_eq!(data.parent_block_hash, tip_header.anchored_header.parent_block);
+                             assert!(data.consensus_hash.is_some());
+                             true
+                         },
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rpc_get_header_by_height() {
+        let tip_header_cell = RefCell::new(None);
+
+        test_rpc("test_rpc_get_header_by_height", 40220, 40221, 50220, 50221,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     let sortdb = peer_server.sortdb.take().unwrap();
+                     let tip = peer_server.chainstate().get_stacks_chain_tip(&sortdb).unwrap().unwrap();
+                     peer_server.sortdb = Some(sortdb);
+
+                     let tip_header = StacksChainState::get_anchored_block_header_info(&peer_server.chainstate().headers_db, &tip.burn_header_hash, &tip.anchored_block_hash).unwrap().unwrap();
+                     let height = tip_header.block_height;
+
+                     *tip_header_cell.borrow_mut() = Some(tip_header);
+                     convo_client.new_getheader_by_height(height, None)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     let tip_header = (*tip_header_cell.borrow()).clone().unwrap();
+                     match http_response {
+                         HttpResponseType::GetHeader(response_md, data) => {
+                             assert_eq!(data.index_block_hash, tip_header.index_block_hash());
+                             assert_eq!(data.block_height, tip_header.block_height);
+                             true
+                         },
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rpc_get_header_by_height_missing() {
+        test_rpc("test_rpc_get_header_by_height_missing", 40230, 40231, 50230, 50231,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     convo_client.new_getheader_by_height(1000000, None)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     match http_response {
+                         HttpResponseType::NotFound(_, _) => true,
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rpc_get_headers() {
+        let tip_header_cell = RefCell::new(None);
+
+        test_rpc("test_rpc_get_headers", 40240, 40241, 50240, 50241,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     let sortdb = peer_server.sortdb.take().unwrap();
+                     let tip = peer_server.chainstate().get_stacks_chain_tip(&sortdb).unwrap().unwrap();
+                     peer_server.sortdb = Some(sortdb);
+
+                     let tip_header = StacksChainState::get_anchored_block_header_info(&peer_server.chainstate().headers_db, &tip.burn_header_hash, &tip.anchored_block_hash).unwrap().unwrap();
+
+                     *tip_header_cell.borrow_mut() = Some(tip_header);
+
+                     // ask for more headers than there are
+                     convo_client.new_getheaders(0, MAX_HEADERS_RANGE_LEN, None)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     let tip_header = (*tip_header_cell.borrow()).clone().unwrap();
+                     match http_response {
+                         HttpResponseType::GetHeaders(response_md, data) => {
+                             assert_eq!(data.len() as u64, tip_header.block_height + 1);
+                             for (i, header_info) in data.iter().enumerate() {
+                                 assert_eq!(header_info.block_height, i as u64);
+                                 // (the boot block's header is synthetic, so its hash isn't the first block's parent)
+                                 if i > 1 {
+                                     assert_eq!(header_info.parent_block_hash, data[i - 1].block_hash);
+                                 }
+                             }
+                             assert_eq!(data.last().unwrap().index_block_hash, tip_header.index_block_hash());
+                             true
+                         },
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rpc_get_header_tips() {
+        let tip_header_cell = RefCell::new(None);
+
+        test_rpc("test_rpc_get_header_tips", 40430, 40431, 50430, 50431,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     let sortdb = peer_server.sortdb.take().unwrap();
+                     let tip = peer_server.chainstate().get_stacks_chain_tip(&sortdb).unwrap().unwrap();
+                     peer_server.sortdb = Some(sortdb);
+
+                     let tip_header = StacksChainState::get_anchored_block_header_info(&peer_server.chainstate().headers_db, &tip.burn_header_hash, &tip.anchored_block_hash).unwrap().unwrap();
+
+                     *tip_header_cell.borrow_mut() = Some(tip_header);
+
+                     convo_client.new_getheader_tips()
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     let tip_header = (*tip_header_cell.borrow()).clone().unwrap();
+                     match http_response {
+                         HttpResponseType::GetHeaders(response_md, data) => {
+                             // there's only the one fork
+                             assert_eq!(data.len(), 1);
+                             assert_eq!(data[0].index_block_hash, tip_header.index_block_hash());
+                             assert_eq!(data[0].block_height, tip_header.block_height);
+                             true
+                         },
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rpc_get_fee_rates() {
+        test_rpc("test_rpc_get_fee_rates", 40250, 40251, 50250, 50251,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     convo_client.new_getfeerates(None)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     match http_response {
+                         HttpResponseType::FeeRates(response_md, data) => {
+                             // the only mined transactions paid no fee, and the mempool is empty
+                             assert_eq!(*data, fee_estimator::FeeRateEstimate::minimum());
+                             true
+                         },
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rpc_estimate_transaction_fee() {
+        test_rpc("test_rpc_estimate_transaction_fee", 40260, 40261, 50260, 50261,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     let privk2 = StacksPrivateKey::from_hex("94c319327cc5cd04da7147d32d836eb2e4c44f4db39aa5ede7314a761183d0c701").unwrap();
+                     let privk1 = StacksPrivateKey::from_hex("9f1f85a512a96a244e4c0d762788500687feb97481639572e3bffbd6860e6ab001").unwrap();
+                     let addr1 = StacksAddress::from_public_keys(C32_ADDRESS_VERSION_TESTNET_SINGLESIG, &AddressHashMode::SerializeP2PKH, 1, &vec![StacksPublicKey::from_private(&privk1)]).unwrap();
+
+                     let mut tx_cc = StacksTransaction::new(TransactionVersion::Testnet,
+                                                            TransactionAuth::from_p2pkh(&privk2).unwrap(),
+                                                            TransactionPayload::new_contract_call(addr1.clone(), "hello-world", "add-unit", vec![]).unwrap());
+
+                     tx_cc.chain_id = 0x80000000;
+                     tx_cc.auth.set_origin_nonce(0);
+                     tx_cc.set_fee_rate(1);
+
+                     let mut tx_signer = StacksTransactionSigner::new(&tx_cc);
+                     tx_signer.sign_origin(&privk2).unwrap();
+                     let tx_cc_signed = tx_signer.get_tx().unwrap();
+
+                     convo_client.new_estimate_transaction_fee(tx_cc_signed, None)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     match http_response {
+                         HttpResponseType::TransactionFeeEstimate(response_md, data) => {
+                             assert!(data.okay);
+                             assert!(data.estimated_len > 0);
+                             assert_eq!(data.fee_rates, fee_estimator::FeeRateEstimate::minimum());
+                             assert_eq!(data.estimated_fees, data.fee_rates.fees_for_len(data.estimated_len));
+                             assert!(data.execution_cost.as_ref().unwrap().runtime > 0);
+                             true
+                         },
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
+    /// Submit a contract-call from privk2 to the server's mempool at the chain tip.
+    fn submit_pending_tx(peer_server: &mut TestPeer) -> StacksTransaction {
+        let privk2 = StacksPrivateKey::from_hex("94c319327cc5cd04da7147d32d836eb2e4c44f4db39aa5ede7314a761183d0c701").unwrap();
+        let privk1 = StacksPrivateKey::from_hex("9f1f85a512a96a244e4c0d762788500687feb97481639572e3bffbd6860e6ab001").unwrap();
+        let addr1 = StacksAddress::from_public_keys(C32_ADDRESS_VERSION_TESTNET_SINGLESIG, &AddressHashMode::SerializeP2PKH, 1, &vec![StacksPublicKey::from_private(&privk1)]).unwrap();
+
+        let mut tx_cc = StacksTransaction::new(TransactionVersion::Testnet,
+                                               TransactionAuth::from_p2pkh(&privk2).unwrap(),
+                                               TransactionPayload::new_contract_call(addr1.clone(), "hello-world", "add-unit", vec![]).unwrap());
+
+        tx_cc.chain_id = 0x80000000;
+        tx_cc.auth.set_origin_nonce(0);
+        tx_cc.set_fee_rate(10);
+
+        let mut tx_signer = StacksTransactionSigner::new(&tx_cc);
+        tx_signer.sign_origin(&privk2).unwrap();
+        let tx_cc_signed = tx_signer.get_tx().unwrap();
+
+        let sortdb = peer_server.sortdb.take().unwrap();
+        let tip = peer_server.chainstate().get_stacks_chain_tip(&sortdb).unwrap().unwrap();
+        peer_server.sortdb = Some(sortdb);
+
+        peer_server.mempool.as_mut().unwrap().submit_raw(&tip.burn_header_hash, &tip.anchored_block_hash, tx_cc_signed.serialize_to_vec()).unwrap();
+        tx_cc_signed
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rpc_get_mempool_txids() {
+        let pending_tx_cell = RefCell::new(None);
+
+        test_rpc("test_rpc_get_mempool_txids", 40270, 40271, 50270, 50271,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     let tx = submit_pending_tx(peer_server);
+                     *pending_tx_cell.borrow_mut() = Some(tx);
+                     convo_client.new_getmempooltxids(0)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     let tx = (*pending_tx_cell.borrow()).clone().unwrap();
+                     match http_response {
+                         HttpResponseType::MemPoolTxids(response_md, data) => {
+                             assert_eq!(data.page, 0);
+                             assert_eq!(data.total, 1);
+                             assert_eq!(data.txids, vec![tx.txid().to_hex()]);
+                             true
+                         },
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rpc_get_mempool_transaction() {
+        let pending_tx_cell = RefCell::new(None);
+
+        test_rpc("test_rpc_get_mempool_transaction", 40280, 40281, 50280, 50281,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     let tx = submit_pending_tx(peer_server);
+                     let txid = tx.txid();
+                     *pending_tx_cell.borrow_mut() = Some(tx);
+                     convo_client.new_getmempooltransaction(txid)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     let tx = (*pending_tx_cell.borrow()).clone().unwrap();
+                     match http_response {
+                         HttpResponseType::MemPoolTransaction(response_md, data) => {
+                             assert_eq!(data.txid, tx.txid().to_hex());
+                             assert_eq!(data.tx, format!("0x{}", to_hex(&tx.serialize_to_vec())));
+                             assert_eq!(data.origin_address, tx.origin_address().to_string());
+                             assert_eq!(data.origin_nonce, 0);
+                             assert_eq!(data.fee_rate, 10);
+                             assert_eq!(data.len, tx.serialize_to_vec().len() as u64);
+                             true
+                         },
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rpc_get_mempool_transaction_missing() {
+        test_rpc("test_rpc_get_mempool_transaction_missing", 40290, 40291, 50290, 50291,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     convo_client.new_getmempooltransaction(Txid([0x11; 32]))
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     match http_response {
+                         HttpResponseType::NotFound(_, _) => true,
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rpc_get_mempool_transactions_for_address() {
+        let pending_tx_cell = RefCell::new(None);
+
+        test_rpc("test_rpc_get_mempool_transactions_for_address", 40300, 40301, 50300, 50301,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     let tx = submit_pending_tx(peer_server);
+                     let origin_address = tx.origin_address();
+                     *pending_tx_cell.borrow_mut() = Some(tx);
+                     convo_client.new_getmempooltransactions_for_address(origin_address)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     let tx = (*pending_tx_cell.borrow()).clone().unwrap();
+                     match http_response {
+                         HttpResponseType::MemPoolTransactions(response_md, data) => {
+                             assert_eq!(data.len(), 1);
+                             assert_eq!(data[0].txid, tx.txid().to_hex());
+                             assert_eq!(data[0].origin_address, tx.origin_address().to_string());
+                             true
+                         },
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rpc_get_account_nonces() {
+        let pending_tx_cell = RefCell::new(None);
+
+        test_rpc("test_rpc_get_account_nonces", 40310, 40311, 50310, 50311,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     let tx = submit_pending_tx(peer_server);
+                     let origin = tx.origin_address().to_account_principal();
+                     *pending_tx_cell.borrow_mut() = Some(tx);
+                     convo_client.new_getaccountnonces(origin, None)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     let tx = (*pending_tx_cell.borrow()).clone().unwrap();
+                     match http_response {
+                         HttpResponseType::GetAccountNonces(response_md, data) => {
+                             assert_eq!(data.confirmed_nonce, 0);
+                             assert_eq!(data.possible_next_nonce, 1);
+                             assert_eq!(data.pending_txids, vec![tx.txid().to_hex()]);
+                             assert_eq!(data.missing_nonces.len(), 0);
+                             true
+                         },
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rpc_eval_read_only() {
+        test_rpc("test_rpc_eval_read_only", 40320, 40321, 50320, 50321,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     convo_client.new_evalreadonly(StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(), "hello-world".try_into().unwrap(),
+                                                   StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap().to_account_principal(),
+                                                   "(+ 1 (get units (unwrap-panic (map-get? unit-map ((account 'ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R))))))".to_string(), None)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     match http_response {
+                         HttpResponseType::EvalReadOnly(response_md, data) => {
+                             assert!(data.okay);
+                             assert_eq!(Value::try_deserialize_hex_untyped(&data.result.clone().unwrap()).unwrap(), Value::Int(124));
+                             assert!(data.cause.is_none());
+                             true
+                         },
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rpc_eval_read_only_write_attempted() {
+        test_rpc("test_rpc_eval_read_only_write_attempted", 40330, 40331, 50330, 50331,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     convo_client.new_evalreadonly(StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(), "hello-world".try_into().unwrap(),
+                                                   StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap().to_account_principal(),
+                                                   "(begin (var-get bar) (add-unit))".to_string(), None)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     match http_response {
+                         HttpResponseType::EvalReadOnly(response_md, data) => {
+                             assert!(!data.okay);
+                             assert!(data.cause.clone().unwrap().find("WriteAttemptedInReadOnly").is_some());
+                             assert!(data.result.is_none());
+                             true
+                         },
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rpc_eval_read_only_type_error() {
+        test_rpc("test_rpc_eval_read_only_type_error", 40440, 40441, 50440, 50441,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     convo_client.new_evalreadonly(StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(), "hello-world".try_into().unwrap(),
+                                                   StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap().to_account_principal(),
+                                                   "(+ (var-get bar) u1)".to_string(), None)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     match http_response {
+                         HttpResponseType::EvalReadOnly(response_md, data) => {
+                             assert!(!data.okay);
+                             assert!(data.cause.clone().unwrap().find("TypeError").is_some());
+                             assert!(data.result.is_none());
+                             true
+                         },
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rpc_get_data_var() {
+        test_rpc("test_rpc_get_data_var", 40340, 40341, 50340, 50341,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     convo_client.new_getdatavar(StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(), "hello-world".try_into().unwrap(),
+                                                 "bar".try_into().unwrap(), None, true)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     match http_response {
+                         HttpResponseType::GetDataVar(response_md, data) => {
+                             assert_eq!(Value::try_deserialize_hex_untyped(&data.data).unwrap(), Value::Int(0));
+                             assert!(data.marf_proof.is_some());
+                             true
+                         },
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rpc_get_data_var_missing() {
+        test_rpc("test_rpc_get_data_var_missing", 40350, 40351, 50350, 50351,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     convo_client.new_getdatavar(StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(), "hello-world".try_into().unwrap(),
+                                                 "does-not-exist".try_into().unwrap(), None, true)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     match http_response {
+                         HttpResponseType::NotFound(_, _) => true,
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rpc_get_map_keys() {
+        test_rpc("test_rpc_get_map_keys", 40360, 40361, 50360, 50361,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     convo_client.new_getmapkeys(StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(), "hello-world".try_into().unwrap(),
+                                                 "unit-map".try_into().unwrap(), None, None)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     match http_response {
+                         HttpResponseType::GetMapKeys(response_md, data) => {
+                             let key = Value::from(TupleData::from_data(vec![
+                                 ("account".into(), StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap().to_account_principal().into())]).unwrap());
+                             assert_eq!(data.keys, vec![format!("0x{}", ClaritySerializable::serialize(&key))]);
+                             assert!(data.next.is_none());
+                             true
+                         },
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rpc_get_ft_balance() {
+        test_rpc("test_rpc_get_ft_balance", 40370, 40371, 50370, 50371,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     convo_client.new_getftbalance(StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(), "hello-world".try_into().unwrap(),
+                                                   "stackaroos".try_into().unwrap(),
+                                                   StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap().to_account_principal(), None, true)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     match http_response {
+                         HttpResponseType::GetFungibleTokenBalance(response_md, data) => {
+                             assert_eq!(data.balance, format!("0x{}", to_hex(&(100 as u128).to_be_bytes())));
+                             assert!(data.marf_proof.is_some());
+                             true
+                         },
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rpc_get_ft_supply() {
+        test_rpc("test_rpc_get_ft_supply", 40380, 40381, 50380, 50381,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     convo_client.new_getftsupply(StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(), "hello-world".try_into().unwrap(),
+                                                  "stackaroos".try_into().unwrap(), None, true)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     match http_response {
+                         HttpResponseType::GetFungibleTokenSupply(response_md, data) => {
+                             assert_eq!(data.total_supply, Some(format!("0x{}", to_hex(&(1000000 as u128).to_be_bytes()))));
+                             assert_eq!(data.circulating_supply, Some(format!("0x{}", to_hex(&(100 as u128).to_be_bytes()))));
+                             assert!(data.marf_proof.is_some());
+                             true
+                         },
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rpc_get_nft_owner() {
+        test_rpc("test_rpc_get_nft_owner", 40390, 40391, 50390, 50391,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     convo_client.new_getnftowner(StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(), "hello-world".try_into().unwrap(),
+                                                  "names".try_into().unwrap(), Value::Int(1), None, true)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     match http_response {
+                         HttpResponseType::GetNonFungibleTokenOwner(response_md, data) => {
+                             assert_eq!(data.owner, "ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R");
+                             assert!(data.marf_proof.is_some());
+                             true
+                         },
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rpc_get_nft_owner_missing() {
+        test_rpc("test_rpc_get_nft_owner_missing", 40400, 40401, 50400, 50401,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     convo_client.new_getnftowner(StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap(), "hello-world".try_into().unwrap(),
+                                                  "names".try_into().unwrap(), Value::Int(2), None, true)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     match http_response {
+                         HttpResponseType::NotFound(_, _) => true,
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+
+    #[test]
+    #[ignore]
+    fn test_rpc_get_asset_holdings() {
+        test_rpc("test_rpc_get_asset_holdings", 40410, 40411, 50410, 50411,
+                 |ref mut peer_client, ref mut convo_client, ref mut peer_server, ref mut convo_server| {
+                     convo_client.new_getassetholdings(StacksAddress::from_string("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap().to_account_principal(), None, None, false)
+                 },
+                 |ref http_request, ref http_response, ref mut peer_client, ref mut peer_server| {
+                     let req_md = http_request.metadata().clone();
+                     match http_response {
+                         HttpResponseType::GetAssetHoldings(response_md, data) => {
+                             assert_eq!(data.fungible_tokens.len(), 1);
+                             assert_eq!(data.fungible_tokens[0].asset_identifier, "ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world::stackaroos");
+                             assert_eq!(data.fungible_tokens[0].balance, format!("0x{}", to_hex(&(100 as u128).to_be_bytes())));
+                             assert!(data.fungible_tokens[0].marf_proof.is_none());
+
+                             assert_eq!(data.non_fungible_tokens.len(), 1);
+                             assert_eq!(data.non_fungible_tokens[0].asset_identifier, "ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world::names");
+                             assert_eq!(data.non_fungible_tokens[0].value, format!("0x{}", ClaritySerializable::serialize(&Value::Int(1))));
+                             assert!(data.next.is_none());
+                             true
+                         },
+                         _ => {
+                             error!("Invalid response; {:?}", &http_response);
+                             false
+                         }
+                     }
+                 });
+    }
+}
@@ -39,6 +39,7 @@ use net::poll::*;
 use net::rpc::*;
 use net::http::*;
 use net::p2p::PeerMap;
+use net::websocket::EventStreamMessage;
 
 use chainstate::burn::db::sortdb::SortitionDB;
 use chainstate::stacks::db::StacksChainState;
@@ -71,6 +72,9 @@ pub struct HttpPeer {
 
     // connection options
     pub connection_opts: ConnectionOptions,
+
+    // events to push to event stream (WebSocket) clients, if anyone is producing them
+    event_stream: Option<Receiver<EventStreamMessage>>,
 }
 
 impl HttpPeer {
@@ -85,7 +89,8 @@ impl HttpPeer {
             http_server_handle: server_handle,
 
             burnchain: burnchain,
-            connection_opts: conn_opts
+            connection_opts: conn_opts,
+            event_stream: None
         }
     }
 
@@ -93,6 +98,11 @@ impl HttpPeer {
         self.http_server_handle = h;
     }
 
+    /// Set the channel from which we'll receive events to push to event stream clients
+    pub fn set_event_stream(&mut self, event_stream: Receiver<EventStreamMessage>) -> () {
+        self.event_stream = Some(event_stream);
+    }
+
     /// Is there a HTTP conversation open to this data_url that is not in progress?
     pub fn find_free_conversation(&self, data_url: &UrlString) -> Option<usize> {
         for (event_id, convo) in self.peers.iter() {
@@ -500,6 +510,53 @@ impl HttpPeer {
         close
     }
     
+    /// Push new events out to event stream clients, and ping the idle ones.
+    /// Since sockets are edge-triggered, write to them directly instead of waiting for them to
+    /// become ready.
+    /// Return the list of conversation event IDs to close (i.e. they're broken, or too slow)
+    fn process_event_stream(&mut self, chainstate: &mut StacksChainState) -> Vec<usize> {
+        let mut msgs = vec![];
+        let mut hung_up = false;
+        if let Some(ref event_stream) = self.event_stream {
+            loop {
+                match event_stream.try_recv() {
+                    Ok(msg) => msgs.push(msg),
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        debug!("Event stream producer hung up");
+                        hung_up = true;
+                        break;
+                    }
+                }
+            }
+        }
+        if hung_up {
+            self.event_stream = None;
+        }
+
+        let mut close = vec![];
+        for (event_id, convo) in self.peers.iter_mut() {
+            if !convo.is_websocket() {
+                continue;
+            }
+
+            if let Err(e) = convo.push_event_stream(&msgs, self.connection_opts.websocket_ping_interval) {
+                info!("Dropping event stream client {:?}: {:?}", convo, &e);
+                close.push(*event_id);
+                continue;
+            }
+
+            if let Some(client_sock) = self.sockets.get_mut(event_id) {
+                if let Err(e) = HttpPeer::saturate_http_socket(client_sock, convo, chainstate) {
+                    info!("Broken event stream connection {:?}: {:?}", convo, &e);
+                    close.push(*event_id);
+                }
+            }
+        }
+
+        close
+    }
+
     /// Update HTTP server state
     /// -- accept new connections
     /// -- send data on ready sockets
@@ -528,6 +585,13 @@ impl HttpPeer {
             self.deregister_http(network_state, error_event);
         }
 
+        // push out events to event stream clients
+        let close_events = self.process_event_stream(chainstate);
+        for close_event in close_events {
+            debug!("Close event stream connection on event {}", close_event);
+            self.deregister_http(network_state, close_event);
+        }
+
         // move conversations along
         let close_events = self.flush_conversations(chainstate);
         for close_event in close_events {
@@ -0,0 +1,875 @@
+/*
+ copyright: (c) 2013-2020 by Blockstack PBC, a public benefit corporation.
+
+ This file is part of Blockstack.
+
+ Blockstack is free software. You may redistribute or modify
+ it under the terms of the GNU General Public License as published by
+ the Free Software Foundation, either version 3 of the License or
+ (at your option) any later version.
+
+ Blockstack is distributed in the hope that it will be useful,
+ but WITHOUT ANY WARRANTY, including without the implied warranty of
+ MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ GNU General Public License for more details.
+
+ You should have received a copy of the GNU General Public License
+ along with Blockstack. If not, see <http://www.gnu.org/licenses/>.
+*/
+
+
+//! Minimal RFC 6455 WebSocket support for the RPC server's event stream.
+//!
+//! A HTTP client that sends `GET /v2/events` with the usual upgrade headers is switched over to
+//! WebSocket framing, and from then on receives a text frame for each event it subscribed to.
+//! Events are produced elsewhere in the node (i.e. by the event dispatcher) and handed to the
+//! HTTP server through an `EventStreamPublisher`.
+
+use std::io;
+use std::io::{Read, Write};
+use std::fmt;
+use std::collections::HashSet;
+
+use std::sync::mpsc::SyncSender;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::sync_channel;
+use std::sync::mpsc::TrySendError;
+
+use sha1::{Sha1, Digest};
+use base64;
+
+use net::Error as net_error;
+
+use vm::types::{Value, PrincipalData, QualifiedContractIdentifier};
+
+use serde_json;
+
+/// Appended to the client's Sec-WebSocket-Key to compute Sec-WebSocket-Accept (RFC 6455, 1.3)
+pub const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// The only WebSocket protocol version we speak
+pub const WEBSOCKET_VERSION: &str = "13";
+
+pub const WEBSOCKET_OPCODE_CONTINUATION: u8 = 0x0;
+pub const WEBSOCKET_OPCODE_TEXT: u8 = 0x1;
+pub const WEBSOCKET_OPCODE_BINARY: u8 = 0x2;
+pub const WEBSOCKET_OPCODE_CLOSE: u8 = 0x8;
+pub const WEBSOCKET_OPCODE_PING: u8 = 0x9;
+pub const WEBSOCKET_OPCODE_PONG: u8 = 0xA;
+
+/// Normal closure status code
+pub const WEBSOCKET_CLOSE_NORMAL: u16 = 1000;
+
+/// Largest message we'll accept from a client.  Clients only ever send us subscription requests,
+/// so this is generous.
+pub const MAX_WEBSOCKET_MESSAGE_LEN: usize = 65536;
+
+/// Most topics a single client can be subscribed to at once.
+pub const MAX_WEBSOCKET_TOPICS: usize = 64;
+
+/// How many event stream messages can be queued up for the HTTP server before new ones get
+/// dropped.
+pub const EVENT_STREAM_CHANNEL_CAPACITY: usize = 1024;
+
+/// Calculate the Sec-WebSocket-Accept header value for a client's Sec-WebSocket-Key.
+pub fn make_accept_key(key: &str) -> String {
+    let mut hasher = Sha1::new();
+    hasher.input(key.trim().as_bytes());
+    hasher.input(WEBSOCKET_GUID.as_bytes());
+    base64::encode(hasher.result().as_slice())
+}
+
+/// A single WebSocket frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebSocketFrame {
+    pub fin: bool,
+    pub opcode: u8,
+    pub payload: Vec<u8>
+}
+
+impl WebSocketFrame {
+    pub fn text(text: &str) -> WebSocketFrame {
+        WebSocketFrame { fin: true, opcode: WEBSOCKET_OPCODE_TEXT, payload: text.as_bytes().to_vec() }
+    }
+
+    pub fn ping(payload: Vec<u8>) -> WebSocketFrame {
+        WebSocketFrame { fin: true, opcode: WEBSOCKET_OPCODE_PING, payload: payload }
+    }
+
+    pub fn pong(payload: Vec<u8>) -> WebSocketFrame {
+        WebSocketFrame { fin: true, opcode: WEBSOCKET_OPCODE_PONG, payload: payload }
+    }
+
+    pub fn close(code: u16) -> WebSocketFrame {
+        WebSocketFrame { fin: true, opcode: WEBSOCKET_OPCODE_CLOSE, payload: code.to_be_bytes().to_vec() }
+    }
+
+    /// Encode this frame.  Servers send unmasked frames; clients must pass a masking key.
+    pub fn encode(&self, mask: Option<[u8; 4]>) -> Vec<u8> {
+        let mut ret = Vec::with_capacity(self.payload.len() + 14);
+        let fin_bit = if self.fin { 0x80 } else { 0x00 };
+        ret.push(fin_bit | (self.opcode & 0x0f));
+
+        let mask_bit = if mask.is_some() { 0x80 } else { 0x00 };
+        let len = self.payload.len();
+        if len < 126 {
+            ret.push(mask_bit | (len as u8));
+        }
+        else if len <= (u16::max_value() as usize) {
+            ret.push(mask_bit | 126);
+            ret.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        else {
+            ret.push(mask_bit | 127);
+            ret.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+
+        match mask {
+            Some(key) => {
+                ret.extend_from_slice(&key);
+                for (i, b) in self.payload.iter().enumerate() {
+                    ret.push(b ^ key[i % 4]);
+                }
+            },
+            None => {
+                ret.extend_from_slice(&self.payload);
+            }
+        }
+        ret
+    }
+
+    /// Try to decode a frame from the front of `buf`.
+    /// Returns Ok(None) if `buf` does not yet hold a whole frame, and otherwise the frame and the
+    /// number of bytes it took up.  Frames from clients must be masked (RFC 6455, 5.1).
+    pub fn decode(buf: &[u8], require_mask: bool) -> Result<Option<(WebSocketFrame, usize)>, net_error> {
+        if buf.len() < 2 {
+            return Ok(None);
+        }
+
+        let fin = buf[0] & 0x80 != 0;
+        if buf[0] & 0x70 != 0 {
+            return Err(net_error::DeserializeError("WebSocket frame uses unsupported extension bits".to_string()));
+        }
+        let opcode = buf[0] & 0x0f;
+        let masked = buf[1] & 0x80 != 0;
+        if require_mask && !masked {
+            return Err(net_error::DeserializeError("WebSocket frame from client is not masked".to_string()));
+        }
+
+        let mut ptr = 2;
+        let len = match buf[1] & 0x7f {
+            126 => {
+                if buf.len() < ptr + 2 {
+                    return Ok(None);
+                }
+                let mut len_bytes = [0u8; 2];
+                len_bytes.copy_from_slice(&buf[ptr..ptr+2]);
+                ptr += 2;
+                u16::from_be_bytes(len_bytes) as u64
+            },
+            127 => {
+                if buf.len() < ptr + 8 {
+                    return Ok(None);
+                }
+                let mut len_bytes = [0u8; 8];
+                len_bytes.copy_from_slice(&buf[ptr..ptr+8]);
+                ptr += 8;
+                u64::from_be_bytes(len_bytes)
+            },
+            l => l as u64
+        };
+
+        if len > MAX_WEBSOCKET_MESSAGE_LEN as u64 {
+            return Err(net_error::OverflowError(format!("WebSocket frame is too big ({} bytes)", len)));
+        }
+        let len = len as usize;
+
+        if opcode & 0x8 != 0 && (len > 125 || !fin) {
+            return Err(net_error::DeserializeError("Invalid WebSocket control frame".to_string()));
+        }
+
+        let mask = if masked {
+            if buf.len() < ptr + 4 {
+                return Ok(None);
+            }
+            let mut key = [0u8; 4];
+            key.copy_from_slice(&buf[ptr..ptr+4]);
+            ptr += 4;
+            Some(key)
+        }
+        else {
+            None
+        };
+
+        if buf.len() < ptr + len {
+            return Ok(None);
+        }
+
+        let payload = match mask {
+            Some(key) => buf[ptr..ptr+len].iter().enumerate().map(|(i, b)| b ^ key[i % 4]).collect(),
+            None => buf[ptr..ptr+len].to_vec()
+        };
+
+        Ok(Some((WebSocketFrame { fin: fin, opcode: opcode, payload: payload }, ptr + len)))
+    }
+}
+
+/// Things a client can subscribe to on the event stream
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum EventStreamTopic {
+    /// Every newly-processed anchored block
+    Blocks,
+    /// Every newly-received microblock stream segment
+    Microblocks,
+    /// Every transaction newly admitted to the mempool
+    MemPool,
+    /// Smart contract (i.e. `print`) events emitted by this contract
+    ContractEvents(QualifiedContractIdentifier),
+    /// Smart contract events sent by, or mentioning, this principal
+    PrincipalEvents(PrincipalData)
+}
+
+impl EventStreamTopic {
+    /// Parse a topic.  Topics are `blocks`, `microblocks`, `mempool`,
+    /// `contract:{contract identifier}`, and `principal:{principal}`.
+    pub fn parse(topic: &str) -> Option<EventStreamTopic> {
+        match topic {
+            "blocks" => Some(EventStreamTopic::Blocks),
+            "microblocks" => Some(EventStreamTopic::Microblocks),
+            "mempool" => Some(EventStreamTopic::MemPool),
+            _ => {
+                if topic.starts_with("contract:") {
+                    QualifiedContractIdentifier::parse(&topic["contract:".len()..]).ok()
+                        .map(EventStreamTopic::ContractEvents)
+                }
+                else if topic.starts_with("principal:") {
+                    PrincipalData::parse(&topic["principal:".len()..]).ok()
+                        .map(EventStreamTopic::PrincipalEvents)
+                }
+                else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for EventStreamTopic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EventStreamTopic::Blocks => write!(f, "blocks"),
+            EventStreamTopic::Microblocks => write!(f, "microblocks"),
+            EventStreamTopic::MemPool => write!(f, "mempool"),
+            EventStreamTopic::ContractEvents(contract_id) => write!(f, "contract:{}", contract_id),
+            EventStreamTopic::PrincipalEvents(principal) => write!(f, "principal:{}", principal)
+        }
+    }
+}
+
+/// An event to be pushed to subscribed WebSocket clients.  Payloads are the same JSON documents
+/// the event dispatcher POSTs to its observers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventStreamMessage {
+    NewBlock(serde_json::Value),
+    NewMicroblocks(serde_json::Value),
+    NewMempoolTxs(serde_json::Value),
+    ContractEvent {
+        contract_identifier: QualifiedContractIdentifier,
+        principals: Vec<PrincipalData>,
+        payload: serde_json::Value
+    }
+}
+
+/// Find every principal mentioned in a Clarity value
+fn collect_principals(value: &Value, principals: &mut Vec<PrincipalData>) -> () {
+    match value {
+        Value::Principal(ref p) => {
+            if !principals.contains(p) {
+                principals.push(p.clone());
+            }
+        },
+        Value::List(ref list_data) => {
+            for item in list_data.data.iter() {
+                collect_principals(item, principals);
+            }
+        },
+        Value::Tuple(ref tuple_data) => {
+            for (_, item) in tuple_data.data_map.iter() {
+                collect_principals(item, principals);
+            }
+        },
+        Value::Optional(ref opt_data) => {
+            if let Some(ref item) = opt_data.data {
+                collect_principals(item, principals);
+            }
+        },
+        Value::Response(ref res_data) => {
+            collect_principals(&res_data.data, principals);
+        },
+        _ => {}
+    }
+}
+
+impl EventStreamMessage {
+    /// Make a smart contract event message.  The event is of interest to the transaction's
+    /// sender, the contract itself, and any principal that appears in the event's value.
+    pub fn contract_event(contract_identifier: QualifiedContractIdentifier, sender: PrincipalData, value: &Value, payload: serde_json::Value) -> EventStreamMessage {
+        let mut principals = vec![sender, PrincipalData::Contract(contract_identifier.clone())];
+        collect_principals(value, &mut principals);
+        EventStreamMessage::ContractEvent {
+            contract_identifier,
+            principals,
+            payload
+        }
+    }
+
+    /// Name of the event, as sent to clients
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            EventStreamMessage::NewBlock(_) => "new_block",
+            EventStreamMessage::NewMicroblocks(_) => "new_microblocks",
+            EventStreamMessage::NewMempoolTxs(_) => "new_mempool_tx",
+            EventStreamMessage::ContractEvent { .. } => "contract_event"
+        }
+    }
+
+    /// Would a client subscribed to these topics want this message?
+    pub fn matches(&self, topics: &HashSet<EventStreamTopic>) -> bool {
+        match self {
+            EventStreamMessage::NewBlock(_) => topics.contains(&EventStreamTopic::Blocks),
+            EventStreamMessage::NewMicroblocks(_) => topics.contains(&EventStreamTopic::Microblocks),
+            EventStreamMessage::NewMempoolTxs(_) => topics.contains(&EventStreamTopic::MemPool),
+            EventStreamMessage::ContractEvent { ref contract_identifier, ref principals, .. } => {
+                topics.iter().any(|topic| match topic {
+                    EventStreamTopic::ContractEvents(ref contract_id) => contract_id == contract_identifier,
+                    EventStreamTopic::PrincipalEvents(ref principal) => principals.contains(principal),
+                    _ => false
+                })
+            }
+        }
+    }
+
+    /// The text sent to clients
+    pub fn to_json(&self) -> serde_json::Value {
+        let payload = match self {
+            EventStreamMessage::NewBlock(ref payload) => payload,
+            EventStreamMessage::NewMicroblocks(ref payload) => payload,
+            EventStreamMessage::NewMempoolTxs(ref payload) => payload,
+            EventStreamMessage::ContractEvent { ref payload, .. } => payload
+        };
+        json!({
+            "event": self.event_name(),
+            "payload": payload
+        })
+    }
+}
+
+/// Sending half of the event stream.  Held by whatever produces events.
+#[derive(Debug, Clone)]
+pub struct EventStreamPublisher {
+    sender: SyncSender<EventStreamMessage>
+}
+
+impl EventStreamPublisher {
+    /// Hand a message off to the HTTP server.  Never blocks -- if the HTTP server has fallen
+    /// behind, the message is dropped.
+    pub fn publish(&self, msg: EventStreamMessage) -> () {
+        match self.sender.try_send(msg) {
+            Ok(_) => {},
+            Err(TrySendError::Full(msg)) => {
+                warn!("Event stream is full; dropping {} message", msg.event_name());
+            },
+            Err(TrySendError::Disconnected(_)) => {
+                debug!("Event stream receiver hung up");
+            }
+        }
+    }
+}
+
+/// Make the channel that connects an event producer to the HTTP server
+pub fn event_stream_channel(capacity: usize) -> (EventStreamPublisher, Receiver<EventStreamMessage>) {
+    let (sender, receiver) = sync_channel(capacity);
+    (EventStreamPublisher { sender }, receiver)
+}
+
+/// Subscription requests clients send as text frames, e.g.
+/// `{"subscribe": ["blocks"], "unsubscribe": ["mempool"]}`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventStreamRequest {
+    #[serde(default)]
+    pub subscribe: Vec<String>,
+    #[serde(default)]
+    pub unsubscribe: Vec<String>
+}
+
+/// Parse a list of topic strings
+pub fn parse_topics(topic_strs: &Vec<String>) -> Result<Vec<EventStreamTopic>, String> {
+    let mut topics = vec![];
+    for topic_str in topic_strs.iter() {
+        match EventStreamTopic::parse(topic_str) {
+            Some(topic) => topics.push(topic),
+            None => {
+                return Err(format!("Unrecognized topic '{}'", topic_str));
+            }
+        }
+    }
+    Ok(topics)
+}
+
+/// State of a HTTP conversation that has been upgraded to a WebSocket
+#[derive(Debug)]
+pub struct WebSocketState {
+    topics: HashSet<EventStreamTopic>,
+    recv_buf: Vec<u8>,
+    fragments: Option<(u8, Vec<u8>)>,
+    send_buf: Vec<u8>,
+    max_send_buf: usize,
+    last_ping: u64,
+    closed: bool
+}
+
+impl WebSocketState {
+    pub fn new(topics: Vec<EventStreamTopic>, max_send_buf: usize, now: u64) -> WebSocketState {
+        WebSocketState {
+            topics: topics.into_iter().collect(),
+            recv_buf: vec![],
+            fragments: None,
+            send_buf: vec![],
+            max_send_buf: max_send_buf,
+            last_ping: now,
+            closed: false
+        }
+    }
+
+    pub fn get_topics(&self) -> &HashSet<EventStreamTopic> {
+        &self.topics
+    }
+
+    /// Has either side closed the WebSocket?
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Has everything we queued been written out?
+    pub fn is_flushed(&self) -> bool {
+        self.send_buf.len() == 0
+    }
+
+    /// Queue a frame to send.  Fails if the client isn't keeping up with us.  This goes for
+    /// control frames too, so a client can't grow the buffer by sending pings and never reading
+    /// the pongs.
+    fn queue_frame(&mut self, frame: &WebSocketFrame) -> Result<(), net_error> {
+        let bytes = frame.encode(None);
+        if self.send_buf.len() + bytes.len() > self.max_send_buf {
+            debug!("WebSocket send buffer is full ({} bytes pending)", self.send_buf.len());
+            return Err(net_error::OutboxOverflow);
+        }
+        self.send_buf.extend_from_slice(&bytes);
+        Ok(())
+    }
+
+    fn queue_json(&mut self, value: &serde_json::Value) -> Result<(), net_error> {
+        let text = serde_json::to_string(value)
+            .map_err(|e| net_error::SerializeError(format!("Failed to serialize event as JSON: {:?}", &e)))?;
+        self.queue_frame(&WebSocketFrame::text(&text))
+    }
+
+    /// Queue up an event, if the client is subscribed to it.
+    pub fn push_message(&mut self, msg: &EventStreamMessage) -> Result<(), net_error> {
+        if self.closed || !msg.matches(&self.topics) {
+            return Ok(());
+        }
+        self.queue_json(&msg.to_json())
+    }
+
+    /// Queue up a ping if we haven't sent one in a while, so idle clients don't get
+    /// disconnected for inactivity.
+    pub fn try_ping(&mut self, now: u64, interval: u64) -> Result<(), net_error> {
+        if self.closed || self.last_ping + interval > now {
+            return Ok(());
+        }
+        self.last_ping = now;
+        self.queue_frame(&WebSocketFrame::ping(vec![]))
+    }
+
+    /// Act on a subscription request from the client, and reply with the set of topics it is now
+    /// subscribed to (or with an error).
+    fn handle_text(&mut self, text: &[u8]) -> Result<(), net_error> {
+        let request : EventStreamRequest = match serde_json::from_slice(text) {
+            Ok(req) => req,
+            Err(e) => {
+                return self.queue_json(&json!({ "error": format!("Failed to parse request: {}", e) }));
+            }
+        };
+
+        let subscribe = match parse_topics(&request.subscribe) {
+            Ok(topics) => topics,
+            Err(msg) => {
+                return self.queue_json(&json!({ "error": msg }));
+            }
+        };
+        let unsubscribe = match parse_topics(&request.unsubscribe) {
+            Ok(topics) => topics,
+            Err(msg) => {
+                return self.queue_json(&json!({ "error": msg }));
+            }
+        };
+
+        let mut topics = self.topics.clone();
+        for topic in subscribe.into_iter() {
+            topics.insert(topic);
+        }
+        for topic in unsubscribe.iter() {
+            topics.remove(topic);
+        }
+        if topics.len() > MAX_WEBSOCKET_TOPICS {
+            return self.queue_json(&json!({ "error": format!("Too many topics (at most {} are allowed)", MAX_WEBSOCKET_TOPICS) }));
+        }
+        self.topics = topics;
+
+        let mut topic_strs : Vec<String> = self.topics.iter().map(|t| t.to_string()).collect();
+        topic_strs.sort();
+        self.queue_json(&json!({ "subscribed": topic_strs }))
+    }
+
+    fn handle_frame(&mut self, frame: WebSocketFrame) -> Result<(), net_error> {
+        match frame.opcode {
+            WEBSOCKET_OPCODE_PING => {
+                self.queue_frame(&WebSocketFrame::pong(frame.payload))?;
+            },
+            WEBSOCKET_OPCODE_PONG => {},
+            WEBSOCKET_OPCODE_CLOSE => {
+                if !self.closed {
+                    // echo the close
+                    self.queue_frame(&WebSocketFrame::close(WEBSOCKET_CLOSE_NORMAL))?;
+                    self.closed = true;
+                }
+            },
+            WEBSOCKET_OPCODE_TEXT | WEBSOCKET_OPCODE_BINARY => {
+                if self.fragments.is_some() {
+                    return Err(net_error::DeserializeError("WebSocket data frame interleaved with fragmented message".to_string()));
+                }
+                if frame.fin {
+                    if frame.opcode == WEBSOCKET_OPCODE_TEXT {
+                        self.handle_text(&frame.payload)?;
+                    }
+                    else {
+                        debug!("Ignoring binary WebSocket message");
+                    }
+                }
+                else {
+                    self.fragments = Some((frame.opcode, frame.payload));
+                }
+            },
+            WEBSOCKET_OPCODE_CONTINUATION => {
+                let (opcode, mut payload) = self.fragments.take()
+                    .ok_or(net_error::DeserializeError("WebSocket continuation frame without a message".to_string()))?;
+
+                payload.extend_from_slice(&frame.payload);
+                if payload.len() > MAX_WEBSOCKET_MESSAGE_LEN {
+                    return Err(net_error::OverflowError("WebSocket message is too big".to_string()));
+                }
+
+                if frame.fin {
+                    if opcode == WEBSOCKET_OPCODE_TEXT {
+                        self.handle_text(&payload)?;
+                    }
+                }
+                else {
+                    self.fragments = Some((opcode, payload));
+                }
+            },
+            _ => {
+                return Err(net_error::DeserializeError(format!("Unknown WebSocket opcode {}", frame.opcode)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Decode and act on all the whole frames we have received
+    fn process_frames(&mut self) -> Result<(), net_error> {
+        while !self.closed {
+            match WebSocketFrame::decode(&self.recv_buf, true)? {
+                Some((frame, consumed)) => {
+                    self.recv_buf.drain(0..consumed);
+                    self.handle_frame(frame)?;
+                },
+                None => {
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Read as many bytes as we can from the client, and act on the frames they contain.
+    /// Returns the number of bytes read.
+    pub fn recv<R: Read>(&mut self, fd: &mut R) -> Result<usize, net_error> {
+        let mut total_read = 0;
+        let mut socket_closed = false;
+        loop {
+            let mut buf = [0u8; 4096];
+            let num_read = match fd.read(&mut buf) {
+                Ok(0) => {
+                    socket_closed = true;
+                    break;
+                },
+                Ok(count) => count,
+                Err(e) => {
+                    if e.kind() == io::ErrorKind::WouldBlock || (cfg!(windows) && e.kind() == io::ErrorKind::TimedOut) {
+                        break;
+                    }
+                    else if e.kind() == io::ErrorKind::BrokenPipe || e.kind() == io::ErrorKind::ConnectionReset {
+                        socket_closed = true;
+                        break;
+                    }
+                    else {
+                        debug!("Failed to read from WebSocket: {:?}", &e);
+                        return Err(net_error::RecvError(format!("Failed to read: {:?}", &e)));
+                    }
+                }
+            };
+
+            total_read += num_read;
+            self.recv_buf.extend_from_slice(&buf[0..num_read]);
+            self.process_frames()?;
+        }
+
+        if socket_closed && total_read == 0 {
+            return Err(net_error::PermanentlyDrained);
+        }
+        Ok(total_read)
+    }
+
+    /// Write as much queued data as we can to the client.  Returns the number of bytes written.
+    pub fn send<W: Write>(&mut self, fd: &mut W) -> Result<usize, net_error> {
+        let mut total_sent = 0;
+        while self.send_buf.len() > 0 {
+            let num_written = match fd.write(&self.send_buf) {
+                Ok(0) => {
+                    break;
+                },
+                Ok(count) => count,
+                Err(e) => {
+                    if e.kind() == io::ErrorKind::WouldBlock || (cfg!(windows) && e.kind() == io::ErrorKind::TimedOut) {
+                        break;
+                    }
+                    else {
+                        debug!("Failed to write to WebSocket: {:?}", &e);
+                        return Err(net_error::SendError(format!("Failed to send {} bytes", self.send_buf.len())));
+                    }
+                }
+            };
+            self.send_buf.drain(0..num_written);
+            total_sent += num_written;
+        }
+        Ok(total_sent)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use vm::types::*;
+    use chainstate::stacks::StacksAddress;
+    use util::hash::Hash160;
+
+    #[test]
+    fn test_websocket_accept_key() {
+        // example from RFC 6455, section 1.3
+        assert_eq!(make_accept_key("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+    }
+
+    #[test]
+    fn test_websocket_frame_codec() {
+        let frames = vec![
+            WebSocketFrame::text("hello"),
+            WebSocketFrame::text(&"a".repeat(200)),
+            WebSocketFrame::text(&"b".repeat(65536)),
+            WebSocketFrame::ping(vec![1, 2, 3]),
+            WebSocketFrame::pong(vec![]),
+            WebSocketFrame::close(WEBSOCKET_CLOSE_NORMAL),
+        ];
+
+        for frame in frames.iter() {
+            for mask in [None, Some([0x37, 0xfa, 0x21, 0x3d])].iter() {
+                let bytes = frame.encode(*mask);
+                let (decoded, consumed) = WebSocketFrame::decode(&bytes, false).unwrap().unwrap();
+                assert_eq!(decoded, *frame);
+                assert_eq!(consumed, bytes.len());
+
+                // not enough bytes yet
+                for i in 0..bytes.len() {
+                    assert!(WebSocketFrame::decode(&bytes[0..i], false).unwrap().is_none());
+                }
+
+                // clients must mask
+                if mask.is_none() {
+                    assert!(WebSocketFrame::decode(&bytes, true).is_err());
+                }
+            }
+        }
+
+        // masked "Hello" from RFC 6455, section 5.7
+        let bytes = vec![0x81, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f, 0x4d, 0x51, 0x58];
+        let (decoded, consumed) = WebSocketFrame::decode(&bytes, true).unwrap().unwrap();
+        assert_eq!(decoded, WebSocketFrame::text("Hello"));
+        assert_eq!(consumed, bytes.len());
+    }
+
+    #[test]
+    fn test_event_stream_topic_parse() {
+        let contract_id = QualifiedContractIdentifier::parse("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world").unwrap();
+        let principal = PrincipalData::parse("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R").unwrap();
+        let tests = vec![
+            ("blocks", Some(EventStreamTopic::Blocks)),
+            ("microblocks", Some(EventStreamTopic::Microblocks)),
+            ("mempool", Some(EventStreamTopic::MemPool)),
+            ("contract:ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world", Some(EventStreamTopic::ContractEvents(contract_id))),
+            ("principal:ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R", Some(EventStreamTopic::PrincipalEvents(principal))),
+            ("contract:ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R", None),
+            ("principal:nope", None),
+            ("nope", None),
+        ];
+
+        for (topic_str, expected) in tests.into_iter() {
+            let topic = EventStreamTopic::parse(topic_str);
+            assert_eq!(topic, expected);
+            if let Some(topic) = topic {
+                assert_eq!(topic.to_string(), topic_str);
+            }
+        }
+    }
+
+    #[test]
+    fn test_event_stream_message_matches() {
+        let contract_id = QualifiedContractIdentifier::parse("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.hello-world").unwrap();
+        let other_contract_id = QualifiedContractIdentifier::parse("ST2DS4MSWSGJ3W9FBC6BVT0Y92S345HY8N3T6AV7R.goodbye-world").unwrap();
+        let sender = PrincipalData::from(StacksAddress { version: 26, bytes: Hash160([0x11; 20]) });
+        let mentioned = PrincipalData::from(StacksAddress { version: 26, bytes: Hash160([0x22; 20]) });
+        let stranger = PrincipalData::from(StacksAddress { version: 26, bytes: Hash160([0x33; 20]) });
+
+        let value = Value::some(Value::Tuple(TupleData::from_data(vec![
+            ("who".into(), Value::Principal(mentioned.clone()))]).unwrap())).unwrap();
+
+        let msg = EventStreamMessage::contract_event(contract_id.clone(), sender.clone(), &value, json!({}));
+
+        let topic_sets = vec![
+            (vec![EventStreamTopic::ContractEvents(contract_id.clone())], true),
+            (vec![EventStreamTopic::ContractEvents(other_contract_id.clone())], false),
+            (vec![EventStreamTopic::PrincipalEvents(sender.clone())], true),
+            (vec![EventStreamTopic::PrincipalEvents(mentioned.clone())], true),
+            (vec![EventStreamTopic::PrincipalEvents(PrincipalData::Contract(contract_id.clone()))], true),
+            (vec![EventStreamTopic::PrincipalEvents(stranger.clone())], false),
+            (vec![EventStreamTopic::Blocks, EventStreamTopic::MemPool], false),
+        ];
+
+        for (topics, expected) in topic_sets.into_iter() {
+            let topics : HashSet<EventStreamTopic> = topics.into_iter().collect();
+            assert_eq!(msg.matches(&topics), expected);
+        }
+
+        let topics : HashSet<EventStreamTopic> = vec![EventStreamTopic::Blocks].into_iter().collect();
+        assert!(EventStreamMessage::NewBlock(json!({})).matches(&topics));
+        assert!(!EventStreamMessage::NewMicroblocks(json!({})).matches(&topics));
+        assert!(!EventStreamMessage::NewMempoolTxs(json!([])).matches(&topics));
+    }
+
+    #[test]
+    fn test_websocket_state() {
+        let mut state = WebSocketState::new(vec![EventStreamTopic::Blocks], 1024, 0);
+
+        // subscribe to the mempool, and drop blocks
+        let request = WebSocketFrame::text("{\"subscribe\":[\"mempool\"],\"unsubscribe\":[\"blocks\"]}").encode(Some([1, 2, 3, 4]));
+        let ping = WebSocketFrame::ping(vec![5, 6]).encode(Some([4, 3, 2, 1]));
+        let mut input = request.clone();
+        input.extend_from_slice(&ping);
+
+        let nr = state.recv(&mut &input[..]).unwrap();
+        assert_eq!(nr, input.len());
+
+        let mut output = vec![];
+        state.send(&mut output).unwrap();
+        assert!(state.is_flushed());
+
+        let (reply, consumed) = WebSocketFrame::decode(&output, false).unwrap().unwrap();
+        assert_eq!(reply, WebSocketFrame::text("{\"subscribed\":[\"mempool\"]}"));
+        let (pong, _) = WebSocketFrame::decode(&output[consumed..], false).unwrap().unwrap();
+        assert_eq!(pong, WebSocketFrame::pong(vec![5, 6]));
+
+        // only get mempool events
+        state.push_message(&EventStreamMessage::NewBlock(json!({"block_height": 1}))).unwrap();
+        state.push_message(&EventStreamMessage::NewMempoolTxs(json!(["0x00"]))).unwrap();
+
+        let mut output = vec![];
+        state.send(&mut output).unwrap();
+        let (event, consumed) = WebSocketFrame::decode(&output, false).unwrap().unwrap();
+        assert_eq!(event, WebSocketFrame::text("{\"event\":\"new_mempool_tx\",\"payload\":[\"0x00\"]}"));
+        assert_eq!(consumed, output.len());
+
+        // bad topic
+        let request = WebSocketFrame::text("{\"subscribe\":[\"nope\"]}").encode(Some([1, 2, 3, 4]));
+        state.recv(&mut &request[..]).unwrap();
+        let mut output = vec![];
+        state.send(&mut output).unwrap();
+        let (reply, _) = WebSocketFrame::decode(&output, false).unwrap().unwrap();
+        assert_eq!(reply, WebSocketFrame::text("{\"error\":\"Unrecognized topic 'nope'\"}"));
+
+        // subscriptions are capped
+        let too_many : Vec<String> = (0..MAX_WEBSOCKET_TOPICS).map(|i| format!("contract:S1G2081040G2081040G2081040G208105NK8PE5.c{}", i)).collect();
+        let request = WebSocketFrame::text(&serde_json::to_string(&json!({ "subscribe": too_many })).unwrap()).encode(Some([1, 2, 3, 4]));
+        state.recv(&mut &request[..]).unwrap();
+        let mut output = vec![];
+        state.send(&mut output).unwrap();
+        let (reply, _) = WebSocketFrame::decode(&output, false).unwrap().unwrap();
+        assert_eq!(reply, WebSocketFrame::text(&format!("{{\"error\":\"Too many topics (at most {} are allowed)\"}}", MAX_WEBSOCKET_TOPICS)));
+        assert_eq!(state.get_topics().len(), 1);
+
+        // pings only go out on the interval
+        state.try_ping(5, 10).unwrap();
+        assert!(state.is_flushed());
+        state.try_ping(10, 10).unwrap();
+        let mut output = vec![];
+        state.send(&mut output).unwrap();
+        let (ping, _) = WebSocketFrame::decode(&output, false).unwrap().unwrap();
+        assert_eq!(ping, WebSocketFrame::ping(vec![]));
+
+        // slow clients get cut off
+        let big = EventStreamMessage::NewMempoolTxs(json!(["0x".to_string() + &"00".repeat(1024)]));
+        match state.push_message(&big) {
+            Err(net_error::OutboxOverflow) => {},
+            x => panic!("Expected OutboxOverflow, got {:?}", &x)
+        }
+
+        // so do clients that send pings without reading the pongs
+        let mut pinged_state = WebSocketState::new(vec![], 64, 0);
+        let ping = WebSocketFrame::ping(vec![7; 16]).encode(Some([1, 2, 3, 4]));
+        let mut result = Ok(0);
+        for _ in 0..10 {
+            result = pinged_state.recv(&mut &ping[..]);
+            if result.is_err() {
+                break;
+            }
+        }
+        match result {
+            Err(net_error::OutboxOverflow) => {},
+            x => panic!("Expected OutboxOverflow, got {:?}", &x)
+        }
+
+        // client closes
+        let close = WebSocketFrame::close(WEBSOCKET_CLOSE_NORMAL).encode(Some([9, 9, 9, 9]));
+        state.recv(&mut &close[..]).unwrap();
+        assert!(state.is_closed());
+        let mut output = vec![];
+        state.send(&mut output).unwrap();
+        let (reply, _) = WebSocketFrame::decode(&output, false).unwrap().unwrap();
+        assert_eq!(reply, WebSocketFrame::close(WEBSOCKET_CLOSE_NORMAL));
+
+        // EOF
+        let empty : Vec<u8> = vec![];
+        match state.recv(&mut &empty[..]) {
+            Err(net_error::PermanentlyDrained) => {},
+            x => panic!("Expected PermanentlyDrained, got {:?}", &x)
+        }
+    }
+}
@@ -72,6 +72,14 @@ impl <'a> AnalysisDatabase <'a> {
         self.store.prepare_for_contract_metadata(contract_identifier, Sha512Trunc256Sum([0; 32]));
     }
 
+    // used by analysis-only stores (e.g., the language server) which never publish the
+    //   contracts they analyze, but still need the contract -> contract hash key that
+    //   the analysis is stored against.
+    pub fn insert_contract_hash(&mut self, contract_identifier: &QualifiedContractIdentifier, contract_content: &str) {
+        use util::hash::Sha512Trunc256Sum;
+        self.store.prepare_for_contract_metadata(contract_identifier, Sha512Trunc256Sum::from_data(contract_content.as_bytes()));
+    }
+
     pub fn has_contract(&mut self, contract_identifier: &QualifiedContractIdentifier) -> bool {
         self.store.has_metadata_entry(contract_identifier, AnalysisDatabase::storage_key())
     }
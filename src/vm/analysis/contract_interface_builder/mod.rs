@@ -22,6 +22,7 @@ pub fn build_contract_interface(contract_analysis: &ContractAnalysis) -> Contrac
         type_map: _,
         cost_track: _,
         contract_interface: _,
+        warnings: _,
     } = contract_analysis;
 
     contract_interface.functions.append(
@@ -83,6 +84,8 @@ pub enum ContractInterfaceAtomType {
     bool,
     principal,
     buffer { length: u32 },
+    string_ascii { length: u32 },
+    string_utf8 { length: u32 },
     tuple(Vec<ContractInterfaceTupleEntryType>),
     optional(Box<ContractInterfaceAtomType>),
     response { ok: Box<ContractInterfaceAtomType>, error: Box<ContractInterfaceAtomType> },
@@ -133,6 +136,8 @@ impl ContractInterfaceAtomType {
             PrincipalType => ContractInterfaceAtomType::principal,
             TraitReferenceType(_) => ContractInterfaceAtomType::trait_reference,
             BufferType(len) => ContractInterfaceAtomType::buffer { length: len.into() },
+            StringASCIIType(len) => ContractInterfaceAtomType::string_ascii { length: len.into() },
+            StringUTF8Type(len) => ContractInterfaceAtomType::string_utf8 { length: len.into() },
             TupleType(sig) => Self::from_tuple_type(sig),
             ListType(list_data) => {
                 let (type_f, length) = list_data.clone().destruct();
@@ -35,6 +35,8 @@ pub enum CheckErrors {
 
     NoSuperType(TypeSignature, TypeSignature),
     InvalidTypeDescription,
+    InvalidCharactersDetected,
+    InvalidUTF8Encoding,
     UnknownTypeName(String),
 
     // union type mismatch
@@ -69,6 +71,8 @@ pub enum CheckErrors {
     BadTransferFTArguments,
     BadTransferNFTArguments,
     BadMintFTArguments,
+    BadBurnFTArguments,
+    BadBurnNFTArguments,
 
     // tuples
     BadTupleFieldName,
@@ -283,6 +287,8 @@ impl DiagnosableError for CheckErrors {
             CheckErrors::CostBalanceExceeded(a, b) => format!("contract execution cost exceeded budget: {:?} > {:?}", a, b),
             CheckErrors::MemoryBalanceExceeded(a, b) => format!("contract execution cost exceeded memory budget: {:?} > {:?}", a, b),
             CheckErrors::InvalidTypeDescription => "supplied type description is invalid".into(),
+            CheckErrors::InvalidCharactersDetected => "string-ascii values may only contain printable ASCII characters, tabs and line breaks".into(),
+            CheckErrors::InvalidUTF8Encoding => "string-utf8 value is not valid UTF-8".into(),
             CheckErrors::EmptyTuplesNotAllowed => "tuple types may not be empty".into(),
             CheckErrors::BadSyntaxExpectedListOfPairs => "bad syntax: function expects a list of pairs to bind names, e.g., ((name-0 a) (name-1 b) ...)".into(),
             CheckErrors::UnknownTypeName(name) => format!("failed to parse type: '{}'", name),
@@ -317,6 +323,8 @@ impl DiagnosableError for CheckErrors {
             CheckErrors::BadTransferFTArguments => format!("transfer expects an int amount, from principal, to principal"),
             CheckErrors::BadTransferNFTArguments => format!("transfer expects an asset, from principal, to principal"),
             CheckErrors::BadMintFTArguments => format!("mint expects an int amount and from principal"),
+            CheckErrors::BadBurnFTArguments => format!("burn expects an int amount and from principal"),
+            CheckErrors::BadBurnNFTArguments => format!("burn expects an asset and from principal"),
             CheckErrors::BadMapName => format!("invalid map name"),
             CheckErrors::NoSuchMap(map_name) => format!("use of unresolved map '{}'", map_name),
             CheckErrors::DefineFunctionBadSignature => format!("invalid function definition"),
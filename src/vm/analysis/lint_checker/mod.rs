@@ -0,0 +1,371 @@
+use std::collections::HashSet;
+
+use vm::analysis::types::{ContractAnalysis, AnalysisPass};
+use vm::analysis::AnalysisDatabase;
+use vm::analysis::errors::CheckResult;
+use vm::analysis::type_checker::contexts::TypeMap;
+use vm::diagnostic::{Diagnostic, DiagnosableError};
+use vm::representations::{SymbolicExpression, ClarityName};
+use vm::representations::SymbolicExpressionType::{Atom, List};
+use vm::types::{TypeSignature, FunctionType, FixedFunction};
+use vm::functions::define::DefineFunctionsParsed;
+use vm::is_reserved;
+
+#[cfg(test)]
+mod tests;
+
+/// Non-fatal problems found in a contract which passed analysis.  These never cause a contract
+///  to be rejected: the lint pass is not part of `run_analysis`, and is only invoked by tooling.
+#[derive(Debug, PartialEq)]
+pub enum LintWarnings {
+    UnusedPrivateFunction(String),
+    UnusedConstant(String),
+    UnusedMap(String),
+    ShadowedBinding(String),
+    UnreachableBranch(bool),
+    UncheckedResponse(String),
+    UncheckedMapInsert(String),
+    UnwrapPanicOnInput(String),
+    PublicFunctionNeverErrs(String),
+}
+
+impl DiagnosableError for LintWarnings {
+    fn message(&self) -> String {
+        match &self {
+            LintWarnings::UnusedPrivateFunction(name) => format!("private function '{}' is never called", name),
+            LintWarnings::UnusedConstant(name) => format!("constant '{}' is never used", name),
+            LintWarnings::UnusedMap(name) => format!("map '{}' is never used", name),
+            LintWarnings::ShadowedBinding(name) => format!("binding '{}' shadows a contract-level definition or built-in", name),
+            LintWarnings::UnreachableBranch(condition) => format!("condition is always {}, so the {} branch is unreachable",
+                                                                  condition, if *condition { "else" } else { "then" }),
+            LintWarnings::UncheckedResponse(function) => format!("response of contract call to '{}' is ignored", function),
+            LintWarnings::UncheckedMapInsert(map) => format!("result of inserting into map '{}' is ignored", map),
+            LintWarnings::UnwrapPanicOnInput(name) => format!("unwrap-panic on user-supplied input '{}' aborts the transaction without an error code", name),
+            LintWarnings::PublicFunctionNeverErrs(name) => format!("public function '{}' never returns an err, so its writes are always committed", name),
+        }
+    }
+
+    fn suggestion(&self) -> Option<String> {
+        match &self {
+            LintWarnings::ShadowedBinding(_) => Some(format!("rename the binding")),
+            LintWarnings::UncheckedResponse(_) => Some(format!("check the response with try!, unwrap! or asserts!, or make the call the last expression")),
+            LintWarnings::UncheckedMapInsert(_) => Some(format!("check the result with asserts!, or use map-set to overwrite existing entries")),
+            LintWarnings::UnwrapPanicOnInput(_) => Some(format!("use unwrap! or unwrap-err! with an error code")),
+            _ => None
+        }
+    }
+}
+
+pub struct LintChecker <'a> {
+    type_map: Option<&'a TypeMap>,
+    warnings: Vec<Diagnostic>,
+}
+
+impl <'a> AnalysisPass for LintChecker <'a> {
+
+    fn run_pass(contract_analysis: &mut ContractAnalysis, _analysis_db: &mut AnalysisDatabase) -> CheckResult<()> {
+        let warnings = {
+            let mut command = LintChecker::new(contract_analysis.type_map.as_ref());
+            command.run(contract_analysis);
+            command.warnings
+        };
+        contract_analysis.warnings.extend(warnings);
+        Ok(())
+    }
+}
+
+impl <'a> LintChecker <'a> {
+
+    fn new(type_map: Option<&'a TypeMap>) -> LintChecker<'a> {
+        Self {
+            type_map,
+            warnings: vec![],
+        }
+    }
+
+    fn warn(&mut self, warning: LintWarnings, expr: &SymbolicExpression) {
+        let mut diagnostic = Diagnostic::warn(&warning);
+        diagnostic.spans = vec![expr.span.clone()];
+        self.warnings.push(diagnostic);
+    }
+
+    pub fn run(&mut self, contract_analysis: &ContractAnalysis) {
+        // every name defined at the contract level, which let and match bindings may shadow
+        //  (the type checker rejects most such bindings, but not all of them)
+        let mut definitions = HashSet::new();
+        let mut used = HashSet::new();
+
+        for exp in contract_analysis.expressions.iter() {
+            collect_definition(exp, &mut definitions, &mut used);
+        }
+
+        for exp in contract_analysis.expressions.iter() {
+            // the contract passed the type checker, so any define is well-formed
+            let define = match DefineFunctionsParsed::try_parse(exp) {
+                Ok(Some(define)) => define,
+                _ => continue
+            };
+            match define {
+                DefineFunctionsParsed::PrivateFunction { signature, body } => {
+                    if let Some(name) = function_name(signature) {
+                        if !used.contains(name) {
+                            self.warn(LintWarnings::UnusedPrivateFunction(name.to_string()), exp);
+                        }
+                    }
+                    self.check_function_body(signature, body, false, &definitions);
+                },
+                DefineFunctionsParsed::ReadOnlyFunction { signature, body } => {
+                    self.check_function_body(signature, body, true, &definitions);
+                },
+                DefineFunctionsParsed::PublicFunction { signature, body } => {
+                    if let Some(name) = function_name(signature) {
+                        if never_errs(contract_analysis, name) {
+                            self.warn(LintWarnings::PublicFunctionNeverErrs(name.to_string()), exp);
+                        }
+                    }
+                    self.check_function_body(signature, body, true, &definitions);
+                },
+                DefineFunctionsParsed::Constant { name, .. } => {
+                    if !used.contains(name) {
+                        self.warn(LintWarnings::UnusedConstant(name.to_string()), exp);
+                    }
+                },
+                DefineFunctionsParsed::Map { name, .. } => {
+                    if !used.contains(name) {
+                        self.warn(LintWarnings::UnusedMap(name.to_string()), exp);
+                    }
+                },
+                _ => {}
+            }
+        }
+    }
+
+    // Arguments of public and read-only functions are supplied by the caller, and so are
+    //  treated as user-controlled input.
+    fn check_function_body(&mut self, signature: &[SymbolicExpression], body: &SymbolicExpression,
+                           args_are_input: bool, definitions: &HashSet<ClarityName>) {
+        let mut bindings = vec![];
+        for arg in signature.iter().skip(1) {
+            if let Some(name) = arg.match_list().and_then(|pair| pair.get(0)).and_then(|name| name.match_atom()) {
+                bindings.push((name.clone(), args_are_input));
+            }
+        }
+        self.check_expression(body, &mut bindings, definitions);
+    }
+
+    // `bindings` is the stack of local names in scope, and whether each holds user input.
+    fn check_expression(&mut self, expr: &SymbolicExpression, bindings: &mut Vec<(ClarityName, bool)>,
+                        definitions: &HashSet<ClarityName>) {
+        let list = match expr.expr {
+            List(ref list) => list,
+            _ => return
+        };
+        let function = match list.get(0).and_then(|head| head.match_atom()) {
+            Some(function) => function.as_str(),
+            None => return self.check_all(list, bindings, definitions)
+        };
+
+        match function {
+            "let" if list.len() >= 3 && list[1].match_list().is_some() => {
+                let scope_start = bindings.len();
+                let mut new_bindings = vec![];
+                for binding in list[1].match_list().unwrap().iter() {
+                    let pair = match binding.match_list() {
+                        Some(pair) if pair.len() == 2 => pair,
+                        _ => continue
+                    };
+                    // binding values are evaluated in the enclosing scope
+                    self.check_expression(&pair[1], bindings, definitions);
+                    if let Some(name) = pair[0].match_atom() {
+                        self.check_binding_name(name, binding, definitions);
+                        new_bindings.push((name.clone(), references_input(&pair[1], bindings)));
+                    }
+                }
+                bindings.extend(new_bindings);
+                self.check_all(&list[2..], bindings, definitions);
+                bindings.truncate(scope_start);
+            },
+            "match" if list.len() == 5 || list.len() == 6 => {
+                self.check_expression(&list[1], bindings, definitions);
+                let is_input = references_input(&list[1], bindings);
+                // (match input some-name if-some if-none) or (match input ok-name if-ok err-name if-err)
+                let branches: &[(usize, Option<usize>)] = if list.len() == 5 {
+                    &[(3, Some(2)), (4, None)]
+                } else {
+                    &[(3, Some(2)), (5, Some(4))]
+                };
+                for (branch, name_index) in branches.iter() {
+                    let scope_start = bindings.len();
+                    if let Some(name_index) = name_index {
+                        if let Some(name) = list[*name_index].match_atom() {
+                            self.check_binding_name(name, &list[*name_index], definitions);
+                            bindings.push((name.clone(), is_input));
+                        }
+                    }
+                    self.check_expression(&list[*branch], bindings, definitions);
+                    bindings.truncate(scope_start);
+                }
+            },
+            "begin" => {
+                if list.len() > 2 {
+                    for statement in list[1..list.len() - 1].iter() {
+                        self.check_statement(statement);
+                    }
+                }
+                self.check_all(&list[1..], bindings, definitions);
+            },
+            "if" if list.len() == 4 => {
+                if let Some(condition) = list[1].match_atom() {
+                    match condition.as_str() {
+                        "true" => self.warn(LintWarnings::UnreachableBranch(true), &list[3]),
+                        "false" => self.warn(LintWarnings::UnreachableBranch(false), &list[2]),
+                        _ => {}
+                    }
+                }
+                self.check_all(&list[1..], bindings, definitions);
+            },
+            "unwrap-panic" | "unwrap-err-panic" => {
+                if let Some(input) = list.get(1).and_then(|arg| input_reference(arg, bindings)) {
+                    self.warn(LintWarnings::UnwrapPanicOnInput(input.to_string()), expr);
+                }
+                self.check_all(&list[1..], bindings, definitions);
+            },
+            _ => self.check_all(&list[1..], bindings, definitions)
+        }
+    }
+
+    fn check_all(&mut self, exprs: &[SymbolicExpression], bindings: &mut Vec<(ClarityName, bool)>,
+                 definitions: &HashSet<ClarityName>) {
+        for expr in exprs.iter() {
+            self.check_expression(expr, bindings, definitions);
+        }
+    }
+
+    fn check_binding_name(&mut self, name: &ClarityName, binding: &SymbolicExpression, definitions: &HashSet<ClarityName>) {
+        if definitions.contains(name) || is_reserved(name) {
+            self.warn(LintWarnings::ShadowedBinding(name.to_string()), binding);
+        }
+    }
+
+    // A statement of a `begin` other than its last, whose value is discarded.
+    fn check_statement(&mut self, statement: &SymbolicExpression) {
+        let list = match statement.match_list() {
+            Some(list) => list,
+            None => return
+        };
+        match list.get(0).and_then(|head| head.match_atom()).map(|name| name.as_str()) {
+            Some("map-insert") => {
+                if let Some(map) = list.get(1).and_then(|map| map.match_atom()) {
+                    self.warn(LintWarnings::UncheckedMapInsert(map.to_string()), statement);
+                }
+            },
+            Some("contract-call?") => {
+                let returns_response = match self.type_map.and_then(|type_map| type_map.get_type(statement)) {
+                    Some(TypeSignature::ResponseType(_)) => true,
+                    _ => false
+                };
+                if returns_response {
+                    let function = list.get(2).and_then(|name| name.match_atom())
+                        .map(|name| name.to_string())
+                        .unwrap_or_default();
+                    self.warn(LintWarnings::UncheckedResponse(function), statement);
+                }
+            },
+            _ => {}
+        }
+    }
+}
+
+fn function_name(signature: &[SymbolicExpression]) -> Option<&ClarityName> {
+    signature.get(0).and_then(|name| name.match_atom())
+}
+
+// Whether the type checker inferred the public function `name` can only return `ok`.
+fn never_errs(contract_analysis: &ContractAnalysis, name: &str) -> bool {
+    match contract_analysis.get_public_function_type(name) {
+        Some(FunctionType::Fixed(FixedFunction { returns: TypeSignature::ResponseType(ref response), .. })) =>
+            response.1 == TypeSignature::NoType,
+        _ => false
+    }
+}
+
+fn collect_definition(exp: &SymbolicExpression, definitions: &mut HashSet<ClarityName>, used: &mut HashSet<ClarityName>) {
+    let define = match DefineFunctionsParsed::try_parse(exp) {
+        Ok(Some(define)) => define,
+        Ok(None) => return collect_uses(exp, used),
+        Err(_) => return
+    };
+    match define {
+        DefineFunctionsParsed::PrivateFunction { signature, body } => {
+            if let Some(name) = function_name(signature) {
+                definitions.insert(name.clone());
+            }
+            collect_uses(body, used);
+        },
+        DefineFunctionsParsed::ReadOnlyFunction { signature, body } |
+        DefineFunctionsParsed::PublicFunction { signature, body } => {
+            if let Some(name) = function_name(signature) {
+                definitions.insert(name.clone());
+            }
+            collect_uses(body, used);
+        },
+        DefineFunctionsParsed::Constant { name, value } => {
+            definitions.insert(name.clone());
+            collect_uses(value, used);
+        },
+        DefineFunctionsParsed::Map { name, .. } => {
+            definitions.insert(name.clone());
+        },
+        DefineFunctionsParsed::PersistedVariable { name, initial, .. } => {
+            definitions.insert(name.clone());
+            collect_uses(initial, used);
+        },
+        DefineFunctionsParsed::BoundedFungibleToken { name, max_supply } => {
+            definitions.insert(name.clone());
+            collect_uses(max_supply, used);
+        },
+        DefineFunctionsParsed::UnboundedFungibleToken { name } |
+        DefineFunctionsParsed::NonFungibleToken { name, .. } |
+        DefineFunctionsParsed::Trait { name, .. } |
+        DefineFunctionsParsed::UseTrait { name, .. } => {
+            definitions.insert(name.clone());
+        },
+        DefineFunctionsParsed::ImplTrait { .. } => {}
+    }
+}
+
+// Collect every name referenced in `exp`.  This is deliberately coarse: a local binding which
+//  happens to share a definition's name counts as a use of it, so unused definitions are never
+//  over-reported.
+fn collect_uses(exp: &SymbolicExpression, used: &mut HashSet<ClarityName>) {
+    match exp.expr {
+        Atom(ref name) => {
+            if !is_reserved(name) {
+                used.insert(name.clone());
+            }
+        },
+        List(ref list) => {
+            for child in list.iter() {
+                collect_uses(child, used);
+            }
+        },
+        _ => {}
+    }
+}
+
+// The first name bound to user input which `exp` references, if any.
+fn input_reference<'b>(exp: &SymbolicExpression, bindings: &'b [(ClarityName, bool)]) -> Option<&'b ClarityName> {
+    match exp.expr {
+        Atom(ref name) => {
+            bindings.iter().rev()
+                .find(|(bound, _)| bound == name)
+                .and_then(|(bound, is_input)| if *is_input { Some(bound) } else { None })
+        },
+        List(ref list) => list.iter().find_map(|child| input_reference(child, bindings)),
+        _ => None
+    }
+}
+
+fn references_input(exp: &SymbolicExpression, bindings: &[(ClarityName, bool)]) -> bool {
+    input_reference(exp, bindings).is_some()
+}
@@ -0,0 +1,85 @@
+use vm::ast::parse;
+use vm::database::MemoryBackingStore;
+use vm::analysis::{type_check, AnalysisDatabase, AnalysisPass, ContractAnalysis};
+use vm::analysis::lint_checker::{LintChecker, LintWarnings};
+use vm::diagnostic::{DiagnosableError, Level};
+use vm::types::QualifiedContractIdentifier;
+
+fn lint(db: &mut AnalysisDatabase, name: &str, contract: &str) -> Vec<(u32, u32, String)> {
+    let contract_identifier = QualifiedContractIdentifier::local(name).unwrap();
+    let mut expressions = parse(&contract_identifier, contract).unwrap();
+    let mut contract_analysis: ContractAnalysis = db.execute(|db| {
+        db.test_insert_contract_hash(&contract_identifier);
+        let mut analysis = type_check(&contract_identifier, &mut expressions, db, true)?;
+        LintChecker::run_pass(&mut analysis, db).map(|_| analysis)
+    }).unwrap();
+
+    contract_analysis.warnings.drain(..)
+        .map(|warning| {
+            assert_eq!(warning.level, Level::Warning);
+            (warning.spans[0].start_line, warning.spans[0].start_column, warning.message)
+        })
+        .collect()
+}
+
+fn message(warning: LintWarnings) -> String {
+    warning.message()
+}
+
+#[test]
+fn test_lint_warnings() {
+    let contract = "(define-constant owner tx-sender)
+(define-constant unused-const u1)
+(define-map balances ((who principal)) ((amount uint)))
+(define-map unused-map ((who principal)) ((amount uint)))
+(define-read-only (get-balance (who principal))
+  (default-to u0 (get amount (map-get? balances (tuple (who who))))))
+(define-private (unused-helper) u1)
+(define-public (deposit (amount uint))
+  (begin
+    (map-insert balances (tuple (who tx-sender)) (tuple (amount amount)))
+    (ok (is-eq tx-sender owner))))
+(define-public (pick (value (optional uint)))
+  (let ((chosen (unwrap-panic value)))
+    (if true (ok chosen) (err u1))))
+(define-public (safe (value (optional uint)))
+  (let ((get-balance u1))
+    (ok (unwrap! value (err get-balance)))))";
+
+    let mut marf = MemoryBackingStore::new();
+    let mut db = marf.as_analysis_db();
+
+    assert_eq!(lint(&mut db, "linted", contract), vec![
+        (2, 1, message(LintWarnings::UnusedConstant("unused-const".into()))),
+        (4, 1, message(LintWarnings::UnusedMap("unused-map".into()))),
+        (7, 1, message(LintWarnings::UnusedPrivateFunction("unused-helper".into()))),
+        (8, 1, message(LintWarnings::PublicFunctionNeverErrs("deposit".into()))),
+        (10, 5, message(LintWarnings::UncheckedMapInsert("balances".into()))),
+        (13, 17, message(LintWarnings::UnwrapPanicOnInput("value".into()))),
+        (14, 26, message(LintWarnings::UnreachableBranch(true))),
+        (16, 9, message(LintWarnings::ShadowedBinding("get-balance".into()))),
+    ]);
+}
+
+#[test]
+fn test_lint_responses_and_input() {
+    let callee = "(define-public (ping) (if (is-eq tx-sender tx-sender) (ok u1) (err u2)))";
+    let caller = "(define-public (ping-twice)
+  (begin
+    (contract-call? .callee ping)
+    (contract-call? .callee ping)))
+(define-private (helper (x (optional uint)))
+  (unwrap-panic x))
+(define-read-only (checked (x (optional uint)))
+  (match x inner (unwrap-panic (some inner)) (helper none)))";
+
+    let mut marf = MemoryBackingStore::new();
+    let mut db = marf.as_analysis_db();
+
+    assert_eq!(lint(&mut db, "callee", callee), vec![]);
+    // only the discarded response is reported, and private function arguments aren't input
+    assert_eq!(lint(&mut db, "caller", caller), vec![
+        (3, 5, message(LintWarnings::UncheckedResponse("ping".into()))),
+        (8, 18, message(LintWarnings::UnwrapPanicOnInput("inner".into()))),
+    ]);
+}
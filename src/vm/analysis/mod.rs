@@ -3,6 +3,7 @@ pub mod errors;
 pub mod trait_checker;
 pub mod type_checker;
 pub mod read_only_checker;
+pub mod lint_checker;
 pub mod analysis_db;
 pub mod contract_interface_builder;
 
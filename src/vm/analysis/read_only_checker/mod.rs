@@ -1,6 +1,6 @@
 use vm::representations::{SymbolicExpressionType, SymbolicExpression, ClarityName};
 use vm::representations::SymbolicExpressionType::{AtomValue, Atom, List, LiteralValue, TraitReference, Field};
-use vm::types::{TypeSignature, TupleTypeSignature, Value, PrincipalData, parse_name_type_pairs};
+use vm::types::{TypeSignature, TupleTypeSignature, Value, PrincipalData, QualifiedContractIdentifier, parse_name_type_pairs};
 use vm::functions::NativeFunctions;
 use vm::functions::define::DefineFunctionsParsed;
 use vm::functions::tuples;
@@ -54,6 +54,39 @@ impl <'a, 'b> ReadOnlyChecker <'a, 'b> {
         Ok(())
     }
 
+    /// Checks that a standalone expression, evaluated in the context of an already-deployed
+    /// contract, does not write to the chain state.  The contract's own functions are looked up
+    /// in its stored analysis, and only its read-only functions are treated as read-only.
+    pub fn check_read_only_expression(db: &'a mut AnalysisDatabase<'b>, contract_identifier: &QualifiedContractIdentifier,
+                                      expr: &SymbolicExpression) -> CheckResult<()> {
+        let contract_analysis = db.load_contract(contract_identifier)
+            .ok_or_else(|| CheckErrors::NoSuchContract(contract_identifier.to_string()))?;
+
+        let mut checker = ReadOnlyChecker::new(db);
+        for f_name in contract_analysis.private_function_types.keys()
+            .chain(contract_analysis.public_function_types.keys()) {
+            checker.defined_functions.insert(f_name.clone(), false);
+        }
+        for f_name in contract_analysis.read_only_function_types.keys() {
+            checker.defined_functions.insert(f_name.clone(), true);
+        }
+
+        let mut result = checker.check_read_only(expr)
+            .and_then(|is_read_only| {
+                if is_read_only {
+                    Ok(())
+                } else {
+                    Err(CheckErrors::WriteAttemptedInReadOnly.into())
+                }
+            });
+        if let Err(ref mut error) = result {
+            if !error.has_expression() {
+                error.set_expression(expr);
+            }
+        }
+        result
+    }
+
     fn check_define_function(&mut self, signature: &[SymbolicExpression], body: &SymbolicExpression) -> CheckResult<(ClarityName, bool)> {
         let function_name = signature.get(0)
             .ok_or(CheckErrors::DefineFunctionBadSignature)?
@@ -165,7 +198,7 @@ impl <'a, 'b> ReadOnlyChecker <'a, 'b> {
             Unwrap | UnwrapErr | Match | IsErr | IsSome | TryRet |
             ToUInt | ToInt | Append | Concat | AsMaxLen |
             ContractOf |
-            ListCons | GetBlockInfo | TupleGet | Len | Print | AsContract | Begin | FetchVar | GetStxBalance | GetTokenBalance | GetAssetOwner => {
+            ListCons | GetBlockInfo | TupleGet | Len | Print | AsContract | Begin | FetchVar | GetStxBalance | GetTokenBalance | GetAssetOwner | GetTokenSupply => {
                 self.check_all_read_only(args)
             },
             AtBlock => {
@@ -192,7 +225,7 @@ impl <'a, 'b> ReadOnlyChecker <'a, 'b> {
                 res
             },
             StxTransfer | StxBurn |
-            SetEntry | DeleteEntry | InsertEntry | SetVar | MintAsset | MintToken | TransferAsset | TransferToken => {
+            SetEntry | DeleteEntry | InsertEntry | SetVar | MintAsset | MintToken | TransferAsset | TransferToken | BurnAsset | BurnToken => {
                 Ok(false)
             },
             Let => {
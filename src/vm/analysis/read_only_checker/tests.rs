@@ -1,6 +1,7 @@
 use vm::ast::parse;
 use vm::database::{MemoryBackingStore};
 use vm::analysis::{type_check, mem_type_check, CheckError, CheckErrors, AnalysisDatabase};
+use vm::analysis::read_only_checker::ReadOnlyChecker;
 use vm::types::QualifiedContractIdentifier;
 
 #[test]
@@ -174,3 +175,57 @@ fn test_contract_call_read_only_violations() {
     db.execute(|db| type_check(&contract_ok_caller_id, &mut ok_caller, db, false)).unwrap();
 
 }
+
+#[test]
+fn test_read_only_expression_violations() {
+    let contract = 
+        "(define-map tokens ((account principal)) ((balance int)))
+         (define-read-only (get-token-balance)
+            (get balance (map-get? tokens (tuple (account tx-sender))) ))
+         (define-public (mint)
+            (begin
+              (map-set tokens (tuple (account tx-sender))
+                                              (tuple (balance 10)))
+              (ok 1)))";
+
+    let contract_id = QualifiedContractIdentifier::local("contract").unwrap();
+    let missing_id = QualifiedContractIdentifier::local("missing").unwrap();
+    let mut contract = parse(&contract_id, contract).unwrap();
+
+    let mut marf = MemoryBackingStore::new();
+    let mut db = marf.as_analysis_db();
+    db.execute(|db| {
+        db.test_insert_contract_hash(&contract_id);
+        type_check(&contract_id, &mut contract, db, true)
+    }).unwrap();
+
+    let ok_exprs = [
+        "(+ 1 (default-to 0 (get-token-balance)))",
+        "(map-get? tokens (tuple (account tx-sender)))",
+    ];
+    let bad_exprs = [
+        "(mint)",
+        "(map-set tokens (tuple (account tx-sender)) (tuple (balance 1)))",
+        "(begin (get-token-balance) (map-delete tokens (tuple (account tx-sender))))",
+    ];
+
+    // the checker only reads the analysis database, like the RPC handler that uses it
+    db.begin();
+
+    for expr in ok_exprs.iter() {
+        let parsed = parse(&contract_id, expr).unwrap();
+        ReadOnlyChecker::check_read_only_expression(&mut db, &contract_id, &parsed[0]).unwrap();
+    }
+
+    for expr in bad_exprs.iter() {
+        let parsed = parse(&contract_id, expr).unwrap();
+        let err = ReadOnlyChecker::check_read_only_expression(&mut db, &contract_id, &parsed[0]).unwrap_err();
+        assert_eq!(err.err, CheckErrors::WriteAttemptedInReadOnly);
+    }
+
+    let parsed = parse(&contract_id, "(get-token-balance)").unwrap();
+    let err = ReadOnlyChecker::check_read_only_expression(&mut db, &missing_id, &parsed[0]).unwrap_err();
+    assert_eq!(err.err, CheckErrors::NoSuchContract(missing_id.to_string()));
+
+    db.roll_back();
+}
@@ -7,7 +7,7 @@ use std::collections::{HashMap, BTreeMap};
 use vm::representations::{SymbolicExpression, ClarityName, depth_traverse};
 use vm::representations::SymbolicExpressionType::{AtomValue, Atom, List, LiteralValue, TraitReference, Field};
 use vm::types::{TypeSignature, TupleTypeSignature, FunctionArg,
-                FunctionType, FixedFunction, parse_name_type_pairs, Value, PrincipalData};
+                FunctionType, FixedFunction, parse_name_type_pairs, Value, PrincipalData, QualifiedContractIdentifier};
 use vm::types::signatures::{FunctionSignature};
 use vm::functions::NativeFunctions;
 use vm::functions::define::DefineFunctionsParsed;
@@ -211,6 +211,23 @@ impl <'a, 'b> TypeChecker <'a, 'b> {
         }
     }
 
+    /// Type checks a standalone expression, evaluated in the context of an already-deployed
+    /// contract.  The contract's definitions are looked up in its stored analysis.  Returns the
+    /// expression's type, along with the cost tracker, so that the caller can keep charging
+    /// the same budget.
+    pub fn type_check_expression(db: &'a mut AnalysisDatabase<'b>, contract_identifier: &QualifiedContractIdentifier,
+                                 expr: &SymbolicExpression, cost_track: LimitedCostTracker) -> (TypeResult, LimitedCostTracker) {
+        let contract_analysis = match db.load_contract(contract_identifier) {
+            Some(contract_analysis) => contract_analysis,
+            None => return (Err(CheckErrors::NoSuchContract(contract_identifier.to_string()).into()), cost_track)
+        };
+
+        let mut checker = TypeChecker::new(db, cost_track);
+        checker.contract_context = ContractContext::from_contract_analysis(&contract_analysis);
+        let result = checker.type_check(expr, &TypingContext::new());
+        (result, checker.cost_track)
+    }
+
     fn into_contract_analysis(self, contract_analysis: &mut ContractAnalysis) -> LimitedCostTracker {
         self.contract_context.into_contract_analysis(contract_analysis);
         contract_analysis.type_map = Some(self.type_map);
@@ -84,6 +84,65 @@ pub fn check_special_mint_token(checker: &mut TypeChecker, args: &[SymbolicExpre
                   TypeSignature::UIntType))).into())
 }
 
+pub fn check_special_burn_asset(checker: &mut TypeChecker, args: &[SymbolicExpression], context: &TypingContext) -> TypeResult {
+    check_argument_count(3, args)?;
+
+    let asset_name = args[0].match_atom()
+        .ok_or(CheckErrors::BadTokenName)?;
+
+    let expected_owner_type: TypeSignature = TypeSignature::PrincipalType;
+    let expected_asset_type = checker.contract_context.get_nft_type(asset_name)
+        .ok_or(CheckErrors::NoSuchNFT(asset_name.to_string()))?
+        .clone();
+
+    runtime_cost!(cost_functions::ANALYSIS_TYPE_LOOKUP, checker, expected_asset_type.type_size()?)?;
+
+    checker.type_check_expects(&args[1], context, &expected_asset_type)?;
+    checker.type_check_expects(&args[2], context, &expected_owner_type)?;
+
+    Ok(TypeSignature::ResponseType(
+        Box::new((TypeSignature::BoolType,
+                  TypeSignature::UIntType))).into())
+}
+
+pub fn check_special_burn_token(checker: &mut TypeChecker, args: &[SymbolicExpression], context: &TypingContext) -> TypeResult {
+    check_argument_count(3, args)?;
+
+    let asset_name = args[0].match_atom()
+        .ok_or(CheckErrors::BadTokenName)?;
+
+    let expected_amount: TypeSignature = TypeSignature::UIntType;
+    let expected_owner_type: TypeSignature = TypeSignature::PrincipalType;
+
+    runtime_cost!(cost_functions::ANALYSIS_TYPE_LOOKUP, checker, 1)?;
+
+    checker.type_check_expects(&args[1], context, &expected_amount)?;
+    checker.type_check_expects(&args[2], context, &expected_owner_type)?;
+
+    if !checker.contract_context.ft_exists(asset_name) {
+        return Err(CheckErrors::NoSuchFT(asset_name.to_string()).into());
+    }
+
+    Ok(TypeSignature::ResponseType(
+        Box::new((TypeSignature::BoolType,
+                  TypeSignature::UIntType))).into())
+}
+
+pub fn check_special_get_token_supply(checker: &mut TypeChecker, args: &[SymbolicExpression], _context: &TypingContext) -> TypeResult {
+    check_argument_count(1, args)?;
+
+    let asset_name = args[0].match_atom()
+        .ok_or(CheckErrors::BadTokenName)?;
+
+    if !checker.contract_context.ft_exists(asset_name) {
+        return Err(CheckErrors::NoSuchFT(asset_name.to_string()).into());
+    }
+
+    runtime_cost!(cost_functions::ANALYSIS_TYPE_LOOKUP, checker, 1)?;
+
+    Ok(TypeSignature::UIntType)
+}
+
 pub fn check_special_transfer_asset(checker: &mut TypeChecker, args: &[SymbolicExpression], context: &TypingContext) -> TypeResult {
     check_argument_count(4, args)?;
 
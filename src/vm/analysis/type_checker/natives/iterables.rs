@@ -2,7 +2,7 @@ use vm::functions::NativeFunctions;
 use vm::representations::{SymbolicExpression, SymbolicExpressionType};
 use vm::types::{ TypeSignature, FunctionType };
 use vm::types::{Value, MAX_VALUE_SIZE};
-pub use vm::types::signatures::{ListTypeData, BufferLength};
+pub use vm::types::signatures::{ListTypeData, BufferLength, StringUTF8Length};
 use std::convert::TryFrom;
 use std::convert::TryInto;
 
@@ -151,6 +151,26 @@ pub fn check_special_concat(checker: &mut TypeChecker, args: &[SymbolicExpressio
                 return Err(CheckErrors::TypeError(rhs_type.clone(), TypeSignature::max_buffer()).into());
             }
         },
+        TypeSignature::StringASCIIType(lhs_len) => {
+            if let TypeSignature::StringASCIIType(rhs_len) = rhs_type {
+                let size: u32 = u32::from(lhs_len).checked_add(u32::from(rhs_len))
+                    .ok_or(CheckErrors::MaxLengthOverflow)?;
+                let return_type = TypeSignature::StringASCIIType(size.try_into()?);
+                return Ok(return_type);
+            } else {
+                return Err(CheckErrors::TypeError(rhs_type.clone(), TypeSignature::max_string_ascii()).into());
+            }
+        },
+        TypeSignature::StringUTF8Type(lhs_len) => {
+            if let TypeSignature::StringUTF8Type(rhs_len) = rhs_type {
+                let size: u32 = u32::from(lhs_len).checked_add(u32::from(rhs_len))
+                    .ok_or(CheckErrors::MaxLengthOverflow)?;
+                let return_type = TypeSignature::StringUTF8Type(size.try_into()?);
+                return Ok(return_type);
+            } else {
+                return Err(CheckErrors::TypeError(rhs_type.clone(), TypeSignature::max_string_utf8()).into());
+            }
+        },
         _ => Err(CheckErrors::ExpectedListOrBuffer(lhs_type.clone()).into())
     }
 }
@@ -174,6 +194,33 @@ pub fn check_special_append(checker: &mut TypeChecker, args: &[SymbolicExpressio
             let return_type = TypeSignature::list_of(list_entry_type, new_len)?;
             return Ok(return_type);
         },
+        // strings are appended with a single character of the same encoding
+        TypeSignature::StringASCIIType(lhs_len) => {
+            let rhs_type = checker.type_check(&args[1], context)?;
+            let character_type = TypeSignature::StringASCIIType(1u32.try_into()?);
+
+            analysis_typecheck_cost(checker, &character_type, &rhs_type)?;
+
+            if !character_type.admits_type(&rhs_type) {
+                return Err(CheckErrors::TypeError(character_type, rhs_type).into());
+            }
+            let new_len = u32::from(lhs_len).checked_add(1)
+                .ok_or(CheckErrors::MaxLengthOverflow)?;
+            Ok(TypeSignature::StringASCIIType(new_len.try_into()?))
+        },
+        TypeSignature::StringUTF8Type(lhs_len) => {
+            let rhs_type = checker.type_check(&args[1], context)?;
+            let character_type = TypeSignature::StringUTF8Type(1u32.try_into()?);
+
+            analysis_typecheck_cost(checker, &character_type, &rhs_type)?;
+
+            if !character_type.admits_type(&rhs_type) {
+                return Err(CheckErrors::TypeError(character_type, rhs_type).into());
+            }
+            let new_len = u32::from(lhs_len).checked_add(1)
+                .ok_or(CheckErrors::MaxLengthOverflow)?;
+            Ok(TypeSignature::StringUTF8Type(new_len.try_into()?))
+        },
         _ => Err(CheckErrors::ExpectedListApplication.into())
     }
 }
@@ -206,6 +253,12 @@ pub fn check_special_as_max_len(checker: &mut TypeChecker, args: &[SymbolicExpre
         TypeSignature::BufferType(_) => {
             Ok(TypeSignature::OptionalType(Box::new(TypeSignature::BufferType(BufferLength::try_from(expected_len).unwrap()))))
         },
+        TypeSignature::StringASCIIType(_) => {
+            Ok(TypeSignature::OptionalType(Box::new(TypeSignature::StringASCIIType(BufferLength::try_from(expected_len)?))))
+        },
+        TypeSignature::StringUTF8Type(_) => {
+            Ok(TypeSignature::OptionalType(Box::new(TypeSignature::StringUTF8Type(StringUTF8Length::try_from(expected_len)?))))
+        },
         _ => Err(CheckErrors::ExpectedListOrBuffer(iterable).into())
     }
 }
@@ -217,7 +270,8 @@ pub fn check_special_len(checker: &mut TypeChecker, args: &[SymbolicExpression],
     runtime_cost!(cost_functions::ANALYSIS_ITERABLE_FUNC, checker, 1)?;
 
     match collection_type {
-        TypeSignature::ListType(_) | TypeSignature::BufferType(_) => Ok(()),
+        TypeSignature::ListType(_) | TypeSignature::BufferType(_) |
+        TypeSignature::StringASCIIType(_) | TypeSignature::StringUTF8Type(_) => Ok(()),
         _ => Err(CheckErrors::ExpectedListOrBuffer(collection_type.clone()))
     }?;
 
@@ -126,6 +126,16 @@ fn test_bad_asset_usage() {
                        "(ft-transfer? stackaroos u2 u100 tx-sender)",
                        "(ft-transfer? stackaroos true tx-sender tx-sender)",
                        "(ft-transfer? stackaroos u2 tx-sender u100)",
+                       "(nft-burn? u1234 \"a\" tx-sender)",
+                       "(nft-burn? stackoos \"a\" tx-sender)",
+                       "(nft-burn? stacka-nfts \"a\" u2)",
+                       "(nft-burn? stacka-nfts u2 tx-sender)",
+                       "(ft-burn? stackoos u1 tx-sender)",
+                       "(ft-burn? u1234 u1 tx-sender)",
+                       "(ft-burn? stackaroos true tx-sender)",
+                       "(ft-burn? stackaroos u2 u100)",
+                       "(ft-get-supply stackoos)",
+                       "(ft-get-supply u1234)",
                        "(define-fungible-token stackaroos true)",
                        "(define-non-fungible-token stackaroos integer)",
                        "(ft-mint? stackaroos 100 tx-sender)",
@@ -176,6 +186,20 @@ fn test_bad_asset_usage() {
                                TypeSignature::BoolType),
         CheckErrors::TypeError(TypeSignature::PrincipalType,
                                TypeSignature::UIntType),
+        CheckErrors::BadTokenName,
+        CheckErrors::NoSuchNFT("stackoos".to_string()),
+        CheckErrors::TypeError(TypeSignature::PrincipalType,
+                               TypeSignature::UIntType),
+        CheckErrors::TypeError(buff_type(10),
+                               TypeSignature::UIntType),
+        CheckErrors::NoSuchFT("stackoos".to_string()),
+        CheckErrors::BadTokenName,
+        CheckErrors::TypeError(TypeSignature::UIntType,
+                               TypeSignature::BoolType),
+        CheckErrors::TypeError(TypeSignature::PrincipalType,
+                               TypeSignature::UIntType),
+        CheckErrors::NoSuchFT("stackoos".to_string()),
+        CheckErrors::BadTokenName,
         CheckErrors::TypeError(TypeSignature::UIntType,
                                TypeSignature::BoolType),
         CheckErrors::DefineNFTBadSignature.into(),
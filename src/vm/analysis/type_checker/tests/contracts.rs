@@ -7,7 +7,9 @@ use vm::analysis::{AnalysisDatabase, contract_interface_builder::build_contract_
 use vm::database::MemoryBackingStore;
 use vm::analysis::mem_type_check;
 use vm::analysis::type_check;
-use vm::types::QualifiedContractIdentifier;
+use vm::analysis::type_checker::TypeChecker;
+use vm::costs::LimitedCostTracker;
+use vm::types::{QualifiedContractIdentifier, TypeSignature};
 
 const SIMPLE_TOKENS: &str =
         "(define-map tokens ((account principal)) ((balance uint)))
@@ -578,3 +580,43 @@ fn test_expects() {
     });
 
 }
+
+#[test]
+fn test_type_check_expression() {
+    let contract_id = QualifiedContractIdentifier::local("tokens").unwrap();
+    let missing_id = QualifiedContractIdentifier::local("missing").unwrap();
+    let mut contract = parse(&contract_id, SIMPLE_TOKENS).unwrap();
+
+    let mut marf = MemoryBackingStore::new();
+    let mut db = marf.as_analysis_db();
+    db.execute(|db| {
+        db.test_insert_contract_hash(&contract_id);
+        type_check(&contract_id, &mut contract, db, true)
+    }).unwrap();
+
+    db.begin();
+
+    let parsed = parse(&contract_id, "(my-get-token-balance tx-sender)").unwrap();
+    let expr_type = TypeChecker::type_check_expression(&mut db, &contract_id, &parsed[0], LimitedCostTracker::new_max_limit()).0.unwrap();
+    assert_eq!(expr_type, TypeSignature::UIntType);
+
+    let parsed = parse(&contract_id, "(map-get? tokens (tuple (account tx-sender)))").unwrap();
+    TypeChecker::type_check_expression(&mut db, &contract_id, &parsed[0], LimitedCostTracker::new_max_limit()).0.unwrap();
+
+    let parsed = parse(&contract_id, "(my-get-token-balance 1)").unwrap();
+    let err = TypeChecker::type_check_expression(&mut db, &contract_id, &parsed[0], LimitedCostTracker::new_max_limit()).0.unwrap_err();
+    assert!(match &err.err {
+        &CheckErrors::TypeError(_, _) => true,
+        _ => false
+    });
+
+    let parsed = parse(&contract_id, "(no-such-function)").unwrap();
+    let err = TypeChecker::type_check_expression(&mut db, &contract_id, &parsed[0], LimitedCostTracker::new_max_limit()).0.unwrap_err();
+    assert_eq!(err.err, CheckErrors::UnknownFunction("no-such-function".to_string()));
+
+    let parsed = parse(&contract_id, "(my-get-token-balance tx-sender)").unwrap();
+    let err = TypeChecker::type_check_expression(&mut db, &missing_id, &parsed[0], LimitedCostTracker::new_max_limit()).0.unwrap_err();
+    assert_eq!(err.err, CheckErrors::NoSuchContract(missing_id.to_string()));
+
+    db.roll_back();
+}
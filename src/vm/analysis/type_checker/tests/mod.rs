@@ -764,6 +764,52 @@ fn test_buff_concat() {
     }
 }
 
+#[test]
+fn test_string_iterables() {
+    let good = [
+        "(concat a\"123\" a\"58\")",
+        "(concat u\"caf\\u{e9}\" u\"\\u{1F600}\")",
+        "(append a\"12\" a\"3\")",
+        "(append u\"caf\" u\"\\u{e9}\")",
+        "(len u\"caf\\u{e9}\")",
+        "(as-max-len? a\"123\" u10)",
+        "(as-max-len? u\"caf\\u{e9}\" u10)",
+        "(define-private (greet (name (string-utf8 10))) (concat u\"hi \" name))
+         (greet u\"caf\\u{e9}\")"];
+    let expected = [
+        "(string-ascii 5)",
+        "(string-utf8 5)",
+        "(string-ascii 3)",
+        "(string-utf8 4)",
+        "uint",
+        "(optional (string-ascii 10))",
+        "(optional (string-utf8 10))",
+        "(string-utf8 13)"];
+
+    for (good_test, expected) in good.iter().zip(expected.iter()) {
+        assert_eq!(expected, &format!("{}", type_check_helper(&good_test).unwrap()));
+    }
+
+    let bad = [
+        "(concat a\"123\" u\"58\")",
+        "(concat a\"123\" \"58\")",
+        "(append a\"12\" a\"34\")",
+        "(append u\"12\" a\"3\")",
+        "(define-private (greet (name (string-ascii 3))) name)
+         (greet u\"abc\")"];
+
+    let bad_expected = [
+        CheckErrors::TypeError(TypeSignature::from("(string-utf8 2)"), TypeSignature::max_string_ascii()),
+        CheckErrors::TypeError(buff_type(2), TypeSignature::max_string_ascii()),
+        CheckErrors::TypeError(TypeSignature::from("(string-ascii 1)"), TypeSignature::from("(string-ascii 2)")),
+        CheckErrors::TypeError(TypeSignature::from("(string-utf8 1)"), TypeSignature::from("(string-ascii 1)")),
+        CheckErrors::TypeError(TypeSignature::from("(string-ascii 3)"), TypeSignature::from("(string-utf8 3)")),
+    ];
+    for (bad_test, expected) in bad.iter().zip(bad_expected.iter()) {
+        assert_eq!(expected, &type_check_helper(&bad_test).unwrap_err().err);
+    }
+}
+
 #[test]
 fn test_buff_filter() {
     let good = [
@@ -7,6 +7,7 @@ use vm::analysis::errors::{CheckResult, CheckErrors};
 use vm::analysis::type_checker::contexts::TypeMap;
 use vm::analysis::contract_interface_builder::ContractInterface;
 use vm::costs::{CostTracker, ExecutionCost, LimitedCostTracker};
+use vm::diagnostic::Diagnostic;
 
 const DESERIALIZE_FAIL_MESSAGE: &str = "PANIC: Failed to deserialize bad database data in contract analysis.";
 const SERIALIZE_FAIL_MESSAGE: &str = "PANIC: Failed to deserialize bad database data in contract analysis.";
@@ -34,7 +35,10 @@ pub struct ContractAnalysis {
     #[serde(skip)]
     pub type_map: Option<TypeMap>,
     #[serde(skip)]
-    pub cost_track: Option<LimitedCostTracker>
+    pub cost_track: Option<LimitedCostTracker>,
+    /// Non-fatal diagnostics, from the lint pass
+    #[serde(skip)]
+    pub warnings: Vec<Diagnostic>,
 }
 
 impl ContractAnalysis {
@@ -54,7 +58,8 @@ impl ContractAnalysis {
             implemented_traits: BTreeSet::new(),
             fungible_tokens: BTreeSet::new(),
             non_fungible_tokens: BTreeMap::new(),
-            cost_track: Some(cost_track)
+            cost_track: Some(cost_track),
+            warnings: vec![],
         }
     }
 
@@ -0,0 +1,449 @@
+use vm::ast::errors::{ParseResult, ParseErrors, ParseError};
+use vm::ast::parser::{self, LexItem};
+use vm::ast::stack_depth_checker::StackDepthChecker;
+use vm::ast::types::{ContractAST, BuildASTPass};
+use vm::types::QualifiedContractIdentifier;
+
+#[cfg(test)]
+mod tests;
+
+/// Lists and tuples are broken across lines when they would extend past this column.
+pub const MAX_LINE_LENGTH: usize = 80;
+const INDENT: usize = 2;
+
+/// Runs of consecutive top-level definitions of one of these kinds are aligned on the
+///  column following the defined name.
+const ALIGNED_DEFINES: &[&str] = &["define-constant", "define-data-var", "define-map",
+                                    "define-fungible-token", "define-non-fungible-token"];
+
+struct Comment {
+    text: String,
+    blank_before: bool,
+}
+
+struct Node {
+    expr: Expr,
+    // comments on the lines preceding the node
+    comments: Vec<Comment>,
+    // a comment following the node on the same line
+    trailing: Option<String>,
+    blank_before: bool,
+}
+
+// Lists and tuples carry the comments between their last item and their closing delimiter.
+//  Literals and names are kept as written, so the formatter never changes their meaning.
+enum Expr {
+    Atom(String),
+    List(Vec<Node>, Vec<Comment>),
+    Tuple(Vec<Node>, Vec<Comment>),
+}
+
+struct Frame {
+    is_tuple: bool,
+    is_top_level: bool,
+    children: Vec<Node>,
+    // comments waiting for the next item
+    comments: Vec<Comment>,
+    // a comment following the opening delimiter on the same line
+    open_comment: Option<String>,
+    // the comments and spacing preceding the opening delimiter
+    node_comments: Vec<Comment>,
+    node_blank_before: bool,
+}
+
+impl Frame {
+    fn new(is_tuple: bool, is_top_level: bool, node_comments: Vec<Comment>, node_blank_before: bool) -> Frame {
+        Frame {
+            is_tuple,
+            is_top_level,
+            children: vec![],
+            comments: vec![],
+            open_comment: None,
+            node_comments,
+            node_blank_before,
+        }
+    }
+
+    fn push(&mut self, expr: Expr, comments: Vec<Comment>, blank_before: bool) {
+        let trailing = if self.children.is_empty() { self.open_comment.take() } else { None };
+        self.children.push(Node { expr, comments, trailing, blank_before });
+    }
+
+    fn add_comment(&mut self, text: &str, newlines: usize) {
+        let text = text.trim_end().to_string();
+        if newlines == 0 && self.comments.is_empty() {
+            match self.children.last_mut() {
+                Some(ref mut last) if last.trailing.is_none() => {
+                    last.trailing = Some(text);
+                    return;
+                },
+                None if !self.is_top_level && self.open_comment.is_none() => {
+                    self.open_comment = Some(text);
+                    return;
+                },
+                _ => {}
+            }
+        }
+        self.comments.push(Comment { text, blank_before: newlines > 1 });
+    }
+
+    fn into_node(self) -> Node {
+        let Frame { is_tuple, children, comments, open_comment, node_comments, node_blank_before, .. } = self;
+        let mut dangling = vec![];
+        if let Some(text) = open_comment {
+            dangling.push(Comment { text, blank_before: false });
+        }
+        dangling.extend(comments);
+        let expr = if is_tuple { Expr::Tuple(children, dangling) } else { Expr::List(children, dangling) };
+        Node { expr, comments: node_comments, trailing: None, blank_before: node_blank_before }
+    }
+}
+
+// Rebuild the tree of the source's lists and tuples from its tokens, attaching each comment
+//  to the item it precedes or follows.
+fn build_tree(source: &str) -> ParseResult<Frame> {
+    let mut stack = vec![Frame::new(false, true, vec![], false)];
+    // newlines since the last token or comment
+    let mut newlines = 0;
+
+    for (item, _, _, text) in parser::lex_with_source(source)?.into_iter() {
+        match item {
+            LexItem::Whitespace => {
+                if text.starts_with(";;") {
+                    stack.last_mut().unwrap().add_comment(text, newlines);
+                    newlines = 0;
+                } else {
+                    newlines += text.matches('\n').count();
+                }
+            },
+            LexItem::LeftParen | LexItem::LeftCurly => {
+                let comments = stack.last_mut().unwrap().comments.drain(..).collect();
+                let is_tuple = match item { LexItem::LeftCurly => true, _ => false };
+                stack.push(Frame::new(is_tuple, false, comments, newlines > 1));
+                newlines = 0;
+            },
+            LexItem::RightParen | LexItem::RightCurly => {
+                if stack.len() < 2 {
+                    return Err(ParseError::new(ParseErrors::ClosingParenthesisUnexpected));
+                }
+                let node = stack.pop().unwrap().into_node();
+                let parent = stack.last_mut().unwrap();
+                let trailing = if parent.children.is_empty() { parent.open_comment.take() } else { None };
+                parent.children.push(Node { trailing, .. node });
+                newlines = 0;
+            },
+            LexItem::CommaSeparator | LexItem::ColonSeparator => {},
+            _ => {
+                let frame = stack.last_mut().unwrap();
+                let comments = frame.comments.drain(..).collect();
+                frame.push(Expr::Atom(text.to_string()), comments, newlines > 1);
+                newlines = 0;
+            }
+        }
+    }
+
+    if stack.len() != 1 {
+        return Err(ParseError::new(ParseErrors::ClosingParenthesisExpected));
+    }
+    Ok(stack.pop().unwrap())
+}
+
+/// Re-emit a contract's source with canonical layout: two-space indentation, lists and tuples
+///  broken one item per line when they don't fit in `MAX_LINE_LENGTH` columns, and runs of
+///  simple definitions aligned.  Comments and blank lines between items are preserved, and
+///  formatting a formatted contract leaves it unchanged.
+pub fn format_source(source: &str) -> ParseResult<String> {
+    // the formatter works from the tokens, but only formats sources which parse
+    let pre_expressions = parser::parse(source)?;
+    let mut contract_ast = ContractAST::new(QualifiedContractIdentifier::transient(), pre_expressions);
+    StackDepthChecker::run_pass(&mut contract_ast)?;
+
+    let top_level = build_tree(source)?;
+    let alignments = align_definitions(&top_level.children);
+
+    let mut output = String::new();
+    for (node, alignment) in top_level.children.iter().zip(alignments.iter()) {
+        write_comments(&mut output, &node.comments, 0);
+        if node.blank_before && !output.is_empty() {
+            output.push('\n');
+        }
+        match alignment {
+            Some(width) => output.push_str(&render_aligned(&node.expr, *width)),
+            None => output.push_str(&render(&node.expr, 0))
+        }
+        if let Some(ref trailing) = node.trailing {
+            output.push(' ');
+            output.push_str(trailing);
+        }
+        output.push('\n');
+    }
+    write_comments(&mut output, &top_level.comments, 0);
+    Ok(output)
+}
+
+fn write_comments(output: &mut String, comments: &[Comment], indent: usize) {
+    for comment in comments.iter() {
+        if comment.blank_before && !output.is_empty() {
+            output.push('\n');
+        }
+        output.push_str(&" ".repeat(indent));
+        output.push_str(&comment.text);
+        output.push('\n');
+    }
+}
+
+fn has_comments(node: &Node) -> bool {
+    !node.comments.is_empty() || node.trailing.is_some() || contains_comments(&node.expr)
+}
+
+fn contains_comments(expr: &Expr) -> bool {
+    match expr {
+        Expr::Atom(_) => false,
+        Expr::List(children, dangling) | Expr::Tuple(children, dangling) =>
+            !dangling.is_empty() || children.iter().any(has_comments)
+    }
+}
+
+fn render_flat(expr: &Expr) -> String {
+    match expr {
+        Expr::Atom(text) => text.clone(),
+        Expr::List(children, _) => {
+            let items: Vec<_> = children.iter().map(|child| render_flat(&child.expr)).collect();
+            format!("({})", items.join(" "))
+        },
+        Expr::Tuple(children, _) => {
+            let pairs: Vec<_> = children.chunks(2)
+                .map(|pair| pair.iter().map(|item| render_flat(&item.expr)).collect::<Vec<_>>().join(": "))
+                .collect();
+            format!("{{{}}}", pairs.join(", "))
+        }
+    }
+}
+
+// The column following `text`, if it is written starting at `column`.
+fn end_column(text: &str, column: usize) -> usize {
+    match text.rfind('\n') {
+        Some(index) => text.len() - index - 1,
+        None => column + text.len()
+    }
+}
+
+/// Render an expression whose first character is written at column `indent`.
+fn render(expr: &Expr, indent: usize) -> String {
+    if let Expr::Atom(text) = expr {
+        return text.clone();
+    }
+    if !contains_comments(expr) {
+        let flat = render_flat(expr);
+        if indent + flat.len() <= MAX_LINE_LENGTH {
+            return flat;
+        }
+    }
+    match expr {
+        Expr::List(children, dangling) => render_list(children, dangling, indent),
+        Expr::Tuple(children, dangling) => render_tuple(children, dangling, indent),
+        Expr::Atom(_) => unreachable!("Atoms are rendered as written")
+    }
+}
+
+// How many arguments an application keeps on its opening line when broken.
+fn header_arguments(function: &str) -> usize {
+    match function {
+        "begin" | "tuple" | "list" | "and" | "or" => 0,
+        _ => 1
+    }
+}
+
+// Write the items which don't fit on the opening line of a list or tuple, one per line.
+fn write_body_line(output: &mut String, node: &Node, text: &str, indent: usize, first: bool) {
+    for comment in node.comments.iter() {
+        output.push('\n');
+        if comment.blank_before && !first {
+            output.push('\n');
+        }
+        output.push_str(&" ".repeat(indent));
+        output.push_str(&comment.text);
+    }
+    output.push('\n');
+    if node.blank_before && !first && node.comments.is_empty() {
+        output.push('\n');
+    }
+    output.push_str(&" ".repeat(indent));
+    output.push_str(text);
+}
+
+fn write_closing(output: &mut String, dangling: &[Comment], body_indent: usize, indent: usize,
+                 ends_with_comment: bool, closing: char) {
+    for comment in dangling.iter() {
+        output.push('\n');
+        output.push_str(&" ".repeat(body_indent));
+        output.push_str(&comment.text);
+    }
+    if ends_with_comment || !dangling.is_empty() {
+        output.push('\n');
+        output.push_str(&" ".repeat(indent));
+    }
+    output.push(closing);
+}
+
+// Applications keep their function and first argument on the opening line and indent the rest
+//  beneath it; other lists (e.g., binding lists) align their items with the first.
+fn render_list(children: &[Node], dangling: &[Comment], indent: usize) -> String {
+    let (mut header, body_indent) = match children.get(0).map(|head| &head.expr) {
+        Some(Expr::Atom(function)) => (1 + header_arguments(function), indent + INDENT),
+        _ => (1, indent + 1)
+    };
+    header = header.min(children.len());
+    if children.get(0).map(|head| !head.comments.is_empty()).unwrap_or(false) {
+        header = 0;
+    }
+    for (index, child) in children[..header].iter().enumerate() {
+        if index > 0 && !child.comments.is_empty() {
+            header = index;
+            break;
+        }
+        if child.trailing.is_some() {
+            header = index + 1;
+            break;
+        }
+    }
+
+    let mut output = "(".to_string();
+    let mut column = indent + 1;
+    let mut ends_with_comment = false;
+    for (index, child) in children[..header].iter().enumerate() {
+        if index > 0 {
+            output.push(' ');
+            column += 1;
+        }
+        let text = render(&child.expr, column);
+        column = end_column(&text, column);
+        output.push_str(&text);
+        if let Some(ref trailing) = child.trailing {
+            output.push(' ');
+            output.push_str(trailing);
+            ends_with_comment = true;
+        }
+    }
+
+    for (index, child) in children[header..].iter().enumerate() {
+        let text = render(&child.expr, body_indent);
+        write_body_line(&mut output, child, &text, body_indent, index == 0);
+        ends_with_comment = false;
+        if let Some(ref trailing) = child.trailing {
+            output.push(' ');
+            output.push_str(trailing);
+            ends_with_comment = true;
+        }
+    }
+
+    write_closing(&mut output, dangling, body_indent, indent, ends_with_comment, ')');
+    output
+}
+
+// Broken tuples have one `key: value` pair per line, indented past the opening brace, and
+//  close on a line of their own.
+fn render_tuple(children: &[Node], dangling: &[Comment], indent: usize) -> String {
+    let body_indent = indent + INDENT;
+    let pair_count = (children.len() + 1) / 2;
+
+    let mut output = "{".to_string();
+    for (index, pair) in children.chunks(2).enumerate() {
+        let (key, value) = (&pair[0], pair.get(1));
+        let key_text = render_flat(&key.expr);
+        let separator = if index + 1 < pair_count { "," } else { "" };
+        let text = match value {
+            Some(value) => format!("{}: {}{}", key_text, render(&value.expr, body_indent + key_text.len() + 2), separator),
+            None => key_text
+        };
+
+        write_body_line(&mut output, key, &text, body_indent, index == 0);
+        // comments between a key and its value are moved after the pair
+        let comments: Vec<_> = value.iter().flat_map(|value| value.comments.iter()).map(|comment| comment.text.as_str())
+            .chain(key.trailing.iter().map(|text| text.as_str()))
+            .chain(value.iter().filter_map(|value| value.trailing.as_ref()).map(|text| text.as_str()))
+            .collect();
+        if !comments.is_empty() {
+            output.push(' ');
+            output.push_str(&comments.join(" "));
+        }
+    }
+
+    write_closing(&mut output, dangling, body_indent, indent, true, '}');
+    output
+}
+
+// For each top-level node, the width to pad its defined name to, if it is part of an aligned
+//  run of definitions.  Runs are broken by blank lines and by other kinds of forms, and are
+//  only aligned if every definition in the run still fits on one line.
+fn align_definitions(nodes: &[Node]) -> Vec<Option<usize>> {
+    let mut alignments = vec![None; nodes.len()];
+    let mut run_start = 0;
+    while run_start < nodes.len() {
+        let kind = match alignable_definition(&nodes[run_start]) {
+            Some((kind, _, _)) => kind,
+            None => {
+                run_start += 1;
+                continue;
+            }
+        };
+
+        let mut run_end = run_start + 1;
+        while run_end < nodes.len() && !starts_paragraph(&nodes[run_end]) {
+            match alignable_definition(&nodes[run_end]) {
+                Some((next_kind, _, _)) if next_kind == kind => run_end += 1,
+                _ => break
+            }
+        }
+
+        let definitions: Vec<_> = nodes[run_start..run_end].iter()
+            .filter_map(alignable_definition)
+            .collect();
+        let width = definitions.iter().map(|(_, name, _)| name.len()).max().unwrap_or(0);
+        let fits = definitions.iter()
+            .all(|(kind, _, rest)| kind.len() + width + rest.len() + 4 <= MAX_LINE_LENGTH);
+        if fits && definitions.len() > 1 {
+            for alignment in alignments[run_start..run_end].iter_mut() {
+                *alignment = Some(width);
+            }
+        }
+        run_start = run_end;
+    }
+    alignments
+}
+
+fn starts_paragraph(node: &Node) -> bool {
+    node.blank_before || node.comments.iter().any(|comment| comment.blank_before)
+}
+
+// The kind, name, and flat remainder of a definition which may be aligned.
+fn alignable_definition(node: &Node) -> Option<(&str, &str, String)> {
+    let children = match node.expr {
+        Expr::List(ref children, ref dangling) if dangling.is_empty() && children.len() >= 3 => children,
+        _ => return None
+    };
+    if contains_comments(&node.expr) {
+        return None;
+    }
+    let (kind, name) = match (&children[0].expr, &children[1].expr) {
+        (Expr::Atom(kind), Expr::Atom(name)) if ALIGNED_DEFINES.contains(&kind.as_str()) => (kind, name),
+        _ => return None
+    };
+    let rest: Vec<_> = children[2..].iter().map(|child| render_flat(&child.expr)).collect();
+    let rest = rest.join(" ");
+    if kind.len() + name.len() + rest.len() + 4 > MAX_LINE_LENGTH {
+        return None;
+    }
+    Some((kind.as_str(), name.as_str(), rest))
+}
+
+fn render_aligned(expr: &Expr, width: usize) -> String {
+    match expr {
+        Expr::List(children, _) => {
+            let rest: Vec<_> = children[2..].iter().map(|child| render_flat(&child.expr)).collect();
+            format!("({} {:<width$} {})", render_flat(&children[0].expr), render_flat(&children[1].expr),
+                    rest.join(" "), width = width)
+        },
+        _ => render(expr, 0)
+    }
+}
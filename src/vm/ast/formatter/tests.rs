@@ -0,0 +1,100 @@
+use std::fs;
+
+use vm::ast::formatter::format_source;
+use vm::ast::parser;
+use vm::representations::PreSymbolicExpression;
+use vm::representations::PreSymbolicExpressionType::{List, Tuple};
+
+// Whether two parses are the same, ignoring where their expressions are in the source.
+fn same_expressions(a: &[PreSymbolicExpression], b: &[PreSymbolicExpression]) -> bool {
+    a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| {
+        match (&a.pre_expr, &b.pre_expr) {
+            (List(a), List(b)) | (Tuple(a), Tuple(b)) => same_expressions(a, b),
+            (a, b) => a == b
+        }
+    })
+}
+
+fn assert_formats_to(source: &str, expected: &str) {
+    let formatted = format_source(source).unwrap();
+    assert_eq!(formatted, expected);
+    assert_eq!(format_source(&formatted).unwrap(), formatted);
+    assert!(same_expressions(&parser::parse(source).unwrap(), &parser::parse(&formatted).unwrap()));
+}
+
+#[test]
+fn test_line_breaking_and_alignment() {
+    let source = "(define-constant ERR-UNAUTHORIZED (err u401)) (define-constant OWNER tx-sender)
+(define-map balances ((owner principal)) ((amount uint)))
+(define-public (transfer (amount uint) (recipient principal)) (begin (asserts! (is-eq tx-sender OWNER) ERR-UNAUTHORIZED) (ok (map-set balances (tuple (owner recipient)) (tuple (amount amount))))))";
+
+    let expected = format!("(define-constant ERR-UNAUTHORIZED (err u401))
+(define-constant OWNER{} tx-sender)
+(define-map balances ((owner principal)) ((amount uint)))
+(define-public (transfer (amount uint) (recipient principal))
+  (begin
+    (asserts! (is-eq tx-sender OWNER) ERR-UNAUTHORIZED)
+    (ok (map-set balances (tuple (owner recipient)) (tuple (amount amount))))))
+", " ".repeat(11));
+
+    assert_formats_to(source, &expected);
+}
+
+#[test]
+fn test_comments_and_tuples() {
+    let source = ";; A counter.
+
+(define-data-var counter int 0)    ;; the count
+
+;; bump it
+(define-public (bump (by int)) ;; by how much
+    (let ((current (var-get counter)) (next (+ by 1)))
+;; store it
+    (var-set counter next)
+    (ok {previous: current, next: next, caller: tx-sender, block: block-height, note: \"bumped\"})))
+;; end
+";
+
+    let expected = ";; A counter.
+
+(define-data-var counter int 0) ;; the count
+
+;; bump it
+(define-public (bump (by int)) ;; by how much
+  (let ((current (var-get counter)) (next (+ by 1)))
+    ;; store it
+    (var-set counter next)
+    (ok {
+          previous: current,
+          next: next,
+          caller: tx-sender,
+          block: block-height,
+          note: \"bumped\"
+        })))
+;; end
+";
+
+    assert_formats_to(source, expected);
+}
+
+#[test]
+fn test_literals_kept_as_written() {
+    assert_formats_to("(define-constant   greeting   \"hi  (there)\")\n(print   0x00ff)  (print u\"\\u{2603}\")",
+                      "(define-constant greeting \"hi  (there)\")\n(print 0x00ff)\n(print u\"\\u{2603}\")\n");
+}
+
+#[test]
+fn test_sample_contracts() {
+    for path in ["sample-contracts/tokens.clar", "sample-contracts/names.clar"].iter() {
+        let source = fs::read_to_string(path).unwrap();
+        let formatted = format_source(&source).unwrap();
+        assert_eq!(format_source(&formatted).unwrap(), formatted);
+        assert!(same_expressions(&parser::parse(&source).unwrap(), &parser::parse(&formatted).unwrap()));
+    }
+}
+
+#[test]
+fn test_rejects_unparseable() {
+    assert!(format_source("(define-constant x").is_err());
+    assert!(format_source("(define-constant x 1))").is_err());
+}
@@ -7,6 +7,7 @@ pub mod sugar_expander;
 pub mod types;
 pub mod errors;
 pub mod stack_depth_checker;
+pub mod formatter;
 use vm::errors::{Error, RuntimeErrorType};
 use vm::costs::{cost_functions, CostTracker};
 
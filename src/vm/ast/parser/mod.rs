@@ -32,7 +32,7 @@ enum TokenType {
     Whitespace, Comma, Colon,
     LParens, RParens,
     LCurly, RCurly,
-    StringLiteral, HexStringLiteral,
+    StringLiteral, StringASCIILiteral, StringUTF8Literal, HexStringLiteral,
     UIntLiteral, IntLiteral,
     Variable, TraitReferenceLiteral, PrincipalLiteral,
     SugaredContractIdentifierLiteral,
@@ -72,6 +72,41 @@ fn get_value_or_err(input: &str, captures: Captures) -> ParseResult<String> {
     Ok(input[matched.start()..matched.end()].to_string())
 }
 
+/// Resolve the escape sequences of a string-ascii or string-utf8 literal.
+///   `\u{...}` escapes are only accepted when `allow_unicode` is set.
+fn unescape_string(input: &str, allow_unicode: bool) -> ParseResult<String> {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+        let escaped = match chars.next() {
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('u') if allow_unicode => {
+                let rest = chars.as_str();
+                let close = Some(rest)
+                    .filter(|rest| rest.starts_with('{'))
+                    .and_then(|rest| rest.find('}'))
+                    .ok_or_else(|| ParseError::new(ParseErrors::FailedParsingString(input.to_string())))?;
+                let escaped = u32::from_str_radix(&rest[1..close], 16).ok()
+                    .and_then(std::char::from_u32)
+                    .ok_or_else(|| ParseError::new(ParseErrors::FailedParsingString(input.to_string())))?;
+                chars = rest[close + 1..].chars();
+                escaped
+            },
+            _ => return Err(ParseError::new(ParseErrors::FailedParsingString(input.to_string())))
+        };
+        output.push(escaped);
+    }
+    Ok(output)
+}
+
 fn get_lines_at(input: &str) -> Vec<usize> {
     let mut out: Vec<_> = input.match_indices("\n")
         .map(|(ix, _)| ix)
@@ -91,12 +126,23 @@ lazy_static! {
 }
 
 pub fn lex(input: &str) -> ParseResult<Vec<(LexItem, u32, u32)>> {
+    let items = lex_with_source(input)?;
+    Ok(items.into_iter()
+       .map(|(item, line, column, _)| (item, line, column))
+       .collect())
+}
+
+/// Like `lex`, but also returns the source text each item was lexed from, including
+///  the text of whitespace and comments.  Concatenated, these reproduce the input.
+pub fn lex_with_source(input: &str) -> ParseResult<Vec<(LexItem, u32, u32, &str)>> {
     // Aaron: I'd like these to be static, but that'd require using
     //    lazy_static (or just hand implementing that), and I'm not convinced
     //    it's worth either (1) an extern macro, or (2) the complexity of hand implementing.
 
     let lex_matchers: &[LexMatcher] = &[
         LexMatcher::new(r##""(?P<value>((\\")|([[ -~]&&[^"]]))*)""##, TokenType::StringLiteral),
+        LexMatcher::new(r##"a"(?P<value>((\\[ -~])|([[ -~]&&[^"\\]]))*)""##, TokenType::StringASCIILiteral),
+        LexMatcher::new(r##"u"(?P<value>((\\[ -~])|([^"\\\n]))*)""##, TokenType::StringUTF8Literal),
         LexMatcher::new(";;[ -~]*", TokenType::Whitespace), // ;; comments.
         LexMatcher::new("[\n]+", TokenType::Whitespace),
         LexMatcher::new("[ \t]+", TokenType::Whitespace),
@@ -131,13 +177,15 @@ pub fn lex(input: &str) -> ParseResult<Vec<(LexItem, u32, u32)>> {
     let mut column_pos: u32 = 1;
     let mut did_match = true;
     while did_match && munch_index < input.len() {
-        if let Some(next_line_ix) = next_line_break {
-            if munch_index > next_line_ix {
-                next_line_break = line_indices.pop();
-                column_pos = 1;
-                current_line = current_line.checked_add(1)
-                    .ok_or(ParseError::new(ParseErrors::ProgramTooLarge))?;
+        // a single whitespace item may span several line breaks
+        while let Some(next_line_ix) = next_line_break {
+            if munch_index <= next_line_ix {
+                break;
             }
+            next_line_break = line_indices.pop();
+            column_pos = 1;
+            current_line = current_line.checked_add(1)
+                .ok_or(ParseError::new(ParseErrors::ProgramTooLarge))?;
         }
 
         did_match = false;
@@ -301,9 +349,27 @@ pub fn lex(input: &str) -> ParseResult<Vec<(LexItem, u32, u32)>> {
                         }?;
                         Ok(LexItem::LiteralValue(str_value.len(), value))
                     },
+                    TokenType::StringASCIILiteral => {
+                        let str_value = get_value_or_err(current_slice, captures)?;
+                        let unescaped = unescape_string(&str_value, false)?;
+                        let value = match Value::string_ascii_from_bytes(unescaped.into_bytes()) {
+                            Ok(parsed) => Ok(parsed),
+                            Err(_e) => Err(ParseError::new(ParseErrors::FailedParsingString(str_value.clone())))
+                        }?;
+                        Ok(LexItem::LiteralValue(str_value.len(), value))
+                    },
+                    TokenType::StringUTF8Literal => {
+                        let str_value = get_value_or_err(current_slice, captures)?;
+                        let unescaped = unescape_string(&str_value, true)?;
+                        let value = match Value::string_utf8_from_str(&unescaped) {
+                            Ok(parsed) => Ok(parsed),
+                            Err(_e) => Err(ParseError::new(ParseErrors::FailedParsingString(str_value.clone())))
+                        }?;
+                        Ok(LexItem::LiteralValue(str_value.len(), value))
+                    },
                 }?;
 
-                result.push((token, current_line, column_pos));
+                result.push((token, current_line, column_pos, &current_slice[..whole_match.end()]));
                 column_pos += whole_match.end() as u32;
                 did_match = true;
                 break;
@@ -545,6 +611,19 @@ mod test {
         e
     }
 
+    #[test]
+    fn test_spans_after_blank_lines() {
+        let input = "(a)\n\n\n  (b c)";
+        let program = vec![
+            make_list(1, 1, 1, 3, Box::new([make_atom("a", 1, 2, 1, 2)])),
+            make_list(4, 3, 4, 7, Box::new([
+                make_atom("b", 4, 4, 4, 4),
+                make_atom("c", 4, 6, 4, 6)]))];
+
+        let parsed = ast::parser::parse(&input);
+        assert_eq!(Ok(program), parsed, "Should match expected symbolic expression");
+    }
+
     #[test]
     fn test_parse_let_expression() {
 
@@ -671,6 +750,33 @@ r#"z (let ((x 1) (y 2))
         });
     }
 
+    #[test]
+    fn test_parse_string_literals() {
+        let input = r#"(concat a"hello \"world\"\n" "buff") u"caf\u{e9} \u{1F600}""#;
+        let parsed = ast::parser::parse(&input).unwrap();
+
+        let list = parsed[0].match_list().unwrap();
+        assert_eq!(list[1].match_atom_value(),
+                   Some(&Value::string_ascii_from_bytes(b"hello \"world\"\n".to_vec()).unwrap()));
+        // plain quotes are still buffer literals
+        assert_eq!(list[2].match_atom_value(),
+                   Some(&Value::buff_from(b"buff".to_vec()).unwrap()));
+        assert_eq!(parsed[1].match_atom_value(),
+                   Some(&Value::string_utf8_from_str("caf\u{e9} \u{1F600}").unwrap()));
+
+        // unicode escapes are only legal in string-utf8
+        assert!(match ast::parser::parse(r#"a"caf\u{e9}""#).unwrap_err().err {
+            ParseErrors::FailedParsingString(_) => true, _ => false });
+        assert!(match ast::parser::parse(r#"u"\u{d800}""#).unwrap_err().err {
+            ParseErrors::FailedParsingString(_) => true, _ => false });
+        assert!(match ast::parser::parse(r#"u"\u{e9""#).unwrap_err().err {
+            ParseErrors::FailedParsingString(_) => true, _ => false });
+        assert!(match ast::parser::parse(r#"a"\q""#).unwrap_err().err {
+            ParseErrors::FailedParsingString(_) => true, _ => false });
+        // string-ascii literals can't hold non-ASCII characters
+        assert!(ast::parser::parse("a\"caf\u{e9}\"").is_err());
+    }
+
     #[test]
     fn test_parse_failures() {
         use vm::errors::{Error, RuntimeErrorType};
@@ -0,0 +1,589 @@
+/*
+ copyright: (c) 2013-2020 by Blockstack PBC, a public benefit corporation.
+
+ This file is part of Blockstack.
+
+ Blockstack is free software. You may redistribute or modify
+ it under the terms of the GNU General Public License as published by
+ the Free Software Foundation, either version 3 of the License or
+ (at your option) any later version.
+
+ Blockstack is distributed in the hope that it will be useful,
+ but WITHOUT ANY WARRANTY, including without the implied warranty of
+ MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+ GNU General Public License for more details.
+
+ You should have received a copy of the GNU General Public License
+ along with Blockstack. If not, see <http://www.gnu.org/licenses/>.
+*/
+
+// Typed Rust bindings for calling a contract, generated from its `ContractInterface`.
+//
+// `generate_bindings` writes a module with a struct for the contract, whose methods build
+//  `contract-call?` payloads and `/v2/contracts/call-read` requests, and decode the values the
+//  functions return.  Tuples become structs, and the other types map onto Rust's:
+//  `int` is `i128`, `(optional T)` is `Option<T>`, `(response T E)` is `Result<T, E>`, and so on.
+//  The generated code calls the helpers below, which do the work common to every contract.
+
+use std::collections::BTreeSet;
+use std::error;
+use std::fmt;
+
+use chainstate::stacks::{StacksAddress, TransactionContractCall, TransactionPayload};
+use net::{CallReadOnlyRequestBody, CallReadOnlyResponse};
+use vm::analysis::contract_interface_builder::{ContractInterface, ContractInterfaceAtomType,
+                                               ContractInterfaceFunctionAccess, ContractInterfaceTupleEntryType};
+use vm::database::ClaritySerializable;
+use vm::representations::{ClarityName, ContractName};
+use vm::types::{ASCIIData, OptionalData, PrincipalData, ResponseData, TupleData, Value};
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BindingError {
+    /// A value isn't of the type the contract declares.
+    UnexpectedValue(Value),
+    /// A tuple is missing one of its declared fields.
+    MissingField(String),
+    /// A read-only call failed, for the node's stated cause.
+    CallFailed(String),
+    /// A read-only call's result isn't a serialized value.
+    Deserialization(String),
+}
+
+impl fmt::Display for BindingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BindingError::UnexpectedValue(value) => write!(f, "Unexpected value: {}", value),
+            BindingError::MissingField(name) => write!(f, "Tuple has no field named '{}'", name),
+            BindingError::CallFailed(cause) => write!(f, "Read-only call failed: {}", cause),
+            BindingError::Deserialization(message) => write!(f, "Failed to deserialize result: {}", message),
+        }
+    }
+}
+
+impl error::Error for BindingError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        None
+    }
+}
+
+// Helpers used by generated bindings.
+//
+// Values converted from Rust panic if they can't be represented (e.g., a buffer larger than the
+//  maximum value size), like the other conversions to `Value`: the bindings take plain Rust values,
+//  so the caller is responsible for keeping them within the contract's declared bounds.
+
+pub fn contract_call(address: &StacksAddress, contract_name: &ContractName, function_name: &str, function_args: Vec<Value>) -> TransactionPayload {
+    TransactionPayload::ContractCall(TransactionContractCall {
+        address: *address,
+        contract_name: contract_name.clone(),
+        function_name: ClarityName::from(function_name),
+        function_args
+    })
+}
+
+/// The path and body of a `/v2/contracts/call-read` request.
+pub fn read_only_request(address: &StacksAddress, contract_name: &ContractName, function_name: &str,
+                         sender: &PrincipalData, function_args: Vec<Value>) -> (String, CallReadOnlyRequestBody) {
+    let path = format!("/v2/contracts/call-read/{}/{}/{}", address, contract_name.as_str(), function_name);
+    let body = CallReadOnlyRequestBody {
+        sender: sender.to_string(),
+        arguments: function_args.iter().map(|arg| arg.serialize()).collect()
+    };
+    (path, body)
+}
+
+/// The value returned by a `/v2/contracts/call-read` request.
+pub fn read_only_result(response: &CallReadOnlyResponse) -> Result<Value, BindingError> {
+    if !response.okay {
+        return Err(BindingError::CallFailed(response.cause.clone().unwrap_or_default()));
+    }
+    let result = response.result.as_ref()
+        .ok_or_else(|| BindingError::Deserialization("Missing result".into()))?;
+    Value::try_deserialize_hex_untyped(result)
+        .map_err(|e| BindingError::Deserialization(e.to_string()))
+}
+
+pub fn buffer_value(data: Vec<u8>) -> Value {
+    Value::buff_from(data).expect("Buffer exceeds the maximum value size")
+}
+
+pub fn string_ascii_value(string: String) -> Value {
+    Value::string_ascii_from_bytes(string.into_bytes()).expect("Invalid string-ascii")
+}
+
+pub fn string_utf8_value(string: String) -> Value {
+    Value::string_utf8_from_str(&string).expect("String exceeds the maximum value size")
+}
+
+pub fn list_value(items: Vec<Value>) -> Value {
+    Value::list_from(items).expect("List items must have the same type")
+}
+
+pub fn optional_value(data: Option<Value>) -> Value {
+    match data {
+        Some(value) => Value::some(value).expect("Value exceeds the maximum value size"),
+        None => Value::none()
+    }
+}
+
+pub fn response_value(data: Result<Value, Value>) -> Value {
+    let value = match data {
+        Ok(value) => Value::okay(value),
+        Err(value) => Value::error(value)
+    };
+    value.expect("Value exceeds the maximum value size")
+}
+
+pub fn tuple_value(fields: Vec<(&str, Value)>) -> Value {
+    let fields = fields.into_iter()
+        .map(|(name, value)| (ClarityName::from(name), value))
+        .collect();
+    Value::Tuple(TupleData::from_data(fields).expect("Tuple exceeds the maximum value size"))
+}
+
+/// Values of the `none` type are only found where a contract never looks, like the error of a
+///  function which never fails, so anything is accepted.
+pub fn expect_unit(_value: Value) -> Result<(), BindingError> {
+    Ok(())
+}
+
+pub fn expect_int(value: Value) -> Result<i128, BindingError> {
+    match value {
+        Value::Int(data) => Ok(data),
+        other => Err(BindingError::UnexpectedValue(other))
+    }
+}
+
+pub fn expect_uint(value: Value) -> Result<u128, BindingError> {
+    match value {
+        Value::UInt(data) => Ok(data),
+        other => Err(BindingError::UnexpectedValue(other))
+    }
+}
+
+pub fn expect_bool(value: Value) -> Result<bool, BindingError> {
+    match value {
+        Value::Bool(data) => Ok(data),
+        other => Err(BindingError::UnexpectedValue(other))
+    }
+}
+
+pub fn expect_principal(value: Value) -> Result<PrincipalData, BindingError> {
+    match value {
+        Value::Principal(data) => Ok(data),
+        other => Err(BindingError::UnexpectedValue(other))
+    }
+}
+
+pub fn expect_buffer(value: Value) -> Result<Vec<u8>, BindingError> {
+    match value {
+        Value::Buffer(buffer) => Ok(buffer.data),
+        other => Err(BindingError::UnexpectedValue(other))
+    }
+}
+
+pub fn expect_string_ascii(value: Value) -> Result<String, BindingError> {
+    match value {
+        Value::StringASCII(string) => String::from_utf8(string.data)
+            .map_err(|e| BindingError::UnexpectedValue(Value::StringASCII(ASCIIData { data: e.into_bytes() }))),
+        other => Err(BindingError::UnexpectedValue(other))
+    }
+}
+
+pub fn expect_string_utf8(value: Value) -> Result<String, BindingError> {
+    match value {
+        Value::StringUTF8(string) => {
+            let bytes: Vec<u8> = string.data.iter().flat_map(|c| c.iter().cloned()).collect();
+            String::from_utf8(bytes)
+                .map_err(|_| BindingError::UnexpectedValue(Value::StringUTF8(string)))
+        },
+        other => Err(BindingError::UnexpectedValue(other))
+    }
+}
+
+pub fn expect_list(value: Value) -> Result<Vec<Value>, BindingError> {
+    match value {
+        Value::List(list) => Ok(list.data),
+        other => Err(BindingError::UnexpectedValue(other))
+    }
+}
+
+pub fn expect_optional(value: Value) -> Result<Option<Value>, BindingError> {
+    match value {
+        Value::Optional(OptionalData { data }) => Ok(data.map(|inner| *inner)),
+        other => Err(BindingError::UnexpectedValue(other))
+    }
+}
+
+pub fn expect_response(value: Value) -> Result<Result<Value, Value>, BindingError> {
+    match value {
+        Value::Response(ResponseData { committed: true, data }) => Ok(Ok(*data)),
+        Value::Response(ResponseData { committed: false, data }) => Ok(Err(*data)),
+        other => Err(BindingError::UnexpectedValue(other))
+    }
+}
+
+pub fn expect_tuple(value: Value) -> Result<TupleData, BindingError> {
+    match value {
+        Value::Tuple(tuple) => Ok(tuple),
+        other => Err(BindingError::UnexpectedValue(other))
+    }
+}
+
+pub fn take_field(tuple: &mut TupleData, name: &str) -> Result<Value, BindingError> {
+    tuple.data_map.remove(name)
+        .ok_or_else(|| BindingError::MissingField(name.to_string()))
+}
+
+// Names which generated types mustn't take, because the bindings import them.
+const IMPORTED_TYPE_NAMES: &[&str] = &[
+    "BindingError", "CallReadOnlyRequestBody", "CallReadOnlyResponse", "ContractName", "Option",
+    "PrincipalData", "Result", "StacksAddress", "String", "TransactionPayload", "TryFrom", "TupleData",
+    "Value", "Vec"];
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "unsafe",
+    "use", "where", "while", "abstract", "become", "box", "do", "final", "macro", "override",
+    "priv", "typeof", "unsized", "virtual", "yield"];
+
+// The words of a Clarity name, e.g., `get-balance?` is `get` and `balance`.
+fn words(name: &str) -> Vec<String> {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_ascii_lowercase())
+        .collect()
+}
+
+fn snake_case(name: &str) -> String {
+    let words = words(name);
+    let snake = if words.is_empty() { "value".to_string() } else { words.join("_") };
+    if RUST_KEYWORDS.contains(&snake.as_str()) {
+        format!("{}_", snake)
+    } else {
+        snake
+    }
+}
+
+fn camel_case(name: &str) -> String {
+    words(name).iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new()
+            }
+        })
+        .collect()
+}
+
+// Different Clarity names can make the same Rust name (`is-ok?` and `is-ok!`), so later ones
+//  are numbered.
+fn unique_name(taken: &mut BTreeSet<String>, wanted: String) -> String {
+    let mut name = wanted.clone();
+    let mut count = 1;
+    while taken.contains(&name) {
+        count += 1;
+        name = format!("{}{}", wanted, count);
+    }
+    taken.insert(name.clone());
+    name
+}
+
+// A Clarity type, with its tuples replaced by the structs generated for them.
+enum Binding {
+    Unit,
+    Int,
+    UInt,
+    Bool,
+    Principal,
+    Buffer,
+    StringASCII,
+    StringUTF8,
+    Optional(Box<Binding>),
+    Response(Box<Binding>, Box<Binding>),
+    List(Box<Binding>),
+    Tuple(String),
+}
+
+impl Binding {
+    fn rust_type(&self) -> String {
+        match self {
+            Binding::Unit => "()".into(),
+            Binding::Int => "i128".into(),
+            Binding::UInt => "u128".into(),
+            Binding::Bool => "bool".into(),
+            Binding::Principal => "PrincipalData".into(),
+            Binding::Buffer => "Vec<u8>".into(),
+            Binding::StringASCII | Binding::StringUTF8 => "String".into(),
+            Binding::Optional(inner) => format!("Option<{}>", inner.rust_type()),
+            Binding::Response(ok, err) => format!("Result<{}, {}>", ok.rust_type(), err.rust_type()),
+            Binding::List(item) => format!("Vec<{}>", item.rust_type()),
+            Binding::Tuple(name) => name.clone(),
+        }
+    }
+
+    // An expression converting `expr` to a `Value`.  Nested closures name their argument after
+    //  their depth, so that they don't shadow each other.
+    fn to_value(&self, expr: &str, depth: usize) -> String {
+        let var = format!("v{}", depth);
+        match self {
+            Binding::Unit => "Value::none()".into(),
+            Binding::Int => format!("Value::Int({})", expr),
+            Binding::UInt => format!("Value::UInt({})", expr),
+            Binding::Bool => format!("Value::Bool({})", expr),
+            Binding::Principal => format!("Value::Principal({})", expr),
+            Binding::Buffer => format!("buffer_value({})", expr),
+            Binding::StringASCII => format!("string_ascii_value({})", expr),
+            Binding::StringUTF8 => format!("string_utf8_value({})", expr),
+            Binding::Optional(inner) =>
+                format!("optional_value({}.map(|{}| {}))", expr, var, inner.to_value(&var, depth + 1)),
+            Binding::Response(ok, err) =>
+                format!("response_value({}.map(|{}| {}).map_err(|{}| {}))",
+                        expr, var, ok.to_value(&var, depth + 1), var, err.to_value(&var, depth + 1)),
+            Binding::List(item) =>
+                format!("list_value({}.into_iter().map(|{}| {}).collect())", expr, var, item.to_value(&var, depth + 1)),
+            Binding::Tuple(_) => format!("Value::from({})", expr),
+        }
+    }
+
+    // An expression decoding the `Value` `expr`, as a `Result<_, BindingError>`.
+    fn from_value(&self, expr: &str, depth: usize) -> String {
+        let var = format!("v{}", depth);
+        match self {
+            Binding::Unit => format!("expect_unit({})", expr),
+            Binding::Int => format!("expect_int({})", expr),
+            Binding::UInt => format!("expect_uint({})", expr),
+            Binding::Bool => format!("expect_bool({})", expr),
+            Binding::Principal => format!("expect_principal({})", expr),
+            Binding::Buffer => format!("expect_buffer({})", expr),
+            Binding::StringASCII => format!("expect_string_ascii({})", expr),
+            Binding::StringUTF8 => format!("expect_string_utf8({})", expr),
+            Binding::Optional(inner) =>
+                format!("expect_optional({}).and_then(|{}| {}.map(|{}| {}).transpose())",
+                        expr, var, var, var, inner.from_value(&var, depth + 1)),
+            Binding::Response(ok, err) =>
+                format!("expect_response({}).and_then(|{}| match {} {{ Ok({}) => {}.map(Ok), Err({}) => {}.map(Err) }})",
+                        expr, var, var, var, ok.from_value(&var, depth + 1), var, err.from_value(&var, depth + 1)),
+            Binding::List(item) =>
+                format!("expect_list({}).and_then(|{}| {}.into_iter().map(|{}| {}).collect::<Result<Vec<_>, _>>())",
+                        expr, var, var, var, item.from_value(&var, depth + 1)),
+            Binding::Tuple(name) => format!("{}::try_from({})", name, expr),
+        }
+    }
+}
+
+struct BindingsGenerator {
+    type_names: BTreeSet<String>,
+    // the structs generated for tuples and arguments, in the order they're needed
+    declarations: Vec<String>,
+}
+
+impl BindingsGenerator {
+    fn new() -> BindingsGenerator {
+        BindingsGenerator {
+            type_names: IMPORTED_TYPE_NAMES.iter().map(|name| name.to_string()).collect(),
+            declarations: vec![]
+        }
+    }
+
+    // Name a tuple after where it appears, e.g., the `owner` field of `get-info`'s output is
+    //  `GetInfoOutputOwner`.
+    fn resolve(&mut self, atom: &ContractInterfaceAtomType, hint: &str) -> Binding {
+        match atom {
+            ContractInterfaceAtomType::none => Binding::Unit,
+            ContractInterfaceAtomType::int128 => Binding::Int,
+            ContractInterfaceAtomType::uint128 => Binding::UInt,
+            ContractInterfaceAtomType::bool => Binding::Bool,
+            ContractInterfaceAtomType::principal | ContractInterfaceAtomType::trait_reference => Binding::Principal,
+            ContractInterfaceAtomType::buffer { .. } => Binding::Buffer,
+            ContractInterfaceAtomType::string_ascii { .. } => Binding::StringASCII,
+            ContractInterfaceAtomType::string_utf8 { .. } => Binding::StringUTF8,
+            ContractInterfaceAtomType::optional(inner) => Binding::Optional(Box::new(self.resolve(inner, hint))),
+            ContractInterfaceAtomType::response { ok, error } =>
+                Binding::Response(Box::new(self.resolve(ok, &format!("{}Ok", hint))),
+                                  Box::new(self.resolve(error, &format!("{}Err", hint)))),
+            ContractInterfaceAtomType::list { type_f, .. } =>
+                Binding::List(Box::new(self.resolve(type_f, &format!("{}Item", hint)))),
+            ContractInterfaceAtomType::tuple(entries) => Binding::Tuple(self.declare_tuple(entries, hint)),
+        }
+    }
+
+    fn fields(&mut self, entries: &[(String, &ContractInterfaceAtomType)], hint: &str) -> Vec<(String, String, Binding)> {
+        let mut field_names = BTreeSet::new();
+        entries.iter()
+            .map(|(name, atom)| {
+                let field = unique_name(&mut field_names, snake_case(name));
+                let binding = self.resolve(atom, &format!("{}{}", hint, camel_case(name)));
+                (name.clone(), field, binding)
+            })
+            .collect()
+    }
+
+    fn declare_tuple(&mut self, entries: &[ContractInterfaceTupleEntryType], hint: &str) -> String {
+        let name = unique_name(&mut self.type_names, hint.to_string());
+        let entries: Vec<_> = entries.iter().map(|entry| (entry.name.clone(), &entry.type_f)).collect();
+        let fields = self.fields(&entries, &name);
+
+        let mut code = String::new();
+        code.push_str(&format!("#[derive(Debug, Clone, PartialEq)]\npub struct {} {{\n", name));
+        for (_, field, binding) in fields.iter() {
+            code.push_str(&format!("    pub {}: {},\n", field, binding.rust_type()));
+        }
+        code.push_str("}\n\n");
+
+        code.push_str(&format!("impl From<{}> for Value {{\n    fn from(tuple: {}) -> Value {{\n        tuple_value(vec![\n", name, name));
+        for (clarity_name, field, binding) in fields.iter() {
+            code.push_str(&format!("            (\"{}\", {}),\n", clarity_name, binding.to_value(&format!("tuple.{}", field), 0)));
+        }
+        code.push_str("        ])\n    }\n}\n\n");
+
+        code.push_str(&format!("impl TryFrom<Value> for {} {{\n    type Error = BindingError;\n\n", name));
+        code.push_str(&format!("    fn try_from(value: Value) -> Result<{}, BindingError> {{\n", name));
+        code.push_str("        let mut tuple = expect_tuple(value)?;\n");
+        code.push_str(&format!("        Ok({} {{\n", name));
+        for (clarity_name, field, binding) in fields.iter() {
+            code.push_str(&format!("            {}: {}?,\n", field,
+                                   binding.from_value(&format!("take_field(&mut tuple, \"{}\")?", clarity_name), 0)));
+        }
+        code.push_str("        })\n    }\n}\n");
+
+        self.declarations.push(code);
+        name
+    }
+
+    // Declare the struct of a function's arguments, returning its name.
+    fn declare_arguments(&mut self, function_name: &str, args: &[(String, &ContractInterfaceAtomType)]) -> String {
+        let name = unique_name(&mut self.type_names, format!("{}Args", camel_case(function_name)));
+        let fields = self.fields(args, &camel_case(function_name));
+
+        let mut code = format!("/// The arguments of `{}`.\n#[derive(Debug, Clone, PartialEq)]\npub struct {} {{\n", function_name, name);
+        for (_, field, binding) in fields.iter() {
+            code.push_str(&format!("    pub {}: {},\n", field, binding.rust_type()));
+        }
+        code.push_str("}\n\n");
+
+        code.push_str(&format!("impl From<{}> for Vec<Value> {{\n    fn from(args: {}) -> Vec<Value> {{\n        vec![\n", name, name));
+        for (_, field, binding) in fields.iter() {
+            code.push_str(&format!("            {},\n", binding.to_value(&format!("args.{}", field), 0)));
+        }
+        code.push_str("        ]\n    }\n}\n");
+
+        self.declarations.push(code);
+        name
+    }
+}
+
+/// Generate Rust bindings for calling the contract with the given interface.  Private functions
+///  can't be called from outside the contract, so they're left out.
+pub fn generate_bindings(contract_name: &str, interface: &ContractInterface) -> String {
+    let mut generator = BindingsGenerator::new();
+    let contract_type = unique_name(&mut generator.type_names, camel_case(contract_name));
+    let mut method_names: BTreeSet<String> = vec!["new".to_string()].into_iter().collect();
+    let mut methods = vec![];
+    let mut any_read_only = false;
+    let mut any_tuples = false;
+
+    for function in interface.functions.iter() {
+        let read_only = match function.access {
+            ContractInterfaceFunctionAccess::private => continue,
+            ContractInterfaceFunctionAccess::public => false,
+            ContractInterfaceFunctionAccess::read_only => true,
+        };
+
+        // every method for the function gets the same number, if one is needed
+        let mut method = snake_case(&function.name);
+        let mut count = 1;
+        while [method.clone(), format!("decode_{}", method), format!("{}_request", method), format!("{}_response", method)]
+            .iter().any(|name| method_names.contains(name)) {
+            count += 1;
+            method = format!("{}{}", snake_case(&function.name), count);
+        }
+        for name in [method.clone(), format!("decode_{}", method), format!("{}_request", method), format!("{}_response", method)].iter() {
+            method_names.insert(name.clone());
+        }
+
+        let args: Vec<_> = function.args.iter().map(|arg| (arg.name.clone(), &arg.type_f)).collect();
+        let (params, arg_values) = if args.is_empty() {
+            ("".to_string(), "vec![]".to_string())
+        } else {
+            let args_type = generator.declare_arguments(&function.name, &args);
+            (format!(", args: {}", args_type), "args.into()".to_string())
+        };
+        let output = generator.resolve(&function.outputs.type_f, &format!("{}Output", camel_case(&function.name)));
+        let output_type = output.rust_type();
+
+        let mut code = format!(
+"    /// A call of `{name}`, to be signed into a transaction.
+    pub fn {method}(&self{params}) -> TransactionPayload {{
+        contract_call(&self.address, &self.contract_name, \"{name}\", {arg_values})
+    }}
+
+    /// Decode the value returned by `{name}`.
+    pub fn decode_{method}(value: Value) -> Result<{output_type}, BindingError> {{
+        {decode}
+    }}
+", name = function.name, method = method, params = params, arg_values = arg_values,
+   output_type = output_type, decode = output.from_value("value", 0));
+
+        if read_only {
+            any_read_only = true;
+            code.push_str(&format!(
+"
+    /// The path and body of a `/v2/contracts/call-read` request evaluating `{name}`.
+    pub fn {method}_request(&self, sender: &PrincipalData{params}) -> (String, CallReadOnlyRequestBody) {{
+        read_only_request(&self.address, &self.contract_name, \"{name}\", sender, {arg_values})
+    }}
+
+    /// Decode the response to a `{method}_request`.
+    pub fn {method}_response(response: &CallReadOnlyResponse) -> Result<{output_type}, BindingError> {{
+        Self::decode_{method}(read_only_result(response)?)
+    }}
+", name = function.name, method = method, params = params, arg_values = arg_values, output_type = output_type));
+        }
+        methods.push(code);
+    }
+
+    any_tuples |= generator.declarations.iter().any(|declaration| declaration.contains("impl TryFrom<Value>"));
+
+    // only what's used is imported, so that the bindings compile without warnings
+    let mut code = format!(
+"// Bindings for the `{contract_name}` contract, generated from its interface by
+//  `clarity-cli generate_bindings`.  Regenerate them rather than editing them.
+
+{try_from}use blockstack_lib::chainstate::stacks::{{StacksAddress, TransactionPayload}};
+{net}use blockstack_lib::vm::bindings::*;
+use blockstack_lib::vm::types::{{PrincipalData, Value}};
+use blockstack_lib::vm::ContractName;
+
+/// The `{contract_name}` contract, as deployed by `address` under `contract_name`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct {contract_type} {{
+    pub address: StacksAddress,
+    pub contract_name: ContractName,
+}}
+
+impl {contract_type} {{
+    pub fn new(address: StacksAddress, contract_name: ContractName) -> {contract_type} {{
+        {contract_type} {{ address, contract_name }}
+    }}
+", contract_name = contract_name, contract_type = contract_type,
+   try_from = if any_tuples { "use std::convert::TryFrom;\n\n" } else { "" },
+   net = if any_read_only { "use blockstack_lib::net::{CallReadOnlyRequestBody, CallReadOnlyResponse};\n" } else { "" });
+
+    for method in methods.iter() {
+        code.push('\n');
+        code.push_str(method);
+    }
+    code.push_str("}\n");
+
+    for declaration in generator.declarations.iter() {
+        code.push('\n');
+        code.push_str(declaration);
+    }
+    code
+}
@@ -0,0 +1,124 @@
+use chainstate::stacks::{StacksAddress, TransactionPayload};
+use net::CallReadOnlyResponse;
+use util::hash::Hash160;
+use vm::analysis::contract_interface_builder::{build_contract_interface, ContractInterface};
+use vm::analysis::type_check;
+use vm::ast::parse;
+use vm::bindings::*;
+use vm::database::{ClaritySerializable, MemoryBackingStore};
+use vm::representations::ContractName;
+use vm::types::{PrincipalData, QualifiedContractIdentifier, Value};
+
+fn interface_of(contract: &str) -> ContractInterface {
+    let contract_identifier = QualifiedContractIdentifier::local("bound").unwrap();
+    let mut expressions = parse(&contract_identifier, contract).unwrap();
+    let mut marf = MemoryBackingStore::new();
+    let mut db = marf.as_analysis_db();
+    let contract_analysis = type_check(&contract_identifier, &mut expressions, &mut db, false).unwrap();
+    build_contract_interface(&contract_analysis)
+}
+
+#[test]
+fn test_generate_bindings() {
+    let contract = "(define-map balances ((owner principal)) ((amount uint)))
+(define-public (transfer (amount uint) (recipient principal) (memo (optional (buff 34))))
+  (if (> amount u0) (ok true) (err u1)))
+(define-read-only (get-info (who principal))
+  (ok {owner: who, names: (list a\"a\" a\"b\"), balance: u0}))
+(define-private (helper) 1)
+(define-read-only (is-ok!) false)
+(define-read-only (is-ok?) true)";
+
+    let bindings = generate_bindings("token-registry", &interface_of(contract));
+
+    assert!(bindings.contains("pub struct TokenRegistry {\n    pub address: StacksAddress,\n    pub contract_name: ContractName,\n}"));
+    assert!(bindings.contains("use std::convert::TryFrom;"));
+    assert!(bindings.contains("use blockstack_lib::net::{CallReadOnlyRequestBody, CallReadOnlyResponse};"));
+
+    // arguments, in order, with their conversions
+    assert!(bindings.contains("pub struct TransferArgs {
+    pub amount: u128,
+    pub recipient: PrincipalData,
+    pub memo: Option<Vec<u8>>,
+}"));
+    assert!(bindings.contains("            Value::UInt(args.amount),
+            Value::Principal(args.recipient),
+            optional_value(args.memo.map(|v0| buffer_value(v0))),"));
+    assert!(bindings.contains("pub fn transfer(&self, args: TransferArgs) -> TransactionPayload {
+        contract_call(&self.address, &self.contract_name, \"transfer\", args.into())"));
+    assert!(bindings.contains("pub fn decode_transfer(value: Value) -> Result<Result<bool, u128>, BindingError> {
+        expect_response(value).and_then(|v0| match v0 { Ok(v0) => expect_bool(v0).map(Ok), Err(v0) => expect_uint(v0).map(Err) })"));
+
+    // tuples become structs, named after where they're found
+    assert!(bindings.contains("pub struct GetInfoOutputOk {
+    pub balance: u128,
+    pub names: Vec<String>,
+    pub owner: PrincipalData,
+}"));
+    assert!(bindings.contains("            names: expect_list(take_field(&mut tuple, \"names\")?)\
+.and_then(|v0| v0.into_iter().map(|v0| expect_string_ascii(v0)).collect::<Result<Vec<_>, _>>())?,"));
+    assert!(bindings.contains("pub fn decode_get_info(value: Value) -> Result<Result<GetInfoOutputOk, ()>, BindingError>"));
+
+    // read-only functions can also be evaluated by a node
+    assert!(bindings.contains("pub fn get_info_request(&self, sender: &PrincipalData, args: GetInfoArgs) -> (String, CallReadOnlyRequestBody)"));
+    assert!(bindings.contains("pub fn get_info_response(response: &CallReadOnlyResponse) -> Result<Result<GetInfoOutputOk, ()>, BindingError>"));
+    assert!(!bindings.contains("transfer_request"));
+
+    // private functions can't be called, and names which clash are numbered
+    assert!(!bindings.contains("helper"));
+    assert!(bindings.contains("pub fn is_ok(&self) -> TransactionPayload {
+        contract_call(&self.address, &self.contract_name, \"is-ok!\", vec![])"));
+    assert!(bindings.contains("pub fn is_ok2(&self) -> TransactionPayload {
+        contract_call(&self.address, &self.contract_name, \"is-ok?\", vec![])"));
+}
+
+#[test]
+fn test_fixture_is_current() {
+    // tests/bindings.rs compiles these; regenerate them with
+    //  `clarity-cli generate_bindings tests/fixtures/token_registry.clar`
+    let interface = interface_of(include_str!("../../../tests/fixtures/token_registry.clar"));
+    assert_eq!(generate_bindings("token_registry", &interface),
+               include_str!("../../../tests/fixtures/token_registry_bindings.rs"));
+}
+
+#[test]
+fn test_minimal_imports() {
+    let bindings = generate_bindings("counter", &interface_of("(define-public (increment (by int)) (ok by))"));
+    assert!(!bindings.contains("TryFrom"));
+    assert!(!bindings.contains("CallReadOnly"));
+    assert!(bindings.contains("pub fn decode_increment(value: Value) -> Result<Result<i128, ()>, BindingError>"));
+}
+
+#[test]
+fn test_helpers() {
+    let address = StacksAddress { version: 26, bytes: Hash160([1; 20]) };
+    let contract_name = ContractName::from("counter");
+
+    match contract_call(&address, &contract_name, "increment", vec![Value::Int(1)]) {
+        TransactionPayload::ContractCall(call) => {
+            assert_eq!(call.address, address);
+            assert_eq!(call.function_name.as_str(), "increment");
+            assert_eq!(call.function_args, vec![Value::Int(1)]);
+        },
+        _ => panic!("Expected a contract call")
+    }
+
+    let sender = PrincipalData::from(address);
+    let (path, body) = read_only_request(&address, &contract_name, "get-count", &sender, vec![Value::UInt(2)]);
+    assert_eq!(path, format!("/v2/contracts/call-read/{}/counter/get-count", address));
+    assert_eq!(body.sender, sender.to_string());
+    assert_eq!(body.arguments, vec![Value::UInt(2).serialize()]);
+
+    let okay = CallReadOnlyResponse { okay: true, result: Some(Value::UInt(3).serialize()), cause: None };
+    assert_eq!(read_only_result(&okay), Ok(Value::UInt(3)));
+    let failed = CallReadOnlyResponse { okay: false, result: None, cause: Some("Unchecked(NoSuchContract)".into()) };
+    assert_eq!(read_only_result(&failed), Err(BindingError::CallFailed("Unchecked(NoSuchContract)".into())));
+
+    let mut tuple = expect_tuple(tuple_value(vec![("name", string_utf8_value("caf\u{e9}".into()))])).unwrap();
+    assert_eq!(expect_string_utf8(take_field(&mut tuple, "name").unwrap()), Ok("caf\u{e9}".to_string()));
+    assert_eq!(take_field(&mut tuple, "name"), Err(BindingError::MissingField("name".into())));
+
+    assert_eq!(expect_response(response_value(Err(Value::Int(1)))), Ok(Err(Value::Int(1))));
+    assert_eq!(expect_optional(optional_value(None)), Ok(None));
+    assert_eq!(expect_int(Value::UInt(1)), Err(BindingError::UnexpectedValue(Value::UInt(1))));
+}
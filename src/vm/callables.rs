@@ -128,11 +128,16 @@ impl DefinedFunction {
             }
         }
 
+        if let Some(hook) = env.global_context.eval_hook.take() {
+            hook.will_call_function(env, &context, &self.name, args);
+            env.global_context.eval_hook = Some(hook);
+        }
+
         let result = eval(&self.body, env, &context);
 
         // if the error wasn't actually an error, but a function return,
         //    pull that out and return it.
-        match result {
+        let result = match result {
             Ok(r) => Ok(r),
             Err(e) => {
                 match e {
@@ -140,7 +145,14 @@ impl DefinedFunction {
                     _ => Err(e)
                 }
             }
+        };
+
+        if let Some(hook) = env.global_context.eval_hook.take() {
+            hook.did_return_from_function(env, &self.name, &result);
+            env.global_context.eval_hook = Some(hook);
         }
+
+        result
     }
 
     pub fn check_trait_expectations(&self, 
@@ -9,6 +9,7 @@ use vm::analysis::{ContractAnalysis, errors::CheckError, errors::CheckErrors};
 use vm::ast;
 use vm::analysis;
 use vm::costs::{LimitedCostTracker, ExecutionCost, CostTracker};
+use vm::profiler::{CostProfiler, CostProfile};
 
 use chainstate::burn::BlockHeaderHash;
 use chainstate::stacks::StacksBlockId;
@@ -35,6 +36,7 @@ use std::fmt;
 pub struct ClarityInstance {
     datastore: Option<MarfedKV>,
     block_limit: ExecutionCost,
+    profile_costs: bool,
 }
 
 ///
@@ -56,7 +58,9 @@ pub struct ClarityTransactionConnection<'a> {
     log: Option<RollbackWrapperPersistedLog>,
     store: &'a mut MarfedKV,
     header_db: &'a dyn HeadersDB,
-    cost_track: &'a mut Option<LimitedCostTracker>
+    cost_track: &'a mut Option<LimitedCostTracker>,
+    profile_costs: bool,
+    cost_profile: Option<CostProfile>,
 }
 
 pub struct ClarityReadOnlyConnection<'a> {
@@ -169,7 +173,13 @@ impl ClarityBlockConnection<'_> {
 
 impl ClarityInstance {
     pub fn new(datastore: MarfedKV, block_limit: ExecutionCost) -> ClarityInstance {
-        ClarityInstance { datastore: Some(datastore), block_limit }
+        ClarityInstance { datastore: Some(datastore), block_limit, profile_costs: false }
+    }
+
+    /// Attribute the cost of each contract call and contract initialization in the blocks
+    ///  processed from now on to the functions that incurred it.  Off by default.
+    pub fn set_cost_profiling(&mut self, enabled: bool) {
+        self.profile_costs = enabled;
     }
 
     pub fn with_marf<F, R> (&mut self, f: F) -> R
@@ -246,6 +256,21 @@ impl ClarityInstance {
             .map_err(Error::from)
     }
 
+    /// Is the (opt-in) index of data map keys maintained?
+    pub fn has_map_key_index(&self) -> bool {
+        let datastore = self.datastore.as_ref()
+            .expect("FAIL: use of has_map_key_index while prior block neither committed nor rolled back.");
+        datastore.has_map_key_index()
+    }
+
+    /// Start maintaining the (opt-in) index of data map keys, which allows the live keys of a
+    ///   contract's data maps to be enumerated.  Only keys written from now on are indexed.
+    pub fn enable_map_key_index(&mut self) -> Result<(), InterpreterError> {
+        let datastore = self.datastore.as_mut()
+            .expect("FAIL: use of enable_map_key_index while prior block neither committed nor rolled back.");
+        datastore.enable_map_key_index()
+    }
+
     pub fn destroy(mut self) -> MarfedKV {
         let datastore = self.datastore.take()
             .expect("FAIL: attempt to recover database connection from clarity instance which is still open");
@@ -327,6 +352,26 @@ impl <'a> ClarityReadOnlyConnection <'a> {
     pub fn done(self) {
         self.parent.datastore.replace(self.datastore);
     }
+
+    /// Run a transaction against this chain tip, charging its execution to the given cost
+    ///   tracker.  Its changes are only ever kept in its edit log, which is thrown away
+    ///   when the transaction is dropped.
+    pub fn with_simulated_transaction <F, R> (&mut self, cost_track: LimitedCostTracker, todo: F) -> R
+    where F: FnOnce(&mut ClarityTransactionConnection) -> R {
+        self.datastore.begin_simulation();
+        let mut cost_track = Some(cost_track);
+        let mut log = RollbackWrapperPersistedLog::new();
+        log.nest();
+        let result = {
+            let mut tx = ClarityTransactionConnection {
+                store: &mut self.datastore, cost_track: &mut cost_track, header_db: self.header_db,
+                log: Some(log), profile_costs: false, cost_profile: None
+            };
+            todo(&mut tx)
+        };
+        self.datastore.end_simulation();
+        result
+    }
 }
 
 impl <'a> ClarityBlockConnection <'a> {
@@ -415,10 +460,11 @@ impl <'a> ClarityBlockConnection <'a> {
         let store = &mut self.datastore;
         let cost_track = &mut self.cost_track;
         let header_db = &self.header_db;
+        let profile_costs = self.parent.profile_costs;
         let mut log = RollbackWrapperPersistedLog::new();
         log.nest();
         ClarityTransactionConnection {
-            store, cost_track, header_db, log: Some(log)
+            store, cost_track, header_db, log: Some(log), profile_costs, cost_profile: None
         }
     }
 
@@ -546,15 +592,25 @@ impl <'a> ClarityTransactionConnection <'a> {
     fn with_abort_callback<F, A, R>(&mut self, to_do: F, abort_call_back: A) -> Result<(R, AssetMap, Vec<StacksTransactionEvent>, bool), Error>
     where A: FnOnce(&AssetMap, &mut ClarityDatabase) -> bool,
           F: FnOnce(&mut OwnedEnvironment) -> Result<(R, AssetMap, Vec<StacksTransactionEvent>), Error> {
-        using!(self.log, "log", |log| {
-            using!(self.cost_track, "cost tracker", |cost_track| {
+        let (result, cost_profile) = using!(self.log, "log", |log| {
+            using!(self.cost_track, "cost tracker", |cost_track: LimitedCostTracker| {
                 let rollback_wrapper = RollbackWrapper::from_persisted_log(self.store, log);
                 let mut db = ClarityDatabase::new_with_rollback_wrapper(rollback_wrapper, &self.header_db);
 
                 // wrap the whole contract-call in a claritydb transaction,
                 //   so we can abort on call_back's boolean retun
                 db.begin();
+                let cost_before = cost_track.get_total();
+                // with cost profiling on, attribute the cost of the transaction to what incurred it
+                let mut profiler = if self.profile_costs {
+                    Some(CostProfiler::new())
+                } else {
+                    None
+                };
                 let mut vm_env = OwnedEnvironment::new_cost_limited(db, cost_track);
+                if let Some(ref mut profiler) = profiler {
+                    vm_env.set_eval_hook(profiler);
+                }
                 let result = to_do(&mut vm_env);
                 let (mut db, cost_track) = vm_env.destruct()
                     .expect("Failed to recover database reference after executing transaction");
@@ -575,13 +631,29 @@ impl <'a> ClarityTransactionConnection <'a> {
                         Err(e)
                     }
                 };
+                let edit_log : RollbackWrapperPersistedLog = db.destroy().into();
 
-                (cost_track, (db.destroy().into(), result))
+                let cost_profile : Option<CostProfile> = profiler.map(|profiler| {
+                    let mut cost = cost_track.get_total();
+                    cost.sub(&cost_before).expect("BUG: total block cost decreased");
+                    profiler.into_profile(cost)
+                });
+
+                (cost_track, (edit_log, (result, cost_profile)))
             })
-        })
+        });
+
+        self.cost_profile = cost_profile;
+        result
     }
     
 
+    /// Take the cost profile of the last contract call or initialization run in this
+    ///  transaction.  Profiles are only made when cost profiling is enabled on the ClarityInstance.
+    pub fn take_cost_profile(&mut self) -> Option<CostProfile> {
+        self.cost_profile.take()
+    }
+
     /// Save a contract analysis output to the AnalysisDatabase
     /// An error here would indicate that something has gone terribly wrong in the processing of a contract insert.
     ///   the caller should likely abort the whole block or panic
@@ -688,8 +760,8 @@ impl <'a> ClarityTransactionConnection <'a> {
 mod tests {
     use super::*;
     use vm::analysis::errors::CheckErrors;
-    use vm::types::{Value, StandardPrincipalData};
-    use vm::database::{NULL_HEADER_DB, ClarityBackingStore, MarfedKV};
+    use vm::types::{Value, StandardPrincipalData, TupleData};
+    use vm::database::{NULL_HEADER_DB, ClarityBackingStore, ClaritySerializable, MarfedKV};
     use chainstate::stacks::index::storage::{TrieFileStorage};
     use rusqlite::NO_PARAMS;
     use std::fs;
@@ -845,6 +917,155 @@ mod tests {
                    .query_row::<u32,_,_>("SELECT COUNT(value) FROM data_table", NO_PARAMS, |row| row.get(0)).unwrap());
     }
 
+    #[test]
+    pub fn test_cost_profiling() {
+        let marf = MarfedKV::temporary();
+        let mut clarity_instance = ClarityInstance::new(marf, ExecutionCost::max_value());
+        let contract_identifier = QualifiedContractIdentifier::local("foo").unwrap();
+        let sender: PrincipalData = StandardPrincipalData::transient().into();
+        let contract = "(define-public (foo (x int)) (ok (+ x x)))";
+
+        fn call_foo(conn: &mut ClarityBlockConnection, sender: &PrincipalData,
+                    contract_identifier: &QualifiedContractIdentifier) -> Option<CostProfile> {
+            conn.as_transaction(|tx| {
+                tx.run_contract_call(sender, contract_identifier, "foo", &[Value::Int(1)], |_, _| false).unwrap();
+                tx.take_cost_profile()
+            })
+        }
+
+        {
+            let mut conn = clarity_instance.begin_block(&StacksBlockId::sentinel(),
+                                                        &StacksBlockId([0 as u8; 32]),
+                                                        &NULL_HEADER_DB);
+            conn.as_transaction(|conn| {
+                let (ct_ast, ct_analysis) = conn.analyze_smart_contract(&contract_identifier, &contract).unwrap();
+                conn.initialize_smart_contract(
+                    &contract_identifier, &ct_ast, &contract, |_,_| false).unwrap();
+                conn.save_analysis(&contract_identifier, &ct_analysis).unwrap();
+            });
+
+            // off by default
+            assert!(call_foo(&mut conn, &sender, &contract_identifier).is_none());
+            conn.commit_block();
+        }
+
+        clarity_instance.set_cost_profiling(true);
+
+        {
+            let mut conn = clarity_instance.begin_block(&StacksBlockId([0 as u8; 32]),
+                                                        &StacksBlockId([1 as u8; 32]),
+                                                        &NULL_HEADER_DB);
+            let profile = call_foo(&mut conn, &sender, &contract_identifier).unwrap();
+            assert!(profile.entries.len() > 0);
+            assert!(profile.total.runtime > 0);
+            conn.commit_block();
+        }
+    }
+
+    #[test]
+    pub fn test_map_key_index() {
+        let marf = MarfedKV::temporary();
+        let mut clarity_instance = ClarityInstance::new(marf, ExecutionCost::max_value());
+        clarity_instance.enable_map_key_index().unwrap();
+
+        let contract_identifier = QualifiedContractIdentifier::local("foo").unwrap();
+        let contract = "
+        (define-map things ((k int)) ((v int)))
+        (define-public (set-thing (k int)) (begin (map-set things ((k k)) ((v k))) (ok true)))
+        (define-public (delete-thing (k int)) (begin (map-delete things ((k k))) (ok true)))";
+
+        fn call(conn: &mut ClarityBlockConnection, function: &str, k: i128) {
+            let sender: PrincipalData = StandardPrincipalData::transient().into();
+            let contract_identifier = QualifiedContractIdentifier::local("foo").unwrap();
+            conn.as_transaction(|tx| tx.run_contract_call(&sender, &contract_identifier, function, &[Value::Int(k)],
+                                                          |_, _| false)).unwrap();
+        }
+
+        {
+            let mut conn = clarity_instance.begin_block(&StacksBlockId::sentinel(),
+                                                        &StacksBlockId([0 as u8; 32]),
+                                                        &NULL_HEADER_DB);
+            conn.as_transaction(|conn| {
+                let (ct_ast, ct_analysis) = conn.analyze_smart_contract(&contract_identifier, &contract).unwrap();
+                conn.initialize_smart_contract(
+                    &contract_identifier, &ct_ast, &contract, |_,_| false).unwrap();
+                conn.save_analysis(&contract_identifier, &ct_analysis).unwrap();
+            });
+            for k in 1..4 {
+                call(&mut conn, "set-thing", k);
+            }
+            conn.commit_block();
+        }
+
+        {
+            let mut conn = clarity_instance.begin_block(&StacksBlockId([0 as u8; 32]),
+                                                        &StacksBlockId([1 as u8; 32]),
+                                                        &NULL_HEADER_DB);
+            call(&mut conn, "delete-thing", 2);
+            call(&mut conn, "set-thing", 4);
+            conn.commit_block();
+        }
+
+        // a sibling fork
+        {
+            let mut conn = clarity_instance.begin_block(&StacksBlockId([0 as u8; 32]),
+                                                        &StacksBlockId([2 as u8; 32]),
+                                                        &NULL_HEADER_DB);
+            call(&mut conn, "set-thing", 5);
+            conn.commit_block();
+        }
+
+        let key = |k: i128| Value::from(TupleData::from_data(vec![("k".into(), Value::Int(k))]).unwrap()).serialize();
+        let mut get_keys = |tip: u8, after: Option<String>, count: u32, max_scanned: u32| {
+            let mut conn = clarity_instance.read_only_connection(&StacksBlockId([tip; 32]), &NULL_HEADER_DB);
+            let page = conn.with_clarity_db_readonly(|db| db.get_map_keys(&contract_identifier, "things", after.as_ref().map(|k| k.as_str()), count, max_scanned))
+                .unwrap()
+                .unwrap();
+            conn.done();
+            page
+        };
+
+        assert_eq!(get_keys(0, None, 10, 100), (vec![key(1), key(2), key(3)], None));
+        assert_eq!(get_keys(1, None, 10, 100), (vec![key(1), key(3), key(4)], None));
+        assert_eq!(get_keys(2, None, 10, 100), (vec![key(1), key(2), key(3), key(5)], None));
+
+        // paging skips over keys that aren't live at the tip
+        assert_eq!(get_keys(1, None, 2, 100), (vec![key(1), key(3)], Some(key(3))));
+        assert_eq!(get_keys(1, Some(key(3)), 2, 100), (vec![key(4)], None));
+        assert_eq!(get_keys(1, Some(key(4)), 2, 100), (vec![], None));
+
+        // scanning stops at the limit, even if that leaves the page short
+        assert_eq!(get_keys(1, None, 10, 2), (vec![key(1)], Some(key(2))));
+        assert_eq!(get_keys(1, Some(key(2)), 10, 2), (vec![key(3), key(4)], Some(key(4))));
+        assert_eq!(get_keys(1, Some(key(4)), 10, 2), (vec![], None));
+
+        // keys written by an aborted transaction or a rolled-back block are never indexed
+        {
+            let mut conn = clarity_instance.begin_block(&StacksBlockId([1 as u8; 32]),
+                                                        &StacksBlockId([3 as u8; 32]),
+                                                        &NULL_HEADER_DB);
+            let sender: PrincipalData = StandardPrincipalData::transient().into();
+            let err = conn.as_transaction(|tx| tx.run_contract_call(&sender, &contract_identifier, "set-thing", &[Value::Int(6)],
+                                                                    |_, _| true)).unwrap_err();
+            assert!(match err {
+                Error::AbortedByCallback(..) => true,
+                _ => false
+            });
+            conn.commit_block();
+        }
+        {
+            let mut conn = clarity_instance.begin_block(&StacksBlockId([1 as u8; 32]),
+                                                        &StacksBlockId([4 as u8; 32]),
+                                                        &NULL_HEADER_DB);
+            call(&mut conn, "set-thing", 7);
+            conn.rollback_block();
+        }
+
+        let mut marf = clarity_instance.destroy();
+        assert_eq!(marf.get_side_store().get_indexed_map_keys(&contract_identifier.to_string(), "things", None, 10),
+                   Some(vec![key(1), key(2), key(3), key(4), key(5)]));
+    }
+
     #[test]
     fn test_unconfirmed() {
         let test_name = "/tmp/clarity_test_unconfirmed";
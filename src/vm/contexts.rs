@@ -12,6 +12,7 @@ use vm::contracts::Contract;
 use vm::ast::ContractAST;
 use vm::costs::{CostTracker, ExecutionCost, LimitedCostTracker, cost_functions, CostErrors};
 use vm::ast;
+use vm::hooks::EvalHook;
 use vm::{eval, is_reserved};
 
 use chainstate::burn::{VRFSeed, BlockHeaderHash};
@@ -77,6 +78,7 @@ pub struct GlobalContext<'a> {
     pub database: ClarityDatabase<'a>,
     read_only: Vec<bool>,
     pub cost_track: LimitedCostTracker,
+    pub eval_hook: Option<&'a mut dyn EvalHook>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -188,6 +190,16 @@ impl AssetMap {
         Ok(())
     }
 
+    // Burned tokens leave the owner's hands just like transferred ones, so they are
+    //   tallied alongside transfers and checked by the same post-conditions.
+    pub fn add_asset_burn(&mut self, principal: &PrincipalData, asset: AssetIdentifier, burned: Value) {
+        self.add_asset_transfer(principal, asset, burned)
+    }
+
+    pub fn add_token_burn(&mut self, principal: &PrincipalData, asset: AssetIdentifier, amount: u128) -> Result<()> {
+        self.add_token_transfer(principal, asset, amount)
+    }
+
     // This will add any asset transfer data from other to self,
     //   aborting _all_ changes in the event of an error, leaving self unchanged
     pub fn commit_other(&mut self, mut other: AssetMap) -> Result<()> {
@@ -462,6 +474,16 @@ impl <'a> OwnedEnvironment <'a> {
                             |exec_env| exec_env.eval_read_only(contract, program))
     }
 
+    /// Lend an evaluation hook to this environment, which will be called for every
+    ///  expression it evaluates from now on.
+    pub fn set_eval_hook(&mut self, hook: &'a mut dyn EvalHook) {
+        self.context.eval_hook = Some(hook);
+    }
+
+    pub fn get_cost_total(&self) -> ExecutionCost {
+        self.context.cost_track.get_total()
+    }
+
     pub fn begin(&mut self) {
         self.context.begin();
     }
@@ -791,6 +813,19 @@ impl <'a,'b> Environment <'a,'b> {
         Ok(())
     }
 
+    pub fn register_nft_burn_event(&mut self, sender: PrincipalData, value: Value, asset_identifier: AssetIdentifier) -> Result<()> {
+        let event_data = NFTBurnEventData {
+            sender,
+            asset_identifier,
+            value
+        };
+
+        if let Some(batch) = self.global_context.event_batches.last_mut() {
+            batch.events.push(StacksTransactionEvent::NFTEvent(NFTEventType::NFTBurnEvent(event_data)));
+        }
+        Ok(())
+    }
+
     pub fn register_ft_transfer_event(&mut self, sender: PrincipalData, recipient: PrincipalData, amount: u128, asset_identifier: AssetIdentifier) -> Result<()> {
         let event_data = FTTransferEventData {
             sender,
@@ -817,6 +852,19 @@ impl <'a,'b> Environment <'a,'b> {
         }
         Ok(())
     }
+
+    pub fn register_ft_burn_event(&mut self, sender: PrincipalData, amount: u128, asset_identifier: AssetIdentifier) -> Result<()> {
+        let event_data = FTBurnEventData {
+            sender,
+            asset_identifier,
+            amount
+        };
+
+        if let Some(batch) = self.global_context.event_batches.last_mut() {
+            batch.events.push(StacksTransactionEvent::FTEvent(FTEventType::FTBurnEvent(event_data)));
+        }
+        Ok(())
+    }
 }
 
 impl <'a> GlobalContext<'a> {
@@ -828,6 +876,7 @@ impl <'a> GlobalContext<'a> {
             read_only: Vec::new(),
             asset_maps: Vec::new(),
             event_batches: Vec::new(),
+            eval_hook: None,
         }
     }
 
@@ -854,6 +903,20 @@ impl <'a> GlobalContext<'a> {
             .add_token_transfer(sender, asset_identifier, transfered)
     }
 
+    pub fn log_asset_burn(&mut self, sender: &PrincipalData, contract_identifier: &QualifiedContractIdentifier, asset_name: &ClarityName, burned: Value) {
+        let asset_identifier = AssetIdentifier { contract_identifier: contract_identifier.clone(),
+                                                 asset_name: asset_name.clone() };
+        self.get_asset_map()
+            .add_asset_burn(sender, asset_identifier, burned)
+    }
+
+    pub fn log_token_burn(&mut self, sender: &PrincipalData, contract_identifier: &QualifiedContractIdentifier, asset_name: &ClarityName, burned: u128) -> Result<()> {
+        let asset_identifier = AssetIdentifier { contract_identifier: contract_identifier.clone(),
+                                                 asset_name: asset_name.clone() };
+        self.get_asset_map()
+            .add_token_burn(sender, asset_identifier, burned)
+    }
+
     pub fn log_stx_transfer(&mut self, sender: &PrincipalData, transfered: u128) -> Result<()> {
         self.get_asset_map()
             .add_stx_transfer(sender, transfered)
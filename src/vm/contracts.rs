@@ -1,8 +1,11 @@
+use std::collections::BTreeMap;
 use std::convert::TryInto;
 use vm::{Value, apply, eval_all};
 use vm::representations::{SymbolicExpression};
 use vm::errors::{InterpreterResult as Result};
-use vm::callables::CallableType;
+use vm::callables::{CallableType, DefinedFunction, DefineType};
+use vm::functions::define::DefineFunctionsParsed;
+use vm::types::signatures::parse_name_type_pairs;
 use vm::contexts::{Environment, LocalContext, ContractContext, GlobalContext};
 use vm::ast::ContractAST;
 use vm::types::QualifiedContractIdentifier;
@@ -23,4 +26,47 @@ impl Contract {
         Ok(Contract { contract_context: contract_context })
     }
 
+    /// Are this contract's functions exactly the ones defined in the given AST?  Used to check a
+    ///   contract context that came from somewhere untrusted against the contract's source.
+    pub fn defines_functions_of(&self, contract: &ContractAST) -> bool {
+        let context_name = self.contract_context.contract_identifier.to_string();
+        let mut expected = BTreeMap::new();
+        for expression in contract.expressions.iter() {
+            let (signature, body, define_type) = match DefineFunctionsParsed::try_parse(expression) {
+                Ok(Some(DefineFunctionsParsed::PrivateFunction { signature, body })) => (signature, body, DefineType::Private),
+                Ok(Some(DefineFunctionsParsed::ReadOnlyFunction { signature, body })) => (signature, body, DefineType::ReadOnly),
+                Ok(Some(DefineFunctionsParsed::PublicFunction { signature, body })) => (signature, body, DefineType::Public),
+                Ok(_) => continue,
+                Err(_) => return false
+            };
+
+            let (function_name, arguments) = match signature.split_first() {
+                Some((function_name, arguments)) => match function_name.match_atom() {
+                    Some(function_name) => (function_name, arguments),
+                    None => return false
+                },
+                None => return false
+            };
+            let arguments = match parse_name_type_pairs(arguments, &mut ()) {
+                Ok(arguments) => arguments,
+                Err(_) => return false
+            };
+
+            let function = DefinedFunction::new(arguments, body.clone(), define_type, function_name, &context_name);
+            expected.insert(function_name.to_string(), serialize_function(&function));
+        }
+
+        let actual : BTreeMap<_, _> = self.contract_context.functions.iter()
+            .map(|(function_name, function)| (function_name.to_string(), serialize_function(function)))
+            .collect();
+
+        expected == actual
+    }
+
+}
+
+// DefinedFunction has no PartialEq, so functions are compared by their serializations
+fn serialize_function(function: &DefinedFunction) -> String {
+    serde_json::to_string(function)
+        .expect("Failed to serialize DefinedFunction")
 }
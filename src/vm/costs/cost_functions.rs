@@ -134,6 +134,13 @@ def_runtime_cost!(APPEND { Linear(1, 1) });
 def_runtime_cost!(CONCAT { Linear(1, 1) });
 def_runtime_cost!(AS_MAX_LEN { Constant(1) });
 
+// strings are rebuilt (and string-ascii re-validated) when appended to or concatenated,
+//  so these are charged on the size of the whole string, not just what's added to it
+def_runtime_cost!(STRING_ASCII_APPEND { Linear(1, 1) });
+def_runtime_cost!(STRING_UTF8_APPEND { Linear(1, 1) });
+def_runtime_cost!(STRING_ASCII_CONCAT { Linear(1, 1) });
+def_runtime_cost!(STRING_UTF8_CONCAT { Linear(1, 1) });
+
 def_runtime_cost!(CONTRACT_CALL { Constant(1) });
 def_runtime_cost!(CONTRACT_OF { Constant(1) });
 
@@ -287,4 +294,25 @@ pub const NFT_OWNER: SimpleCostSpecification = SimpleCostSpecification {
     read_count: Constant(1),
     read_length: Constant(1) };
 
+pub const FT_BURN: SimpleCostSpecification = SimpleCostSpecification {
+    write_length: Constant(1),
+    write_count: Constant(2),
+    runtime: Constant(1),
+    read_count: Constant(2),
+    read_length: Constant(1) };
+
+pub const FT_SUPPLY: SimpleCostSpecification = SimpleCostSpecification {
+    write_length: Constant(0),
+    write_count: Constant(0),
+    runtime: Constant(1),
+    read_count: Constant(1),
+    read_length: Constant(1) };
+
+pub const NFT_BURN: SimpleCostSpecification = SimpleCostSpecification {
+    write_length: Constant(1),
+    write_count: Constant(1),
+    runtime: Linear(1, 1),
+    read_count: Constant(1),
+    read_length: Constant(1) };
+
 pub const TYPE_CHECK_COST: TypeCheckCost = TypeCheckCost {};
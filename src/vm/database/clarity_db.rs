@@ -1,5 +1,6 @@
 use std::collections::{VecDeque, HashMap};
 use std::convert::TryFrom;
+use std::cmp;
 use rusqlite::OptionalExtension;
 
 use vm::contracts::Contract;
@@ -186,10 +187,14 @@ impl <'a> ClarityDatabase <'a> {
         self.store.get_value(key, expected)
     }
 
-    pub fn get_with_proof <T> (&mut self, key: &str) -> Option<(T, TrieMerkleProof<StacksBlockId>)> where T: ClarityDeserializable<T> {
+    pub fn get_with_proof <T> (&mut self, key: &str) -> Result<Option<(T, TrieMerkleProof<StacksBlockId>)>> where T: ClarityDeserializable<T> {
         self.store.get_with_proof(key)
     }
 
+    pub fn get_exclusion_proof(&mut self, key: &str) -> Result<Option<TrieMerkleProof<StacksBlockId>>> {
+        self.store.get_exclusion_proof(key)
+    }
+
     pub fn make_key_for_trip(contract_identifier: &QualifiedContractIdentifier, data: StoreType, var_name: &str) -> String {
         format!("vm::{}::{}::{}", contract_identifier, data as u8, var_name)
     }
@@ -368,6 +373,10 @@ impl <'a> ClarityDatabase <'a> {
         return Ok(Value::Bool(true))
     }
 
+    pub fn make_key_for_data_var(contract_identifier: &QualifiedContractIdentifier, variable_name: &str) -> String {
+        ClarityDatabase::make_key_for_trip(contract_identifier, StoreType::Variable, variable_name)
+    }
+
     pub fn lookup_variable(&mut self, contract_identifier: &QualifiedContractIdentifier, variable_name: &str) -> Result<Value>  {
         let variable_descriptor = self.load_variable(contract_identifier, variable_name)?;
 
@@ -448,7 +457,8 @@ impl <'a> ClarityDatabase <'a> {
             return Err(CheckErrors::TypeValueError(map_descriptor.value_type, value).into())
         }
 
-        let key = ClarityDatabase::make_key_for_quad(contract_identifier, StoreType::DataMap, map_name, key_value.serialize());
+        let serialized_key = key_value.serialize();
+        let key = ClarityDatabase::make_key_for_quad(contract_identifier, StoreType::DataMap, map_name, serialized_key.clone());
         let stored_type = TypeSignature::new_option(map_descriptor.value_type)?;
 
         if return_if_exists && self.data_map_entry_exists(&key, &stored_type)? {
@@ -457,6 +467,7 @@ impl <'a> ClarityDatabase <'a> {
 
         let placed_value = Value::some(value)?;
         self.put(&key, &placed_value);
+        self.store.index_map_key(contract_identifier, map_name, &serialized_key);
 
         return Ok(Value::Bool(true))
     }
@@ -477,13 +488,54 @@ impl <'a> ClarityDatabase <'a> {
 
         return Ok(Value::Bool(true))
     }
+
+    /// Get up to `count` serialized keys of a data map which have an entry at the current chain tip,
+    ///   in order of their serialization, starting after the serialized key `after`.
+    ///   This is served from the (opt-in) data map key index, which records every key ever set in
+    ///   any fork -- so deleted keys and keys set only in other forks are filtered out here.  No more
+    ///   than `max_scanned` indexed keys are examined, so a page can be short (or empty) even though
+    ///   live keys remain.
+    ///   Returns the live keys found, along with the serialized key to continue after if the index
+    ///   was not exhausted.  Returns None if the data map key index is not enabled.
+    pub fn get_map_keys(&mut self, contract_identifier: &QualifiedContractIdentifier, map_name: &str, after: Option<&str>,
+                        count: u32, max_scanned: u32) -> Result<Option<(Vec<String>, Option<String>)>> {
+        let map_descriptor = self.load_map(contract_identifier, map_name)?;
+        let stored_type = TypeSignature::new_option(map_descriptor.value_type)?;
+
+        let mut live_keys = vec![];
+        let mut cursor = after.map(|key| key.to_string());
+        let mut scanned = 0;
+        while (live_keys.len() as u32) < count && scanned < max_scanned {
+            let batch = cmp::min(count - live_keys.len() as u32, max_scanned - scanned);
+            let indexed_keys = match self.store.get_indexed_map_keys(contract_identifier, map_name, cursor.as_ref().map(|key| key.as_str()), batch) {
+                Some(keys) => keys,
+                None => return Ok(None)
+            };
+            let exhausted = (indexed_keys.len() as u32) < batch;
+
+            for serialized_key in indexed_keys.into_iter() {
+                scanned += 1;
+                let key = ClarityDatabase::make_key_for_quad(contract_identifier, StoreType::DataMap, map_name, serialized_key.clone());
+                if self.data_map_entry_exists(&key, &stored_type)? {
+                    live_keys.push(serialized_key.clone());
+                }
+                cursor = Some(serialized_key);
+            }
+
+            if exhausted {
+                return Ok(Some((live_keys, None)))
+            }
+        }
+
+        Ok(Some((live_keys, cursor)))
+    }
 }
 
 // Asset Functions
 
 impl <'a> ClarityDatabase <'a> {
     pub fn create_fungible_token(&mut self, contract_identifier: &QualifiedContractIdentifier, token_name: &str, total_supply: &Option<u128>) {
-        let data = FungibleTokenMetadata { total_supply: total_supply.clone() };
+        let data = FungibleTokenMetadata { total_supply: total_supply.clone(), tracks_supply: true };
 
         let key = ClarityDatabase::make_metadata_key(StoreType::FungibleTokenMeta, token_name);
         self.insert_metadata(contract_identifier, &key, &data);
@@ -519,30 +571,80 @@ impl <'a> ClarityDatabase <'a> {
 
     pub fn checked_increase_token_supply(&mut self, contract_identifier: &QualifiedContractIdentifier, token_name: &str, amount: u128) -> Result<()> {
         let descriptor = self.load_ft(contract_identifier, token_name)?;
+        if !descriptor.tracks_circulating_supply() {
+            return Ok(())
+        }
 
-        if let Some(total_supply) = descriptor.total_supply {
-            let key = ClarityDatabase::make_key_for_trip(contract_identifier, StoreType::CirculatingSupply, token_name);
-            let current_supply: u128 = self.get(&key)
-                .expect("ERROR: Clarity VM failed to track token supply.");
- 
-            let new_supply = current_supply.checked_add(amount)
-                .ok_or(RuntimeErrorType::ArithmeticOverflow)?;
+        let key = ClarityDatabase::make_key_for_trip(contract_identifier, StoreType::CirculatingSupply, token_name);
+        // unbounded tokens have no supply entry until their first mint
+        let current_supply: u128 = self.get(&key).unwrap_or(0);
+
+        let new_supply = current_supply.checked_add(amount)
+            .ok_or(RuntimeErrorType::ArithmeticOverflow)?;
 
+        if let Some(total_supply) = descriptor.total_supply {
             if new_supply > total_supply {
-                Err(RuntimeErrorType::SupplyOverflow(new_supply, total_supply).into())
-            } else {
-                self.put(&key, &new_supply);
-                Ok(())
+                return Err(RuntimeErrorType::SupplyOverflow(new_supply, total_supply).into())
             }
-        } else {
-            Ok(())
         }
+
+        self.put(&key, &new_supply);
+        Ok(())
+    }
+
+    pub fn checked_decrease_token_supply(&mut self, contract_identifier: &QualifiedContractIdentifier, token_name: &str, amount: u128) -> Result<()> {
+        let descriptor = self.load_ft(contract_identifier, token_name)?;
+        if !descriptor.tracks_circulating_supply() {
+            return Ok(())
+        }
+
+        let key = ClarityDatabase::make_key_for_trip(contract_identifier, StoreType::CirculatingSupply, token_name);
+        let current_supply: u128 = self.get(&key).unwrap_or(0);
+
+        // balances of a token whose supply is tracked never sum to more than it, so this
+        //   would be a VM bug
+        let new_supply = current_supply.checked_sub(amount)
+            .ok_or(RuntimeErrorType::SupplyUnderflow(current_supply, amount))?;
+
+        self.put(&key, &new_supply);
+        Ok(())
+    }
+
+    pub fn make_key_for_ft_supply(contract_identifier: &QualifiedContractIdentifier, token_name: &str) -> String {
+        ClarityDatabase::make_key_for_trip(contract_identifier, StoreType::CirculatingSupply, token_name)
+    }
+
+    pub fn make_key_for_ft_balance(contract_identifier: &QualifiedContractIdentifier, token_name: &str, principal: &PrincipalData) -> String {
+        ClarityDatabase::make_key_for_quad(contract_identifier, StoreType::FungibleToken, token_name, principal.serialize())
+    }
+
+    pub fn make_key_for_nft_owner(contract_identifier: &QualifiedContractIdentifier, asset_name: &str, asset: &Value) -> String {
+        ClarityDatabase::make_key_for_quad(contract_identifier, StoreType::NonFungibleToken, asset_name, asset.serialize())
+    }
+
+    /// Get the maximum supply of a fungible token, or None if it was defined without one.
+    pub fn get_ft_total_supply(&mut self, contract_identifier: &QualifiedContractIdentifier, token_name: &str) -> Result<Option<u128>> {
+        let descriptor = self.load_ft(contract_identifier, token_name)?;
+        Ok(descriptor.total_supply)
+    }
+
+    /// Get the amount of a fungible token minted and not yet burned, or None if the token's
+    ///  supply isn't tracked (see FungibleTokenMetadata::tracks_circulating_supply).
+    pub fn get_ft_circulating_supply(&mut self, contract_identifier: &QualifiedContractIdentifier, token_name: &str) -> Result<Option<u128>> {
+        let descriptor = self.load_ft(contract_identifier, token_name)?;
+        if !descriptor.tracks_circulating_supply() {
+            return Ok(None)
+        }
+
+        let key = ClarityDatabase::make_key_for_ft_supply(contract_identifier, token_name);
+        let current_supply: u128 = self.get(&key).unwrap_or(0);
+        Ok(Some(current_supply))
     }
 
     pub fn get_ft_balance(&mut self, contract_identifier: &QualifiedContractIdentifier, token_name: &str, principal: &PrincipalData) -> Result<u128> {
         self.load_ft(contract_identifier, token_name)?;
 
-        let key =  ClarityDatabase::make_key_for_quad(contract_identifier, StoreType::FungibleToken, token_name, principal.serialize());
+        let key = ClarityDatabase::make_key_for_ft_balance(contract_identifier, token_name, principal);
 
         let result = self.get(&key);
         match result {
@@ -566,8 +668,12 @@ impl <'a> ClarityDatabase <'a> {
 
         let key = ClarityDatabase::make_key_for_quad(contract_identifier, StoreType::NonFungibleToken, asset_name, asset.serialize());
 
-        let result = self.get(&key);
-        result.ok_or(RuntimeErrorType::NoSuchToken.into())
+        // burned tokens are stored as having no owner
+        let result: Option<Option<PrincipalData>> = self.get(&key);
+        match result {
+            Some(Some(owner)) => Ok(owner),
+            _ => Err(RuntimeErrorType::NoSuchToken.into())
+        }
     }
 
     pub fn get_nft_key_type(&mut self, contract_identifier: &QualifiedContractIdentifier, asset_name: &str) -> Result<TypeSignature> {
@@ -583,7 +689,22 @@ impl <'a> ClarityDatabase <'a> {
 
         let key = ClarityDatabase::make_key_for_quad(contract_identifier, StoreType::NonFungibleToken, asset_name, asset.serialize());
 
-        self.put(&key, principal);
+        self.put(&key, &Some(principal.clone()));
+
+        Ok(())
+    }
+
+    /// Burn a non-fungible token.  A burned token has no owner, the same as a token that was never
+    ///  minted, so its identifier can be minted again.
+    pub fn burn_nft(&mut self, contract_identifier: &QualifiedContractIdentifier, asset_name: &str, asset: &Value) -> Result<()> {
+        let descriptor = self.load_nft(contract_identifier, asset_name)?;
+        if !descriptor.key_type.admits(asset) {
+            return Err(CheckErrors::TypeValueError(descriptor.key_type, (*asset).clone()).into())
+        }
+
+        let key = ClarityDatabase::make_key_for_quad(contract_identifier, StoreType::NonFungibleToken, asset_name, asset.serialize());
+
+        self.put(&key, &(None as Option<PrincipalData>));
 
         Ok(())
     }
@@ -631,3 +752,37 @@ impl<'a> ClarityDatabase<'a> {
         self.put(&key, &nonce);
     }
 }
+
+#[cfg(test)]
+#[test]
+fn test_untracked_supply() {
+    use vm::database::MemoryBackingStore;
+
+    let mut store = MemoryBackingStore::new();
+    let mut db = store.as_clarity_db();
+    db.begin();
+    let contract_identifier = QualifiedContractIdentifier::local("tokens").unwrap();
+
+    // an unbounded token defined before its supply was tracked, minted back then
+    let key = ClarityDatabase::make_metadata_key(StoreType::FungibleTokenMeta, "legacy");
+    db.insert_metadata(&contract_identifier, &key, &"{\"total_supply\":null}".to_string());
+    let supply_key = ClarityDatabase::make_key_for_ft_supply(&contract_identifier, "legacy");
+
+    // its balances can still be minted and burned, and its state stays as it was
+    db.checked_increase_token_supply(&contract_identifier, "legacy", 10).unwrap();
+    db.checked_decrease_token_supply(&contract_identifier, "legacy", 25).unwrap();
+    assert_eq!(db.get::<u128>(&supply_key), None);
+    assert_eq!(db.get_ft_circulating_supply(&contract_identifier, "legacy").unwrap(), None);
+
+    // unbounded tokens defined from now on track it
+    db.create_fungible_token(&contract_identifier, "tracked", &None);
+    assert_eq!(db.get_ft_circulating_supply(&contract_identifier, "tracked").unwrap(), Some(0));
+    db.checked_increase_token_supply(&contract_identifier, "tracked", 10).unwrap();
+    assert_eq!(db.get_ft_circulating_supply(&contract_identifier, "tracked").unwrap(), Some(10));
+    match db.checked_decrease_token_supply(&contract_identifier, "tracked", 25) {
+        Err(Error::Runtime(RuntimeErrorType::SupplyUnderflow(10, 25), _)) => {},
+        x => panic!("Expected SupplyUnderflow, got {:?}", x)
+    }
+
+    db.roll_back();
+}
@@ -66,6 +66,10 @@ where T: Eq + Hash + Clone {
 pub struct RollbackContext {
     edits: Vec<(String, RollbackValueCheck)>,
     metadata_edits: Vec<((QualifiedContractIdentifier, String), RollbackValueCheck)>,
+    // data map keys to record in the side store's (opt-in) data map key index.  These are
+    //   only written out when committing to the underlying store, so that keys written by
+    //   rolled-back contexts are never indexed.
+    map_key_edits: Vec<(QualifiedContractIdentifier, String, String)>,
 }
 
 pub struct RollbackWrapper <'a> {
@@ -118,7 +122,8 @@ impl RollbackWrapperPersistedLog {
 
     pub fn nest(&mut self) {
         self.stack.push(RollbackContext { edits: Vec::new(),
-                                          metadata_edits: Vec::new() });
+                                          metadata_edits: Vec::new(),
+                                          map_key_edits: Vec::new() });
     }
 }
 
@@ -159,7 +164,8 @@ impl <'a> RollbackWrapper <'a> {
 
     pub fn nest(&mut self) {
         self.stack.push(RollbackContext { edits: Vec::new(),
-                                          metadata_edits: Vec::new() });
+                                          metadata_edits: Vec::new(),
+                                          map_key_edits: Vec::new() });
     }
 
     // Rollback the child's edits.
@@ -202,6 +208,13 @@ impl <'a> RollbackWrapper <'a> {
             if metadata_edits.len() > 0 {
                 self.store.put_all_metadata(metadata_edits);
             }
+
+            if last_item.map_key_edits.len() > 0 {
+                let side_store = self.store.get_side_store();
+                for (contract, map_name, key) in last_item.map_key_edits.drain(..) {
+                    side_store.index_map_key(&contract.to_string(), &map_name, &key);
+                }
+            }
         } else {
             // bubble up to the next item in the stack
             let next_up = self.stack.last_mut().unwrap();
@@ -211,6 +224,7 @@ impl <'a> RollbackWrapper <'a> {
             for (key, value) in last_item.metadata_edits.drain(..) {
                 next_up.metadata_edits.push((key, value));
             }
+            next_up.map_key_edits.append(&mut last_item.map_key_edits);
         }
     }
 }
@@ -239,9 +253,15 @@ impl <'a> RollbackWrapper <'a> {
 
     /// this function will only return commitment proofs for values _already_ materialized
     ///  in the underlying store. otherwise it returns None.
-    pub fn get_with_proof<T>(&mut self, key: &str) -> Option<(T, TrieMerkleProof<StacksBlockId>)> where T: ClarityDeserializable<T> {
-        self.store.get_with_proof(key)
-            .map(|(value, proof)| (T::deserialize(&value), proof))
+    pub fn get_with_proof<T>(&mut self, key: &str) -> Result<Option<(T, TrieMerkleProof<StacksBlockId>)>> where T: ClarityDeserializable<T> {
+        Ok(self.store.get_with_proof(key)?
+            .map(|(value, proof)| (T::deserialize(&value), proof)))
+    }
+
+    /// likewise, this function will only return proofs that a key is absent from the underlying
+    ///  store, regardless of any uncommitted edits.
+    pub fn get_exclusion_proof(&mut self, key: &str) -> Result<Option<TrieMerkleProof<StacksBlockId>>> {
+        self.store.get_exclusion_proof(key)
     }
 
     pub fn get<T>(&mut self, key: &str) -> Option<T> where T: ClarityDeserializable<T> {
@@ -311,6 +331,21 @@ impl <'a> RollbackWrapper <'a> {
         }
     }
 
+    /// Record a data map key in the side store's data map key index (if enabled), once this
+    ///   context commits to the underlying store.  Nothing is recorded if it rolls back.
+    pub fn index_map_key(&mut self, contract: &QualifiedContractIdentifier, map_name: &str, key: &str) {
+        let current = self.stack.last_mut()
+            .expect("ERROR: Clarity VM attempted PUT on non-nested context.");
+
+        current.map_key_edits.push((contract.clone(), map_name.to_string(), key.to_string()));
+    }
+
+    /// Get indexed data map keys from the side store.  Returns None if the index is not enabled.
+    ///   Note that indexed keys are not necessarily live at the current chain tip.
+    pub fn get_indexed_map_keys(&mut self, contract: &QualifiedContractIdentifier, map_name: &str, after: Option<&str>, count: u32) -> Option<Vec<String>> {
+        self.store.get_side_store().get_indexed_map_keys(&contract.to_string(), map_name, after, count)
+    }
+
     pub fn has_entry(&mut self, key: &str) -> bool {
         self.stack.last()
             .expect("ERROR: Clarity VM attempted GET on non-nested context.");
@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 
 use vm::types::{QualifiedContractIdentifier};
-use vm::errors::{InterpreterError, CheckErrors, InterpreterResult as Result, IncomparableError, RuntimeErrorType};
+use vm::errors::{Error, InterpreterError, CheckErrors, InterpreterResult as Result, IncomparableError, RuntimeErrorType};
 use vm::database::{SqliteConnection, ClarityDatabase, HeadersDB, NULL_HEADER_DB,
                    ClaritySerializable, ClarityDeserializable};
 use vm::analysis::{AnalysisDatabase};
@@ -27,7 +27,10 @@ pub struct MarfedKV {
     marf: MARF<StacksBlockId>,
     // Since the MARF only stores 32 bytes of value,
     //   we need another storage
-    side_store: SqliteConnection
+    side_store: SqliteConnection,
+    // When simulating a transaction on a read-only chain tip, the height of the block
+    //   that would be built on it (see begin_simulation())
+    simulated_block_height: Option<u32>
 }
 
 pub struct MemoryBackingStore {
@@ -42,7 +45,11 @@ pub trait ClarityBackingStore {
     fn put_all(&mut self, items: Vec<(String, String)>);
     /// fetch K-V out of the committed datastore
     fn get(&mut self, key: &str) -> Option<String>;
-    fn get_with_proof(&mut self, key: &str) -> Option<(String, TrieMerkleProof<StacksBlockId>)>;
+    /// fetch K-V out of the committed datastore, along with a proof of it
+    fn get_with_proof(&mut self, key: &str) -> Result<Option<(String, TrieMerkleProof<StacksBlockId>)>>;
+    /// fetch a proof that K has no value in the committed datastore (None if it has one, or if
+    ///   the datastore can't prove it)
+    fn get_exclusion_proof(&mut self, key: &str) -> Result<Option<TrieMerkleProof<StacksBlockId>>>;
     fn has_entry(&mut self, key: &str) -> bool {
         self.get(key).is_some()
     }
@@ -161,7 +168,7 @@ impl MarfedKV {
             None => StacksBlockId::sentinel()
         };
 
-        Ok( MarfedKV { marf, chain_tip, side_store } )
+        Ok( MarfedKV { marf, chain_tip, side_store, simulated_block_height: None } )
     }
     
     pub fn open_unconfirmed(path_str: &str, miner_tip: Option<&StacksBlockId>) -> Result<MarfedKV> {
@@ -171,7 +178,7 @@ impl MarfedKV {
             None => StacksBlockId::sentinel()
         };
 
-        Ok( MarfedKV { marf, chain_tip, side_store } )
+        Ok( MarfedKV { marf, chain_tip, side_store, simulated_block_height: None } )
     }
 
     // used by benchmarks
@@ -189,7 +196,7 @@ impl MarfedKV {
 
         let chain_tip =  StacksBlockId::sentinel();
 
-        MarfedKV { marf, chain_tip, side_store }
+        MarfedKV { marf, chain_tip, side_store, simulated_block_height: None }
     }
 
     pub fn as_clarity_db<'a>(&'a mut self, headers_db: &'a dyn HeadersDB) -> ClarityDatabase<'a> {
@@ -283,6 +290,19 @@ impl MarfedKV {
 
     pub fn set_chain_tip(&mut self, bhh: &StacksBlockId) {
         self.chain_tip = bhh.clone();
+        self.simulated_block_height = None;
+    }
+
+    /// Simulate building a block on the current (read-only) chain tip.  Nothing can be written
+    ///   to the MARF, but anything that needs the height of the open block (like a contract's
+    ///   commitment) gets the height of the block that would be built on the chain tip.
+    pub fn begin_simulation(&mut self) {
+        let next_height = self.get_current_block_height() + 1;
+        self.simulated_block_height = Some(next_height);
+    }
+
+    pub fn end_simulation(&mut self) {
+        self.simulated_block_height = None;
     }
 
     // This function *should not* be called by
@@ -308,6 +328,16 @@ impl MarfedKV {
     pub fn make_contract_hash_key(contract: &QualifiedContractIdentifier) -> String {
         format!("clarity-contract::{}", contract)
     }
+
+    /// Is the data map key index maintained in the side store?
+    pub fn has_map_key_index(&self) -> bool {
+        self.side_store.has_map_key_index()
+    }
+
+    /// Start maintaining the data map key index in the side store.
+    pub fn enable_map_key_index(&mut self) -> Result<()> {
+        self.side_store.enable_map_key_index()
+    }
 }
 
 impl ClarityBackingStore for MarfedKV {
@@ -316,10 +346,13 @@ impl ClarityBackingStore for MarfedKV {
     }
 
     fn set_block_hash(&mut self, bhh: StacksBlockId) -> Result<StacksBlockId> {
-        self.marf.check_ancestor_block_hash(&bhh).map_err(|e| {
+        self.marf.check_ancestor_block_hash(&bhh).map_err(|e| -> Error {
             match e {
-                MarfError::NotFoundError => RuntimeErrorType::UnknownBlockHeaderHash(BlockHeaderHash(bhh.0)),
-                MarfError::NonMatchingForks(_,_) => RuntimeErrorType::UnknownBlockHeaderHash(BlockHeaderHash(bhh.0)),
+                MarfError::NotFoundError => RuntimeErrorType::UnknownBlockHeaderHash(BlockHeaderHash(bhh.0)).into(),
+                MarfError::NonMatchingForks(_,_) => RuntimeErrorType::UnknownBlockHeaderHash(BlockHeaderHash(bhh.0)).into(),
+                // a pruned node can't evaluate this -- but an archive node can, so it's not a runtime error.
+                // Blocks that fail this way are left unprocessed (see StacksChainState::is_pruned_state_error()).
+                MarfError::PrunedError => InterpreterError::MarfFailure(IncomparableError{ err: e }).into(),
                 _ => panic!("ERROR: Unexpected MARF failure: {}", e)
             }
         })?;
@@ -352,25 +385,39 @@ impl ClarityBackingStore for MarfedKV {
 
     fn get_open_chain_tip_height(&mut self) -> u32 {
         self.marf.get_open_chain_tip_height()
+            .or(self.simulated_block_height)
             .expect("Attempted to get the open chain tip from an unopened context.")
     }
 
-    fn get_with_proof(&mut self, key: &str) -> Option<(String, TrieMerkleProof<StacksBlockId>)> {
-        self.marf.get_with_proof(&self.chain_tip, key)
+    fn get_with_proof(&mut self, key: &str) -> Result<Option<(String, TrieMerkleProof<StacksBlockId>)>> {
+        let value_and_proof = self.marf.get_with_proof(&self.chain_tip, key)
             .or_else(|e| {
                 match e {
                     MarfError::NotFoundError => Ok(None),
                     _ => Err(e)
                 }
             })
-            .expect("ERROR: Unexpected MARF Failure on GET")
+            .map_err(|e| -> Error { InterpreterError::MarfFailure(IncomparableError{ err: e }).into() })?;
+
+        Ok(value_and_proof
             .map(|(marf_value, proof)| {
                 let side_key = marf_value.to_hex();
                 let data = self.side_store.get(&side_key)
                     .expect(&format!("ERROR: MARF contained value_hash not found in side storage: {}",
                                      side_key));
                 (data, proof)
+            }))
+    }
+
+    fn get_exclusion_proof(&mut self, key: &str) -> Result<Option<TrieMerkleProof<StacksBlockId>>> {
+        self.marf.get_exclusion_proof(&self.chain_tip, key)
+            .or_else(|e| {
+                match e {
+                    MarfError::NotFoundError => Ok(None),
+                    _ => Err(e)
+                }
             })
+            .map_err(|e| InterpreterError::MarfFailure(IncomparableError{ err: e }).into())
     }
 
     fn get(&mut self, key: &str) -> Option<String> {
@@ -439,11 +486,16 @@ impl ClarityBackingStore for MemoryBackingStore {
         self.side_store.get(key)
     }
 
-    fn get_with_proof(&mut self, key: &str) -> Option<(String, TrieMerkleProof<StacksBlockId>)> {
-        self.side_store.get(key)
+    fn get_with_proof(&mut self, key: &str) -> Result<Option<(String, TrieMerkleProof<StacksBlockId>)>> {
+        Ok(self.side_store.get(key)
             .map(|x| {
                 (x, TrieMerkleProof(vec![]))
-            })
+            }))
+    }
+
+    fn get_exclusion_proof(&mut self, _key: &str) -> Result<Option<TrieMerkleProof<StacksBlockId>>> {
+        // there's no trie to prove anything against
+        Ok(None)
     }
 
     fn get_side_store(&mut self) -> &mut SqliteConnection {
@@ -7,7 +7,8 @@ mod key_value_wrapper;
 use std::collections::HashMap;
 
 pub use self::key_value_wrapper::{RollbackWrapper, RollbackWrapperPersistedLog};
-pub use self::clarity_db::{ClarityDatabase, HeadersDB, NULL_HEADER_DB, STORE_CONTRACT_SRC_INTERFACE};
-pub use self::structures::{ClaritySerializable, ClarityDeserializable};
+pub use self::clarity_db::{ClarityDatabase, HeadersDB, NULL_HEADER_DB, STORE_CONTRACT_SRC_INTERFACE, StoreType};
+pub use self::structures::{ClaritySerializable, ClarityDeserializable,
+                            DataMapMetadata, DataVariableMetadata, FungibleTokenMetadata, NonFungibleTokenMetadata};
 pub use self::sqlite::{SqliteConnection};
 pub use self::marf::{MemoryBackingStore, MarfedKV, ClarityBackingStore};
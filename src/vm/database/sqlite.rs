@@ -11,7 +11,9 @@ use vm::errors::{Error, InterpreterError, RuntimeErrorType, InterpreterResult as
 const SQL_FAIL_MESSAGE: &str = "PANIC: SQL Failure in Smart Contract VM.";
 
 pub struct SqliteConnection {
-    conn: Connection
+    conn: Connection,
+    // whether or not the (opt-in) data map key index is maintained in this store
+    index_map_keys: bool
 }
 
 fn sqlite_put(conn: &Connection, key: &str, value: &str) {
@@ -78,6 +80,55 @@ impl SqliteConnection {
         sqlite_has_entry(&self.conn, key)
     }
 
+    /// Is the data map key index maintained in this store?
+    pub fn has_map_key_index(&self) -> bool {
+        self.index_map_keys
+    }
+
+    /// Start maintaining the data map key index in this store.  Only keys written after the index
+    ///   is enabled will be recorded.  The index persists -- every later connection to this store
+    ///   will maintain it as well.
+    pub fn enable_map_key_index(&mut self) -> Result<()> {
+        self.conn.execute("CREATE TABLE IF NOT EXISTS data_map_keys
+                      (contract TEXT NOT NULL, map TEXT NOT NULL, key TEXT NOT NULL,
+                       UNIQUE (contract, map, key))", NO_PARAMS)
+            .map_err(|x| InterpreterError::SqliteError(IncomparableError{ err: x }))?;
+        self.index_map_keys = true;
+        Ok(())
+    }
+
+    /// Record that a key was written to a data map, if the data map key index is enabled.
+    ///   Rows are written inside the savepoint of the block that wrote the key, so they are
+    ///   rolled back with it.  The index records keys written in _any_ fork, though, so callers
+    ///   must check that a key is live at their chain tip.
+    pub fn index_map_key(&mut self, contract_id: &str, map_name: &str, key: &str) {
+        if !self.index_map_keys {
+            return;
+        }
+
+        let params: [&dyn ToSql; 3] = [&contract_id, &map_name, &key];
+        self.conn.execute("INSERT OR IGNORE INTO data_map_keys (contract, map, key) VALUES (?, ?, ?)", &params)
+            .expect(SQL_FAIL_MESSAGE);
+    }
+
+    /// Get up to `count` of the indexed keys of a data map, in order, starting after `after`.
+    ///   Returns None if the data map key index is not enabled.
+    pub fn get_indexed_map_keys(&mut self, contract_id: &str, map_name: &str, after: Option<&str>, count: u32) -> Option<Vec<String>> {
+        if !self.index_map_keys {
+            return None;
+        }
+
+        let after = after.unwrap_or("");
+        let params: [&dyn ToSql; 4] = [&contract_id, &map_name, &after, &count];
+        let mut stmt = self.conn.prepare("SELECT key FROM data_map_keys WHERE contract = ? AND map = ? AND key > ? ORDER BY key LIMIT ?")
+            .expect(SQL_FAIL_MESSAGE);
+        let keys = stmt.query_map(&params, |row| row.get(0))
+            .expect(SQL_FAIL_MESSAGE)
+            .collect::<std::result::Result<Vec<String>, _>>()
+            .expect(SQL_FAIL_MESSAGE);
+        Some(keys)
+    }
+
     /// begin, commit, rollback a save point identified by key
     ///    this is used to clean up any data from aborted blocks
     ///     (NOT aborted transactions that is handled by the clarity vm directly).
@@ -163,7 +214,13 @@ impl SqliteConnection {
         conn.busy_handler(Some(tx_busy_handler))
             .map_err(|x| InterpreterError::SqliteError(IncomparableError{ err: x }))?;
 
-        Ok(SqliteConnection { conn })
+        let index_map_keys = conn.query_row("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?", &["data_map_keys"],
+                                            |row| row.get::<_, i64>(0))
+            .optional()
+            .map_err(|x| InterpreterError::SqliteError(IncomparableError{ err: x }))?
+            .is_some();
+
+        Ok(SqliteConnection { conn, index_map_keys })
     }
 
     #[cfg(test)]
@@ -183,3 +240,35 @@ fn test_rollback() {
     conn.rollback(&bhh);
     conn.commit(&bhh); // shouldn't be on the stack!
 }
+
+#[cfg(test)]
+#[test]
+fn test_map_key_index() {
+    let mut conn = SqliteConnection::memory().unwrap();
+    assert!(!conn.has_map_key_index());
+
+    // not recorded until the index is enabled
+    conn.index_map_key("contract", "map", "0a");
+    assert_eq!(conn.get_indexed_map_keys("contract", "map", None, 10), None);
+
+    conn.enable_map_key_index().unwrap();
+    assert!(conn.has_map_key_index());
+    assert_eq!(conn.get_indexed_map_keys("contract", "map", None, 10), Some(vec![]));
+
+    conn.index_map_key("contract", "map", "0c");
+    conn.index_map_key("contract", "map", "0b");
+    conn.index_map_key("contract", "map", "0c");
+    conn.index_map_key("contract", "other-map", "0d");
+
+    assert_eq!(conn.get_indexed_map_keys("contract", "map", None, 10), Some(vec!["0b".to_string(), "0c".to_string()]));
+    assert_eq!(conn.get_indexed_map_keys("contract", "map", None, 1), Some(vec!["0b".to_string()]));
+    assert_eq!(conn.get_indexed_map_keys("contract", "map", Some("0b"), 10), Some(vec!["0c".to_string()]));
+    assert_eq!(conn.get_indexed_map_keys("contract", "other-map", None, 10), Some(vec!["0d".to_string()]));
+
+    // index rows are rolled back with the block that wrote them
+    let bhh = StacksBlockId([1; 32]);
+    conn.begin(&bhh);
+    conn.index_map_key("contract", "map", "0e");
+    conn.rollback(&bhh);
+    assert_eq!(conn.get_indexed_map_keys("contract", "map", Some("0c"), 10), Some(vec![]));
+}
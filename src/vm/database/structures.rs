@@ -42,11 +42,23 @@ macro_rules! clarity_serializable {
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FungibleTokenMetadata {
-    pub total_supply: Option<u128>
+    pub total_supply: Option<u128>,
+    // unbounded tokens defined before `ft-burn?` and `ft-get-supply` don't have this set
+    #[serde(default)]
+    pub tracks_supply: bool,
 }
 
 clarity_serializable!(FungibleTokenMetadata);
 
+impl FungibleTokenMetadata {
+    /// Is the token's circulating supply tracked?  It always is for tokens with a maximum
+    ///  supply, but unbounded tokens defined before it was tracked for them never had it
+    ///  recorded, so their supply is left untracked.
+    pub fn tracks_circulating_supply(&self) -> bool {
+        self.total_supply.is_some() || self.tracks_supply
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct NonFungibleTokenMetadata {
     pub key_type: TypeSignature
@@ -89,6 +101,23 @@ pub struct SimmedBlock {
 clarity_serializable!(SimmedBlock);
 
 clarity_serializable!(PrincipalData);
+
+// NFT owners.  `Some(principal)` serializes exactly like `principal`, so owners stored
+//   before tokens could be burned still deserialize.
+impl ClaritySerializable for Option<PrincipalData> {
+    fn serialize(&self) -> String {
+        serde_json::to_string(self)
+            .expect("Failed to serialize vm.Value")
+    }
+}
+
+impl ClarityDeserializable<Option<PrincipalData>> for Option<PrincipalData> {
+    fn deserialize(json: &str) -> Self {
+        serde_json::from_str(json)
+            .expect("Failed to serialize vm.Value")
+    }
+}
+
 clarity_serializable!(i128);
 clarity_serializable!(u128);
 clarity_serializable!(u64);
@@ -0,0 +1,472 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{BufRead, Write};
+
+use vm::contexts::{Environment, LocalContext};
+use vm::errors::{InterpreterResult as Result};
+use vm::execute as vm_execute;
+use vm::hooks::EvalHook;
+use vm::representations::{SymbolicExpression, SymbolicExpressionType};
+use vm::types::{QualifiedContractIdentifier, Value};
+
+const HELP: &str = "Commands:
+  step, s                  evaluate up to the next function application
+  next, n                  evaluate up to the next application that is not nested in this one
+  finish, f                run until the current function returns
+  continue, c              run until the next breakpoint
+  break, b <location>      stop at a line (`12`, or `<contract-identifier>:12`) or a function name
+  delete, d <n>            remove breakpoint <n>
+  breakpoints              list breakpoints
+  print, p <name>          show a local variable, constant, or data-var
+  locals                   show the variables bound in the current scope
+  map <name> <key>         show the entry of a data map at a key
+  env                      show the contract, sender, caller and block height
+  backtrace, bt            show the user-defined functions being executed
+  list, l                  show the source around the current expression
+  quit, q                  stop debugging and let the call run to completion
+  help, h                  show this message
+An empty line repeats the last command.";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Breakpoint {
+    /// Stop at the first application evaluated on a line of a contract.
+    Line(QualifiedContractIdentifier, u32),
+    /// Stop when a user-defined function with this name is called, in any contract.
+    Function(String),
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Breakpoint::Line(contract_identifier, line) => write!(f, "{}:{}", contract_identifier, line),
+            Breakpoint::Function(name) => write!(f, "{}", name)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum StepMode {
+    // stop at every function application
+    Step,
+    // stop at the next application at or above this evaluation depth
+    Next(usize),
+    // stop once the function whose body is evaluated at this depth returns
+    Finish(usize),
+    // only stop at breakpoints
+    Continue,
+    // never stop again
+    Detached,
+}
+
+/// An `EvalHook` which pauses evaluation at breakpoints, or after each step, and reads
+///  debugger commands from `input` until told to resume.  Pausing only ever happens before a
+///  function application, so stepping skips over literals and variable references.
+pub struct Debugger<R: BufRead, W: Write> {
+    input: R,
+    output: W,
+    breakpoints: Vec<Breakpoint>,
+    mode: StepMode,
+    last_command: String,
+    // number of expressions currently being evaluated
+    depth: usize,
+    // name, contract and body evaluation depth of each user-defined function being executed
+    call_stack: Vec<(String, QualifiedContractIdentifier, usize)>,
+    last_line: Option<(QualifiedContractIdentifier, u32)>,
+    sources: HashMap<QualifiedContractIdentifier, Vec<String>>,
+}
+
+impl <R: BufRead, W: Write> Debugger<R, W> {
+    /// Make a debugger which stops at the first function application it sees.
+    pub fn new(input: R, output: W) -> Debugger<R, W> {
+        Debugger {
+            input,
+            output,
+            breakpoints: vec![],
+            mode: StepMode::Step,
+            last_command: String::new(),
+            depth: 0,
+            call_stack: vec![],
+            last_line: None,
+            sources: HashMap::new(),
+        }
+    }
+
+    /// Make a debugger which runs until it hits one of `breakpoints`.
+    pub fn with_breakpoints(input: R, output: W, breakpoints: Vec<Breakpoint>) -> Debugger<R, W> {
+        Debugger {
+            breakpoints,
+            mode: StepMode::Continue,
+            .. Debugger::new(input, output)
+        }
+    }
+
+    pub fn get_breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+
+    fn should_pause(&mut self, contract_identifier: &QualifiedContractIdentifier, expr: &SymbolicExpression) -> bool {
+        let line = expr.span.start_line;
+        let is_new_line = match self.last_line {
+            Some((ref last_contract, last_line)) => last_contract != contract_identifier || last_line != line,
+            None => true
+        };
+        self.last_line = Some((contract_identifier.clone(), line));
+
+        let at_breakpoint = is_new_line && self.breakpoints.iter().any(|breakpoint| match breakpoint {
+            Breakpoint::Line(break_contract, break_line) => break_contract == contract_identifier && *break_line == line,
+            Breakpoint::Function(_) => false
+        });
+
+        match self.mode {
+            StepMode::Detached => false,
+            StepMode::Step => true,
+            StepMode::Next(depth) => self.depth <= depth || at_breakpoint,
+            StepMode::Finish(depth) => self.depth < depth || at_breakpoint,
+            StepMode::Continue => at_breakpoint,
+        }
+    }
+
+    fn source_line(&mut self, env: &mut Environment, contract_identifier: &QualifiedContractIdentifier, line: u32) -> Option<String> {
+        if !self.sources.contains_key(contract_identifier) {
+            let source = env.global_context.database.get_contract_src(contract_identifier)?;
+            self.sources.insert(contract_identifier.clone(), source.lines().map(|l| l.to_string()).collect());
+        }
+        let lines = self.sources.get(contract_identifier)?;
+        lines.get((line as usize).checked_sub(1)?).cloned()
+    }
+
+    fn show_location(&mut self, env: &mut Environment, expr: &SymbolicExpression) {
+        let contract_identifier = env.contract_context.contract_identifier.clone();
+        let _ = writeln!(self.output, "{}:{}:{} {}", &contract_identifier,
+                         expr.span.start_line, expr.span.start_column, expr);
+        if let Some(source) = self.source_line(env, &contract_identifier, expr.span.start_line) {
+            let _ = writeln!(self.output, "{:>5} | {}", expr.span.start_line, source);
+        }
+    }
+
+    fn list_source(&mut self, env: &mut Environment, line: u32) {
+        let contract_identifier = env.contract_context.contract_identifier.clone();
+        for current in line.saturating_sub(3).max(1)..(line + 4) {
+            if let Some(source) = self.source_line(env, &contract_identifier, current) {
+                let marker = if current == line { ">" } else { " " };
+                let _ = writeln!(self.output, "{}{:>4} | {}", marker, current, source);
+            }
+        }
+    }
+
+    fn parse_breakpoint(&self, env: &Environment, location: &str) -> std::result::Result<Breakpoint, String> {
+        if let Ok(line) = location.parse::<u32>() {
+            return Ok(Breakpoint::Line(env.contract_context.contract_identifier.clone(), line))
+        }
+        let mut parts = location.rsplitn(2, ':');
+        match (parts.next(), parts.next()) {
+            (Some(line), Some(contract)) => {
+                let line = line.parse::<u32>()
+                    .map_err(|_| format!("Invalid line number: {}", line))?;
+                let contract_identifier = QualifiedContractIdentifier::parse(contract)
+                    .map_err(|e| format!("Invalid contract identifier: {}", e))?;
+                Ok(Breakpoint::Line(contract_identifier, line))
+            },
+            _ => Ok(Breakpoint::Function(location.to_string()))
+        }
+    }
+
+    fn print_variable(&mut self, env: &mut Environment, context: &LocalContext, name: &str) {
+        if let Some(value) = context.lookup_variable(name) {
+            let _ = writeln!(self.output, "{} = {}", name, value);
+        } else if let Some(value) = env.contract_context.lookup_variable(name) {
+            let _ = writeln!(self.output, "{} = {} (constant)", name, value);
+        } else {
+            match env.global_context.database.lookup_variable(&env.contract_context.contract_identifier, name) {
+                Ok(value) => { let _ = writeln!(self.output, "{} = {} (data-var)", name, value); },
+                Err(_) => { let _ = writeln!(self.output, "No variable named {} is in scope", name); }
+            }
+        }
+    }
+
+    fn print_locals(&mut self, context: &LocalContext) {
+        let mut names = vec![];
+        let mut scope = Some(context);
+        while let Some(current) = scope {
+            let mut bound: Vec<_> = current.variables.iter()
+                .filter(|(name, _)| !names.iter().any(|seen: &(String, String)| seen.0 == name.as_str()))
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect();
+            bound.sort();
+            names.extend(bound);
+            scope = current.parent;
+        }
+        if names.is_empty() {
+            let _ = writeln!(self.output, "No local variables");
+        }
+        for (name, value) in names.iter() {
+            let _ = writeln!(self.output, "{} = {}", name, value);
+        }
+    }
+
+    fn print_map_entry(&mut self, env: &mut Environment, arguments: &str) {
+        let mut parts = arguments.splitn(2, ' ');
+        let (map_name, key) = match (parts.next(), parts.next()) {
+            (Some(map_name), Some(key)) if map_name.len() > 0 => (map_name, key),
+            _ => {
+                let _ = writeln!(self.output, "Usage: map <name> <key>");
+                return
+            }
+        };
+        let key = match vm_execute(key) {
+            Ok(Some(key)) => key,
+            Ok(None) => {
+                let _ = writeln!(self.output, "Key does not evaluate to a value");
+                return
+            },
+            Err(e) => {
+                let _ = writeln!(self.output, "Failed to evaluate key: {}", e);
+                return
+            }
+        };
+        match env.global_context.database.fetch_entry(&env.contract_context.contract_identifier, map_name, &key) {
+            Ok(entry) => { let _ = writeln!(self.output, "{}", entry); },
+            Err(e) => { let _ = writeln!(self.output, "Failed to read map entry: {}", e); }
+        }
+    }
+
+    fn print_env(&mut self, env: &mut Environment) {
+        let block_height = env.global_context.database.get_current_block_height();
+        let _ = writeln!(self.output, "contract: {}", env.contract_context.contract_identifier);
+        let _ = writeln!(self.output, "sender: {}", display_optional(&env.sender));
+        let _ = writeln!(self.output, "caller: {}", display_optional(&env.caller));
+        let _ = writeln!(self.output, "block-height: {}", block_height);
+    }
+
+    fn print_backtrace(&mut self) {
+        for (index, (name, contract_identifier, _)) in self.call_stack.iter().rev().enumerate() {
+            let _ = writeln!(self.output, "#{} {}::{}", index, contract_identifier, name);
+        }
+    }
+
+    // Read and run commands until one resumes execution.
+    fn pause(&mut self, env: &mut Environment, context: &LocalContext, expr: Option<&SymbolicExpression>) {
+        loop {
+            let _ = write!(self.output, "(debug) ");
+            let _ = self.output.flush();
+
+            let mut line = String::new();
+            match self.input.read_line(&mut line) {
+                Ok(0) | Err(_) => {
+                    // no more commands: let the call finish
+                    self.mode = StepMode::Detached;
+                    return
+                },
+                Ok(_) => {}
+            }
+
+            let mut command = line.trim().to_string();
+            if command.is_empty() {
+                command = self.last_command.clone();
+            } else {
+                self.last_command = command.clone();
+            }
+
+            let mut parts = command.splitn(2, ' ');
+            let verb = parts.next().unwrap_or("");
+            let arguments = parts.next().unwrap_or("").trim();
+
+            match verb {
+                "step" | "s" => {
+                    self.mode = StepMode::Step;
+                    return
+                },
+                "next" | "n" => {
+                    self.mode = StepMode::Next(self.depth);
+                    return
+                },
+                "finish" | "f" => {
+                    match self.call_stack.last() {
+                        Some((_, _, depth)) => {
+                            self.mode = StepMode::Finish(*depth);
+                            return
+                        },
+                        None => { let _ = writeln!(self.output, "Not inside a function"); }
+                    }
+                },
+                "continue" | "c" => {
+                    self.mode = StepMode::Continue;
+                    return
+                },
+                "quit" | "q" => {
+                    self.mode = StepMode::Detached;
+                    return
+                },
+                "break" | "b" => {
+                    match self.parse_breakpoint(env, arguments) {
+                        Ok(breakpoint) if arguments.len() > 0 => {
+                            let _ = writeln!(self.output, "Breakpoint {} at {}", self.breakpoints.len(), breakpoint);
+                            self.breakpoints.push(breakpoint);
+                        },
+                        Ok(_) => { let _ = writeln!(self.output, "Usage: break <line> | <contract-identifier>:<line> | <function>"); },
+                        Err(e) => { let _ = writeln!(self.output, "{}", e); }
+                    }
+                },
+                "delete" | "d" => {
+                    match arguments.parse::<usize>() {
+                        Ok(index) if index < self.breakpoints.len() => {
+                            let breakpoint = self.breakpoints.remove(index);
+                            let _ = writeln!(self.output, "Deleted breakpoint at {}", breakpoint);
+                        },
+                        _ => { let _ = writeln!(self.output, "No breakpoint {}", arguments); }
+                    }
+                },
+                "breakpoints" => {
+                    if self.breakpoints.is_empty() {
+                        let _ = writeln!(self.output, "No breakpoints");
+                    }
+                    for (index, breakpoint) in self.breakpoints.iter().enumerate() {
+                        let _ = writeln!(self.output, "{}: {}", index, breakpoint);
+                    }
+                },
+                "print" | "p" => self.print_variable(env, context, arguments),
+                "locals" => self.print_locals(context),
+                "map" => self.print_map_entry(env, arguments),
+                "env" => self.print_env(env),
+                "backtrace" | "bt" => self.print_backtrace(),
+                "list" | "l" => {
+                    match expr {
+                        Some(expr) => self.list_source(env, expr.span.start_line),
+                        None => { let _ = writeln!(self.output, "No current expression"); }
+                    }
+                },
+                "help" | "h" => { let _ = writeln!(self.output, "{}", HELP); },
+                _ => { let _ = writeln!(self.output, "Unknown command: {}. Type `help` for a list of commands.", verb); }
+            }
+        }
+    }
+}
+
+fn display_optional(value: &Option<Value>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => "none".to_string()
+    }
+}
+
+impl <R: BufRead, W: Write> EvalHook for Debugger<R, W> {
+    fn will_begin_eval(&mut self, env: &mut Environment, context: &LocalContext, expr: &SymbolicExpression) {
+        if let SymbolicExpressionType::List(_) = expr.expr {
+            let contract_identifier = env.contract_context.contract_identifier.clone();
+            if self.should_pause(&contract_identifier, expr) {
+                self.show_location(env, expr);
+                self.pause(env, context, Some(expr));
+            }
+        }
+        self.depth += 1;
+    }
+
+    fn did_finish_eval(&mut self, _env: &mut Environment, _context: &LocalContext, _expr: &SymbolicExpression, _result: &Result<Value>) {
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    fn will_call_function(&mut self, env: &mut Environment, context: &LocalContext, name: &str, args: &[Value]) {
+        let contract_identifier = env.contract_context.contract_identifier.clone();
+        self.call_stack.push((name.to_string(), contract_identifier.clone(), self.depth));
+
+        let at_breakpoint = self.breakpoints.iter().any(|breakpoint| match breakpoint {
+            Breakpoint::Function(break_name) => break_name == name,
+            Breakpoint::Line(..) => false
+        });
+        if at_breakpoint && self.mode != StepMode::Detached {
+            let args: Vec<_> = args.iter().map(|arg| arg.to_string()).collect();
+            let _ = writeln!(self.output, "Called {}::{} with ({})", contract_identifier, name, args.join(" "));
+            self.pause(env, context, None);
+        }
+    }
+
+    fn did_return_from_function(&mut self, _env: &mut Environment, name: &str, result: &Result<Value>) {
+        if let Some((_, _, depth)) = self.call_stack.pop() {
+            if self.mode == StepMode::Finish(depth) {
+                match result {
+                    Ok(value) => { let _ = writeln!(self.output, "{} returned {}", name, value); },
+                    Err(e) => { let _ = writeln!(self.output, "{} failed: {}", name, e); }
+                }
+                self.mode = StepMode::Step;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Cursor;
+    use vm::contexts::OwnedEnvironment;
+    use vm::database::MemoryBackingStore;
+    use vm::types::PrincipalData;
+
+    const CONTRACT: &str = "(define-data-var total int 0)
+(define-map balances ((owner principal)) ((amount int)))
+(define-private (double (x int))
+  (* x 2))
+(define-public (add (by int))
+  (let ((doubled (double by)))
+    (var-set total (+ (var-get total) doubled))
+    (ok doubled)))
+";
+
+    fn debug_call(debugger: &mut Debugger<Cursor<&str>, Vec<u8>>) -> Value {
+        let contract_identifier = QualifiedContractIdentifier::local("adder").unwrap();
+        let sender = Value::Principal(PrincipalData::parse("S1G2081040G2081040G2081040G208105NK8PE5").unwrap());
+
+        let mut marf = MemoryBackingStore::new();
+        let mut owned_env = OwnedEnvironment::new(marf.as_clarity_db());
+        owned_env.initialize_contract(contract_identifier.clone(), CONTRACT).unwrap();
+        owned_env.set_eval_hook(debugger);
+        let (result, _, _) = owned_env.execute_transaction(
+            sender, contract_identifier, "add", &[SymbolicExpression::atom_value(Value::Int(3))]).unwrap();
+        result
+    }
+
+    #[test]
+    fn test_breakpoints_and_inspection() {
+        let commands = "break double\ncontinue\nlocals\nfinish\nprint doubled\np total\n\
+                        map balances (tuple (owner 'S1G2081040G2081040G2081040G208105NK8PE5))\nbreakpoints\nquit\n";
+        let mut debugger = Debugger::new(Cursor::new(commands), vec![]);
+        assert_eq!(debug_call(&mut debugger), Value::okay(Value::Int(6)).unwrap());
+        assert_eq!(debugger.get_breakpoints(), &[Breakpoint::Function("double".to_string())]);
+
+        let output = String::from_utf8(debugger.output).unwrap();
+        let expected = [
+            ".adder:6:3 ( let ( ( doubled ( double by ) ) ) ( var-set total ( + ( var-get total ) doubled ) ) ( ok doubled ) )",
+            "    6 |   (let ((doubled (double by)))",
+            "Breakpoint 0 at double",
+            ".adder::double with (3)",
+            "x = 3",
+            "double returned 6",
+            ".adder:7:5 ( var-set total",
+            "doubled = 6",
+            "total = 0 (data-var)",
+            "none",
+            "0: double"];
+        let mut remaining = output.as_str();
+        for line in expected.iter() {
+            let index = remaining.find(line).expect(&format!("Expected `{}` in:\n{}", line, output));
+            remaining = &remaining[index + line.len()..];
+        }
+    }
+
+    #[test]
+    fn test_stepping() {
+        let contract_identifier = QualifiedContractIdentifier::local("adder").unwrap();
+        let breakpoints = vec![Breakpoint::Line(contract_identifier, 7)];
+        let mut debugger = Debugger::with_breakpoints(Cursor::new("step\nnext\nbt\n"), vec![], breakpoints);
+        assert_eq!(debug_call(&mut debugger), Value::okay(Value::Int(6)).unwrap());
+
+        let output = String::from_utf8(debugger.output).unwrap();
+        let stops: Vec<_> = output.lines()
+            .filter_map(|line| line.split("(debug) ").last())
+            .filter(|line| line.contains(".adder:") && !line.starts_with('#'))
+            .filter_map(|line| line.split(' ').next().unwrap().split('.').nth(1))
+            .collect();
+        // the breakpoint, then into the `+` application, then over the `var-get` nested in it
+        assert_eq!(stops, vec!["adder:7:5", "adder:7:20", "adder:8:5"]);
+        assert!(output.contains(".adder::add"));
+    }
+}
@@ -3,11 +3,12 @@ use std::fmt;
 
 /// In a near future, we can go further in our static analysis and provide different levels 
 /// of diagnostics, such as warnings, hints, best practices, etc.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[derive(Serialize, Deserialize)]
 #[derive(PartialEq)]
 pub enum Level {
     Error,
+    Warning,
 }
 
 pub trait DiagnosableError {
@@ -15,7 +16,7 @@ pub trait DiagnosableError {
     fn suggestion(&self) -> Option<String>;
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[derive(Serialize, Deserialize)]
 #[derive(PartialEq)]
 pub struct Diagnostic {
@@ -35,6 +36,15 @@ impl Diagnostic {
             suggestion: error.suggestion(),
         }
     }
+
+    pub fn warn(warning: &dyn DiagnosableError) -> Diagnostic {
+        Diagnostic {
+            spans: vec![],
+            level: Level::Warning,
+            message: warning.message(),
+            suggestion: warning.suggestion(),
+        }
+    }
 }
 
 impl fmt::Display for Diagnostic {
@@ -12,21 +12,21 @@ struct ReferenceAPIs {
 }
 
 #[derive(Serialize, Clone)]
-struct KeywordAPI {
-    name: &'static str,
-    output_type: &'static str,
-    description: &'static str,
-    example: &'static str
+pub struct KeywordAPI {
+    pub name: &'static str,
+    pub output_type: &'static str,
+    pub description: &'static str,
+    pub example: &'static str
 }
 
 #[derive(Serialize)]
-struct FunctionAPI {
-    name: String,
-    input_type: String,
-    output_type: String,
-    signature: String,
-    description: String,
-    example: String
+pub struct FunctionAPI {
+    pub name: String,
+    pub input_type: String,
+    pub output_type: String,
+    pub signature: String,
+    pub description: String,
+    pub example: String
 }
 
 struct SimpleFunctionAPI {
@@ -394,44 +394,52 @@ has to be a literal function name.",
 };
 
 const CONCAT_API: SpecialAPI = SpecialAPI {
-    input_type: "(buff, buff)|(list, list)",
-    output_type: "buff|list",
+    input_type: "(buff, buff)|(string-ascii, string-ascii)|(string-utf8, string-utf8)|(list, list)",
+    output_type: "buff|string-ascii|string-utf8|list",
     signature: "(concat buff-a buff-b)",
-    description: "The `concat` function takes two buffers or two lists with the same entry type,
-and returns a concatenated buffer or list of the same entry type, with max_len = max_len_a + max_len_b.",
+    description: "The `concat` function takes two buffers, two strings of the same encoding, or two lists
+with the same entry type, and returns a concatenated buffer, string or list of the same entry type,
+with max_len = max_len_a + max_len_b.",
     example: "(concat \"hello \" \"world\") ;; Returns 0x68656c6c6f20776f726c64
-                                            ;; hex form of \"hello world\""
+                                            ;; hex form of \"hello world\"
+(concat a\"hello \" a\"world\") ;; Returns a\"hello world\"
+(concat u\"caf\\u{e9} \" u\"ol\\u{e9}\") ;; Returns u\"caf\\u{e9} ol\\u{e9}\""
 };
 
 const APPEND_API: SpecialAPI = SpecialAPI {
-    input_type: "list A, A",
-    output_type: "list",
+    input_type: "list A, A|string-ascii, string-ascii|string-utf8, string-utf8",
+    output_type: "list|string-ascii|string-utf8",
     signature: "(append (list 1 2 3 4) 5)",
     description: "The `append` function takes a list and another value with the same entry type,
-or a buffer and another buffer of length 1 and outputs a buffer or a list of the same type with max_len += 1.",
-    example: "(append (list 1 2 3 4) 5) ;; Returns (1 2 3 4 5)"
+or a string and a single-character string of the same encoding, and outputs a list or string of
+the same type with max_len += 1.",
+    example: "(append (list 1 2 3 4) 5) ;; Returns (1 2 3 4 5)
+(append a\"hell\" a\"o\") ;; Returns a\"hello\""
 };
 
 const ASSERTS_MAX_LEN_API: SpecialAPI = SpecialAPI {
-    input_type: "buff|list, uint",
-    output_type: "(optional buff|list)",
+    input_type: "buff|string-ascii|string-utf8|list, uint",
+    output_type: "(optional buff|string-ascii|string-utf8|list)",
     signature: "(as-max-len? buffer u10)",
-    description: "The `as-max-len?` function takes a length N (must be a literal) and a buffer or list argument, which must be typed as a list
-or buffer of length M and outputs that same list or buffer, but typed with max length N.
+    description: "The `as-max-len?` function takes a length N (must be a literal) and a buffer, string or list argument, which must be typed as a list,
+string or buffer of length M and outputs that same list, string or buffer, but typed with max length N.
 
 This function returns an optional type with the resulting iterable. If the input iterable is less than
 or equal to the supplied max-len, it returns `(some <iterable>)`, otherwise it returns `none`.",
     example: "(as-max-len? (list 2 2 2) u3) ;; Returns (some (2 2 2))
-(as-max-len? (list 1 2 3) u2) ;; Returns none"
+(as-max-len? (list 1 2 3) u2) ;; Returns none
+(as-max-len? u\"caf\\u{e9}\" u4) ;; Returns (some u\"caf\\u{e9}\")"
 };
 
 const LEN_API: SpecialAPI = SpecialAPI {
-    input_type: "buff|list",
+    input_type: "buff|string-ascii|string-utf8|list",
     output_type: "uint",
     signature: "(len buffer)",
-    description: "The `len` function returns the length of a given buffer or list.",
+    description: "The `len` function returns the length of a given buffer, string or list. The length of
+a `string-utf8` is its number of characters, not its number of bytes.",
     example: "(len \"blockstack\") ;; Returns u10
 (len (list 1 2 3 4 5)) ;; Returns u5
+(len u\"caf\\u{e9}\") ;; Returns u4
 "
 };
 
@@ -1166,7 +1174,8 @@ If an asset identified by `asset-identifier` _already exists_, this function wil
 
 `(err u1)`
 
-Otherwise, on successfuly mint, it returns `(ok true)`.
+Otherwise, on successfuly mint, it returns `(ok true)`.  An asset which was burned with `nft-burn?` no longer exists,
+so its `asset-identifier` may be minted again.
 ",
     example: "
 (define-non-fungible-token stackaroo (buff 40))
@@ -1249,6 +1258,65 @@ one of the following error codes:
 "
 };
 
+const BURN_TOKEN: SpecialAPI = SpecialAPI {
+    input_type: "TokenName, uint, principal",
+    output_type: "(response bool uint)",
+    signature: "(ft-burn? token-name amount sender)",
+    description: "`ft-burn?` is used to decrease the token balance for the `sender` principal for a token
+type defined using `define-fungible-token`. The decreased token balance is _not_ transfered to another principal, but
+rather destroyed, reducing the circulating supply.
+
+This function returns (ok true) if the burn is successful. In the event of an unsuccessful burn it returns
+one of the following error codes:
+
+`(err u1)` -- `sender` does not have enough balance to burn
+`(err u3)` -- amount to burn is non-positive
+",
+    example: "
+(define-fungible-token stackaroo)
+(ft-mint? stackaroo u100 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR)
+(ft-burn? stackaroo u50 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR) ;; returns (ok true)
+(ft-burn? stackaroo u60 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR) ;; returns (err u1)
+"
+};
+
+const BURN_ASSET: SpecialAPI = SpecialAPI {
+    input_type: "AssetName, A, principal",
+    output_type: "(response bool uint)",
+    signature: "(nft-burn? asset-class asset-identifier sender)",
+    description: "`nft-burn?` is used to destroy an asset identified by `asset-identifier` and owned by `sender`.
+The `asset-class` must have been defined by `define-non-fungible-token` and `asset-identifier`
+must be of the type specified in that definition. Once burned, the asset has no owner and may be minted again.
+
+This function returns (ok true) if the burn is successful. In the event of an unsuccessful burn it returns
+one of the following error codes:
+
+`(err u1)` -- `sender` does not own the asset
+`(err u3)` -- asset identified by asset-identifier does not exist
+",
+    example: "
+(define-non-fungible-token stackaroo (buff 40))
+(nft-mint? stackaroo \"Roo\" 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR)
+(nft-burn? stackaroo \"Roo\" 'SPAXYA5XS51713FDTQ8H94EJ4V579CXMTRNBZKSF) ;; returns (err u1)
+(nft-burn? stackaroo \"Roo\" 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR) ;; returns (ok true)
+(nft-burn? stackaroo \"Roo\" 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR) ;; returns (err u3)
+"
+};
+
+const GET_SUPPLY: SpecialAPI = SpecialAPI {
+    input_type: "TokenName",
+    output_type: "uint",
+    signature: "(ft-get-supply token-name)",
+    description: "`ft-get-supply` returns the circulating supply of `token-name`: the amount minted and not yet burned.
+The token type must have been defined using `define-fungible-token`.",
+    example: "
+(define-fungible-token stackaroo)
+(ft-mint? stackaroo u100 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR)
+(ft-burn? stackaroo u40 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR)
+(ft-get-supply stackaroo) ;; Returns u60
+"
+};
+
 const STX_GET_BALANCE: SimpleFunctionAPI = SimpleFunctionAPI {
     name: None,
     signature: "(stx-get-balance owner)",
@@ -1306,7 +1374,7 @@ one of the following error codes:
 "
 };
 
-fn make_api_reference(function: &NativeFunctions) -> FunctionAPI {
+pub fn make_api_reference(function: &NativeFunctions) -> FunctionAPI {
     use vm::functions::NativeFunctions::*;
     let name = function.get_name();
     match function {
@@ -1377,6 +1445,9 @@ fn make_api_reference(function: &NativeFunctions) -> FunctionAPI {
         GetAssetOwner => make_for_special(&GET_OWNER, name),
         TransferToken => make_for_special(&TOKEN_TRANSFER, name),
         TransferAsset => make_for_special(&ASSET_TRANSFER, name),
+        BurnToken => make_for_special(&BURN_TOKEN, name),
+        BurnAsset => make_for_special(&BURN_ASSET, name),
+        GetTokenSupply => make_for_special(&GET_SUPPLY, name),
         AtBlock => make_for_special(&AT_BLOCK, name),
         GetStxBalance => make_for_simple_native(&STX_GET_BALANCE, &GetStxBalance, name),
         StxTransfer => make_for_simple_native(&STX_TRANSFER, &StxTransfer, name),
@@ -1384,7 +1455,7 @@ fn make_api_reference(function: &NativeFunctions) -> FunctionAPI {
     }
 }
 
-fn make_keyword_reference(variable: &NativeVariables) -> Option<KeywordAPI> {
+pub fn make_keyword_reference(variable: &NativeVariables) -> Option<KeywordAPI> {
     match variable {
         NativeVariables::TxSender => Some(TX_SENDER_KEYWORD.clone()),
         NativeVariables::ContractCaller => Some(CONTRACT_CALLER_KEYWORD.clone()),
@@ -1418,7 +1489,7 @@ fn make_for_define(api: &DefineAPI, name: String) -> FunctionAPI {
     }
 }
 
-fn make_define_reference(define_type: &DefineFunctions) -> FunctionAPI {
+pub fn make_define_reference(define_type: &DefineFunctions) -> FunctionAPI {
     use vm::functions::define::DefineFunctions::*;
     let name = define_type.get_name();
     match define_type {
@@ -1436,6 +1507,26 @@ fn make_define_reference(define_type: &DefineFunctions) -> FunctionAPI {
     }
 }
 
+/// The reference for a native function, by name
+pub fn lookup_function_reference(name: &str) -> Option<FunctionAPI> {
+    NativeFunctions::lookup_by_name(name).map(|function| make_api_reference(&function))
+}
+
+/// The reference for a define function (e.g. `define-map`), by name
+pub fn lookup_define_reference(name: &str) -> Option<FunctionAPI> {
+    DefineFunctions::lookup_by_name(name).map(|define_type| make_define_reference(&define_type))
+}
+
+/// The reference for a keyword (e.g. `tx-sender`), by name
+pub fn lookup_keyword_reference(name: &str) -> Option<KeywordAPI> {
+    NativeVariables::lookup_by_name(name).and_then(|variable| make_keyword_reference(&variable))
+}
+
+/// The names of the native functions, define functions, and keywords
+pub fn native_names() -> (&'static [&'static str], &'static [&'static str], &'static [&'static str]) {
+    (NativeFunctions::ALL_NAMES, DefineFunctions::ALL_NAMES, NativeVariables::ALL_NAMES)
+}
+
 fn make_all_api_reference() -> ReferenceAPIs {
     let mut functions: Vec<_> = NativeFunctions::ALL.iter()
         .map(|x| make_api_reference(x))
@@ -12,6 +12,8 @@ enum MintAssetErrorCodes { ALREADY_EXIST = 1 }
 enum MintTokenErrorCodes { NON_POSITIVE_AMOUNT = 1 }
 enum TransferAssetErrorCodes { NOT_OWNED_BY = 1, SENDER_IS_RECIPIENT = 2, DOES_NOT_EXIST = 3 }
 enum TransferTokenErrorCodes { NOT_ENOUGH_BALANCE = 1, SENDER_IS_RECIPIENT = 2, NON_POSITIVE_AMOUNT = 3 }
+enum BurnAssetErrorCodes { NOT_OWNED_BY = 1, DOES_NOT_EXIST = 3 }
+enum BurnTokenErrorCodes { NOT_ENOUGH_BALANCE = 1, NON_POSITIVE_AMOUNT = 3 }
 enum StxErrorCodes { NOT_ENOUGH_BALANCE = 1, SENDER_IS_RECIPIENT = 2, NON_POSITIVE_AMOUNT = 3, SENDER_IS_NOT_TX_SENDER = 4 }
 
 macro_rules! clarity_ecode {
@@ -337,6 +339,123 @@ pub fn special_transfer_token(args: &[SymbolicExpression],
     }
 }
 
+pub fn special_burn_token(args: &[SymbolicExpression],
+                          env: &mut Environment,
+                          context: &LocalContext) -> Result<Value> {
+    check_argument_count(3, args)?;
+
+    runtime_cost!(cost_functions::FT_BURN, env, 0)?;
+
+    let token_name = args[0].match_atom()
+        .ok_or(CheckErrors::BadTokenName)?;
+
+    let amount = eval(&args[1], env, context)?;
+    let from =   eval(&args[2], env, context)?;
+
+    if let (Value::UInt(amount),
+            Value::Principal(ref from_principal)) = (amount, from) {
+        if amount <= 0 {
+            return clarity_ecode!(BurnTokenErrorCodes::NON_POSITIVE_AMOUNT)
+        }
+
+        let from_bal = env.global_context.database.get_ft_balance(&env.contract_context.contract_identifier, token_name, from_principal)?;
+
+        if from_bal < amount {
+            return clarity_ecode!(BurnTokenErrorCodes::NOT_ENOUGH_BALANCE)
+        }
+
+        let final_from_bal = from_bal - amount;
+
+        env.global_context.database.checked_decrease_token_supply(
+            &env.contract_context.contract_identifier, token_name, amount)?;
+
+        env.add_memory(TypeSignature::PrincipalType.size() as u64)?;
+        env.add_memory(TypeSignature::UIntType.size() as u64)?;
+
+        env.global_context.database.set_ft_balance(&env.contract_context.contract_identifier, token_name, from_principal, final_from_bal)?;
+
+        env.global_context.log_token_burn(from_principal, &env.contract_context.contract_identifier, token_name, amount)?;
+
+        let asset_identifier = AssetIdentifier {
+            contract_identifier: env.contract_context.contract_identifier.clone(),
+            asset_name: token_name.clone()
+        };
+        env.register_ft_burn_event(from_principal.clone(), amount, asset_identifier)?;
+
+        Ok(Value::okay_true())
+    } else {
+        Err(CheckErrors::BadBurnFTArguments.into())
+    }
+}
+
+pub fn special_burn_asset(args: &[SymbolicExpression],
+                          env: &mut Environment,
+                          context: &LocalContext) -> Result<Value> {
+    check_argument_count(3, args)?;
+
+    let asset_name = args[0].match_atom()
+        .ok_or(CheckErrors::BadTokenName)?;
+
+    let asset =  eval(&args[1], env, context)?;
+    let from  =  eval(&args[2], env, context)?;
+
+    let expected_asset_type = env.global_context.database.get_nft_key_type(&env.contract_context.contract_identifier, asset_name)?;
+
+    runtime_cost!(cost_functions::NFT_BURN, env, expected_asset_type.size())?;
+
+    if !expected_asset_type.admits(&asset) {
+        return Err(CheckErrors::TypeValueError(expected_asset_type, asset).into())
+    }
+
+    if let Value::Principal(ref from_principal) = from {
+        let current_owner = match env.global_context.database.get_nft_owner(&env.contract_context.contract_identifier, asset_name, &asset) {
+            Ok(owner) => Ok(owner),
+            Err(Error::Runtime(RuntimeErrorType::NoSuchToken, _)) => {
+                return clarity_ecode!(BurnAssetErrorCodes::DOES_NOT_EXIST)
+            },
+            Err(e) => Err(e)
+        }?;
+
+        if current_owner != *from_principal {
+            return clarity_ecode!(BurnAssetErrorCodes::NOT_OWNED_BY)
+        }
+
+        env.add_memory(TypeSignature::PrincipalType.size() as u64)?;
+        env.add_memory(expected_asset_type.size() as u64)?;
+
+        env.global_context.database.burn_nft(&env.contract_context.contract_identifier, asset_name, &asset)?;
+
+        env.global_context.log_asset_burn(from_principal, &env.contract_context.contract_identifier, asset_name, asset.clone());
+
+        let asset_identifier = AssetIdentifier {
+            contract_identifier: env.contract_context.contract_identifier.clone(),
+            asset_name: asset_name.clone()
+        };
+        env.register_nft_burn_event(from_principal.clone(), asset, asset_identifier)?;
+
+        Ok(Value::okay_true())
+    } else {
+        Err(CheckErrors::BadBurnNFTArguments.into())
+    }
+}
+
+pub fn special_get_token_supply(args: &[SymbolicExpression],
+                                env: &mut Environment,
+                                _context: &LocalContext) -> Result<Value> {
+    check_argument_count(1, args)?;
+
+    runtime_cost!(cost_functions::FT_SUPPLY, env, 0)?;
+
+    let token_name = args[0].match_atom()
+        .ok_or(CheckErrors::BadTokenName)?;
+
+    // a contract can only get the supply of its own tokens, and any contract using
+    //   ft-get-supply defines them with their supply tracked
+    let supply = env.global_context.database.get_ft_circulating_supply(&env.contract_context.contract_identifier, token_name)?
+        .ok_or_else(|| InterpreterError::InterpreterError(format!("Supply of {} is not tracked", token_name.as_str())))?;
+    Ok(Value::UInt(supply))
+}
+
 pub fn special_get_balance(args: &[SymbolicExpression],
                            env: &mut Environment,
                            context: &LocalContext) -> Result<Value> {
@@ -1,6 +1,7 @@
 use vm::costs::{cost_functions, CostOverflowingMath};
 use vm::errors::{CheckErrors, RuntimeErrorType, InterpreterResult as Result, check_argument_count};
-use vm::types::{Value, ListData, signatures::ListTypeData, TypeSignature::BoolType, TypeSignature};
+use vm::types::{Value, ListData, ASCIIData, UTF8Data, signatures::ListTypeData, signatures::MAX_UTF8_CHAR_SIZE,
+                TypeSignature::BoolType, TypeSignature};
 use vm::representations::{SymbolicExpression, SymbolicExpressionType};
 use vm::{LocalContext, Environment, eval, apply, lookup_function};
 use std::convert::TryInto;
@@ -152,6 +153,32 @@ pub fn special_append(args: &[SymbolicExpression], env: &mut Environment, contex
                 Err(CheckErrors::TypeValueError(entry_type, element).into())
             }
         },
+        // strings may only be appended with a single character of the same encoding
+        Value::StringASCII(ASCIIData { mut data }) => {
+            let element = eval(&args[1], env, context)?;
+            runtime_cost!(cost_functions::STRING_ASCII_APPEND, env,
+                          (data.len() as u64).cost_overflow_add(u64::from(element.size()))?)?;
+            if let Value::StringASCII(ref character) = element {
+                if character.data.len() <= 1 {
+                    data.extend_from_slice(&character.data);
+                    return Value::string_ascii_from_bytes(data)
+                }
+            }
+            Err(CheckErrors::TypeValueError(TypeSignature::StringASCIIType(1u32.try_into()?), element).into())
+        },
+        Value::StringUTF8(UTF8Data { mut data }) => {
+            let element = eval(&args[1], env, context)?;
+            runtime_cost!(cost_functions::STRING_UTF8_APPEND, env,
+                          (data.len() as u64).cost_overflow_mul(u64::from(MAX_UTF8_CHAR_SIZE))?
+                          .cost_overflow_add(u64::from(element.size()))?)?;
+            if let Value::StringUTF8(ref character) = element {
+                if character.data.len() <= 1 {
+                    data.extend_from_slice(&character.data);
+                    return Value::string_utf8_from_chars(data)
+                }
+            }
+            Err(CheckErrors::TypeValueError(TypeSignature::StringUTF8Type(1u32.try_into()?), element).into())
+        },
         _ => Err(CheckErrors::ExpectedListApplication.into())
     }
 }
@@ -162,7 +189,12 @@ pub fn special_concat(args: &[SymbolicExpression], env: &mut Environment, contex
     let lhs = eval(&args[0], env, context)?;
     let rhs = eval(&args[1], env, context)?;
 
-    runtime_cost!(cost_functions::CONCAT, env,
+    let concat_cost = match lhs {
+        Value::StringASCII(_) => cost_functions::STRING_ASCII_CONCAT,
+        Value::StringUTF8(_) => cost_functions::STRING_UTF8_CONCAT,
+        _ => cost_functions::CONCAT
+    };
+    runtime_cost!(concat_cost, env,
                   u64::from(lhs.size()).cost_overflow_add(
                       u64::from(rhs.size()))?)?;
 
@@ -177,6 +209,16 @@ pub fn special_concat(args: &[SymbolicExpression], env: &mut Environment, contex
             data.append(&mut rhs_data.data);
             Value::buff_from(data)
         },
+        (Value::StringASCII(lhs_data), Value::StringASCII(mut rhs_data)) => {
+            let mut data = lhs_data.data;
+            data.append(&mut rhs_data.data);
+            Value::string_ascii_from_bytes(data)
+        },
+        (Value::StringUTF8(lhs_data), Value::StringUTF8(mut rhs_data)) => {
+            let mut data = lhs_data.data;
+            data.append(&mut rhs_data.data);
+            Value::string_utf8_from_chars(data)
+        },
         (_, _) => {
             Err(RuntimeErrorType::BadTypeConstruction.into())
         }
@@ -194,6 +236,8 @@ pub fn special_as_max_len(args: &[SymbolicExpression], env: &mut Environment, co
         let iterable_len = match iterable {
             Value::List(ref list) => list.data.len(),
             Value::Buffer(ref buff) => buff.data.len(),
+            Value::StringASCII(ref string) => string.data.len(),
+            Value::StringUTF8(ref string) => string.data.len(),
             _ => return Err(CheckErrors::ExpectedListOrBuffer(TypeSignature::type_of(&iterable)).into())
         };
         if iterable_len as u128 > *expected_len {
@@ -214,6 +258,9 @@ pub fn native_len(iterable: Value) -> Result<Value> {
     match iterable {
         Value::List(list) => Ok(Value::UInt(list.data.len() as u128)),
         Value::Buffer(buff) => Ok(Value::UInt(buff.data.len() as u128)),
+        Value::StringASCII(string) => Ok(Value::UInt(string.data.len() as u128)),
+        // the length of a string-utf8 is its number of characters
+        Value::StringUTF8(string) => Ok(Value::UInt(string.data.len() as u128)),
         _ => Err(CheckErrors::ExpectedListOrBuffer(TypeSignature::type_of(&iterable)).into())
     }
 }
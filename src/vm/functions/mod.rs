@@ -88,6 +88,9 @@ define_named_enum!(NativeFunctions {
     GetStxBalance("stx-get-balance"),
     StxTransfer("stx-transfer?"),
     StxBurn("stx-burn?"),
+    BurnToken("ft-burn?"),
+    BurnAsset("nft-burn?"),
+    GetTokenSupply("ft-get-supply"),
 });
 
 pub fn lookup_reserved_functions(name: &str) -> Option<CallableType> {
@@ -166,6 +169,9 @@ pub fn lookup_reserved_functions(name: &str) -> Option<CallableType> {
             GetStxBalance => SpecialFunction("special_stx_balance", &assets::special_stx_balance),
             StxTransfer => SpecialFunction("special_stx_transfer", &assets::special_stx_transfer),
             StxBurn => SpecialFunction("special_stx_burn", &assets::special_stx_burn),
+            BurnToken => SpecialFunction("special_burn_token", &assets::special_burn_token),
+            BurnAsset => SpecialFunction("special_burn_asset", &assets::special_burn_asset),
+            GetTokenSupply => SpecialFunction("special_get_token_supply", &assets::special_get_token_supply),
         };
         Some(callable)
     } else {
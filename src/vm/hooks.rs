@@ -0,0 +1,32 @@
+use vm::contexts::{Environment, LocalContext};
+use vm::errors::{InterpreterResult as Result};
+use vm::representations::SymbolicExpression;
+use vm::types::Value;
+
+/// Callbacks into the evaluator, used by developer tools (tracers, coverage, debuggers) to
+///  observe a contract call as it executes.  A hook is lent to a `GlobalContext` with
+///  `OwnedEnvironment::set_eval_hook`, and is invoked for every expression evaluated in
+///  that context, including expressions in other contracts reached through `contract-call?`.
+///
+/// Hooks must not change the outcome of evaluation: they receive the `Environment` so they
+///  can inspect it, but should treat it as read-only.
+pub trait EvalHook {
+    /// Called before `expr` is evaluated.
+    fn will_begin_eval(&mut self, _env: &mut Environment, _context: &LocalContext, _expr: &SymbolicExpression) {}
+
+    /// Called once the arguments of a native or user-defined function application have been
+    ///  evaluated, just before the function is applied to them.  Special functions (like `if`
+    ///  and `let`) evaluate their own arguments, so this is not called for them.
+    fn did_evaluate_args(&mut self, _env: &mut Environment, _context: &LocalContext, _args: &[Value]) {}
+
+    /// Called after `expr` has been evaluated, with the result of evaluating it.
+    fn did_finish_eval(&mut self, _env: &mut Environment, _context: &LocalContext, _expr: &SymbolicExpression, _result: &Result<Value>) {}
+
+    /// Called when a user-defined function is applied, once its arguments have been bound in
+    ///  `context`, just before its body is evaluated.
+    fn will_call_function(&mut self, _env: &mut Environment, _context: &LocalContext, _name: &str, _args: &[Value]) {}
+
+    /// Called when a user-defined function returns, with its result (after any early return
+    ///  has been unwrapped).
+    fn did_return_from_function(&mut self, _env: &mut Environment, _name: &str, _result: &Result<Value>) {}
+}
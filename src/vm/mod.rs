@@ -21,7 +21,15 @@ mod variables;
 mod callables;
 
 pub mod docs;
+pub mod bindings;
 pub mod analysis;
+pub mod hooks;
+pub mod profiler;
+
+#[cfg(feature = "developer-mode")]
+pub mod tracing;
+#[cfg(feature = "developer-mode")]
+pub mod debugger;
 
 #[cfg(test)]
 pub mod tests;
@@ -137,6 +145,10 @@ pub fn apply(function: &CallableType, args: &[SymbolicExpression],
             used_memory += arg_value.get_memory_use();
             evaluated_args.push(arg_value);
         }
+        if let Some(hook) = env.global_context.eval_hook.take() {
+            hook.did_evaluate_args(env, context, &evaluated_args);
+            env.global_context.eval_hook = Some(hook);
+        }
         let mut resp = match function {
             CallableType::NativeFunction(_, function, cost_function) => {
                 let arg_size = evaluated_args.len();
@@ -153,22 +165,37 @@ pub fn apply(function: &CallableType, args: &[SymbolicExpression],
     }
 }
 
+fn eval_application(children: &[SymbolicExpression], env: &mut Environment, context: &LocalContext) -> Result<Value> {
+    let (function_variable, rest) = children.split_first()
+        .ok_or(CheckErrors::NonFunctionApplication)?;
+    let function_name = function_variable.match_atom()
+        .ok_or(CheckErrors::BadFunctionName)?;
+    let f = lookup_function(&function_name, env)?;
+    apply(&f, &rest, env, context)
+}
+
 pub fn eval <'a> (exp: &SymbolicExpression, env: &'a mut Environment, context: &LocalContext) -> Result<Value> {
     use vm::representations::SymbolicExpressionType::{AtomValue, Atom, List, LiteralValue, TraitReference, Field};
 
-    match exp.expr {
+    // the hook is taken out of the global context while it runs, so that it can be handed the environment
+    if let Some(hook) = env.global_context.eval_hook.take() {
+        hook.will_begin_eval(env, context, exp);
+        env.global_context.eval_hook = Some(hook);
+    }
+
+    let result = match exp.expr {
         AtomValue(ref value) | LiteralValue(ref value) => Ok(value.clone()),
         Atom(ref value) => lookup_variable(&value, context, env),
-        List(ref children) => {
-            let (function_variable, rest) = children.split_first()
-                .ok_or(CheckErrors::NonFunctionApplication)?;
-            let function_name = function_variable.match_atom()
-                .ok_or(CheckErrors::BadFunctionName)?;
-            let f = lookup_function(&function_name, env)?;
-            apply(&f, &rest, env, context)
-        },
+        List(ref children) => eval_application(children, env, context),
         TraitReference(_, _) | Field(_) => unreachable!("can't be evaluated"),
+    };
+
+    if let Some(hook) = env.global_context.eval_hook.take() {
+        hook.did_finish_eval(env, context, exp, &result);
+        env.global_context.eval_hook = Some(hook);
     }
+
+    result
 }
 
 
@@ -0,0 +1,312 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::str::FromStr;
+
+use vm::contexts::{Environment, LocalContext};
+use vm::costs::ExecutionCost;
+use vm::errors::{InterpreterResult as Result};
+use vm::functions::NativeFunctions;
+use vm::hooks::EvalHook;
+use vm::representations::SymbolicExpression;
+use vm::types::Value;
+
+/// The frame which costs incurred outside of any function or native (e.g., type-checking the
+///  arguments of a contract call) are attributed to.
+pub const UNATTRIBUTED_FRAME: &str = "[transaction]";
+
+/// A single dimension of `ExecutionCost`, for reports which can only show one number per stack.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CostMetric {
+    Runtime,
+    ReadCount,
+    ReadLength,
+    WriteCount,
+    WriteLength,
+}
+
+impl CostMetric {
+    pub fn of(&self, cost: &ExecutionCost) -> u64 {
+        match self {
+            CostMetric::Runtime => cost.runtime,
+            CostMetric::ReadCount => cost.read_count,
+            CostMetric::ReadLength => cost.read_length,
+            CostMetric::WriteCount => cost.write_count,
+            CostMetric::WriteLength => cost.write_length,
+        }
+    }
+}
+
+impl FromStr for CostMetric {
+    type Err = String;
+    fn from_str(s: &str) -> std::result::Result<CostMetric, String> {
+        match s {
+            "runtime" => Ok(CostMetric::Runtime),
+            "read_count" => Ok(CostMetric::ReadCount),
+            "read_length" => Ok(CostMetric::ReadLength),
+            "write_count" => Ok(CostMetric::WriteCount),
+            "write_length" => Ok(CostMetric::WriteLength),
+            _ => Err(format!("Unknown cost metric: {}", s))
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileEntry {
+    pub calls: u64,
+    /// Cost incurred by the entry itself, excluding the entries it invoked
+    pub self_cost: ExecutionCost,
+    /// Cost incurred by the entry and everything it invoked
+    pub total_cost: ExecutionCost,
+}
+
+/// Where the cost of an execution went.  Entries are named after the user-defined function
+///  (`<contract-identifier>::<name>`) or native (`map-get?`) applied; natives which access a data
+///  map, data var or token name it, as in `map-get?(balances)`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostProfile {
+    /// Cost incurred directly in each call stack, keyed by the stack's entry names joined by `;`
+    pub stacks: BTreeMap<String, ExecutionCost>,
+    pub entries: BTreeMap<String, ProfileEntry>,
+    pub total: ExecutionCost,
+}
+
+impl CostProfile {
+    /// Render the profile in the folded-stack format read by `flamegraph.pl` and `inferno`,
+    ///  weighing each stack by one dimension of its cost.
+    pub fn make_folded_report(&self, metric: CostMetric) -> String {
+        let mut output = String::new();
+        for (stack, cost) in self.stacks.iter() {
+            let weight = metric.of(cost);
+            if weight > 0 {
+                writeln!(&mut output, "{} {}", stack, weight).unwrap();
+            }
+        }
+        output
+    }
+
+    /// Render a table of the entries, most expensive (by total runtime) first.
+    pub fn make_summary(&self) -> String {
+        let mut entries: Vec<_> = self.entries.iter().collect();
+        entries.sort_by(|(a_name, a), (b_name, b)| b.total_cost.runtime.cmp(&a.total_cost.runtime)
+                        .then_with(|| a_name.cmp(b_name)));
+
+        let mut output = String::new();
+        writeln!(&mut output, "{:>8} {:>12} {:>12} {:>8} {:>12} {:>8} {:>12}  {}",
+                 "calls", "self runtime", "runtime", "reads", "read length", "writes", "write length", "name").unwrap();
+        for (name, entry) in entries.iter() {
+            let cost = &entry.total_cost;
+            writeln!(&mut output, "{:>8} {:>12} {:>12} {:>8} {:>12} {:>8} {:>12}  {}",
+                     entry.calls, entry.self_cost.runtime, cost.runtime, cost.read_count, cost.read_length,
+                     cost.write_count, cost.write_length, name).unwrap();
+        }
+        writeln!(&mut output, "total: {}", self.total).unwrap();
+        output
+    }
+}
+
+struct Frame {
+    name: String,
+    start: ExecutionCost,
+    // total cost of the frames opened within this one
+    children: ExecutionCost,
+}
+
+/// An `EvalHook` which attributes the cost of an execution to the functions, natives, and data
+///  it touched, by sampling the cost tracker's running total as each is entered and left.
+pub struct CostProfiler {
+    frames: Vec<Frame>,
+    // whether each expression being evaluated opened a frame
+    opened: Vec<bool>,
+    profile: CostProfile,
+    // total cost of the outermost frames
+    attributed: ExecutionCost,
+}
+
+fn cost_since(start: &ExecutionCost, now: &ExecutionCost) -> ExecutionCost {
+    let mut cost = now.clone();
+    match cost.sub(start) {
+        Ok(_) => cost,
+        Err(_) => ExecutionCost::zero()
+    }
+}
+
+// Costs are bounded by the tracker's total, which is itself checked for overflow, so any
+//  overflow accumulating them is ignored.
+fn accumulate(into: &mut ExecutionCost, cost: &ExecutionCost) {
+    let _ = into.add(cost);
+}
+
+// The frame name of a native application, or None if `expr` isn't one.  User-defined function
+//   applications open their frames when the function is called instead.
+fn native_frame_name(expr: &SymbolicExpression) -> Option<String> {
+    use vm::functions::NativeFunctions::*;
+
+    let list = expr.match_list()?;
+    let name = list.get(0)?.match_atom()?;
+    let native = NativeFunctions::lookup_by_name(name)?;
+    match native {
+        FetchVar | SetVar | FetchEntry | SetEntry | InsertEntry | DeleteEntry |
+        GetTokenBalance | GetAssetOwner | TransferToken | TransferAsset | MintAsset | MintToken |
+        BurnToken | BurnAsset | GetTokenSupply => {
+            match list.get(1).and_then(|target| target.match_atom()) {
+                Some(target) => Some(format!("{}({})", name.as_str(), target.as_str())),
+                None => Some(name.to_string())
+            }
+        },
+        _ => Some(name.to_string())
+    }
+}
+
+impl CostProfiler {
+    pub fn new() -> CostProfiler {
+        CostProfiler {
+            frames: vec![],
+            opened: vec![],
+            profile: CostProfile {
+                stacks: BTreeMap::new(),
+                entries: BTreeMap::new(),
+                total: ExecutionCost::zero(),
+            },
+            attributed: ExecutionCost::zero(),
+        }
+    }
+
+    /// Finish profiling an execution which cost `total` in all.  Any of it which wasn't incurred
+    ///  within a frame is attributed to `UNATTRIBUTED_FRAME`.
+    pub fn into_profile(self, total: ExecutionCost) -> CostProfile {
+        let CostProfiler { mut profile, attributed, .. } = self;
+        let unattributed = cost_since(&attributed, &total);
+        if unattributed != ExecutionCost::zero() {
+            accumulate(profile.stacks.entry(UNATTRIBUTED_FRAME.to_string()).or_insert_with(ExecutionCost::zero),
+                       &unattributed);
+            profile.entries.insert(UNATTRIBUTED_FRAME.to_string(), ProfileEntry {
+                calls: 1,
+                self_cost: unattributed.clone(),
+                total_cost: unattributed,
+            });
+        }
+        profile.total = total;
+        profile
+    }
+
+    fn push_frame(&mut self, env: &Environment, name: String) {
+        self.frames.push(Frame {
+            name,
+            start: env.global_context.cost_track.get_total(),
+            children: ExecutionCost::zero(),
+        });
+    }
+
+    fn pop_frame(&mut self, env: &Environment) {
+        let stack = self.frames.iter().map(|frame| frame.name.as_str()).collect::<Vec<_>>().join(";");
+        let frame = match self.frames.pop() {
+            Some(frame) => frame,
+            None => return
+        };
+
+        let total_cost = cost_since(&frame.start, &env.global_context.cost_track.get_total());
+        let self_cost = cost_since(&frame.children, &total_cost);
+
+        accumulate(self.profile.stacks.entry(stack).or_insert_with(ExecutionCost::zero), &self_cost);
+
+        let entry = self.profile.entries.entry(frame.name).or_insert_with(|| ProfileEntry {
+            calls: 0,
+            self_cost: ExecutionCost::zero(),
+            total_cost: ExecutionCost::zero(),
+        });
+        entry.calls += 1;
+        accumulate(&mut entry.self_cost, &self_cost);
+        accumulate(&mut entry.total_cost, &total_cost);
+
+        match self.frames.last_mut() {
+            Some(parent) => accumulate(&mut parent.children, &total_cost),
+            None => accumulate(&mut self.attributed, &total_cost)
+        }
+    }
+}
+
+impl EvalHook for CostProfiler {
+    fn will_begin_eval(&mut self, env: &mut Environment, _context: &LocalContext, expr: &SymbolicExpression) {
+        match native_frame_name(expr) {
+            Some(name) => {
+                self.push_frame(env, name);
+                self.opened.push(true);
+            },
+            None => self.opened.push(false)
+        }
+    }
+
+    fn did_finish_eval(&mut self, env: &mut Environment, _context: &LocalContext, _expr: &SymbolicExpression, _result: &Result<Value>) {
+        if let Some(true) = self.opened.pop() {
+            self.pop_frame(env);
+        }
+    }
+
+    fn will_call_function(&mut self, env: &mut Environment, _context: &LocalContext, name: &str, _args: &[Value]) {
+        let name = format!("{}::{}", env.contract_context.contract_identifier, name);
+        self.push_frame(env, name);
+    }
+
+    fn did_return_from_function(&mut self, env: &mut Environment, _name: &str, _result: &Result<Value>) {
+        self.pop_frame(env);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use vm::contexts::OwnedEnvironment;
+    use vm::database::MemoryBackingStore;
+    use vm::types::{PrincipalData, QualifiedContractIdentifier};
+
+    const CONTRACT: &str = "(define-map balances ((owner principal)) ((amount uint)))
+(define-private (credit (owner principal) (amount uint))
+  (map-set balances (tuple (owner owner)) (tuple (amount amount))))
+(define-public (deposit (amount uint))
+  (begin
+    (credit tx-sender amount)
+    (ok (map-get? balances (tuple (owner tx-sender))))))
+";
+
+    #[test]
+    fn test_cost_attribution() {
+        let contract_identifier = QualifiedContractIdentifier::local("bank").unwrap();
+        let sender = Value::Principal(PrincipalData::parse("S1G2081040G2081040G2081040G208105NK8PE5").unwrap());
+
+        let mut profiler = CostProfiler::new();
+        let mut marf = MemoryBackingStore::new();
+        let total = {
+            let mut owned_env = OwnedEnvironment::new(marf.as_clarity_db());
+            owned_env.initialize_contract(contract_identifier.clone(), CONTRACT).unwrap();
+            let before = owned_env.get_cost_total();
+            owned_env.set_eval_hook(&mut profiler);
+            owned_env.execute_transaction(
+                sender, contract_identifier.clone(), "deposit", &[SymbolicExpression::atom_value(Value::UInt(10))]).unwrap();
+            cost_since(&before, &owned_env.get_cost_total())
+        };
+        let profile = profiler.into_profile(total.clone());
+
+        let deposit = format!("{}::deposit", contract_identifier);
+        let credit = format!("{}::credit", contract_identifier);
+        assert_eq!(profile.entries[&deposit].calls, 1);
+        assert_eq!(profile.entries[&credit].calls, 1);
+        assert_eq!(profile.entries["map-set(balances)"].total_cost.write_count, 1);
+        assert_eq!(profile.entries["map-get?(balances)"].total_cost.read_count, 1);
+        assert_eq!(profile.entries["tuple"].calls, 3);
+
+        // everything the call cost is attributed exactly once
+        let mut attributed = ExecutionCost::zero();
+        for cost in profile.stacks.values() {
+            attributed.add(cost).unwrap();
+        }
+        assert_eq!(attributed, total);
+        assert_eq!(profile.total, total);
+
+        let credit_stack = format!("{};begin;{};map-set(balances)", deposit, credit);
+        assert_eq!(profile.stacks[&credit_stack], profile.entries["map-set(balances)"].self_cost);
+
+        let folded = profile.make_folded_report(CostMetric::WriteCount);
+        assert_eq!(folded, format!("{} 1\n", credit_stack));
+        assert!(profile.make_summary().contains("map-get?(balances)"));
+    }
+}
@@ -5,6 +5,7 @@ use vm::contexts::{OwnedEnvironment, GlobalContext, AssetMap, AssetMapEntry};
 use chainstate::stacks::events::{StacksTransactionEvent};
 use vm::representations::SymbolicExpression;
 use vm::contracts::Contract;
+use vm::database::ClarityDatabase;
 use util::hash::hex_bytes;
 use vm::tests::{with_memory_environment, with_marfed_environment, symbols_from_values,
                 execute, is_err_code, is_committed};
@@ -525,6 +526,137 @@ fn total_supply(owned_env: &mut OwnedEnvironment) {
     });
 }
 
+fn test_burn_and_supply(owned_env: &mut OwnedEnvironment) {
+    let contract = "(define-fungible-token stackaroos)
+         (define-non-fungible-token names int)
+         (define-read-only (get-supply)
+            (ft-get-supply stackaroos))
+         (define-public (burn-tokens (amount uint))
+            (ft-burn? stackaroos amount tx-sender))
+         (define-public (burn-name (name int))
+            (nft-burn? names name tx-sender))
+         (define-public (mint-name (name int))
+            (nft-mint? names name tx-sender))
+         (define-read-only (get-name-owner (name int))
+            (nft-get-owner? names name))
+         (begin (ft-mint? stackaroos u100 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR)
+                (ft-mint? stackaroos u20 'SM2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQVX8X0G)
+                (nft-mint? names 1 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR))";
+
+    let p1 = execute("'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR");
+    let p2 = execute("'SM2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQVX8X0G");
+
+    let p1_principal = match p1 {
+        Value::Principal(PrincipalData::Standard(ref data)) => data.clone(),
+        _ => panic!()
+    };
+
+    let contract_id = QualifiedContractIdentifier::new(p1_principal.clone(), "tokens".into());
+    let token_identifier = AssetIdentifier { contract_identifier: contract_id.clone(),
+                                             asset_name: "stackaroos".into() };
+    let names_identifier = AssetIdentifier { contract_identifier: contract_id.clone(),
+                                             asset_name: "names".into() };
+
+    owned_env.initialize_contract(contract_id.clone(), contract).unwrap();
+
+    let (result, _asset_map, _events) = execute_transaction(owned_env,
+        p1.clone(), &contract_id, "get-supply", &vec![]).unwrap();
+    assert_eq!(result, Value::UInt(120));
+
+    let (result, asset_map, _events) = execute_transaction(owned_env,
+        p1.clone(), &contract_id, "burn-tokens", &symbols_from_values(vec![Value::UInt(30)])).unwrap();
+    assert!(is_committed(&result));
+
+    let asset_map = asset_map.to_table();
+    assert_eq!(asset_map[&PrincipalData::Standard(p1_principal.clone())][&token_identifier], AssetMapEntry::Token(30));
+
+    let (result, asset_map, _events) = execute_transaction(owned_env,
+        p2.clone(), &contract_id, "burn-tokens", &symbols_from_values(vec![Value::UInt(21)])).unwrap();
+    assert!(is_err_code(&result, 1));
+    assert_eq!(asset_map.to_table().len(), 0);
+
+    let (result, _asset_map, _events) = execute_transaction(owned_env,
+        p2.clone(), &contract_id, "burn-tokens", &symbols_from_values(vec![Value::UInt(0)])).unwrap();
+    assert!(is_err_code(&result, 3));
+
+    let (result, _asset_map, _events) = execute_transaction(owned_env,
+        p1.clone(), &contract_id, "get-supply", &vec![]).unwrap();
+    assert_eq!(result, Value::UInt(90));
+
+    let (result, asset_map, _events) = execute_transaction(owned_env,
+        p2.clone(), &contract_id, "burn-name", &symbols_from_values(vec![Value::Int(1)])).unwrap();
+    assert!(is_err_code(&result, 1));
+    assert_eq!(asset_map.to_table().len(), 0);
+
+    let (result, asset_map, _events) = execute_transaction(owned_env,
+        p1.clone(), &contract_id, "burn-name", &symbols_from_values(vec![Value::Int(1)])).unwrap();
+    assert!(is_committed(&result));
+
+    let asset_map = asset_map.to_table();
+    assert_eq!(asset_map[&PrincipalData::Standard(p1_principal.clone())][&names_identifier],
+               AssetMapEntry::Asset(vec![Value::Int(1)]));
+
+    let (result, _asset_map, _events) = execute_transaction(owned_env,
+        p1.clone(), &contract_id, "burn-name", &symbols_from_values(vec![Value::Int(1)])).unwrap();
+    assert!(is_err_code(&result, 3));
+
+    let (result, _asset_map, _events) = execute_transaction(owned_env,
+        p1.clone(), &contract_id, "get-name-owner", &symbols_from_values(vec![Value::Int(1)])).unwrap();
+    assert_eq!(result, Value::none());
+
+    // a burned name has no owner, so it can be minted again, by anyone
+    let (result, _asset_map, _events) = execute_transaction(owned_env,
+        p2.clone(), &contract_id, "mint-name", &symbols_from_values(vec![Value::Int(1)])).unwrap();
+    assert!(is_committed(&result));
+
+    let (result, _asset_map, _events) = execute_transaction(owned_env,
+        p1.clone(), &contract_id, "get-name-owner", &symbols_from_values(vec![Value::Int(1)])).unwrap();
+    assert_eq!(result, Value::some(p2.clone()).unwrap());
+
+    // ...but only once
+    let (result, _asset_map, _events) = execute_transaction(owned_env,
+        p1.clone(), &contract_id, "mint-name", &symbols_from_values(vec![Value::Int(1)])).unwrap();
+    assert!(is_err_code(&result, 1));
+}
+
+fn test_unbounded_supply(owned_env: &mut OwnedEnvironment) {
+    let contract = "(define-fungible-token stackaroos)
+         (define-read-only (get-supply)
+            (ft-get-supply stackaroos))
+         (define-public (mint (amount uint))
+            (ft-mint? stackaroos amount tx-sender))";
+
+    let p1 = execute("'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR");
+
+    let p1_principal = match p1 {
+        Value::Principal(PrincipalData::Standard(ref data)) => data.clone(),
+        _ => panic!()
+    };
+
+    let contract_id = QualifiedContractIdentifier::new(p1_principal.clone(), "tokens".into());
+
+    owned_env.initialize_contract(contract_id.clone(), contract).unwrap();
+
+    // an unbounded token is deployed without a circulating supply entry
+    let supply_key = ClarityDatabase::make_key_for_ft_supply(&contract_id, "stackaroos");
+    let (stored_supply, _asset_map, _events) = owned_env.execute_in_env(p1.clone(), |env| {
+        Ok(env.global_context.database.get::<u128>(&supply_key))
+    }).unwrap();
+    assert_eq!(stored_supply, None);
+
+    let (result, _asset_map, _events) = execute_transaction(owned_env,
+        p1.clone(), &contract_id, "get-supply", &vec![]).unwrap();
+    assert_eq!(result, Value::UInt(0));
+
+    let (result, _asset_map, _events) = execute_transaction(owned_env,
+        p1.clone(), &contract_id, "mint", &symbols_from_values(vec![Value::UInt(50)])).unwrap();
+    assert!(is_committed(&result));
+
+    let (result, _asset_map, _events) = execute_transaction(owned_env,
+        p1.clone(), &contract_id, "get-supply", &vec![]).unwrap();
+    assert_eq!(result, Value::UInt(50));
+}
+
 fn test_overlapping_nfts(owned_env: &mut OwnedEnvironment) {
     let tokens_contract = FIRST_CLASS_TOKENS;
     let names_contract = ASSET_NAMES;
@@ -731,7 +863,8 @@ fn test_simple_naming_system(owned_env: &mut OwnedEnvironment) {
 #[test]
 fn test_all() {
     let to_test = [test_overlapping_nfts, test_simple_token_system,
-                   test_simple_naming_system, total_supply, test_native_stx_ops];
+                   test_simple_naming_system, total_supply, test_native_stx_ops,
+                   test_burn_and_supply, test_unbounded_supply];
     for test in to_test.iter() {
         with_memory_environment(test, true);
         with_marfed_environment(test, true);
@@ -93,6 +93,9 @@ pub fn get_simple_test(function: &NativeFunctions) -> &'static str {
         GetStxBalance => "(stx-get-balance 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR)",
         StxTransfer => "(stx-transfer? u1 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR)",
         StxBurn => "(stx-burn? u1 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR)",
+        BurnToken => "(ft-burn? ft-foo u1 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR)",
+        BurnAsset => "(nft-burn? nft-foo 1 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR)",
+        GetTokenSupply => "(ft-get-supply ft-foo)",
     }
 }
 
@@ -117,6 +120,10 @@ fn test_tracked_costs(prog: &str) -> ExecutionCost {
                          (define-constant tuple-foo (tuple (a 1)))
                          (define-constant list-foo (list true))
                          (define-constant list-bar (list 1))
+                         (define-constant ascii-min a\"a\")
+                         (define-constant ascii-max a\"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\")
+                         (define-constant utf8-min u\"a\")
+                         (define-constant utf8-max u\"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa\")
                          (use-trait trait-1 .contract-trait.trait-1)
                          (define-public (execute (contract <trait-1>)) (ok {}))", prog);
 
@@ -171,3 +178,19 @@ fn test_all() {
         assert!(cost.exceeds(&baseline));
     }
 }
+
+#[test]
+fn test_string_costs() {
+    // looking up a longer string costs more, but appending to or concatenating it should cost
+    //  more still, since the whole string is rebuilt
+    for (min, max, character) in [("ascii-min", "ascii-max", "a\"b\""), ("utf8-min", "utf8-max", "u\"b\"")].iter() {
+        let len_delta = test_tracked_costs(&format!("(len {})", max)).runtime
+            - test_tracked_costs(&format!("(len {})", min)).runtime;
+        let append_delta = test_tracked_costs(&format!("(append {} {})", max, character)).runtime
+            - test_tracked_costs(&format!("(append {} {})", min, character)).runtime;
+        let concat_delta = test_tracked_costs(&format!("(concat {} {})", max, character)).runtime
+            - test_tracked_costs(&format!("(concat {} {})", min, character)).runtime;
+        assert!(append_delta > len_delta);
+        assert!(concat_delta > len_delta);
+    }
+}
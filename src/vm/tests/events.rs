@@ -326,3 +326,87 @@ fn test_emit_nft_mint_nok() {
     assert_eq!(value, Value::error(Value::UInt(1)).unwrap());
     assert_eq!(events.len(), 0);
 }
+
+#[test]
+fn test_emit_ft_burn_ok() {
+    let contract =
+        "(define-constant sender 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR)
+        (define-fungible-token token)
+        (define-public (emit-event-ok)
+            (begin
+                (unwrap-panic (ft-burn? token u4 sender))
+                (ok u1)))
+        (begin (ft-mint? token u10 sender))";
+
+    let (value, mut events) = helper_execute(contract, "emit-event-ok");
+    assert_eq!(value, Value::okay(Value::UInt(1)).unwrap());
+    assert_eq!(events.len(), 1);
+    match events.pop() {
+        Some(StacksTransactionEvent::FTEvent(FTEventType::FTBurnEvent(data))) => {
+            let contract_identifier = QualifiedContractIdentifier::local("contract").unwrap();
+            let asset_identifier = AssetIdentifier { contract_identifier, asset_name: "token".into() };
+            assert_eq!(data.asset_identifier, asset_identifier);
+            assert_eq!(data.amount, 4u128);
+            assert_eq!(Value::Principal(data.sender), execute("'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR"));
+        },
+        _ => panic!("assertion failed")
+    };
+}
+
+#[test]
+fn test_emit_ft_burn_nok() {
+    let contract =
+        "(define-constant sender 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR)
+        (define-fungible-token token)
+        (define-public (emit-event-nok)
+            (begin
+                (unwrap-panic (ft-burn? token u4 sender))
+                (err u1)))
+        (begin (ft-mint? token u10 sender))";
+
+    let (value, events) = helper_execute(contract, "emit-event-nok");
+    assert_eq!(value, Value::error(Value::UInt(1)).unwrap());
+    assert_eq!(events.len(), 0);
+}
+
+#[test]
+fn test_emit_nft_burn_ok() {
+    let contract =
+        "(define-constant sender 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR)
+        (define-non-fungible-token token uint)
+        (define-public (emit-event-ok)
+            (begin
+                (unwrap-panic (nft-burn? token u1 sender))
+                (ok u1)))
+        (begin (nft-mint? token u1 sender))";
+
+    let (value, mut events) = helper_execute(contract, "emit-event-ok");
+    assert_eq!(value, Value::okay(Value::UInt(1)).unwrap());
+    assert_eq!(events.len(), 1);
+    match events.pop() {
+        Some(StacksTransactionEvent::NFTEvent(NFTEventType::NFTBurnEvent(data))) => {
+            let contract_identifier = QualifiedContractIdentifier::local("contract").unwrap();
+            let asset_identifier = AssetIdentifier { contract_identifier, asset_name: "token".into() };
+            assert_eq!(data.asset_identifier, asset_identifier);
+            assert_eq!(data.value, execute("u1"));
+            assert_eq!(Value::Principal(data.sender), execute("'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR"));
+        },
+        _ => panic!("assertion failed")
+    };
+}
+
+#[test]
+fn test_emit_nft_burn_nok() {
+    let contract =
+        "(define-constant sender 'SZ2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKQ9H6DPR)
+        (define-non-fungible-token token uint)
+        (define-public (emit-event-nok)
+            (begin
+                (unwrap-panic (nft-burn? token u1 sender))
+                (err u1)))
+        (begin (nft-mint? token u1 sender))";
+
+    let (value, events) = helper_execute(contract, "emit-event-nok");
+    assert_eq!(value, Value::error(Value::UInt(1)).unwrap());
+    assert_eq!(events.len(), 0);
+}
@@ -1,5 +1,5 @@
 use vm::types::{Value, TypeSignature};
-use vm::types::TypeSignature::{IntType, UIntType, BoolType, ListType, BufferType};
+use vm::types::TypeSignature::{IntType, UIntType, BoolType, ListType, BufferType, StringASCIIType, StringUTF8Type};
 use vm::types::signatures::{ListTypeData};
 
 use vm::execute;
@@ -341,6 +341,94 @@ fn test_buff_len() {
     assert_eq!(expected, execute(test1).unwrap().unwrap());
 }
 
+#[test]
+fn test_string_len() {
+    let tests = [
+        "(len a\"blockstack\")",
+        "(len a\"\")",
+        // characters, not bytes
+        "(len u\"caf\\u{e9} \\u{1F600}\")"];
+
+    let expected = [
+        Value::UInt(10),
+        Value::UInt(0),
+        Value::UInt(6)];
+
+    for (test, expected) in tests.iter().zip(expected.iter()) {
+        assert_eq!(expected.clone(), execute(test).unwrap().unwrap());
+    }
+}
+
+#[test]
+fn test_simple_string_concat() {
+    let tests = [
+        "(concat a\"hello \" a\"world\")",
+        "(concat u\"caf\\u{e9} \" u\"ol\\u{e9}\")",
+        "(concat a\"\" a\"\")"];
+
+    let expected = [
+        Value::string_ascii_from_bytes(b"hello world".to_vec()).unwrap(),
+        Value::string_utf8_from_str("caf\u{e9} ol\u{e9}").unwrap(),
+        Value::string_ascii_from_bytes(vec![]).unwrap()];
+
+    for (test, expected) in tests.iter().zip(expected.iter()) {
+        assert_eq!(expected.clone(), execute(test).unwrap().unwrap());
+    }
+
+    assert_eq!(
+        execute("(concat a\"hello\" u\"world\")").unwrap_err(),
+        RuntimeErrorType::BadTypeConstruction.into());
+
+    assert_eq!(
+        execute("(concat a\"hello\" \"world\")").unwrap_err(),
+        RuntimeErrorType::BadTypeConstruction.into());
+}
+
+#[test]
+fn test_simple_string_append() {
+    let tests = [
+        "(append a\"hell\" a\"o\")",
+        "(append u\"caf\" u\"\\u{e9}\")",
+        "(append a\"\" a\"\")"];
+
+    let expected = [
+        Value::string_ascii_from_bytes(b"hello".to_vec()).unwrap(),
+        Value::string_utf8_from_str("caf\u{e9}").unwrap(),
+        Value::string_ascii_from_bytes(vec![]).unwrap()];
+
+    for (test, expected) in tests.iter().zip(expected.iter()) {
+        assert_eq!(expected.clone(), execute(test).unwrap().unwrap());
+    }
+
+    assert_eq!(
+        execute("(append a\"hell\" a\"oo\")").unwrap_err(),
+        CheckErrors::TypeValueError(StringASCIIType(1_u32.try_into().unwrap()),
+                                    Value::string_ascii_from_bytes(b"oo".to_vec()).unwrap()).into());
+
+    assert_eq!(
+        execute("(append u\"caf\" a\"e\")").unwrap_err(),
+        CheckErrors::TypeValueError(StringUTF8Type(1_u32.try_into().unwrap()),
+                                    Value::string_ascii_from_bytes(b"e".to_vec()).unwrap()).into());
+}
+
+#[test]
+fn test_simple_string_assert_max_len() {
+    let tests = [
+        "(as-max-len? a\"123\" u3)",
+        "(as-max-len? a\"123\" u2)",
+        "(as-max-len? u\"caf\\u{e9}\" u4)",
+        "(as-max-len? u\"caf\\u{e9}\" u3)"];
+
+    let expected = [
+        Value::some(Value::string_ascii_from_bytes(b"123".to_vec()).unwrap()).unwrap(),
+        Value::none(),
+        Value::some(Value::string_utf8_from_str("caf\u{e9}").unwrap()).unwrap(),
+        Value::none()];
+
+    for (test, expected) in tests.iter().zip(expected.iter()) {
+        assert_eq!(expected.clone(), execute(test).unwrap().unwrap());
+    }
+}
 
 #[test]
 fn test_construct_bad_list() {
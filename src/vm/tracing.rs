@@ -0,0 +1,322 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+
+use vm::contexts::{Environment, LocalContext};
+use vm::costs::ExecutionCost;
+use vm::errors::{Error, InterpreterResult as Result};
+use vm::hooks::EvalHook;
+use vm::representations::{Span, SymbolicExpression, SymbolicExpressionType};
+use vm::types::{QualifiedContractIdentifier, Value};
+
+/// One evaluated expression in an execution trace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceEntry {
+    /// Nesting depth of the expression within the traced execution
+    pub depth: usize,
+    pub contract_identifier: QualifiedContractIdentifier,
+    pub span: Span,
+    /// Name of the function applied, if the expression is a function application
+    pub callee: Option<String>,
+    /// Evaluated arguments, for applications of native and user-defined functions
+    pub arguments: Option<Vec<Value>>,
+    /// The value of the expression, or a description of the error raised evaluating it
+    pub result: Option<std::result::Result<Value, String>>,
+    /// The cost incurred evaluating the expression, including its subexpressions
+    pub cost: ExecutionCost,
+}
+
+/// An `EvalHook` which records every evaluated expression, along with how many times each
+///  source location was reached.  The latter can be rendered as an lcov coverage report.
+pub struct ExecutionTracer {
+    record_trace: bool,
+    trace: Vec<TraceEntry>,
+    // trace index and running cost total of each expression being evaluated
+    open: Vec<(usize, ExecutionCost)>,
+    hits: HashMap<QualifiedContractIdentifier, HashMap<(u32, u32), u64>>,
+    failed_at: Option<(QualifiedContractIdentifier, Span)>,
+}
+
+impl ExecutionTracer {
+    /// Make a tracer that records a full trace as well as coverage.
+    pub fn new() -> ExecutionTracer {
+        ExecutionTracer {
+            record_trace: true,
+            trace: vec![],
+            open: vec![],
+            hits: HashMap::new(),
+            failed_at: None,
+        }
+    }
+
+    /// Make a tracer that only records coverage.  Use this for long-running executions.
+    pub fn coverage_only() -> ExecutionTracer {
+        ExecutionTracer {
+            record_trace: false,
+            .. ExecutionTracer::new()
+        }
+    }
+
+    pub fn get_trace(&self) -> &[TraceEntry] {
+        &self.trace
+    }
+
+    /// The location of the innermost expression which raised an error, if any did.
+    pub fn get_failure_location(&self) -> Option<&(QualifiedContractIdentifier, Span)> {
+        self.failed_at.as_ref()
+    }
+
+    /// Render the trace, one expression per line, indented by depth.
+    pub fn format_trace(&self) -> String {
+        let mut output = String::new();
+        for entry in self.trace.iter() {
+            let mut line = format!("{}{} {}:{}", "  ".repeat(entry.depth), &entry.contract_identifier,
+                                   entry.span.start_line, entry.span.start_column);
+            if let Some(ref callee) = entry.callee {
+                write!(&mut line, " ({}", callee).unwrap();
+                if let Some(ref args) = entry.arguments {
+                    for arg in args.iter() {
+                        write!(&mut line, " {}", arg).unwrap();
+                    }
+                }
+                line.push(')');
+            }
+            match entry.result {
+                Some(Ok(ref value)) => write!(&mut line, " -> {}", value).unwrap(),
+                Some(Err(ref error)) => write!(&mut line, " -> error: {}", error).unwrap(),
+                None => {}
+            }
+            writeln!(&mut output, "{} {}", line, entry.cost).unwrap();
+        }
+        output
+    }
+
+    /// Render an lcov tracefile of a contract's coverage.  `expressions` is the contract's
+    ///  AST, used to find every line that could have been reached, and `source_path` is the
+    ///  file name to report the coverage under.
+    pub fn make_lcov_report(&self, contract_identifier: &QualifiedContractIdentifier,
+                            expressions: &[SymbolicExpression], source_path: &str) -> String {
+        let empty = HashMap::new();
+        let hits = self.hits.get(contract_identifier).unwrap_or(&empty);
+
+        let mut reachable = BTreeSet::new();
+        let mut functions = vec![];
+        for expression in expressions.iter() {
+            match defined_function(expression) {
+                Some((name, body)) => {
+                    let count = hits.get(&(body.span.start_line, body.span.start_column)).cloned().unwrap_or(0);
+                    functions.push((expression.span.start_line, name, count));
+                    collect_lines(body, &mut reachable);
+                },
+                None => collect_lines(expression, &mut reachable)
+            }
+        }
+
+        let mut line_hits = BTreeMap::new();
+        for line in reachable.into_iter() {
+            line_hits.insert(line, 0);
+        }
+        for ((line, _), count) in hits.iter() {
+            if let Some(line_count) = line_hits.get_mut(line) {
+                *line_count += count;
+            }
+        }
+
+        let mut output = String::new();
+        writeln!(&mut output, "TN:").unwrap();
+        writeln!(&mut output, "SF:{}", source_path).unwrap();
+        for (line, name, _) in functions.iter() {
+            writeln!(&mut output, "FN:{},{}", line, name).unwrap();
+        }
+        for (_, name, count) in functions.iter() {
+            writeln!(&mut output, "FNDA:{},{}", count, name).unwrap();
+        }
+        writeln!(&mut output, "FNF:{}", functions.len()).unwrap();
+        writeln!(&mut output, "FNH:{}", functions.iter().filter(|(_, _, count)| *count > 0).count()).unwrap();
+        for (line, count) in line_hits.iter() {
+            writeln!(&mut output, "DA:{},{}", line, count).unwrap();
+        }
+        writeln!(&mut output, "LF:{}", line_hits.len()).unwrap();
+        writeln!(&mut output, "LH:{}", line_hits.values().filter(|count| **count > 0).count()).unwrap();
+        writeln!(&mut output, "end_of_record").unwrap();
+        output
+    }
+}
+
+/// If `expression` defines a function, return its name and body.
+fn defined_function(expression: &SymbolicExpression) -> Option<(String, &SymbolicExpression)> {
+    let list = expression.match_list()?;
+    match list.get(0)?.match_atom()?.as_str() {
+        "define-public" | "define-private" | "define-read-only" => {
+            let name = list.get(1)?.match_list()?.get(0)?.match_atom()?;
+            Some((name.to_string(), list.get(2)?))
+        },
+        _ => None
+    }
+}
+
+// Collect the lines of every expression which may be evaluated.  The function name at the head
+//   of an application is looked up rather than evaluated, so it is skipped.
+fn collect_lines(expression: &SymbolicExpression, lines: &mut BTreeSet<u32>) {
+    if expression.span.start_line > 0 {
+        lines.insert(expression.span.start_line);
+    }
+    if let SymbolicExpressionType::List(ref children) = expression.expr {
+        for child in children.iter().skip(1) {
+            collect_lines(child, lines);
+        }
+    }
+}
+
+impl EvalHook for ExecutionTracer {
+    fn will_begin_eval(&mut self, env: &mut Environment, _context: &LocalContext, expr: &SymbolicExpression) {
+        let contract_identifier = &env.contract_context.contract_identifier;
+        if expr.span.start_line > 0 {
+            *self.hits.entry(contract_identifier.clone())
+                .or_insert_with(HashMap::new)
+                .entry((expr.span.start_line, expr.span.start_column))
+                .or_insert(0) += 1;
+        }
+
+        if self.record_trace {
+            let callee = expr.match_list()
+                .and_then(|list| list.get(0))
+                .and_then(|head| head.match_atom())
+                .map(|name| name.to_string());
+            self.trace.push(TraceEntry {
+                depth: self.open.len(),
+                contract_identifier: contract_identifier.clone(),
+                span: expr.span.clone(),
+                callee,
+                arguments: None,
+                result: None,
+                cost: ExecutionCost::zero(),
+            });
+            self.open.push((self.trace.len() - 1, env.global_context.cost_track.get_total()));
+        }
+    }
+
+    fn did_evaluate_args(&mut self, _env: &mut Environment, _context: &LocalContext, args: &[Value]) {
+        if let Some((index, _)) = self.open.last() {
+            self.trace[*index].arguments = Some(args.to_vec());
+        }
+    }
+
+    fn did_finish_eval(&mut self, env: &mut Environment, _context: &LocalContext, expr: &SymbolicExpression, result: &Result<Value>) {
+        if let Err(ref e) = result {
+            // early returns (e.g., from `asserts!`) unwind as errors, but aren't failures
+            let is_failure = match e {
+                Error::ShortReturn(_) => false,
+                _ => true
+            };
+            if is_failure && self.failed_at.is_none() {
+                self.failed_at = Some((env.contract_context.contract_identifier.clone(), expr.span.clone()));
+            }
+        }
+
+        if let Some((index, start_cost)) = self.open.pop() {
+            let mut cost = env.global_context.cost_track.get_total();
+            if cost.sub(&start_cost).is_err() {
+                cost = ExecutionCost::zero();
+            }
+            let entry = &mut self.trace[index];
+            entry.cost = cost;
+            entry.result = Some(match result {
+                Ok(value) => Ok(value.clone()),
+                Err(e) => Err(e.to_string())
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use vm::ast::build_ast;
+    use vm::contexts::OwnedEnvironment;
+    use vm::database::MemoryBackingStore;
+    use vm::types::PrincipalData;
+
+    const CONTRACT: &str = "(define-data-var counter int 0)
+(define-public (bump (by int))
+  (if (> by 0)
+      (ok (var-set counter (+ (var-get counter) by)))
+      (err by)))
+(define-private (unused)
+  (var-get counter))
+";
+
+    #[test]
+    fn test_trace_and_coverage() {
+        let contract_identifier = QualifiedContractIdentifier::local("counter").unwrap();
+        let sender = Value::Principal(PrincipalData::parse("S1G2081040G2081040G2081040G208105NK8PE5").unwrap());
+
+        let mut tracer = ExecutionTracer::new();
+        let mut marf = MemoryBackingStore::new();
+        {
+            let mut owned_env = OwnedEnvironment::new(marf.as_clarity_db());
+            owned_env.initialize_contract(contract_identifier.clone(), CONTRACT).unwrap();
+            owned_env.set_eval_hook(&mut tracer);
+            let (result, _, _) = owned_env.execute_transaction(
+                sender, contract_identifier.clone(), "bump", &[SymbolicExpression::atom_value(Value::Int(2))]).unwrap();
+            assert_eq!(result, Value::okay(Value::Bool(true)).unwrap());
+        }
+
+        let trace = tracer.get_trace();
+        assert_eq!(trace[0].callee, Some("if".to_string()));
+        assert_eq!(trace[0].span.start_line, 3);
+        assert_eq!(trace[0].result, Some(Ok(Value::okay(Value::Bool(true)).unwrap())));
+        assert!(trace[0].cost.runtime > 0);
+
+        let comparison = trace.iter().find(|entry| entry.callee == Some(">".to_string())).unwrap();
+        assert_eq!(comparison.depth, 1);
+        assert_eq!(comparison.arguments, Some(vec![Value::Int(2), Value::Int(0)]));
+        assert_eq!(comparison.result, Some(Ok(Value::Bool(true))));
+        assert!(tracer.get_failure_location().is_none());
+
+        let expressions = build_ast(&contract_identifier, CONTRACT, &mut ()).unwrap().expressions;
+        let report = tracer.make_lcov_report(&contract_identifier, &expressions, "counter.clar");
+        assert_eq!(report, "TN:
+SF:counter.clar
+FN:2,bump
+FN:6,unused
+FNDA:1,bump
+FNDA:0,unused
+FNF:2
+FNH:1
+DA:1,0
+DA:3,4
+DA:4,5
+DA:5,0
+DA:7,0
+LF:5
+LH:2
+end_of_record
+");
+    }
+
+    #[test]
+    fn test_failure_location() {
+        let mut tracer = ExecutionTracer::new();
+        let mut marf = MemoryBackingStore::new();
+        {
+            let mut owned_env = OwnedEnvironment::new(marf.as_clarity_db());
+            owned_env.set_eval_hook(&mut tracer);
+            owned_env.eval_raw("(begin (asserts! true (err 1))\n  (+ 1 (unwrap-panic (err 2))))").unwrap_err();
+        }
+
+        let (_, span) = tracer.get_failure_location().unwrap();
+        assert_eq!((span.start_line, span.start_column), (2, 8));
+
+        // blank lines before the failure
+        let mut tracer = ExecutionTracer::new();
+        let mut marf = MemoryBackingStore::new();
+        {
+            let mut owned_env = OwnedEnvironment::new(marf.as_clarity_db());
+            owned_env.set_eval_hook(&mut tracer);
+            owned_env.eval_raw("(begin (asserts! true (err 1))\n\n\n  (+ 1 (unwrap-panic (err 2))))").unwrap_err();
+        }
+
+        let (_, span) = tracer.get_failure_location().unwrap();
+        assert_eq!((span.start_line, span.start_column), (4, 8));
+    }
+}
@@ -13,7 +13,7 @@ use util::hash;
 pub use vm::types::signatures::{
     TupleTypeSignature, AssetIdentifier, FixedFunction, FunctionSignature,
     TypeSignature, FunctionType, ListTypeData, FunctionArg, parse_name_type_pairs,
-    BUFF_64, BUFF_32, BUFF_20, BufferLength
+    BUFF_64, BUFF_32, BUFF_20, BufferLength, StringUTF8Length
 };
 
 pub const MAX_VALUE_SIZE: u32 = 1024 * 1024; // 1MB
@@ -36,6 +36,19 @@ pub struct BuffData {
     pub data: Vec<u8>,
 }
 
+/// Bytes of a `string-ascii`: printable ASCII characters, plus tabs and newlines.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ASCIIData {
+    pub data: Vec<u8>,
+}
+
+/// Characters of a `string-utf8`, each stored as its UTF-8 encoding, so that the
+///   length of the string is the number of characters.
+#[derive(Clone, PartialEq, Eq)]
+pub struct UTF8Data {
+    pub data: Vec<Vec<u8>>,
+}
+
 #[derive(Debug, Clone, Eq, Serialize, Deserialize)]
 pub struct ListData {
     pub data: Vec<Value>,
@@ -175,6 +188,8 @@ pub enum Value {
     UInt(u128),
     Bool(bool),
     Buffer(BuffData),
+    StringASCII(ASCIIData),
+    StringUTF8(UTF8Data),
     List(ListData),
     Principal(PrincipalData),
     Tuple(TupleData),
@@ -343,6 +358,42 @@ impl Value {
     pub fn buff_from_byte(byte: u8) -> Value {
         Value::Buffer(BuffData { data: vec![byte] })
     }
+
+    pub fn string_ascii_from_bytes(bytes: Vec<u8>) -> Result<Value> {
+        // check the string size
+        BufferLength::try_from(bytes.len())?;
+
+        for b in bytes.iter() {
+            if !ASCIIData::is_legal_byte(*b) {
+                return Err(CheckErrors::InvalidCharactersDetected.into());
+            }
+        }
+        Ok(Value::StringASCII(ASCIIData { data: bytes }))
+    }
+
+    pub fn string_utf8_from_str(string: &str) -> Result<Value> {
+        let data: Vec<Vec<u8>> = string.chars()
+            .map(|c| {
+                let mut encoded = [0u8; 4];
+                c.encode_utf8(&mut encoded).as_bytes().to_vec()
+            })
+            .collect();
+        Value::string_utf8_from_chars(data)
+    }
+
+    /// Invariant: each entry is the UTF-8 encoding of exactly one character,
+    ///   e.g., the characters of other string-utf8 values.
+    pub fn string_utf8_from_chars(data: Vec<Vec<u8>>) -> Result<Value> {
+        // check the string size
+        StringUTF8Length::try_from(data.len())?;
+        Ok(Value::StringUTF8(UTF8Data { data }))
+    }
+
+    pub fn string_utf8_from_bytes(bytes: Vec<u8>) -> Result<Value> {
+        let string = String::from_utf8(bytes)
+            .map_err(|_| CheckErrors::InvalidUTF8Encoding)?;
+        Value::string_utf8_from_str(&string)
+    }
 }
 
 impl BuffData {
@@ -351,6 +402,41 @@ impl BuffData {
     }
 }
 
+impl ASCIIData {
+    pub fn len(&self) -> BufferLength {
+        self.data.len().try_into().unwrap()
+    }
+
+    /// Printable characters, tabs and line breaks.
+    pub fn is_legal_byte(b: u8) -> bool {
+        (b >= 0x20 && b <= 0x7e) || b == b'\t' || b == b'\n' || b == b'\r'
+    }
+
+    pub fn as_str(&self) -> &str {
+        // only ever constructed from ASCII bytes
+        std::str::from_utf8(&self.data)
+            .expect("FATAL: string-ascii contains non-ASCII bytes")
+    }
+}
+
+impl UTF8Data {
+    /// Number of characters
+    pub fn len(&self) -> StringUTF8Length {
+        self.data.len().try_into().unwrap()
+    }
+
+    pub fn to_string(&self) -> String {
+        let bytes: Vec<u8> = self.data.iter().flat_map(|c| c.iter().cloned()).collect();
+        // only ever constructed from valid UTF-8 characters
+        String::from_utf8(bytes)
+            .expect("FATAL: string-utf8 contains invalid UTF-8")
+    }
+
+    pub fn encoded_len(&self) -> usize {
+        self.data.iter().map(|c| c.len()).sum()
+    }
+}
+
 impl ListData {
     pub fn len(&self) -> u32 {
         self.data.len().try_into().unwrap()
@@ -387,6 +473,93 @@ impl fmt::Debug for BuffData {
     }
 }
 
+/// Write a string literal body, escaping whatever the parser would need escaped.  Anything
+///   outside of ASCII is written as a \u{..} escape, so the literal stays plain ASCII.
+fn fmt_escaped(f: &mut fmt::Formatter, string: &str) -> fmt::Result {
+    for c in string.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\t' => write!(f, "\\t")?,
+            '\r' => write!(f, "\\r")?,
+            c if c.is_control() || !c.is_ascii() => write!(f, "\\u{{{:x}}}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    Ok(())
+}
+
+impl fmt::Display for ASCIIData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a\"")?;
+        fmt_escaped(f, self.as_str())?;
+        write!(f, "\"")
+    }
+}
+
+impl fmt::Debug for ASCIIData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl fmt::Display for UTF8Data {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "u\"")?;
+        fmt_escaped(f, &self.to_string())?;
+        write!(f, "\"")
+    }
+}
+
+impl fmt::Debug for UTF8Data {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+// Strings are (de)serialized as JSON strings, so that contract ASTs, events and API
+//   responses carry readable text.
+// (The serde traits are kept out of this module's namespace, where they would make
+//   ClaritySerializable::serialize() ambiguous for Value.)
+mod string_serde {
+    use serde::{Serialize, Serializer, Deserialize, Deserializer};
+    use serde::de::Error as SerdeError;
+    use super::{ASCIIData, UTF8Data, Value};
+
+    impl Serialize for ASCIIData {
+        fn serialize<S: Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+            s.serialize_str(self.as_str())
+        }
+    }
+
+    impl<'de> Deserialize<'de> for ASCIIData {
+        fn deserialize<D: Deserializer<'de>>(d: D) -> std::result::Result<ASCIIData, D::Error> {
+            let string = String::deserialize(d)?;
+            match Value::string_ascii_from_bytes(string.into_bytes()) {
+                Ok(Value::StringASCII(data)) => Ok(data),
+                _ => Err(D::Error::custom("invalid string-ascii"))
+            }
+        }
+    }
+
+    impl Serialize for UTF8Data {
+        fn serialize<S: Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+            s.serialize_str(&self.to_string())
+        }
+    }
+
+    impl<'de> Deserialize<'de> for UTF8Data {
+        fn deserialize<D: Deserializer<'de>>(d: D) -> std::result::Result<UTF8Data, D::Error> {
+            let string = String::deserialize(d)?;
+            match Value::string_utf8_from_str(&string) {
+                Ok(Value::StringUTF8(data)) => Ok(data),
+                _ => Err(D::Error::custom("invalid string-utf8"))
+            }
+        }
+    }
+}
+
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -394,6 +567,8 @@ impl fmt::Display for Value {
             Value::UInt(int) => write!(f, "u{}", int),
             Value::Bool(boolean) => write!(f, "{}", boolean),
             Value::Buffer(vec_bytes) => write!(f, "0x{}", &vec_bytes),
+            Value::StringASCII(string) => write!(f, "{}", string),
+            Value::StringUTF8(string) => write!(f, "{}", string),
             Value::Tuple(data) => write!(f, "{}", data),
             Value::Principal(principal_data) => write!(f, "{}", principal_data),
             Value::Optional(opt_data) => write!(f, "{}", opt_data),
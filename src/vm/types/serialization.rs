@@ -88,7 +88,9 @@ define_u8_enum!(TypePrefix {
     OptionalNone = 9,
     OptionalSome = 10,
     List = 11,
-    Tuple = 12
+    Tuple = 12,
+    StringASCII = 13,
+    StringUTF8 = 14
 });
 
 impl From<&PrincipalData> for TypePrefix {
@@ -109,6 +111,8 @@ impl From<&Value> for TypePrefix {
             Int(_) => TypePrefix::Int,
             UInt(_) => TypePrefix::UInt,
             Buffer(_) => TypePrefix::Buffer,
+            StringASCII(_) => TypePrefix::StringASCII,
+            StringUTF8(_) => TypePrefix::StringUTF8,
             Bool(value) => {
                 if *value {
                     TypePrefix::BoolTrue
@@ -303,6 +307,67 @@ impl Value {
                 // can safely unwrap, because the buffer length was _already_ checked.
                 Ok(Value::buff_from(data).unwrap())
             },
+            TypePrefix::StringASCII => {
+                let mut buffer_len = [0; 4];
+                r.read_exact(&mut buffer_len)?;
+                let buffer_len = BufferLength::try_from(
+                    u32::from_be_bytes(buffer_len))?;
+
+                if let Some(x) = expected_type {
+                    let passed_test = match x {
+                        TypeSignature::StringASCIIType(expected_len) => {
+                            u32::from(&buffer_len) <= u32::from(expected_len)
+                        },
+                        _ => false
+                    };
+                    if !passed_test {
+                        return Err(SerializationError::DeserializeExpected(x.clone()))
+                    }
+                }
+
+                let mut data = vec![0; u32::from(buffer_len) as usize];
+
+                r.read_exact(&mut data[..])?;
+
+                Value::string_ascii_from_bytes(data)
+                    .map_err(|_| "Illegal string-ascii characters".into())
+            },
+            TypePrefix::StringUTF8 => {
+                // the length prefix counts bytes, not characters
+                let mut buffer_len = [0; 4];
+                r.read_exact(&mut buffer_len)?;
+                let buffer_len = BufferLength::try_from(
+                    u32::from_be_bytes(buffer_len))?;
+
+                // no character is wider than 4 bytes, so the byte length alone rules out
+                //   anything far too long before it is read.
+                if let Some(x) = expected_type {
+                    let passed_test = match x {
+                        TypeSignature::StringUTF8Type(expected_len) => {
+                            (u32::from(&buffer_len) as u64) <= (u32::from(expected_len) as u64) * 4
+                        },
+                        _ => false
+                    };
+                    if !passed_test {
+                        return Err(SerializationError::DeserializeExpected(x.clone()))
+                    }
+                }
+
+                let mut data = vec![0; u32::from(buffer_len) as usize];
+
+                r.read_exact(&mut data[..])?;
+
+                let value = Value::string_utf8_from_bytes(data)
+                    .map_err(|_| "Illegal string-utf8 encoding")?;
+
+                if let Some(x) = expected_type {
+                    if !x.admits(&value) {
+                        return Err(SerializationError::DeserializeExpected(x.clone()))
+                    }
+                }
+
+                Ok(value)
+            },
             TypePrefix::BoolTrue => {
                 check_match!(expected_type, TypeSignature::BoolType)?;
                 Ok(Bool(true))
@@ -464,6 +529,18 @@ impl Value {
                 w.write_all(&(u32::from(value.len()).to_be_bytes()))?;
                 w.write_all(&value.data)?
             }
+            StringASCII(value) => {
+                w.write_all(&(u32::from(value.len()).to_be_bytes()))?;
+                w.write_all(&value.data)?
+            }
+            StringUTF8(value) => {
+                let encoded_len = u32::try_from(value.encoded_len())
+                    .expect("FATAL: string-utf8 longer than u32::max_value() bytes");
+                w.write_all(&encoded_len.to_be_bytes())?;
+                for c in value.data.iter() {
+                    w.write_all(c)?
+                }
+            }
             Principal(Standard(data)) => {
                 data.serialize_write(w)?
             },
@@ -720,6 +797,34 @@ mod tests {
         
     }
 
+    #[test]
+    fn test_strings() {
+        test_deser_ser(Value::string_ascii_from_bytes(vec![]).unwrap());
+        test_deser_ser(Value::string_ascii_from_bytes(b"hello\tworld\n".to_vec()).unwrap());
+        test_deser_ser(Value::string_utf8_from_str("").unwrap());
+        test_deser_ser(Value::string_utf8_from_str("h\u{e9}llo \u{1f600}").unwrap());
+
+        let ascii = Value::string_ascii_from_bytes(b"hello".to_vec()).unwrap();
+        test_bad_expectation(ascii.clone(), TypeSignature::from("(buff 5)"));
+        test_bad_expectation(ascii.clone(), TypeSignature::from("(string-utf8 5)"));
+        test_bad_expectation(ascii.clone(), TypeSignature::from("(string-ascii 4)"));
+
+        // 2 characters, but 8 bytes: the expected length counts characters
+        let utf8 = Value::string_utf8_from_str("\u{1f600}\u{1f600}").unwrap();
+        Value::try_deserialize_hex(&utf8.serialize(), &TypeSignature::from("(string-utf8 2)")).unwrap();
+        test_bad_expectation(utf8.clone(), TypeSignature::from("(string-utf8 1)"));
+        test_bad_expectation(utf8.clone(), TypeSignature::from("(string-ascii 8)"));
+
+        // non-printable bytes aren't legal in a string-ascii
+        assert_eq!(
+            Value::try_deserialize_hex_untyped("0d0000000261ff").unwrap_err(),
+            "Illegal string-ascii characters".into());
+        // nor is a truncated character in a string-utf8
+        assert_eq!(
+            Value::try_deserialize_hex_untyped("0e0000000268c3").unwrap_err(),
+            "Illegal string-utf8 encoding".into());
+    }
+
     #[test]
     fn test_tuples() {
         let t_1 = Value::from(TupleData::from_data(vec![
@@ -806,7 +911,9 @@ mod tests {
                  Value::Int(1), Value::Int(2), Value::Int(3), Value::Int(-4)]).unwrap())),
             ("0c000000020362617a0906666f6f62617203",
              Ok(Value::from(TupleData::from_data(vec![
-                 ("baz".into(), Value::none()), ("foobar".into(), Value::Bool(true))]).unwrap())))
+                 ("baz".into(), Value::none()), ("foobar".into(), Value::Bool(true))]).unwrap()))),
+            ("0d00000003616263", Ok(Value::string_ascii_from_bytes(b"abc".to_vec()).unwrap())),
+            ("0e0000000368c3a9", Ok(Value::string_utf8_from_str("h\u{e9}").unwrap())),
         ];
 
         for (test, expected) in tests.iter() {
@@ -44,6 +44,13 @@ pub struct TupleTypeSignature {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BufferLength (u32);
 
+/// Length of a `string-utf8`, in characters.  Each character may take up to 4 bytes,
+///   so the bound is a quarter of the maximum value size.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StringUTF8Length (u32);
+
+pub const MAX_UTF8_CHAR_SIZE: u32 = 4;
+
 // INVARIANTS enforced by the Type Signatures.
 //   1. A TypeSignature constructor will always fail rather than construct a
 //        type signature for a too large or invalid type. This is why any variable length
@@ -58,6 +65,8 @@ pub enum TypeSignature {
     UIntType,
     BoolType,
     BufferType(BufferLength),
+    StringASCIIType(BufferLength),
+    StringUTF8Type(StringUTF8Length),
     PrincipalType,
     ListType(ListTypeData),
     TupleType(TupleTypeSignature),
@@ -72,6 +81,8 @@ use self::TypeSignature::{
     UIntType, 
     BoolType, 
     BufferType,
+    StringASCIIType,
+    StringUTF8Type,
     PrincipalType, 
     ListType, 
     TupleType, 
@@ -191,6 +202,53 @@ impl TryFrom<i128> for BufferLength {
     }
 }
 
+impl From<&StringUTF8Length> for u32 {
+    fn from(v: &StringUTF8Length) -> u32 {
+        v.0
+    }
+}
+
+impl From<StringUTF8Length> for u32 {
+    fn from(v: StringUTF8Length) -> u32 {
+        v.0
+    }
+}
+
+impl TryFrom<u32> for StringUTF8Length {
+    type Error = CheckErrors;
+    fn try_from(data: u32) -> Result<StringUTF8Length> {
+        if data > MAX_VALUE_SIZE / MAX_UTF8_CHAR_SIZE {
+            Err(CheckErrors::ValueTooLarge)
+        } else {
+            Ok(StringUTF8Length(data))
+        }
+    }
+}
+
+impl TryFrom<usize> for StringUTF8Length {
+    type Error = CheckErrors;
+    fn try_from(data: usize) -> Result<StringUTF8Length> {
+        if data > ((MAX_VALUE_SIZE / MAX_UTF8_CHAR_SIZE) as usize) {
+            Err(CheckErrors::ValueTooLarge)
+        } else {
+            Ok(StringUTF8Length(data as u32))
+        }
+    }
+}
+
+impl TryFrom<i128> for StringUTF8Length {
+    type Error = CheckErrors;
+    fn try_from(data: i128) -> Result<StringUTF8Length> {
+        if data > ((MAX_VALUE_SIZE / MAX_UTF8_CHAR_SIZE) as i128) {
+            Err(CheckErrors::ValueTooLarge)
+        } else if data < 0 {
+            Err(CheckErrors::InvalidTypeDescription)
+        } else {
+            Ok(StringUTF8Length(data as u32))
+        }
+    }
+}
+
 impl ListTypeData {
     pub fn new_list(entry_type: TypeSignature, max_len: u32) -> Result<ListTypeData> {
         let would_be_depth = 1 + entry_type.depth();
@@ -330,6 +388,20 @@ impl TypeSignature {
                     false
                 }
             },
+            StringASCIIType(ref my_len) => {
+                if let StringASCIIType(ref other_len) = other {
+                    my_len.0 >= other_len.0
+                } else {
+                    false
+                }
+            },
+            StringUTF8Type(ref my_len) => {
+                if let StringUTF8Type(ref other_len) = other {
+                    my_len.0 >= other_len.0
+                } else {
+                    false
+                }
+            },
             TupleType(ref tuple_sig) => {
                 if let TupleType(ref other_tuple_sig) = other {
                     tuple_sig.admits(other_tuple_sig)
@@ -491,6 +563,14 @@ impl TypeSignature {
                                 .expect("FAIL: Max Clarity Value Size is no longer realizable in Buffer Type")))
     }
 
+    pub fn max_string_ascii() -> TypeSignature {
+        StringASCIIType(BufferLength(MAX_VALUE_SIZE))
+    }
+
+    pub fn max_string_utf8() -> TypeSignature {
+        StringUTF8Type(StringUTF8Length(MAX_VALUE_SIZE / MAX_UTF8_CHAR_SIZE))
+    }
+
     /// If one of the types is a NoType, return Ok(the other type), otherwise return least_supertype(a, b)
     fn factor_out_no_type(a: &TypeSignature, b: &TypeSignature) -> Result<TypeSignature> {
         if a.is_no_type() {
@@ -518,7 +598,7 @@ impl TypeSignature {
     ///  For two options:
     ///      least_supertype(A, B) := (option least_supertype(some_a, some_b))
     ///        if some_a | some_b is NoType, use the other type's entry.
-    ///  For buffers:
+    ///  For buffers and strings of the same encoding:
     ///      least_supertype(A, B) := (buff len: max(len A, len B))
     ///  For ints, uints, principals, bools:
     ///      least_supertype(A, B) := if A != B, error, else A
@@ -569,6 +649,22 @@ impl TypeSignature {
                 }.clone();
                 Ok(BufferType(buff_len))
             },
+            (StringASCIIType(len_a), StringASCIIType(len_b)) => {
+                let str_len = if u32::from(len_a) > u32::from(len_b) {
+                    len_a
+                } else {
+                    len_b
+                }.clone();
+                Ok(StringASCIIType(str_len))
+            },
+            (StringUTF8Type(len_a), StringUTF8Type(len_b)) => {
+                let str_len = if u32::from(len_a) > u32::from(len_b) {
+                    len_a
+                } else {
+                    len_b
+                }.clone();
+                Ok(StringUTF8Type(str_len))
+            },
             (NoType, x) | (x, NoType) => {
                 Ok(x.clone())
             },
@@ -604,6 +700,16 @@ impl TypeSignature {
                     .expect("ERROR: Too large of a buffer successfully constructed.");
                 BufferType(buff_length)
             },
+            Value::StringASCII(string_data) => {
+                let string_length = BufferLength::try_from(string_data.data.len())
+                    .expect("ERROR: Too large of a string-ascii successfully constructed.");
+                StringASCIIType(string_length)
+            },
+            Value::StringUTF8(string_data) => {
+                let string_length = StringUTF8Length::try_from(string_data.data.len())
+                    .expect("ERROR: Too large of a string-utf8 successfully constructed.");
+                StringUTF8Type(string_length)
+            },
             Value::Tuple(v) => TupleType(
                 v.type_signature.clone()),
             Value::List(list_data) => ListType(list_data.type_signature.clone()),
@@ -686,6 +792,33 @@ impl TypeSignature {
         }
     }
 
+    // Parses type signatures of the forms:
+    // (string-ascii 10)
+    // (string-utf8 10)
+    fn parse_string_ascii_type_repr(type_args: &[SymbolicExpression]) -> Result<TypeSignature> {
+        if type_args.len() != 1 {
+            return Err(CheckErrors::InvalidTypeDescription)
+        }
+        if let SymbolicExpressionType::LiteralValue(Value::Int(max_len)) = &type_args[0].expr {
+            BufferLength::try_from(*max_len)
+                .map(|max_len| TypeSignature::StringASCIIType(max_len))
+        } else {
+            Err(CheckErrors::InvalidTypeDescription)
+        }
+    }
+
+    fn parse_string_utf8_type_repr(type_args: &[SymbolicExpression]) -> Result<TypeSignature> {
+        if type_args.len() != 1 {
+            return Err(CheckErrors::InvalidTypeDescription)
+        }
+        if let SymbolicExpressionType::LiteralValue(Value::Int(max_len)) = &type_args[0].expr {
+            StringUTF8Length::try_from(*max_len)
+                .map(|max_len| TypeSignature::StringUTF8Type(max_len))
+        } else {
+            Err(CheckErrors::InvalidTypeDescription)
+        }
+    }
+
     fn parse_optional_type_repr<A: CostTracker>(type_args: &[SymbolicExpression], accounting: &mut A) -> Result<TypeSignature> {
         if type_args.len() != 1 {
             return Err(CheckErrors::InvalidTypeDescription)
@@ -719,6 +852,8 @@ impl TypeSignature {
                     match compound_type.as_ref() {
                         "list" => TypeSignature::parse_list_type_repr(rest, accounting),
                         "buff" => TypeSignature::parse_buff_type_repr(rest),
+                        "string-ascii" => TypeSignature::parse_string_ascii_type_repr(rest),
+                        "string-utf8" => TypeSignature::parse_string_utf8_type_repr(rest),
                         "tuple" => TypeSignature::parse_tuple_type_repr(rest, accounting),
                         "optional" => TypeSignature::parse_optional_type_repr(rest, accounting),
                         "response" => TypeSignature::parse_response_type_repr(rest, accounting),
@@ -791,7 +926,8 @@ impl TypeSignature {
         match self {
             // NoType's may be asked for their size at runtime --
             //  legal constructions like `(ok 1)` have NoType parts (if they have unknown error variant types).
-            TraitReferenceType(_) | NoType | IntType | UIntType | BoolType | PrincipalType | BufferType(_) => 1,
+            TraitReferenceType(_) | NoType | IntType | UIntType | BoolType | PrincipalType | BufferType(_) |
+            StringASCIIType(_) | StringUTF8Type(_) => 1,
             TupleType(tuple_sig) => {
                 1 + tuple_sig.max_depth()
             },
@@ -818,6 +954,10 @@ impl TypeSignature {
             BoolType => Some(1),
             PrincipalType => Some(148), // 20+128
             BufferType(len) => Some(4 + u32::from(len)),
+            StringASCIIType(len) => Some(4 + u32::from(len)),
+            StringUTF8Type(len) => u32::from(len)
+                .checked_mul(MAX_UTF8_CHAR_SIZE)?
+                .checked_add(4),
             TupleType(tuple_sig) => tuple_sig.inner_size(),
             ListType(list_type) => list_type.inner_size(),
             OptionalType(t) => t.size().checked_add(WRAPPER_VALUE_SIZE),
@@ -847,7 +987,7 @@ impl TypeSignature {
             // These types all only use ~1 byte for their type enum
             NoType | IntType | UIntType | BoolType | PrincipalType => Some(1),
             // u32 length + type enum
-            BufferType(_) => Some(1 + 4),
+            BufferType(_) | StringASCIIType(_) | StringUTF8Type(_) => Some(1 + 4),
             TupleType(tuple_sig) => tuple_sig.type_size(),
             ListType(list_type) => list_type.type_size(),
             OptionalType(t) => {
@@ -1011,6 +1151,8 @@ impl fmt::Display for TypeSignature {
             UIntType => write!(f, "uint"),
             BoolType => write!(f, "bool"),
             BufferType(len) => write!(f, "(buff {})", len),
+            StringASCIIType(len) => write!(f, "(string-ascii {})", len),
+            StringUTF8Type(len) => write!(f, "(string-utf8 {})", len),
             OptionalType(t) => write!(f, "(optional {})", t),
             ResponseType(v) => write!(f, "(response {} {})", v.0, v.1),
             TupleType(t) => write!(f, "{}", t),
@@ -1027,6 +1169,12 @@ impl fmt::Display for BufferLength {
     }
 }
 
+impl fmt::Display for StringUTF8Length {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 impl fmt::Display for FunctionArg {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.signature)
@@ -1053,6 +1201,27 @@ mod test {
         assert_eq!(TypeSignature::type_of(&value), type_descr);
     }
 
+    #[test]
+    fn string_supertypes() {
+        assert_eq!(TypeSignature::least_supertype(&"(string-ascii 3)".into(), &"(string-ascii 5)".into()),
+                   Ok("(string-ascii 5)".into()));
+        assert_eq!(TypeSignature::least_supertype(&"(string-utf8 7)".into(), &"(string-utf8 2)".into()),
+                   Ok("(string-utf8 7)".into()));
+        assert!(TypeSignature::least_supertype(&"(string-ascii 3)".into(), &"(string-utf8 3)".into()).is_err());
+        assert!(TypeSignature::least_supertype(&"(string-ascii 3)".into(), &"(buff 3)".into()).is_err());
+
+        let ascii: TypeSignature = "(string-ascii 3)".into();
+        assert!(ascii.admits(&Value::string_ascii_from_bytes(b"abc".to_vec()).unwrap()));
+        assert!(!ascii.admits(&Value::string_ascii_from_bytes(b"abcd".to_vec()).unwrap()));
+        assert!(!ascii.admits(&Value::buff_from(b"abc".to_vec()).unwrap()));
+
+        // utf8 lengths count characters, not bytes
+        let utf8: TypeSignature = "(string-utf8 3)".into();
+        assert!(utf8.admits(&Value::string_utf8_from_str("h\u{e9}\u{1f600}").unwrap()));
+        assert!(!utf8.admits(&Value::string_utf8_from_str("h\u{e9}ll\u{f6}").unwrap()));
+        assert_eq!(utf8.size(), 16);
+    }
+
     #[test]
     fn type_signature_way_too_big() {
         // first_tuple.type_size ~= 131
@@ -1094,6 +1263,12 @@ mod test {
             ("(list 4294967295 (buff 2))", ValueTooLarge),
             ("(list 2147483647 (buff 2))", ValueTooLarge),
             ("(tuple (l (buff 1048576)))", ValueTooLarge),
+            ("(string-ascii)", InvalidTypeDescription),
+            ("(string-ascii 1048577)", ValueTooLarge),
+            ("(string-ascii u10)", InvalidTypeDescription),
+            ("(string-utf8 262145)", ValueTooLarge),
+            ("(string-utf8 -1)", InvalidTypeDescription),
+            ("(list 2 (string-utf8 262144))", ValueTooLarge),
         ];
 
         for (desc, expected) in bad_type_descriptions.iter() {
@@ -1107,6 +1282,8 @@ mod test {
             "(buff 1048576)",
             "(list 4400 bool)",
             "(tuple (l (buff 1048550)))",
+            "(string-ascii 1048576)",
+            "(string-utf8 262143)",
         ];
 
         for desc in okay_types.iter() {
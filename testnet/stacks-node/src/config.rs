@@ -9,6 +9,7 @@ use stacks::burnchains::{
     MagicBytes, BLOCKSTACK_MAGIC_MAINNET};
 use stacks::burnchains::bitcoin::indexer::FIRST_BLOCK_MAINNET;
 use stacks::net::connection::ConnectionOptions;
+use stacks::core::mempool::MemPoolLimits;
 use stacks::net::{Neighbor, NeighborKey, PeerAddress};
 use stacks::util::secp256k1::Secp256k1PublicKey;
 use stacks::util::hash::{to_hex, hex_bytes};
@@ -28,6 +29,7 @@ pub struct ConfigFile {
     pub events_observer: Option<Vec<EventObserverConfigFile>>,
     pub connection_options: Option<ConnectionOptionsFile>,
     pub block_limit: Option<BlockLimitFile>,
+    pub mempool: Option<MemPoolConfigFile>,
 }
 
 impl ConfigFile {
@@ -191,6 +193,7 @@ pub struct Config {
     pub events_observers: Vec<EventObserverConfig>,
     pub connection_options: ConnectionOptions,
     pub block_limit: ExecutionCost,
+    pub mempool_limits: MemPoolLimits,
 }
 
 lazy_static! {
@@ -264,6 +267,9 @@ impl Config {
                     mine_microblocks: node.mine_microblocks.unwrap_or(default_node_config.mine_microblocks),
                     wait_time_for_microblocks: node.wait_time_for_microblocks.unwrap_or(default_node_config.wait_time_for_microblocks),
                     prometheus_bind: node.prometheus_bind,
+                    index_map_keys: node.index_map_keys.unwrap_or(default_node_config.index_map_keys),
+                    index_transactions: node.index_transactions.unwrap_or(default_node_config.index_transactions),
+                    profile_costs: node.profile_costs.unwrap_or(default_node_config.profile_costs),
                 };
                 node_config.set_bootstrap_node(node.bootstrap_node);
                 node_config
@@ -387,6 +393,8 @@ impl Config {
                     dns_timeout: opts.dns_timeout.unwrap_or_else(|| HELIUM_DEFAULT_CONNECTION_OPTIONS.dns_timeout.clone()),
                     max_inflight_blocks: opts.max_inflight_blocks.unwrap_or_else(|| HELIUM_DEFAULT_CONNECTION_OPTIONS.max_inflight_blocks.clone()),
                     maximum_call_argument_size: opts.maximum_call_argument_size.unwrap_or_else(|| HELIUM_DEFAULT_CONNECTION_OPTIONS.maximum_call_argument_size.clone()),
+                    websocket_ping_interval: opts.websocket_ping_interval.unwrap_or_else(|| HELIUM_DEFAULT_CONNECTION_OPTIONS.websocket_ping_interval.clone()),
+                    max_websocket_buffer: opts.max_websocket_buffer.unwrap_or_else(|| HELIUM_DEFAULT_CONNECTION_OPTIONS.max_websocket_buffer.clone()),
                     ..ConnectionOptions::default() 
                 }
             },
@@ -406,13 +414,26 @@ impl Config {
             None => HELIUM_BLOCK_LIMIT.clone()
         };
 
+        let mempool_limits = match config_file.mempool {
+            Some(opts) => {
+                let default_limits = MemPoolLimits::default();
+                MemPoolLimits {
+                    max_size: opts.max_size.unwrap_or(default_limits.max_size),
+                    max_txs_per_origin: opts.max_txs_per_origin.unwrap_or(default_limits.max_txs_per_origin),
+                    replace_by_fee_min_bump: opts.replace_by_fee_min_bump.unwrap_or(default_limits.replace_by_fee_min_bump),
+                }
+            },
+            None => MemPoolLimits::default()
+        };
+
         Config {
             node,
             burnchain,
             initial_balances,
             events_observers,
             connection_options,
-            block_limit
+            block_limit,
+            mempool_limits
         }
     }
 
@@ -437,6 +458,10 @@ impl Config {
         format!("{}/peer_db.sqlite", self.node.working_dir)
     }
 
+    pub fn get_event_queue_path(&self) -> String {
+        format!("{}/event_observers.sqlite", self.node.working_dir)
+    }
+
     pub fn add_initial_balance(&mut self, address: String, amount: u64) {
         let new_balance = InitialBalance { address: PrincipalData::parse_standard_principal(&address).unwrap().into(), amount };
         self.initial_balances.push(new_balance);
@@ -466,6 +491,7 @@ impl std::default::Default for Config {
             events_observers: vec![],
             connection_options,
             block_limit,
+            mempool_limits: MemPoolLimits::default(),
         }
     }
 }
@@ -565,6 +591,9 @@ pub struct NodeConfig {
     pub mine_microblocks: bool,
     pub wait_time_for_microblocks: u64,
     pub prometheus_bind: Option<String>,
+    pub index_map_keys: bool,
+    pub index_transactions: bool,
+    pub profile_costs: bool,
 }
 
 impl NodeConfig {
@@ -599,6 +628,9 @@ impl NodeConfig {
             mine_microblocks: false,
             wait_time_for_microblocks: 0,
             prometheus_bind: None,
+            index_map_keys: false,
+            index_transactions: false,
+            profile_costs: false,
         }
     }
 
@@ -669,6 +701,15 @@ pub struct ConnectionOptionsFile {
     pub read_only_call_limit_read_count: Option<u64>,
     pub read_only_call_limit_runtime: Option<u64>,
     pub maximum_call_argument_size: Option<u32>,
+    pub websocket_ping_interval: Option<u64>,
+    pub max_websocket_buffer: Option<usize>,
+}
+
+#[derive(Clone, Default, Deserialize)]
+pub struct MemPoolConfigFile {
+    pub max_size: Option<u64>,
+    pub max_txs_per_origin: Option<u64>,
+    pub replace_by_fee_min_bump: Option<u64>,
 }
 
 #[derive(Clone, Default, Deserialize)]
@@ -696,6 +737,9 @@ pub struct NodeConfigFile {
     pub mine_microblocks: Option<bool>,
     pub wait_time_for_microblocks: Option<u64>,
     pub prometheus_bind: Option<String>,
+    pub index_map_keys: Option<bool>,
+    pub index_transactions: Option<bool>,
+    pub profile_costs: Option<bool>,
 }
 
 #[derive(Clone, Deserialize, Default)]
@@ -1,5 +1,10 @@
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
+use std::cmp;
+use std::fs;
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{sync_channel, SyncSender, Receiver, RecvTimeoutError, TrySendError};
+use std::thread;
 use std::time::Duration;
 use std::thread::sleep;
 
@@ -11,19 +16,25 @@ use serde_json::json;
 
 use stacks::burnchains::Txid;
 use stacks::chainstate::stacks::events::{StacksTransactionEvent, STXEventType, FTEventType, NFTEventType};
-use stacks::chainstate::stacks::StacksTransaction;
+use stacks::chainstate::stacks::{StacksTransaction, StacksMicroblock};
 use stacks::net::StacksMessageCodec;
-use stacks::vm::types::{Value, QualifiedContractIdentifier, AssetIdentifier};
+use stacks::net::websocket::{EventStreamPublisher, EventStreamMessage};
+use stacks::vm::types::{Value, PrincipalData, QualifiedContractIdentifier, AssetIdentifier};
 use stacks::vm::analysis::{contract_interface_builder::build_contract_interface};
+use stacks::vm::profiler::CostMetric;
 use stacks::util::hash::{bytes_to_hex};
 use stacks::chainstate::stacks::StacksBlockId;
 
 use super::config::{EventObserverConfig, EventKeyType};
+use super::event_queue::{EventQueueDB, MAX_PENDING_PAYLOADS};
+use super::monitoring::update_event_observer_backlog_gauge;
 use super::node::{ChainTip};
 
 #[derive(Debug, Clone)]
 struct EventObserver {
     endpoint: String,
+    /// nudges this observer's delivery thread when new payloads are queued
+    wakeup: SyncSender<()>,
 }
 
 const STATUS_RESP_TRUE: &str = "success";
@@ -33,62 +44,113 @@ const STATUS_RESP_POST_CONDITION: &str  = "abort_by_post_condition";
 pub const PATH_MEMPOOL_TX_SUBMIT: &str = "new_mempool_tx";
 pub const PATH_BLOCK_PROCESSED: &str = "new_block";
 
-impl EventObserver {
-
-    fn send_payload(&self, payload: &serde_json::Value, path: &str) {
+/// How long a delivery thread with nothing to send waits before re-checking the queue
+const DELIVERY_POLL_INTERVAL: Duration = Duration::from_millis(1_000);
+/// Retry backoff for an observer that fails to accept a payload; doubles up to the maximum
+const DELIVERY_BACKOFF_MIN: Duration = Duration::from_millis(1_000);
+const DELIVERY_BACKOFF_MAX: Duration = Duration::from_millis(60_000);
 
-        let body = match serde_json::to_vec(&payload) {
-            Ok(body) => body,
-            Err(err) => {
-                error!("Event dispatcher: serialization failed  - {:?}", err);
-                return
-            }
-        };
+impl EventObserver {
 
+    /// Try to POST a payload to the observer once.  Returns true if the observer accepted it.
+    fn post_payload(endpoint: &str, path: &str, body: Vec<u8>) -> bool {
         let url = {
             let joined_components = match path.starts_with("/") {
-                true => format!("{}{}", &self.endpoint, path),
-                false => format!("{}/{}", &self.endpoint, path)
+                true => format!("{}{}", endpoint, path),
+                false => format!("{}/{}", endpoint, path)
             };
             let url = format!("http://{}", joined_components);
             Url::parse(&url).expect(&format!("Event dispatcher: unable to parse {} as a URL", url))
         };
 
-        let backoff = Duration::from_millis((1.0 * 1_000.0) as u64);
+        let mut req = Request::new(Method::Post, url);
+        req.append_header("Content-Type", "application/json").expect("Unable to set header");
+        req.set_body(body);
 
-        loop {
-            let body = body.clone();
-            let mut req = Request::new(Method::Post, url.clone());
-            req.append_header("Content-Type", "application/json").expect("Unable to set header");
-            req.set_body(body);
-
-            let response = async_std::task::block_on(async {
-                let stream = match TcpStream::connect(self.endpoint.clone()).await {
-                    Ok(stream) => stream,
-                    Err(err) => {
-                        println!("Event dispatcher: connection failed  - {:?}", err);
-                        return None;
-                    }
-                };    
-    
-                match client::connect(stream, req).await {
-                    Ok(response) => Some(response),
-                    Err(err) => {
-                        println!("Event dispatcher: rpc invokation failed  - {:?}", err);
-                        return None;
-                    }
+        let response = async_std::task::block_on(async {
+            let stream = match TcpStream::connect(endpoint).await {
+                Ok(stream) => stream,
+                Err(err) => {
+                    warn!("Event dispatcher: connection to {} failed  - {:?}", endpoint, err);
+                    return None;
                 }
-            });
+            };
 
-            if let Some(response) = response {
+            match client::connect(stream, req).await {
+                Ok(response) => Some(response),
+                Err(err) => {
+                    warn!("Event dispatcher: rpc invokation failed  - {:?}", err);
+                    return None;
+                }
+            }
+        });
+
+        match response {
+            Some(response) => {
                 if response.status().is_success() {
-                    break;
+                    true
                 } else {
-                    error!("Event dispatcher: POST {} failed with error {:?}", self.endpoint, response);
+                    error!("Event dispatcher: POST {} failed with error {:?}", endpoint, response);
+                    false
                 }
+            },
+            None => false
+        }
+    }
+
+    /// Deliver this observer's queued payloads in order, backing off while it is unreachable.
+    /// Runs until every dispatcher holding this observer has been dropped.
+    fn run_delivery(endpoint: String, queue: Arc<Mutex<EventQueueDB>>, wakeup: Receiver<()>) {
+        let mut backoff = DELIVERY_BACKOFF_MIN;
+        loop {
+            let next = queue.lock().expect("FATAL: event queue lock poisoned")
+                .get_next_payload(&endpoint);
+
+            let pending = match next {
+                Ok(Some(pending)) => pending,
+                Ok(None) => {
+                    match wakeup.recv_timeout(DELIVERY_POLL_INTERVAL) {
+                        Ok(()) | Err(RecvTimeoutError::Timeout) => continue,
+                        Err(RecvTimeoutError::Disconnected) => {
+                            debug!("Event dispatcher: stopping delivery to {}", &endpoint);
+                            return;
+                        }
+                    }
+                },
+                Err(e) => {
+                    error!("Event dispatcher: failed to load queued payload for {}: {:?}", &endpoint, &e);
+                    sleep(backoff);
+                    continue;
+                }
+            };
+
+            if EventObserver::post_payload(&endpoint, &pending.path, pending.payload.into_bytes()) {
+                backoff = DELIVERY_BACKOFF_MIN;
+
+                let mut queue = queue.lock().expect("FATAL: event queue lock poisoned");
+                if let Err(e) = queue.mark_delivered(&endpoint, pending.id) {
+                    // the payload will be re-sent, which observers must tolerate anyway
+                    error!("Event dispatcher: failed to advance cursor for {}: {:?}", &endpoint, &e);
+                }
+                if let Ok(backlog) = queue.get_backlog(&endpoint) {
+                    update_event_observer_backlog_gauge(&endpoint, backlog as i64);
+                }
+            } else {
+                warn!("Event dispatcher: failed to deliver payload {} to {}; retrying in {:?}", pending.id, &endpoint, &backoff);
+                sleep(backoff);
+                backoff = cmp::min(backoff * 2, DELIVERY_BACKOFF_MAX);
             }
-            sleep(backoff);
-        };
+        }
+    }
+
+    fn notify(&self) {
+        match self.wakeup.try_send(()) {
+            // a full channel means the delivery thread already has a wakeup pending
+            Ok(()) | Err(TrySendError::Full(_)) => {},
+            Err(TrySendError::Disconnected(_)) => {
+                warn!("Event dispatcher: delivery thread for {} is gone", &self.endpoint);
+            }
+        }
     }
 
     fn make_new_mempool_txs_payload(transactions: Vec<StacksTransaction>) -> serde_json::Value {
@@ -100,12 +162,23 @@ impl EventObserver {
         serde_json::Value::Array(raw_txs)
     }
 
-    fn send_new_mempool_txs(&self, payload: &serde_json::Value) {
-        self.send_payload(payload, PATH_MEMPOOL_TX_SUBMIT);
+    fn make_new_microblocks_payload(index_anchor_block: &StacksBlockId, microblocks: &Vec<StacksMicroblock>) -> serde_json::Value {
+        let serialized_microblocks: Vec<serde_json::Value> = microblocks.iter().map(|mblock| {
+            json!({
+                "microblock_hash": format!("0x{}", mblock.block_hash()),
+                "sequence": mblock.header.sequence,
+                "raw_microblock": format!("0x{}", &bytes_to_hex(&mblock.serialize_to_vec())),
+            })
+        }).collect();
+
+        json!({
+            "parent_index_block_hash": format!("0x{}", index_anchor_block),
+            "microblocks": serialized_microblocks,
+        })
     }
 
-    fn send(&mut self, filtered_events: Vec<&(bool, Txid, &StacksTransactionEvent)>, chain_tip: &ChainTip,
-            parent_index_hash: &StacksBlockId) {
+    fn make_new_block_payload(filtered_events: Vec<&(bool, Txid, &StacksTransactionEvent)>, chain_tip: &ChainTip,
+                              parent_index_hash: &StacksBlockId) -> serde_json::Value {
         // Serialize events to JSON
         let serialized_events: Vec<serde_json::Value> = filtered_events.iter().map(|(committed, txid, event)|
             event.json_serialize(txid, *committed)
@@ -149,7 +222,7 @@ impl EventObserver {
                     None => json!(null)
                 }
             };
-            let val = json!({
+            let mut val = json!({
                 "txid": format!("0x{}", tx.txid()),
                 "tx_index": tx_index,
                 "status": success,
@@ -157,12 +230,20 @@ impl EventObserver {
                 "raw_tx": format!("0x{}", raw_tx.join("")),
                 "contract_abi": contract_interface_json,
             });
+            // only present when the node runs with profile_costs enabled
+            if let Some(ref profile) = receipt.cost_profile {
+                val["cost_profile"] = json!({
+                    "folded_stacks": profile.make_folded_report(CostMetric::Runtime),
+                    "entries": profile.entries,
+                    "total": profile.total,
+                });
+            }
             tx_index += 1;
             val
         }).collect();
         
         // Wrap events
-        let payload = json!({
+        json!({
             "block_hash": format!("0x{}", chain_tip.block.block_hash()),
             "block_height": chain_tip.metadata.block_height,
             "burn_block_time": chain_tip.metadata.burn_header_timestamp,
@@ -172,10 +253,7 @@ impl EventObserver {
             "parent_microblock": format!("0x{}", chain_tip.block.header.parent_microblock),
             "events": serialized_events,
             "transactions": serialized_txs,
-        });
-
-        // Send payload
-        self.send_payload(&payload, PATH_BLOCK_PROCESSED);
+        })
     }
 }
 
@@ -187,11 +265,15 @@ pub struct EventDispatcher {
     mempool_observers_lookup: HashSet<u16>,
     stx_observers_lookup: HashSet<u16>,
     any_event_observers_lookup: HashSet<u16>,
+    event_stream: Option<EventStreamPublisher>,
+    queue_path: String,
+    queue: Option<Arc<Mutex<EventQueueDB>>>,
 }
 
 impl EventDispatcher {
 
-    pub fn new() -> EventDispatcher {
+    /// Payloads for registered observers are persisted at `queue_path` until delivered.
+    pub fn new(queue_path: &str) -> EventDispatcher {
         EventDispatcher {
             registered_observers: vec![],
             contract_events_observers_lookup: HashMap::new(),
@@ -199,9 +281,18 @@ impl EventDispatcher {
             stx_observers_lookup: HashSet::new(),
             any_event_observers_lookup: HashSet::new(),
             mempool_observers_lookup: HashSet::new(),
+            event_stream: None,
+            queue_path: queue_path.to_string(),
+            queue: None,
         }
     }
 
+    /// Also publish blocks, microblocks, mempool transactions and contract events to the RPC
+    /// server's event stream.
+    pub fn set_event_stream(&mut self, publisher: EventStreamPublisher) {
+        self.event_stream = Some(publisher);
+    }
+
     pub fn process_chain_tip(&mut self, chain_tip: &ChainTip, parent_index_hash: &StacksBlockId) {
 
         let mut dispatch_matrix: Vec<HashSet<usize>> = self.registered_observers.iter().map(|_| HashSet::new()).collect();
@@ -231,12 +322,18 @@ impl EventDispatcher {
                     StacksTransactionEvent::NFTEvent(NFTEventType::NFTMintEvent(event_data)) => {
                         self.update_dispatch_matrix_if_observer_subscribed(&event_data.asset_identifier, i, &mut dispatch_matrix);
                     },
+                    StacksTransactionEvent::NFTEvent(NFTEventType::NFTBurnEvent(event_data)) => {
+                        self.update_dispatch_matrix_if_observer_subscribed(&event_data.asset_identifier, i, &mut dispatch_matrix);
+                    },
                     StacksTransactionEvent::FTEvent(FTEventType::FTTransferEvent(event_data)) => {
                         self.update_dispatch_matrix_if_observer_subscribed(&event_data.asset_identifier, i, &mut dispatch_matrix);
                     },
                     StacksTransactionEvent::FTEvent(FTEventType::FTMintEvent(event_data)) => {
                         self.update_dispatch_matrix_if_observer_subscribed(&event_data.asset_identifier, i, &mut dispatch_matrix);
                     },
+                    StacksTransactionEvent::FTEvent(FTEventType::FTBurnEvent(event_data)) => {
+                        self.update_dispatch_matrix_if_observer_subscribed(&event_data.asset_identifier, i, &mut dispatch_matrix);
+                    },
                 }
                 events.push((!receipt.post_condition_aborted, tx_hash, event));
                 for o_i in &self.any_event_observers_lookup {
@@ -247,11 +344,33 @@ impl EventDispatcher {
         }
 
 
+        let mut payloads = vec![];
         for (observer_id, filtered_events_ids) in dispatch_matrix.iter().enumerate() {
             let filtered_events: Vec<_> = filtered_events_ids.iter()
                 .map(|event_id| &events[*event_id]).collect();
 
-            self.registered_observers[observer_id].send(filtered_events, chain_tip, parent_index_hash);
+            let payload = EventObserver::make_new_block_payload(filtered_events, chain_tip, parent_index_hash);
+            payloads.push((observer_id, PATH_BLOCK_PROCESSED, payload));
+        }
+        self.enqueue_payloads(payloads);
+        self.log_observer_backlogs();
+
+        if let Some(ref event_stream) = self.event_stream {
+            // event stream clients do their own filtering
+            let payload = EventObserver::make_new_block_payload(events.iter().collect(), chain_tip, parent_index_hash);
+            event_stream.publish(EventStreamMessage::NewBlock(payload));
+
+            for receipt in chain_tip.receipts.iter() {
+                let tx_hash = receipt.transaction.txid();
+                let sender = PrincipalData::from(receipt.transaction.origin_address());
+                for event in receipt.events.iter() {
+                    if let StacksTransactionEvent::SmartContractEvent(event_data) = event {
+                        let msg = EventStreamMessage::contract_event(event_data.key.0.clone(), sender.clone(), &event_data.value,
+                                                                     event.json_serialize(&tx_hash, !receipt.post_condition_aborted));
+                        event_stream.publish(msg);
+                    }
+                }
+            }
         }
     }
 
@@ -262,17 +381,109 @@ impl EventDispatcher {
                 self.mempool_observers_lookup.contains(&(*obs_id as u16)) ||
                     self.any_event_observers_lookup.contains(&(*obs_id as u16))
             }).collect();
-        if interested_observers.len() < 1 {
+        if interested_observers.len() < 1 && self.event_stream.is_none() {
             return;
         }
 
         let payload = EventObserver::make_new_mempool_txs_payload(txs);
 
-        for (_, observer) in interested_observers.iter() {
-            observer.send_new_mempool_txs(&payload);
+        let payloads = interested_observers.iter()
+            .map(|(observer_id, _)| (*observer_id, PATH_MEMPOOL_TX_SUBMIT, payload.clone()))
+            .collect();
+        self.enqueue_payloads(payloads);
+
+        if let Some(ref event_stream) = self.event_stream {
+            event_stream.publish(EventStreamMessage::NewMempoolTxs(payload));
+        }
+    }
+
+    /// Microblocks only go to the event stream; observers learn about them once they're confirmed.
+    pub fn process_new_microblocks(&self, index_anchor_block: &StacksBlockId, microblocks: &Vec<StacksMicroblock>) {
+        if let Some(ref event_stream) = self.event_stream {
+            let payload = EventObserver::make_new_microblocks_payload(index_anchor_block, microblocks);
+            event_stream.publish(EventStreamMessage::NewMicroblocks(payload));
+        }
+    }
+
+    /// Persist (observer index, path, payload) entries in one transaction, then wake up the
+    /// observers' delivery threads.
+    fn enqueue_payloads(&self, payloads: Vec<(usize, &str, serde_json::Value)>) {
+        if payloads.len() == 0 {
+            return;
+        }
+
+        let queue = self.queue.as_ref().expect("FATAL: event observers registered without a payload queue");
+        {
+            let entries: Vec<_> = payloads.iter()
+                .map(|(observer_id, path, payload)| (self.registered_observers[*observer_id].endpoint.as_str(), *path, payload))
+                .collect();
+
+            queue.lock().expect("FATAL: event queue lock poisoned")
+                .enqueue(&entries)
+                .expect("FATAL: failed to persist event observer payloads");
+        }
+
+        for (observer_id, ..) in payloads.iter() {
+            self.registered_observers[*observer_id].notify();
         }
     }
 
+    /// Number of payloads not yet acknowledged by each registered observer.
+    fn get_observer_backlogs(&self) -> Vec<(String, u64)> {
+        let queue = match self.queue {
+            Some(ref queue) => queue.lock().expect("FATAL: event queue lock poisoned"),
+            None => return vec![]
+        };
+
+        self.registered_observers.iter().map(|observer| {
+            let backlog = queue.get_backlog(&observer.endpoint)
+                .unwrap_or_else(|e| {
+                    error!("Event dispatcher: failed to query backlog for {}: {:?}", &observer.endpoint, &e);
+                    0
+                });
+            (observer.endpoint.clone(), backlog)
+        }).collect()
+    }
+
+    /// Report how far behind each observer is, warning about the ones getting close to the
+    /// point where their oldest payloads are dropped.
+    fn log_observer_backlogs(&self) {
+        for (endpoint, backlog) in self.get_observer_backlogs().into_iter() {
+            update_event_observer_backlog_gauge(&endpoint, backlog as i64);
+            if backlog > MAX_PENDING_PAYLOADS / 2 {
+                warn!("Event dispatcher: {} payloads pending for {} (at most {} are kept)", backlog, &endpoint, MAX_PENDING_PAYLOADS);
+            } else {
+                debug!("Event dispatcher: {} payloads pending for {}", backlog, &endpoint);
+            }
+        }
+    }
+
+    /// Drop queued payloads for observers that are no longer configured.  Call this once all of
+    /// the configured observers are registered.
+    pub fn purge_unregistered_observers(&mut self) {
+        if self.queue.is_none() {
+            if fs::metadata(&self.queue_path).is_err() {
+                return;
+            }
+            self.open_queue();
+        }
+        let queue = self.queue.as_ref().expect("FATAL: event observer queue not opened");
+
+        let endpoints: Vec<_> = self.registered_observers.iter().map(|o| o.endpoint.as_str()).collect();
+        let dropped = queue.lock().expect("FATAL: event queue lock poisoned")
+            .purge_observers_except(&endpoints)
+            .expect("FATAL: failed to purge event observer queue");
+        if dropped > 0 {
+            info!("Event dispatcher: dropped {} payloads queued for observers that are no longer configured", dropped);
+        }
+    }
+
+    fn open_queue(&mut self) {
+        let queue = EventQueueDB::open(&self.queue_path, MAX_PENDING_PAYLOADS)
+            .expect(&format!("FATAL: failed to open event observer queue at {}", &self.queue_path));
+        self.queue = Some(Arc::new(Mutex::new(queue)));
+    }
+
     fn update_dispatch_matrix_if_observer_subscribed(&self, asset_identifier: &AssetIdentifier, event_index: usize, dispatch_matrix: &mut Vec<HashSet<usize>>) {
         if let Some(observer_indexes) = self.assets_observers_lookup.get(asset_identifier) {
             for o_i in observer_indexes {
@@ -282,10 +493,28 @@ impl EventDispatcher {
     }
 
     pub fn register_observer(&mut self, conf: &EventObserverConfig) {
-        // let event_observer = EventObserver::new(&conf.address, conf.port);
-        info!("Registering event observer at: {}", conf.endpoint);
-        let event_observer = EventObserver { 
+        if self.queue.is_none() {
+            self.open_queue();
+        }
+        let queue = self.queue.clone().expect("FATAL: event observer queue not opened");
+
+        // anything left over from a previous run is delivered first
+        let backlog = queue.lock().expect("FATAL: event queue lock poisoned")
+            .get_backlog(&conf.endpoint)
+            .expect("FATAL: failed to query event observer queue");
+        info!("Registering event observer at: {} ({} payloads pending)", conf.endpoint, backlog);
+        update_event_observer_backlog_gauge(&conf.endpoint, backlog as i64);
+
+        let (wakeup_send, wakeup_recv) = sync_channel(1);
+        let endpoint = conf.endpoint.clone();
+        thread::Builder::new()
+            .name(format!("event-observer-{}", &conf.endpoint))
+            .spawn(move || EventObserver::run_delivery(endpoint, queue, wakeup_recv))
+            .expect("FATAL: failed to spawn event observer delivery thread");
+
+        let event_observer = EventObserver {
             endpoint: conf.endpoint.clone(),
+            wakeup: wakeup_send,
         };
 
         let observer_index = self.registered_observers.len() as u16;
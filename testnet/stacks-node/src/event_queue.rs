@@ -0,0 +1,288 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+use rusqlite::{NO_PARAMS, OpenFlags, Row};
+use rusqlite::types::ToSql;
+
+use stacks::util::db::{
+    DBConn, FromRow, FromColumn, Error as db_error,
+    query_row, query_count, u64_to_sql, tx_begin_immediate, tx_busy_handler
+};
+
+const EVENT_QUEUE_SQL : &'static [&'static str] = &[
+    r#"
+    -- ids are never reused, so a cursor can't skip a payload enqueued after a delete
+    CREATE TABLE pending_payloads(
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        observer TEXT NOT NULL,
+        path TEXT NOT NULL,
+        payload TEXT NOT NULL
+    );
+    "#,
+    r#"
+    CREATE INDEX pending_payloads_by_observer ON pending_payloads(observer, id);
+    "#,
+    r#"
+    CREATE TABLE observer_cursors(
+        observer TEXT PRIMARY KEY,
+        last_delivered INTEGER NOT NULL
+    );
+    "#,
+];
+
+/// Most payloads kept for any one observer.  An observer that falls further behind than this
+/// loses its oldest payloads, so a dead observer can't grow the queue without bound.
+pub const MAX_PENDING_PAYLOADS: u64 = 10_000;
+
+const OBSERVER_CURSOR : &'static str = "IFNULL((SELECT last_delivered FROM observer_cursors WHERE observer = ?1), 0)";
+
+/// A payload waiting to be POSTed to an event observer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueuedPayload {
+    pub id: u64,
+    pub path: String,
+    pub payload: String,
+}
+
+impl FromRow<QueuedPayload> for QueuedPayload {
+    fn from_row<'a>(row: &'a Row) -> Result<QueuedPayload, db_error> {
+        let id = u64::from_column(row, "id")?;
+        let path : String = row.get("path");
+        let payload : String = row.get("payload");
+        Ok(QueuedPayload {
+            id,
+            path,
+            payload,
+        })
+    }
+}
+
+/// On-disk queue of event observer payloads.  Each observer has a cursor pointing at the last
+/// payload it acknowledged, so undelivered payloads survive a node restart.
+pub struct EventQueueDB {
+    conn: DBConn,
+    max_pending: u64,
+}
+
+impl EventQueueDB {
+    fn instantiate(conn: &mut DBConn) -> Result<(), db_error> {
+        let tx = tx_begin_immediate(conn)?;
+
+        for cmd in EVENT_QUEUE_SQL {
+            tx.execute(cmd, NO_PARAMS).map_err(db_error::SqliteError)?;
+        }
+
+        tx.commit().map_err(db_error::SqliteError)?;
+        Ok(())
+    }
+
+    /// Open the queue at the given path, creating it (and its parent directory) if needed.
+    /// Each observer keeps at most `max_pending` undelivered payloads.
+    pub fn open(path: &str, max_pending: u64) -> Result<EventQueueDB, db_error> {
+        let mut create_flag = false;
+        let open_flags =
+            if fs::metadata(path).is_err() {
+                if let Some(parent) = PathBuf::from(path).parent() {
+                    fs::create_dir_all(parent).map_err(db_error::IOError)?;
+                }
+                create_flag = true;
+                OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_CREATE
+            }
+            else {
+                OpenFlags::SQLITE_OPEN_READ_WRITE
+            };
+
+        let mut conn = DBConn::open_with_flags(path, open_flags).map_err(db_error::SqliteError)?;
+        conn.busy_handler(Some(tx_busy_handler)).map_err(db_error::SqliteError)?;
+
+        if create_flag {
+            EventQueueDB::instantiate(&mut conn)?;
+        }
+
+        Ok(EventQueueDB {
+            conn,
+            max_pending,
+        })
+    }
+
+    /// Persist a batch of (observer endpoint, path, payload) entries atomically.  If this
+    /// pushes an observer over the pending limit, its oldest payloads are dropped.
+    pub fn enqueue(&mut self, entries: &[(&str, &str, &serde_json::Value)]) -> Result<(), db_error> {
+        let max_pending = u64_to_sql(self.max_pending)?;
+        let tx = tx_begin_immediate(&mut self.conn)?;
+
+        for (observer, path, payload) in entries.iter() {
+            let payload = serde_json::to_string(payload).map_err(db_error::SerializationError)?;
+            let args: &[&dyn ToSql] = &[observer, path, &payload];
+            tx.execute("INSERT INTO pending_payloads (observer, path, payload) VALUES (?1, ?2, ?3)", args)
+                .map_err(db_error::SqliteError)?;
+        }
+
+        let observers: HashSet<_> = entries.iter().map(|(observer, ..)| *observer).collect();
+        for observer in observers.into_iter() {
+            let args: &[&dyn ToSql] = &[&observer, &max_pending];
+            let dropped = tx.execute("DELETE FROM pending_payloads WHERE observer = ?1 AND id NOT IN \
+                                      (SELECT id FROM pending_payloads WHERE observer = ?1 ORDER BY id DESC LIMIT ?2)", args)
+                .map_err(db_error::SqliteError)?;
+            if dropped > 0 {
+                warn!("Event dispatcher: {} is more than {} payloads behind; dropped the {} oldest",
+                      observer, self.max_pending, dropped);
+            }
+        }
+
+        tx.commit().map_err(db_error::SqliteError)?;
+        Ok(())
+    }
+
+    /// Get the oldest payload the given observer has not acknowledged yet.
+    pub fn get_next_payload(&self, observer: &str) -> Result<Option<QueuedPayload>, db_error> {
+        let sql = format!("SELECT * FROM pending_payloads WHERE observer = ?1 AND id > {} ORDER BY id ASC LIMIT 1", OBSERVER_CURSOR);
+        query_row(&self.conn, &sql, &[&observer as &dyn ToSql])
+    }
+
+    /// Advance the observer's cursor past the given payload, and drop everything it has now
+    /// acknowledged.
+    pub fn mark_delivered(&mut self, observer: &str, id: u64) -> Result<(), db_error> {
+        let id = u64_to_sql(id)?;
+        let tx = tx_begin_immediate(&mut self.conn)?;
+
+        let args: &[&dyn ToSql] = &[&observer, &id];
+        tx.execute("INSERT OR REPLACE INTO observer_cursors (observer, last_delivered) VALUES (?1, ?2)", args)
+            .map_err(db_error::SqliteError)?;
+        tx.execute("DELETE FROM pending_payloads WHERE observer = ?1 AND id <= ?2", args)
+            .map_err(db_error::SqliteError)?;
+
+        tx.commit().map_err(db_error::SqliteError)?;
+        Ok(())
+    }
+
+    /// How many payloads are waiting to be delivered to the given observer.
+    pub fn get_backlog(&self, observer: &str) -> Result<u64, db_error> {
+        let sql = format!("SELECT COUNT(*) FROM pending_payloads WHERE observer = ?1 AND id > {}", OBSERVER_CURSOR);
+        let count = query_count(&self.conn, &sql, &[&observer as &dyn ToSql])?;
+        Ok(count as u64)
+    }
+
+    /// Drop the payloads and cursors of every observer not in `observers`, e.g. ones that were
+    /// removed from the config.  Returns how many payloads were dropped.
+    pub fn purge_observers_except(&mut self, observers: &[&str]) -> Result<u64, db_error> {
+        let tx = tx_begin_immediate(&mut self.conn)?;
+
+        let placeholders: Vec<_> = (1..=observers.len()).map(|i| format!("?{}", i)).collect();
+        let keep = format!("observer NOT IN ({})", placeholders.join(", "));
+        let args: Vec<&dyn ToSql> = observers.iter().map(|o| o as &dyn ToSql).collect();
+
+        let dropped = tx.execute(&format!("DELETE FROM pending_payloads WHERE {}", keep), &args)
+            .map_err(db_error::SqliteError)?;
+        tx.execute(&format!("DELETE FROM observer_cursors WHERE {}", keep), &args)
+            .map_err(db_error::SqliteError)?;
+
+        tx.commit().map_err(db_error::SqliteError)?;
+        Ok(dropped as u64)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn open_test_queue(name: &str) -> (String, EventQueueDB) {
+        let path = format!("/tmp/stacks-node-tests/event-queue/{}.sqlite", name);
+        if fs::metadata(&path).is_ok() {
+            fs::remove_file(&path).unwrap();
+        }
+        let queue = EventQueueDB::open(&path, MAX_PENDING_PAYLOADS).unwrap();
+        (path, queue)
+    }
+
+    #[test]
+    fn test_event_queue_cursors() {
+        let (_path, mut queue) = open_test_queue("cursors");
+
+        let a = json!({"block_height": 1});
+        let b = json!({"block_height": 2});
+        queue.enqueue(&[("alice:3700", "new_block", &a), ("bob:3700", "new_block", &a)]).unwrap();
+        queue.enqueue(&[("alice:3700", "new_block", &b)]).unwrap();
+
+        assert_eq!(queue.get_backlog("alice:3700").unwrap(), 2);
+        assert_eq!(queue.get_backlog("bob:3700").unwrap(), 1);
+        assert_eq!(queue.get_backlog("carol:3700").unwrap(), 0);
+
+        let next = queue.get_next_payload("alice:3700").unwrap().unwrap();
+        assert_eq!(next.path, "new_block");
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&next.payload).unwrap(), a);
+
+        // delivering to one observer leaves the others' cursors alone
+        queue.mark_delivered("alice:3700", next.id).unwrap();
+        assert_eq!(queue.get_backlog("alice:3700").unwrap(), 1);
+        assert_eq!(queue.get_backlog("bob:3700").unwrap(), 1);
+
+        let next = queue.get_next_payload("alice:3700").unwrap().unwrap();
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&next.payload).unwrap(), b);
+        queue.mark_delivered("alice:3700", next.id).unwrap();
+
+        assert!(queue.get_next_payload("alice:3700").unwrap().is_none());
+        assert_eq!(queue.get_backlog("alice:3700").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_event_queue_resume() {
+        let (path, mut queue) = open_test_queue("resume");
+
+        let a = json!({"block_height": 1});
+        let b = json!({"block_height": 2});
+        queue.enqueue(&[("alice:3700", "new_block", &a), ("alice:3700", "new_mempool_tx", &b)]).unwrap();
+
+        let next = queue.get_next_payload("alice:3700").unwrap().unwrap();
+        queue.mark_delivered("alice:3700", next.id).unwrap();
+        drop(queue);
+
+        // undelivered payloads are still there after re-opening
+        let queue = EventQueueDB::open(&path, MAX_PENDING_PAYLOADS).unwrap();
+        assert_eq!(queue.get_backlog("alice:3700").unwrap(), 1);
+
+        let next = queue.get_next_payload("alice:3700").unwrap().unwrap();
+        assert_eq!(next.path, "new_mempool_tx");
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&next.payload).unwrap(), b);
+    }
+
+    #[test]
+    fn test_event_queue_cap() {
+        let (path, queue) = open_test_queue("cap");
+        drop(queue);
+        let mut queue = EventQueueDB::open(&path, 2).unwrap();
+
+        for height in 1..=3 {
+            let payload = json!({"block_height": height});
+            queue.enqueue(&[("alice:3700", "new_block", &payload), ("bob:3700", "new_block", &payload)]).unwrap();
+        }
+
+        // the oldest payload is dropped once an observer falls too far behind
+        assert_eq!(queue.get_backlog("alice:3700").unwrap(), 2);
+        assert_eq!(queue.get_backlog("bob:3700").unwrap(), 2);
+        let next = queue.get_next_payload("alice:3700").unwrap().unwrap();
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&next.payload).unwrap(), json!({"block_height": 2}));
+    }
+
+    #[test]
+    fn test_event_queue_purge_observers() {
+        let (_path, mut queue) = open_test_queue("purge");
+
+        let a = json!({"block_height": 1});
+        let b = json!({"block_height": 2});
+        queue.enqueue(&[("alice:3700", "new_block", &a), ("bob:3700", "new_block", &a), ("bob:3700", "new_block", &b)]).unwrap();
+        let next = queue.get_next_payload("bob:3700").unwrap().unwrap();
+        queue.mark_delivered("bob:3700", next.id).unwrap();
+
+        assert_eq!(queue.purge_observers_except(&["alice:3700"]).unwrap(), 1);
+        assert_eq!(queue.get_backlog("alice:3700").unwrap(), 1);
+        assert_eq!(queue.get_backlog("bob:3700").unwrap(), 0);
+        let cursors_sql = "SELECT COUNT(*) FROM observer_cursors".to_string();
+        assert_eq!(query_count(&queue.conn, &cursors_sql, NO_PARAMS).unwrap(), 0);
+
+        // with no observers configured, everything goes
+        assert_eq!(queue.purge_observers_except(&[]).unwrap(), 1);
+        assert!(queue.get_next_payload("alice:3700").unwrap().is_none());
+    }
+}
@@ -16,6 +16,7 @@ pub mod node;
 pub mod tenure;
 pub mod config;
 pub mod event_dispatcher;
+pub mod event_queue;
 pub mod operations;
 pub mod burnchains;
 pub mod neon_node;
@@ -2,7 +2,8 @@
 
 pub use stacks::monitoring::{
     increment_warning_emitted_counter, 
-    increment_errors_emitted_counter
+    increment_errors_emitted_counter,
+    update_event_observer_backlog_gauge
 };
 
 #[cfg(feature = "monitoring_prom")]
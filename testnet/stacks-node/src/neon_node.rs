@@ -7,6 +7,7 @@ use std::{thread, thread::JoinHandle};
 use std::net::SocketAddr;
 use std::collections::VecDeque;
 use std::default::Default;
+use std::process;
 
 use stacks::burnchains::{Burnchain, BurnchainHeaderHash, Txid, PublicKey};
 use stacks::chainstate::burn::db::sortdb::{SortitionDB, SortitionId};
@@ -25,6 +26,7 @@ use stacks::chainstate::burn::operations::{
 use stacks::chainstate::stacks::{StacksBlockBuilder, miner::StacksMicroblockBuilder};
 use stacks::chainstate::burn::BlockSnapshot;
 use stacks::chainstate::stacks::{Error as ChainstateError};
+use stacks::chainstate::stacks::index::{Error as MarfError};
 use stacks::chainstate::stacks::StacksPublicKey;
 
 use stacks::core::mempool::MemPoolDB;
@@ -39,7 +41,8 @@ use stacks::net::{
     db::{ PeerDB, LocalPeer }, relay::Relayer,
     dns::DNSResolver, p2p::PeerNetwork,
     Error as NetError, PeerAddress, StacksMessageCodec,
-    NetworkResult, rpc::RPCHandlerArgs
+    NetworkResult, rpc::RPCHandlerArgs,
+    websocket::{ event_stream_channel, EVENT_STREAM_CHANNEL_CAPACITY }
 };
 use std::sync::mpsc;
 use std::sync::mpsc::{sync_channel, TrySendError, TryRecvError, SyncSender, Receiver};
@@ -253,6 +256,7 @@ fn spawn_peer(mut this: PeerNetwork, p2p_sock: &SocketAddr, rpc_sock: &SocketAdd
     let mut mem_pool = MemPoolDB::open(
         false, TESTNET_CHAIN_ID, &stacks_chainstate_path)
         .map_err(NetError::DBError)?;
+    mem_pool.set_limits(config.mempool_limits.clone());
 
     // buffer up blocks to store without stalling the p2p thread
     let mut results_with_data = VecDeque::new();
@@ -326,6 +330,16 @@ fn spawn_peer(mut this: PeerNetwork, p2p_sock: &SocketAddr, rpc_sock: &SocketAdd
     Ok(server_thread)
 }
 
+/// Stop the node because the next block needs Clarity state that was pruned from its chainstate
+/// with `blockstack-core marf-prune` (for example, a transaction reads it with `at-block`).  The
+/// node can't process that block or any block built on it, so it would otherwise retry forever.
+fn exit_on_pruned_state(chainstate_path: &str) -> ! {
+    error!("Cannot process the next Stacks block: it needs Clarity state that was pruned from the chainstate at {}.  \
+            This node can't make progress until it's resynced: either restore an unpruned copy of the chainstate, \
+            or start from an empty `working_dir` and sync the chain again.", chainstate_path);
+    process::exit(1);
+}
+
 fn spawn_miner_relayer(mut relayer: Relayer, local_peer: LocalPeer,
                        config: Config, mut keychain: Keychain,
                        burn_db_path: String, stacks_chainstate_path: String, 
@@ -344,10 +358,12 @@ fn spawn_miner_relayer(mut relayer: Relayer, local_peer: LocalPeer,
     let mut chainstate = StacksChainState::open_with_block_limit(
         false, TESTNET_CHAIN_ID, &stacks_chainstate_path, config.block_limit.clone())
         .map_err(|e| NetError::ChainstateError(e.to_string()))?;
+    chainstate.set_cost_profiling(config.node.profile_costs);
     
     let mut mem_pool = MemPoolDB::open(
         false, TESTNET_CHAIN_ID, &stacks_chainstate_path)
         .map_err(NetError::DBError)?;
+    mem_pool.set_limits(config.mempool_limits.clone());
 
     let mut last_mined_block: Option<AssembledAnchorBlock> = None;
     let burn_fee_cap = config.burnchain.burn_fee_cap;
@@ -377,7 +393,11 @@ fn spawn_miner_relayer(mut relayer: Relayer, local_peer: LocalPeer,
                     debug!("Relayer: Try process attacheable blocks");
 
                     // process any attachable blocks
-                    let block_receipts = chainstate.process_blocks(&mut sortdb, 1).expect("BUG: failure processing chainstate");
+                    let block_receipts = match chainstate.process_blocks(&mut sortdb, 1) {
+                        Ok(block_receipts) => block_receipts,
+                        Err(e) if StacksChainState::is_pruned_state_error(&e) => exit_on_pruned_state(&stacks_chainstate_path),
+                        Err(e) => panic!("BUG: failure processing chainstate: {:?}", &e)
+                    };
                     let mut epoch_receipts = vec![];
                     let mut num_processed = 0;
                     for (epoch_receipt_opt, _poison_microblock_opt) in block_receipts.into_iter() {
@@ -402,9 +422,12 @@ fn spawn_miner_relayer(mut relayer: Relayer, local_peer: LocalPeer,
                 },
                 RelayerDirective::HandleNetResult(ref mut net_result) => {
                     debug!("Relayer: Handle network result");
-                    let net_receipts = relayer.process_network_result(&local_peer, net_result,
-                                                                        &mut sortdb, &mut chainstate, &mut mem_pool)
-                        .expect("BUG: failure processing network results");
+                    let net_receipts = match relayer.process_network_result(&local_peer, net_result,
+                                                                              &mut sortdb, &mut chainstate, &mut mem_pool) {
+                        Ok(net_receipts) => net_receipts,
+                        Err(NetError::MARFError(MarfError::PrunedError)) => exit_on_pruned_state(&stacks_chainstate_path),
+                        Err(e) => panic!("BUG: failure processing network results: {:?}", &e)
+                    };
 
                     // TODO: extricate the poison block transaction(s) from the relayer and feed
                     // them to the miner
@@ -412,6 +435,10 @@ fn spawn_miner_relayer(mut relayer: Relayer, local_peer: LocalPeer,
                         dispatcher_announce_block(&blocks_path, &mut event_dispatcher, epoch_receipt.header, None, &mut sortdb, epoch_receipt.tx_receipts);
                     }
 
+                    for mblocks_data in net_receipts.microblocks_processed.iter() {
+                        event_dispatcher.process_new_microblocks(&mblocks_data.index_anchor_block, &mblocks_data.microblocks);
+                    }
+
                     let mempool_txs_added = net_receipts.mempool_txs_added.len();
                     if mempool_txs_added > 0 {
                         event_dispatcher.process_new_mempool_txs(net_receipts.mempool_txs_added);
@@ -487,6 +514,10 @@ fn spawn_miner_relayer(mut relayer: Relayer, local_peer: LocalPeer,
                                 if let Err(e) = chainstate.refresh_unconfirmed_state() {
                                     warn!("Failed to refresh unconfirmed state after processing microblock {}/{}-{}: {:?}", &mined_burn_hh, &block_header_hash, mined_microblock.block_hash(), &e);
                                 }
+                                event_dispatcher.process_new_microblocks(
+                                    &StacksBlockHeader::make_index_block_hash(&burn_header_hash, &block_header_hash),
+                                    &vec![mined_microblock.clone()]);
+
                                 // broadcast to peers
                                 let microblock_hash = mined_microblock.header.block_hash();
                                 if let Err(e) = relayer.broadcast_microblock(&block_header_hash, &burn_header_hash,
@@ -555,7 +586,7 @@ fn dispatcher_announce_block(blocks_path: &str, event_dispatcher: &mut EventDisp
 }
 
 impl InitializedNeonNode {
-    fn new(config: Config, keychain: Keychain, event_dispatcher: EventDispatcher,
+    fn new(config: Config, keychain: Keychain, mut event_dispatcher: EventDispatcher,
            last_burn_block: Option<BurnchainTip>,
            miner: bool, blocks_processed: BlocksProcessedCounter) -> InitializedNeonNode {
         // we can call _open_ here rather than _connect_, since connect is first called in
@@ -626,6 +657,11 @@ impl InitializedNeonNode {
         // setup the relayer channel
         let (relay_send, relay_recv) = sync_channel(RELAYER_MAX_BUFFER);
 
+        // the relayer feeds the RPC server's event stream
+        let (event_stream_send, event_stream_recv) = event_stream_channel(EVENT_STREAM_CHANNEL_CAPACITY);
+        event_dispatcher.set_event_stream(event_stream_send);
+        p2p_net.http.set_event_stream(event_stream_recv);
+
         let burnchain_signer = keychain.get_burnchain_signer();
         let relayer = Relayer::from_p2p(&mut p2p_net);
 
@@ -930,7 +966,7 @@ impl NeonGenesisNode {
         let initial_balances = config.initial_balances.iter().map(|e| (e.address.clone(), e.amount)).collect();
 
         // do the initial open!
-        let _chain_state = match StacksChainState::open_and_exec(
+        let mut chain_state = match StacksChainState::open_and_exec(
             false, 
             TESTNET_CHAIN_ID, 
             &config.get_chainstate_path(), 
@@ -941,10 +977,38 @@ impl NeonGenesisNode {
             Err(err) => panic!("Error while opening chain state at path {}: {:?}", config.get_chainstate_path(), err)
         };
 
-        let mut event_dispatcher = EventDispatcher::new();
+        if config.node.index_map_keys {
+            match chain_state.enable_map_key_index() {
+                Ok(()) => {},
+                Err(ChainstateError::InvalidChainstateDB) => {
+                    // keys written by blocks already processed can't be recovered
+                    error!("Cannot enable the data map key index on the chainstate at {}, since it has already processed blocks.  \
+                            The index can only be started on a new chainstate: either set `index_map_keys = false` in the [node] section of the config, \
+                            or start from an empty `working_dir` and sync the chain again.", config.get_chainstate_path());
+                    process::exit(1);
+                },
+                Err(e) => panic!("Error while enabling the data map key index: {:?}", e)
+            }
+        }
+
+        if config.node.index_transactions {
+            match chain_state.enable_transaction_index() {
+                Ok(()) => {},
+                Err(e) => {
+                    // e.g. the chainstate is locked by another process, or the disk is full
+                    error!("Cannot enable the transaction index on the chainstate at {}: {:?}.  \
+                            Either fix the problem and restart the node, or set `index_transactions = false` in the [node] section of the config.",
+                           config.get_chainstate_path(), &e);
+                    process::exit(1);
+                }
+            }
+        }
+
+        let mut event_dispatcher = EventDispatcher::new(&config.get_event_queue_path());
         for observer in config.events_observers.iter() {
             event_dispatcher.register_observer(observer);
         }
+        event_dispatcher.purge_unregistered_observers();
 
         Self {
             keychain,
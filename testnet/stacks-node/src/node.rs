@@ -5,6 +5,7 @@ use std::convert::TryFrom;
 use std::{thread, time, thread::JoinHandle};
 use std::net::SocketAddr;
 use std::default::Default;
+use std::process;
 
 use stacks::burnchains::{Burnchain, BurnchainHeaderHash, Txid};
 use stacks::chainstate::burn::db::sortdb::{SortitionDB};
@@ -14,13 +15,14 @@ use stacks::chainstate::stacks::{
     StacksBlock, TransactionPayload, StacksAddress, StacksTransactionSigner,
     StacksTransaction, TransactionVersion, StacksMicroblock, CoinbasePayload,
     TransactionAnchorMode, StacksBlockHeader };
+use stacks::chainstate::stacks::{Error as ChainstateError};
 use stacks::chainstate::burn::{ConsensusHash, VRFSeed, BlockHeaderHash};
 use stacks::chainstate::burn::operations::{
     LeaderBlockCommitOp,
     LeaderKeyRegisterOp,
     BlockstackOperationType,
 };
-use stacks::core::mempool::MemPoolDB;
+use stacks::core::mempool::{MemPoolDB, MemPoolLimits};
 use stacks::net::{
     p2p::PeerNetwork, Error as NetError, db::PeerDB, PeerAddress,
     rpc::RPCHandlerArgs
@@ -71,7 +73,7 @@ pub struct Node {
 
 fn spawn_peer(mut this: PeerNetwork, p2p_sock: &SocketAddr, rpc_sock: &SocketAddr,
               burn_db_path: String, stacks_chainstate_path: String, event_dispatcher: EventDispatcher,
-              exit_at_block_height: Option<u64>, poll_timeout: u64) -> Result<JoinHandle<()>, NetError> {
+              exit_at_block_height: Option<u64>, mempool_limits: MemPoolLimits, poll_timeout: u64) -> Result<JoinHandle<()>, NetError> {
     this.bind(p2p_sock, rpc_sock).unwrap();
     let server_thread = thread::spawn(move || {
         let handler_args = RPCHandlerArgs { exit_at_block_height: exit_at_block_height.as_ref(),
@@ -106,6 +108,7 @@ fn spawn_peer(mut this: PeerNetwork, p2p_sock: &SocketAddr, rpc_sock: &SocketAdd
                     continue;
                 }
             };
+            mem_pool.set_limits(mempool_limits.clone());
 
             let net_result = this.run(&sortdb, &mut chainstate, &mut mem_pool, None,
                                       false, poll_timeout, &handler_args)
@@ -133,15 +136,45 @@ impl Node {
             false, TESTNET_CHAIN_ID, &config.get_chainstate_path(),
             Some(initial_balances), boot_block_exec, config.block_limit.clone());
 
-        let chain_state = match chain_state_result {
+        let mut chain_state = match chain_state_result {
             Ok(res) => res,
             Err(err) => panic!("Error while opening chain state at path {}: {:?}", config.get_chainstate_path(), err)
         };
-        let mut event_dispatcher = EventDispatcher::new();
+
+        if config.node.index_map_keys {
+            match chain_state.enable_map_key_index() {
+                Ok(()) => {},
+                Err(ChainstateError::InvalidChainstateDB) => {
+                    // keys written by blocks already processed can't be recovered
+                    error!("Cannot enable the data map key index on the chainstate at {}, since it has already processed blocks.  \
+                            The index can only be started on a new chainstate: either set `index_map_keys = false` in the [node] section of the config, \
+                            or start from an empty `working_dir` and sync the chain again.", config.get_chainstate_path());
+                    process::exit(1);
+                },
+                Err(e) => panic!("Error while enabling the data map key index: {:?}", e)
+            }
+        }
+
+        if config.node.index_transactions {
+            match chain_state.enable_transaction_index() {
+                Ok(()) => {},
+                Err(e) => {
+                    // e.g. the chainstate is locked by another process, or the disk is full
+                    error!("Cannot enable the transaction index on the chainstate at {}: {:?}.  \
+                            Either fix the problem and restart the node, or set `index_transactions = false` in the [node] section of the config.",
+                           config.get_chainstate_path(), &e);
+                    process::exit(1);
+                }
+            }
+        }
+
+        chain_state.set_cost_profiling(config.node.profile_costs);
+        let mut event_dispatcher = EventDispatcher::new(&config.get_event_queue_path());
 
         for observer in &config.events_observers {
             event_dispatcher.register_observer(observer);
         }
+        event_dispatcher.purge_unregistered_observers();
 
         Self {
             active_registered_key: None,
@@ -163,11 +196,12 @@ impl Node {
 
         let keychain = Keychain::default(config.node.seed.clone());
 
-        let mut event_dispatcher = EventDispatcher::new();
+        let mut event_dispatcher = EventDispatcher::new(&config.get_event_queue_path());
 
         for observer in &config.events_observers {
             event_dispatcher.register_observer(observer);
         }
+        event_dispatcher.purge_unregistered_observers();
 
         let chainstate_path = config.get_chainstate_path();
         let sortdb_path = config.get_burn_db_file_path();
@@ -290,6 +324,7 @@ impl Node {
             self.config.get_chainstate_path(),
             event_dispatcher,
             exit_at_block_height,
+            self.config.mempool_limits.clone(),
             1000).unwrap();
 
         info!("Bound HTTP server on: {}", &self.config.node.rpc_bind);
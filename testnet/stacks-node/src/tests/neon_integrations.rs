@@ -45,6 +45,7 @@ mod test_observer {
     use warp;
     use warp::Filter;
     use std::thread;
+    use std::time::{Instant, Duration};
     use tokio;
 
     pub const EVENT_OBSERVER_PORT: u16 = 50303;
@@ -78,6 +79,15 @@ mod test_observer {
         NEW_BLOCKS.lock().unwrap().clone()
     }
 
+    /// Payloads are delivered from background threads, so poll until `ready` holds (or give up
+    /// after `timeout_secs` and let the caller's assertions fail).
+    pub fn wait_for<F: Fn() -> bool>(ready: F, timeout_secs: u64) {
+        let start = Instant::now();
+        while !ready() && start.elapsed() < Duration::from_secs(timeout_secs) {
+            thread::sleep(Duration::from_millis(100));
+        }
+    }
+
     async fn serve() {
         let new_blocks = warp::path!("new_block")
             .and(warp::post())
@@ -362,6 +372,11 @@ fn microblock_integration_test() {
 
     eprintln!("{:#?}", client.get(&path).send().unwrap().json::<serde_json::Value>().unwrap());
 
+    test_observer::wait_for(|| {
+        test_observer::get_memtxs().len() >= 1 &&
+            test_observer::get_blocks().len() as u64 >= tip_info.stacks_tip_height
+    }, 60);
+
     let memtx_events = test_observer::get_memtxs();
     assert_eq!(memtx_events.len(), 1);
     assert_eq!(&memtx_events[0], &format!("0x{}", &bytes_to_hex(&tx)));
@@ -0,0 +1,77 @@
+extern crate blockstack_lib;
+
+// The bindings `clarity-cli generate_bindings` makes for fixtures/token_registry.clar, compiled
+//  as a crate using blockstack_lib would compile them.  vm::bindings::tests checks that they're
+//  still what the generator makes.  Not every binding is exercised here.
+#[allow(dead_code)]
+mod token_registry {
+    include!("fixtures/token_registry_bindings.rs");
+}
+
+use blockstack_lib::chainstate::stacks::{StacksAddress, TransactionPayload};
+use blockstack_lib::net::CallReadOnlyResponse;
+use blockstack_lib::util::hash::Hash160;
+use blockstack_lib::vm::bindings::BindingError;
+use blockstack_lib::vm::database::ClaritySerializable;
+use blockstack_lib::vm::types::{PrincipalData, Value};
+use blockstack_lib::vm::ContractName;
+
+use token_registry::*;
+
+fn registry() -> TokenRegistry {
+    let address = StacksAddress { version: 26, bytes: Hash160([1; 20]) };
+    TokenRegistry::new(address, ContractName::from("token-registry"))
+}
+
+#[test]
+fn test_contract_calls() {
+    let registry = registry();
+    let recipient = PrincipalData::from(StacksAddress { version: 26, bytes: Hash160([2; 20]) });
+
+    let args = TransferArgs { amount: 10, recipient: recipient.clone(), memo: Some(vec![1, 2]) };
+    match registry.transfer(args) {
+        TransactionPayload::ContractCall(call) => {
+            assert_eq!(call.address, registry.address);
+            assert_eq!(call.contract_name, registry.contract_name);
+            assert_eq!(call.function_name.as_str(), "transfer");
+            assert_eq!(call.function_args,
+                       vec![Value::UInt(10), Value::Principal(recipient),
+                            Value::some(Value::buff_from(vec![1, 2]).unwrap()).unwrap()]);
+        },
+        _ => panic!("Expected a contract call")
+    }
+
+    match registry.is_ok2() {
+        TransactionPayload::ContractCall(call) => {
+            assert_eq!(call.function_name.as_str(), "is-ok?");
+            assert!(call.function_args.is_empty());
+        },
+        _ => panic!("Expected a contract call")
+    }
+
+    assert_eq!(TokenRegistry::decode_transfer(Value::okay(Value::Bool(true)).unwrap()), Ok(Ok(true)));
+    assert_eq!(TokenRegistry::decode_transfer(Value::error(Value::UInt(1)).unwrap()), Ok(Err(1)));
+    assert_eq!(TokenRegistry::decode_transfer(Value::Bool(true)), Err(BindingError::UnexpectedValue(Value::Bool(true))));
+}
+
+#[test]
+fn test_read_only_calls() {
+    let registry = registry();
+    let sender = PrincipalData::from(registry.address);
+
+    let (path, body) = registry.get_info_request(&sender, GetInfoArgs { who: sender.clone() });
+    assert_eq!(path, format!("/v2/contracts/call-read/{}/token-registry/get-info", registry.address));
+    assert_eq!(body.arguments, vec![Value::Principal(sender.clone()).serialize()]);
+
+    let info = GetInfoOutputOk { balance: 0, names: vec!["a".into(), "b".into()], owner: sender.clone() };
+    let response = CallReadOnlyResponse {
+        okay: true,
+        result: Some(Value::okay(info.clone().into()).unwrap().serialize()),
+        cause: None
+    };
+    assert_eq!(TokenRegistry::get_info_response(&response), Ok(Ok(info)));
+
+    let greeting = Value::some(Value::string_utf8_from_str("caf\u{e9}").unwrap()).unwrap();
+    assert_eq!(TokenRegistry::decode_get_greeting(greeting), Ok(Some("caf\u{e9}".to_string())));
+    assert_eq!(TokenRegistry::decode_get_greeting(Value::none()), Ok(None));
+}
@@ -0,0 +1,189 @@
+// Bindings for the `token_registry` contract, generated from its interface by
+//  `clarity-cli generate_bindings`.  Regenerate them rather than editing them.
+
+use std::convert::TryFrom;
+
+use blockstack_lib::chainstate::stacks::{StacksAddress, TransactionPayload};
+use blockstack_lib::net::{CallReadOnlyRequestBody, CallReadOnlyResponse};
+use blockstack_lib::vm::bindings::*;
+use blockstack_lib::vm::types::{PrincipalData, Value};
+use blockstack_lib::vm::ContractName;
+
+/// The `token_registry` contract, as deployed by `address` under `contract_name`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenRegistry {
+    pub address: StacksAddress,
+    pub contract_name: ContractName,
+}
+
+impl TokenRegistry {
+    pub fn new(address: StacksAddress, contract_name: ContractName) -> TokenRegistry {
+        TokenRegistry { address, contract_name }
+    }
+
+    /// A call of `transfer`, to be signed into a transaction.
+    pub fn transfer(&self, args: TransferArgs) -> TransactionPayload {
+        contract_call(&self.address, &self.contract_name, "transfer", args.into())
+    }
+
+    /// Decode the value returned by `transfer`.
+    pub fn decode_transfer(value: Value) -> Result<Result<bool, u128>, BindingError> {
+        expect_response(value).and_then(|v0| match v0 { Ok(v0) => expect_bool(v0).map(Ok), Err(v0) => expect_uint(v0).map(Err) })
+    }
+
+    /// A call of `get-greeting`, to be signed into a transaction.
+    pub fn get_greeting(&self, args: GetGreetingArgs) -> TransactionPayload {
+        contract_call(&self.address, &self.contract_name, "get-greeting", args.into())
+    }
+
+    /// Decode the value returned by `get-greeting`.
+    pub fn decode_get_greeting(value: Value) -> Result<Option<String>, BindingError> {
+        expect_optional(value).and_then(|v0| v0.map(|v0| expect_string_utf8(v0)).transpose())
+    }
+
+    /// The path and body of a `/v2/contracts/call-read` request evaluating `get-greeting`.
+    pub fn get_greeting_request(&self, sender: &PrincipalData, args: GetGreetingArgs) -> (String, CallReadOnlyRequestBody) {
+        read_only_request(&self.address, &self.contract_name, "get-greeting", sender, args.into())
+    }
+
+    /// Decode the response to a `get_greeting_request`.
+    pub fn get_greeting_response(response: &CallReadOnlyResponse) -> Result<Option<String>, BindingError> {
+        Self::decode_get_greeting(read_only_result(response)?)
+    }
+
+    /// A call of `get-info`, to be signed into a transaction.
+    pub fn get_info(&self, args: GetInfoArgs) -> TransactionPayload {
+        contract_call(&self.address, &self.contract_name, "get-info", args.into())
+    }
+
+    /// Decode the value returned by `get-info`.
+    pub fn decode_get_info(value: Value) -> Result<Result<GetInfoOutputOk, ()>, BindingError> {
+        expect_response(value).and_then(|v0| match v0 { Ok(v0) => GetInfoOutputOk::try_from(v0).map(Ok), Err(v0) => expect_unit(v0).map(Err) })
+    }
+
+    /// The path and body of a `/v2/contracts/call-read` request evaluating `get-info`.
+    pub fn get_info_request(&self, sender: &PrincipalData, args: GetInfoArgs) -> (String, CallReadOnlyRequestBody) {
+        read_only_request(&self.address, &self.contract_name, "get-info", sender, args.into())
+    }
+
+    /// Decode the response to a `get_info_request`.
+    pub fn get_info_response(response: &CallReadOnlyResponse) -> Result<Result<GetInfoOutputOk, ()>, BindingError> {
+        Self::decode_get_info(read_only_result(response)?)
+    }
+
+    /// A call of `is-ok!`, to be signed into a transaction.
+    pub fn is_ok(&self) -> TransactionPayload {
+        contract_call(&self.address, &self.contract_name, "is-ok!", vec![])
+    }
+
+    /// Decode the value returned by `is-ok!`.
+    pub fn decode_is_ok(value: Value) -> Result<bool, BindingError> {
+        expect_bool(value)
+    }
+
+    /// The path and body of a `/v2/contracts/call-read` request evaluating `is-ok!`.
+    pub fn is_ok_request(&self, sender: &PrincipalData) -> (String, CallReadOnlyRequestBody) {
+        read_only_request(&self.address, &self.contract_name, "is-ok!", sender, vec![])
+    }
+
+    /// Decode the response to a `is_ok_request`.
+    pub fn is_ok_response(response: &CallReadOnlyResponse) -> Result<bool, BindingError> {
+        Self::decode_is_ok(read_only_result(response)?)
+    }
+
+    /// A call of `is-ok?`, to be signed into a transaction.
+    pub fn is_ok2(&self) -> TransactionPayload {
+        contract_call(&self.address, &self.contract_name, "is-ok?", vec![])
+    }
+
+    /// Decode the value returned by `is-ok?`.
+    pub fn decode_is_ok2(value: Value) -> Result<bool, BindingError> {
+        expect_bool(value)
+    }
+
+    /// The path and body of a `/v2/contracts/call-read` request evaluating `is-ok?`.
+    pub fn is_ok2_request(&self, sender: &PrincipalData) -> (String, CallReadOnlyRequestBody) {
+        read_only_request(&self.address, &self.contract_name, "is-ok?", sender, vec![])
+    }
+
+    /// Decode the response to a `is_ok2_request`.
+    pub fn is_ok2_response(response: &CallReadOnlyResponse) -> Result<bool, BindingError> {
+        Self::decode_is_ok2(read_only_result(response)?)
+    }
+}
+
+/// The arguments of `transfer`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransferArgs {
+    pub amount: u128,
+    pub recipient: PrincipalData,
+    pub memo: Option<Vec<u8>>,
+}
+
+impl From<TransferArgs> for Vec<Value> {
+    fn from(args: TransferArgs) -> Vec<Value> {
+        vec![
+            Value::UInt(args.amount),
+            Value::Principal(args.recipient),
+            optional_value(args.memo.map(|v0| buffer_value(v0))),
+        ]
+    }
+}
+
+/// The arguments of `get-greeting`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetGreetingArgs {
+    pub name: String,
+}
+
+impl From<GetGreetingArgs> for Vec<Value> {
+    fn from(args: GetGreetingArgs) -> Vec<Value> {
+        vec![
+            string_utf8_value(args.name),
+        ]
+    }
+}
+
+/// The arguments of `get-info`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetInfoArgs {
+    pub who: PrincipalData,
+}
+
+impl From<GetInfoArgs> for Vec<Value> {
+    fn from(args: GetInfoArgs) -> Vec<Value> {
+        vec![
+            Value::Principal(args.who),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GetInfoOutputOk {
+    pub balance: u128,
+    pub names: Vec<String>,
+    pub owner: PrincipalData,
+}
+
+impl From<GetInfoOutputOk> for Value {
+    fn from(tuple: GetInfoOutputOk) -> Value {
+        tuple_value(vec![
+            ("balance", Value::UInt(tuple.balance)),
+            ("names", list_value(tuple.names.into_iter().map(|v0| string_ascii_value(v0)).collect())),
+            ("owner", Value::Principal(tuple.owner)),
+        ])
+    }
+}
+
+impl TryFrom<Value> for GetInfoOutputOk {
+    type Error = BindingError;
+
+    fn try_from(value: Value) -> Result<GetInfoOutputOk, BindingError> {
+        let mut tuple = expect_tuple(value)?;
+        Ok(GetInfoOutputOk {
+            balance: expect_uint(take_field(&mut tuple, "balance")?)?,
+            names: expect_list(take_field(&mut tuple, "names")?).and_then(|v0| v0.into_iter().map(|v0| expect_string_ascii(v0)).collect::<Result<Vec<_>, _>>())?,
+            owner: expect_principal(take_field(&mut tuple, "owner")?)?,
+        })
+    }
+}